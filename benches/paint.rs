@@ -0,0 +1,86 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The paint time of a tree of 5000 labels when one of them changed.
+//!
+//! `cached` paints the tree like a frame does: only the changed label records its fragment
+//! again, the other labels append theirs. `uncached` marks every widget, which is what each
+//! frame did before the pods kept their fragments.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use xilem::widget::{BoxConstraints, ChangeFlags, Headless, Label, LinearLayout, Pod, PodSlots};
+use xilem::Axis;
+
+const ROWS: usize = 50;
+const COLUMNS: usize = 100;
+
+/// A column of rows of labels.
+fn tree() -> Pod {
+    let rows = (0..ROWS)
+        .map(|row| {
+            let labels = (0..COLUMNS)
+                .map(|column| Pod::new(Label::new(format!("{row}:{column}"))))
+                .collect();
+            Pod::new(LinearLayout::new(labels, 4.0, Axis::Horizontal))
+        })
+        .collect();
+    Pod::new(LinearLayout::new(rows, 2.0, Axis::Vertical))
+}
+
+fn children(pod: &mut Pod) -> &mut PodSlots {
+    &mut pod.downcast_mut::<LinearLayout>().unwrap().children
+}
+
+/// Marks the label at `row` and `column` and its ancestors, like a view changing its text.
+fn mark_label(root: &mut Pod, row: usize, column: usize) {
+    let row = &mut children(root)[row];
+    let changes = children(row)[column].mark(ChangeFlags::PAINT);
+    let changes = row.mark(changes);
+    let _ = root.mark(changes);
+}
+
+fn mark_all(root: &mut Pod) {
+    for row in children(root).iter_mut() {
+        for label in children(row).iter_mut() {
+            let _ = label.mark(ChangeFlags::PAINT);
+        }
+        let _ = row.mark(ChangeFlags::PAINT);
+    }
+    let _ = root.mark(ChangeFlags::PAINT);
+}
+
+fn one_dirty_leaf(c: &mut Criterion) {
+    let mut headless = Headless::new();
+    let mut root = tree();
+    headless.layout(&mut root, &BoxConstraints::UNBOUNDED);
+    headless.paint(&mut root);
+
+    let mut group = c.benchmark_group("paint one dirty leaf of 5000");
+    group.bench_function("cached", |b| {
+        b.iter(|| {
+            mark_label(&mut root, ROWS / 2, COLUMNS / 2);
+            headless.paint(&mut root);
+        })
+    });
+    group.bench_function("uncached", |b| {
+        b.iter(|| {
+            mark_all(&mut root);
+            headless.paint(&mut root);
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, one_dirty_leaf);
+criterion_main!(benches);
@@ -3,7 +3,7 @@
 
 #[macro_export]
 macro_rules! generate_anyview_trait {
-    ($anyview:ident, $viewtrait:ident, $viewmarker:ty, $cx:ty, $changeflags:ty, $anywidget:ident, $boxedview:ident; $($ss:tt)*) => {
+    ($anyview:ident, $viewtrait:ident, $viewmarker:ty, $cx:ty, $changeflags:ty, $anywidget:ident, $boxedview:ident, $message:ty; $($ss:tt)*) => {
         /// A trait enabling type erasure of views.
         pub trait $anyview<T, A = ()> {
             fn as_any(&self) -> &dyn std::any::Any;
@@ -26,9 +26,9 @@ macro_rules! generate_anyview_trait {
                 &self,
                 id_path: &[$crate::Id],
                 state: &mut dyn std::any::Any,
-                message: Box<dyn std::any::Any>,
+                message: $message,
                 app_state: &mut T,
-            ) -> $crate::MessageResult<A>;
+            ) -> $crate::MessageResult<A, $message>;
         }
 
         impl<T, A, V: $viewtrait<T, A> + 'static> $anyview<T, A> for V
@@ -58,38 +58,33 @@ macro_rules! generate_anyview_trait {
             ) -> ChangeFlags {
                 use std::ops::DerefMut;
                 if let Some(prev) = prev.as_any().downcast_ref() {
-                    if let Some(state) = state.downcast_mut() {
-                        if let Some(element) = element.deref_mut().as_any_mut().downcast_mut() {
-                            self.rebuild(cx, prev, id, state, element)
-                        } else {
-                            eprintln!("downcast of element failed in dyn_rebuild");
-                            <$changeflags>::default()
-                        }
-                    } else {
-                        eprintln!("downcast of state failed in dyn_rebuild");
-                        <$changeflags>::default()
+                    let same_element = element.deref_mut().as_any_mut().downcast_mut();
+                    if let (Some(state), Some(element)) = (state.downcast_mut(), same_element) {
+                        return self.rebuild(cx, prev, id, state, element);
                     }
-                } else {
-                    let (new_id, new_state, new_element) = self.build(cx);
-                    *id = new_id;
-                    *state = Box::new(new_state);
-                    *element = Box::new(new_element);
-                    <$changeflags>::tree_structure()
+                    // The view is of the same type, but the state or the element was built by
+                    // another one, so it is built anew like for a view of another type.
                 }
+                let (new_id, new_state, new_element) = self.build(cx);
+                *id = new_id;
+                *state = Box::new(new_state);
+                *element = Box::new(new_element);
+                <$changeflags>::tree_structure()
             }
 
             fn dyn_message(
                 &self,
                 id_path: &[$crate::Id],
                 state: &mut dyn std::any::Any,
-                message: Box<dyn std::any::Any>,
+                message: $message,
                 app_state: &mut T,
-            ) -> $crate::MessageResult<A> {
+            ) -> $crate::MessageResult<A, $message> {
                 if let Some(state) = state.downcast_mut() {
                     self.message(id_path, state, message, app_state)
                 } else {
-                    // Possibly softer failure?
-                    panic!("downcast error in dyn_event");
+                    // The state belongs to a view of another type, which was replaced since the
+                    // message was sent.
+                    $crate::MessageResult::Stale(message)
                 }
             }
         }
@@ -125,9 +120,9 @@ macro_rules! generate_anyview_trait {
                 &self,
                 id_path: &[$crate::Id],
                 state: &mut Self::State,
-                message: Box<dyn std::any::Any>,
+                message: $message,
                 app_state: &mut T,
-            ) -> $crate::MessageResult<A> {
+            ) -> $crate::MessageResult<A, $message> {
                 use std::ops::{Deref, DerefMut};
                 self.deref()
                     .dyn_message(id_path, state.deref_mut(), message, app_state)
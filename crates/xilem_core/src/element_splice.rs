@@ -0,0 +1,36 @@
+// Copyright 2023 the Druid Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+/// The elements of a view sequence as its `rebuild` walks through them.
+///
+/// A splice has a cursor, which starts before the first element. Sequences advance it past
+/// the elements they keep, and insert or delete elements at it. Containers choose how their
+/// children are stored by handing a splice over that storage to the sequence, like
+/// [`VecSplice`](crate::VecSplice) over a `Vec`.
+pub trait ElementSplice<T> {
+    /// Moves the cursor past `n` elements, leaving them as they are.
+    fn skip(&mut self, n: usize);
+
+    /// Deletes the `n` elements after the cursor.
+    fn delete(&mut self, n: usize);
+
+    /// Inserts `element` at the cursor, and moves the cursor past it.
+    fn push(&mut self, element: T);
+
+    /// Inserts `elements` at the cursor, and moves the cursor past them.
+    fn extend(&mut self, elements: Vec<T>) {
+        for element in elements {
+            self.push(element);
+        }
+    }
+
+    /// The element after the cursor, moving the cursor past it.
+    fn mutate(&mut self) -> &mut T;
+
+    /// How many elements are before the cursor.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -16,12 +16,14 @@
 //! here, but that also requires coordination with the context.
 
 mod any_view;
+mod element_splice;
 mod id;
 mod message;
 mod sequence;
 mod vec_splice;
 mod view;
 
+pub use element_splice::ElementSplice;
 pub use id::{Id, IdPath};
 pub use message::{AsyncWake, MessageResult};
 pub use vec_splice::VecSplice;
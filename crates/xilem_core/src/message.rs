@@ -1,35 +1,107 @@
 // Copyright 2022 The Druid Authors.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::any::Any;
-
 #[macro_export]
 macro_rules! message {
     ($($bounds:tt)*) => {
+        /// A message addressed to the view at `id_path`.
+        ///
+        /// Besides the payload it keeps the type name of the payload and an optional tag naming
+        /// its source, which are used to tell where a message came from when it can't be
+        /// delivered.
         pub struct Message {
             pub id_path: xilem_core::IdPath,
             pub body: Box<dyn std::any::Any + $($bounds)*>,
+            source: Option<&'static str>,
+            type_name: &'static str,
         }
 
         impl Message {
-            pub fn new(id_path: xilem_core::IdPath, event: impl std::any::Any + $($bounds)*) -> Message {
+            pub fn new<E: std::any::Any + $($bounds)*>(
+                id_path: xilem_core::IdPath,
+                event: E,
+            ) -> Message {
                 Message {
                     id_path,
                     body: Box::new(event),
+                    source: None,
+                    type_name: std::any::type_name::<E>(),
+                }
+            }
+
+            /// Tags the message with the name of its source, for example the widget sending it.
+            pub fn with_source(mut self, source: &'static str) -> Message {
+                self.source = Some(source);
+                self
+            }
+
+            /// The tag set by [`with_source`](Self::with_source).
+            pub fn source(&self) -> Option<&'static str> {
+                self.source
+            }
+
+            /// The type name of the payload, as given by [`std::any::type_name`].
+            pub fn type_name(&self) -> &'static str {
+                self.type_name
+            }
+
+            /// Returns true if the payload is of type `M`.
+            pub fn is<M: std::any::Any>(&self) -> bool {
+                self.body.is::<M>()
+            }
+
+            /// A reference to the payload if it is of type `M`.
+            pub fn downcast_ref<M: std::any::Any>(&self) -> Option<&M> {
+                self.body.downcast_ref::<M>()
+            }
+
+            /// Takes the payload out of the message if it is of type `M`, or returns the
+            /// unchanged message, so it can be passed on to another handler.
+            pub fn downcast<M: std::any::Any>(self) -> Result<M, Message> {
+                let Message {
+                    id_path,
+                    body,
+                    source,
+                    type_name,
+                } = self;
+                match body.downcast::<M>() {
+                    Ok(payload) => Ok(*payload),
+                    Err(body) => Err(Message {
+                        id_path,
+                        body,
+                        source,
+                        type_name,
+                    }),
                 }
             }
         }
+
+        impl std::fmt::Debug for Message {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("Message")
+                    .field("id_path", &self.id_path)
+                    .field("type_name", &self.type_name)
+                    .field("source", &self.source)
+                    .finish_non_exhaustive()
+            }
+        }
     };
 }
 
 /// A result wrapper type for event handlers.
 #[derive(Default)]
-pub enum MessageResult<A> {
+pub enum MessageResult<A, M> {
     /// The event handler was invoked and returned an action.
     ///
     /// Use this return type if your widgets should respond to events by passing
     /// a value up the tree, rather than changing their internal state.
     Action(A),
+    /// The event handler returned several actions, which are handled in order.
+    ///
+    /// One gesture can mean several things to the app, for example a drag across a selection
+    /// deselects the old items and selects the new ones. Build it with
+    /// [`from_actions`](MessageResult::from_actions), the app is rebuilt once after all of them.
+    Actions(Vec<A>),
     /// The event handler received a change request that requests a rebuild.
     ///
     /// Note: A rebuild will always occur if there was a state change. This return
@@ -48,27 +120,190 @@ pub enum MessageResult<A> {
     /// The event was addressed to an id path no longer in the tree.
     ///
     /// This is a normal outcome for async operation when the tree is changing
-    /// dynamically, but otherwise indicates a logic error.
-    Stale(Box<dyn Any>),
+    /// dynamically, but otherwise indicates a logic error. The message is handed back
+    /// unchanged, so the caller can pass it on or log where it came from.
+    Stale(M),
 }
 
 // TODO: does this belong in core?
 pub struct AsyncWake;
 
-impl<A> MessageResult<A> {
-    pub fn map<B>(self, f: impl FnOnce(A) -> B) -> MessageResult<B> {
+impl<A, M> MessageResult<A, M> {
+    /// Returns the actions as one result, [`Nop`](MessageResult::Nop) if there are none.
+    pub fn from_actions(actions: impl IntoIterator<Item = A>) -> Self {
+        let mut actions: Vec<A> = actions.into_iter().collect();
+        match actions.len() {
+            0 => MessageResult::Nop,
+            1 => MessageResult::Action(actions.pop().unwrap()),
+            _ => MessageResult::Actions(actions),
+        }
+    }
+
+    pub fn map<B>(self, mut f: impl FnMut(A) -> B) -> MessageResult<B, M> {
         match self {
             MessageResult::Action(a) => MessageResult::Action(f(a)),
+            MessageResult::Actions(actions) => {
+                MessageResult::Actions(actions.into_iter().map(f).collect())
+            }
             MessageResult::RequestRebuild => MessageResult::RequestRebuild,
             MessageResult::Stale(event) => MessageResult::Stale(event),
             MessageResult::Nop => MessageResult::Nop,
         }
     }
 
-    pub fn or(self, f: impl FnOnce(Box<dyn Any>) -> Self) -> Self {
+    /// Chains a handler producing another result to the actions, if there are any.
+    ///
+    /// The results for a batch of actions are combined with [`merge`](MessageResult::merge).
+    pub fn and_then<B>(self, mut f: impl FnMut(A) -> MessageResult<B, M>) -> MessageResult<B, M> {
         match self {
-            MessageResult::Stale(event) => f(event),
+            MessageResult::Action(a) => f(a),
+            MessageResult::Actions(actions) => actions
+                .into_iter()
+                .fold(MessageResult::Nop, |result, a| result.merge(f(a))),
+            MessageResult::RequestRebuild => MessageResult::RequestRebuild,
+            MessageResult::Stale(event) => MessageResult::Stale(event),
+            MessageResult::Nop => MessageResult::Nop,
+        }
+    }
+
+    /// Combines two results of handling the same message, keeping the actions of both in order.
+    ///
+    /// A rebuild request is dropped next to actions, as they rebuild the app anyway. A stale
+    /// result only remains if both are stale, keeping the first message. Next to a
+    /// [`Nop`](MessageResult::Nop) it is dropped, as the message did reach a handler.
+    pub fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (MessageResult::Stale(message), MessageResult::Stale(_)) => {
+                MessageResult::Stale(message)
+            }
+            (
+                MessageResult::Stale(_) | MessageResult::Nop,
+                MessageResult::Stale(_) | MessageResult::Nop,
+            ) => MessageResult::Nop,
+            (MessageResult::Stale(_) | MessageResult::Nop, other) => other,
+            (result, MessageResult::Stale(_) | MessageResult::Nop) => result,
+            (MessageResult::RequestRebuild, other) => other,
+            (result, MessageResult::RequestRebuild) => result,
+            (first, second) => {
+                let mut actions = first.into_actions();
+                actions.extend(second.into_actions());
+                MessageResult::Actions(actions)
+            }
+        }
+    }
+
+    /// The actions of the result in order, empty if there are none.
+    pub fn into_actions(self) -> Vec<A> {
+        match self {
+            MessageResult::Action(a) => vec![a],
+            MessageResult::Actions(actions) => actions,
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns true if the message resulted in an action or a rebuild request.
+    ///
+    /// Either way the app has to be rebuilt, even if no action reached the app state.
+    pub fn handled(&self) -> bool {
+        matches!(
+            self,
+            MessageResult::Action(_) | MessageResult::Actions(_) | MessageResult::RequestRebuild
+        )
+    }
+
+    /// Hands a stale message to another handler.
+    pub fn or(self, f: impl FnOnce(M) -> Self) -> Self {
+        match self {
+            MessageResult::Stale(message) => f(message),
             _ => self,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::MessageResult::{self, *};
+
+    type TestResult = MessageResult<u32, &'static str>;
+
+    fn summary(result: TestResult) -> String {
+        match result {
+            Action(a) => format!("action {a}"),
+            Actions(actions) => format!("actions {actions:?}"),
+            RequestRebuild => "rebuild".into(),
+            Nop => "nop".into(),
+            Stale(message) => format!("stale {message}"),
+        }
+    }
+
+    /// A result of the given kind, the actions start at `first`.
+    fn result(kind: &str, first: u32) -> TestResult {
+        match kind {
+            "action" => Action(first),
+            "actions" => Actions(vec![first, first + 1]),
+            "rebuild" => RequestRebuild,
+            "nop" => Nop,
+            _ => Stale(if first == 0 { "first" } else { "second" }),
+        }
+    }
+
+    #[test]
+    fn merge_covers_every_pair() {
+        let kinds = ["action", "actions", "rebuild", "nop", "stale"];
+        // One row for each kind of the first result, one column for each kind of the second.
+        let expected = [
+            [
+                "actions [0, 10]",
+                "actions [0, 10, 11]",
+                "action 0",
+                "action 0",
+                "action 0",
+            ],
+            [
+                "actions [0, 1, 10]",
+                "actions [0, 1, 10, 11]",
+                "actions [0, 1]",
+                "actions [0, 1]",
+                "actions [0, 1]",
+            ],
+            [
+                "action 10",
+                "actions [10, 11]",
+                "rebuild",
+                "rebuild",
+                "rebuild",
+            ],
+            ["action 10", "actions [10, 11]", "rebuild", "nop", "nop"],
+            [
+                "action 10",
+                "actions [10, 11]",
+                "rebuild",
+                "nop",
+                "stale first",
+            ],
+        ];
+        for (&first, row) in kinds.iter().zip(expected) {
+            for (&second, expected) in kinds.iter().zip(row) {
+                let merged = result(first, 0).merge(result(second, 10));
+                assert_eq!(summary(merged), expected, "{first} merged with {second}");
+            }
+        }
+    }
+
+    #[test]
+    fn batches_concatenate_in_order() {
+        assert_eq!(summary(MessageResult::from_actions([])), "nop");
+        assert_eq!(summary(MessageResult::from_actions([4])), "action 4");
+
+        let batch: TestResult = MessageResult::from_actions([1, 2, 3]);
+        let chained = batch.and_then(|a| match a {
+            2 => Nop,
+            a => MessageResult::from_actions([a, a * 10]),
+        });
+        assert_eq!(summary(chained), "actions [1, 10, 3, 30]");
+
+        let mapped = result("actions", 5).map(|a| a * 2);
+        assert_eq!(summary(mapped), "actions [10, 12]");
+        assert_eq!(result("actions", 5).into_actions(), [5, 6]);
+    }
+}
@@ -4,7 +4,7 @@
 #[doc(hidden)]
 #[macro_export]
 macro_rules! impl_view_tuple {
-    ( $viewseq:ident, $pod:ty, $cx:ty, $changeflags:ty, $( $t:ident),* ; $( $i:tt ),* ) => {
+    ( $viewseq:ident, $pod:ty, $cx:ty, $changeflags:ty, $message:ty, $( $t:ident),* ; $( $i:tt ),* ) => {
         impl<T, A, $( $t: $viewseq<T, A> ),* > $viewseq<T, A> for ( $( $t, )* ) {
             type State = ( $( $t::State, )*);
 
@@ -19,7 +19,7 @@ macro_rules! impl_view_tuple {
                 cx: &mut $cx,
                 prev: &Self,
                 state: &mut Self::State,
-                els: &mut $crate::VecSplice<$pod>,
+                els: &mut dyn $crate::ElementSplice<$pod>,
             ) -> ChangeFlags {
                 let mut changed = <$changeflags>::default();
                 $(
@@ -33,14 +33,24 @@ macro_rules! impl_view_tuple {
                 &self,
                 id_path: &[$crate::Id],
                 state: &mut Self::State,
-                message: Box<dyn std::any::Any>,
+                message: $message,
                 app_state: &mut T,
-            ) -> $crate::MessageResult<A> {
+            ) -> $crate::MessageResult<A, $message> {
+                let Some(&head) = id_path.first() else {
+                    return $crate::MessageResult::Stale(message);
+                };
+                $(
+                    if self.$i.owns_id(&state.$i, head) {
+                        return self.$i.message(id_path, &mut state.$i, message, app_state);
+                    }
+                )*
                 $crate::MessageResult::Stale(message)
+            }
+
+            fn owns_id(&self, state: &Self::State, id: $crate::Id) -> bool {
+                false
                 $(
-                    .or(|message|{
-                        self.$i.message(id_path, &mut state.$i, message, app_state)
-                    })
+                    || self.$i.owns_id(&state.$i, id)
                 )*
             }
 
@@ -56,7 +66,7 @@ macro_rules! impl_view_tuple {
 
 #[macro_export]
 macro_rules! generate_viewsequence_trait {
-    ($viewseq:ident, $view:ident, $viewmarker: ident, $bound:ident, $cx:ty, $changeflags:ty, $pod:ty; $( $ss:tt )* ) => {
+    ($viewseq:ident, $view:ident, $viewmarker: ident, $bound:ident, $cx:ty, $changeflags:ty, $pod:ty, $message:ty; $( $ss:tt )* ) => {
         /// This trait represents a (possibly empty) sequence of views.
         ///
         /// It is up to the parent view how to lay out and display them.
@@ -75,7 +85,7 @@ macro_rules! generate_viewsequence_trait {
                 cx: &mut $cx,
                 prev: &Self,
                 state: &mut Self::State,
-                element: &mut $crate::VecSplice<$pod>,
+                element: &mut dyn $crate::ElementSplice<$pod>,
             ) -> $changeflags;
 
             /// Propagate a message.
@@ -86,12 +96,17 @@ macro_rules! generate_viewsequence_trait {
                 &self,
                 id_path: &[$crate::Id],
                 state: &mut Self::State,
-                message: Box<dyn std::any::Any>,
+                message: $message,
                 app_state: &mut T,
-            ) -> $crate::MessageResult<A>;
+            ) -> $crate::MessageResult<A, $message>;
 
             /// Returns the current amount of widgets built by this sequence.
             fn count(&self, state: &Self::State) -> usize;
+
+            /// Whether this sequence built the element an id path starting with `id` leads to.
+            ///
+            /// Sequences of sequences use this to hand a message only to the child owning it.
+            fn owns_id(&self, state: &Self::State, id: $crate::Id) -> bool;
         }
 
         impl<T, A, V: $view<T, A> + $viewmarker> $viewseq<T, A> for V
@@ -111,10 +126,10 @@ macro_rules! generate_viewsequence_trait {
                 cx: &mut $cx,
                 prev: &Self,
                 state: &mut Self::State,
-                element: &mut $crate::VecSplice<$pod>,
+                element: &mut dyn $crate::ElementSplice<$pod>,
             ) -> $changeflags {
                 let el = element.mutate();
-                let downcast = el.downcast_mut().unwrap();
+                let downcast = el.expect_mut();
                 let flags = <V as $view<T, A>>::rebuild(
                     self,
                     cx,
@@ -131,9 +146,9 @@ macro_rules! generate_viewsequence_trait {
                 &self,
                 id_path: &[$crate::Id],
                 state: &mut Self::State,
-                message: Box<dyn std::any::Any>,
+                message: $message,
                 app_state: &mut T,
-            ) -> $crate::MessageResult<A> {
+            ) -> $crate::MessageResult<A, $message> {
                 if let Some((first, rest_path)) = id_path.split_first() {
                     if first == &state.1 {
                         return <V as $view<T, A>>::message(
@@ -151,6 +166,10 @@ macro_rules! generate_viewsequence_trait {
             fn count(&self, _state: &Self::State) -> usize {
                 1
             }
+
+            fn owns_id(&self, state: &Self::State, id: $crate::Id) -> bool {
+                id == state.1
+            }
         }
 
         impl<T, A, VT: $viewseq<T, A>> $viewseq<T, A> for Option<VT> {
@@ -171,7 +190,7 @@ macro_rules! generate_viewsequence_trait {
                 cx: &mut $cx,
                 prev: &Self,
                 state: &mut Self::State,
-                element: &mut $crate::VecSplice<$pod>,
+                element: &mut dyn $crate::ElementSplice<$pod>,
             ) -> $changeflags {
                 match (self, &mut *state, prev) {
                     (Some(this), Some(state), Some(prev)) => this.rebuild(cx, prev, state, element),
@@ -183,7 +202,9 @@ macro_rules! generate_viewsequence_trait {
                         <$changeflags>::tree_structure()
                     }
                     (Some(this), None, None) => {
-                        let seq_state = element.as_vec(|vec| this.build(cx, vec));
+                        let mut elements = vec![];
+                        let seq_state = this.build(cx, &mut elements);
+                        element.extend(elements);
                         *state = Some(seq_state);
 
                         <$changeflags>::tree_structure()
@@ -197,9 +218,9 @@ macro_rules! generate_viewsequence_trait {
                 &self,
                 id_path: &[$crate::Id],
                 state: &mut Self::State,
-                message: Box<dyn std::any::Any>,
+                message: $message,
                 app_state: &mut T,
-            ) -> $crate::MessageResult<A> {
+            ) -> $crate::MessageResult<A, $message> {
                 match (self, state) {
                     (Some(vt), Some(state)) => vt.message(id_path, state, message, app_state),
                     (None, None) => $crate::MessageResult::Stale(message),
@@ -214,6 +235,14 @@ macro_rules! generate_viewsequence_trait {
                     _ => panic!("non matching state and prev value"),
                 }
             }
+
+            fn owns_id(&self, state: &Self::State, id: $crate::Id) -> bool {
+                match (self, state) {
+                    (Some(vt), Some(state)) => vt.owns_id(state, id),
+                    (None, None) => false,
+                    _ => panic!("non matching state and prev value"),
+                }
+            }
         }
 
         impl<T, A, VT: $viewseq<T, A>> $viewseq<T, A> for Vec<VT> {
@@ -228,7 +257,7 @@ macro_rules! generate_viewsequence_trait {
                 cx: &mut $cx,
                 prev: &Self,
                 state: &mut Self::State,
-                elements: &mut $crate::VecSplice<$pod>,
+                elements: &mut dyn $crate::ElementSplice<$pod>,
             ) -> $changeflags {
                 let mut changed = <$changeflags>::default();
                 for ((child, child_prev), child_state) in self.iter().zip(prev).zip(state.iter_mut()) {
@@ -249,10 +278,7 @@ macro_rules! generate_viewsequence_trait {
                     for i in prev.len()..n {
                         state.push(self[i].build(cx, &mut child_elements));
                     }
-                    // Discussion question: should VecSplice get an extend method?
-                    for element in child_elements {
-                        elements.push(element);
-                    }
+                    elements.extend(child_elements);
                     changed |= <$changeflags>::tree_structure();
                 }
                 changed
@@ -268,18 +294,28 @@ macro_rules! generate_viewsequence_trait {
                 &self,
                 id_path: &[$crate::Id],
                 state: &mut Self::State,
-                message: Box<dyn std::any::Any>,
+                message: $message,
                 app_state: &mut T,
-            ) -> $crate::MessageResult<A> {
-                let mut result = $crate::MessageResult::Stale(message);
-                for (child, child_state) in self.iter().zip(state) {
-                    if let $crate::MessageResult::Stale(message) = result {
-                        result = child.message(id_path, child_state, message, app_state);
-                    } else {
-                        break;
+            ) -> $crate::MessageResult<A, $message> {
+                let Some(&head) = id_path.first() else {
+                    return $crate::MessageResult::Stale(message);
+                };
+                let owner = self
+                    .iter()
+                    .zip(state)
+                    .find(|(child, child_state)| child.owns_id(child_state, head));
+                match owner {
+                    Some((child, child_state)) => {
+                        child.message(id_path, child_state, message, app_state)
                     }
+                    None => $crate::MessageResult::Stale(message),
                 }
-                result
+            }
+
+            fn owns_id(&self, state: &Self::State, id: $crate::Id) -> bool {
+                self.iter()
+                    .zip(state)
+                    .any(|(child, child_state)| child.owns_id(child_state, id))
             }
         }
 
@@ -295,26 +331,26 @@ macro_rules! generate_viewsequence_trait {
         #[doc = concat!("`", stringify!($viewmarker), "`.")]
         pub trait $viewmarker {}
 
-        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags, ;);
-        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags,
+        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags, $message, ;);
+        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags, $message,
             V0; 0);
-        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags,
+        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags, $message,
             V0, V1; 0, 1);
-        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags,
+        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags, $message,
             V0, V1, V2; 0, 1, 2);
-        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags,
+        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags, $message,
             V0, V1, V2, V3; 0, 1, 2, 3);
-        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags,
+        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags, $message,
             V0, V1, V2, V3, V4; 0, 1, 2, 3, 4);
-        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags,
+        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags, $message,
             V0, V1, V2, V3, V4, V5; 0, 1, 2, 3, 4, 5);
-        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags,
+        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags, $message,
             V0, V1, V2, V3, V4, V5, V6; 0, 1, 2, 3, 4, 5, 6);
-        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags,
+        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags, $message,
             V0, V1, V2, V3, V4, V5, V6, V7; 0, 1, 2, 3, 4, 5, 6, 7);
-        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags,
+        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags, $message,
             V0, V1, V2, V3, V4, V5, V6, V7, V8; 0, 1, 2, 3, 4, 5, 6, 7, 8);
-        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags,
+        $crate::impl_view_tuple!($viewseq, $pod, $cx, $changeflags, $message,
             V0, V1, V2, V3, V4, V5, V6, V7, V8, V9; 0, 1, 2, 3, 4, 5, 6, 7, 8, 9);
     };
 }
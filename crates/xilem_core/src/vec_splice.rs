@@ -1,6 +1,8 @@
 // Copyright 2023 the Druid Authors.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::ElementSplice;
+
 pub struct VecSplice<'a, 'b, T> {
     v: &'a mut Vec<T>,
     scratch: &'b mut Vec<T>,
@@ -73,3 +75,29 @@ impl<'a, 'b, T> VecSplice<'a, 'b, T> {
         }
     }
 }
+
+impl<'a, 'b, T> ElementSplice<T> for VecSplice<'a, 'b, T> {
+    fn skip(&mut self, n: usize) {
+        VecSplice::skip(self, n);
+    }
+
+    fn delete(&mut self, n: usize) {
+        VecSplice::delete(self, n);
+    }
+
+    fn push(&mut self, element: T) {
+        VecSplice::push(self, element);
+    }
+
+    fn extend(&mut self, mut elements: Vec<T>) {
+        self.as_vec(|vec| vec.append(&mut elements));
+    }
+
+    fn mutate(&mut self) -> &mut T {
+        VecSplice::mutate(self)
+    }
+
+    fn len(&self) -> usize {
+        VecSplice::len(self)
+    }
+}
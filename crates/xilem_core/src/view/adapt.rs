@@ -1,6 +1,6 @@
 #[macro_export]
 macro_rules! generate_adapt_view {
-    ($viewtrait:ident, $cx:ty, $changeflags:ty; $($ss:tt)*) => {
+    ($viewtrait:ident, $cx:ty, $changeflags:ty, $message:ty; $($ss:tt)*) => {
         /// A view that wraps a child view and modifies the state that callbacks have access to.
         ///
         /// # Examples
@@ -60,7 +60,7 @@ macro_rules! generate_adapt_view {
             ChildT,
             ChildA,
             V,
-            F = fn(&mut ParentT, AdaptThunk<ChildT, ChildA, V>) -> $crate::MessageResult<ParentA>,
+            F = fn(&mut ParentT, AdaptThunk<ChildT, ChildA, V>) -> $crate::MessageResult<ParentA, $message>,
         > {
             f: F,
             child: V,
@@ -75,13 +75,13 @@ macro_rules! generate_adapt_view {
             child: &'a V,
             state: &'a mut V::State,
             id_path: &'a [$crate::Id],
-            message: Box<dyn std::any::Any>,
+            message: $message,
         }
 
         impl<ParentT, ParentA, ChildT, ChildA, V, F> Adapt<ParentT, ParentA, ChildT, ChildA, V, F>
         where
             V: $viewtrait<ChildT, ChildA>,
-            F: Fn(&mut ParentT, AdaptThunk<ChildT, ChildA, V>) -> $crate::MessageResult<ParentA> $( $ss )*,
+            F: Fn(&mut ParentT, AdaptThunk<ChildT, ChildA, V>) -> $crate::MessageResult<ParentA, $message> $( $ss )*,
         {
             pub fn new(f: F, child: V) -> Self {
                 Adapt {
@@ -93,7 +93,7 @@ macro_rules! generate_adapt_view {
         }
 
         impl<'a, ChildT, ChildA, V: $viewtrait<ChildT, ChildA>> AdaptThunk<'a, ChildT, ChildA, V> {
-            pub fn call(self, app_state: &mut ChildT) -> $crate::MessageResult<ChildA> {
+            pub fn call(self, app_state: &mut ChildT) -> $crate::MessageResult<ChildA, $message> {
                 self.child
                     .message(self.id_path, self.state, self.message, app_state)
             }
@@ -103,7 +103,7 @@ macro_rules! generate_adapt_view {
             for Adapt<ParentT, ParentA, ChildT, ChildA, V, F>
         where
             V: $viewtrait<ChildT, ChildA>,
-            F: Fn(&mut ParentT, AdaptThunk<ChildT, ChildA, V>) -> $crate::MessageResult<ParentA> $( $ss )*,
+            F: Fn(&mut ParentT, AdaptThunk<ChildT, ChildA, V>) -> $crate::MessageResult<ParentA, $message> $( $ss )*,
         {
             type State = V::State;
 
@@ -128,9 +128,9 @@ macro_rules! generate_adapt_view {
                 &self,
                 id_path: &[$crate::Id],
                 state: &mut Self::State,
-                message: Box<dyn std::any::Any>,
+                message: $message,
                 app_state: &mut ParentT,
-            ) -> $crate::MessageResult<ParentA> {
+            ) -> $crate::MessageResult<ParentA, $message> {
                 let thunk = AdaptThunk {
                     child: &self.child,
                     state,
@@ -145,7 +145,7 @@ macro_rules! generate_adapt_view {
             for Adapt<ParentT, ParentA, ChildT, ChildA, V, F>
         where
             V: $viewtrait<ChildT, ChildA>,
-            F: Fn(&mut ParentT, AdaptThunk<ChildT, ChildA, V>) -> $crate::MessageResult<ParentA> $( $ss )*,
+            F: Fn(&mut ParentT, AdaptThunk<ChildT, ChildA, V>) -> $crate::MessageResult<ParentA, $message> $( $ss )*,
         {
         }
     };
@@ -153,7 +153,7 @@ macro_rules! generate_adapt_view {
 
 #[macro_export]
 macro_rules! generate_adapt_state_view {
-    ($viewtrait:ident, $cx:ty, $changeflags:ty; $($ss:tt)*) => {
+    ($viewtrait:ident, $cx:ty, $changeflags:ty, $message:ty; $($ss:tt)*) => {
         /// A view that wraps a child view and modifies the state that callbacks have access to.
         pub struct AdaptState<ParentT, ChildT, V, F = fn(&mut ParentT) -> &mut ChildT> {
             f: F,
@@ -201,9 +201,9 @@ macro_rules! generate_adapt_state_view {
                 &self,
                 id_path: &[$crate::Id],
                 state: &mut Self::State,
-                message: Box<dyn std::any::Any>,
+                message: $message,
                 app_state: &mut ParentT,
-            ) -> $crate::MessageResult<A> {
+            ) -> $crate::MessageResult<A, $message> {
                 self.child
                     .message(id_path, state, message, (self.f)(app_state))
             }
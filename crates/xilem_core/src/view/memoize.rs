@@ -10,7 +10,8 @@ macro_rules! generate_memoize_view {
      $cx:ty,
      $changeflags:ty,
      $staticviewfunction:ident,
-     $memoizeviewfunction:ident;
+     $memoizeviewfunction:ident,
+     $message:ty;
      $($ss:tt)*
     ) => {
         pub struct $memoizeview<D, F> {
@@ -78,9 +79,9 @@ macro_rules! generate_memoize_view {
                 &self,
                 id_path: &[$crate::Id],
                 state: &mut Self::State,
-                event: Box<dyn std::any::Any>,
+                event: $message,
                 app_state: &mut T,
-            ) -> $crate::MessageResult<A> {
+            ) -> $crate::MessageResult<A, $message> {
                 let r = state
                     .view
                     .message(id_path, &mut state.view_state, event, app_state);
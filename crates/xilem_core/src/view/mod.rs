@@ -14,11 +14,13 @@ mod memoize;
 ///    methods, and be responsible for managing element creation & deletion.
 ///  - `$changeflags` - The type that reports down/up the tree. Can be used to avoid
 ///    doing work when we can prove nothing needs doing.
+///  - `$message` - The message type, usually defined with [`message!`](crate::message),
+///    which is passed down the tree and handed back in stale results.
 ///  - `$ss` - (optional) parent traits to this trait (e.g. `:Send`). Also applied to
 ///    the state type requirements
 #[macro_export]
 macro_rules! generate_view_trait {
-    ($viewtrait:ident, $bound:ident, $cx:ty, $changeflags:ty; $($ss:tt)*) => {
+    ($viewtrait:ident, $bound:ident, $cx:ty, $changeflags:ty, $message:ty; $($ss:tt)*) => {
         /// A view object representing a node in the UI.
         ///
         /// This is a central trait for representing UI. An app will generate a tree of
@@ -66,9 +68,9 @@ macro_rules! generate_view_trait {
                 &self,
                 id_path: &[$crate::Id],
                 state: &mut Self::State,
-                message: Box<dyn std::any::Any>,
+                message: $message,
                 app_state: &mut T,
-            ) -> $crate::MessageResult<A>;
+            ) -> $crate::MessageResult<A, $message>;
         }
     };
 }
@@ -6,9 +6,9 @@ use std::{cell::RefCell, rc::Rc};
 use crate::{
     context::Cx,
     view::{DomNode, View},
-    Message,
+    Message, MessageResult,
 };
-use xilem_core::{Id, MessageResult};
+use xilem_core::Id;
 
 /// The type responsible for running your app.
 pub struct App<T, V: View<T>, F: FnMut(&mut T) -> V>(Rc<RefCell<AppInner<T, V, F>>>);
@@ -90,14 +90,15 @@ impl<T: 'static, V: View<T> + 'static, F: FnMut(&mut T) -> V + 'static> AppRunne
         let mut inner_guard = self.0.borrow_mut();
         let inner = &mut *inner_guard;
         if let Some(view) = &mut inner.view {
+            let id_path = message.id_path.clone();
             let message_result = view.message(
-                &message.id_path[1..],
+                &id_path[1..],
                 inner.state.as_mut().unwrap(),
-                message.body,
+                message,
                 &mut inner.data,
             );
             match message_result {
-                MessageResult::Nop | MessageResult::Action(_) => {
+                MessageResult::Nop | MessageResult::Action(_) | MessageResult::Actions(_) => {
                     // Nothing to do.
                 }
                 MessageResult::RequestRebuild => {
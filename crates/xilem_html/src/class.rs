@@ -1,13 +1,14 @@
 // Copyright 2023 the Druid Authors.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{any::Any, borrow::Cow};
+use std::borrow::Cow;
 
-use xilem_core::{Id, MessageResult};
+use xilem_core::Id;
 
 use crate::{
     context::{ChangeFlags, Cx},
     view::{DomElement, View, ViewMarker},
+    Message, MessageResult,
 };
 
 pub struct Class<V> {
@@ -67,7 +68,7 @@ where
         &self,
         id_path: &[Id],
         state: &mut Self::State,
-        message: Box<dyn Any>,
+        message: Message,
         app_state: &mut T,
     ) -> MessageResult<()> {
         self.child.message(id_path, state, message, app_state)
@@ -108,10 +108,7 @@ impl Default for Cx {
 
 impl MessageThunk {
     pub fn push_message(&self, message_body: impl Any + 'static) {
-        let message = Message {
-            id_path: self.id_path.clone(),
-            body: Box::new(message_body),
-        };
+        let message = Message::new(self.id_path.clone(), message_body);
         self.app_ref.handle_message(message);
     }
 }
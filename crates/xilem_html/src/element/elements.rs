@@ -90,9 +90,9 @@ macro_rules! element {
                 &self,
                 id_path: &[xilem_core::Id],
                 state: &mut Self::State,
-                message: Box<dyn std::any::Any>,
+                message: crate::Message,
                 app_state: &mut T_,
-            ) -> xilem_core::MessageResult<A_> {
+            ) -> crate::MessageResult<A_> {
                 self.0.message(id_path, state, message, app_state)
             }
         }
@@ -5,11 +5,12 @@
 use crate::{
     context::{ChangeFlags, Cx},
     view::{DomElement, Pod, View, ViewMarker, ViewSequence},
+    Message, MessageResult,
 };
 
 use std::{borrow::Cow, cmp::Ordering, collections::BTreeMap, fmt};
 use wasm_bindgen::{JsCast, UnwrapThrowExt};
-use xilem_core::{Id, MessageResult, VecSplice};
+use xilem_core::{Id, VecSplice};
 
 #[cfg(feature = "typed")]
 pub mod elements;
@@ -249,7 +250,7 @@ where
         &self,
         id_path: &[Id],
         state: &mut Self::State,
-        message: Box<dyn std::any::Any>,
+        message: Message,
         app_state: &mut T,
     ) -> MessageResult<A> {
         self.children
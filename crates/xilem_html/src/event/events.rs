@@ -103,9 +103,9 @@ macro_rules! event {
                 &self,
                 id_path: &[xilem_core::Id],
                 state: &mut Self::State,
-                message: Box<dyn std::any::Any>,
+                message: crate::Message,
                 app_state: &mut T,
-            ) -> xilem_core::MessageResult<A> {
+            ) -> crate::MessageResult<A> {
                 self.inner.message(id_path, state, message, app_state)
             }
         }
@@ -4,15 +4,16 @@
 #[cfg(feature = "typed")]
 pub mod events;
 
-use std::{any::Any, marker::PhantomData, ops::Deref};
+use std::{marker::PhantomData, ops::Deref};
 
 use gloo::events::{EventListener, EventListenerOptions};
 use wasm_bindgen::{JsCast, UnwrapThrowExt};
-use xilem_core::{Id, MessageResult};
+use xilem_core::Id;
 
 use crate::{
     context::{ChangeFlags, Cx},
     view::{DomNode, View, ViewMarker},
+    Message, MessageResult,
 };
 
 /// Wraps a [`View`] `V` and attaches an event listener.
@@ -106,7 +107,7 @@ where
         &self,
         id_path: &[Id],
         state: &mut Self::State,
-        message: Box<dyn Any>,
+        message: Message,
         app_state: &mut T,
     ) -> MessageResult<A> {
         match message.downcast_ref::<EventMsg<Event<E, V::Element>>>() {
@@ -17,8 +17,6 @@ mod view;
 #[cfg(feature = "typed")]
 mod view_ext;
 
-pub use xilem_core::MessageResult;
-
 pub use app::App;
 pub use class::class;
 pub use context::{ChangeFlags, Cx};
@@ -38,6 +36,9 @@ pub use view_ext::ViewExt;
 
 xilem_core::message!();
 
+/// The result of delivering a [`Message`] to a view, stale messages are handed back unchanged.
+pub type MessageResult<A> = xilem_core::MessageResult<A, Message>;
+
 /// The HTML namespace: `http://www.w3.org/1999/xhtml`
 pub const HTML_NS: &str = "http://www.w3.org/1999/xhtml";
 /// The SVG namespace: `http://www.w3.org/2000/svg`
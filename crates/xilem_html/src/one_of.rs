@@ -80,9 +80,9 @@ macro_rules! one_of {
                 &self,
                 id_path: &[xilem_core::Id],
                 state: &mut Self::State,
-                message: Box<dyn std::any::Any>,
+                message: crate::Message,
                 app_state: &mut VT,
-            ) -> xilem_core::MessageResult<VA> {
+            ) -> crate::MessageResult<VA> {
                 match self {
                     $(
                         $ident::$vars(view) => {
@@ -119,7 +119,7 @@ macro_rules! one_of {
                 cx: &mut Cx,
                 prev: &Self,
                 state: &mut Self::State,
-                element: &mut xilem_core::VecSplice<Pod>,
+                element: &mut dyn xilem_core::ElementSplice<Pod>,
             ) -> ChangeFlags {
                 match (prev, self) {
                     $(
@@ -136,8 +136,9 @@ macro_rules! one_of {
                         }
                         // Variant has changed
                         (_, $ident::$vars(view_sequence)) => {
-                            let new_state =
-                                element.as_vec(|elements| view_sequence.build(cx, elements));
+                            let mut elements = vec![];
+                            let new_state = view_sequence.build(cx, &mut elements);
+                            element.extend(elements);
                             *state = $ident::$vars(new_state);
                             ChangeFlags::STRUCTURE
                         }
@@ -149,9 +150,9 @@ macro_rules! one_of {
                 &self,
                 id_path: &[xilem_core::Id],
                 state: &mut Self::State,
-                message: Box<dyn std::any::Any>,
+                message: crate::Message,
                 app_state: &mut VT,
-            ) -> xilem_core::MessageResult<VA> {
+            ) -> crate::MessageResult<VA> {
                 match self {
                     $(
                         $ident::$vars(view_sequence) => {
@@ -184,6 +185,23 @@ macro_rules! one_of {
                     )+
                 }
             }
+
+            fn owns_id(&self, state: &Self::State, id: xilem_core::Id) -> bool {
+                match self {
+                    $(
+                        $ident::$vars(view_sequence) => {
+                            let $ident::$vars(state) = state else {
+                                throw_str(concat!(
+                                    "invalid state/view_sequence in ",
+                                    stringify!($ident),
+                                    " (unreachable)",
+                                ));
+                            };
+                            view_sequence.owns_id(state, id)
+                        }
+                    )+
+                }
+            }
         }
 
     };
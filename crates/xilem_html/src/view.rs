@@ -6,9 +6,9 @@
 
 use std::{any::Any, borrow::Cow, ops::Deref};
 
-use xilem_core::{Id, MessageResult};
+use xilem_core::Id;
 
-use crate::{context::Cx, ChangeFlags};
+use crate::{context::Cx, ChangeFlags, Message, MessageResult};
 
 mod sealed {
     pub trait Sealed {}
@@ -91,17 +91,27 @@ impl Pod {
         self.0.as_any_mut().downcast_mut()
     }
 
+    #[track_caller]
+    fn expect_mut<T: 'static>(&mut self) -> &mut T {
+        self.downcast_mut().unwrap_or_else(|| {
+            panic!(
+                "expected the node in the pod to be a `{}`",
+                std::any::type_name::<T>()
+            )
+        })
+    }
+
     fn mark(&mut self, flags: ChangeFlags) -> ChangeFlags {
         flags
     }
 }
 
-xilem_core::generate_view_trait! {View, DomNode, Cx, ChangeFlags;}
-xilem_core::generate_viewsequence_trait! {ViewSequence, View, ViewMarker, DomNode, Cx, ChangeFlags, Pod;}
-xilem_core::generate_anyview_trait! {AnyView, View, ViewMarker, Cx, ChangeFlags, AnyNode, BoxedView;}
-xilem_core::generate_memoize_view! {Memoize, MemoizeState, View, ViewMarker, Cx, ChangeFlags, s, memoize;}
-xilem_core::generate_adapt_view! {View, Cx, ChangeFlags;}
-xilem_core::generate_adapt_state_view! {View, Cx, ChangeFlags;}
+xilem_core::generate_view_trait! {View, DomNode, Cx, ChangeFlags, Message;}
+xilem_core::generate_viewsequence_trait! {ViewSequence, View, ViewMarker, DomNode, Cx, ChangeFlags, Pod, Message;}
+xilem_core::generate_anyview_trait! {AnyView, View, ViewMarker, Cx, ChangeFlags, AnyNode, BoxedView, Message;}
+xilem_core::generate_memoize_view! {Memoize, MemoizeState, View, ViewMarker, Cx, ChangeFlags, s, memoize, Message;}
+xilem_core::generate_adapt_view! {View, Cx, ChangeFlags, Message;}
+xilem_core::generate_adapt_state_view! {View, Cx, ChangeFlags, Message;}
 
 // strings -> text nodes
 
@@ -136,7 +146,7 @@ impl<T, A> View<T, A> for &'static str {
         &self,
         _id_path: &[Id],
         _state: &mut Self::State,
-        _message: Box<dyn std::any::Any>,
+        _message: Message,
         _app_state: &mut T,
     ) -> MessageResult<A> {
         MessageResult::Nop
@@ -174,7 +184,7 @@ impl<T, A> View<T, A> for String {
         &self,
         _id_path: &[Id],
         _state: &mut Self::State,
-        _message: Box<dyn std::any::Any>,
+        _message: Message,
         _app_state: &mut T,
     ) -> MessageResult<A> {
         MessageResult::Nop
@@ -212,7 +222,7 @@ impl<T, A> View<T, A> for Cow<'static, str> {
         &self,
         _id_path: &[Id],
         _state: &mut Self::State,
-        _message: Box<dyn std::any::Any>,
+        _message: Message,
         _app_state: &mut T,
     ) -> MessageResult<A> {
         MessageResult::Nop
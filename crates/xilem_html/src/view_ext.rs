@@ -66,7 +66,7 @@ pub trait ViewExt<T, A>: View<T, A> + Sized {
 
     fn adapt<ParentT, ParentA, F>(self, f: F) -> Adapt<ParentT, ParentA, T, A, Self, F>
     where
-        F: Fn(&mut ParentT, AdaptThunk<T, A, Self>) -> xilem_core::MessageResult<ParentA>,
+        F: Fn(&mut ParentT, AdaptThunk<T, A, Self>) -> crate::MessageResult<ParentA>,
     {
         Adapt::new(f, self)
     }
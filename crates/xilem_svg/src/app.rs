@@ -93,10 +93,11 @@ impl<T: 'static, V: View<T> + 'static, F: FnMut(&mut T) -> V + 'static> AppRunne
         let mut inner_guard = self.0.borrow_mut();
         let inner = &mut *inner_guard;
         if let Some(view) = &mut inner.view {
+            let id_path = message.id_path.clone();
             view.message(
-                &message.id_path[1..],
+                &id_path[1..],
                 inner.state.as_mut().unwrap(),
-                message.body,
+                message,
                 &mut inner.data,
             );
             let new_view = (inner.app_logic)(&mut inner.data);
@@ -1,13 +1,12 @@
 // Copyright 2023 the Druid Authors.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::any::Any;
-
-use xilem_core::{Id, MessageResult};
+use xilem_core::Id;
 
 use crate::{
     context::{ChangeFlags, Cx},
     view::{DomElement, View, ViewMarker},
+    Message, MessageResult,
 };
 
 pub struct Class<V> {
@@ -63,7 +62,7 @@ impl<T, V: View<T>> View<T> for Class<V> {
         &self,
         id_path: &[Id],
         state: &mut Self::State,
-        message: Box<dyn Any>,
+        message: Message,
         app_state: &mut T,
     ) -> MessageResult<()> {
         self.child.message(id_path, state, message, app_state)
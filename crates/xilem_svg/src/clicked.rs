@@ -1,16 +1,15 @@
 // Copyright 2023 the Druid Authors.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::any::Any;
-
 use wasm_bindgen::{prelude::Closure, JsCast};
 use web_sys::SvgElement;
 
-use xilem_core::{Id, MessageResult};
+use xilem_core::Id;
 
 use crate::{
     context::{ChangeFlags, Cx},
     view::{DomElement, View, ViewMarker},
+    Message, MessageResult,
 };
 
 pub struct Clicked<V, F> {
@@ -72,7 +71,7 @@ impl<T, F: Fn(&mut T) + Send, V: View<T>> View<T> for Clicked<V, F> {
         &self,
         id_path: &[Id],
         state: &mut Self::State,
-        message: Box<dyn Any>,
+        message: Message,
         app_state: &mut T,
     ) -> MessageResult<()> {
         if message.downcast_ref::<ClickedMsg>().is_some() {
@@ -5,11 +5,12 @@
 
 use web_sys::Element;
 
-use xilem_core::{Id, MessageResult, VecSplice};
+use xilem_core::{Id, VecSplice};
 
 use crate::{
     context::{ChangeFlags, Cx},
     view::{Pod, View, ViewMarker, ViewSequence},
+    Message, MessageResult,
 };
 
 pub struct Group<VS> {
@@ -82,7 +83,7 @@ where
         &self,
         id_path: &[Id],
         state: &mut Self::State,
-        message: Box<dyn std::any::Any>,
+        message: Message,
         app_state: &mut T,
     ) -> MessageResult<A> {
         self.children
@@ -6,12 +6,13 @@
 use kurbo::{BezPath, Circle, Line, Rect};
 use web_sys::Element;
 
-use xilem_core::{Id, MessageResult};
+use xilem_core::Id;
 
 use crate::{
     context::{ChangeFlags, Cx},
     pointer::PointerMsg,
     view::{View, ViewMarker},
+    Message, MessageResult,
 };
 
 pub trait KurboShape: Sized {
@@ -98,7 +99,7 @@ impl<T> View<T> for Line {
         &self,
         _id_path: &[Id],
         _state: &mut Self::State,
-        message: Box<dyn std::any::Any>,
+        message: Message,
         _app_state: &mut T,
     ) -> MessageResult<()> {
         MessageResult::Stale(message)
@@ -165,7 +166,7 @@ impl<T> View<T> for Rect {
         &self,
         _id_path: &[Id],
         _state: &mut Self::State,
-        message: Box<dyn std::any::Any>,
+        message: Message,
         _app_state: &mut T,
     ) -> MessageResult<()> {
         MessageResult::Stale(message)
@@ -226,7 +227,7 @@ impl<T> View<T> for Circle {
         &self,
         _id_path: &[Id],
         _state: &mut Self::State,
-        message: Box<dyn std::any::Any>,
+        message: Message,
         _app_state: &mut T,
     ) -> MessageResult<()> {
         MessageResult::Stale(message)
@@ -269,7 +270,7 @@ impl<T> View<T> for BezPath {
         &self,
         _id_path: &[Id],
         _state: &mut Self::State,
-        message: Box<dyn std::any::Any>,
+        message: Message,
         _app_state: &mut T,
     ) -> MessageResult<()> {
         MessageResult::Stale(message)
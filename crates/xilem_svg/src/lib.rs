@@ -28,6 +28,9 @@ pub use context::ChangeFlags;
 
 xilem_core::message!(Send);
 
+/// The result of delivering a [`Message`] to a view, stale messages are handed back unchanged.
+pub type MessageResult<A> = xilem_core::MessageResult<A, Message>;
+
 #[derive(Default)]
 struct AppState {
     x: f64,
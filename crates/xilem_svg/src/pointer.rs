@@ -3,16 +3,15 @@
 
 //! Interactivity with pointer events.
 
-use std::any::Any;
-
 use wasm_bindgen::{prelude::Closure, JsCast};
 use web_sys::PointerEvent;
 
-use xilem_core::{Id, MessageResult};
+use xilem_core::Id;
 
 use crate::{
     context::{ChangeFlags, Cx},
     view::{DomElement, View, ViewMarker},
+    Message, MessageResult,
 };
 
 pub struct Pointer<V, F> {
@@ -127,12 +126,12 @@ impl<T, F: Fn(&mut T, PointerMsg) + Send, V: View<T>> View<T> for Pointer<V, F>
         &self,
         id_path: &[Id],
         state: &mut Self::State,
-        message: Box<dyn Any>,
+        message: Message,
         app_state: &mut T,
     ) -> MessageResult<()> {
         match message.downcast() {
             Ok(msg) => {
-                (self.callback)(app_state, *msg);
+                (self.callback)(app_state, msg);
                 MessageResult::Action(())
             }
             Err(message) => self
@@ -6,7 +6,7 @@
 
 use std::ops::Deref;
 
-use crate::{context::Cx, ChangeFlags};
+use crate::{context::Cx, ChangeFlags, Message};
 
 // A possible refinement of xilem_core is to allow a single concrete type
 // for a view element, rather than an associated type with a bound.
@@ -67,14 +67,24 @@ impl Pod {
         self.0.as_any_mut().downcast_mut()
     }
 
+    #[track_caller]
+    fn expect_mut<T: 'static>(&mut self) -> &mut T {
+        self.downcast_mut().unwrap_or_else(|| {
+            panic!(
+                "expected the node in the pod to be a `{}`",
+                std::any::type_name::<T>()
+            )
+        })
+    }
+
     fn mark(&mut self, flags: ChangeFlags) -> ChangeFlags {
         flags
     }
 }
 
-xilem_core::generate_view_trait! {View, DomElement, Cx, ChangeFlags;}
-xilem_core::generate_viewsequence_trait! {ViewSequence, View, ViewMarker, DomElement, Cx, ChangeFlags, Pod;}
-xilem_core::generate_anyview_trait! {AnyView, View, ViewMarker, Cx, ChangeFlags, AnyElement, BoxedView;}
-xilem_core::generate_memoize_view! {Memoize, MemoizeState, View, ViewMarker, Cx, ChangeFlags, s, memoize;}
-xilem_core::generate_adapt_view! {View, Cx, ChangeFlags;}
-xilem_core::generate_adapt_state_view! {View, Cx, ChangeFlags;}
+xilem_core::generate_view_trait! {View, DomElement, Cx, ChangeFlags, Message;}
+xilem_core::generate_viewsequence_trait! {ViewSequence, View, ViewMarker, DomElement, Cx, ChangeFlags, Pod, Message;}
+xilem_core::generate_anyview_trait! {AnyView, View, ViewMarker, Cx, ChangeFlags, AnyElement, BoxedView, Message;}
+xilem_core::generate_memoize_view! {Memoize, MemoizeState, View, ViewMarker, Cx, ChangeFlags, s, memoize, Message;}
+xilem_core::generate_adapt_view! {View, Cx, ChangeFlags, Message;}
+xilem_core::generate_adapt_state_view! {View, Cx, ChangeFlags, Message;}
@@ -0,0 +1,63 @@
+use xilem::view::{button, h_stack, label, scoped, slider, v_stack, View};
+use xilem::{App, AppLauncher};
+
+#[derive(Clone, Copy)]
+enum Channel {
+    Red,
+    Green,
+    Blue,
+}
+
+enum PickerMsg {
+    Set(Channel, u8),
+    Reset,
+}
+
+/// A color picker keeping its color to itself, the app never sees it.
+fn color_picker<T: 'static>(name: &'static str) -> impl View<T> {
+    scoped(
+        || [0x80u8; 3],
+        move |rgb: &[u8; 3]| {
+            let channel = |channel: Channel| {
+                slider(
+                    rgb[channel as usize] as f64,
+                    0.0..255.0,
+                    move |_: &mut T, value| PickerMsg::Set(channel, value as u8),
+                )
+            };
+            v_stack((
+                h_stack((
+                    label(format!(
+                        "{name}: #{:02x}{:02x}{:02x}",
+                        rgb[0], rgb[1], rgb[2]
+                    )),
+                    button("Reset", |_: &mut T| PickerMsg::Reset),
+                )),
+                channel(Channel::Red),
+                channel(Channel::Green),
+                channel(Channel::Blue),
+            ))
+        },
+        |rgb: &mut [u8; 3], msg| match msg {
+            PickerMsg::Set(channel, value) => rgb[channel as usize] = value,
+            PickerMsg::Reset => *rgb = [0x80; 3],
+        },
+    )
+}
+
+fn app_logic(clicks: &mut u32) -> impl View<u32> {
+    v_stack((
+        // Rebuilding after this click does not reset the pickers.
+        button(format!("Clicked {clicks} times"), |clicks: &mut u32| {
+            *clicks += 1;
+        }),
+        color_picker("Foreground"),
+        color_picker("Background"),
+    ))
+    .with_spacing(10.0)
+}
+
+fn main() {
+    let app = App::new(0, app_logic);
+    AppLauncher::new(app).run()
+}
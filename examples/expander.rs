@@ -0,0 +1,39 @@
+use xilem::view::{button, label, use_state, v_stack, Local, View, ViewMarker};
+use xilem::{App, AppLauncher};
+
+/// A section which can be expanded and collapsed.
+///
+/// Whether it is open is local to the view, so it works with any app state.
+fn expander<T>(title: &'static str, body: &'static str) -> impl View<T> + ViewMarker {
+    use_state(
+        || false,
+        move |open: &Local<bool>| {
+            let arrow = if **open { "▾" } else { "▸" };
+            v_stack((
+                button(format!("{arrow} {title}"), |open: &mut Local<bool>| {
+                    **open = !**open;
+                }),
+                label(if **open { body } else { "" }),
+            ))
+        },
+    )
+}
+
+fn app_logic(_data: &mut ()) -> impl View<()> {
+    v_stack((
+        expander(
+            "What is this?",
+            "Each section keeps track of whether it is open.",
+        ),
+        expander(
+            "Where is that stored?",
+            "In the view tree, not in the app state.",
+        ),
+    ))
+    .with_spacing(10.0)
+}
+
+fn main() {
+    let app = App::new((), app_logic);
+    AppLauncher::new(app).run()
+}
@@ -0,0 +1,71 @@
+use vello::kurbo::{Affine, BezPath, Point, Size};
+use vello::peniko::{Color, Stroke};
+use xilem::view::{button, canvas, h_stack, v_stack};
+use xilem::{view::View, App, AppLauncher};
+
+struct AppData {
+    values: Vec<f64>,
+    seed: u64,
+}
+
+impl AppData {
+    /// Appends a pseudo random walk step, keeping the last 50 values.
+    fn push_value(&mut self) {
+        self.seed = self.seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+        let step = (self.seed >> 33) as f64 / (1u64 << 31) as f64 - 0.5;
+        let last = self.values.last().copied().unwrap_or(0.5);
+        self.values.push((last + step * 0.3).clamp(0.0, 1.0));
+        if self.values.len() > 50 {
+            self.values.remove(0);
+        }
+    }
+}
+
+fn sparkline_path(values: &[f64], size: Size) -> BezPath {
+    let mut path = BezPath::new();
+    let step = size.width / (values.len().max(2) - 1) as f64;
+    for (i, value) in values.iter().enumerate() {
+        let point = Point::new(i as f64 * step, (1.0 - value) * size.height);
+        if i == 0 {
+            path.move_to(point);
+        } else {
+            path.line_to(point);
+        }
+    }
+    path
+}
+
+fn app_logic(data: &mut AppData) -> impl View<AppData> {
+    let values = data.values.clone();
+    v_stack((
+        canvas(move |builder, size| {
+            let path = sparkline_path(&values, size);
+            builder.stroke(
+                &Stroke::new(2.0),
+                Affine::IDENTITY,
+                Color::rgb8(0x5a, 0x9b, 0xe6),
+                None,
+                &path,
+            );
+        })
+        .preferred_size((400.0, 120.0))
+        .key(data.values.clone()),
+        h_stack((
+            button("add", |data: &mut AppData| data.push_value()),
+            button("clear", |data: &mut AppData| data.values.clear()),
+        )),
+    ))
+    .with_spacing(20.0)
+}
+
+fn main() {
+    let mut data = AppData {
+        values: vec![],
+        seed: 1,
+    };
+    for _ in 0..20 {
+        data.push_value();
+    }
+    let app = App::new(data, app_logic);
+    AppLauncher::new(app).run()
+}
@@ -0,0 +1,57 @@
+use vello::peniko::Color;
+use xilem::view::{button, checkbox, slider, themed, v_stack, with_theme, View};
+use xilem::{App, AppLauncher, Theme, ThemeOverride};
+
+struct AppData {
+    dark: bool,
+    checked: bool,
+    volume: f64,
+}
+
+fn app_logic(data: &mut AppData) -> impl View<AppData> {
+    let theme = if data.dark {
+        Theme::dark()
+    } else {
+        Theme::light()
+    };
+    let warning = ThemeOverride {
+        accent: Some(Color::rgb8(0xe6, 0x7e, 0x22)),
+        ..Default::default()
+    };
+    with_theme(
+        theme,
+        v_stack((
+            button(
+                if data.dark { "Light" } else { "Dark" },
+                |data: &mut AppData| {
+                    data.dark = !data.dark;
+                },
+            ),
+            checkbox(data.checked, |data: &mut AppData, checked| {
+                data.checked = checked;
+            })
+            .with_label("Enabled"),
+            slider(data.volume, 0.0..1.0, |data: &mut AppData, volume| {
+                data.volume = volume;
+            }),
+            // Only this slider uses the warning color.
+            themed(
+                warning,
+                slider(data.volume, 0.0..1.0, |data: &mut AppData, volume| {
+                    data.volume = volume;
+                }),
+            ),
+        ))
+        .with_spacing(10.0),
+    )
+}
+
+fn main() {
+    let data = AppData {
+        dark: true,
+        checked: false,
+        volume: 0.5,
+    };
+    let app = App::new(data, app_logic);
+    AppLauncher::new(app).run()
+}
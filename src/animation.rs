@@ -0,0 +1,135 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interpolation of values for animations, see [`animate`](crate::view::animate).
+
+use vello::kurbo::{Point, Size, Vec2};
+use vello::peniko::Color;
+
+/// A value which can be blended with another one of the same type.
+pub trait Lerp: Clone + PartialEq {
+    /// The value `t` of the way from `self` to `other`, where `t` is usually between 0 and 1.
+    fn lerp(&self, other: &Self, t: f64) -> Self;
+}
+
+impl Lerp for f64 {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for Point {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Point::lerp(*self, *other, t)
+    }
+}
+
+impl Lerp for Vec2 {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Vec2::lerp(*self, *other, t)
+    }
+}
+
+impl Lerp for Size {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Size::new(
+            self.width.lerp(&other.width, t),
+            self.height.lerp(&other.height, t),
+        )
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        let channel =
+            |a: u8, b: u8| (a as f64).lerp(&(b as f64), t).round().clamp(0.0, 255.0) as u8;
+        Color::rgba8(
+            channel(self.r, other.r),
+            channel(self.g, other.g),
+            channel(self.b, other.b),
+            channel(self.a, other.a),
+        )
+    }
+}
+
+/// How the progress of an animation maps to the progress of the animated value.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing {
+    Linear,
+    /// Starts and ends slowly, the same as `CubicBezier(0.42, 0.0, 0.58, 1.0)`.
+    #[default]
+    EaseInOut,
+    /// A cubic Bézier curve from (0, 0) to (1, 1) with the two given control points, as in CSS.
+    CubicBezier(f64, f64, f64, f64),
+}
+
+impl Easing {
+    /// The progress of the value at the progress `t` of the animation, both between 0 and 1.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+            Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+        }
+    }
+}
+
+/// The y of the point on the curve with the x `x`.
+fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, x: f64) -> f64 {
+    let bezier = |a: f64, b: f64, s: f64| {
+        let r = 1.0 - s;
+        3.0 * r * r * s * a + 3.0 * r * s * s * b + s * s * s
+    };
+    // x grows monotonically with the curve parameter for control points between 0 and 1.
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..32 {
+        let mid = (low + high) * 0.5;
+        if bezier(x1, x2, mid) < x {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    bezier(y1, y2, (low + high) * 0.5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn easing_keeps_the_ends_and_is_monotonic() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseInOut,
+            Easing::CubicBezier(0.25, 0.1, 0.25, 1.0),
+        ] {
+            assert!(easing.apply(0.0).abs() < 1e-6);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-6);
+            let samples: Vec<_> = (0..=20).map(|i| easing.apply(i as f64 / 20.0)).collect();
+            assert!(samples.windows(2).all(|pair| pair[0] <= pair[1]));
+        }
+        assert!((Easing::EaseInOut.apply(0.5) - 0.5).abs() < 1e-6);
+        assert!(Easing::EaseInOut.apply(0.1) < 0.1);
+    }
+
+    #[test]
+    fn colors_blend_per_channel() {
+        let from = Color::rgba8(0, 100, 200, 255);
+        let to = Color::rgba8(100, 100, 0, 55);
+        assert_eq!(from.lerp(&to, 0.5), Color::rgba8(50, 100, 100, 155));
+    }
+}
@@ -13,26 +13,30 @@
 // limitations under the License.
 
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::SyncSender;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use accesskit::TreeUpdate;
-use glazier::{IdleHandle, IdleToken, WindowHandle};
+use glazier::{Cursor, IdleHandle, IdleToken, WindowHandle};
 use parley::FontContext;
 use tokio::runtime::Runtime;
 use vello::kurbo::{Point, Rect, Size};
 use vello::SceneFragment;
-use xilem_core::{AsyncWake, MessageResult};
 
+use crate::commands::CommandQueue;
+use crate::recording::{tree_hash, Input, Recorder, Replayer};
 use crate::widget::{
-    AccessCx, BoxConstraints, CxState, EventCx, LayoutCx, LifeCycle, LifeCycleCx, PaintCx, Pod,
-    PodFlags, UpdateCx, ViewContext, WidgetState,
+    AccessCx, BoxConstraints, ChangeFlags, CxState, Damage, EventCx, HitChain, LayoutCx, LifeCycle,
+    LifeCycleCx, MockTimerSource, OverlayHost, PaintCx, Pod, PodFlags, SystemTimerSource,
+    TimerSource, UpdateCx, ViewContext, WidgetHandle, WidgetQuery, WidgetState, WindowState,
 };
 use crate::{
     view::{Cx, Id, View},
     widget::Event,
 };
-use crate::{IdPath, Message};
+use crate::{IdPath, Message, MessageResult, Profiler};
 
 /// App is the native backend implementation of Xilem. It contains the code interacting with glazier
 /// and vello.
@@ -49,7 +53,16 @@ pub struct App<T, V: View<T>> {
     root_pod: Option<Pod>,
     size: Size,
     new_size: Size,
+    /// Whether the window reported an empty size, which it does while it is minimized.
+    is_minimized: bool,
     cursor_pos: Option<Point>,
+    /// The cursor last handed to the shell.
+    shell_cursor: Option<Cursor>,
+    window_state: WindowState,
+    timer_source: Box<dyn TimerSource>,
+    last_anim_frame: Option<Instant>,
+    recorder: Option<Recorder>,
+    replay: Option<Replay>,
     cx: Cx,
     font_cx: FontContext,
     pub(crate) rt: Runtime,
@@ -58,6 +71,7 @@ pub struct App<T, V: View<T>> {
     window_id: crate::id::Id,
     pub(crate) accesskit_connected: bool,
     node_classes: accesskit::NodeClassSet,
+    log_stale_messages: Arc<AtomicBool>,
 }
 
 /// The standard delay for waiting for async futures.
@@ -80,13 +94,16 @@ struct AppTask<T, V: View<T>, F: FnMut(&mut T) -> V> {
     idle_handle: Option<IdleHandle>,
     pending_async: HashSet<Id>,
     ui_state: UiState,
+    log_stale_messages: Arc<AtomicBool>,
+    /// Delivers the messages sent by commands.
+    wake_chan: SyncSender<Message>,
 }
 
 /// A message sent from the main UI thread ([`App`]) to the [`AppTask`].
 pub(crate) enum AppReq {
     SetIdleHandle(IdleHandle),
     Events(Vec<Message>),
-    Wake(IdPath),
+    Wake(Message),
     // Parameter indicates whether it should be delayed for async
     Render(bool),
 }
@@ -113,6 +130,36 @@ enum UiState {
     WokeUI,
 }
 
+/// A recording being played back by the [`App`], on a clock of its own.
+struct Replay {
+    replayer: Replayer,
+    clock: MockTimerSource,
+    /// The time of the clock when the recording starts.
+    start: Instant,
+    /// The wall-clock time of the first frame of the replay.
+    started: Option<Instant>,
+    /// The hash and time of a checkpoint to check once the frame is painted.
+    checkpoint: Option<(u64, u64)>,
+}
+
+impl Replay {
+    /// Moves the clock forward to `at` into the recording.
+    fn advance_to(&mut self, at: Duration) {
+        let now = self.clock.now();
+        let target = self.start + at;
+        if target > now {
+            self.clock.advance(target - now);
+        }
+    }
+
+    /// The wall-clock time when the clock reaches `instant`.
+    fn wall_time(&self, instant: Instant) -> Instant {
+        let started = self.started.unwrap_or_else(Instant::now);
+        let at = instant.saturating_duration_since(self.start);
+        started + at.div_f64(self.replayer.speed())
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct WakeQueue(Arc<Mutex<Vec<IdPath>>>);
 
@@ -141,11 +188,13 @@ where
         let req_tx_clone = req_tx.clone();
         let (wake_tx, wake_rx) = std::sync::mpsc::sync_channel(10);
         std::thread::spawn(move || {
-            while let Ok(id_path) = wake_rx.recv() {
-                let _ = req_tx_clone.blocking_send(AppReq::Wake(id_path));
+            while let Ok(message) = wake_rx.recv() {
+                let _ = req_tx_clone.blocking_send(AppReq::Wake(message));
             }
         });
         let cx = Cx::new(&wake_tx);
+        let log_stale_messages = Arc::new(AtomicBool::new(false));
+        let task_log_stale_messages = log_stale_messages.clone();
 
         // spawn app task
         rt.spawn(async move {
@@ -160,6 +209,8 @@ where
                 idle_handle: None,
                 pending_async: HashSet::new(),
                 ui_state: UiState::Start,
+                log_stale_messages: task_log_stale_messages,
+                wake_chan: wake_tx,
             };
             app_task.run().await;
         });
@@ -174,16 +225,92 @@ where
             root_state: WidgetState::new(),
             size: Default::default(),
             new_size: Default::default(),
+            is_minimized: false,
             cursor_pos: None,
+            shell_cursor: None,
+            window_state: WindowState::new(),
+            timer_source: Box::new(SystemTimerSource),
+            last_anim_frame: None,
+            recorder: None,
+            replay: None,
             cx,
             font_cx: FontContext::new(),
             rt,
             window_id: crate::id::Id::next(),
             accesskit_connected: false,
             node_classes: accesskit::NodeClassSet::new(),
+            log_stale_messages,
         }
     }
 
+    /// Record the timings of [`profiled`](crate::view::profiled) views into `profiler`.
+    ///
+    /// Keep a clone of it to read the stats, for example from a debug panel.
+    pub fn with_profiler(mut self, profiler: Profiler) -> Self {
+        self.cx.set_profiler(profiler);
+        self
+    }
+
+    /// Sets how far wheels which scroll by lines scroll per line, 20 pixels by default.
+    pub fn with_wheel_line_height(mut self, height: f64) -> Self {
+        self.window_state.wheel_line_height = height;
+        self
+    }
+
+    /// Sets how soon and how close to the first press the following presses of a double or
+    /// triple click have to be, 500 milliseconds and 4 pixels by default.
+    pub fn with_multi_click(mut self, interval: Duration, distance: f64) -> Self {
+        self.window_state.clicks.interval = interval;
+        self.window_state.clicks.distance = distance;
+        self
+    }
+
+    /// Measures timers and the intervals of animation frames with `source` instead of the
+    /// system clock.
+    ///
+    /// Tests pass a [`MockTimerSource`](crate::widget::MockTimerSource) to fire timers and
+    /// advance animations deterministically.
+    pub fn with_timer_source(mut self, source: impl TimerSource + 'static) -> Self {
+        self.window_state.timers.set_now(source.now());
+        self.timer_source = Box::new(source);
+        self
+    }
+
+    /// Records the input of the window into `recorder`, keep a clone of it to save the
+    /// recording.
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Plays back a recording as if the user did it again, see [`Replayer`].
+    ///
+    /// Timers and animation frames follow the time of the recording, which replaces the timer
+    /// source. Input of the user is still delivered. When the app diverged from the recording at
+    /// a checkpoint, the replay logs an error and stops, and the timers follow the wall clock
+    /// again.
+    pub fn with_replay(mut self, replayer: Replayer) -> Self {
+        let clock = MockTimerSource::new();
+        self = self.with_timer_source(clock.clone());
+        self.replay = Some(Replay {
+            replayer,
+            start: clock.now(),
+            clock,
+            started: None,
+            checkpoint: None,
+        });
+        self
+    }
+
+    /// Log messages which can't be delivered, because the view they are addressed to is gone.
+    ///
+    /// The messages are logged at debug level with the type name of their payload, their source
+    /// and their id path.
+    pub fn with_stale_message_logging(self, enabled: bool) -> Self {
+        self.log_stale_messages.store(enabled, Ordering::Relaxed);
+        self
+    }
+
     pub fn connect(&mut self, window_handle: WindowHandle) {
         self.window_handle = window_handle.clone();
         if let Some(idle_handle) = window_handle.get_idle_handle() {
@@ -194,7 +321,29 @@ where
     }
 
     pub fn size(&mut self, size: Size) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record_resize(self.timer_source.now(), size);
+        }
         self.new_size = size;
+        let is_minimized = size.area() == 0.0;
+        if is_minimized == self.is_minimized {
+            return;
+        }
+        self.is_minimized = is_minimized;
+        // Nothing is shown in a minimized window, so animations pause until it is restored.
+        if let Some(root_pod) = self.root_pod.as_mut() {
+            let mut cx_state = CxState::new(
+                &self.window_handle,
+                &mut self.font_cx,
+                &mut self.events,
+                &mut self.window_state,
+            );
+            let mut lifecycle_cx = LifeCycleCx::new(&mut cx_state, &mut self.root_state);
+            root_pod.lifecycle(
+                &mut lifecycle_cx,
+                &LifeCycle::AnimationsPaused(is_minimized),
+            );
+        }
     }
 
     pub fn accessibility(&mut self) -> TreeUpdate {
@@ -212,7 +361,12 @@ where
         let window_node = window_node_builder.build(&mut self.node_classes);
         update.nodes.push((self.window_id.into(), window_node));
         update.tree = Some(accesskit::Tree::new(self.window_id.into()));
-        let mut cx_state = CxState::new(&self.window_handle, &mut self.font_cx, &mut self.events);
+        let mut cx_state = CxState::new(
+            &self.window_handle,
+            &mut self.font_cx,
+            &mut self.events,
+            &mut self.window_state,
+        );
         let mut access_cx = AccessCx {
             cx_state: &mut cx_state,
             widget_state: &mut self.root_state,
@@ -220,6 +374,7 @@ where
             node_classes: &mut self.node_classes,
         };
         root_pod.accessibility(&mut access_cx);
+        update.focus = self.window_state.focus.map(Into::into);
         update
     }
 
@@ -228,23 +383,77 @@ where
     /// This is not just painting, but involves processing events, doing layout
     /// if needed, updating the accessibility tree, and then actually painting.
     pub fn paint(&mut self) {
+        self.play_replay();
+        self.fire_timers();
+        self.deliver_widget_messages();
+        let mut anim_frame_sent = false;
         loop {
             self.send_events();
             // TODO: be more lazy re-rendering
             self.render();
             let root_pod = self.root_pod.as_mut().unwrap();
-            let mut cx_state =
-                CxState::new(&self.window_handle, &mut self.font_cx, &mut self.events);
-
+            let scale = self
+                .window_handle
+                .get_scale()
+                .map_or(1.0, |scale| scale.x());
+            let scale_changed = scale != self.window_state.scale;
+            self.window_state.scale = scale;
+            let mut cx_state = CxState::new(
+                &self.window_handle,
+                &mut self.font_cx,
+                &mut self.events,
+                &mut self.window_state,
+            );
+
+            let tree_changed = root_pod.state.flags.contains(PodFlags::TREE_CHANGED);
             let mut lifecycle_cx = LifeCycleCx::new(&mut cx_state, &mut self.root_state);
+            Pod::deliver_removed(&mut lifecycle_cx);
             root_pod.lifecycle(&mut lifecycle_cx, &LifeCycle::TreeUpdate);
+            if tree_changed {
+                root_pod.repair_focus(&mut lifecycle_cx);
+                root_pod.drop_stale_timers(&mut lifecycle_cx);
+                root_pod.drop_stale_pointer_capture(&mut lifecycle_cx);
+                root_pod.drop_stale_cursor_overrides(&mut lifecycle_cx);
+            }
+
+            if !anim_frame_sent {
+                anim_frame_sent = true;
+                if root_pod.anim_frame_requested() {
+                    // All widgets animating in this frame get the same interval.
+                    let now = self.timer_source.now();
+                    let interval = self
+                        .last_anim_frame
+                        .map(|last| now.duration_since(last).as_nanos() as u64)
+                        .unwrap_or(0);
+                    self.last_anim_frame = Some(now);
+                    let mut lifecycle_cx = LifeCycleCx::new(&mut cx_state, &mut self.root_state);
+                    root_pod.lifecycle(&mut lifecycle_cx, &LifeCycle::AnimFrame(interval));
+                } else {
+                    self.last_anim_frame = None;
+                }
+            }
 
-            if root_pod.state.flags.contains(PodFlags::REQUEST_UPDATE) {
+            // Only the passes for the changes marked since the last frame run.
+            if root_pod.pending_changes().contains(ChangeFlags::UPDATE) {
                 let mut update_cx = UpdateCx::new(&mut cx_state, &mut self.root_state);
                 root_pod.update(&mut update_cx);
             }
-            if root_pod.state.flags.contains(PodFlags::REQUEST_LAYOUT) || self.size != self.new_size
+            let mut lifecycle_cx = LifeCycleCx::new(&mut cx_state, &mut self.root_state);
+            root_pod.deliver_focus_change(&mut lifecycle_cx);
+            root_pod.deliver_pointer_capture_loss(&mut lifecycle_cx);
+            if self.size != self.new_size {
+                root_pod.lifecycle(&mut lifecycle_cx, &LifeCycle::WindowResized(self.new_size));
+            }
+            if scale_changed {
+                root_pod.lifecycle(&mut lifecycle_cx, &LifeCycle::ScaleChanged(scale));
+            }
+            if root_pod.pending_changes().contains(ChangeFlags::LAYOUT)
+                || self.size != self.new_size
+                || scale_changed
             {
+                if self.size != self.new_size || scale_changed {
+                    cx_state.window_state().damage.add_full();
+                }
                 self.size = self.new_size;
                 let mut layout_cx = LayoutCx::new(&mut cx_state, &mut self.root_state);
                 let bc = BoxConstraints::tight(self.size);
@@ -283,15 +492,81 @@ where
             // Borrow again to avoid multiple borrows.
             // TODO: maybe make accessibility a method on CxState?
             let root_pod = self.root_pod.as_mut().unwrap();
-            let mut cx_state =
-                CxState::new(&self.window_handle, &mut self.font_cx, &mut self.events);
+            let mut cx_state = CxState::new(
+                &self.window_handle,
+                &mut self.font_cx,
+                &mut self.events,
+                &mut self.window_state,
+            );
             let mut paint_cx = PaintCx::new(&mut cx_state, &mut self.root_state);
             root_pod.paint_impl(&mut paint_cx);
             break;
         }
+        self.check_recording();
+        if std::mem::take(&mut self.window_state.close_window) {
+            self.window_handle.close();
+        }
+    }
+
+    /// Delivers the inputs of the replay which are due, and moves its clock along.
+    fn play_replay(&mut self) {
+        let Some(mut replay) = self.replay.take() else {
+            return;
+        };
+        let elapsed = replay.started.get_or_insert_with(Instant::now).elapsed();
+        // A checkpoint has to be checked before later inputs are delivered.
+        while replay.checkpoint.is_none() {
+            let Some(recorded) = replay.replayer.pop_due(elapsed) else {
+                break;
+            };
+            replay.advance_to(Duration::from_micros(recorded.at_us));
+            self.fire_timers();
+            match recorded.input {
+                Input::Resize { width, height } => self.size(Size::new(width, height)),
+                Input::Checkpoint { hash } => replay.checkpoint = Some((hash, recorded.at_us)),
+                input => {
+                    if let Some(event) = input.to_event() {
+                        self.window_event(event);
+                    }
+                }
+            }
+        }
+        let now = elapsed.mul_f64(replay.replayer.speed());
+        let until = replay.replayer.next_at().map_or(now, |next| next.min(now));
+        replay.advance_to(until);
+        self.replay = Some(replay);
+    }
+
+    /// Records a checkpoint the recorder was asked for, or checks the one of the replay.
+    fn check_recording(&mut self) {
+        if let Some(recorder) = self.recorder.clone() {
+            if recorder.take_checkpoint_request() {
+                let hash = tree_hash(&self.accessibility());
+                recorder.record(self.timer_source.now(), Input::Checkpoint { hash });
+            }
+        }
+        let checkpoint = self
+            .replay
+            .as_mut()
+            .and_then(|replay| replay.checkpoint.take());
+        if let Some((hash, at_us)) = checkpoint {
+            let actual = tree_hash(&self.accessibility());
+            if actual != hash {
+                tracing::error!(
+                    "the replay diverged from the recording at {at_us}µs, stopping it \
+                     (expected tree hash {hash:#x}, got {actual:#x})"
+                );
+                // The rest of the recording would act on a different app.
+                self.replay = None;
+                self.timer_source = Box::new(SystemTimerSource);
+            }
+        }
     }
 
     pub fn window_event(&mut self, event: Event) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record_event(self.timer_source.now(), &event);
+        }
         match &event {
             Event::MouseUp(me)
             | Event::MouseMove(me)
@@ -302,15 +577,155 @@ where
             Event::MouseLeft() => {
                 self.cursor_pos = None;
             }
+            // The primary touch or pen may stand in for the mouse.
+            Event::PointerDown(pointer)
+            | Event::PointerMove(pointer)
+            | Event::PointerUp(pointer)
+                if pointer.is_primary =>
+            {
+                self.cursor_pos = Some(pointer.pos);
+            }
             _ => {}
         }
+        // Timers requested while handling the event start now, not at the last frame.
+        self.window_state.timers.set_now(self.timer_source.now());
+
+        self.ensure_root();
+        let root_pod = self.root_pod.as_mut().unwrap();
+        let mut cx_state = CxState::new(
+            &self.window_handle,
+            &mut self.font_cx,
+            &mut self.events,
+            &mut self.window_state,
+        );
+        let mut event_cx = EventCx::new(&mut cx_state, &mut self.root_state);
+        root_pod.window_event(&mut event_cx, &event);
+        let mut lifecycle_cx = LifeCycleCx::new(&mut cx_state, &mut self.root_state);
+        root_pod.deliver_focus_change(&mut lifecycle_cx);
+        root_pod.deliver_pointer_capture_loss(&mut lifecycle_cx);
+        self.send_events();
+    }
+
+    /// Tells the widgets that the window gained or lost keyboard focus.
+    pub fn window_focus(&mut self, focused: bool) {
+        if !focused {
+            self.window_event(Event::WindowLostFocus);
+        }
+        self.ensure_root();
+        let root_pod = self.root_pod.as_mut().unwrap();
+        let mut cx_state = CxState::new(
+            &self.window_handle,
+            &mut self.font_cx,
+            &mut self.events,
+            &mut self.window_state,
+        );
+        let mut lifecycle_cx = LifeCycleCx::new(&mut cx_state, &mut self.root_state);
+        root_pod.lifecycle(&mut lifecycle_cx, &LifeCycle::WindowFocus(focused));
+        self.send_events();
+    }
 
+    /// Asks the widgets whether the window may close, which it may unless one of them handled
+    /// the [`Event::CloseRequested`].
+    pub fn request_close(&mut self) -> bool {
         self.ensure_root();
         let root_pod = self.root_pod.as_mut().unwrap();
-        let mut cx_state = CxState::new(&self.window_handle, &mut self.font_cx, &mut self.events);
+        let mut cx_state = CxState::new(
+            &self.window_handle,
+            &mut self.font_cx,
+            &mut self.events,
+            &mut self.window_state,
+        );
         let mut event_cx = EventCx::new(&mut cx_state, &mut self.root_state);
-        root_pod.event(&mut event_cx, &event);
+        root_pod.window_event(&mut event_cx, &Event::CloseRequested);
+        let may_close = !event_cx.is_handled();
         self.send_events();
+        may_close
+    }
+
+    /// The mouse cursor the widgets under the mouse asked for, when it changed since the last
+    /// call. The shell only sets the cursor of the window when it did.
+    pub fn take_cursor_change(&mut self) -> Option<Cursor> {
+        let cursor = self.window_state.cursor();
+        if self.shell_cursor.as_ref() == Some(&cursor) {
+            return None;
+        }
+        self.shell_cursor = Some(cursor.clone());
+        Some(cursor)
+    }
+
+    /// Whether a widget requested an animation frame, the shell keeps painting while it does.
+    pub fn is_animating(&self) -> bool {
+        self.root_pod
+            .as_ref()
+            .is_some_and(|root_pod| root_pod.anim_frame_requested())
+    }
+
+    /// When the earliest pending timer elapses, the shell should paint again by then.
+    ///
+    /// During a replay this is also when the next input of the recording is due.
+    pub fn next_timer_deadline(&self) -> Option<Instant> {
+        let timers = self.window_state.timers.next_deadline();
+        let Some(replay) = &self.replay else {
+            return timers;
+        };
+        let elapsed = replay
+            .started
+            .map_or(Duration::ZERO, |started| started.elapsed());
+        let next_input = replay
+            .replayer
+            .next_delay(elapsed)
+            .map(|delay| Instant::now() + delay);
+        let timers = timers.map(|deadline| replay.wall_time(deadline));
+        next_input.into_iter().chain(timers).min()
+    }
+
+    /// Delivers the timers which elapsed since the last frame.
+    fn fire_timers(&mut self) {
+        self.window_state.timers.set_now(self.timer_source.now());
+        if self.window_state.timers.next_deadline().is_none() {
+            return;
+        }
+        self.ensure_root();
+        let root_pod = self.root_pod.as_mut().unwrap();
+        while let Some(token) = self.window_state.timers.pop_due() {
+            let mut cx_state = CxState::new(
+                &self.window_handle,
+                &mut self.font_cx,
+                &mut self.events,
+                &mut self.window_state,
+            );
+            let mut event_cx = EventCx::new(&mut cx_state, &mut self.root_state);
+            root_pod.event(&mut event_cx, &Event::Timer(token));
+            let mut lifecycle_cx = LifeCycleCx::new(&mut cx_state, &mut self.root_state);
+            root_pod.deliver_focus_change(&mut lifecycle_cx);
+            root_pod.deliver_pointer_capture_loss(&mut lifecycle_cx);
+        }
+    }
+
+    /// Delivers the messages sent through [`WidgetHandle`]s since the last frame.
+    fn deliver_widget_messages(&mut self) {
+        let pending = self.cx.registry().take_pending();
+        if pending.is_empty() {
+            return;
+        }
+        self.ensure_root();
+        let root_pod = self.root_pod.as_mut().unwrap();
+        for (target, message) in pending {
+            let mut cx_state = CxState::new(
+                &self.window_handle,
+                &mut self.font_cx,
+                &mut self.events,
+                &mut self.window_state,
+            );
+            let mut event_cx = EventCx::new(&mut cx_state, &mut self.root_state);
+            root_pod.event(
+                &mut event_cx,
+                &Event::RouteWidgetMessage { target, message },
+            );
+            let mut lifecycle_cx = LifeCycleCx::new(&mut cx_state, &mut self.root_state);
+            root_pod.deliver_focus_change(&mut lifecycle_cx);
+            root_pod.deliver_pointer_capture_loss(&mut lifecycle_cx);
+        }
     }
 
     fn send_events(&mut self) {
@@ -343,23 +758,27 @@ where
         if let Some(response) = self.response_chan.blocking_recv() {
             let state = if let Some(root_pod) = self.root_pod.as_mut() {
                 let mut state = response.state.unwrap();
+                let host = root_pod.expect_mut::<OverlayHost>();
+                let content = host.content_mut();
                 let changes = response.view.rebuild(
                     &mut self.cx,
                     response.prev.as_ref().unwrap(),
                     self.id.as_mut().unwrap(),
                     &mut state,
                     //TODO: fail more gracefully but make it explicit that this is a bug
-                    root_pod
+                    content
                         .downcast_mut()
                         .expect("the root widget changed its type, this should never happen!"),
                 );
+                let changes = content.mark(changes);
                 let _ = root_pod.mark(changes);
                 assert!(self.cx.is_empty(), "id path imbalance on rebuild");
                 state
             } else {
                 let (id, state, root_widget) = response.view.build(&mut self.cx);
                 assert!(self.cx.is_empty(), "id path imbalance on build");
-                self.root_pod = Some(Pod::new(root_widget));
+                // Overlays like tooltips and menus are shown above the root widget.
+                self.root_pod = Some(Pod::new(OverlayHost::new(Pod::new(root_widget))));
                 self.id = Some(id);
                 state
             };
@@ -379,6 +798,23 @@ impl<T, V: View<T>> App<T, V> {
     pub fn fragment(&self) -> &SceneFragment {
         &self.root_pod.as_ref().unwrap().fragment
     }
+
+    /// Takes the parts of the window painted anew since the last call, in logical pixels.
+    pub fn take_damage(&mut self) -> Damage {
+        std::mem::take(&mut self.window_state.damage)
+    }
+
+    /// The widgets at `pos` in the window, as of the last layout, see [`Pod::widget_at`].
+    pub fn widget_at(&self, pos: Point) -> Option<HitChain> {
+        self.root_pod.as_ref()?.widget_at(pos)
+    }
+
+    /// The widget of the [tagged](crate::view::ViewExt::tagged) view with the tag or view id.
+    ///
+    /// Messages sent through the handle are delivered with the next frame.
+    pub fn find_widget<'a>(&self, query: impl Into<WidgetQuery<'a>>) -> Option<WidgetHandle> {
+        self.cx.registry().find_widget(query)
+    }
 }
 
 impl<T, V: View<T>, F: FnMut(&mut T) -> V> AppTask<T, V, F> {
@@ -394,27 +830,19 @@ impl<T, V: View<T>, F: FnMut(&mut T) -> V> AppTask<T, V, F> {
                 Ok(Some(req)) => match req {
                     AppReq::SetIdleHandle(handle) => self.idle_handle = Some(handle),
                     AppReq::Events(events) => {
-                        for event in events {
-                            let id_path = &event.id_path[1..];
-                            self.view.as_ref().unwrap().message(
-                                id_path,
-                                self.state.as_mut().unwrap(),
-                                event.body,
-                                &mut self.data,
-                            );
-                        }
+                        let ((), commands) = CommandQueue::collect(|| {
+                            for event in events {
+                                self.handle_event(event);
+                            }
+                        });
+                        commands.run(&self.wake_chan);
                     }
-                    AppReq::Wake(id_path) => {
-                        let needs_rebuild;
-                        {
-                            let result = self.view.as_ref().unwrap().message(
-                                &id_path[1..],
-                                self.state.as_mut().unwrap(),
-                                Box::new(AsyncWake),
-                                &mut self.data,
-                            );
-                            needs_rebuild = matches!(result, MessageResult::RequestRebuild);
-                        }
+                    AppReq::Wake(message) => {
+                        let id_path = message.id_path.clone();
+                        // Actions changed the app state, as for subscriptions.
+                        let (needs_rebuild, commands) =
+                            CommandQueue::collect(|| self.handle_event(message));
+                        commands.run(&self.wake_chan);
 
                         if needs_rebuild {
                             // request re-render from UI thread
@@ -424,8 +852,9 @@ impl<T, V: View<T>, F: FnMut(&mut T) -> V> AppTask<T, V, F> {
                                 }
                                 self.ui_state = UiState::WokeUI;
                             }
-                            let id = id_path.last().unwrap();
-                            self.pending_async.remove(id);
+                            if let Some(id) = id_path.last() {
+                                self.pending_async.remove(id);
+                            }
                             if self.pending_async.is_empty() && self.ui_state == UiState::Delayed {
                                 self.render().await;
                                 deadline = None;
@@ -451,6 +880,39 @@ impl<T, V: View<T>, F: FnMut(&mut T) -> V> AppTask<T, V, F> {
         }
     }
 
+    /// Delivers a message to the view it is addressed to.
+    ///
+    /// Returns true if the message resulted in an action or a rebuild request.
+    fn handle_event(&mut self, event: Message) -> bool {
+        let id_path = event.id_path.clone();
+        // A message without a path isn't addressed to any view.
+        let Some((_, id_path)) = id_path.split_first() else {
+            self.log_stale(&event);
+            return false;
+        };
+        let result = self.view.as_ref().unwrap().message(
+            id_path,
+            self.state.as_mut().unwrap(),
+            event,
+            &mut self.data,
+        );
+        if let MessageResult::Stale(event) = &result {
+            self.log_stale(event);
+        }
+        result.handled()
+    }
+
+    fn log_stale(&self, message: &Message) {
+        if self.log_stale_messages.load(Ordering::Relaxed) {
+            tracing::debug!(
+                "undeliverable {} message from {} to {:?}",
+                message.type_name(),
+                message.source().unwrap_or("unknown source"),
+                message.id_path
+            );
+        }
+    }
+
     async fn render(&mut self) {
         let view = (self.app_logic)(&mut self.data);
         let response = RenderResponse {
@@ -469,3 +931,66 @@ impl<T, V: View<T>, F: FnMut(&mut T) -> V> AppTask<T, V, F> {
         self.ui_state = UiState::Start;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording::Recording;
+    use crate::view::{label, Label};
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    #[test]
+    fn messages_without_a_path_are_stale() {
+        let (_, req_chan) = tokio::sync::mpsc::channel(1);
+        let (response_chan, _) = tokio::sync::mpsc::channel(1);
+        let (_, return_chan) = tokio::sync::mpsc::channel(1);
+        let (wake_chan, _) = std::sync::mpsc::sync_channel(1);
+        let view = label("view");
+        let (id, state, _) = view.build(&mut view_cx());
+        let mut task = AppTask {
+            req_chan,
+            response_chan,
+            return_chan,
+            data: (),
+            app_logic: |_: &mut ()| label("view"),
+            view: Some(view),
+            state: Some(state),
+            idle_handle: None,
+            pending_async: HashSet::new(),
+            ui_state: UiState::Start,
+            log_stale_messages: Arc::new(AtomicBool::new(true)),
+            wake_chan,
+        };
+
+        assert!(!task.handle_event(Message::new(vec![], 5u32)));
+        // A path of just the root view reaches it.
+        assert!(!task.handle_event(Message::new(vec![id], 5u32)));
+    }
+
+    /// An app replaying an empty recording, with its widget tree built.
+    fn replaying_app() -> App<(), Label> {
+        let mut app = App::new((), |_: &mut ()| label("replayed"))
+            .with_replay(Replayer::new(Recording::default()));
+        app.ensure_root();
+        app
+    }
+
+    #[test]
+    fn diverged_replays_stop() {
+        let mut app = replaying_app();
+        let hash = tree_hash(&app.accessibility());
+        let clock = app.replay.as_ref().unwrap().clock.clone();
+
+        app.replay.as_mut().unwrap().checkpoint = Some((hash, 0));
+        app.check_recording();
+        assert!(app.replay.is_some());
+
+        clock.advance(Duration::from_secs(3600));
+        app.replay.as_mut().unwrap().checkpoint = Some((hash ^ 1, 1000));
+        app.check_recording();
+        assert!(app.replay.is_none());
+        // The timers follow the wall clock again, rather than the clock of the replay.
+        assert!(app.timer_source.now() < clock.now());
+    }
+}
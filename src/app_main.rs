@@ -13,10 +13,11 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::time::Instant;
 
 use accesskit::TreeUpdate;
 use glazier::{
-    Application, Cursor, HotKey, IdleToken, Menu, MouseEvent, Region, Scalable, SysMods,
+    Application, HotKey, IdleToken, KeyEvent, Menu, MouseEvent, Region, Scalable, SysMods,
     WinHandler, WindowBuilder, WindowHandle,
 };
 use vello::{
@@ -45,6 +46,9 @@ struct MainState<T, V: View<T>> {
     renderer: Option<Renderer>,
     scene: Scene,
     counter: u64,
+    /// The deadline of the timer requested from the shell, which wakes up the app when the
+    /// earliest widget timer elapses.
+    timer_deadline: Option<Instant>,
 }
 
 const QUIT_MENU_ID: u32 = 0x100;
@@ -98,15 +102,30 @@ impl<T: Send + 'static, V: View<T> + 'static> WinHandler for MainState<T, V> {
 
     fn paint(&mut self, _: &Region) {
         self.app.paint();
+        // The shell may have lost the contents of the window, so everything is presented.
+        let _ = self.app.take_damage();
         self.render();
         self.schedule_render();
+        self.schedule_timer();
+        self.update_cursor();
     }
 
     // TODO: temporary hack
     fn idle(&mut self, _: IdleToken) {
         self.app.paint();
-        self.render();
+        // The surface presents whole frames, the damage only tells whether a frame is needed.
+        if !self.app.take_damage().is_empty() {
+            self.render();
+        }
         self.schedule_render();
+        self.schedule_timer();
+        self.update_cursor();
+    }
+
+    fn timer(&mut self, _: glazier::TimerToken) {
+        // Painting delivers the elapsed widget timers.
+        self.timer_deadline = None;
+        self.handle.invalidate();
     }
 
     fn command(&mut self, id: u32) {
@@ -133,27 +152,52 @@ impl<T: Send + 'static, V: View<T> + 'static> WinHandler for MainState<T, V> {
     fn mouse_down(&mut self, event: &MouseEvent) {
         self.app.window_event(Event::MouseDown(event.into()));
         self.handle.invalidate();
+        self.update_cursor();
     }
 
     fn mouse_up(&mut self, event: &MouseEvent) {
         self.app.window_event(Event::MouseUp(event.into()));
         self.handle.invalidate();
+        self.update_cursor();
     }
 
     fn mouse_move(&mut self, event: &MouseEvent) {
         self.app.window_event(Event::MouseMove(event.into()));
         self.handle.invalidate();
-        self.handle.set_cursor(&Cursor::Arrow);
+        self.update_cursor();
     }
 
     fn wheel(&mut self, event: &MouseEvent) {
         self.app.window_event(Event::MouseWheel(event.into()));
         self.handle.invalidate();
+        self.update_cursor();
     }
 
     fn mouse_leave(&mut self) {
         self.app.window_event(Event::MouseLeft());
         self.handle.invalidate();
+        self.update_cursor();
+    }
+
+    fn key_down(&mut self, event: KeyEvent) -> bool {
+        self.app.window_event(Event::KeyDown(event));
+        self.handle.invalidate();
+        true
+    }
+
+    fn key_up(&mut self, event: KeyEvent) {
+        self.app.window_event(Event::KeyUp(event));
+        self.handle.invalidate();
+    }
+
+    fn got_focus(&mut self) {
+        self.app.window_focus(true);
+        self.handle.invalidate();
+    }
+
+    fn lost_focus(&mut self) {
+        self.app.window_focus(false);
+        self.handle.invalidate();
     }
 
     fn size(&mut self, size: Size) {
@@ -161,7 +205,11 @@ impl<T: Send + 'static, V: View<T> + 'static> WinHandler for MainState<T, V> {
     }
 
     fn request_close(&mut self) {
-        self.handle.close();
+        if self.app.request_close() {
+            self.handle.close();
+        } else {
+            self.handle.invalidate();
+        }
     }
 
     fn destroy(&mut self) {
@@ -187,20 +235,49 @@ where
             renderer: None,
             scene: Scene::default(),
             counter: 0,
+            timer_deadline: None,
+        }
+    }
+
+    fn update_cursor(&mut self) {
+        if let Some(cursor) = self.app.take_cursor_change() {
+            self.handle.set_cursor(&cursor);
+        }
+    }
+
+    /// Requests a shell timer for the earliest widget timer, unless one is already due by then.
+    fn schedule_timer(&mut self) {
+        let Some(deadline) = self.app.next_timer_deadline() else {
+            return;
+        };
+        if self
+            .timer_deadline
+            .is_some_and(|requested| requested <= deadline)
+        {
+            return;
         }
+        self.timer_deadline = Some(deadline);
+        self.handle
+            .request_timer(deadline.saturating_duration_since(Instant::now()));
     }
 
+    /// Paints again right away while widgets animate, events and wake-ups paint otherwise.
     #[cfg(target_os = "macos")]
     fn schedule_render(&self) {
-        self.handle
-            .get_idle_handle()
-            .unwrap()
-            .schedule_idle(IdleToken::new(0));
+        if self.app.is_animating() {
+            self.handle
+                .get_idle_handle()
+                .unwrap()
+                .schedule_idle(IdleToken::new(0));
+        }
     }
 
+    /// Paints again right away while widgets animate, events and wake-ups paint otherwise.
     #[cfg(not(target_os = "macos"))]
     fn schedule_render(&self) {
-        self.handle.invalidate();
+        if self.app.is_animating() {
+            self.handle.invalidate();
+        }
     }
 
     fn render(&mut self) {
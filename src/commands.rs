@@ -0,0 +1,203 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Side effects requested by callbacks, which run after the app handled the messages of a frame.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::mpsc::SyncSender;
+use std::time::Duration;
+
+use crate::{IdPath, Message};
+
+thread_local! {
+    /// The queue of the messages being handled on this thread, if there are any.
+    static QUEUE: RefCell<Option<Vec<Command>>> = RefCell::new(None);
+}
+
+enum Command {
+    Spawn(Pin<Box<dyn Future<Output = ()> + Send>>),
+    SendTo(Message),
+    Schedule(Duration, Message),
+}
+
+/// The command queue of the app, see [`commands`].
+///
+/// Commands run in the order they were queued, after all actions of the messages handled
+/// together, and before the app is rebuilt.
+pub struct Commands {
+    // Only usable on the thread handling the messages.
+    phantom: PhantomData<*const ()>,
+}
+
+/// Returns the command queue of the app, for side effects of callbacks which shouldn't happen
+/// while the app handles messages, like starting a download or opening a dialog.
+///
+/// # Panics
+///
+/// When called outside of message handling, for example in the app logic building the view.
+pub fn commands() -> Commands {
+    let is_handling = QUEUE.with(|queue| queue.borrow().is_some());
+    assert!(
+        is_handling,
+        "commands can only be queued while the app handles messages"
+    );
+    Commands {
+        phantom: PhantomData,
+    }
+}
+
+impl Commands {
+    /// Runs `future` on the runtime of the app.
+    ///
+    /// A panic in the future ends only that future.
+    pub fn spawn(&self, future: impl Future<Output = ()> + Send + 'static) {
+        push(Command::Spawn(Box::pin(future)));
+    }
+
+    /// Sends `payload` to the view at `id_path`, as a message of its own.
+    pub fn send_to(&self, id_path: IdPath, payload: impl Any + Send) {
+        let message = Message::new(id_path, payload).with_source("commands");
+        push(Command::SendTo(message));
+    }
+
+    /// Sends `payload` to the view at `id_path` once `delay` passed.
+    pub fn schedule(&self, delay: Duration, id_path: IdPath, payload: impl Any + Send) {
+        let message = Message::new(id_path, payload).with_source("commands");
+        push(Command::Schedule(delay, message));
+    }
+}
+
+fn push(command: Command) {
+    QUEUE.with(|queue| {
+        queue
+            .borrow_mut()
+            .as_mut()
+            .expect("commands can only be queued while the app handles messages")
+            .push(command);
+    });
+}
+
+/// The commands queued while handling a batch of messages.
+pub(crate) struct CommandQueue(Vec<Command>);
+
+impl CommandQueue {
+    /// Runs `f` with a queue for [`commands`], returning what was queued.
+    ///
+    /// The queue of an outer call is restored afterwards, even when `f` panics.
+    pub(crate) fn collect<R>(f: impl FnOnce() -> R) -> (R, CommandQueue) {
+        struct Restore(Option<Vec<Command>>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let outer = self.0.take();
+                QUEUE.with(|queue| *queue.borrow_mut() = outer);
+            }
+        }
+
+        let outer = QUEUE.with(|queue| queue.borrow_mut().replace(Vec::new()));
+        let restore = Restore(outer);
+        let result = f();
+        let queued = QUEUE.with(|queue| queue.borrow_mut().take().unwrap_or_default());
+        drop(restore);
+        (result, CommandQueue(queued))
+    }
+
+    /// Runs the commands in order, messages are delivered through `wake_chan`.
+    ///
+    /// Must be called within the tokio runtime of the app.
+    pub(crate) fn run(self, wake_chan: &SyncSender<Message>) {
+        for command in self.0 {
+            match command {
+                Command::Spawn(future) => {
+                    tokio::spawn(future);
+                }
+                Command::SendTo(message) => {
+                    let _ = wake_chan.send(message);
+                }
+                Command::Schedule(delay, message) => {
+                    let wake_chan = wake_chan.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        let _ = wake_chan.send(message);
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::sync_channel;
+    use test_log::test;
+
+    fn payloads(messages: &std::sync::mpsc::Receiver<Message>) -> Vec<u32> {
+        messages
+            .try_iter()
+            .map(|message| message.downcast::<u32>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn commands_run_after_the_batch() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (wake_tx, wake_rx) = sync_channel(10);
+        let mut log = vec![];
+
+        let ((), queue) = CommandQueue::collect(|| {
+            for n in 1..=3 {
+                log.push(n);
+                commands().send_to(vec![], n);
+            }
+        });
+        // Nothing ran while the messages were handled.
+        assert_eq!(log, [1, 2, 3]);
+        assert!(payloads(&wake_rx).is_empty());
+
+        let _guard = rt.enter();
+        queue.run(&wake_tx);
+        assert_eq!(payloads(&wake_rx), [1, 2, 3]);
+    }
+
+    #[test]
+    fn panics_leave_the_queue_usable() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (wake_tx, wake_rx) = sync_channel(10);
+
+        let panicked = std::panic::catch_unwind(|| {
+            CommandQueue::collect(|| {
+                commands().send_to(vec![], 1u32);
+                panic!("callback failed");
+            })
+        });
+        assert!(panicked.is_err());
+        assert!(std::panic::catch_unwind(commands).is_err());
+
+        let ((), queue) = CommandQueue::collect(|| {
+            commands().spawn(async { panic!("command failed") });
+            commands().schedule(Duration::from_millis(1), vec![], 2u32);
+            commands().send_to(vec![], 3u32);
+        });
+        rt.block_on(async {
+            queue.run(&wake_tx);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        });
+        assert_eq!(payloads(&wake_rx), [3, 2]);
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Values which flow down the tree without being passed to every view, such as theme colors.
+//!
+//! Views set values for their subtree with [`with_env`](crate::view::with_env) and read them with
+//! [`env_reader`](crate::view::env_reader). Widgets below read them from their contexts, for
+//! example during layout and paint.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// The name of a value in the [`Env`], together with its type.
+///
+/// Keys are usually declared as constants. Names have to be unique, a value stored under the
+/// name of a key with a different type is not found.
+pub struct Key<V> {
+    name: &'static str,
+    phantom: PhantomData<fn() -> V>,
+}
+
+impl<V> Key<V> {
+    pub const fn new(name: &'static str) -> Self {
+        Key {
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<V> Clone for Key<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for Key<V> {}
+
+impl<V> fmt::Debug for Key<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Key").field(&self.name).finish()
+    }
+}
+
+/// A value stored in the [`Env`], which can be compared to other values of unknown type.
+pub(crate) trait EnvValue: Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+
+    fn dyn_eq(&self, other: &dyn EnvValue) -> bool;
+}
+
+impl<V: PartialEq + Send + Sync + 'static> EnvValue for V {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn dyn_eq(&self, other: &dyn EnvValue) -> bool {
+        other.as_any().downcast_ref::<V>() == Some(self)
+    }
+}
+
+/// A value as it was read from the [`Env`], `None` if it was not set.
+pub(crate) type RawValue = Option<Arc<dyn EnvValue>>;
+
+/// Whether two values read from the [`Env`] are the same.
+pub(crate) fn same_value(a: &RawValue, b: &RawValue) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => Arc::ptr_eq(a, b) || a.dyn_eq(b.as_ref()),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// A typed map of values set by the ancestors of a view or widget.
+///
+/// Cloning is cheap, the values are shared until one of the clones is changed.
+#[derive(Clone, Default)]
+pub struct Env {
+    values: Arc<HashMap<&'static str, Arc<dyn EnvValue>>>,
+}
+
+impl Env {
+    pub fn new() -> Self {
+        Env::default()
+    }
+
+    /// The value for `key`, `None` if no ancestor set it.
+    pub fn get<V: 'static>(&self, key: Key<V>) -> Option<&V> {
+        self.values.get(key.name)?.as_any().downcast_ref()
+    }
+
+    /// Sets the value for `key`, replacing the previous one.
+    pub fn set<V: PartialEq + Send + Sync + 'static>(&mut self, key: Key<V>, value: V) {
+        Arc::make_mut(&mut self.values).insert(key.name, Arc::new(value));
+    }
+
+    /// Builder-style method for [`set`](Env::set).
+    pub fn with<V: PartialEq + Send + Sync + 'static>(mut self, key: Key<V>, value: V) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    pub(crate) fn raw(&self, name: &str) -> RawValue {
+        self.values.get(name).cloned()
+    }
+
+    /// Whether both have the same values, apart from the one named `name`.
+    pub(crate) fn same_except(&self, other: &Env, name: &str) -> bool {
+        let others = |env: &Env| env.values.keys().filter(|key| **key != name).count();
+        others(self) == others(other)
+            && self
+                .values
+                .iter()
+                .filter(|(key, _)| **key != name)
+                .all(|(key, value)| same_value(&Some(value.clone()), &other.raw(key)))
+    }
+}
+
+impl PartialEq for Env {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.values, &other.values)
+            || self.values.len() == other.values.len()
+                && self
+                    .values
+                    .iter()
+                    .all(|(name, value)| same_value(&Some(value.clone()), &other.raw(name)))
+    }
+}
+
+impl fmt::Debug for Env {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.values.keys()).finish()
+    }
+}
@@ -1,6 +1,6 @@
 use crate::widget::BoxConstraints;
-use std::ops::Range;
-use vello::kurbo::{Point, Rect, Size, Vec2};
+use std::ops::{Add, Range, RangeInclusive, Sub};
+use vello::kurbo::{Insets, Point, Rect, Size, Vec2};
 
 /// An axis in visual space.
 ///
@@ -93,6 +93,185 @@ impl Axis {
             Axis::Vertical => T::new(minor, major),
         }
     }
+
+    /// Returns `bc` with the bounds on this axis replaced by `min_major` and `max_major`, the
+    /// bounds on the orthogonal axis are kept.
+    ///
+    /// If `min_major` exceeds `max_major` it is lowered to `max_major`.
+    pub fn constraints(
+        self,
+        bc: &BoxConstraints,
+        min_major: f64,
+        max_major: f64,
+    ) -> BoxConstraints {
+        BoxConstraints::new(
+            self.with_major(bc.min(), min_major.min(max_major)),
+            self.with_major(bc.max(), max_major),
+        )
+    }
+
+    /// Returns `x` for the horizontal and `y` for the vertical axis.
+    pub fn select<S>(self, x: S, y: S) -> S {
+        match self {
+            Axis::Horizontal => x,
+            Axis::Vertical => y,
+        }
+    }
+
+    /// Returns the given values as an `(x, y)` tuple.
+    pub fn pack_tuple<S>(self, major: S, minor: S) -> (S, S) {
+        match self {
+            Axis::Horizontal => (major, minor),
+            Axis::Vertical => (minor, major),
+        }
+    }
+
+    /// Returns the sum of the leading and trailing insets on this axis.
+    pub fn major_total(self, insets: Insets) -> f64 {
+        self.major(insets).total()
+    }
+}
+
+/// An [`Axis`] together with the end at which items start.
+///
+/// Layout code can place items as if they always started at the low end of the axis and let
+/// [`Direction::resolve`] mirror the positions for the reversed directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    LeftToRight,
+    RightToLeft,
+    TopToBottom,
+    BottomToTop,
+}
+
+impl Direction {
+    /// Returns the axis along which items are placed.
+    pub fn axis(self) -> Axis {
+        match self {
+            Direction::LeftToRight | Direction::RightToLeft => Axis::Horizontal,
+            Direction::TopToBottom | Direction::BottomToTop => Axis::Vertical,
+        }
+    }
+
+    /// Returns true if items start at the high end of the axis.
+    pub fn is_reversed(self) -> bool {
+        matches!(self, Direction::RightToLeft | Direction::BottomToTop)
+    }
+
+    /// Returns the direction on the same axis starting at the other end.
+    pub fn reversed(self) -> Self {
+        match self {
+            Direction::LeftToRight => Direction::RightToLeft,
+            Direction::RightToLeft => Direction::LeftToRight,
+            Direction::TopToBottom => Direction::BottomToTop,
+            Direction::BottomToTop => Direction::TopToBottom,
+        }
+    }
+
+    /// Mirrors horizontal directions for right-to-left text, vertical ones are unchanged.
+    pub fn with_rtl(self, rtl: bool) -> Self {
+        if rtl && self.axis() == Axis::Horizontal {
+            self.reversed()
+        } else {
+            self
+        }
+    }
+
+    /// Converts a position measured from the start of this direction to one measured from the
+    /// low end of the axis.
+    ///
+    /// `origin_major` is the distance of the item from the start, `extent` its size and
+    /// `container_extent` the size of the container, all on [`axis`](Self::axis).
+    pub fn resolve(self, origin_major: f64, extent: f64, container_extent: f64) -> f64 {
+        if self.is_reversed() {
+            container_extent - origin_major - extent
+        } else {
+            origin_major
+        }
+    }
+}
+
+impl From<(Axis, bool)> for Direction {
+    /// Creates the direction on the given axis, reversed if the flag is set.
+    fn from((axis, reversed): (Axis, bool)) -> Self {
+        match (axis, reversed) {
+            (Axis::Horizontal, false) => Direction::LeftToRight,
+            (Axis::Horizontal, true) => Direction::RightToLeft,
+            (Axis::Vertical, false) => Direction::TopToBottom,
+            (Axis::Vertical, true) => Direction::BottomToTop,
+        }
+    }
+}
+
+/// Rounds `value` to the closest device pixel at the given scale factor.
+pub fn round_to_pixel(value: f64, scale: f64) -> f64 {
+    (value * scale).round() / scale
+}
+
+/// Rounds `value` up to the next device pixel at the given scale factor.
+///
+/// Values less than a rounding error above a pixel boundary are rounded down, so already snapped
+/// values are not moved by another pixel.
+pub fn ceil_to_pixel(value: f64, scale: f64) -> f64 {
+    const EPSILON: f64 = 1e-6;
+    (value * scale - EPSILON).ceil() / scale
+}
+
+/// Rounds a stroke width to whole device pixels, at least one.
+///
+/// Stroked along the inset edge of a pixel aligned rectangle, a stroke of this width covers
+/// whole device pixels: its center lands on a pixel center for odd and on a pixel boundary for
+/// even widths.
+pub fn snap_stroke_width(width: f64, scale: f64) -> f64 {
+    (width * scale).round().max(1.0) / scale
+}
+
+/// Returns whether `value` is on a device pixel boundary at the given scale factor.
+pub fn is_on_pixel(value: f64, scale: f64) -> bool {
+    const EPSILON: f64 = 1e-6;
+    (value * scale - (value * scale).round()).abs() < EPSILON
+}
+
+/// Rounds `extents` to device pixels, so that they sum up to their rounded total.
+///
+/// Rounding each extent on its own makes the last edge drift from the edge of the container,
+/// three thirds of 100 pixels add up to 99. Instead, every extent is rounded down, and the pixels
+/// missing from the total go to the extents which lost the most, the earlier ones first on ties.
+pub fn distribute_to_pixels(extents: &mut [f64], scale: f64) {
+    const EPSILON: f64 = 1e-6;
+    let total = (extents.iter().sum::<f64>() * scale).round();
+    let mut pixels: Vec<f64> = extents
+        .iter()
+        .map(|extent| (extent * scale + EPSILON).floor())
+        .collect();
+    let mut order: Vec<usize> = (0..extents.len()).collect();
+    // The sort is stable, which keeps ties in order.
+    order.sort_by(|a, b| {
+        let remainder = |index: usize| extents[index] * scale - pixels[index];
+        remainder(*b).total_cmp(&remainder(*a))
+    });
+    let missing = (total - pixels.iter().sum::<f64>()).max(0.0) as usize;
+    for index in order.into_iter().take(missing) {
+        pixels[index] += 1.0;
+    }
+    for (extent, pixels) in extents.iter_mut().zip(pixels) {
+        *extent = pixels / scale;
+    }
+}
+
+/// Pixel snapping helpers for [`Size`].
+pub trait SizeExt {
+    /// Rounds both dimensions up to the next device pixel at the given scale factor.
+    fn expand_to_pixel(self, scale: f64) -> Size;
+}
+
+impl SizeExt for Size {
+    fn expand_to_pixel(self, scale: f64) -> Size {
+        Size::new(
+            ceil_to_pixel(self.width, scale),
+            ceil_to_pixel(self.height, scale),
+        )
+    }
 }
 
 /// Types implementing this Trait can be used with [`Axis`] to create axis independent algorithms.
@@ -167,12 +346,91 @@ impl Dim2 for Size {
 /// A Span is a range of values on a given [`Axis`].
 ///
 /// Its main use is to define [`Dim2`] for [`Rect`]. This in turn allows us to use Axis together
-/// with Rect.
+/// with Rect. Like the sides of a `Rect` it includes `low` but not `high`, a span with `high` not
+/// above `low` is empty.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Span {
     pub low: f64,
     pub high: f64,
 }
 
+impl Span {
+    pub fn new(low: f64, high: f64) -> Self {
+        Span { low, high }
+    }
+
+    /// The length of the span, zero if it is empty.
+    pub fn len(self) -> f64 {
+        (self.high - self.low).max(0.0)
+    }
+
+    /// Returns true if the span contains no values.
+    pub fn is_empty(self) -> bool {
+        self.high <= self.low
+    }
+
+    /// Returns true if `value` lies in the span.
+    pub fn contains(self, value: f64) -> bool {
+        self.low <= value && value < self.high
+    }
+
+    /// Returns the values contained in both spans, or `None` if they don't overlap.
+    pub fn intersect(self, other: Span) -> Option<Span> {
+        let span = Span::new(self.low.max(other.low), self.high.min(other.high));
+        (!span.is_empty()).then_some(span)
+    }
+
+    /// Returns the smallest span containing both spans. Empty spans are ignored.
+    pub fn union(self, other: Span) -> Span {
+        if self.is_empty() {
+            other
+        } else if other.is_empty() {
+            self
+        } else {
+            Span::new(self.low.min(other.low), self.high.max(other.high))
+        }
+    }
+
+    /// Moves the span by `offset`.
+    pub fn translate(self, offset: f64) -> Span {
+        Span::new(self.low + offset, self.high + offset)
+    }
+
+    /// Moves both ends of the span into `bounds`.
+    pub fn clamp_to(self, bounds: Span) -> Span {
+        let clamp = |value: f64| value.max(bounds.low).min(bounds.high);
+        Span::new(clamp(self.low), clamp(self.high))
+    }
+}
+
+impl From<Range<f64>> for Span {
+    fn from(range: Range<f64>) -> Self {
+        Span::new(range.start, range.end)
+    }
+}
+
+impl From<Span> for Range<f64> {
+    fn from(span: Span) -> Self {
+        span.low..span.high
+    }
+}
+
+impl Add<f64> for Span {
+    type Output = Span;
+
+    fn add(self, offset: f64) -> Span {
+        self.translate(offset)
+    }
+}
+
+impl Sub<f64> for Span {
+    type Output = Span;
+
+    fn sub(self, offset: f64) -> Span {
+        self.translate(-offset)
+    }
+}
+
 impl Dim2 for Rect {
     type Scalar = Span;
 
@@ -195,19 +453,557 @@ impl Dim2 for Rect {
     }
 }
 
+/// The minimum and maximum extent allowed by [`BoxConstraints`] on a given [`Axis`].
+///
+/// Both bounds are inclusive, like the ones of `BoxConstraints`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisBounds {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl AxisBounds {
+    pub fn new(min: f64, max: f64) -> Self {
+        AxisBounds { min, max }
+    }
+
+    /// Returns true if `extent` satisfies the bounds.
+    pub fn contains(self, extent: f64) -> bool {
+        self.min <= extent && extent <= self.max
+    }
+
+    /// Clamps `extent` into the bounds.
+    pub fn constrain(self, extent: f64) -> f64 {
+        extent.max(self.min).min(self.max)
+    }
+}
+
+impl From<RangeInclusive<f64>> for AxisBounds {
+    fn from(range: RangeInclusive<f64>) -> Self {
+        AxisBounds::new(*range.start(), *range.end())
+    }
+}
+
+impl From<AxisBounds> for RangeInclusive<f64> {
+    fn from(bounds: AxisBounds) -> Self {
+        bounds.min..=bounds.max
+    }
+}
+
 impl Dim2 for BoxConstraints {
-    //TODO: Range has an Exclusive upper Bound, this is not consistent with BoxConstrains.
-    type Scalar = Range<f64>;
+    type Scalar = AxisBounds;
+
+    fn new(x: Self::Scalar, y: Self::Scalar) -> Self {
+        BoxConstraints::new(Size::new(x.min, y.min), Size::new(x.max, y.max))
+    }
+
+    fn x(self) -> Self::Scalar {
+        AxisBounds::new(self.min().width, self.max().width)
+    }
+
+    fn y(self) -> Self::Scalar {
+        AxisBounds::new(self.min().height, self.max().height)
+    }
+}
+
+impl<S: Copy> Dim2 for (S, S) {
+    type Scalar = S;
+
+    fn new(x: Self::Scalar, y: Self::Scalar) -> Self {
+        (x, y)
+    }
+
+    fn x(self) -> Self::Scalar {
+        self.0
+    }
+
+    fn y(self) -> Self::Scalar {
+        self.1
+    }
+}
+
+/// The position of a cell in a grid, the column is on the horizontal and the row on the
+/// vertical [`Axis`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Cell {
+    pub col: usize,
+    pub row: usize,
+}
+
+impl Cell {
+    pub fn new(col: usize, row: usize) -> Self {
+        Cell { col, row }
+    }
+}
+
+impl Dim2 for Cell {
+    type Scalar = usize;
+
+    fn new(x: Self::Scalar, y: Self::Scalar) -> Self {
+        Cell::new(x, y)
+    }
+
+    fn x(self) -> Self::Scalar {
+        self.col
+    }
+
+    fn y(self) -> Self::Scalar {
+        self.row
+    }
+}
+
+/// The leading and trailing insets on a given [`Axis`].
+///
+/// It is the [`Dim2::Scalar`] of [`Insets`]: leading is the left or top inset, trailing the
+/// right or bottom one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisInsets {
+    pub leading: f64,
+    pub trailing: f64,
+}
+
+impl AxisInsets {
+    /// Returns the sum of both insets.
+    pub fn total(self) -> f64 {
+        self.leading + self.trailing
+    }
+}
+
+impl Dim2 for Insets {
+    type Scalar = AxisInsets;
 
     fn new(x: Self::Scalar, y: Self::Scalar) -> Self {
-        BoxConstraints::new(Size::new(x.start, y.start), Size::new(x.end, y.end))
+        Insets::new(x.leading, y.leading, x.trailing, y.trailing)
     }
 
     fn x(self) -> Self::Scalar {
-        self.min().width..self.max().width
+        AxisInsets {
+            leading: self.x0,
+            trailing: self.x1,
+        }
     }
 
     fn y(self) -> Self::Scalar {
-        self.min().height..self.max().height
+        AxisInsets {
+            leading: self.y0,
+            trailing: self.y1,
+        }
+    }
+}
+
+/// Axis aware helpers for [`Rect`].
+pub trait RectExt {
+    /// Moves the edges on the given axis inwards, `leading` from the low and `trailing` from
+    /// the high edge. Negative values move them outwards.
+    fn inset_axis(self, axis: Axis, leading: f64, trailing: f64) -> Rect;
+
+    /// Returns the extent of the rect on the given axis.
+    fn major_span(self, axis: Axis) -> Span;
+
+    /// Returns the extent of the rect on the axis orthogonal to the given one.
+    fn minor_span(self, axis: Axis) -> Span;
+
+    /// Replaces the extent of the rect on the given axis.
+    fn with_major_span(self, axis: Axis, span: Span) -> Rect;
+
+    /// Moves the rect by `offset` along the given axis.
+    fn translate_major(self, axis: Axis, offset: f64) -> Rect;
+
+    /// Returns true if the extent of the rect on the given axis overlaps `span`.
+    ///
+    /// Like [`Span::intersect`], touching spans don't overlap. The other axis is ignored, so a
+    /// rect with zero area still overlaps if its extent on this axis does.
+    fn intersects_major(self, axis: Axis, span: Span) -> bool;
+}
+
+impl RectExt for Rect {
+    fn inset_axis(self, axis: Axis, leading: f64, trailing: f64) -> Rect {
+        axis.map_major(self, |span| {
+            Span::new(span.low + leading, span.high - trailing)
+        })
+    }
+
+    fn major_span(self, axis: Axis) -> Span {
+        axis.major(self)
+    }
+
+    fn minor_span(self, axis: Axis) -> Span {
+        axis.minor(self)
+    }
+
+    fn with_major_span(self, axis: Axis, span: Span) -> Rect {
+        axis.with_major(self, span)
+    }
+
+    fn translate_major(self, axis: Axis, offset: f64) -> Rect {
+        axis.map_major(self, |span| span + offset)
+    }
+
+    fn intersects_major(self, axis: Axis, span: Span) -> bool {
+        self.major_span(axis).intersect(span).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    fn round_trips<T: Dim2 + PartialEq + std::fmt::Debug>(value: T) {
+        assert_eq!(T::new(value.x(), value.y()), value);
+        for axis in [Axis::Horizontal, Axis::Vertical] {
+            assert_eq!(axis.pack::<T>(axis.major(value), axis.minor(value)), value);
+        }
+    }
+
+    #[test]
+    fn dim2_round_trips() {
+        round_trips(Point::new(1.0, 2.0));
+        round_trips(Vec2::new(-3.0, 4.0));
+        round_trips(Size::new(5.0, 6.0));
+        round_trips(Rect::new(1.0, 2.0, 3.0, 4.0));
+        round_trips(Insets::new(1.0, 2.0, 3.0, 4.0));
+        round_trips((1usize, 2usize));
+        round_trips((-1isize, 2isize));
+        round_trips(Cell::new(3, 4));
+        round_trips(BoxConstraints::new(Size::ZERO, Size::new(10.0, 20.0)));
+        round_trips(BoxConstraints::UNBOUNDED);
+        round_trips(BoxConstraints::new(
+            Size::new(f64::INFINITY, 0.0),
+            Size::new(f64::INFINITY, f64::INFINITY),
+        ));
+    }
+
+    #[test]
+    fn box_constraints_bounds_are_inclusive() {
+        let bc = BoxConstraints::new(Size::new(10.0, 0.0), Size::new(100.0, f64::INFINITY));
+        let width = Axis::Horizontal.major(bc);
+        assert_eq!(width, AxisBounds::new(10.0, 100.0));
+        assert!(width.contains(100.0));
+        assert_eq!(width.constrain(500.0), 100.0);
+        assert_eq!(bc.constrain(Size::new(100.0, 0.0)).width, 100.0);
+
+        let height = Axis::Vertical.major(bc);
+        assert!(height.contains(f64::INFINITY));
+        assert_eq!(RangeInclusive::from(height), 0.0..=f64::INFINITY);
+        assert_eq!(AxisBounds::from(0.0..=f64::INFINITY), height);
+        assert_eq!(
+            Axis::Vertical.with_major(bc, AxisBounds::new(5.0, 50.0)),
+            BoxConstraints::new(Size::new(10.0, 5.0), Size::new(100.0, 50.0))
+        );
+    }
+
+    #[test]
+    fn select_and_pack_tuple() {
+        assert_eq!(Axis::Horizontal.select("x", "y"), "x");
+        assert_eq!(Axis::Vertical.select("x", "y"), "y");
+        assert_eq!(Axis::Horizontal.pack_tuple(1, 2), (1, 2));
+        assert_eq!(Axis::Vertical.pack_tuple(1, 2), (2, 1));
+        for axis in [Axis::Horizontal, Axis::Vertical] {
+            let (x, y) = axis.pack_tuple(1, 2);
+            assert_eq!(axis.major((x, y)), 1);
+            assert_eq!(axis.minor((x, y)), 2);
+            assert_eq!(axis.pack_tuple(1, 2), axis.pack::<(i32, i32)>(1, 2));
+            assert_eq!(axis.select(x, y), 1);
+        }
+    }
+
+    #[test]
+    fn cell_axes() {
+        let cell = Cell::new(3, 7);
+        assert_eq!(Axis::Horizontal.major(cell), 3);
+        assert_eq!(Axis::Horizontal.minor(cell), 7);
+        assert_eq!(Axis::Vertical.with_major(cell, 0), Cell::new(3, 0));
+        assert_eq!(
+            Axis::Vertical.map_minor(cell, |col| col + 1),
+            Cell::new(4, 7)
+        );
+    }
+
+    #[test]
+    fn insets_per_axis() {
+        let insets = Insets::new(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(
+            Axis::Horizontal.major(insets),
+            AxisInsets {
+                leading: 1.0,
+                trailing: 3.0
+            }
+        );
+        assert_eq!(Axis::Horizontal.major_total(insets), insets.x_value());
+        assert_eq!(Axis::Vertical.major_total(insets), insets.y_value());
+        assert_eq!(Axis::Vertical.minor(insets).total(), 4.0);
+    }
+
+    #[test]
+    fn rect_inset_axis() {
+        let rect = Rect::new(0.0, 0.0, 100.0, 50.0);
+        assert_eq!(
+            rect.inset_axis(Axis::Horizontal, 10.0, 20.0),
+            Rect::new(10.0, 0.0, 80.0, 50.0)
+        );
+        assert_eq!(
+            rect.inset_axis(Axis::Vertical, -5.0, 5.0),
+            Rect::new(0.0, -5.0, 100.0, 45.0)
+        );
+        let insets = Insets::new(1.0, 2.0, 3.0, 4.0);
+        let both = rect
+            .inset_axis(Axis::Horizontal, insets.x0, insets.x1)
+            .inset_axis(Axis::Vertical, insets.y0, insets.y1);
+        assert_eq!(both, rect - insets);
+    }
+
+    const SAMPLES: [f64; 6] = [-10.0, -1.0, 0.0, 0.5, 3.0, 10.0];
+
+    /// All spans with ends from `SAMPLES`, including empty and inverted ones.
+    fn spans() -> impl Iterator<Item = Span> {
+        SAMPLES
+            .into_iter()
+            .flat_map(|low| SAMPLES.into_iter().map(move |high| Span::new(low, high)))
+    }
+
+    #[test]
+    fn span_basics() {
+        let span = Span::new(1.0, 4.0);
+        assert_eq!(span.len(), 3.0);
+        assert!(span.contains(1.0));
+        assert!(!span.contains(4.0));
+        assert_eq!(span + 2.0, Span::new(3.0, 6.0));
+        assert_eq!(span - 1.0, Span::new(0.0, 3.0));
+        assert_eq!(Span::from(1.0..4.0), span);
+        assert_eq!(Range::from(span), 1.0..4.0);
+        assert_eq!(span.clamp_to(Span::new(2.0, 10.0)), Span::new(2.0, 4.0));
+        assert_eq!(span.clamp_to(Span::new(5.0, 10.0)), Span::new(5.0, 5.0));
+        assert_eq!(span.intersect(Span::new(4.0, 5.0)), None);
+        assert_eq!(span.union(Span::new(6.0, 7.0)), Span::new(1.0, 7.0));
+    }
+
+    #[test]
+    fn inverted_spans_are_empty() {
+        let inverted = Span::new(4.0, 1.0);
+        assert!(inverted.is_empty());
+        assert_eq!(inverted.len(), 0.0);
+        assert!(!SAMPLES.into_iter().any(|value| inverted.contains(value)));
+        assert_eq!(inverted.intersect(Span::new(0.0, 10.0)), None);
+        assert_eq!(inverted.union(Span::new(0.0, 1.0)), Span::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn span_set_identities() {
+        for a in spans() {
+            assert_eq!(a.intersect(a), (!a.is_empty()).then_some(a));
+            assert_eq!(a.union(a), a);
+            assert_eq!(Span::from(Range::from(a)), a);
+            for b in spans() {
+                let intersection = a.intersect(b);
+                let union = a.union(b);
+                assert_eq!(intersection, b.intersect(a), "{a:?} {b:?}");
+                if !a.is_empty() && !b.is_empty() {
+                    assert_eq!(union, b.union(a), "{a:?} {b:?}");
+                }
+                if let Some(intersection) = intersection {
+                    assert!(!intersection.is_empty());
+                    assert!(intersection.len() <= a.len().min(b.len()));
+                }
+                for value in SAMPLES.into_iter().chain([2.0, 5.0]) {
+                    let in_both = a.contains(value) && b.contains(value);
+                    let in_either = a.contains(value) || b.contains(value);
+                    assert_eq!(
+                        intersection.map_or(false, |span| span.contains(value)),
+                        in_both,
+                        "{a:?} {b:?} {value}"
+                    );
+                    if in_either {
+                        assert!(union.contains(value), "{a:?} {b:?} {value}");
+                    }
+                }
+            }
+        }
+    }
+
+    const DIRECTIONS: [Direction; 4] = [
+        Direction::LeftToRight,
+        Direction::RightToLeft,
+        Direction::TopToBottom,
+        Direction::BottomToTop,
+    ];
+
+    #[test]
+    fn direction_resolve() {
+        // An item of extent 20 placed 10 from the start of a container of extent 100.
+        assert_eq!(Direction::LeftToRight.resolve(10.0, 20.0, 100.0), 10.0);
+        assert_eq!(Direction::TopToBottom.resolve(10.0, 20.0, 100.0), 10.0);
+        assert_eq!(Direction::RightToLeft.resolve(10.0, 20.0, 100.0), 70.0);
+        assert_eq!(Direction::BottomToTop.resolve(10.0, 20.0, 100.0), 70.0);
+        for direction in DIRECTIONS {
+            // The first item touches the start edge, the last one the end edge.
+            let first = direction.resolve(0.0, 20.0, 100.0);
+            let last = direction.resolve(80.0, 20.0, 100.0);
+            assert_eq!(first, if direction.is_reversed() { 80.0 } else { 0.0 });
+            assert_eq!(last, if direction.is_reversed() { 0.0 } else { 80.0 });
+            // Mirroring is its own inverse.
+            let resolved = direction.resolve(10.0, 20.0, 100.0);
+            assert_eq!(direction.resolve(resolved, 20.0, 100.0), 10.0);
+        }
+    }
+
+    #[test]
+    fn direction_conversions() {
+        for direction in DIRECTIONS {
+            let axis = direction.axis();
+            assert_eq!(Direction::from((axis, direction.is_reversed())), direction);
+            assert_eq!(direction.reversed().reversed(), direction);
+            assert_eq!(direction.reversed().axis(), axis);
+            assert_ne!(direction.reversed().is_reversed(), direction.is_reversed());
+        }
+    }
+
+    #[test]
+    fn direction_with_rtl() {
+        assert_eq!(
+            Direction::LeftToRight.with_rtl(true),
+            Direction::RightToLeft
+        );
+        // A reversed row in right-to-left text runs left to right again.
+        assert_eq!(
+            Direction::from((Axis::Horizontal, true)).with_rtl(true),
+            Direction::LeftToRight
+        );
+        assert_eq!(
+            Direction::TopToBottom.with_rtl(true),
+            Direction::TopToBottom
+        );
+        for direction in DIRECTIONS {
+            assert_eq!(direction.with_rtl(false), direction);
+        }
+    }
+
+    const SCALES: [f64; 3] = [1.0, 1.25, 2.0];
+
+    #[test]
+    fn pixel_rounding() {
+        assert_eq!(round_to_pixel(10.3, 1.0), 10.0);
+        assert_eq!(round_to_pixel(10.3, 1.25), 10.4);
+        assert_eq!(round_to_pixel(10.3, 2.0), 10.5);
+        assert_eq!(ceil_to_pixel(10.1, 1.0), 11.0);
+        assert_eq!(ceil_to_pixel(10.1, 2.0), 10.5);
+        assert_eq!(
+            Size::new(10.1, 3.0).expand_to_pixel(1.25),
+            Size::new(10.4, 3.2)
+        );
+        for scale in SCALES {
+            for value in [0.0, 0.3, 7.9, 10.4, 101.01] {
+                let rounded = round_to_pixel(value, scale);
+                let ceiled = ceil_to_pixel(value, scale);
+                assert!(is_on_pixel(rounded, scale) && is_on_pixel(ceiled, scale));
+                assert!((rounded - value).abs() * scale <= 0.5 + 1e-9);
+                assert!(ceiled >= value - 1e-9 && (ceiled - value) * scale < 1.0);
+                // Snapped values stay where they are.
+                assert_eq!(ceil_to_pixel(ceiled, scale), ceiled);
+                assert_eq!(round_to_pixel(rounded, scale), rounded);
+            }
+        }
+    }
+
+    #[test]
+    fn distributed_extents_add_up_to_the_total() {
+        let mut thirds = [100.0 / 3.0; 3];
+        distribute_to_pixels(&mut thirds, 1.0);
+        assert_eq!(thirds, [34.0, 33.0, 33.0]);
+
+        // At 1.5, a third of 100 logical pixels is exactly 50 device pixels.
+        let mut thirds = [100.0 / 3.0; 3];
+        distribute_to_pixels(&mut thirds, 1.5);
+        assert_eq!(thirds.map(|extent| extent * 1.5), [50.0, 50.0, 50.0]);
+
+        // The extents losing the most get the missing pixels.
+        let mut extents = [10.2, 10.7, 10.6, 10.5];
+        distribute_to_pixels(&mut extents, 1.0);
+        assert_eq!(extents, [10.0, 11.0, 11.0, 10.0]);
+
+        for scale in SCALES {
+            let mut extents = [12.3, 7.77, 40.05, 0.4];
+            let total: f64 = extents.iter().sum();
+            distribute_to_pixels(&mut extents, scale);
+            assert!(extents.iter().all(|extent| is_on_pixel(*extent, scale)));
+            let sum: f64 = extents.iter().sum();
+            assert!((sum - round_to_pixel(total, scale)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn stroke_widths_cover_whole_pixels() {
+        assert_eq!(snap_stroke_width(1.0, 1.0), 1.0);
+        assert_eq!(snap_stroke_width(1.0, 1.25), 0.8);
+        assert_eq!(snap_stroke_width(1.5, 2.0), 1.5);
+        assert_eq!(snap_stroke_width(0.1, 2.0), 0.5);
+        for scale in SCALES {
+            let width = snap_stroke_width(1.0, scale);
+            let rect = Size::new(20.0, 20.0).to_rect().inset(-0.5 * width);
+            // The outer edge of the stroke is on a pixel boundary.
+            assert!(is_on_pixel(rect.x0 - 0.5 * width, scale));
+            assert!(is_on_pixel(width, scale));
+        }
+    }
+
+    #[test]
+    fn rect_spans() {
+        let rect = Rect::new(1.0, 2.0, 11.0, 22.0);
+        assert_eq!(rect.major_span(Axis::Horizontal), Span::new(1.0, 11.0));
+        assert_eq!(rect.minor_span(Axis::Horizontal), Span::new(2.0, 22.0));
+        assert_eq!(rect.major_span(Axis::Vertical), Span::new(2.0, 22.0));
+        assert_eq!(rect.minor_span(Axis::Vertical), Span::new(1.0, 11.0));
+        assert_eq!(
+            rect.with_major_span(Axis::Horizontal, Span::new(0.0, 5.0)),
+            Rect::new(0.0, 2.0, 5.0, 22.0)
+        );
+        assert_eq!(
+            rect.with_major_span(Axis::Vertical, Span::new(0.0, 5.0)),
+            Rect::new(1.0, 0.0, 11.0, 5.0)
+        );
+        assert_eq!(
+            rect.translate_major(Axis::Horizontal, 3.0),
+            rect + Vec2::new(3.0, 0.0)
+        );
+        assert_eq!(
+            rect.translate_major(Axis::Vertical, -2.0),
+            rect + Vec2::new(0.0, -2.0)
+        );
+        for axis in [Axis::Horizontal, Axis::Vertical] {
+            assert_eq!(rect.with_major_span(axis, rect.major_span(axis)), rect);
+            assert_eq!(rect.minor_span(axis), rect.major_span(axis.cross()));
+        }
+    }
+
+    #[test]
+    fn rect_intersects_major() {
+        let rect = Rect::new(10.0, 10.0, 20.0, 20.0);
+        for axis in [Axis::Horizontal, Axis::Vertical] {
+            assert!(rect.intersects_major(axis, Span::new(0.0, 11.0)));
+            assert!(rect.intersects_major(axis, Span::new(15.0, 16.0)));
+            assert!(!rect.intersects_major(axis, Span::new(0.0, 10.0)));
+            assert!(!rect.intersects_major(axis, Span::new(20.0, 30.0)));
+            assert!(!rect.intersects_major(axis, Span::new(30.0, 0.0)));
+        }
+    }
+
+    #[test]
+    fn zero_area_rects() {
+        // A horizontal line has an extent on the x axis only.
+        let line = Rect::new(0.0, 5.0, 10.0, 5.0);
+        assert!(line.intersects_major(Axis::Horizontal, Span::new(5.0, 6.0)));
+        assert!(!line.intersects_major(Axis::Vertical, Span::new(0.0, 10.0)));
+        assert!(line.major_span(Axis::Vertical).is_empty());
+        assert_eq!(line.translate_major(Axis::Vertical, 1.0).y0, 6.0);
+
+        let point = Rect::from_origin_size(Point::new(3.0, 3.0), Size::ZERO);
+        for axis in [Axis::Horizontal, Axis::Vertical] {
+            assert!(!point.intersects_major(axis, Span::new(0.0, 10.0)));
+            assert_eq!(point.major_span(axis).len(), 0.0);
+            assert_eq!(
+                point.with_major_span(axis, Span::new(0.0, 10.0)).area(),
+                0.0
+            );
+        }
     }
 }
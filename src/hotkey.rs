@@ -0,0 +1,405 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Keyboard shortcuts, matched against key events of any layout.
+
+use glazier::{Code, KbKey, KeyEvent, Modifiers};
+
+/// The modifiers taking part in matching, lock keys like caps lock are ignored.
+const MODS: Modifiers = Modifiers::ALT
+    .union(Modifiers::CONTROL)
+    .union(Modifiers::META)
+    .union(Modifiers::SHIFT);
+
+const IS_MAC: bool = cfg!(target_os = "macos");
+
+/// Modifiers named by their role, which map to different keys depending on the platform.
+///
+/// `Cmd` is the command key on macOS and control everywhere else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SysMods {
+    None,
+    Shift,
+    Cmd,
+    AltCmd,
+    CmdShift,
+    AltCmdShift,
+}
+
+/// Modifiers naming the keys to hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawMods {
+    None,
+    Alt,
+    Ctrl,
+    Meta,
+    Shift,
+    AltCtrl,
+    AltMeta,
+    AltShift,
+    CtrlShift,
+    CtrlMeta,
+    MetaShift,
+    AltCtrlMeta,
+    AltCtrlShift,
+    AltMetaShift,
+    CtrlMetaShift,
+    AltCtrlMetaShift,
+}
+
+impl SysMods {
+    fn to_raw(self, is_mac: bool) -> RawMods {
+        match (self, is_mac) {
+            (SysMods::None, _) => RawMods::None,
+            (SysMods::Shift, _) => RawMods::Shift,
+            (SysMods::Cmd, true) => RawMods::Meta,
+            (SysMods::Cmd, false) => RawMods::Ctrl,
+            (SysMods::AltCmd, true) => RawMods::AltMeta,
+            (SysMods::AltCmd, false) => RawMods::AltCtrl,
+            (SysMods::CmdShift, true) => RawMods::MetaShift,
+            (SysMods::CmdShift, false) => RawMods::CtrlShift,
+            (SysMods::AltCmdShift, true) => RawMods::AltMetaShift,
+            (SysMods::AltCmdShift, false) => RawMods::AltCtrlShift,
+        }
+    }
+}
+
+impl From<SysMods> for RawMods {
+    fn from(mods: SysMods) -> RawMods {
+        mods.to_raw(IS_MAC)
+    }
+}
+
+impl From<SysMods> for Option<RawMods> {
+    fn from(mods: SysMods) -> Option<RawMods> {
+        Some(mods.into())
+    }
+}
+
+impl From<RawMods> for Modifiers {
+    fn from(mods: RawMods) -> Modifiers {
+        let (alt, ctrl, meta, shift) = match mods {
+            RawMods::None => (false, false, false, false),
+            RawMods::Alt => (true, false, false, false),
+            RawMods::Ctrl => (false, true, false, false),
+            RawMods::Meta => (false, false, true, false),
+            RawMods::Shift => (false, false, false, true),
+            RawMods::AltCtrl => (true, true, false, false),
+            RawMods::AltMeta => (true, false, true, false),
+            RawMods::AltShift => (true, false, false, true),
+            RawMods::CtrlShift => (false, true, false, true),
+            RawMods::CtrlMeta => (false, true, true, false),
+            RawMods::MetaShift => (false, false, true, true),
+            RawMods::AltCtrlMeta => (true, true, true, false),
+            RawMods::AltCtrlShift => (true, true, false, true),
+            RawMods::AltMetaShift => (true, false, true, true),
+            RawMods::CtrlMetaShift => (false, true, true, true),
+            RawMods::AltCtrlMetaShift => (true, true, true, true),
+        };
+        let mut result = Modifiers::empty();
+        result.set(Modifiers::ALT, alt);
+        result.set(Modifiers::CONTROL, ctrl);
+        result.set(Modifiers::META, meta);
+        result.set(Modifiers::SHIFT, shift);
+        result
+    }
+}
+
+/// A key to press, given as a character like `"s"` or a named key like [`KbKey::Enter`].
+pub trait IntoKey {
+    fn into_key(self) -> KbKey;
+}
+
+impl IntoKey for KbKey {
+    fn into_key(self) -> KbKey {
+        self
+    }
+}
+
+impl IntoKey for &str {
+    fn into_key(self) -> KbKey {
+        KbKey::Character(self.into())
+    }
+}
+
+/// A keyboard shortcut, a key together with the modifiers to hold.
+///
+/// Characters match the character the layout produces, so `"+"` matches wherever the layout
+/// puts it, with or without Shift. Letters ignore case and need Shift to be named in the
+/// modifiers. Digits match the digit keys of the top row instead, which need Shift on some
+/// layouts, so `Ctrl+Shift+1` and `Ctrl+!` are different shortcuts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotKey {
+    mods: Modifiers,
+    key: KbKey,
+}
+
+impl HotKey {
+    /// Creates a shortcut for `key` with `mods`, which are usually [`SysMods`] to get the
+    /// conventions of the platform.
+    pub fn new(mods: impl Into<Option<RawMods>>, key: impl IntoKey) -> Self {
+        let mods = mods.into().unwrap_or(RawMods::None).into();
+        HotKey {
+            mods,
+            key: key.into_key(),
+        }
+    }
+
+    /// The modifiers of the shortcut.
+    pub fn mods(&self) -> Modifiers {
+        self.mods
+    }
+
+    /// The key of the shortcut.
+    pub fn key(&self) -> &KbKey {
+        &self.key
+    }
+
+    /// Returns true if `event` presses this shortcut.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let mods = event.mods & MODS;
+        let KbKey::Character(c) = &self.key else {
+            return mods == self.mods && event.key == self.key;
+        };
+        if let Some(digit) = single_char(c).and_then(|c| c.to_digit(10)) {
+            let is_digit_key = digit_code(&event.code) == Some(digit);
+            return mods == self.mods && (is_digit_key || event.key == self.key);
+        }
+        let KbKey::Character(pressed) = &event.key else {
+            return false;
+        };
+        if pressed.to_lowercase() != c.to_lowercase() {
+            return false;
+        }
+        // A symbol may need Shift on the layout which produced it.
+        let is_symbol = !pressed.chars().any(char::is_alphabetic);
+        let ignored = if is_symbol && !self.mods.contains(Modifiers::SHIFT) {
+            Modifiers::SHIFT
+        } else {
+            Modifiers::empty()
+        };
+        mods - ignored == self.mods
+    }
+
+    /// A label for menus and tooltips, like `⌘S` on macOS and `Ctrl+S` elsewhere.
+    pub fn display_label(&self) -> String {
+        self.label(IS_MAC)
+    }
+
+    fn label(&self, is_mac: bool) -> String {
+        let key = key_label(&self.key, is_mac);
+        if is_mac {
+            let mut label = String::new();
+            for (modifier, symbol) in [
+                (Modifiers::CONTROL, "⌃"),
+                (Modifiers::ALT, "⌥"),
+                (Modifiers::SHIFT, "⇧"),
+                (Modifiers::META, "⌘"),
+            ] {
+                if self.mods.contains(modifier) {
+                    label.push_str(symbol);
+                }
+            }
+            label + &key
+        } else {
+            let meta = if cfg!(target_os = "windows") {
+                "Win"
+            } else {
+                "Super"
+            };
+            let mut parts = vec![];
+            for (modifier, name) in [
+                (Modifiers::CONTROL, "Ctrl"),
+                (Modifiers::ALT, "Alt"),
+                (Modifiers::SHIFT, "Shift"),
+                (Modifiers::META, meta),
+            ] {
+                if self.mods.contains(modifier) {
+                    parts.push(name.to_string());
+                }
+            }
+            parts.push(key);
+            parts.join("+")
+        }
+    }
+}
+
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    let c = chars.next()?;
+    chars.next().is_none().then_some(c)
+}
+
+/// The digit on a key of the top row.
+fn digit_code(code: &Code) -> Option<u32> {
+    let digit = match code {
+        Code::Digit0 => 0,
+        Code::Digit1 => 1,
+        Code::Digit2 => 2,
+        Code::Digit3 => 3,
+        Code::Digit4 => 4,
+        Code::Digit5 => 5,
+        Code::Digit6 => 6,
+        Code::Digit7 => 7,
+        Code::Digit8 => 8,
+        Code::Digit9 => 9,
+        _ => return None,
+    };
+    Some(digit)
+}
+
+fn key_label(key: &KbKey, is_mac: bool) -> String {
+    let symbol = match key {
+        KbKey::Character(c) => return c.to_uppercase(),
+        KbKey::ArrowUp => "↑",
+        KbKey::ArrowDown => "↓",
+        KbKey::ArrowLeft => "←",
+        KbKey::ArrowRight => "→",
+        KbKey::Enter if is_mac => "↩",
+        KbKey::Backspace if is_mac => "⌫",
+        KbKey::Delete if is_mac => "⌦",
+        KbKey::Escape if is_mac => "⎋",
+        KbKey::Tab if is_mac => "⇥",
+        KbKey::Escape => "Esc",
+        KbKey::Delete => "Del",
+        _ => return key.to_string(),
+    };
+    symbol.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glazier::KeyState;
+    use test_log::test;
+
+    fn press(key: impl IntoKey, code: Code, mods: Modifiers) -> KeyEvent {
+        KeyEvent {
+            key: key.into_key(),
+            code,
+            mods,
+            state: KeyState::Down,
+            ..Default::default()
+        }
+    }
+
+    const CTRL: Modifiers = Modifiers::CONTROL;
+    const CTRL_SHIFT: Modifiers = Modifiers::CONTROL.union(Modifiers::SHIFT);
+
+    #[test]
+    fn cmd_follows_the_platform() {
+        assert_eq!(SysMods::Cmd.to_raw(true), RawMods::Meta);
+        assert_eq!(SysMods::Cmd.to_raw(false), RawMods::Ctrl);
+        assert_eq!(SysMods::AltCmdShift.to_raw(true), RawMods::AltMetaShift);
+        assert_eq!(SysMods::AltCmdShift.to_raw(false), RawMods::AltCtrlShift);
+
+        let save = HotKey::new(SysMods::Cmd, "s");
+        let cmd = if cfg!(target_os = "macos") {
+            Modifiers::META
+        } else {
+            Modifiers::CONTROL
+        };
+        assert!(save.matches(&press("s", Code::KeyS, cmd)));
+        assert!(!save.matches(&press("s", Code::KeyS, Modifiers::ALT)));
+    }
+
+    #[test]
+    fn letters_ignore_case_but_not_shift() {
+        let save = HotKey::new(RawMods::Ctrl, "s");
+        let save_as = HotKey::new(RawMods::CtrlShift, "s");
+        assert!(save.matches(&press("s", Code::KeyS, CTRL)));
+        assert!(!save.matches(&press("S", Code::KeyS, CTRL_SHIFT)));
+        assert!(save_as.matches(&press("S", Code::KeyS, CTRL_SHIFT)));
+        assert!(!save_as.matches(&press("s", Code::KeyS, CTRL)));
+        // Caps lock doesn't get in the way.
+        assert!(save.matches(&press("S", Code::KeyS, CTRL | Modifiers::CAPS_LOCK)));
+    }
+
+    #[test]
+    fn letters_follow_the_layout() {
+        let undo = HotKey::new(RawMods::Ctrl, "z");
+        // On a German layout the key labelled Z sits where QWERTY has Y.
+        assert!(undo.matches(&press("z", Code::KeyY, CTRL)));
+        assert!(!undo.matches(&press("y", Code::KeyZ, CTRL)));
+    }
+
+    #[test]
+    fn symbols_accept_the_shift_of_the_layout() {
+        let zoom_in = HotKey::new(RawMods::Ctrl, "+");
+        // US layout, Shift and `=`.
+        assert!(zoom_in.matches(&press("+", Code::Equal, CTRL_SHIFT)));
+        // German layout, a key of its own.
+        assert!(zoom_in.matches(&press("+", Code::BracketRight, CTRL)));
+        assert!(!zoom_in.matches(&press("=", Code::Equal, CTRL)));
+        assert!(!zoom_in.matches(&press("+", Code::Equal, Modifiers::SHIFT)));
+    }
+
+    #[test]
+    fn digits_match_the_top_row() {
+        let first_tab = HotKey::new(RawMods::Ctrl, "1");
+        let shifted = HotKey::new(RawMods::CtrlShift, "1");
+        let bang = HotKey::new(RawMods::Ctrl, "!");
+
+        // US layout.
+        assert!(first_tab.matches(&press("1", Code::Digit1, CTRL)));
+        assert!(!first_tab.matches(&press("!", Code::Digit1, CTRL_SHIFT)));
+        assert!(shifted.matches(&press("!", Code::Digit1, CTRL_SHIFT)));
+        assert!(bang.matches(&press("!", Code::Digit1, CTRL_SHIFT)));
+        assert!(!bang.matches(&press("1", Code::Digit1, CTRL)));
+
+        // French AZERTY puts `&` on the key of 1, the digit needs Shift.
+        assert!(first_tab.matches(&press("&", Code::Digit1, CTRL)));
+        assert!(shifted.matches(&press("1", Code::Digit1, CTRL_SHIFT)));
+        assert!(!bang.matches(&press("&", Code::Digit1, CTRL)));
+
+        // The numeric keypad produces the digit itself.
+        assert!(first_tab.matches(&press("1", Code::Numpad1, CTRL)));
+        assert!(!first_tab.matches(&press("2", Code::Digit2, CTRL)));
+    }
+
+    #[test]
+    fn named_keys_need_exact_modifiers() {
+        let submit = HotKey::new(RawMods::Ctrl, KbKey::Enter);
+        assert!(submit.matches(&press(KbKey::Enter, Code::Enter, CTRL)));
+        assert!(!submit.matches(&press(KbKey::Enter, Code::Enter, CTRL_SHIFT)));
+        assert!(!submit.matches(&press(KbKey::Enter, Code::Enter, Modifiers::empty())));
+
+        let escape = HotKey::new(None, KbKey::Escape);
+        assert!(escape.matches(&press(KbKey::Escape, Code::Escape, Modifiers::NUM_LOCK)));
+        assert!(!escape.matches(&press(KbKey::Escape, Code::Escape, Modifiers::ALT)));
+    }
+
+    #[test]
+    fn labels_follow_the_platform() {
+        let save_as = HotKey::new(RawMods::MetaShift, "s");
+        assert_eq!(save_as.label(true), "⇧⌘S");
+        let save = HotKey::new(RawMods::Ctrl, "s");
+        assert_eq!(save.label(false), "Ctrl+S");
+        assert_eq!(save.label(true), "⌃S");
+
+        let back = HotKey::new(RawMods::Alt, KbKey::ArrowLeft);
+        assert_eq!(back.label(true), "⌥←");
+        assert_eq!(back.label(false), "Alt+←");
+        let close = HotKey::new(None, KbKey::Escape);
+        assert_eq!(close.label(true), "⎋");
+        assert_eq!(close.label(false), "Esc");
+        assert_eq!(
+            HotKey::new(RawMods::CtrlShift, KbKey::F5).label(false),
+            "Ctrl+Shift+F5"
+        );
+
+        let cmd = HotKey::new(SysMods::Cmd, "q").display_label();
+        assert_eq!(cmd, if IS_MAC { "⌘Q" } else { "Ctrl+Q" });
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sharing decoded images between widgets.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+use vello::peniko::{Blob, Format, Image};
+
+/// Decoded images keyed by a token chosen by the app, for example a path or URL.
+///
+/// The cache only holds weak references: an entry lives as long as some widget holds the image
+/// returned for it and is evicted afterwards, the next request for its token decodes it again.
+#[derive(Default)]
+pub struct ImageCache {
+    entries: Mutex<HashMap<String, Weak<Image>>>,
+}
+
+impl ImageCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The cache used by the image views.
+    pub fn global() -> &'static ImageCache {
+        static GLOBAL: OnceLock<ImageCache> = OnceLock::new();
+        GLOBAL.get_or_init(ImageCache::new)
+    }
+
+    /// Returns the image for `token`, calling `decode` only if no live entry exists.
+    pub fn get_or_insert_with(&self, token: &str, decode: impl FnOnce() -> Image) -> Arc<Image> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, image| image.strong_count() > 0);
+        if let Some(image) = entries.get(token).and_then(Weak::upgrade) {
+            return image;
+        }
+        let image = Arc::new(decode());
+        entries.insert(token.to_string(), Arc::downgrade(&image));
+        image
+    }
+
+    /// The number of images which are still referenced.
+    pub fn len(&self) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, image| image.strong_count() > 0);
+        entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Wraps tightly packed, non-premultiplied RGBA pixels.
+///
+/// # Panics
+///
+/// If `pixels` doesn't hold `width * height` pixels.
+pub fn rgba_image(width: u32, height: u32, pixels: Vec<u8>) -> Image {
+    assert_eq!(
+        pixels.len(),
+        width as usize * height as usize * 4,
+        "pixel data doesn't match the image size"
+    );
+    Image::new(Blob::new(Arc::new(pixels)), Format::Rgba8, width, height)
+}
+
+/// Decodes a PNG or JPEG image.
+#[cfg(feature = "image")]
+pub fn decode_image(bytes: &[u8]) -> Result<Image, image::ImageError> {
+    let decoded = image::load_from_memory(bytes)?.into_rgba8();
+    let (width, height) = decoded.dimensions();
+    Ok(rgba_image(width, height, decoded.into_raw()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    fn pixel() -> Image {
+        rgba_image(1, 1, vec![0xff; 4])
+    }
+
+    #[test]
+    fn entries_are_shared_and_evicted_when_unused() {
+        let cache = ImageCache::new();
+        let mut decoded = 0;
+
+        let first = cache.get_or_insert_with("a", || {
+            decoded += 1;
+            pixel()
+        });
+        let second = cache.get_or_insert_with("a", || {
+            decoded += 1;
+            pixel()
+        });
+        assert_eq!(decoded, 1);
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.len(), 1);
+
+        drop(first);
+        assert_eq!(cache.len(), 1);
+        drop(second);
+        assert!(cache.is_empty());
+
+        let _third = cache.get_or_insert_with("a", || {
+            decoded += 1;
+            pixel()
+        });
+        assert_eq!(decoded, 2);
+    }
+}
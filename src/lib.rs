@@ -1,19 +1,42 @@
 extern crate core;
 
+mod animation;
 mod app;
 mod app_main;
 mod bloom;
+mod commands;
+mod env;
 mod geometry;
+mod hotkey;
 mod id;
+mod image_cache;
+mod profile;
+pub mod recording;
 mod text;
+mod theme;
 pub mod view;
 pub mod widget;
 
 xilem_core::message!(Send);
 
-pub use xilem_core::{IdPath, MessageResult};
+pub use xilem_core::IdPath;
 
+/// The result of delivering a [`Message`] to a view, stale messages are handed back unchanged.
+pub type MessageResult<A> = xilem_core::MessageResult<A, Message>;
+
+pub use animation::{Easing, Lerp};
 pub use app::App;
 pub use app_main::AppLauncher;
 pub(crate) use bloom::Bloom;
-pub use geometry::Axis;
+pub use commands::{commands, Commands};
+pub use env::{Env, Key};
+pub use geometry::{
+    ceil_to_pixel, distribute_to_pixels, is_on_pixel, round_to_pixel, snap_stroke_width, Axis,
+    AxisBounds, AxisInsets, Cell, Direction, RectExt, SizeExt,
+};
+pub use hotkey::{HotKey, IntoKey, RawMods, SysMods};
+#[cfg(feature = "image")]
+pub use image_cache::decode_image;
+pub use image_cache::{rgba_image, ImageCache};
+pub use profile::{ProfileStats, Profiler, Timing};
+pub use theme::{Theme, ThemeOverride};
@@ -0,0 +1,168 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Timing of views, see [`profiled`](crate::view::profiled).
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How often something ran and how long it took.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Timing {
+    pub count: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl Timing {
+    fn add(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+    }
+
+    /// The mean duration, zero if it never ran.
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            Duration::ZERO
+        } else {
+            self.total / self.count as u32
+        }
+    }
+}
+
+/// The timings recorded for one label.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProfileStats {
+    pub builds: Timing,
+    pub rebuilds: Timing,
+    pub messages: Timing,
+}
+
+/// Which method of a view was timed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Phase {
+    Build,
+    Rebuild,
+    Message,
+}
+
+/// A registry of timings per label, filled by [`profiled`](crate::view::profiled) views.
+///
+/// This is a cheap handle, clones share the same registry. The one used while building views
+/// is returned by [`Cx::profiler`](crate::view::Cx::profiler), an app can pass in its own with
+/// [`App::with_profiler`](crate::App::with_profiler) to read it from a debug panel.
+///
+/// Profiling is off by default, and `profiled` views then only check a flag.
+#[derive(Clone, Default)]
+pub struct Profiler {
+    inner: Arc<ProfilerInner>,
+}
+
+#[derive(Default)]
+struct ProfilerInner {
+    enabled: AtomicBool,
+    slow_threshold: Mutex<Option<Duration>>,
+    stats: Mutex<BTreeMap<&'static str, ProfileStats>>,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turn recording on or off, the stats recorded so far are kept.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.inner.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.inner.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Log rebuilds taking longer than `threshold`, with their label and change flags.
+    pub fn set_slow_threshold(&self, threshold: Option<Duration>) {
+        *self.inner.slow_threshold.lock().unwrap() = threshold;
+    }
+
+    pub fn slow_threshold(&self) -> Option<Duration> {
+        *self.inner.slow_threshold.lock().unwrap()
+    }
+
+    /// The stats recorded for `label`, `None` if nothing was recorded since the last reset.
+    pub fn stats(&self, label: &str) -> Option<ProfileStats> {
+        self.inner.stats.lock().unwrap().get(label).copied()
+    }
+
+    /// The stats of all labels, sorted by label.
+    pub fn snapshot(&self) -> Vec<(&'static str, ProfileStats)> {
+        let stats = self.inner.stats.lock().unwrap();
+        stats
+            .iter()
+            .map(|(label, stats)| (*label, *stats))
+            .collect()
+    }
+
+    /// Forget all stats, for example at the start of a frame.
+    pub fn reset(&self) {
+        self.inner.stats.lock().unwrap().clear();
+    }
+
+    /// The stats of all labels, and reset them in the same step.
+    pub fn take(&self) -> Vec<(&'static str, ProfileStats)> {
+        let stats = std::mem::take(&mut *self.inner.stats.lock().unwrap());
+        stats.into_iter().collect()
+    }
+
+    pub(crate) fn record(&self, label: &'static str, phase: Phase, elapsed: Duration) {
+        let mut stats = self.inner.stats.lock().unwrap();
+        let stats = stats.entry(label).or_default();
+        match phase {
+            Phase::Build => stats.builds.add(elapsed),
+            Phase::Rebuild => stats.rebuilds.add(elapsed),
+            Phase::Message => stats.messages.add(elapsed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn record_accumulates_per_label() {
+        let profiler = Profiler::new();
+        let ms = Duration::from_millis;
+        profiler.record("list", Phase::Rebuild, ms(2));
+        profiler.record("list", Phase::Rebuild, ms(5));
+        profiler.record("list", Phase::Build, ms(1));
+        profiler.record("header", Phase::Message, ms(3));
+
+        let list = profiler.stats("list").unwrap();
+        assert_eq!(list.builds.count, 1);
+        assert_eq!(list.rebuilds.count, 2);
+        assert_eq!(list.rebuilds.total, ms(7));
+        assert_eq!(list.rebuilds.max, ms(5));
+        assert_eq!(list.messages, Timing::default());
+        let labels: Vec<_> = profiler.snapshot().into_iter().map(|(l, _)| l).collect();
+        assert_eq!(labels, ["header", "list"]);
+
+        assert_eq!(profiler.take().len(), 2);
+        assert_eq!(profiler.stats("list"), None);
+        assert!(profiler.snapshot().is_empty());
+    }
+}
@@ -0,0 +1,498 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recording the input of a window and replaying it, to reproduce what a user did.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use accesskit::TreeUpdate;
+use glazier::{Code, KbKey, KeyEvent, KeyState, Modifiers, MouseButton, MouseButtons};
+#[cfg(feature = "recording")]
+use serde::{Deserialize, Serialize};
+use vello::kurbo::{Point, Size, Vec2};
+
+use crate::widget::{Event, MouseEvent, ScrollUnit};
+
+/// The input of a window in the order it arrived, see [`Recorder`].
+///
+/// With the `recording` feature, it is saved and loaded as JSON.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "recording", derive(Serialize, Deserialize))]
+pub struct Recording {
+    pub inputs: Vec<RecordedInput>,
+}
+
+/// An input and when it arrived, relative to the first input of the recording.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "recording", derive(Serialize, Deserialize))]
+pub struct RecordedInput {
+    /// Microseconds since the first input.
+    pub at_us: u64,
+    pub input: Input,
+}
+
+/// An input of a window.
+///
+/// Pointer events of touches and pens, drags and accessibility actions are not recorded.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "recording", derive(Serialize, Deserialize))]
+pub enum Input {
+    MouseDown(RecordedMouse),
+    MouseUp(RecordedMouse),
+    MouseMove(RecordedMouse),
+    MouseWheel(RecordedMouse),
+    MouseLeft,
+    KeyDown(RecordedKey),
+    KeyUp(RecordedKey),
+    WindowLostFocus,
+    /// The window changed its size.
+    Resize {
+        width: f64,
+        height: f64,
+    },
+    /// A frame marked with [`Recorder::mark_checkpoint`], with the [`tree_hash`] of the
+    /// accessibility tree the replay has to reproduce.
+    Checkpoint {
+        hash: u64,
+    },
+}
+
+/// A mouse event in window coordinates.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "recording", derive(Serialize, Deserialize))]
+pub struct RecordedMouse {
+    pub x: f64,
+    pub y: f64,
+    /// The pressed buttons as bits, 0 to 4 for left, right, middle, X1 and X2.
+    pub buttons: u8,
+    /// The bit index of the button the event is about, `None` for moves.
+    pub button: Option<u8>,
+    pub mods: u32,
+    pub wheel_x: f64,
+    pub wheel_y: f64,
+    /// Whether the wheel delta is in lines rather than pixels.
+    pub wheel_lines: bool,
+}
+
+/// A key event, with the key and its code as named by the W3C.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "recording", derive(Serialize, Deserialize))]
+pub struct RecordedKey {
+    pub key: String,
+    pub code: String,
+    pub mods: u32,
+    pub repeat: bool,
+}
+
+/// The mouse buttons in the order of their bits in a [`RecordedMouse`].
+const MOUSE_BUTTONS: [MouseButton; 5] = [
+    MouseButton::Left,
+    MouseButton::Right,
+    MouseButton::Middle,
+    MouseButton::X1,
+    MouseButton::X2,
+];
+
+#[cfg(feature = "recording")]
+impl Recording {
+    /// Writes the recording as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("recordings are serializable")
+    }
+
+    /// Reads a recording written by [`to_json`](Recording::to_json).
+    pub fn from_json(json: &str) -> serde_json::Result<Recording> {
+        serde_json::from_str(json)
+    }
+}
+
+impl Input {
+    /// The input of a window event, if it is recorded.
+    pub fn from_event(event: &Event) -> Option<Input> {
+        let input = match event {
+            Event::MouseDown(mouse) => Input::MouseDown(RecordedMouse::new(mouse)),
+            Event::MouseUp(mouse) => Input::MouseUp(RecordedMouse::new(mouse)),
+            Event::MouseMove(mouse) => Input::MouseMove(RecordedMouse::new(mouse)),
+            Event::MouseWheel(mouse) => Input::MouseWheel(RecordedMouse::new(mouse)),
+            Event::MouseLeft() => Input::MouseLeft,
+            Event::KeyDown(key) => Input::KeyDown(RecordedKey::new(key)),
+            Event::KeyUp(key) => Input::KeyUp(RecordedKey::new(key)),
+            Event::WindowLostFocus => Input::WindowLostFocus,
+            _ => return None,
+        };
+        Some(input)
+    }
+
+    /// The window event to deliver for the input, `None` for resizes and checkpoints.
+    pub fn to_event(&self) -> Option<Event> {
+        let event = match self {
+            Input::MouseDown(mouse) => Event::MouseDown(mouse.to_event()),
+            Input::MouseUp(mouse) => Event::MouseUp(mouse.to_event()),
+            Input::MouseMove(mouse) => Event::MouseMove(mouse.to_event()),
+            Input::MouseWheel(mouse) => Event::MouseWheel(mouse.to_event()),
+            Input::MouseLeft => Event::MouseLeft(),
+            Input::KeyDown(key) => Event::KeyDown(key.to_event(KeyState::Down)),
+            Input::KeyUp(key) => Event::KeyUp(key.to_event(KeyState::Up)),
+            Input::WindowLostFocus => Event::WindowLostFocus,
+            Input::Resize { .. } | Input::Checkpoint { .. } => return None,
+        };
+        Some(event)
+    }
+}
+
+impl RecordedMouse {
+    fn new(mouse: &MouseEvent) -> Self {
+        let buttons = MOUSE_BUTTONS
+            .iter()
+            .enumerate()
+            .filter(|(_, button)| mouse.buttons.contains(**button))
+            .fold(0, |bits, (index, _)| bits | (1 << index));
+        let button = MOUSE_BUTTONS
+            .iter()
+            .position(|button| *button == mouse.button)
+            .map(|index| index as u8);
+        RecordedMouse {
+            x: mouse.window_pos.x,
+            y: mouse.window_pos.y,
+            buttons,
+            button,
+            mods: mouse.mods.bits(),
+            wheel_x: mouse.wheel_delta.x,
+            wheel_y: mouse.wheel_delta.y,
+            wheel_lines: mouse.wheel_unit == ScrollUnit::Lines,
+        }
+    }
+
+    fn to_event(&self) -> MouseEvent {
+        let pos = Point::new(self.x, self.y);
+        let mut buttons = MouseButtons::new();
+        for (index, button) in MOUSE_BUTTONS.iter().enumerate() {
+            if self.buttons & (1 << index) != 0 {
+                buttons.insert(*button);
+            }
+        }
+        MouseEvent {
+            pos,
+            window_pos: pos,
+            buttons,
+            mods: Modifiers::from_bits_truncate(self.mods),
+            // Counted again by the root as the input is replayed.
+            click_count: 1,
+            focus: false,
+            button: self
+                .button
+                .and_then(|index| MOUSE_BUTTONS.get(index as usize).copied())
+                .unwrap_or(MouseButton::None),
+            wheel_delta: Vec2::new(self.wheel_x, self.wheel_y),
+            wheel_unit: if self.wheel_lines {
+                ScrollUnit::Lines
+            } else {
+                ScrollUnit::Pixels
+            },
+        }
+    }
+}
+
+impl RecordedKey {
+    fn new(key: &KeyEvent) -> Self {
+        RecordedKey {
+            key: key.key.to_string(),
+            code: key.code.to_string(),
+            mods: key.mods.bits(),
+            repeat: key.repeat,
+        }
+    }
+
+    fn to_event(&self, state: KeyState) -> KeyEvent {
+        KeyEvent {
+            key: KbKey::from_str(&self.key).unwrap_or(KbKey::Unidentified),
+            code: Code::from_str(&self.code).unwrap_or(Code::Unidentified),
+            mods: Modifiers::from_bits_truncate(self.mods),
+            repeat: self.repeat,
+            state,
+            ..Default::default()
+        }
+    }
+}
+
+/// A hash of the roles, names, values and bounds in an accessibility tree update.
+///
+/// Widget ids differ between runs of an app, so they don't take part. Replays compare it at
+/// checkpoints to notice when the app diverged from the recording.
+pub fn tree_hash(update: &TreeUpdate) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for (_, node) in &update.nodes {
+        format!("{:?}", node.role()).hash(&mut hasher);
+        node.name().hash(&mut hasher);
+        node.value().hash(&mut hasher);
+        node.children().len().hash(&mut hasher);
+        if let Some(bounds) = node.bounds() {
+            // Quarter pixels, so that rounding noise doesn't count.
+            for coord in [bounds.x0, bounds.y0, bounds.x1, bounds.y1] {
+                ((coord * 4.0).round() as i64).hash(&mut hasher);
+            }
+        }
+    }
+    hasher.finish()
+}
+
+/// Records the input of a window, see [`App::with_recorder`](crate::App::with_recorder).
+///
+/// Clones share the recording, so a clone kept by the app can save it at any time.
+#[derive(Clone, Default)]
+pub struct Recorder(Arc<Mutex<RecorderState>>);
+
+#[derive(Default)]
+struct RecorderState {
+    start: Option<Instant>,
+    recording: Recording,
+    checkpoint_requested: bool,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Recorder::default()
+    }
+
+    /// The input recorded so far.
+    pub fn recording(&self) -> Recording {
+        self.0.lock().unwrap().recording.clone()
+    }
+
+    /// Records the state of the accessibility tree after the next frame, which a replay has to
+    /// match.
+    pub fn mark_checkpoint(&self) {
+        self.0.lock().unwrap().checkpoint_requested = true;
+    }
+
+    pub(crate) fn record(&self, now: Instant, input: Input) {
+        let mut state = self.0.lock().unwrap();
+        let start = *state.start.get_or_insert(now);
+        let at_us = now.saturating_duration_since(start).as_micros() as u64;
+        state.recording.inputs.push(RecordedInput { at_us, input });
+    }
+
+    pub(crate) fn record_event(&self, now: Instant, event: &Event) {
+        if let Some(input) = Input::from_event(event) {
+            self.record(now, input);
+        }
+    }
+
+    pub(crate) fn record_resize(&self, now: Instant, size: Size) {
+        let input = Input::Resize {
+            width: size.width,
+            height: size.height,
+        };
+        self.record(now, input);
+    }
+
+    /// Whether a checkpoint was marked since the last call.
+    pub(crate) fn take_checkpoint_request(&self) -> bool {
+        std::mem::take(&mut self.0.lock().unwrap().checkpoint_requested)
+    }
+}
+
+/// Plays back a [`Recording`], see [`App::with_replay`](crate::App::with_replay).
+///
+/// The inputs arrive at the times they were recorded, divided by the speed. Timers and
+/// animation frames follow the time of the recording rather than the wall clock, so a replay
+/// runs the same way every time.
+pub struct Replayer {
+    recording: Recording,
+    next: usize,
+    speed: f64,
+}
+
+impl Replayer {
+    pub fn new(recording: Recording) -> Self {
+        Replayer {
+            recording,
+            next: 0,
+            speed: 1.0,
+        }
+    }
+
+    /// Plays the recording `speed` times faster than it was recorded.
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        assert!(speed > 0.0, "the speed of a replay must be positive");
+        self.speed = speed;
+        self
+    }
+
+    /// How many times faster than recorded the inputs are played.
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Whether all inputs were played.
+    pub fn is_finished(&self) -> bool {
+        self.next == self.recording.inputs.len()
+    }
+
+    /// The time of the next input in the recording.
+    pub fn next_at(&self) -> Option<Duration> {
+        let input = self.recording.inputs.get(self.next)?;
+        Some(Duration::from_micros(input.at_us))
+    }
+
+    /// How much wall-clock time passes until the next input is due, `elapsed` after the replay
+    /// started.
+    pub fn next_delay(&self, elapsed: Duration) -> Option<Duration> {
+        let at = self.next_at()?.div_f64(self.speed);
+        Some(at.saturating_sub(elapsed))
+    }
+
+    /// The next input, if it is due `elapsed` after the replay started.
+    pub fn pop_due(&mut self, elapsed: Duration) -> Option<RecordedInput> {
+        if self.next_delay(elapsed)? > Duration::ZERO {
+            return None;
+        }
+        self.pop()
+    }
+
+    /// The next input whenever it is due, for replays which don't wait, like those of tests.
+    pub fn pop(&mut self) -> Option<RecordedInput> {
+        let input = self.recording.inputs.get(self.next)?.clone();
+        self.next += 1;
+        Some(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{mouse, Harness, Leaf};
+    use crate::widget::{BoxConstraints, Button, Pod};
+    use test_log::test;
+    use xilem_core::Id;
+
+    const WINDOW: Size = Size::new(200.0, 100.0);
+
+    /// A counter, a button whose clicks are counted from its messages.
+    fn counter(harness: &mut Harness) -> Pod {
+        let button = Button::new(&vec![Id::next()], Pod::new(Leaf::new(80.0, 30.0)));
+        let mut pod = Pod::new(button);
+        harness.layout(&mut pod, &BoxConstraints::tight(WINDOW));
+        pod
+    }
+
+    fn count(harness: &mut Harness) -> usize {
+        harness.take_messages().len()
+    }
+
+    #[test]
+    fn replaying_a_click_sequence_reaches_the_same_state() {
+        let recorder = Recorder::new();
+        let mut harness = Harness::new();
+        harness.recorder = Some(recorder.clone());
+        let mut pod = counter(&mut harness);
+
+        for _ in 0..3 {
+            harness.advance_time(&mut pod, Duration::from_millis(120));
+            harness.event(&mut pod, Event::MouseMove(mouse((40.0, 15.0))));
+            harness.event(&mut pod, Event::MouseDown(mouse((40.0, 15.0))));
+            harness.advance_time(&mut pod, Duration::from_millis(80));
+            harness.event(&mut pod, Event::MouseUp(mouse((40.0, 15.0))));
+        }
+        harness.event(&mut pod, Event::MouseLeft());
+        recorder.mark_checkpoint();
+        harness.checkpoint(&mut pod);
+        let clicks = count(&mut harness);
+        assert_eq!(clicks, 3);
+
+        let recording = recorder.recording();
+        #[cfg(feature = "recording")]
+        assert_eq!(
+            Recording::from_json(&recording.to_json()).unwrap(),
+            recording
+        );
+        let last = recording.inputs.last().unwrap();
+        assert!(matches!(last.input, Input::Checkpoint { .. }));
+        assert_eq!(last.at_us, 480_000);
+
+        let mut replay = Harness::new();
+        let mut pod = counter(&mut replay);
+        replay.replay(&mut pod, Replayer::new(recording));
+        assert_eq!(count(&mut replay), clicks);
+    }
+
+    #[test]
+    #[should_panic(expected = "diverged")]
+    fn replays_notice_a_different_tree() {
+        let mut recording = Recording::default();
+        recording.inputs.push(RecordedInput {
+            at_us: 0,
+            input: Input::Checkpoint { hash: 0 },
+        });
+        let mut harness = Harness::new();
+        let mut pod = counter(&mut harness);
+        harness.replay(&mut pod, Replayer::new(recording));
+    }
+
+    #[test]
+    fn speed_divides_the_delays() {
+        let input = |at_us| RecordedInput {
+            at_us,
+            input: Input::MouseLeft,
+        };
+        let recording = Recording {
+            inputs: vec![input(0), input(1_000_000)],
+        };
+        let mut replayer = Replayer::new(recording).with_speed(4.0);
+        assert!(replayer.pop_due(Duration::ZERO).is_some());
+        assert_eq!(
+            replayer.next_delay(Duration::from_millis(100)),
+            Some(Duration::from_millis(150))
+        );
+        assert!(replayer.pop_due(Duration::from_millis(200)).is_none());
+        assert!(replayer.pop_due(Duration::from_millis(250)).is_some());
+        assert!(replayer.is_finished());
+    }
+
+    #[test]
+    fn keys_and_buttons_round_trip() {
+        let key = KeyEvent {
+            key: KbKey::ArrowLeft,
+            code: Code::ArrowLeft,
+            mods: Modifiers::SHIFT | Modifiers::CONTROL,
+            state: KeyState::Down,
+            ..Default::default()
+        };
+        let event = Input::from_event(&Event::KeyDown(key.clone()))
+            .unwrap()
+            .to_event();
+        let Some(Event::KeyDown(replayed)) = event else {
+            panic!("expected a key press");
+        };
+        assert_eq!(
+            (replayed.key, replayed.code, replayed.mods),
+            (key.key, key.code, key.mods)
+        );
+
+        let mut pressed = mouse((3.0, 4.0));
+        pressed.button = MouseButton::Right;
+        pressed.buttons = MouseButtons::new().with(MouseButton::Right);
+        let input = Input::from_event(&Event::MouseDown(pressed)).unwrap();
+        let Some(Event::MouseDown(replayed)) = input.to_event() else {
+            panic!("expected a mouse press");
+        };
+        assert_eq!(replayed.button, MouseButton::Right);
+        assert!(replayed.buttons.contains(MouseButton::Right));
+        assert!(!replayed.buttons.contains(MouseButton::Left));
+    }
+}
@@ -18,6 +18,26 @@ impl Default for ParleyBrush {
 impl parley::style::Brush for ParleyBrush {}
 
 pub fn render_text(builder: &mut SceneBuilder, transform: Affine, layout: &Layout<ParleyBrush>) {
+    render_glyphs(builder, transform, layout, None);
+}
+
+/// Renders the whole `layout` with `brush` instead of the brushes it was built with, so that
+/// the color of the text can change without laying it out again.
+pub fn render_text_with_brush(
+    builder: &mut SceneBuilder,
+    transform: Affine,
+    layout: &Layout<ParleyBrush>,
+    brush: &Brush,
+) {
+    render_glyphs(builder, transform, layout, Some(brush));
+}
+
+fn render_glyphs(
+    builder: &mut SceneBuilder,
+    transform: Affine,
+    layout: &Layout<ParleyBrush>,
+    brush: Option<&Brush>,
+) {
     let mut gcx = GlyphContext::new();
     for line in layout.lines() {
         for glyph_run in line.glyph_runs() {
@@ -32,7 +52,8 @@ pub fn render_text(builder: &mut SceneBuilder, transform: Affine, layout: &Layou
                 let vars: [(&str, f32); 0] = [];
                 let mut gp = gcx.new_provider(&font_ref, None, font_size, false, vars);
                 for glyph in glyph_run.glyphs() {
-                    if let Some(fragment) = gp.get(glyph.id, Some(&style.brush.0)) {
+                    if let Some(fragment) = gp.get(glyph.id, Some(brush.unwrap_or(&style.brush.0)))
+                    {
                         let gx = x + glyph.x;
                         let gy = y - glyph.y;
                         let xform = Affine::translate((gx as f64, gy as f64))
@@ -0,0 +1,116 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Colors and metrics of the built-in widgets, see [`themed`](crate::view::themed).
+
+use vello::peniko::Color;
+
+use crate::{Env, Key};
+
+/// The colors and metrics the built-in widgets are drawn with.
+///
+/// Widgets read it from the [`Env`] with [`Theme::get`], views set it for a subtree with
+/// [`with_theme`](crate::view::with_theme) or change parts of it with
+/// [`themed`](crate::view::themed). Without either, widgets use [`Theme::dark`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    /// The color of focus rings and of filled or selected parts.
+    pub accent: Color,
+    pub text_color: Color,
+    /// The fill of controls such as buttons and checkboxes.
+    pub background: Color,
+    /// The fill of pressed or disabled controls.
+    pub background_active: Color,
+    pub border: Color,
+    /// The border of hovered controls.
+    pub highlight: Color,
+    pub corner_radius: f64,
+    /// The gap between a control and its label.
+    pub spacing: f64,
+}
+
+/// Parts of a [`Theme`] to replace, the others are kept, see [`themed`](crate::view::themed).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ThemeOverride {
+    pub accent: Option<Color>,
+    pub text_color: Option<Color>,
+    pub corner_radius: Option<f64>,
+    pub spacing: Option<f64>,
+}
+
+/// The key the theme is stored under in the [`Env`].
+pub(crate) const THEME: Key<Theme> = Key::new("xilem.theme");
+
+const DARK: Theme = Theme {
+    accent: Color::rgb8(0x5a, 0x9b, 0xe6),
+    text_color: Color::rgb8(0xf0, 0xf0, 0xea),
+    background: Color::rgb8(0x3a, 0x3a, 0x3a),
+    background_active: Color::rgb8(0x5a, 0x5a, 0x5a),
+    border: Color::rgb8(0x6a, 0x6a, 0x6a),
+    highlight: Color::rgb8(0xa1, 0xa1, 0xa1),
+    corner_radius: 4.0,
+    spacing: 6.0,
+};
+
+const LIGHT: Theme = Theme {
+    accent: Color::rgb8(0x2f, 0x6f, 0xca),
+    text_color: Color::rgb8(0x1e, 0x1e, 0x1e),
+    background: Color::rgb8(0xe8, 0xe8, 0xe8),
+    background_active: Color::rgb8(0xc8, 0xc8, 0xc8),
+    border: Color::rgb8(0x9a, 0x9a, 0x9a),
+    highlight: Color::rgb8(0x5a, 0x5a, 0x5a),
+    corner_radius: 4.0,
+    spacing: 6.0,
+};
+
+impl Theme {
+    /// Light text on dark controls, the default.
+    pub fn dark() -> Self {
+        DARK
+    }
+
+    /// Dark text on light controls.
+    pub fn light() -> Self {
+        LIGHT
+    }
+
+    /// The theme set by the views above, [`Theme::dark`] if none was set.
+    pub fn get(env: &Env) -> &Theme {
+        env.get(THEME).unwrap_or(&DARK)
+    }
+
+    /// Whether widgets have to be laid out again when switching from `self` to `other`.
+    pub(crate) fn same_metrics(&self, other: &Theme) -> bool {
+        self.spacing == other.spacing
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl ThemeOverride {
+    /// `theme` with the parts set here replaced.
+    pub fn apply(&self, theme: &Theme) -> Theme {
+        Theme {
+            accent: self.accent.unwrap_or(theme.accent),
+            text_color: self.text_color.unwrap_or(theme.text_color),
+            corner_radius: self.corner_radius.unwrap_or(theme.corner_radius),
+            spacing: self.spacing.unwrap_or(theme.spacing),
+            ..theme.clone()
+        }
+    }
+}
@@ -0,0 +1,248 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::widget::ChangeFlags;
+use crate::{Message, MessageResult};
+
+use super::{Adapt, AdaptState, AdaptThunk, Cx, Id, View, ViewMarker};
+
+/// Scopes `child` to a different app state and action type.
+///
+/// `f` is called with the app state whenever a message reaches `child` and decides how to
+/// dispatch it: calling the [`AdaptThunk`] with the child's state delivers the message, and
+/// the child's result can be mapped to an action of this view. Building and rebuilding go
+/// straight to `child`, see [`Adapt`] for an example.
+pub fn adapt<ParentT, ParentA, ChildT, ChildA, V, F>(
+    f: F,
+    child: V,
+) -> Adapt<ParentT, ParentA, ChildT, ChildA, V, F>
+where
+    V: View<ChildT, ChildA>,
+    F: Fn(&mut ParentT, AdaptThunk<ChildT, ChildA, V>) -> MessageResult<ParentA> + Send,
+{
+    Adapt::new(f, child)
+}
+
+/// Scopes `child` to the part of the app state `get` and `get_mut` return.
+///
+/// The callbacks of `child` and its descendants receive only that part, while actions pass
+/// through unchanged, and [`Lens::get`] reads the part. For more control over messages and
+/// actions use [`adapt`].
+pub fn lens<ParentT, ChildT, V, G, F>(
+    get: G,
+    get_mut: F,
+    child: V,
+) -> Lens<ParentT, ChildT, V, G, F>
+where
+    G: Fn(&ParentT) -> &ChildT + Send,
+    F: Fn(&mut ParentT) -> &mut ChildT + Send,
+{
+    Lens {
+        get,
+        child: AdaptState::new(get_mut, child),
+    }
+}
+
+/// A view which scopes its child to a part of the app state, see [`lens`].
+pub struct Lens<ParentT, ChildT, V, G, F> {
+    get: G,
+    child: AdaptState<ParentT, ChildT, V, F>,
+}
+
+impl<ParentT, ChildT, V, G, F> Lens<ParentT, ChildT, V, G, F>
+where
+    G: Fn(&ParentT) -> &ChildT,
+{
+    /// Returns the part of `app_state` the child is scoped to.
+    pub fn get<'a>(&self, app_state: &'a ParentT) -> &'a ChildT {
+        (self.get)(app_state)
+    }
+}
+
+impl<ParentT, ChildT, V, G, F> ViewMarker for Lens<ParentT, ChildT, V, G, F> {}
+
+impl<ParentT, ChildT, A, V, G, F> View<ParentT, A> for Lens<ParentT, ChildT, V, G, F>
+where
+    V: View<ChildT, A>,
+    G: Fn(&ParentT) -> &ChildT + Send,
+    F: Fn(&mut ParentT) -> &mut ChildT + Send,
+{
+    type State = V::State;
+
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        self.child.build(cx)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        self.child.rebuild(cx, &prev.child, id, state, element)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut ParentT,
+    ) -> MessageResult<A> {
+        self.child.message(id_path, state, message, app_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::{memoize, textbox, v_stack};
+    use crate::widget::test_util::view_cx;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use test_log::test;
+
+    #[derive(Default)]
+    struct Form {
+        title: String,
+        author: Author,
+    }
+
+    #[derive(Default)]
+    struct Author {
+        name: String,
+        edits: usize,
+    }
+
+    enum AuthorAction {
+        Renamed,
+    }
+
+    /// An author name field two levels below the form.
+    fn author_name() -> impl View<Form, bool> {
+        lens(
+            |form: &Form| &form.author,
+            |form: &mut Form| &mut form.author,
+            adapt(
+                |author: &mut Author, thunk| {
+                    thunk.call(&mut author.name).map(|AuthorAction::Renamed| {
+                        author.edits += 1;
+                        true
+                    })
+                },
+                textbox("", |name: &mut String, new| {
+                    *name = new;
+                    AuthorAction::Renamed
+                }),
+            ),
+        )
+    }
+
+    #[test]
+    fn nested_adapts_reach_the_field() {
+        let mut cx = view_cx();
+        let view = author_name();
+        let (_, mut state, _) = view.build(&mut cx);
+        let mut form = Form::default();
+
+        let message = Message::new(vec![], String::from("Ursula"));
+        let result = view.message(&[], &mut state, message, &mut form);
+        assert!(matches!(result, MessageResult::Action(true)));
+        assert_eq!(form.author.name, "Ursula");
+        assert_eq!(form.author.edits, 1);
+        assert!(form.title.is_empty());
+    }
+
+    #[test]
+    fn stale_messages_pass_through() {
+        let mut cx = view_cx();
+        let view = author_name();
+        let (_, mut state, _) = view.build(&mut cx);
+        let mut form = Form::default();
+
+        // A message for a child which doesn't exist (any more).
+        let message = Message::new(vec![], String::from("Ursula"));
+        let result = view.message(&[Id::next()], &mut state, message, &mut form);
+        let MessageResult::Stale(message) = result else {
+            panic!("expected a stale message");
+        };
+        assert_eq!(message.downcast::<String>().unwrap(), "Ursula");
+        assert!(form.author.name.is_empty());
+        assert_eq!(form.author.edits, 0);
+    }
+
+    #[test]
+    fn lens_scopes_a_sequence() {
+        let mut cx = view_cx();
+        let view = lens(
+            |form: &Form| &form.author,
+            |form: &mut Form| &mut form.author,
+            v_stack((
+                textbox("", |author: &mut Author, name| author.name = name),
+                textbox("", |author: &mut Author, _| author.edits += 1),
+            )),
+        );
+        let (_, mut state, _) = View::<Form>::build(&view, &mut cx);
+        let mut form = Form::default();
+
+        let (name, edits) = (state.0 .1, state.1 .1);
+        let message = Message::new(vec![], String::from("Ursula"));
+        let _ = View::<Form>::message(&view, &[name], &mut state, message, &mut form);
+        let message = Message::new(vec![], String::from("3"));
+        let _ = View::<Form>::message(&view, &[edits], &mut state, message, &mut form);
+        assert_eq!(view.get(&form).name, "Ursula");
+        assert_eq!(view.get(&form).edits, 1);
+        assert!(form.title.is_empty());
+    }
+
+    #[test]
+    fn lens_inside_memoize() {
+        let mut cx = view_cx();
+        let generated = Arc::new(AtomicUsize::new(0));
+        let view = |name: String| {
+            let generated = generated.clone();
+            memoize(name, move |name| {
+                generated.fetch_add(1, Ordering::Relaxed);
+                lens(
+                    |form: &Form| &form.author,
+                    |form: &mut Form| &mut form.author,
+                    textbox(name.clone(), |author: &mut Author, name| author.name = name),
+                )
+            })
+        };
+        let mut form = Form::default();
+        let mut prev = view(form.author.name.clone());
+        let (mut id, mut state, mut element) = View::<Form>::build(&prev, &mut cx);
+
+        let next = view(form.author.name.clone());
+        let changes =
+            View::<Form>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+        assert_eq!(changes, ChangeFlags::empty());
+        assert_eq!(generated.load(Ordering::Relaxed), 1);
+        prev = next;
+
+        let message = Message::new(vec![], String::from("Ursula"));
+        let _ = View::<Form>::message(&prev, &[], &mut state, message, &mut form);
+        assert_eq!(form.author.name, "Ursula");
+
+        // The new name is new data, which generates the lens again.
+        let next = view(form.author.name.clone());
+        let _ = View::<Form>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+        assert_eq!(generated.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Pod};
+use crate::{Easing, Lerp, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A view animating a value, see [`animate`].
+pub struct Animate<W, V, F> {
+    value: W,
+    duration: Duration,
+    easing: Easing,
+    f: F,
+    phantom: PhantomData<fn() -> V>,
+}
+
+pub struct AnimateState<T, A, W, V: View<T, A>> {
+    /// Where the current animation started.
+    from: W,
+    target: W,
+    /// The value the child was last generated from.
+    current: W,
+    elapsed: Duration,
+    child_id: Id,
+    view: V,
+    view_state: V::State,
+}
+
+/// Animates the changes of `value` between rebuilds.
+///
+/// `f` creates the child view from the displayed value. When `value` changes, the displayed
+/// value moves from where it is to the new value over `duration`, following `easing`, and the
+/// child is recreated from it every animation frame. A change during an animation continues
+/// from the displayed value instead of jumping.
+pub fn animate<W, V, F>(value: W, duration: Duration, easing: Easing, f: F) -> Animate<W, V, F>
+where
+    W: Lerp,
+    F: Fn(W) -> V + Send,
+{
+    Animate {
+        value,
+        duration,
+        easing,
+        f,
+        phantom: PhantomData,
+    }
+}
+
+impl<W, V, F> ViewMarker for Animate<W, V, F> {}
+
+impl<T, A, W, V, F> View<T, A> for Animate<W, V, F>
+where
+    W: Lerp + Send,
+    V: View<T, A>,
+    F: Fn(W) -> V + Send,
+{
+    type State = AnimateState<T, A, W, V>;
+
+    type Element = widget::Animator;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let view = (self.f)(self.value.clone());
+        let (id, (child_id, view_state, element)) = cx.with_new_id(|cx| {
+            let (child_id, view_state, child) = view.build(cx);
+            let element = widget::Animator::new(cx.id_path(), Pod::new(child));
+            (child_id, view_state, element)
+        });
+        let state = AnimateState {
+            from: self.value.clone(),
+            target: self.value.clone(),
+            current: self.value.clone(),
+            elapsed: Duration::ZERO,
+            child_id,
+            view,
+            view_state,
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        _prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changes = ChangeFlags::empty();
+        if self.value != state.target {
+            state.from = state.current.clone();
+            state.target = self.value.clone();
+            state.elapsed = Duration::ZERO;
+        }
+        changes |= element.set_running(state.current != state.target);
+        let view = (self.f)(state.current.clone());
+        changes |= cx.with_id(*id, |cx| {
+            let child = element.child_mut();
+            let changes = view.rebuild(
+                cx,
+                &state.view,
+                &mut state.child_id,
+                &mut state.view_state,
+                child.expect_mut(),
+            );
+            child.mark(changes)
+        });
+        state.view = view;
+        changes
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path.split_first() {
+            Some((first, rest)) if *first == state.child_id => {
+                state
+                    .view
+                    .message(rest, &mut state.view_state, message, app_state)
+            }
+            None => match message.downcast::<Duration>() {
+                Ok(interval) => {
+                    if state.current == state.target {
+                        return MessageResult::Nop;
+                    }
+                    state.elapsed += interval;
+                    let progress = if self.duration.is_zero() {
+                        1.0
+                    } else {
+                        state.elapsed.as_secs_f64() / self.duration.as_secs_f64()
+                    };
+                    state.current = if progress >= 1.0 {
+                        state.target.clone()
+                    } else {
+                        state.from.lerp(&state.target, self.easing.apply(progress))
+                    };
+                    MessageResult::RequestRebuild
+                }
+                Err(message) => MessageResult::Stale(message),
+            },
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::view::label;
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    const FRAME: Duration = Duration::from_millis(25);
+
+    /// An animated label recording the values it is generated from.
+    fn animated(
+        value: f64,
+        easing: Easing,
+        seen: &Arc<Mutex<Vec<f64>>>,
+    ) -> impl View<(), Element = widget::Animator> + ViewMarker {
+        let seen = seen.clone();
+        animate(value, Duration::from_millis(100), easing, move |value| {
+            seen.lock().unwrap().push(value);
+            label(format!("{value}"))
+        })
+    }
+
+    fn last(seen: &Arc<Mutex<Vec<f64>>>) -> f64 {
+        *seen.lock().unwrap().last().unwrap()
+    }
+
+    #[test]
+    fn value_follows_the_easing_between_targets() {
+        let mut cx = view_cx();
+        let seen = Arc::new(Mutex::new(vec![]));
+
+        let view = animated(0.0, Easing::Linear, &seen);
+        let (mut id, mut state, mut element) = view.build(&mut cx);
+        assert!(!element.is_running());
+        let next = animated(100.0, Easing::Linear, &seen);
+        next.rebuild(&mut cx, &view, &mut id, &mut state, &mut element);
+        assert!(element.is_running());
+        assert_eq!(last(&seen), 0.0);
+
+        let mut trajectory = vec![];
+        for _ in 0..5 {
+            let result = next.message(&[], &mut state, Message::new(vec![], FRAME), &mut ());
+            if matches!(result, MessageResult::RequestRebuild) {
+                next.rebuild(&mut cx, &next, &mut id, &mut state, &mut element);
+                trajectory.push(last(&seen));
+            }
+        }
+        assert_eq!(trajectory, vec![25.0, 50.0, 75.0, 100.0]);
+        assert!(!element.is_running());
+    }
+
+    #[test]
+    fn retargeting_continues_from_the_displayed_value() {
+        let mut cx = view_cx();
+        let seen = Arc::new(Mutex::new(vec![]));
+
+        let view = animated(0.0, Easing::Linear, &seen);
+        let (mut id, mut state, mut element) = view.build(&mut cx);
+        let next = animated(100.0, Easing::Linear, &seen);
+        next.rebuild(&mut cx, &view, &mut id, &mut state, &mut element);
+        for _ in 0..2 {
+            next.message(&[], &mut state, Message::new(vec![], FRAME), &mut ());
+            next.rebuild(&mut cx, &next, &mut id, &mut state, &mut element);
+        }
+        assert_eq!(last(&seen), 50.0);
+
+        // Back to the start, the child must not jump to either end.
+        let back = animated(0.0, Easing::Linear, &seen);
+        back.rebuild(&mut cx, &next, &mut id, &mut state, &mut element);
+        assert_eq!(last(&seen), 50.0);
+        assert!(element.is_running());
+        for _ in 0..2 {
+            back.message(&[], &mut state, Message::new(vec![], FRAME), &mut ());
+            back.rebuild(&mut cx, &back, &mut id, &mut state, &mut element);
+        }
+        assert_eq!(last(&seen), 25.0);
+    }
+
+    #[test]
+    fn eased_animation_starts_slowly() {
+        let mut cx = view_cx();
+        let seen = Arc::new(Mutex::new(vec![]));
+
+        let view = animated(0.0, Easing::EaseInOut, &seen);
+        let (mut id, mut state, mut element) = view.build(&mut cx);
+        let next = animated(100.0, Easing::EaseInOut, &seen);
+        next.rebuild(&mut cx, &view, &mut id, &mut state, &mut element);
+        next.message(&[], &mut state, Message::new(vec![], FRAME), &mut ());
+        next.rebuild(&mut cx, &next, &mut id, &mut state, &mut element);
+        assert!(last(&seen) > 0.0 && last(&seen) < 25.0);
+        next.message(&[], &mut state, Message::new(vec![], FRAME), &mut ());
+        next.rebuild(&mut cx, &next, &mut id, &mut state, &mut element);
+        assert!((last(&seen) - 50.0).abs() < 1e-6);
+    }
+}
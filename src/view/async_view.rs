@@ -0,0 +1,349 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+use futures_task::{Context, Poll, Waker};
+use xilem_core::AsyncWake;
+
+use crate::view::ViewMarker;
+use crate::widget::AnyWidget;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A view showing the result of a future, see [`async_view`].
+pub struct AsyncView<T, A, K, FF, V1, R> {
+    key: K,
+    future_factory: FF,
+    pending: V1,
+    ready: R,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+pub struct AsyncViewState<T, A, O, V1: View<T, A>, V2: View<T, A>> {
+    task: Task<O>,
+    child_id: Id,
+    child: AsyncChild<T, A, V1, V2>,
+}
+
+/// A future and, once it resolved, its output.
+struct Task<O> {
+    /// Dropped once the future resolved or the key changed, which cancels it.
+    future: Option<Pin<Box<dyn Future<Output = O> + Send>>>,
+    output: Option<O>,
+    waker: Waker,
+}
+
+enum AsyncChild<T, A, V1: View<T, A>, V2: View<T, A>> {
+    Pending(V1::State),
+    Ready(V2, V2::State),
+}
+
+/// Shows `pending` until the future created by `future_factory` resolves, then the view
+/// `ready` creates from its output.
+///
+/// The future is created from `key` when the view is built and again whenever a rebuild
+/// changes the key, dropping the previous future if it is still running. It is polled on the
+/// app's task, which is woken through the id path of this view, and its output is kept in the
+/// view state until the key changes. While the future is pending, rendering is briefly held
+/// back to avoid flashing `pending` for futures which resolve quickly.
+pub fn async_view<T, A, K, FF, F, V1, R, V2>(
+    key: K,
+    future_factory: FF,
+    pending: V1,
+    ready: R,
+) -> AsyncView<T, A, K, FF, V1, R>
+where
+    K: PartialEq + Send,
+    FF: Fn(&K) -> F + Send,
+    F: Future + Send + 'static,
+    V1: View<T, A>,
+    R: Fn(&F::Output) -> V2 + Send,
+    V2: View<T, A>,
+{
+    AsyncView {
+        key,
+        future_factory,
+        pending,
+        ready,
+        phantom: PhantomData,
+    }
+}
+
+impl<O> Task<O> {
+    /// Starts `future` and polls it once, futures which are ready right away don't show the
+    /// pending view at all.
+    fn start(cx: &mut Cx, id: Id, future: impl Future<Output = O> + Send + 'static) -> Self {
+        let mut task = Task {
+            future: Some(Box::pin(future)),
+            output: None,
+            waker: cx.waker(),
+        };
+        task.poll();
+        if task.future.is_some() {
+            cx.add_pending_async(id);
+        }
+        task
+    }
+
+    /// Polls the future, returns whether it resolved just now.
+    fn poll(&mut self) -> bool {
+        let Some(future) = &mut self.future else {
+            return false;
+        };
+        match future.as_mut().poll(&mut Context::from_waker(&self.waker)) {
+            Poll::Ready(output) => {
+                self.future = None;
+                self.output = Some(output);
+                true
+            }
+            Poll::Pending => false,
+        }
+    }
+}
+
+impl<T, A, K, FF, V1, R> ViewMarker for AsyncView<T, A, K, FF, V1, R> {}
+
+impl<T, A, K, FF, F, V1, R, V2> View<T, A> for AsyncView<T, A, K, FF, V1, R>
+where
+    K: PartialEq + Send,
+    FF: Fn(&K) -> F + Send,
+    F: Future + Send + 'static,
+    F::Output: Send,
+    V1: View<T, A>,
+    V1::Element: 'static,
+    R: Fn(&F::Output) -> V2 + Send,
+    V2: View<T, A>,
+    V2::Element: 'static,
+{
+    type State = AsyncViewState<T, A, F::Output, V1, V2>;
+
+    type Element = Box<dyn AnyWidget>;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let id = *cx.id_path().last().unwrap();
+            let task = Task::start(cx, id, (self.future_factory)(&self.key));
+            let (child_id, child, element) = self.build_child(cx, task.output.as_ref());
+            let state = AsyncViewState {
+                task,
+                child_id,
+                child,
+            };
+            (state, element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            if prev.key != self.key {
+                // Replacing the task drops the previous future, if it is still running.
+                state.task = Task::start(cx, *id, (self.future_factory)(&self.key));
+            }
+            match (&mut state.child, &state.task.output) {
+                (AsyncChild::Pending(child_state), None) => {
+                    let element = (**element).as_any_mut().downcast_mut().unwrap();
+                    let child_id = &mut state.child_id;
+                    self.pending
+                        .rebuild(cx, &prev.pending, child_id, child_state, element)
+                }
+                (AsyncChild::Ready(view, child_state), Some(output)) => {
+                    let element = (**element).as_any_mut().downcast_mut().unwrap();
+                    let next = (self.ready)(output);
+                    let child_id = &mut state.child_id;
+                    let changes = next.rebuild(cx, view, child_id, child_state, element);
+                    *view = next;
+                    changes
+                }
+                _ => {
+                    let (child_id, child, new_element) =
+                        self.build_child(cx, state.task.output.as_ref());
+                    state.child_id = child_id;
+                    state.child = child;
+                    *element = new_element;
+                    ChangeFlags::tree_structure()
+                }
+            }
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path.split_first() {
+            Some((first, rest_path)) if *first == state.child_id => match &mut state.child {
+                AsyncChild::Pending(child_state) => {
+                    self.pending
+                        .message(rest_path, child_state, message, app_state)
+                }
+                AsyncChild::Ready(view, child_state) => {
+                    view.message(rest_path, child_state, message, app_state)
+                }
+            },
+            None if message.downcast_ref::<AsyncWake>().is_some() => {
+                if state.task.poll() {
+                    MessageResult::RequestRebuild
+                } else {
+                    MessageResult::Nop
+                }
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+impl<T, A, K, FF, F, V1, R, V2> AsyncView<T, A, K, FF, V1, R>
+where
+    F: Future,
+    V1: View<T, A>,
+    V1::Element: 'static,
+    R: Fn(&F::Output) -> V2,
+    V2: View<T, A>,
+    V2::Element: 'static,
+    FF: Fn(&K) -> F,
+{
+    /// Builds the pending view, or the ready view when there is an output.
+    fn build_child(
+        &self,
+        cx: &mut Cx,
+        output: Option<&F::Output>,
+    ) -> (Id, AsyncChild<T, A, V1, V2>, Box<dyn AnyWidget>) {
+        match output {
+            None => {
+                let (child_id, child_state, element) = self.pending.build(cx);
+                (
+                    child_id,
+                    AsyncChild::Pending(child_state),
+                    Box::new(element),
+                )
+            }
+            Some(output) => {
+                let view = (self.ready)(output);
+                let (child_id, child_state, element) = view.build(cx);
+                (
+                    child_id,
+                    AsyncChild::Ready(view, child_state),
+                    Box::new(element),
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use tokio::sync::oneshot;
+
+    use super::*;
+    use crate::view::label;
+    use crate::widget::test_util::view_cx_with_messages;
+    use test_log::test;
+
+    /// Hands out futures which resolve when the test sends their value.
+    #[derive(Clone, Default)]
+    struct Senders(Arc<Mutex<HashMap<u32, oneshot::Sender<String>>>>);
+
+    impl Senders {
+        fn future(&self, key: u32) -> impl Future<Output = String> + Send + 'static {
+            let (sender, receiver) = oneshot::channel();
+            self.0.lock().unwrap().insert(key, sender);
+            async move { receiver.await.unwrap_or_default() }
+        }
+
+        fn take(&self, key: u32) -> oneshot::Sender<String> {
+            self.0.lock().unwrap().remove(&key).unwrap()
+        }
+    }
+
+    fn view(senders: &Senders, key: u32) -> impl View<(), Element = Box<dyn AnyWidget>> {
+        let senders = senders.clone();
+        async_view(
+            key,
+            move |key| senders.future(*key),
+            label("loading"),
+            |text: &String| label(text.clone()),
+        )
+    }
+
+    fn text(element: &mut Box<dyn AnyWidget>) -> String {
+        let label = (**element)
+            .as_any_mut()
+            .downcast_mut::<crate::widget::Label>();
+        label.unwrap().text().to_string()
+    }
+
+    #[test]
+    fn resolving_after_teardown_finds_no_future() {
+        let (mut cx, _wakes) = view_cx_with_messages();
+        let senders = Senders::default();
+
+        let (_, state, mut element) = view(&senders, 1).build(&mut cx);
+        assert_eq!(text(&mut element), "loading");
+        let sender = senders.take(1);
+        assert!(!sender.is_closed());
+
+        drop(state);
+        assert!(sender.is_closed());
+        assert!(sender.send("late".into()).is_err());
+    }
+
+    #[test]
+    fn rapid_key_changes_only_show_the_last_output() {
+        let (mut cx, wakes) = view_cx_with_messages();
+        let senders = Senders::default();
+
+        let mut prev = view(&senders, 1);
+        let (mut id, mut state, mut element) = prev.build(&mut cx);
+        let first = senders.take(1);
+        for key in [2, 3] {
+            let next = view(&senders, key);
+            let changes = next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+            assert!(!changes.contains(ChangeFlags::TREE));
+            prev = next;
+        }
+        // Each key change dropped the future of the previous key.
+        assert!(first.is_closed());
+        assert!(senders.take(2).is_closed());
+        assert_eq!(text(&mut element), "loading");
+
+        // Resolving wakes the view through its id path, which asks for a rebuild.
+        senders.take(3).send("three".into()).unwrap();
+        assert_eq!(wakes.try_recv().unwrap().id_path, vec![id]);
+        let result = prev.message(&[], &mut state, Message::new(vec![], AsyncWake), &mut ());
+        assert!(matches!(result, MessageResult::RequestRebuild));
+
+        let next = view(&senders, 3);
+        let changes = next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+        assert!(changes.contains(ChangeFlags::TREE));
+        assert_eq!(text(&mut element), "three");
+    }
+}
@@ -12,70 +12,141 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::any::Any;
-
 use crate::view::ViewMarker;
-use crate::{view::Id, widget::ChangeFlags, MessageResult};
+use crate::widget::Pod;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
 
 use super::{Cx, View};
 
-pub struct Button<T, A> {
-    label: String,
+/// A clickable view wrapping `child`.
+///
+/// Clicking invokes the callback with the app state, its return value is the action of this view.
+pub struct Button<T, A, V> {
+    child: V,
+    disabled: bool,
     // consider not boxing
     callback: Box<dyn Fn(&mut T) -> A + Send>,
 }
 
-pub fn button<T, A>(
-    label: impl Into<String>,
+pub fn button<T, A, V: View<T, A>>(
+    child: V,
     clicked: impl Fn(&mut T) -> A + Send + 'static,
-) -> Button<T, A> {
-    Button::new(label, clicked)
+) -> Button<T, A, V> {
+    Button::new(child, clicked)
 }
 
-impl<T, A> Button<T, A> {
-    pub fn new(label: impl Into<String>, clicked: impl Fn(&mut T) -> A + Send + 'static) -> Self {
+impl<T, A, V: View<T, A>> Button<T, A, V> {
+    pub fn new(child: V, clicked: impl Fn(&mut T) -> A + Send + 'static) -> Self {
         Button {
-            label: label.into(),
+            child,
+            disabled: false,
             callback: Box::new(clicked),
         }
     }
+
+    /// Disables the button.
+    ///
+    /// A disabled button ignores all input and is painted greyed out.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
 }
 
-impl<T, A> ViewMarker for Button<T, A> {}
+impl<T, A, V> ViewMarker for Button<T, A, V> {}
 
-impl<T, A> View<T, A> for Button<T, A> {
-    type State = ();
+impl<T, A, V: View<T, A>> View<T, A> for Button<T, A, V>
+where
+    V::Element: 'static,
+{
+    type State = (Id, V::State);
 
     type Element = crate::widget::Button;
 
-    fn build(&self, cx: &mut Cx) -> (crate::view::Id, Self::State, Self::Element) {
-        let (id, element) =
-            cx.with_new_id(|cx| crate::widget::Button::new(cx.id_path(), self.label.clone()));
-        (id, (), element)
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (child_id, child_state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child_element) = self.child.build(cx);
+            let mut element = crate::widget::Button::new(cx.id_path(), Pod::new(child_element));
+            let _ = element.set_disabled(self.disabled);
+            (child_id, child_state, element)
+        });
+        (id, (child_id, child_state), element)
     }
 
     fn rebuild(
         &self,
-        _cx: &mut Cx,
+        cx: &mut Cx,
         prev: &Self,
-        _id: &mut Id,
-        _state: &mut Self::State,
+        id: &mut Id,
+        state: &mut Self::State,
         element: &mut Self::Element,
     ) -> ChangeFlags {
-        if prev.label != self.label {
-            element.set_label(self.label.clone())
-        } else {
-            ChangeFlags::default()
+        let mut changes = cx.with_id(*id, |cx| {
+            let mut child = element.child_mut().as_mut::<V::Element>();
+            child.apply(|child| {
+                self.child
+                    .rebuild(cx, &prev.child, &mut state.0, &mut state.1, child)
+            });
+            child.changes()
+        });
+        if prev.disabled != self.disabled {
+            changes |= element.set_disabled(self.disabled);
         }
+        changes
     }
 
     fn message(
         &self,
-        _id_path: &[Id],
-        _state: &mut Self::State,
-        _message: Box<dyn Any>,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
         app_state: &mut T,
     ) -> MessageResult<A> {
-        MessageResult::Action((self.callback)(app_state))
+        match id_path.split_first() {
+            Some((first, rest_path)) if *first == state.0 => {
+                self.child
+                    .message(rest_path, &mut state.1, message, app_state)
+            }
+            Some(_) => MessageResult::Stale(message),
+            None if self.disabled => MessageResult::Nop,
+            None => MessageResult::Action((self.callback)(app_state)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::label;
+    use crate::widget::test_util::{view_cx, Harness};
+    use crate::widget::{BoxConstraints, Event};
+    use test_log::test;
+
+    #[test]
+    fn accessibility_click_runs_the_callback() {
+        let mut cx = view_cx();
+        let mut harness = Harness::new();
+        let mut clicks = 0;
+
+        let view = button(label("Send"), |clicks: &mut u32| *clicks += 1);
+        let (_, mut state, element) = view.build(&mut cx);
+        let mut pod = Pod::new(element);
+        let node_id: accesskit::NodeId = pod.id().into();
+        harness.layout(&mut pod, &BoxConstraints::UNBOUNDED);
+        let update = harness.accessibility(&mut pod);
+        let (_, node) = update.nodes.iter().find(|(id, _)| *id == node_id).unwrap();
+        assert!(node.supports_action(accesskit::Action::Default));
+
+        let click = accesskit::ActionRequest {
+            action: accesskit::Action::Default,
+            target: node_id,
+            data: None,
+        };
+        harness.event(&mut pod, Event::TargetedAccessibilityAction(click));
+        for message in harness.take_messages() {
+            let id_path = message.id_path.clone();
+            let _ = view.message(&id_path[1..], &mut state, message, &mut clicks);
+        }
+        assert_eq!(clicks, 1);
     }
 }
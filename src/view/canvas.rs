@@ -0,0 +1,165 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use vello::{kurbo::Size, SceneBuilder};
+
+use crate::view::ViewMarker;
+use crate::widget::Event;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A view painted by a closure, see [`canvas`].
+pub struct Canvas<T, A> {
+    paint: crate::widget::PaintFn,
+    preferred_size: Option<Size>,
+    key: Option<Box<dyn CanvasKey>>,
+    // consider not boxing
+    on_pointer: Option<Box<dyn Fn(&mut T, &Event) -> A + Send>>,
+}
+
+/// Creates a canvas which calls `paint` with a scene builder and its size.
+///
+/// The closure usually captures the part of the app state it draws. As it can't be compared,
+/// every rebuild repaints the canvas, unless a [`key`](Canvas::key) tells otherwise.
+pub fn canvas<T, A>(
+    paint: impl Fn(&mut SceneBuilder, Size) + Send + Sync + 'static,
+) -> Canvas<T, A> {
+    Canvas {
+        paint: Arc::new(paint),
+        preferred_size: None,
+        key: None,
+        on_pointer: None,
+    }
+}
+
+impl<T, A> Canvas<T, A> {
+    /// The size to ask for, by default the canvas fills the available space.
+    pub fn preferred_size(mut self, size: impl Into<Size>) -> Self {
+        self.preferred_size = Some(size.into());
+        self
+    }
+
+    /// Skips repainting on rebuilds where `key` equals the key of the previous view.
+    pub fn key(mut self, key: impl PartialEq + Send + 'static) -> Self {
+        self.key = Some(Box::new(key));
+        self
+    }
+
+    /// Handles mouse events on the canvas, in local coordinates.
+    ///
+    /// After a press, the canvas also receives the moves and the release outside of it.
+    pub fn on_pointer(mut self, on_pointer: impl Fn(&mut T, &Event) -> A + Send + 'static) -> Self {
+        self.on_pointer = Some(Box::new(on_pointer));
+        self
+    }
+}
+
+trait CanvasKey: Send {
+    fn as_any(&self) -> &dyn Any;
+
+    fn same_as(&self, other: &dyn CanvasKey) -> bool;
+}
+
+impl<K: PartialEq + Send + 'static> CanvasKey for K {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn same_as(&self, other: &dyn CanvasKey) -> bool {
+        other.as_any().downcast_ref::<K>() == Some(self)
+    }
+}
+
+impl<T, A> ViewMarker for Canvas<T, A> {}
+
+impl<T, A> View<T, A> for Canvas<T, A> {
+    type State = ();
+
+    type Element = crate::widget::Canvas;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, mut element) =
+            cx.with_new_id(|cx| crate::widget::Canvas::new(cx.id_path(), self.paint.clone()));
+        let _ = element.set_preferred_size(self.preferred_size);
+        element.set_forwards_pointer(self.on_pointer.is_some());
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        element.set_forwards_pointer(self.on_pointer.is_some());
+        let mut changes = element.set_preferred_size(self.preferred_size);
+        let unchanged = match (&self.key, &prev.key) {
+            (Some(key), Some(prev_key)) => key.same_as(prev_key.as_ref()),
+            _ => false,
+        };
+        if !unchanged {
+            changes |= element.set_paint(self.paint.clone());
+        }
+        changes
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if let (Some(on_pointer), true) = (&self.on_pointer, id_path.is_empty()) {
+            if let Some(event) = message.downcast_ref::<Event>() {
+                return MessageResult::Action(on_pointer(app_state, event));
+            }
+        }
+        MessageResult::Stale(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    #[test]
+    fn key_skips_repaint() {
+        let mut cx = view_cx();
+        let sparkline = |values: Vec<f64>| canvas::<(), ()>(|_, _| {}).key(values);
+
+        let view = sparkline(vec![1.0, 2.0]);
+        let (mut id, mut state, mut element) = view.build(&mut cx);
+
+        let same = sparkline(vec![1.0, 2.0]);
+        let changes = same.rebuild(&mut cx, &view, &mut id, &mut state, &mut element);
+        assert_eq!(changes, ChangeFlags::empty());
+
+        let next = sparkline(vec![1.0, 3.0]);
+        let changes = next.rebuild(&mut cx, &same, &mut id, &mut state, &mut element);
+        assert_eq!(changes, ChangeFlags::PAINT);
+
+        let unkeyed = canvas::<(), ()>(|_, _| {});
+        let changes = unkeyed.rebuild(&mut cx, &next, &mut id, &mut state, &mut element);
+        assert_eq!(changes, ChangeFlags::PAINT);
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::ViewMarker;
+use crate::widget::{CheckState, Pod, TextWidget};
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A checkbox displaying a value owned by the app state.
+///
+/// The checkbox does not store whether it is checked. Toggling invokes the callback with the new
+/// value, which is expected to update the app state the next rebuild reads from.
+pub struct Checkbox<T, A> {
+    checked: bool,
+    indeterminate: bool,
+    label: Option<String>,
+    // consider not boxing
+    callback: Box<dyn Fn(&mut T, bool) -> A + Send>,
+}
+
+pub fn checkbox<T, A>(
+    checked: bool,
+    on_toggle: impl Fn(&mut T, bool) -> A + Send + 'static,
+) -> Checkbox<T, A> {
+    Checkbox::new(checked, on_toggle)
+}
+
+impl<T, A> Checkbox<T, A> {
+    pub fn new(checked: bool, on_toggle: impl Fn(&mut T, bool) -> A + Send + 'static) -> Self {
+        Checkbox {
+            checked,
+            indeterminate: false,
+            label: None,
+            callback: Box::new(on_toggle),
+        }
+    }
+
+    /// Displays the checkbox as neither checked nor unchecked.
+    ///
+    /// Toggling an indeterminate checkbox reports `true`.
+    pub fn indeterminate(mut self, indeterminate: bool) -> Self {
+        self.indeterminate = indeterminate;
+        self
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    fn check_state(&self) -> CheckState {
+        if self.indeterminate {
+            CheckState::Indeterminate
+        } else {
+            self.checked.into()
+        }
+    }
+}
+
+impl<T, A> ViewMarker for Checkbox<T, A> {}
+
+impl<T, A> View<T, A> for Checkbox<T, A> {
+    type State = ();
+
+    type Element = crate::widget::Checkbox;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, element) = cx.with_new_id(|cx| {
+            let label = self
+                .label
+                .as_ref()
+                .map(|label| Pod::new(TextWidget::new(label.clone())));
+            crate::widget::Checkbox::new(cx.id_path(), self.check_state(), label)
+        });
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changes = element.set_state(self.check_state());
+        if self.label != prev.label {
+            if let (Some(label), true) = (&self.label, prev.label.is_some()) {
+                let pod = element.label_mut().unwrap();
+                let flags = pod.expect_mut::<TextWidget>().set_text(label.clone());
+                changes |= pod.mark(flags);
+            } else {
+                let label = self
+                    .label
+                    .as_ref()
+                    .map(|label| Pod::new(TextWidget::new(label.clone())));
+                changes |= element.set_label(label);
+            }
+        }
+        changes
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if !id_path.is_empty() {
+            return MessageResult::Stale(message);
+        }
+        MessageResult::Action((self.callback)(app_state, self.check_state().toggled()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    #[test]
+    fn toggle_reaches_callback_and_rebuild_shows_it() {
+        let mut cx = view_cx();
+        let mut checked = false;
+
+        let view =
+            checkbox(checked, |checked: &mut bool, value| *checked = value).indeterminate(true);
+        let (mut id, mut state, mut element) = view.build(&mut cx);
+        assert_eq!(element.state(), CheckState::Indeterminate);
+
+        let result = view.message(&[], &mut state, Message::new(vec![], ()), &mut checked);
+        assert!(matches!(result, MessageResult::Action(())));
+        assert!(checked);
+
+        let next = checkbox(checked, |checked: &mut bool, value| *checked = value);
+        let changes = next.rebuild(&mut cx, &view, &mut id, &mut state, &mut element);
+        assert!(changes.contains(ChangeFlags::PAINT));
+        assert_eq!(element.state(), CheckState::Checked);
+    }
+}
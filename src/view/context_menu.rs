@@ -0,0 +1,372 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, ContextMenuMessage, MenuEntry};
+use crate::{HotKey, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A menu shown by [`ViewExt::context_menu`](super::ViewExt::context_menu).
+pub struct Menu<A> {
+    items: Vec<MenuItem<A>>,
+}
+
+/// An item of a [`Menu`], which either produces an action or opens a submenu.
+pub struct MenuItem<A> {
+    label: String,
+    enabled: bool,
+    hotkey: Option<HotKey>,
+    kind: MenuItemKind<A>,
+}
+
+enum MenuItemKind<A> {
+    Action(A),
+    Submenu(Menu<A>),
+}
+
+impl<A> Menu<A> {
+    pub fn new(items: Vec<MenuItem<A>>) -> Self {
+        Menu { items }
+    }
+
+    fn entries(&self) -> Vec<MenuEntry> {
+        self.items
+            .iter()
+            .map(|item| MenuEntry {
+                label: item.label.clone(),
+                enabled: item.enabled,
+                shortcut: item.hotkey.as_ref().map(HotKey::display_label),
+                submenu: match &item.kind {
+                    MenuItemKind::Action(_) => vec![],
+                    MenuItemKind::Submenu(menu) => menu.entries(),
+                },
+            })
+            .collect()
+    }
+
+    /// Takes the action of the item at `path`, if it is an enabled item with an action.
+    fn into_action(self, path: &[usize]) -> Option<A> {
+        let (&index, rest) = path.split_first()?;
+        let item = self.items.into_iter().nth(index)?;
+        if !item.enabled {
+            return None;
+        }
+        match item.kind {
+            MenuItemKind::Action(action) if rest.is_empty() => Some(action),
+            MenuItemKind::Submenu(menu) => menu.into_action(rest),
+            MenuItemKind::Action(_) => None,
+        }
+    }
+}
+
+impl<A> MenuItem<A> {
+    /// An item which produces `action` when chosen.
+    pub fn new(label: impl Into<String>, action: A) -> Self {
+        MenuItem {
+            label: label.into(),
+            enabled: true,
+            hotkey: None,
+            kind: MenuItemKind::Action(action),
+        }
+    }
+
+    /// An item which opens `menu` as a submenu.
+    pub fn submenu(label: impl Into<String>, menu: Menu<A>) -> Self {
+        MenuItem {
+            label: label.into(),
+            enabled: true,
+            hotkey: None,
+            kind: MenuItemKind::Submenu(menu),
+        }
+    }
+
+    /// Sets whether the item can be chosen, disabled items are shown greyed out.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Shows the label of `hotkey` next to the item.
+    ///
+    /// The menu doesn't listen for it, register the same hotkey with
+    /// [`shortcut_scope`](super::shortcut_scope) or [`on_key`](super::on_key).
+    pub fn hotkey(mut self, hotkey: HotKey) -> Self {
+        self.hotkey = Some(hotkey);
+        self
+    }
+}
+
+/// Shows a context menu for a child view, see
+/// [`ViewExt::context_menu`](super::ViewExt::context_menu).
+pub struct ContextMenu<V, F> {
+    child: V,
+    menu: F,
+}
+
+/// The state of a [`ContextMenu`] view.
+pub struct ContextMenuState<T, A, V: View<T, A>> {
+    child: (Id, V::State),
+    /// The menu shown last, whose item actions are still pending.
+    menu: Option<Menu<A>>,
+    /// Whether the menu was asked for and has to be passed to the widget.
+    is_requested: bool,
+}
+
+impl<V, F> ContextMenu<V, F> {
+    pub(crate) fn new(child: V, menu: F) -> Self {
+        ContextMenu { child, menu }
+    }
+}
+
+impl<V, F> ViewMarker for ContextMenu<V, F> {}
+
+impl<T, A: Send, V: View<T, A>, F: Fn(&T) -> Menu<A> + Send> View<T, A> for ContextMenu<V, F> {
+    type State = ContextMenuState<T, A, V>;
+
+    type Element = widget::ContextMenu;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let state = ContextMenuState {
+                child: (child_id, child_state),
+                menu: None,
+                is_requested: false,
+            };
+            let element = widget::ContextMenu::new(cx.id_path(), widget::Pod::new(child));
+            (state, element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let (child_id, child_state) = &mut state.child;
+            let child = element.child_mut();
+            let changes =
+                self.child
+                    .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+            let mut changes = child.mark(changes);
+            if std::mem::take(&mut state.is_requested) {
+                if let Some(menu) = &state.menu {
+                    changes |= element.open(menu.entries());
+                }
+            }
+            changes
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path.split_first() {
+            None => match message.downcast::<ContextMenuMessage>() {
+                Ok(message) => match message {
+                    ContextMenuMessage::Open => {
+                        // The menu is built from the app state here and shown after the rebuild
+                        // which follows every message.
+                        state.menu = Some((self.menu)(app_state));
+                        state.is_requested = true;
+                        MessageResult::Nop
+                    }
+                    ContextMenuMessage::Choose(path) => {
+                        match state.menu.take().and_then(|menu| menu.into_action(&path)) {
+                            Some(action) => MessageResult::Action(action),
+                            None => MessageResult::Nop,
+                        }
+                    }
+                },
+                Err(message) => MessageResult::Stale(message),
+            },
+            Some((first, rest)) if *first == state.child.0 => {
+                self.child
+                    .message(rest, &mut state.child.1, message, app_state)
+            }
+            Some(_) => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::ViewExt;
+    use crate::widget::test_util::{key_down, view_cx, Harness, Leaf};
+    use crate::widget::{BoxConstraints, OverlayHost, Pod};
+    use glazier::KbKey;
+    use test_log::test;
+    use vello::kurbo::Size;
+
+    /// A view showing a fixed widget.
+    struct Blank;
+
+    impl ViewMarker for Blank {}
+
+    impl View<Vec<&'static str>, &'static str> for Blank {
+        type State = ();
+
+        type Element = Leaf;
+
+        fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+            let (id, element) = cx.with_new_id(|_| Leaf::new(100.0, 40.0));
+            (id, (), element)
+        }
+
+        fn rebuild(
+            &self,
+            _cx: &mut Cx,
+            _prev: &Self,
+            _id: &mut Id,
+            _state: &mut Self::State,
+            _element: &mut Self::Element,
+        ) -> ChangeFlags {
+            ChangeFlags::empty()
+        }
+
+        fn message(
+            &self,
+            _id_path: &[Id],
+            _state: &mut Self::State,
+            message: Message,
+            _app_state: &mut Vec<&'static str>,
+        ) -> MessageResult<&'static str> {
+            MessageResult::Stale(message)
+        }
+    }
+
+    fn app_view(
+        _: &Vec<&'static str>,
+    ) -> impl View<Vec<&'static str>, &'static str, Element = widget::ContextMenu> {
+        Blank.context_menu(|history: &Vec<&'static str>| {
+            Menu::new(vec![
+                MenuItem::new("Undo", "undo").enabled(!history.is_empty()),
+                MenuItem::submenu(
+                    "Insert",
+                    Menu::new(vec![
+                        MenuItem::new("Image", "image").enabled(false),
+                        MenuItem::submenu(
+                            "Shape",
+                            Menu::new(vec![
+                                MenuItem::new("Circle", "circle"),
+                                MenuItem::new("Square", "square"),
+                            ]),
+                        ),
+                    ]),
+                ),
+            ])
+        })
+    }
+
+    #[test]
+    fn keyboard_drives_nested_submenus_into_app_state() {
+        let mut cx = view_cx();
+        let mut harness = Harness::new();
+        let mut app_state: Vec<&'static str> = vec![];
+        let bc = BoxConstraints::tight(Size::new(400.0, 300.0));
+
+        let view = app_view(&app_state);
+        let (mut id, mut state, element) = view.build(&mut cx);
+        let mut root = Pod::new(OverlayHost::new(Pod::new(element)));
+        harness.layout(&mut root, &bc);
+        let child = {
+            let host = root.downcast_mut::<OverlayHost>().unwrap();
+            let menu = host
+                .content_mut()
+                .downcast_mut::<widget::ContextMenu>()
+                .unwrap();
+            menu.child_mut().id()
+        };
+        harness.window_state.focus = Some(child);
+
+        // Runs the app loop: delivers the messages, applies the actions and rebuilds.
+        let mut run = |harness: &mut Harness, root: &mut Pod, app_state: &mut Vec<&'static str>| {
+            for message in harness.take_messages() {
+                let id_path = message.id_path.clone();
+                let result = view.message(&id_path[1..], &mut state, message, app_state);
+                if let MessageResult::Action(action) = result {
+                    app_state.push(action);
+                }
+            }
+            let next = app_view(app_state);
+            let host = root.downcast_mut::<OverlayHost>().unwrap();
+            let content = host.content_mut();
+            let changes = next.rebuild(
+                &mut cx,
+                &view,
+                &mut id,
+                &mut state,
+                content.downcast_mut().unwrap(),
+            );
+            let changes = content.mark(changes);
+            let _ = root.mark(changes);
+            harness.update(root);
+            harness.layout(root, &bc);
+        };
+
+        harness.event(&mut root, key_down(KbKey::ContextMenu));
+        run(&mut harness, &mut root, &mut app_state);
+        let overlay_count =
+            |root: &mut Pod| root.downcast_mut::<OverlayHost>().unwrap().overlay_count();
+        assert_eq!(overlay_count(&mut root), 1);
+
+        // "Undo" is disabled with an empty history, so the first item is "Insert".
+        harness.event(&mut root, key_down(KbKey::ArrowDown));
+        harness.event(&mut root, key_down(KbKey::ArrowRight));
+        harness.layout(&mut root, &bc);
+        assert_eq!(overlay_count(&mut root), 2);
+        // "Image" is disabled, so "Shape" is highlighted.
+        harness.event(&mut root, key_down(KbKey::ArrowRight));
+        harness.layout(&mut root, &bc);
+        assert_eq!(overlay_count(&mut root), 3);
+        harness.event(&mut root, key_down(KbKey::ArrowLeft));
+        assert_eq!(overlay_count(&mut root), 2);
+        harness.event(&mut root, key_down(KbKey::Enter));
+        harness.layout(&mut root, &bc);
+        harness.event(&mut root, key_down(KbKey::ArrowDown));
+        harness.event(&mut root, key_down(KbKey::Enter));
+        run(&mut harness, &mut root, &mut app_state);
+
+        assert_eq!(app_state, vec!["square"]);
+        assert_eq!(overlay_count(&mut root), 0);
+        assert_eq!(harness.window_state.focus, Some(child));
+
+        // With a history "Undo" is enabled, and Escape closes the menu without an action.
+        harness.event(&mut root, key_down(KbKey::ContextMenu));
+        run(&mut harness, &mut root, &mut app_state);
+        harness.event(&mut root, key_down(KbKey::ArrowDown));
+        harness.event(&mut root, key_down(KbKey::Escape));
+        run(&mut harness, &mut root, &mut app_state);
+        assert_eq!(app_state, vec!["square"]);
+        assert_eq!(overlay_count(&mut root), 0);
+
+        harness.event(&mut root, key_down(KbKey::ContextMenu));
+        run(&mut harness, &mut root, &mut app_state);
+        harness.event(&mut root, key_down(KbKey::ArrowDown));
+        harness.event(&mut root, key_down(KbKey::Enter));
+        run(&mut harness, &mut root, &mut app_state);
+        assert_eq!(app_state, vec!["square", "undo"]);
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Disables a child view, see [`disabled`].
+pub struct Disabled<V> {
+    child: V,
+    disabled: bool,
+}
+
+/// Disables `child` and everything below it while `disabled` is set.
+///
+/// Disabled widgets ignore pointer and keyboard input, are skipped by Tab and lose focus, and
+/// are painted muted. `disabled(false)` inside a disabled subtree doesn't enable it again.
+/// Changing the flag keeps the widgets below, they are not rebuilt. This is the same as
+/// [`ViewExt::disabled`](super::ViewExt::disabled).
+pub fn disabled<V>(disabled: bool, child: V) -> Disabled<V> {
+    Disabled { child, disabled }
+}
+
+impl<V> ViewMarker for Disabled<V> {}
+
+impl<T, A, V: View<T, A>> View<T, A> for Disabled<V>
+where
+    V::Element: 'static,
+{
+    type State = (Id, V::State);
+
+    type Element = widget::DisabledScope;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let element = widget::DisabledScope::new(Pod::new(child), self.disabled);
+            ((child_id, child_state), element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let (child_id, child_state) = state;
+        let changes = cx.with_id(*id, |cx| {
+            let child = element.child_mut();
+            let changes =
+                self.child
+                    .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+            child.mark(changes)
+        });
+        changes | element.set_disabled(self.disabled)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let (child_id, child_state) = state;
+        match id_path.split_first() {
+            Some((first, rest)) if first == child_id => {
+                self.child.message(rest, child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::{Any, TypeId};
+use std::path::PathBuf;
+
+use vello::kurbo::Point;
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, DragEnded, DropMessage, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Lets the user drag a child view onto views accepting its payload, see [`drag_source`].
+pub struct DragSource<T, A, V, P> {
+    child: V,
+    payload: P,
+    on_end: Option<Box<dyn Fn(&mut T, bool) -> A + Send>>,
+}
+
+/// Lets the user drag `child` and drop a clone of `payload` on a view accepting `P`, see
+/// [`ViewExt::on_drop_of`](super::ViewExt::on_drop_of).
+///
+/// A translucent image of the child follows the pointer while it is dragged. Escape cancels
+/// the drag.
+pub fn drag_source<T, A, V, P>(child: V, payload: P) -> DragSource<T, A, V, P> {
+    DragSource {
+        child,
+        payload,
+        on_end: None,
+    }
+}
+
+impl<T, A, V, P> DragSource<T, A, V, P> {
+    /// Calls `f` when a drag ends, with whether a target took the payload. It didn't when the
+    /// payload was dropped elsewhere or the drag was cancelled.
+    pub fn on_drag_end(mut self, f: impl Fn(&mut T, bool) -> A + Send + 'static) -> Self {
+        self.on_end = Some(Box::new(f));
+        self
+    }
+}
+
+fn make_payload<P: Clone + Send + 'static>(payload: &P) -> impl Fn() -> Box<dyn Any + Send> {
+    let payload = payload.clone();
+    move || Box::new(payload.clone())
+}
+
+impl<T, A, V, P> ViewMarker for DragSource<T, A, V, P> {}
+
+impl<T, A, V: View<T, A>, P: Clone + Send + 'static> View<T, A> for DragSource<T, A, V, P> {
+    type State = (Id, V::State);
+
+    type Element = widget::DragSource;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let payload = make_payload(&self.payload);
+            let element = widget::DragSource::new(cx.id_path(), Pod::new(child), payload);
+            ((child_id, child_state), element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let (child_id, child_state) = state;
+        // Drags which already started keep their payload.
+        element.set_payload(make_payload(&self.payload));
+        cx.with_id(*id, |cx| {
+            let child = element.child_mut();
+            let changes =
+                self.child
+                    .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+            child.mark(changes)
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let (child_id, child_state) = state;
+        match id_path.split_first() {
+            Some((first, rest)) if first == child_id => {
+                self.child.message(rest, child_state, message, app_state)
+            }
+            Some(_) => MessageResult::Stale(message),
+            None => match message.downcast::<DragEnded>() {
+                Ok(ended) => match &self.on_end {
+                    Some(f) => MessageResult::Action(f(app_state, ended.accepted)),
+                    None => MessageResult::Nop,
+                },
+                Err(message) => MessageResult::Stale(message),
+            },
+        }
+    }
+}
+
+/// Accepts drops of `P` on a child view, see [`drop_target`].
+pub struct DropTarget<T, A, V, P> {
+    child: V,
+    on_drop: Box<dyn Fn(&mut T, P, Point) -> A + Send>,
+    on_hover: Option<Box<dyn Fn(&mut T, bool) -> A + Send>>,
+}
+
+/// Calls `on_drop` with the payload and position in the child when a `P` from a
+/// [`drag_source`] is dropped on `child`.
+///
+/// Drags of other types are ignored. With `Vec<PathBuf>` for `P`, files dragged in from other
+/// applications are accepted instead, see [`file_drop`].
+pub fn drop_target<T, A, V, P>(
+    child: V,
+    on_drop: impl Fn(&mut T, P, Point) -> A + Send + 'static,
+) -> DropTarget<T, A, V, P> {
+    DropTarget {
+        child,
+        on_drop: Box::new(on_drop),
+        on_hover: None,
+    }
+}
+
+/// Calls `on_drop` with the paths and position in the child when files from another
+/// application are dropped on `child`.
+pub fn file_drop<T, A, V>(
+    child: V,
+    on_drop: impl Fn(&mut T, Vec<PathBuf>, Point) -> A + Send + 'static,
+) -> DropTarget<T, A, V, Vec<PathBuf>> {
+    drop_target(child, on_drop)
+}
+
+impl<T, A, V, P> DropTarget<T, A, V, P> {
+    /// Calls `f` with `true` when an accepted drag moves onto the child and with `false` when
+    /// it leaves or is dropped, for highlighting the target.
+    pub fn on_hover(mut self, f: impl Fn(&mut T, bool) -> A + Send + 'static) -> Self {
+        self.on_hover = Some(Box::new(f));
+        self
+    }
+}
+
+impl<T, A, V, P> ViewMarker for DropTarget<T, A, V, P> {}
+
+impl<T, A, V: View<T, A>, P: Send + 'static> View<T, A> for DropTarget<T, A, V, P> {
+    type State = (Id, V::State);
+
+    type Element = widget::DropTarget;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let accepts = TypeId::of::<P>();
+            let element = widget::DropTarget::new(cx.id_path(), Pod::new(child), accepts);
+            ((child_id, child_state), element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let (child_id, child_state) = state;
+        cx.with_id(*id, |cx| {
+            let child = element.child_mut();
+            let changes =
+                self.child
+                    .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+            child.mark(changes)
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let (child_id, child_state) = state;
+        match id_path.split_first() {
+            Some((first, rest)) if first == child_id => {
+                self.child.message(rest, child_state, message, app_state)
+            }
+            Some(_) => MessageResult::Stale(message),
+            None => match message.downcast::<DropMessage>() {
+                Ok(drop) => match drop {
+                    DropMessage::Hover(is_hovered) => match &self.on_hover {
+                        Some(f) => MessageResult::Action(f(app_state, is_hovered)),
+                        None => MessageResult::Nop,
+                    },
+                    DropMessage::Dropped(payload, pos) => match payload.downcast::<P>() {
+                        Ok(payload) => {
+                            MessageResult::Action((self.on_drop)(app_state, *payload, pos))
+                        }
+                        Err(_) => MessageResult::Nop,
+                    },
+                },
+                Err(message) => MessageResult::Stale(message),
+            },
+        }
+    }
+}
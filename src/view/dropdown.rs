@@ -0,0 +1,166 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A control for choosing one of several options, see [`dropdown`].
+pub struct Dropdown<T, A, V> {
+    options: Vec<V>,
+    selected: usize,
+    // consider not boxing
+    callback: Box<dyn Fn(&mut T, usize) -> A + Send>,
+}
+
+/// The state of a [`Dropdown`] view.
+pub struct DropdownState<T, A, V: View<T, A>> {
+    /// The view of the selected option shown in the closed control.
+    display: (Id, V::State),
+    options: Vec<(Id, V::State)>,
+}
+
+/// Creates a dropdown showing the option at `selected`, which opens a list of all options.
+///
+/// The options are usually strings. Their text is used to find options by typing and for
+/// accessibility. The callback receives the index of the option the user chose. `options`
+/// must not be empty.
+pub fn dropdown<T, A, V: View<T, A> + ToString>(
+    options: Vec<V>,
+    selected: usize,
+    on_select: impl Fn(&mut T, usize) -> A + Send + 'static,
+) -> Dropdown<T, A, V> {
+    Dropdown {
+        options,
+        selected,
+        callback: Box::new(on_select),
+    }
+}
+
+impl<T, A, V> Dropdown<T, A, V> {
+    fn selected(&self) -> usize {
+        assert!(!self.options.is_empty(), "a dropdown needs options");
+        self.selected.min(self.options.len() - 1)
+    }
+}
+
+impl<T, A, V> ViewMarker for Dropdown<T, A, V> {}
+
+impl<T, A, V: View<T, A> + ToString> View<T, A> for Dropdown<T, A, V> {
+    type State = DropdownState<T, A, V>;
+
+    type Element = widget::Dropdown;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let selected = self.selected();
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (display_id, display_state, display) = self.options[selected].build(cx);
+            let mut options = Vec::with_capacity(self.options.len());
+            let mut elements = Vec::with_capacity(self.options.len());
+            for option in &self.options {
+                let (id, state, element) = option.build(cx);
+                options.push((id, state));
+                elements.push((Pod::new(element), option.to_string()));
+            }
+            let state = DropdownState {
+                display: (display_id, display_state),
+                options,
+            };
+            let element =
+                widget::Dropdown::new(cx.id_path(), Pod::new(display), elements, selected);
+            (state, element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let selected = self.selected();
+        cx.with_id(*id, |cx| {
+            let (display_id, display_state) = &mut state.display;
+            let display = element.display_mut();
+            let changes = self.options[selected].rebuild(
+                cx,
+                &prev.options[prev.selected()],
+                display_id,
+                display_state,
+                display.expect_mut(),
+            );
+            let mut changes = display.mark(changes);
+
+            let common = self.options.len().min(prev.options.len());
+            for (index, (option_id, option_state)) in state.options[..common].iter_mut().enumerate()
+            {
+                let option = &self.options[index];
+                changes |= element.update_option(index, |pod| {
+                    option.rebuild(
+                        cx,
+                        &prev.options[index],
+                        option_id,
+                        option_state,
+                        pod.expect_mut(),
+                    )
+                });
+                changes |= element.set_label(index, option.to_string());
+            }
+            state.options.truncate(common);
+            changes |= element.truncate_options(common);
+            for option in &self.options[common..] {
+                let (id, option_state, option_element) = option.build(cx);
+                state.options.push((id, option_state));
+                changes |= element.push_option(Pod::new(option_element), option.to_string());
+            }
+
+            changes | element.set_selected(selected)
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path.split_first() {
+            None => match message.downcast::<usize>() {
+                Ok(index) => MessageResult::Action((self.callback)(app_state, index)),
+                Err(message) => MessageResult::Stale(message),
+            },
+            Some((first, rest)) if *first == state.display.0 => self.options[self.selected()]
+                .message(rest, &mut state.display.1, message, app_state),
+            Some((first, rest)) => {
+                let option = state
+                    .options
+                    .iter_mut()
+                    .zip(&self.options)
+                    .find(|((id, _), _)| id == first);
+                match option {
+                    Some(((_, option_state), option)) => {
+                        option.message(rest, option_state, message, app_state)
+                    }
+                    None => MessageResult::Stale(message),
+                }
+            }
+        }
+    }
+}
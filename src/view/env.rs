@@ -0,0 +1,307 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+use crate::env::{same_value, RawValue};
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Pod};
+use crate::{Env, Key, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Sets a value in the environment of a child view, see [`with_env`].
+pub struct WithEnv<V, E> {
+    key: Key<E>,
+    value: E,
+    child: V,
+}
+
+/// Sets `key` to `value` for `child` and everything below it.
+///
+/// Views below read the value with [`env_reader`], widgets below from the `env()` of their
+/// contexts. This is the same as [`ViewExt::with_env`](super::ViewExt::with_env).
+pub fn with_env<V, E>(key: Key<E>, value: E, child: V) -> WithEnv<V, E> {
+    WithEnv { key, value, child }
+}
+
+impl<V, E> ViewMarker for WithEnv<V, E> {}
+
+impl<T, A, V, E> View<T, A> for WithEnv<V, E>
+where
+    V: View<T, A>,
+    E: Clone + PartialEq + Send + Sync + 'static,
+{
+    type State = (Id, V::State);
+
+    type Element = widget::EnvScope;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            cx.with_env(self.key, self.value.clone(), |cx| {
+                let (child_id, child_state, child) = self.child.build(cx);
+                let element = widget::EnvScope::new(Pod::new(child), cx.env().clone());
+                ((child_id, child_state), element)
+            })
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let (child_id, child_state) = state;
+        cx.with_id(*id, |cx| {
+            cx.with_env(self.key, self.value.clone(), |cx| {
+                let child = element.child_mut();
+                let changes =
+                    self.child
+                        .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+                child.mark(changes) | element.set_env(cx.env().clone())
+            })
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let (child_id, child_state) = state;
+        match id_path.split_first() {
+            Some((first, rest)) if first == child_id => {
+                self.child.message(rest, child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+/// The environment as seen by the function of an [`env_reader`], which remembers the keys read.
+pub struct EnvReads<'a> {
+    env: &'a Env,
+    reads: RefCell<Vec<(&'static str, RawValue)>>,
+}
+
+impl<'a> EnvReads<'a> {
+    /// The value for `key`, `None` if no ancestor set it.
+    pub fn get<V: 'static>(&self, key: Key<V>) -> Option<&'a V> {
+        let mut reads = self.reads.borrow_mut();
+        if reads.iter().all(|(name, _)| *name != key.name()) {
+            reads.push((key.name(), self.env.raw(key.name())));
+        }
+        self.env.get(key)
+    }
+}
+
+/// Builds a view from the environment, see [`env_reader`].
+pub struct EnvReader<D, F, V> {
+    data: D,
+    f: F,
+    phantom: PhantomData<fn() -> V>,
+}
+
+pub struct EnvReaderState<V, S> {
+    view: V,
+    state: S,
+    reads: Vec<(&'static str, RawValue)>,
+    dirty: bool,
+}
+
+/// Builds a view with `f` from `data` and the values in the environment.
+///
+/// Like [`memoize`](super::memoize), `f` runs again only when `data` changed, or when one of
+/// the values it read from the environment changed. Otherwise the view built before is kept,
+/// though views below it still see changes of the environment.
+pub fn env_reader<D, F, V>(data: D, f: F) -> EnvReader<D, F, V>
+where
+    F: Fn(&EnvReads, &D) -> V,
+{
+    EnvReader {
+        data,
+        f,
+        phantom: PhantomData,
+    }
+}
+
+impl<D, F, V> EnvReader<D, F, V>
+where
+    F: Fn(&EnvReads, &D) -> V,
+{
+    fn read(&self, cx: &Cx) -> (V, Vec<(&'static str, RawValue)>) {
+        let env = EnvReads {
+            env: cx.env(),
+            reads: RefCell::new(vec![]),
+        };
+        let view = (self.f)(&env, &self.data);
+        (view, env.reads.into_inner())
+    }
+}
+
+impl<D, F, V> ViewMarker for EnvReader<D, F, V> {}
+
+impl<T, A, D, F, V> View<T, A> for EnvReader<D, F, V>
+where
+    D: PartialEq + Send,
+    F: Fn(&EnvReads, &D) -> V + Send,
+    V: View<T, A>,
+{
+    type State = EnvReaderState<V, V::State>;
+
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (view, reads) = self.read(cx);
+        let (id, state, element) = view.build(cx);
+        let state = EnvReaderState {
+            view,
+            state,
+            reads,
+            dirty: false,
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let env_changed = state
+            .reads
+            .iter()
+            .any(|(name, value)| !same_value(&cx.env().raw(name), value));
+        if std::mem::take(&mut state.dirty) || env_changed || prev.data != self.data {
+            let (view, reads) = self.read(cx);
+            let changes = view.rebuild(cx, &state.view, id, &mut state.state, element);
+            state.view = view;
+            state.reads = reads;
+            changes
+        } else {
+            // Views below may read values which changed.
+            state
+                .view
+                .rebuild(cx, &state.view, id, &mut state.state, element)
+        }
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let result = state
+            .view
+            .message(id_path, &mut state.state, message, app_state);
+        if matches!(result, MessageResult::RequestRebuild) {
+            state.dirty = true;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::view::v_stack;
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    const ACCENT: Key<u32> = Key::new("test.accent");
+    const SPACING: Key<u32> = Key::new("test.spacing");
+
+    type Reads = Arc<Mutex<Vec<(&'static str, Option<u32>)>>>;
+
+    /// Shows the value of `key` and records each time it is read.
+    fn reader(key: Key<u32>, reads: &Reads) -> impl View<()> + ViewMarker {
+        let reads = reads.clone();
+        env_reader((), move |env, _| {
+            let value = env.get(key).copied();
+            reads.lock().unwrap().push((key.name(), value));
+            format!("{value:?}")
+        })
+    }
+
+    #[test]
+    fn override_applies_to_its_subtree_only() {
+        let mut cx = view_cx();
+        let reads = Reads::default();
+        let view = with_env(
+            ACCENT,
+            1,
+            v_stack((
+                reader(ACCENT, &reads),
+                with_env(ACCENT, 2, reader(ACCENT, &reads)),
+                reader(SPACING, &reads),
+            )),
+        );
+
+        let _ = View::<()>::build(&view, &mut cx);
+        assert_eq!(
+            *reads.lock().unwrap(),
+            [
+                ("test.accent", Some(1)),
+                ("test.accent", Some(2)),
+                ("test.spacing", None)
+            ]
+        );
+    }
+
+    #[test]
+    fn changed_key_rebuilds_its_readers_only() {
+        let mut cx = view_cx();
+        let reads = Reads::default();
+        let view = |accent: u32| {
+            with_env(
+                ACCENT,
+                accent,
+                with_env(
+                    SPACING,
+                    8,
+                    v_stack((reader(ACCENT, &reads), reader(SPACING, &reads))),
+                ),
+            )
+        };
+
+        let mut prev = view(1);
+        let (mut id, mut state, mut element) = View::<()>::build(&prev, &mut cx);
+        reads.lock().unwrap().clear();
+
+        let next = view(1);
+        let changes = View::<()>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+        assert_eq!(changes, ChangeFlags::empty());
+        assert!(reads.lock().unwrap().is_empty());
+        prev = next;
+
+        let next = view(2);
+        let changes = View::<()>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+        assert!(changes.contains(ChangeFlags::DESCENDANT_REQUESTED_PAINT));
+        assert_eq!(*reads.lock().unwrap(), [("test.accent", Some(2))]);
+    }
+}
@@ -0,0 +1,386 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Shows a fallback view when a child view panics, see [`error_boundary`].
+pub struct ErrorBoundary<V, F> {
+    child: V,
+    fallback: F,
+}
+
+/// The action of a fallback view which builds the child of its [`error_boundary`] again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Retry;
+
+/// What is known about a panic caught by an [`error_boundary`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PanicInfoLite {
+    message: String,
+}
+
+impl PanicInfoLite {
+    fn new(payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&'static str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "Box<dyn Any>".into(),
+            },
+        };
+        PanicInfoLite { message }
+    }
+
+    /// The message the code panicked with.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+pub struct ErrorBoundaryState<S, FV, FS> {
+    /// The child, `None` while the boundary is tripped.
+    child: Option<(Id, S)>,
+    fallback: Option<Fallback<FV, FS>>,
+    needs_retry: bool,
+}
+
+struct Fallback<FV, FS> {
+    id: Id,
+    view: FV,
+    state: FS,
+    panic: PanicInfoLite,
+}
+
+/// Shows `child`, or the view built by `fallback` if building or rebuilding `child` panicked.
+///
+/// On a panic, the state and widgets of `child` are dropped, as they may be half updated, and
+/// the boundary trips: messages for `child` are stale, and the fallback is shown with the panic
+/// message. When the fallback returns [`Retry`], `child` is built from scratch in the next
+/// rebuild, which trips the boundary again if it still panics.
+///
+/// Only panics in `build` and `rebuild` are caught, not those in message handling or in
+/// widgets. The state of `child` is dropped but not inspected after a panic, so it doesn't have
+/// to be unwind safe itself, but data shared with other views, for example behind an `Arc`,
+/// may be left half updated. The panic is still reported by the panic hook.
+pub fn error_boundary<V, F, FV>(child: V, fallback: F) -> ErrorBoundary<V, F>
+where
+    F: Fn(&PanicInfoLite) -> FV,
+{
+    ErrorBoundary { child, fallback }
+}
+
+impl<V, F> ErrorBoundary<V, F> {
+    fn build_fallback<T, FV>(
+        &self,
+        cx: &mut Cx,
+        panic: PanicInfoLite,
+    ) -> (Fallback<FV, FV::State>, Pod)
+    where
+        F: Fn(&PanicInfoLite) -> FV,
+        FV: View<T, Retry>,
+        FV::Element: 'static,
+    {
+        let view = (self.fallback)(&panic);
+        let (id, state, element) = view.build(cx);
+        let fallback = Fallback {
+            id,
+            view,
+            state,
+            panic,
+        };
+        (fallback, Pod::new(element))
+    }
+}
+
+impl<V, F> ViewMarker for ErrorBoundary<V, F> {}
+
+impl<T, A, V, F, FV> View<T, A> for ErrorBoundary<V, F>
+where
+    V: View<T, A>,
+    V::Element: 'static,
+    F: Fn(&PanicInfoLite) -> FV + Send,
+    FV: View<T, Retry> + Send,
+    FV::Element: 'static,
+{
+    type State = ErrorBoundaryState<V::State, FV, FV::State>;
+
+    type Element = widget::OneOf;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) =
+            cx.with_new_id(|cx| match cx.catch_unwind(|cx| self.child.build(cx)) {
+                Ok((child_id, child_state, child)) => {
+                    let state = ErrorBoundaryState {
+                        child: Some((child_id, child_state)),
+                        fallback: None,
+                        needs_retry: false,
+                    };
+                    (state, widget::OneOf::new(0, Pod::new(child)))
+                }
+                Err(payload) => {
+                    let (fallback, child) = self.build_fallback(cx, PanicInfoLite::new(payload));
+                    let state = ErrorBoundaryState {
+                        child: None,
+                        fallback: Some(fallback),
+                        needs_retry: false,
+                    };
+                    (state, widget::OneOf::new(1, child))
+                }
+            });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let result = if std::mem::take(&mut state.needs_retry) {
+                cx.catch_unwind(|cx| self.child.build(cx))
+                    .map(|(child_id, child_state, child)| {
+                        state.child = Some((child_id, child_state));
+                        state.fallback = None;
+                        element.show(0, Pod::new(child), false)
+                    })
+            } else if let Some((child_id, child_state)) = &mut state.child {
+                cx.catch_unwind(|cx| {
+                    let pod = element.child_mut();
+                    let changes = self.child.rebuild(
+                        cx,
+                        &prev.child,
+                        child_id,
+                        child_state,
+                        pod.expect_mut(),
+                    );
+                    pod.mark(changes)
+                })
+            } else {
+                let fallback = state.fallback.as_mut().unwrap();
+                let view = (self.fallback)(&fallback.panic);
+                let pod = element.child_mut();
+                let changes = view.rebuild(
+                    cx,
+                    &fallback.view,
+                    &mut fallback.id,
+                    &mut fallback.state,
+                    pod.expect_mut(),
+                );
+                fallback.view = view;
+                Ok(pod.mark(changes))
+            };
+            match result {
+                Ok(changes) => changes,
+                Err(payload) => {
+                    state.child = None;
+                    let (fallback, child) = self.build_fallback(cx, PanicInfoLite::new(payload));
+                    state.fallback = Some(fallback);
+                    element.show(1, child, false)
+                }
+            }
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let Some((first, rest)) = id_path.split_first() else {
+            return MessageResult::Stale(message);
+        };
+        if let Some((child_id, child_state)) = &mut state.child {
+            if first == child_id {
+                return self.child.message(rest, child_state, message, app_state);
+            }
+        }
+        match &mut state.fallback {
+            Some(fallback) if *first == fallback.id => {
+                match fallback
+                    .view
+                    .message(rest, &mut fallback.state, message, app_state)
+                {
+                    MessageResult::Action(Retry) => {
+                        state.needs_retry = true;
+                        MessageResult::RequestRebuild
+                    }
+                    MessageResult::Actions(retries) => {
+                        state.needs_retry |= !retries.is_empty();
+                        MessageResult::RequestRebuild
+                    }
+                    MessageResult::RequestRebuild => MessageResult::RequestRebuild,
+                    MessageResult::Nop => MessageResult::Nop,
+                    MessageResult::Stale(message) => MessageResult::Stale(message),
+                }
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    /// A view which panics in rebuild while `fails` is set, and ignores messages.
+    struct Fragile {
+        fails: bool,
+    }
+
+    impl ViewMarker for Fragile {}
+
+    impl View<()> for Fragile {
+        type State = ();
+
+        type Element = widget::Empty;
+
+        fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+            let (id, element) = cx.with_new_id(|_| widget::Empty);
+            (id, (), element)
+        }
+
+        fn rebuild(
+            &self,
+            _cx: &mut Cx,
+            _prev: &Self,
+            _id: &mut Id,
+            _state: &mut Self::State,
+            _element: &mut Self::Element,
+        ) -> ChangeFlags {
+            assert!(!self.fails, "fragile view broke");
+            ChangeFlags::empty()
+        }
+
+        fn message(
+            &self,
+            _id_path: &[Id],
+            _state: &mut Self::State,
+            _message: Message,
+            _app_state: &mut (),
+        ) -> MessageResult<()> {
+            MessageResult::Nop
+        }
+    }
+
+    /// A fallback view which asks for a retry on every message.
+    struct RetryButton;
+
+    impl ViewMarker for RetryButton {}
+
+    impl View<(), Retry> for RetryButton {
+        type State = ();
+
+        type Element = widget::Empty;
+
+        fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+            let (id, element) = cx.with_new_id(|_| widget::Empty);
+            (id, (), element)
+        }
+
+        fn rebuild(
+            &self,
+            _cx: &mut Cx,
+            _prev: &Self,
+            _id: &mut Id,
+            _state: &mut Self::State,
+            _element: &mut Self::Element,
+        ) -> ChangeFlags {
+            ChangeFlags::empty()
+        }
+
+        fn message(
+            &self,
+            _id_path: &[Id],
+            _state: &mut Self::State,
+            _message: Message,
+            _app_state: &mut (),
+        ) -> MessageResult<Retry> {
+            MessageResult::Action(Retry)
+        }
+    }
+
+    #[test]
+    fn panic_trips_the_boundary_and_retry_recovers() {
+        let mut cx = view_cx();
+        let view = |fails: bool| {
+            error_boundary(Fragile { fails }, |panic: &PanicInfoLite| {
+                assert_eq!(panic.message(), "fragile view broke");
+                RetryButton
+            })
+        };
+
+        let mut prev = view(false);
+        let (mut id, mut state, mut element) = View::<()>::build(&prev, &mut cx);
+        let child_id = state.child.as_ref().unwrap().0;
+        for fails in [false, false, true] {
+            let next = view(fails);
+            let _ = View::<()>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+            prev = next;
+        }
+        assert!(cx.is_empty());
+        assert_eq!(element.index(), 1);
+        let result = View::<()>::message(
+            &prev,
+            &[child_id],
+            &mut state,
+            Message::new(vec![], ()),
+            &mut (),
+        );
+        assert!(matches!(result, MessageResult::Stale(_)));
+
+        // Rebuilding a tripped boundary doesn't touch the child.
+        let next = view(true);
+        let _ = View::<()>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+        prev = next;
+        assert_eq!(element.index(), 1);
+
+        let fallback_id = state.fallback.as_ref().unwrap().id;
+        let result = View::<()>::message(
+            &prev,
+            &[fallback_id],
+            &mut state,
+            Message::new(vec![], ()),
+            &mut (),
+        );
+        assert!(matches!(result, MessageResult::RequestRebuild));
+        let next = view(false);
+        let changes = View::<()>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+        assert!(changes.contains(ChangeFlags::tree_structure()));
+        assert_eq!(element.index(), 0);
+        assert!(state.fallback.is_none());
+        let new_child_id = state.child.as_ref().unwrap().0;
+        assert_ne!(new_child_id, child_id);
+        let result = View::<()>::message(
+            &next,
+            &[new_child_id],
+            &mut state,
+            Message::new(vec![], ()),
+            &mut (),
+        );
+        assert!(matches!(result, MessageResult::Nop));
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Controls keyboard focus for a child view, see [`focus_scope`] and
+/// [`ViewExt::auto_focus`](super::ViewExt::auto_focus).
+pub struct FocusScope<V> {
+    child: V,
+    contain: bool,
+    auto_focus: bool,
+}
+
+/// Keeps Tab and Shift+Tab cycling inside `child` once keyboard focus is in it, as for a dialog.
+pub fn focus_scope<V>(child: V) -> FocusScope<V> {
+    FocusScope {
+        child,
+        contain: true,
+        auto_focus: false,
+    }
+}
+
+impl<V> FocusScope<V> {
+    /// Focuses the first focusable widget of the child when it is first shown.
+    pub fn auto_focus(mut self) -> Self {
+        self.auto_focus = true;
+        self
+    }
+
+    pub(crate) fn new_auto_focus(child: V) -> Self {
+        FocusScope {
+            child,
+            contain: false,
+            auto_focus: true,
+        }
+    }
+}
+
+impl<V> ViewMarker for FocusScope<V> {}
+
+impl<T, A, V: View<T, A>> View<T, A> for FocusScope<V> {
+    type State = (Id, V::State);
+
+    type Element = widget::FocusScope;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let mut element = widget::FocusScope::new(Pod::new(child), self.contain);
+            if self.auto_focus {
+                element.focus_first();
+            }
+            ((child_id, child_state), element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let (child_id, child_state) = state;
+        if self.contain != prev.contain {
+            element.set_contain(self.contain);
+        }
+        cx.with_id(*id, |cx| {
+            let child = element.child_mut();
+            let changes =
+                self.child
+                    .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+            child.mark(changes)
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let (child_id, child_state) = state;
+        match id_path.split_first() {
+            Some((first, rest)) if first == child_id => {
+                self.child.message(rest, child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use vello::kurbo::Point;
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, DragUpdate, GestureEvent, Gestures, PinchUpdate, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+type Callback<T, A, P> = Option<Box<dyn Fn(&mut T, P) -> A + Send>>;
+
+/// Recognizes gestures on a child view, see [`gesture`].
+pub struct Gesture<T, A, V> {
+    child: V,
+    on_click: Callback<T, A, Point>,
+    on_double_click: Callback<T, A, Point>,
+    on_long_press: Callback<T, A, Point>,
+    on_drag: Callback<T, A, DragUpdate>,
+    on_pinch: Callback<T, A, PinchUpdate>,
+    long_press_delay: Duration,
+}
+
+/// Recognizes clicks, double clicks, long presses, drags and pinches on `child`.
+///
+/// Only the gestures with a callback are recognized, each callback produces an action. A press
+/// which turned into a drag or a long press is no click. Positions are in the coordinates of the
+/// child.
+pub fn gesture<T, A, V>(child: V) -> Gesture<T, A, V> {
+    Gesture {
+        child,
+        on_click: None,
+        on_double_click: None,
+        on_long_press: None,
+        on_drag: None,
+        on_pinch: None,
+        long_press_delay: widget::Gesture::DEFAULT_LONG_PRESS_DELAY,
+    }
+}
+
+impl<T, A, V> Gesture<T, A, V> {
+    pub fn on_click(mut self, f: impl Fn(&mut T, Point) -> A + Send + 'static) -> Self {
+        self.on_click = Some(Box::new(f));
+        self
+    }
+
+    pub fn on_double_click(mut self, f: impl Fn(&mut T, Point) -> A + Send + 'static) -> Self {
+        self.on_double_click = Some(Box::new(f));
+        self
+    }
+
+    pub fn on_long_press(mut self, f: impl Fn(&mut T, Point) -> A + Send + 'static) -> Self {
+        self.on_long_press = Some(Box::new(f));
+        self
+    }
+
+    /// Calls `f` when a drag starts, for every move and when it ends.
+    pub fn on_drag(mut self, f: impl Fn(&mut T, DragUpdate) -> A + Send + 'static) -> Self {
+        self.on_drag = Some(Box::new(f));
+        self
+    }
+
+    /// Calls `f` when two fingers start to pinch, for every move and when one is lifted.
+    pub fn on_pinch(mut self, f: impl Fn(&mut T, PinchUpdate) -> A + Send + 'static) -> Self {
+        self.on_pinch = Some(Box::new(f));
+        self
+    }
+
+    /// Sets how long the pointer has to be held for a long press.
+    pub fn long_press_delay(mut self, delay: Duration) -> Self {
+        self.long_press_delay = delay;
+        self
+    }
+
+    fn gestures(&self) -> Gestures {
+        let mut gestures = Gestures::empty();
+        gestures.set(Gestures::CLICK, self.on_click.is_some());
+        gestures.set(Gestures::DOUBLE_CLICK, self.on_double_click.is_some());
+        gestures.set(Gestures::LONG_PRESS, self.on_long_press.is_some());
+        gestures.set(Gestures::DRAG, self.on_drag.is_some());
+        gestures.set(Gestures::PINCH, self.on_pinch.is_some());
+        gestures
+    }
+}
+
+impl<T, A, V> ViewMarker for Gesture<T, A, V> {}
+
+impl<T, A, V: View<T, A>> View<T, A> for Gesture<T, A, V> {
+    type State = (Id, V::State);
+
+    type Element = widget::Gesture;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let mut element = widget::Gesture::new(cx.id_path(), Pod::new(child), self.gestures());
+            element.set_long_press_delay(self.long_press_delay);
+            ((child_id, child_state), element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let (child_id, child_state) = state;
+        element.set_gestures(self.gestures());
+        if self.long_press_delay != prev.long_press_delay {
+            element.set_long_press_delay(self.long_press_delay);
+        }
+        cx.with_id(*id, |cx| {
+            let child = element.child_mut();
+            let changes =
+                self.child
+                    .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+            child.mark(changes)
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let (child_id, child_state) = state;
+        match id_path.split_first() {
+            Some((first, rest)) if first == child_id => {
+                self.child.message(rest, child_state, message, app_state)
+            }
+            Some(_) => MessageResult::Stale(message),
+            None => match message.downcast::<GestureEvent>() {
+                Ok(event) => {
+                    let action = match event {
+                        GestureEvent::Click(pos) => {
+                            self.on_click.as_ref().map(|f| f(app_state, pos))
+                        }
+                        GestureEvent::DoubleClick(pos) => {
+                            self.on_double_click.as_ref().map(|f| f(app_state, pos))
+                        }
+                        GestureEvent::LongPress(pos) => {
+                            self.on_long_press.as_ref().map(|f| f(app_state, pos))
+                        }
+                        GestureEvent::Drag(update) => {
+                            self.on_drag.as_ref().map(|f| f(app_state, update))
+                        }
+                        GestureEvent::Pinch(update) => {
+                            self.on_pinch.as_ref().map(|f| f(app_state, update))
+                        }
+                    };
+                    action.map_or(MessageResult::Nop, MessageResult::Action)
+                }
+                Err(message) => MessageResult::Stale(message),
+            },
+        }
+    }
+}
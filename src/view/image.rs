@@ -0,0 +1,157 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use crate::view::ViewMarker;
+use crate::widget::{Fit, UnitPoint};
+use crate::ImageCache;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Where an [`image`] view gets its pixels from.
+///
+/// The token identifies the image in the [`ImageCache`], the decode function only runs when no
+/// widget shows an image with the same token yet.
+#[derive(Clone)]
+pub struct ImageSource {
+    token: Arc<str>,
+    decode: Arc<dyn Fn() -> vello::peniko::Image + Send + Sync>,
+}
+
+impl ImageSource {
+    pub fn new(
+        token: impl Into<Arc<str>>,
+        decode: impl Fn() -> vello::peniko::Image + Send + Sync + 'static,
+    ) -> Self {
+        ImageSource {
+            token: token.into(),
+            decode: Arc::new(decode),
+        }
+    }
+
+    /// Decodes PNG or JPEG data, an image which fails to decode is shown as empty.
+    #[cfg(feature = "image")]
+    pub fn encoded(token: impl Into<Arc<str>>, bytes: impl Into<Arc<[u8]>>) -> Self {
+        let token = token.into();
+        let bytes = bytes.into();
+        let name = token.clone();
+        ImageSource::new(token, move || {
+            crate::decode_image(&bytes).unwrap_or_else(|err| {
+                tracing::warn!("failed to decode image {name}: {err}");
+                crate::rgba_image(0, 0, vec![])
+            })
+        })
+    }
+
+    fn load(&self) -> Arc<vello::peniko::Image> {
+        ImageCache::global().get_or_insert_with(&self.token, || (self.decode)())
+    }
+}
+
+/// Displays a raster image, see [`image`].
+pub struct Image {
+    source: ImageSource,
+    fit: Fit,
+    alignment: UnitPoint,
+}
+
+/// Creates an image view, by default contained and centered in its box.
+pub fn image(source: ImageSource) -> Image {
+    Image {
+        source,
+        fit: Fit::default(),
+        alignment: UnitPoint::CENTER,
+    }
+}
+
+impl Image {
+    pub fn fit(mut self, fit: Fit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    pub fn alignment(mut self, alignment: UnitPoint) -> Self {
+        self.alignment = alignment;
+        self
+    }
+}
+
+impl ViewMarker for Image {}
+
+impl<T, A> View<T, A> for Image {
+    type State = ();
+
+    type Element = crate::widget::Image;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, mut element) = cx.with_new_id(|_| crate::widget::Image::new(self.source.load()));
+        let _ = element.set_fit(self.fit) | element.set_alignment(self.alignment);
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changes = element.set_fit(self.fit) | element.set_alignment(self.alignment);
+        if self.source.token != prev.source.token {
+            changes |= element.set_image(self.source.load());
+        }
+        changes
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rgba_image;
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    #[test]
+    fn rebuild_only_paints_when_token_changes() {
+        let mut cx = view_cx();
+        let red = || ImageSource::new("view-test-red", || rgba_image(2, 1, vec![0xff; 8]));
+        let blue = ImageSource::new("view-test-blue", || rgba_image(2, 1, vec![0x80; 8]));
+
+        let view = image(red());
+        let (mut id, mut state, mut element) = <Image as View<()>>::build(&view, &mut cx);
+
+        let same = image(red());
+        let changes =
+            <Image as View<()>>::rebuild(&same, &mut cx, &view, &mut id, &mut state, &mut element);
+        assert_eq!(changes, ChangeFlags::empty());
+
+        let next = image(blue);
+        let changes =
+            <Image as View<()>>::rebuild(&next, &mut cx, &same, &mut id, &mut state, &mut element);
+        assert_eq!(changes, ChangeFlags::PAINT);
+    }
+}
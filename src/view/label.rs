@@ -0,0 +1,151 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vello::peniko::Color;
+
+use crate::view::ViewMarker;
+use crate::widget::TextAlignment;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Wrapping static text, see [`label`].
+pub struct Label {
+    text: String,
+    text_size: f32,
+    text_color: Color,
+    alignment: TextAlignment,
+    max_lines: Option<usize>,
+}
+
+/// Creates a label which wraps its text to the available width.
+pub fn label(text: impl Into<String>) -> Label {
+    Label {
+        text: text.into(),
+        text_size: 16.0,
+        text_color: Color::rgb8(0xf0, 0xf0, 0xea),
+        alignment: TextAlignment::default(),
+        max_lines: None,
+    }
+}
+
+impl Label {
+    pub fn text_size(mut self, text_size: f32) -> Self {
+        self.text_size = text_size;
+        self
+    }
+
+    pub fn text_color(mut self, text_color: Color) -> Self {
+        self.text_color = text_color;
+        self
+    }
+
+    pub fn alignment(mut self, alignment: TextAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Cuts the text off with an ellipsis when it needs more than `max_lines` lines.
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines);
+        self
+    }
+}
+
+impl ViewMarker for Label {}
+
+impl<T, A> View<T, A> for Label {
+    type State = ();
+
+    type Element = crate::widget::Label;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, mut element) = cx.with_new_id(|_| crate::widget::Label::new(self.text.clone()));
+        // The element has not been laid out yet, so the change flags are not needed.
+        let _ = element.set_text_size(self.text_size)
+            | element.set_text_color(self.text_color)
+            | element.set_alignment(self.alignment)
+            | element.set_max_lines(self.max_lines);
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        element.set_text(self.text.clone())
+            | element.set_text_size(self.text_size)
+            | element.set_text_color(self.text_color)
+            | element.set_alignment(self.alignment)
+            | element.set_max_lines(self.max_lines)
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::v_stack;
+    use crate::widget::test_util::{view_cx, Harness};
+    use crate::widget::{BoxConstraints, Pod};
+    use test_log::test;
+
+    #[test]
+    fn color_changes_only_repaint() {
+        let mut harness = Harness::new();
+        let mut cx = view_cx();
+        let bc = BoxConstraints::UNBOUNDED;
+        let view = v_stack((label("Hello"),));
+        let (mut id, mut state, element) = View::<(), ()>::build(&view, &mut cx);
+        let mut root = Pod::new(element);
+        harness.frame(&mut root, &bc);
+
+        let mut show = |next, prev, root: &mut Pod| {
+            let changes = View::<(), ()>::rebuild(
+                next,
+                &mut cx,
+                prev,
+                &mut id,
+                &mut state,
+                root.expect_mut(),
+            );
+            let _ = root.mark(changes);
+            harness.frame(root, &bc)
+        };
+        let recolored = v_stack((label("Hello").text_color(Color::RED),));
+        let passes = show(&recolored, &view, &mut root);
+        assert!(passes.contains(ChangeFlags::PAINT));
+        assert!(!passes.intersects(ChangeFlags::UPDATE | ChangeFlags::LAYOUT));
+
+        let renamed = v_stack((label("Hello, world").text_color(Color::RED),));
+        let passes = show(&renamed, &recolored, &mut root);
+        assert!(passes.contains(ChangeFlags::LAYOUT | ChangeFlags::PAINT));
+
+        // Nothing changed, so nothing runs.
+        assert!(show(&renamed, &renamed, &mut root).is_empty());
+    }
+}
@@ -78,7 +78,7 @@ where
                     &mut state.child_state,
                 ) {
                     let child_pod = element.child_mut().as_mut().unwrap();
-                    let child_element = child_pod.downcast_mut().unwrap();
+                    let child_element = child_pod.expect_mut();
                     let changed = view.rebuild(cx, prev_view, id, child_state, child_element);
                     state.child_view = Some(view);
                     if changed {
@@ -0,0 +1,167 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::marker::PhantomData;
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A view built only once its widget is laid out, see [`lazy`].
+pub struct Lazy<F, V> {
+    f: F,
+    phantom: PhantomData<fn() -> V>,
+}
+
+pub struct LazyState<V, S> {
+    is_requested: bool,
+    child: Option<(Id, V, S)>,
+}
+
+/// Defers creating and building the view returned by `f` until its place is laid out.
+///
+/// Until then, a placeholder takes its place, so content in collapsed sections, inactive tabs
+/// and the like costs nothing. Once built, `f` runs on every rebuild and its view is diffed as
+/// usual. The app state is captured by `f` from where the view tree is created.
+pub fn lazy<F, V>(f: F) -> Lazy<F, V>
+where
+    F: Fn() -> V + Send,
+{
+    Lazy {
+        f,
+        phantom: PhantomData,
+    }
+}
+
+impl<F, V> ViewMarker for Lazy<F, V> {}
+
+impl<T, A, F, V> View<T, A> for Lazy<F, V>
+where
+    F: Fn() -> V + Send,
+    V: View<T, A>,
+    V::Element: 'static,
+{
+    type State = LazyState<V, V::State>;
+
+    type Element = widget::Lazy;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, element) = cx.with_new_id(|cx| widget::Lazy::new(cx.id_path()));
+        let state = LazyState {
+            is_requested: false,
+            child: None,
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        _prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| match &mut state.child {
+            Some((child_id, view, child_state)) => {
+                let next = (self.f)();
+                let child = element.child_mut().unwrap();
+                let changes = next.rebuild(cx, view, child_id, child_state, child.expect_mut());
+                *view = next;
+                child.mark(changes)
+            }
+            None if state.is_requested => {
+                let view = (self.f)();
+                let (child_id, child_state, child) = view.build(cx);
+                state.child = Some((child_id, view, child_state));
+                element.set_child(Pod::new(child))
+            }
+            None => ChangeFlags::empty(),
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match (id_path.split_first(), &mut state.child) {
+            (Some((first, rest_path)), Some((child_id, view, child_state)))
+                if *first == *child_id =>
+            {
+                view.message(rest_path, child_state, message, app_state)
+            }
+            (None, None) if message.is::<()>() => {
+                state.is_requested = true;
+                MessageResult::RequestRebuild
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::view::label;
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    fn counted(calls: &Arc<AtomicUsize>) -> impl View<(), Element = widget::Lazy> + ViewMarker {
+        let calls = calls.clone();
+        lazy(move || {
+            calls.fetch_add(1, Ordering::Relaxed);
+            label("expensive")
+        })
+    }
+
+    #[test]
+    fn view_is_created_once_requested() {
+        let mut cx = view_cx();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let mut prev = counted(&calls);
+        let (mut id, mut state, mut element) = prev.build(&mut cx);
+        for _ in 0..3 {
+            let next = counted(&calls);
+            next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+            prev = next;
+        }
+        assert_eq!(calls.load(Ordering::Relaxed), 0);
+        assert!(element.child_mut().is_none());
+
+        // The request of the placeholder after its first layout.
+        let result = prev.message(&[], &mut state, Message::new(vec![], ()), &mut ());
+        assert!(matches!(result, MessageResult::RequestRebuild));
+        let next = counted(&calls);
+        let changes = next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+        assert!(changes.contains(ChangeFlags::tree_structure()));
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        let child = element.child_mut().unwrap();
+        assert!(child.downcast_mut::<widget::Label>().is_some());
+
+        // From now on, the view is diffed like any child.
+        let last = counted(&calls);
+        let changes = last.rebuild(&mut cx, &next, &mut id, &mut state, &mut element);
+        assert!(!changes.contains(ChangeFlags::tree_structure()));
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+}
@@ -12,14 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{any::Any, marker::PhantomData};
+use std::marker::PhantomData;
 
 use crate::geometry::Axis;
-use crate::view::{Id, VecSplice, ViewMarker, ViewSequence};
-use crate::widget::{self, ChangeFlags};
-use crate::MessageResult;
+use crate::view::{Id, ViewMarker, ViewSequence};
+use crate::widget::{self, ChangeFlags, LinearMeta, Overflow, Pod};
+use crate::{Message, MessageResult};
 
-use super::{Cx, View};
+use super::{Cx, ElementSplice, View};
 
 /// LinearLayout is a simple view which does layout for the specified ViewSequence.
 ///
@@ -31,6 +31,8 @@ pub struct LinearLayout<T, A, VT: ViewSequence<T, A>> {
     children: VT,
     spacing: f64,
     axis: Axis,
+    reversed: bool,
+    overflow: Overflow,
     phantom: PhantomData<fn() -> (T, A)>,
 }
 
@@ -52,6 +54,8 @@ impl<T, A, VT: ViewSequence<T, A>> LinearLayout<T, A, VT> {
             phantom,
             spacing: 0.0,
             axis,
+            reversed: false,
+            overflow: Overflow::default(),
         }
     }
 
@@ -59,6 +63,18 @@ impl<T, A, VT: ViewSequence<T, A>> LinearLayout<T, A, VT> {
         self.spacing = spacing;
         self
     }
+
+    /// Places the children starting at the end of the axis, right to left or bottom to top.
+    pub fn reversed(mut self, reversed: bool) -> Self {
+        self.reversed = reversed;
+        self
+    }
+
+    /// Sets what happens when the children don't fit on the major axis.
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
 }
 
 impl<T, A, VT: ViewSequence<T, A>> ViewMarker for LinearLayout<T, A, VT> {}
@@ -71,7 +87,9 @@ impl<T, A, VT: ViewSequence<T, A>> View<T, A> for LinearLayout<T, A, VT> {
     fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
         let mut elements = vec![];
         let (id, state) = cx.with_new_id(|cx| self.children.build(cx, &mut elements));
-        let column = widget::LinearLayout::new(elements, self.spacing, self.axis);
+        let mut column = widget::LinearLayout::new(elements, self.spacing, self.axis);
+        column.reversed = self.reversed;
+        column.overflow = self.overflow;
         (id, state, column)
     }
 
@@ -83,30 +101,144 @@ impl<T, A, VT: ViewSequence<T, A>> View<T, A> for LinearLayout<T, A, VT> {
         state: &mut Self::State,
         element: &mut Self::Element,
     ) -> ChangeFlags {
-        let mut scratch = vec![];
-        let mut splice = VecSplice::new(&mut element.children, &mut scratch);
+        let mut splice = element.children.splice();
 
-        let mut flags = cx.with_id(*id, |cx| {
+        let flags = cx.with_id(*id, |cx| {
             self.children
                 .rebuild(cx, &prev.children, state, &mut splice)
         });
 
-        if self.spacing != prev.spacing || self.axis != prev.axis {
-            element.spacing = self.spacing;
-            element.axis = self.axis;
-            flags |= ChangeFlags::LAYOUT;
-        }
-
         flags
+            | element.set_spacing(self.spacing)
+            | element.set_axis(self.axis)
+            | element.set_reversed(self.reversed)
+            | element.set_overflow(self.overflow)
     }
 
     fn message(
         &self,
         id_path: &[Id],
         state: &mut Self::State,
-        event: Box<dyn Any>,
+        event: Message,
         app_state: &mut T,
     ) -> MessageResult<A> {
         self.children.message(id_path, state, event, app_state)
     }
 }
+
+/// A child of a [`LinearLayout`] with its own shrink factor, see [`LinearMeta::shrink`].
+///
+/// The factor stays with the child when the children before it change.
+pub struct Shrink<V> {
+    shrink: f64,
+    child: V,
+}
+
+/// Gives `child` the shrink factor `shrink` in a [`LinearLayout`], `0.0` makes it rigid.
+pub fn shrink<V>(shrink: f64, child: V) -> Shrink<V> {
+    Shrink { shrink, child }
+}
+
+impl<V> Shrink<V> {
+    fn meta(&self) -> LinearMeta {
+        LinearMeta {
+            shrink: self.shrink,
+        }
+    }
+}
+
+impl<T, A, V: View<T, A>> ViewSequence<T, A> for Shrink<V>
+where
+    V::Element: 'static,
+{
+    type State = (V::State, Id);
+
+    fn build(&self, cx: &mut Cx, elements: &mut Vec<Pod>) -> Self::State {
+        let (id, state, element) = self.child.build(cx);
+        let mut pod = Pod::new(element);
+        let _ = pod.set_linear_meta(self.meta());
+        elements.push(pod);
+        (state, id)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        state: &mut Self::State,
+        element: &mut dyn ElementSplice<Pod>,
+    ) -> ChangeFlags {
+        let pod = element.mutate();
+        let flags = self.child.rebuild(
+            cx,
+            &prev.child,
+            &mut state.1,
+            &mut state.0,
+            pod.expect_mut(),
+        );
+        pod.mark(flags) | pod.set_linear_meta(self.meta())
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path.split_first() {
+            Some((first, rest_path)) if *first == state.1 => {
+                self.child
+                    .message(rest_path, &mut state.0, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+
+    fn count(&self, _state: &Self::State) -> usize {
+        1
+    }
+
+    fn owns_id(&self, state: &Self::State, id: Id) -> bool {
+        id == state.1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::label;
+    use crate::widget::test_util::view_cx;
+
+    fn shrinks(row: &widget::LinearLayout) -> Vec<f64> {
+        row.children
+            .iter()
+            .map(|child| child.linear_meta().shrink)
+            .collect()
+    }
+
+    #[test]
+    fn shrink_factors_stay_with_their_children() {
+        let mut cx = view_cx();
+        let view = |leading: bool| {
+            h_stack((
+                leading.then(|| label("leading")),
+                shrink(0.0, label("rigid")),
+                label("shrinking"),
+            ))
+        };
+        let prev = view(false);
+        let (mut id, mut state, mut row) = prev.build(&mut cx);
+        assert_eq!(shrinks(&row), [0.0, 1.0]);
+
+        let next = view(true);
+        let flags = next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut row);
+        assert!(flags.contains(ChangeFlags::TREE));
+        assert_eq!(shrinks(&row), [1.0, 0.0, 1.0]);
+
+        let prev = next;
+        let next = h_stack((None, shrink(0.5, label("rigid")), label("shrinking")));
+        let _ = next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut row);
+        assert_eq!(shrinks(&row), [0.5, 1.0]);
+    }
+}
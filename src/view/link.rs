@@ -0,0 +1,78 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::ViewMarker;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Clickable text, see [`link`].
+pub struct Link<T, A> {
+    text: String,
+    // consider not boxing
+    callback: Box<dyn Fn(&mut T) -> A + Send>,
+}
+
+/// Creates text which looks like a link and works like a [`button`](super::button).
+///
+/// It takes part in the Tab order and is activated by clicking it or by Enter and Space while
+/// focused, each activation invokes the callback with the app state.
+pub fn link<T, A>(
+    text: impl Into<String>,
+    clicked: impl Fn(&mut T) -> A + Send + 'static,
+) -> Link<T, A> {
+    Link {
+        text: text.into(),
+        callback: Box::new(clicked),
+    }
+}
+
+impl<T, A> ViewMarker for Link<T, A> {}
+
+impl<T, A> View<T, A> for Link<T, A> {
+    type State = ();
+
+    type Element = crate::widget::Link;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, element) =
+            cx.with_new_id(|cx| crate::widget::Link::new(cx.id_path(), self.text.clone()));
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        element.set_text(self.text.clone())
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if id_path.is_empty() {
+            MessageResult::Action((self.callback)(app_state))
+        } else {
+            MessageResult::Stale(message)
+        }
+    }
+}
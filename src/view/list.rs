@@ -12,10 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::view::{Cx, VecSplice, ViewSequence};
+use crate::view::{Cx, ElementSplice, ViewSequence};
 use crate::widget::{ChangeFlags, Pod};
-use crate::MessageResult;
-use std::any::Any;
+use crate::{Message, MessageResult};
 use std::marker::PhantomData;
 
 /// A simple view sequence which builds a dynamic amount of sub sequences.
@@ -72,7 +71,7 @@ impl<T, A, VT: ViewSequence<T, A>, F: Fn(usize) -> VT + Send> ViewSequence<T, A>
         cx: &mut Cx,
         prev: &Self,
         state: &mut Self::State,
-        element: &mut VecSplice<Pod>,
+        element: &mut dyn ElementSplice<Pod>,
     ) -> ChangeFlags {
         // Common length
         let leading = element.len();
@@ -94,14 +93,16 @@ impl<T, A, VT: ViewSequence<T, A>, F: Fn(usize) -> VT + Send> ViewSequence<T, A>
 
         while self.items > state.views.len() {
             let vt = (self.build)(state.views.len());
-            let vt_state = element.as_vec(|vec| vt.build(cx, vec));
+            let mut elements = vec![];
+            let vt_state = vt.build(cx, &mut elements);
+            element.extend(elements);
             state.views.push((vt, vt_state));
         }
 
         // We only check if our length changes. If one of the sub sequences changes thier size they
-        // have to set ChangeFlags::all() them self's.
+        // have to set ChangeFlags::tree_structure() them self's.
         if self.items != prev.items {
-            flags |= ChangeFlags::all();
+            flags |= ChangeFlags::tree_structure();
         }
 
         state.element_count = element.len() - leading;
@@ -113,18 +114,30 @@ impl<T, A, VT: ViewSequence<T, A>, F: Fn(usize) -> VT + Send> ViewSequence<T, A>
         &self,
         id_path: &[xilem_core::Id],
         state: &mut Self::State,
-        message: Box<dyn Any>,
+        message: Message,
         app_state: &mut T,
     ) -> MessageResult<A> {
-        state
+        let Some(&head) = id_path.first() else {
+            return MessageResult::Stale(message);
+        };
+        match state
             .views
             .iter_mut()
-            .fold(MessageResult::Stale(message), |result, (vt, vt_state)| {
-                result.or(|message| vt.message(id_path, vt_state, message, app_state))
-            })
+            .find(|(vt, vt_state)| vt.owns_id(vt_state, head))
+        {
+            Some((vt, vt_state)) => vt.message(id_path, vt_state, message, app_state),
+            None => MessageResult::Stale(message),
+        }
     }
 
     fn count(&self, state: &Self::State) -> usize {
         state.element_count
     }
+
+    fn owns_id(&self, state: &Self::State, id: xilem_core::Id) -> bool {
+        state
+            .views
+            .iter()
+            .any(|(vt, vt_state)| vt.owns_id(vt_state, id))
+    }
 }
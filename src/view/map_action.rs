@@ -0,0 +1,298 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::marker::PhantomData;
+
+use crate::view::ViewMarker;
+use crate::widget::Pod;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, ElementSplice, View, ViewSequence};
+
+/// A view which translates the actions of its child, see [`map_action`].
+pub struct MapAction<T, A, B, V, F = fn(&mut T, A) -> B> {
+    child: V,
+    f: F,
+    phantom: PhantomData<fn() -> (T, A, B)>,
+}
+
+/// Translates the actions of `child` with `f`, which also gets the app state.
+///
+/// This lets a reusable component speak its own action type while the parent sees its own,
+/// results without an action pass through unchanged.
+pub fn map_action<T, A, B, V, F>(child: V, f: F) -> MapAction<T, A, B, V, F>
+where
+    V: View<T, A>,
+    F: Fn(&mut T, A) -> B + Send,
+{
+    MapAction {
+        child,
+        f,
+        phantom: PhantomData,
+    }
+}
+
+impl<T, A, B, V, F> ViewMarker for MapAction<T, A, B, V, F> {}
+
+impl<T, A, B, V, F> View<T, B> for MapAction<T, A, B, V, F>
+where
+    V: View<T, A>,
+    F: Fn(&mut T, A) -> B + Send,
+{
+    type State = V::State;
+
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        self.child.build(cx)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        self.child.rebuild(cx, &prev.child, id, state, element)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<B> {
+        self.child
+            .message(id_path, state, message, app_state)
+            .map(|action| (self.f)(app_state, action))
+    }
+}
+
+/// A view sequence which translates the actions of its children, see [`map_action_seq`].
+pub struct MapActionSeq<T, A, B, VT, F = fn(&mut T, A) -> B> {
+    children: VT,
+    f: F,
+    phantom: PhantomData<fn() -> (T, A, B)>,
+}
+
+/// Translates the actions of all views in `children` with `f`, like [`map_action`] does for a
+/// single view.
+pub fn map_action_seq<T, A, B, VT, F>(children: VT, f: F) -> MapActionSeq<T, A, B, VT, F>
+where
+    VT: ViewSequence<T, A>,
+    F: Fn(&mut T, A) -> B + Send,
+{
+    MapActionSeq {
+        children,
+        f,
+        phantom: PhantomData,
+    }
+}
+
+impl<T, A, B, VT, F> ViewSequence<T, B> for MapActionSeq<T, A, B, VT, F>
+where
+    VT: ViewSequence<T, A>,
+    F: Fn(&mut T, A) -> B + Send,
+{
+    type State = VT::State;
+
+    fn build(&self, cx: &mut Cx, elements: &mut Vec<Pod>) -> Self::State {
+        self.children.build(cx, elements)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        state: &mut Self::State,
+        element: &mut dyn ElementSplice<Pod>,
+    ) -> ChangeFlags {
+        self.children.rebuild(cx, &prev.children, state, element)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<B> {
+        self.children
+            .message(id_path, state, message, app_state)
+            .map(|action| (self.f)(app_state, action))
+    }
+
+    fn count(&self, state: &Self::State) -> usize {
+        self.children.count(state)
+    }
+
+    fn owns_id(&self, state: &Self::State, id: Id) -> bool {
+        self.children.owns_id(state, id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::textbox;
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    #[derive(Debug, PartialEq)]
+    enum PickerAction {
+        Picked(String),
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum SettingsAction {
+        Accent(String),
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum AppAction {
+        Settings(SettingsAction),
+    }
+
+    /// A color picker component wrapped into two layers of translation.
+    fn nested() -> impl View<Vec<&'static str>, AppAction> + ViewMarker {
+        let picker = textbox("", |log: &mut Vec<&str>, color| {
+            log.push("picker");
+            PickerAction::Picked(color)
+        });
+        let settings = map_action(
+            picker,
+            |log: &mut Vec<&str>, PickerAction::Picked(color)| {
+                log.push("settings");
+                SettingsAction::Accent(color)
+            },
+        );
+        map_action(settings, |log: &mut Vec<&str>, action| {
+            log.push("app");
+            AppAction::Settings(action)
+        })
+    }
+
+    #[test]
+    fn nested_layers_compose() {
+        let mut cx = view_cx();
+        let view = nested();
+        let (_, mut state, _) = view.build(&mut cx);
+        let mut log = vec![];
+
+        let message = Message::new(vec![], String::from("teal"));
+        let result = view.message(&[], &mut state, message, &mut log);
+        let MessageResult::Action(action) = result else {
+            panic!("expected an action");
+        };
+        assert_eq!(
+            action,
+            AppAction::Settings(SettingsAction::Accent("teal".into()))
+        );
+        assert_eq!(log, ["picker", "settings", "app"]);
+    }
+
+    #[test]
+    fn stale_is_not_converted() {
+        let mut cx = view_cx();
+        let view = nested();
+        let (_, mut state, _) = view.build(&mut cx);
+        let mut log = vec![];
+
+        let message = Message::new(vec![], String::from("teal"));
+        let result = view.message(&[Id::next()], &mut state, message, &mut log);
+        assert!(matches!(result, MessageResult::Stale(_)));
+        assert!(log.is_empty());
+
+        // The same holds for sequences.
+        let children = (nested(), nested());
+        let sequence = map_action_seq(children, |_: &mut Vec<&str>, action| action);
+        let mut elements = vec![];
+        let mut state = sequence.build(&mut cx, &mut elements);
+        assert_eq!(sequence.count(&state), 2);
+        let message = Message::new(vec![], String::from("teal"));
+        let result = sequence.message(&[Id::next()], &mut state, message, &mut log);
+        assert!(matches!(result, MessageResult::Stale(_)));
+        assert!(log.is_empty());
+    }
+
+    /// A view turning a message of several numbers into a batch of actions.
+    struct Batch;
+
+    impl ViewMarker for Batch {}
+
+    impl View<Vec<&'static str>, u32> for Batch {
+        type State = ();
+
+        type Element = crate::widget::Empty;
+
+        fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+            let (id, element) = cx.with_new_id(|_| crate::widget::Empty);
+            (id, (), element)
+        }
+
+        fn rebuild(
+            &self,
+            _cx: &mut Cx,
+            _prev: &Self,
+            _id: &mut Id,
+            _state: &mut Self::State,
+            _element: &mut Self::Element,
+        ) -> ChangeFlags {
+            ChangeFlags::empty()
+        }
+
+        fn message(
+            &self,
+            _id_path: &[Id],
+            _state: &mut Self::State,
+            message: Message,
+            _app_state: &mut Vec<&'static str>,
+        ) -> MessageResult<u32> {
+            MessageResult::from_actions(message.downcast::<Vec<u32>>().unwrap())
+        }
+    }
+
+    #[test]
+    fn batches_are_translated_in_order() {
+        let mut cx = view_cx();
+        let view = map_action(Batch, |log: &mut Vec<&str>, n| {
+            log.push("reduce");
+            n * 10
+        });
+        let (_, mut state, _) = view.build(&mut cx);
+        let mut log = vec![];
+
+        let result = view.message(
+            &[],
+            &mut state,
+            Message::new(vec![], vec![1, 2, 3]),
+            &mut log,
+        );
+        assert_eq!(log, ["reduce"; 3]);
+        // The whole batch asks for a single rebuild.
+        assert!(result.handled());
+        assert_eq!(result.into_actions(), [10, 20, 30]);
+
+        let result = view.message(
+            &[],
+            &mut state,
+            Message::new(vec![], Vec::<u32>::new()),
+            &mut log,
+        );
+        assert!(matches!(result, MessageResult::Nop));
+    }
+}
@@ -13,20 +13,123 @@
 // limitations under the License.
 
 // mod async_list;
+mod adapt;
+mod animate;
+mod async_view;
 mod button;
+mod canvas;
+mod checkbox;
+mod context_menu;
+mod disabled;
+mod drag_drop;
+mod dropdown;
+mod env;
+mod error_boundary;
+mod focus_scope;
+mod gesture;
+mod image;
+mod label;
+mod lazy;
 // mod layout_observer;
 // mod list;
+mod progress_bar;
+mod radio_group;
+mod rate_limit;
+mod rich_text;
+mod scoped;
+mod shortcuts;
+mod slider;
+mod spinner;
+mod stepper;
+mod subscribe;
+mod switch;
+mod tagged;
 // mod scroll_view;
-// mod text;
-// mod use_state;
 mod linear_layout;
+mod link;
 mod list;
+mod map_action;
+mod modal;
+mod mount;
+mod on_event;
+mod one_of;
+mod paste;
+mod profiled;
+mod text;
+mod text_area;
+mod text_box;
+mod theme;
+mod timer;
+mod toast;
+mod tooltip;
+mod use_state;
 #[allow(clippy::module_inception)]
 mod view;
+mod view_ext;
+mod virtual_list;
+mod window_events;
+mod wrapper;
 
-pub use xilem_core::{Id, IdPath, VecSplice};
+pub use xilem_core::{ElementSplice, Id, IdPath, VecSplice};
 
+pub use self::image::{image, Image, ImageSource};
+pub use adapt::{adapt, lens, Lens};
+pub use animate::{animate, Animate, AnimateState};
+pub use async_view::{async_view, AsyncView, AsyncViewState};
 pub use button::button;
-pub use linear_layout::{h_stack, v_stack, LinearLayout};
+pub use canvas::{canvas, Canvas};
+pub use checkbox::{checkbox, Checkbox};
+pub use context_menu::{ContextMenu, ContextMenuState, Menu, MenuItem};
+pub use disabled::{disabled, Disabled};
+pub use drag_drop::{drag_source, drop_target, file_drop, DragSource, DropTarget};
+pub use dropdown::{dropdown, Dropdown, DropdownState};
+pub use env::{env_reader, with_env, EnvReader, EnvReaderState, EnvReads, WithEnv};
+pub use error_boundary::{error_boundary, ErrorBoundary, ErrorBoundaryState, PanicInfoLite, Retry};
+pub use focus_scope::{focus_scope, FocusScope};
+pub use gesture::{gesture, Gesture};
+pub use label::{label, Label};
+pub use lazy::{lazy, Lazy, LazyState};
+pub use linear_layout::{h_stack, shrink, v_stack, LinearLayout, Shrink};
+pub use link::{link, Link};
 pub use list::{list, List};
-pub use view::{Adapt, AdaptState, Cx, Memoize, View, ViewMarker, ViewSequence};
+pub use map_action::{map_action, map_action_seq, MapAction, MapActionSeq};
+pub use modal::{modal, Modal, ModalState};
+pub use mount::{
+    on_mount, on_unmount, run_once, OnMount, OnMountState, OnUnmount, OnUnmountState, RunOnce,
+};
+pub use on_event::{on_click, on_event, on_hover, OnEvent};
+pub use one_of::{
+    one_of_a, one_of_b, OneOf2, OneOf2State, OneOf3, OneOf3State, OneOf4, OneOf4State, OneOf5,
+    OneOf5State, OneOf6, OneOf6State, OneOf7, OneOf7State, OneOf8, OneOf8State, RetainInactive,
+};
+pub use paste::{on_paste, OnPaste};
+pub use profiled::{profiled, Profiled, ProfiledState};
+pub use progress_bar::{progress, ProgressBar};
+pub use radio_group::{radio_group, RadioGroup, RadioGroupState};
+pub use rate_limit::{debounced, throttled, Debounced, RateLimitState, Throttled};
+pub use rich_text::{link_span, rich_text, span, RichText, Span};
+pub use scoped::{scoped, Scoped, ScopedState};
+pub use shortcuts::{on_key, shortcut_scope, Shortcuts};
+pub use slider::{slider, Slider};
+pub use spinner::{spinner, Spinner};
+pub use stepper::{stepper, Stepper};
+pub use subscribe::{subscribe, Subscribe, SubscribeState};
+pub use switch::{switch, Switch};
+pub use tagged::{tagged, Tagged};
+pub use text_area::{text_area, TextArea};
+pub use text_box::{textbox, TextBox};
+pub use theme::{themed, with_theme, Themed, ThemedState};
+pub use timer::{interval, timeout, Timer};
+pub use toast::{toasts, Toast, Toasts, ToastsState};
+pub use tooltip::{tooltip, Tooltip};
+pub use use_state::{use_state, Local, UseState, UseStateState};
+pub use view::{
+    memoize, Adapt, AdaptState, AdaptThunk, AnyView, BoxedView, Cx, Memoize, MessageSender, View,
+    ViewMarker, ViewSequence,
+};
+pub use view_ext::ViewExt;
+pub use virtual_list::{virtual_list, VirtualList, VirtualListState};
+pub use window_events::{
+    on_close_request, on_window_resize, CloseDecision, OnCloseRequest, OnWindowResize,
+};
+pub use wrapper::{SequenceWrapper, Wrapped, WrappedSequence, WrappedState, Wrapper};
@@ -0,0 +1,144 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, DismissRequest, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Shows a dialog above the whole window while it is open, see [`modal`].
+pub struct Modal<V, D, F> {
+    child: V,
+    open: bool,
+    dialog: D,
+    on_dismiss: F,
+}
+
+/// The state of a [`Modal`] view.
+pub struct ModalState<T, A, V: View<T, A>, D: View<T, A>> {
+    child: (Id, V::State),
+    /// The dialog while it is open.
+    dialog: Option<(Id, D::State)>,
+}
+
+/// Shows `dialog` on top of the whole window while `open` is true, blocking interaction with
+/// `child` and everything else below it.
+///
+/// Escape and presses outside of the dialog call `on_dismiss`, which usually clears the state
+/// `open` is derived from: the dialog only closes when `open` becomes false. Dialogs may contain
+/// modals themselves, which stack on top. This is the same as
+/// [`ViewExt::modal`](super::ViewExt::modal).
+pub fn modal<V, D, F>(child: V, open: bool, dialog: D, on_dismiss: F) -> Modal<V, D, F> {
+    Modal {
+        child,
+        open,
+        dialog,
+        on_dismiss,
+    }
+}
+
+impl<V, D, F> ViewMarker for Modal<V, D, F> {}
+
+impl<T, A, V: View<T, A>, D: View<T, A>, F: Fn(&mut T) -> A + Send> View<T, A> for Modal<V, D, F> {
+    type State = ModalState<T, A, V, D>;
+
+    type Element = widget::Modal;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let (dialog_state, dialog) = if self.open {
+                let (dialog_id, dialog_state, dialog) = self.dialog.build(cx);
+                (Some((dialog_id, dialog_state)), Some(Pod::new(dialog)))
+            } else {
+                (None, None)
+            };
+            let state = ModalState {
+                child: (child_id, child_state),
+                dialog: dialog_state,
+            };
+            let element = widget::Modal::new(cx.id_path(), Pod::new(child), dialog);
+            (state, element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let (child_id, child_state) = &mut state.child;
+            let child = element.child_mut();
+            let changes =
+                self.child
+                    .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+            let mut changes = child.mark(changes);
+            match (&mut state.dialog, self.open) {
+                (Some((dialog_id, dialog_state)), true) => {
+                    changes |= element.update_dialog(|dialog| {
+                        self.dialog.rebuild(
+                            cx,
+                            &prev.dialog,
+                            dialog_id,
+                            dialog_state,
+                            dialog.expect_mut(),
+                        )
+                    });
+                }
+                (None, true) => {
+                    let (dialog_id, dialog_state, dialog) = self.dialog.build(cx);
+                    state.dialog = Some((dialog_id, dialog_state));
+                    changes |= element.set_dialog(Some(Pod::new(dialog)));
+                }
+                (Some(_), false) => {
+                    state.dialog = None;
+                    changes |= element.set_dialog(None);
+                }
+                (None, false) => (),
+            }
+            changes
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path.split_first() {
+            None => match message.downcast::<DismissRequest>() {
+                Ok(_) => MessageResult::Action((self.on_dismiss)(app_state)),
+                Err(message) => MessageResult::Stale(message),
+            },
+            Some((first, rest)) if *first == state.child.0 => {
+                self.child
+                    .message(rest, &mut state.child.1, message, app_state)
+            }
+            Some((first, rest)) => match &mut state.dialog {
+                Some((dialog_id, dialog_state)) if first == dialog_id => {
+                    self.dialog.message(rest, dialog_state, message, app_state)
+                }
+                _ => MessageResult::Stale(message),
+            },
+        }
+    }
+}
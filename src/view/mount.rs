@@ -0,0 +1,397 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// An invisible view invoking a callback once, see [`run_once`].
+pub struct RunOnce<T, A> {
+    // consider not boxing
+    callback: Box<dyn Fn(&mut T) -> A + Send>,
+}
+
+/// Invokes a callback once when a child view appears, see [`on_mount`].
+pub struct OnMount<T, A, V> {
+    child: V,
+    // consider not boxing
+    callback: Box<dyn Fn(&mut T) -> A + Send>,
+}
+
+pub struct OnMountState<S> {
+    child_id: Id,
+    child_state: S,
+    is_done: bool,
+}
+
+/// Invokes a callback when a child view is removed, see [`on_unmount`].
+pub struct OnUnmount<V, F> {
+    child: V,
+    callback: F,
+}
+
+pub struct OnUnmountState<S, F: Fn()> {
+    child_state: S,
+    callback: F,
+}
+
+/// Invokes `callback` once, right after the view was added to the tree.
+///
+/// The callback runs with the app state in the message handling following the first update,
+/// its return value is the action of this view. Rebuilds don't invoke it again, but removing
+/// the view and adding it again does, as that creates a new instance.
+pub fn run_once<T, A>(callback: impl Fn(&mut T) -> A + Send + 'static) -> RunOnce<T, A> {
+    RunOnce {
+        callback: Box::new(callback),
+    }
+}
+
+/// Invokes `callback` once, right after `child` was added to the tree, like [`run_once`].
+pub fn on_mount<T, A, V: View<T, A>>(
+    child: V,
+    callback: impl Fn(&mut T) -> A + Send + 'static,
+) -> OnMount<T, A, V> {
+    OnMount {
+        child,
+        callback: Box::new(callback),
+    }
+}
+
+/// Invokes `callback` when `child` is removed from the tree, that is when its state is dropped.
+///
+/// Views have no access to the app state while they are removed, the callback has to capture
+/// what it needs, for example a channel. It is taken from the most recent view.
+pub fn on_unmount<V, F: Fn() + Clone + Send>(child: V, callback: F) -> OnUnmount<V, F> {
+    OnUnmount { child, callback }
+}
+
+impl<S, F: Fn()> Drop for OnUnmountState<S, F> {
+    fn drop(&mut self) {
+        (self.callback)();
+    }
+}
+
+impl<T, A> ViewMarker for RunOnce<T, A> {}
+
+impl<T, A> View<T, A> for RunOnce<T, A> {
+    type State = bool;
+
+    type Element = widget::Mount;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, element) = cx.with_new_id(|cx| widget::Mount::new(cx.id_path(), None));
+        (id, false, element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        _element: &mut Self::Element,
+    ) -> ChangeFlags {
+        ChangeFlags::empty()
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        is_done: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if !id_path.is_empty() || !message.is::<()>() {
+            MessageResult::Stale(message)
+        } else if std::mem::replace(is_done, true) {
+            MessageResult::Nop
+        } else {
+            MessageResult::Action((self.callback)(app_state))
+        }
+    }
+}
+
+impl<T, A, V> ViewMarker for OnMount<T, A, V> {}
+
+impl<T, A, V: View<T, A>> View<T, A> for OnMount<T, A, V>
+where
+    V::Element: 'static,
+{
+    type State = OnMountState<V::State>;
+
+    type Element = widget::Mount;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (child_id, child_state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let element = widget::Mount::new(cx.id_path(), Some(Pod::new(child)));
+            (child_id, child_state, element)
+        });
+        let state = OnMountState {
+            child_id,
+            child_state,
+            is_done: false,
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let child = element.child_mut().unwrap();
+            let changes = self.child.rebuild(
+                cx,
+                &prev.child,
+                &mut state.child_id,
+                &mut state.child_state,
+                child.expect_mut(),
+            );
+            child.mark(changes)
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path.split_first() {
+            Some((first, rest_path)) if *first == state.child_id => {
+                self.child
+                    .message(rest_path, &mut state.child_state, message, app_state)
+            }
+            None if message.is::<()>() => {
+                if std::mem::replace(&mut state.is_done, true) {
+                    MessageResult::Nop
+                } else {
+                    MessageResult::Action((self.callback)(app_state))
+                }
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+impl<V, F> ViewMarker for OnUnmount<V, F> {}
+
+impl<T, A, V, F> View<T, A> for OnUnmount<V, F>
+where
+    V: View<T, A>,
+    F: Fn() + Clone + Send,
+{
+    type State = OnUnmountState<V::State, F>;
+
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, child_state, element) = self.child.build(cx);
+        let state = OnUnmountState {
+            child_state,
+            callback: self.callback.clone(),
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        state.callback = self.callback.clone();
+        self.child
+            .rebuild(cx, &prev.child, id, &mut state.child_state, element)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.child
+            .message(id_path, &mut state.child_state, message, app_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::view::{label, one_of_a, one_of_b, Label, OneOf2, OneOf2State};
+    use crate::widget::test_util::{view_cx, Harness};
+    use test_log::test;
+
+    fn counting(count: &mut u32) -> u32 {
+        *count += 1;
+        *count
+    }
+
+    #[test]
+    fn callback_runs_once_across_rebuilds() {
+        let mut cx = view_cx();
+
+        let mut prev = run_once(counting);
+        let (mut id, mut state, mut element) = prev.build(&mut cx);
+        let mut count = 0;
+        let result = prev.message(&[], &mut state, Message::new(vec![], ()), &mut count);
+        assert!(matches!(result, MessageResult::Action(1)));
+        for _ in 0..10 {
+            let next = run_once(counting);
+            next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+            let result = next.message(&[], &mut state, Message::new(vec![], ()), &mut count);
+            assert!(matches!(result, MessageResult::Nop));
+            prev = next;
+        }
+        assert_eq!(count, 1);
+    }
+
+    type Shown = OneOf2<OnMount<u32, u32, Label>, Label>;
+
+    fn shown(show: bool) -> Shown {
+        if show {
+            one_of_a(on_mount(label("shown"), counting))
+        } else {
+            one_of_b(label("hidden"))
+        }
+    }
+
+    /// A view switching between a mounted and a hidden branch, run in a harness.
+    struct Switcher {
+        cx: Cx,
+        harness: Harness,
+        view: Shown,
+        id: Id,
+        state: OneOf2State<u32, u32, OnMount<u32, u32, Label>, Label>,
+        root: Pod,
+    }
+
+    impl Switcher {
+        fn new() -> Self {
+            let mut cx = view_cx();
+            let view = shown(true);
+            let (id, state, element) = view.build(&mut cx);
+            Switcher {
+                cx,
+                harness: Harness::new(),
+                view,
+                id,
+                state,
+                root: Pod::new(element),
+            }
+        }
+
+        /// Runs an update and returns the paths of the mount messages sent in it.
+        fn update(&mut self) -> Vec<Vec<Id>> {
+            self.harness.update(&mut self.root);
+            let messages = self.harness.take_messages();
+            // The paths start with the id of the `OneOf`, which is handled by its parent.
+            messages
+                .into_iter()
+                .map(|message| message.id_path[1..].to_vec())
+                .collect()
+        }
+
+        fn show(&mut self, show: bool) -> Vec<Vec<Id>> {
+            let next = shown(show);
+            let element = self.root.downcast_mut().unwrap();
+            let changes = next.rebuild(
+                &mut self.cx,
+                &self.view,
+                &mut self.id,
+                &mut self.state,
+                element,
+            );
+            let _ = self.root.mark(changes);
+            self.view = next;
+            self.update()
+        }
+
+        fn deliver(&mut self, path: &[Id], count: &mut u32) -> MessageResult<u32> {
+            self.view
+                .message(path, &mut self.state, Message::new(vec![], ()), count)
+        }
+    }
+
+    #[test]
+    fn reinserted_view_runs_again_and_removed_one_is_stale() {
+        let mut switcher = Switcher::new();
+        let mut count = 0;
+        let first = switcher.update();
+        assert_eq!(first.len(), 1);
+
+        // Removed before its message was delivered.
+        assert!(switcher.show(false).is_empty());
+        let result = switcher.deliver(&first[0], &mut count);
+        assert!(matches!(result, MessageResult::Stale(_)));
+
+        let again = switcher.show(true);
+        assert_eq!(again.len(), 1);
+        assert_ne!(again[0], first[0]);
+        let result = switcher.deliver(&again[0], &mut count);
+        assert!(matches!(result, MessageResult::Action(1)));
+        let result = switcher.deliver(&first[0], &mut count);
+        assert!(matches!(result, MessageResult::Stale(_)));
+
+        for _ in 0..3 {
+            assert!(switcher.show(true).is_empty());
+            let result = switcher.deliver(&again[0], &mut count);
+            assert!(matches!(result, MessageResult::Nop));
+        }
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn unmount_callback_runs_when_the_state_is_dropped() {
+        let mut cx = view_cx();
+        let unmounts = Arc::new(AtomicUsize::new(0));
+        let view = |unmounts: &Arc<AtomicUsize>| {
+            let unmounts = unmounts.clone();
+            on_unmount(label("child"), move || {
+                unmounts.fetch_add(1, Ordering::Relaxed);
+            })
+        };
+
+        let prev = view(&unmounts);
+        let (mut id, mut state, mut element) = View::<()>::build(&prev, &mut cx);
+        for _ in 0..3 {
+            View::<()>::rebuild(
+                &view(&unmounts),
+                &mut cx,
+                &prev,
+                &mut id,
+                &mut state,
+                &mut element,
+            );
+        }
+        assert_eq!(unmounts.load(Ordering::Relaxed), 0);
+        drop(state);
+        assert_eq!(unmounts.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, EventPayload, Listen, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Turns pointer events on a child view into actions, see [`on_event`].
+pub struct OnEvent<T, A, V> {
+    child: V,
+    listen: Listen,
+    // consider not boxing
+    callback: Box<dyn Fn(&mut T, &EventPayload) -> Option<A> + Send>,
+}
+
+/// Calls `callback` with the pointer events on `child`, an action is produced when it returns
+/// one.
+///
+/// Presses, releases, moves, clicks and hover changes are all reported, see [`EventPayload`].
+/// The layout of the child is not changed. [`ViewExt::on_click`] and [`ViewExt::on_hover`]
+/// cover the common cases.
+///
+/// [`ViewExt::on_click`]: super::ViewExt::on_click
+/// [`ViewExt::on_hover`]: super::ViewExt::on_hover
+pub fn on_event<T, A, V>(
+    child: V,
+    callback: impl Fn(&mut T, &EventPayload) -> Option<A> + Send + 'static,
+) -> OnEvent<T, A, V> {
+    OnEvent {
+        child,
+        listen: Listen::all(),
+        callback: Box::new(callback),
+    }
+}
+
+/// Calls `clicked` when `child` is clicked with the primary mouse button, see
+/// [`ViewExt::on_click`](super::ViewExt::on_click).
+pub fn on_click<T, A, V>(
+    child: V,
+    clicked: impl Fn(&mut T) -> A + Send + 'static,
+) -> OnEvent<T, A, V> {
+    OnEvent {
+        child,
+        listen: Listen::CLICK,
+        callback: Box::new(move |app_state, event| {
+            matches!(event, EventPayload::Click(_)).then(|| clicked(app_state))
+        }),
+    }
+}
+
+/// Calls `hovered` with `true` when the pointer enters `child` and with `false` when it leaves,
+/// see [`ViewExt::on_hover`](super::ViewExt::on_hover).
+pub fn on_hover<T, A, V>(
+    child: V,
+    hovered: impl Fn(&mut T, bool) -> A + Send + 'static,
+) -> OnEvent<T, A, V> {
+    OnEvent {
+        child,
+        listen: Listen::HOVER,
+        callback: Box::new(move |app_state, event| match event {
+            EventPayload::Hover(is_hot) => Some(hovered(app_state, *is_hot)),
+            _ => None,
+        }),
+    }
+}
+
+impl<T, A, V> ViewMarker for OnEvent<T, A, V> {}
+
+impl<T, A, V: View<T, A>> View<T, A> for OnEvent<T, A, V> {
+    type State = (Id, V::State);
+
+    type Element = widget::OnEvent;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let element = widget::OnEvent::new(cx.id_path(), Pod::new(child), self.listen);
+            ((child_id, child_state), element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let (child_id, child_state) = state;
+        if self.listen != prev.listen {
+            element.set_listen(self.listen);
+        }
+        cx.with_id(*id, |cx| {
+            let child = element.child_mut();
+            let changes =
+                self.child
+                    .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+            child.mark(changes)
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let (child_id, child_state) = state;
+        match id_path.split_first() {
+            Some((first, rest)) if first == child_id => {
+                self.child.message(rest, child_state, message, app_state)
+            }
+            Some(_) => MessageResult::Stale(message),
+            None => match message.downcast::<EventPayload>() {
+                Ok(event) => match (self.callback)(app_state, &event) {
+                    Some(action) => MessageResult::Action(action),
+                    None => MessageResult::Nop,
+                },
+                Err(message) => MessageResult::Stale(message),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{mouse, view_cx};
+    use test_log::test;
+
+    #[test]
+    fn callbacks_see_only_their_events() {
+        let mut cx = view_cx();
+        let view = on_click("save", |count: &mut u32| *count += 1);
+        let (_, mut state, _) = View::<u32, ()>::build(&view, &mut cx);
+        let mut count = 0;
+
+        for event in [
+            EventPayload::Hover(true),
+            EventPayload::Click(mouse((1.0, 1.0))),
+        ] {
+            let _ = view.message(&[], &mut state, Message::new(vec![], event), &mut count);
+        }
+        assert_eq!(count, 1);
+    }
+}
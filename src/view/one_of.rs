@@ -0,0 +1,420 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A `OneOf` view which keeps its hidden branches, see `retain_inactive` on
+/// [`OneOf2`] and its siblings.
+#[derive(Clone)]
+pub struct RetainInactive<O>(O);
+
+/// The state of a branch which was shown at least once.
+struct Branch<T, A, V: View<T, A>> {
+    id: Id,
+    state: V::State,
+    /// The last view of a hidden branch, it is diffed against when the branch is shown again.
+    view: Option<V>,
+}
+
+/// Shows `view` as the first branch of a [`OneOf2`].
+pub fn one_of_a<V1, V2>(view: V1) -> OneOf2<V1, V2> {
+    OneOf2::A(view)
+}
+
+/// Shows `view` as the second branch of a [`OneOf2`].
+pub fn one_of_b<V1, V2>(view: V2) -> OneOf2<V1, V2> {
+    OneOf2::B(view)
+}
+
+macro_rules! one_of {
+    ($name:ident, $state:ident; $($variant:ident $slot:ident $index:literal $view:ident),+) => {
+        /// One of several views of different types, for example in the branches of an `if`
+        /// or a `match`.
+        ///
+        /// Rebuilding with the same branch diffs against the previous view as usual. Switching
+        /// to another branch removes the widgets of the shown one and builds the new one with
+        /// a fresh id, so messages still addressed to the old branch are stale. With
+        /// `retain_inactive`, a hidden branch keeps its view state and its widgets instead
+        /// and comes back as it was left.
+        #[derive(Clone)]
+        pub enum $name<$($view),+> {
+            $($variant($view)),+
+        }
+
+        pub struct $state<T, A, $($view: View<T, A>),+> {
+            $($slot: Option<Branch<T, A, $view>>),+
+        }
+
+        impl<$($view),+> ViewMarker for $name<$($view),+> {}
+
+        impl<$($view),+> ViewMarker for RetainInactive<$name<$($view),+>> {}
+
+        impl<$($view),+> $name<$($view),+> {
+            /// Keeps the view state and widgets of hidden branches, so that showing a branch
+            /// again restores them.
+            ///
+            /// The views of the branches need to be cloned to diff against them later.
+            pub fn retain_inactive(self) -> RetainInactive<Self>
+            where
+                Self: Clone,
+            {
+                RetainInactive(self)
+            }
+
+            fn build_branch<T, A>(
+                &self,
+                cx: &mut Cx,
+            ) -> (Id, $state<T, A, $($view),+>, widget::OneOf)
+            where
+                $($view: View<T, A>, $view::Element: 'static),+
+            {
+                let (id, (state, element)) = cx.with_new_id(|cx| {
+                    let mut state = $state { $($slot: None),+ };
+                    let element = match self {
+                        $($name::$variant(view) => {
+                            let (child_id, child_state, child) = view.build(cx);
+                            state.$slot = Some(Branch {
+                                id: child_id,
+                                state: child_state,
+                                view: None,
+                            });
+                            widget::OneOf::new($index, Pod::new(child))
+                        })+
+                    };
+                    (state, element)
+                });
+                (id, state, element)
+            }
+
+            /// Rebuilds the shown branch, `keep` returns the view to keep of a branch which is
+            /// hidden now, or `None` to drop it.
+            fn rebuild_branch<T, A>(
+                &self,
+                cx: &mut Cx,
+                prev: &Self,
+                id: &mut Id,
+                state: &mut $state<T, A, $($view),+>,
+                element: &mut widget::OneOf,
+                keep: impl FnOnce(&Self) -> Option<Self>,
+            ) -> ChangeFlags
+            where
+                $($view: View<T, A>, $view::Element: 'static),+
+            {
+                cx.with_id(*id, |cx| {
+                    match (self, prev) {
+                        $(($name::$variant(view), $name::$variant(prev)) => {
+                            let branch = state.$slot.as_mut().unwrap();
+                            let child = element.child_mut();
+                            let changes = view.rebuild(
+                                cx,
+                                prev,
+                                &mut branch.id,
+                                &mut branch.state,
+                                child.expect_mut(),
+                            );
+                            return child.mark(changes);
+                        })+
+                        _ => (),
+                    }
+
+                    let kept = keep(prev);
+                    let park = kept.is_some();
+                    match kept {
+                        $(Some($name::$variant(view)) => {
+                            state.$slot.as_mut().unwrap().view = Some(view);
+                        })+
+                        None => match prev {
+                            $($name::$variant(_) => state.$slot = None),+
+                        },
+                    }
+
+                    match self {
+                        $($name::$variant(view) => {
+                            if let Some(mut child) = element.take_parked($index) {
+                                // Only retained branches are parked.
+                                let branch = state.$slot.as_mut().unwrap();
+                                let prev = branch.view.take().unwrap();
+                                let changes = view.rebuild(
+                                    cx,
+                                    &prev,
+                                    &mut branch.id,
+                                    &mut branch.state,
+                                    child.expect_mut(),
+                                );
+                                let _ = child.mark(changes);
+                                element.show($index, child, park)
+                            } else {
+                                let (child_id, child_state, child) = view.build(cx);
+                                state.$slot = Some(Branch {
+                                    id: child_id,
+                                    state: child_state,
+                                    view: None,
+                                });
+                                element.show($index, Pod::new(child), park)
+                            }
+                        })+
+                    }
+                })
+            }
+
+            fn message_branch<T, A>(
+                &self,
+                id_path: &[Id],
+                state: &mut $state<T, A, $($view),+>,
+                message: Message,
+                app_state: &mut T,
+            ) -> MessageResult<A>
+            where
+                $($view: View<T, A>),+
+            {
+                let Some((first, rest_path)) = id_path.split_first() else {
+                    return MessageResult::Stale(message);
+                };
+                match self {
+                    $($name::$variant(view) => match &mut state.$slot {
+                        Some(branch) if branch.id == *first => {
+                            view.message(rest_path, &mut branch.state, message, app_state)
+                        }
+                        _ => MessageResult::Stale(message),
+                    }),+
+                }
+            }
+        }
+
+        impl<T, A, $($view),+> View<T, A> for $name<$($view),+>
+        where
+            $($view: View<T, A>, $view::Element: 'static),+
+        {
+            type State = $state<T, A, $($view),+>;
+
+            type Element = widget::OneOf;
+
+            fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+                self.build_branch(cx)
+            }
+
+            fn rebuild(
+                &self,
+                cx: &mut Cx,
+                prev: &Self,
+                id: &mut Id,
+                state: &mut Self::State,
+                element: &mut Self::Element,
+            ) -> ChangeFlags {
+                self.rebuild_branch(cx, prev, id, state, element, |_| None)
+            }
+
+            fn message(
+                &self,
+                id_path: &[Id],
+                state: &mut Self::State,
+                message: Message,
+                app_state: &mut T,
+            ) -> MessageResult<A> {
+                self.message_branch(id_path, state, message, app_state)
+            }
+        }
+
+        impl<T, A, $($view),+> View<T, A> for RetainInactive<$name<$($view),+>>
+        where
+            $($view: View<T, A> + Clone, $view::Element: 'static),+
+        {
+            type State = $state<T, A, $($view),+>;
+
+            type Element = widget::OneOf;
+
+            fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+                self.0.build_branch(cx)
+            }
+
+            fn rebuild(
+                &self,
+                cx: &mut Cx,
+                prev: &Self,
+                id: &mut Id,
+                state: &mut Self::State,
+                element: &mut Self::Element,
+            ) -> ChangeFlags {
+                self.0
+                    .rebuild_branch(cx, &prev.0, id, state, element, |prev| Some(prev.clone()))
+            }
+
+            fn message(
+                &self,
+                id_path: &[Id],
+                state: &mut Self::State,
+                message: Message,
+                app_state: &mut T,
+            ) -> MessageResult<A> {
+                self.0.message_branch(id_path, state, message, app_state)
+            }
+        }
+    };
+}
+
+one_of!(OneOf2, OneOf2State; A a 0 V1, B b 1 V2);
+one_of!(OneOf3, OneOf3State; A a 0 V1, B b 1 V2, C c 2 V3);
+one_of!(OneOf4, OneOf4State; A a 0 V1, B b 1 V2, C c 2 V3, D d 3 V4);
+one_of!(OneOf5, OneOf5State; A a 0 V1, B b 1 V2, C c 2 V3, D d 3 V4, E e 4 V5);
+one_of!(OneOf6, OneOf6State; A a 0 V1, B b 1 V2, C c 2 V3, D d 3 V4, E e 4 V5, F f 5 V6);
+one_of!(
+    OneOf7, OneOf7State;
+    A a 0 V1, B b 1 V2, C c 2 V3, D d 3 V4, E e 4 V5, F f 5 V6, G g 6 V7
+);
+one_of!(
+    OneOf8, OneOf8State;
+    A a 0 V1, B b 1 V2, C c 2 V3, D d 3 V4, E e 4 V5, F f 5 V6, G g 6 V7, H h 7 V8
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::button::Button;
+    use crate::view::{button, label, Label};
+    use crate::widget::test_util::{view_cx, Leaf};
+    use test_log::test;
+
+    /// Counts the messages it receives in its state.
+    #[derive(Clone)]
+    struct Counter;
+
+    impl ViewMarker for Counter {}
+
+    impl View<()> for Counter {
+        type State = u32;
+
+        type Element = Leaf;
+
+        fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+            let (id, element) = cx.with_new_id(|_| Leaf::new(10.0, 10.0));
+            (id, 0, element)
+        }
+
+        fn rebuild(
+            &self,
+            _cx: &mut Cx,
+            _prev: &Self,
+            _id: &mut Id,
+            _state: &mut Self::State,
+            _element: &mut Self::Element,
+        ) -> ChangeFlags {
+            ChangeFlags::empty()
+        }
+
+        fn message(
+            &self,
+            _id_path: &[Id],
+            state: &mut Self::State,
+            _message: Message,
+            _app_state: &mut (),
+        ) -> MessageResult<()> {
+            *state += 1;
+            MessageResult::Nop
+        }
+    }
+
+    fn text_or_button(show_button: bool) -> OneOf2<Label, Button<(), (), Label>> {
+        if show_button {
+            one_of_b(button(label("press"), |_: &mut ()| ()))
+        } else {
+            one_of_a(label("text"))
+        }
+    }
+
+    fn count(state: &OneOf2State<(), (), Counter, Counter>) -> Option<(Id, u32)> {
+        state.a.as_ref().map(|branch| (branch.id, branch.state))
+    }
+
+    #[test]
+    fn switching_branches_replaces_the_widget() {
+        let mut cx = view_cx();
+
+        let first = text_or_button(false);
+        let (mut id, mut state, mut element) = first.build(&mut cx);
+        let label_id = state.a.as_ref().unwrap().id;
+        assert!(element
+            .child_mut()
+            .downcast_mut::<widget::Label>()
+            .is_some());
+
+        let second = text_or_button(true);
+        let changes = second.rebuild(&mut cx, &first, &mut id, &mut state, &mut element);
+        assert!(changes.contains(ChangeFlags::tree_structure()));
+        assert_eq!(element.index(), 1);
+        assert!(element
+            .child_mut()
+            .downcast_mut::<widget::Button>()
+            .is_some());
+        assert!(state.a.is_none());
+
+        // Messages for the removed branch are stale, also once it is shown again.
+        let third = text_or_button(false);
+        third.rebuild(&mut cx, &second, &mut id, &mut state, &mut element);
+        assert_ne!(state.a.as_ref().unwrap().id, label_id);
+        let result = third.message(&[label_id], &mut state, Message::new(vec![], ()), &mut ());
+        assert!(matches!(result, MessageResult::Stale(_)));
+    }
+
+    #[test]
+    fn hidden_branch_state_is_dropped_by_default() {
+        let mut cx = view_cx();
+
+        let a = OneOf2::<Counter, Counter>::A(Counter);
+        let b = OneOf2::<Counter, Counter>::B(Counter);
+        let (mut id, mut state, mut element) = a.build(&mut cx);
+        let (child_id, _) = count(&state).unwrap();
+        for _ in 0..3 {
+            a.message(&[child_id], &mut state, Message::new(vec![], ()), &mut ());
+        }
+        assert_eq!(count(&state), Some((child_id, 3)));
+
+        b.rebuild(&mut cx, &a, &mut id, &mut state, &mut element);
+        assert_eq!(count(&state), None);
+        a.rebuild(&mut cx, &b, &mut id, &mut state, &mut element);
+        let (new_id, messages) = count(&state).unwrap();
+        assert_ne!(new_id, child_id);
+        assert_eq!(messages, 0);
+    }
+
+    #[test]
+    fn retained_branch_comes_back_as_it_was() {
+        let mut cx = view_cx();
+
+        let a = OneOf2::<Counter, Counter>::A(Counter).retain_inactive();
+        let b = OneOf2::<Counter, Counter>::B(Counter).retain_inactive();
+        let (mut id, mut state, mut element) = a.build(&mut cx);
+        let (child_id, _) = count(&state).unwrap();
+        let widget_id = element.child_mut().id();
+        for _ in 0..3 {
+            a.message(&[child_id], &mut state, Message::new(vec![], ()), &mut ());
+        }
+
+        b.rebuild(&mut cx, &a, &mut id, &mut state, &mut element);
+        assert_eq!(count(&state), Some((child_id, 3)));
+        // The hidden branch doesn't receive messages.
+        let result = b.message(&[child_id], &mut state, Message::new(vec![], ()), &mut ());
+        assert!(matches!(result, MessageResult::Stale(_)));
+
+        let changes = a.rebuild(&mut cx, &b, &mut id, &mut state, &mut element);
+        assert!(changes.contains(ChangeFlags::tree_structure()));
+        assert_eq!(count(&state), Some((child_id, 3)));
+        assert_eq!(element.child_mut().id(), widget_id);
+        a.message(&[child_id], &mut state, Message::new(vec![], ()), &mut ());
+        assert_eq!(count(&state), Some((child_id, 4)));
+    }
+}
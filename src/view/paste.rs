@@ -0,0 +1,96 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, PasteData, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Turns pastes inside a child view into actions, see [`on_paste`].
+pub struct OnPaste<T, A, V> {
+    child: V,
+    // consider not boxing
+    callback: Box<dyn Fn(&mut T, PasteData) -> A + Send>,
+}
+
+/// Calls `pasted` with the contents of the clipboard when the user pastes while keyboard focus
+/// is inside `child`.
+///
+/// Widgets which handle pastes themselves, like text boxes, keep them. The layout of the child
+/// is not changed.
+pub fn on_paste<T, A, V>(
+    child: V,
+    pasted: impl Fn(&mut T, PasteData) -> A + Send + 'static,
+) -> OnPaste<T, A, V> {
+    OnPaste {
+        child,
+        callback: Box::new(pasted),
+    }
+}
+
+impl<T, A, V> ViewMarker for OnPaste<T, A, V> {}
+
+impl<T, A, V: View<T, A>> View<T, A> for OnPaste<T, A, V> {
+    type State = (Id, V::State);
+
+    type Element = widget::PasteTarget;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let element = widget::PasteTarget::new(cx.id_path(), Pod::new(child));
+            ((child_id, child_state), element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let (child_id, child_state) = state;
+        cx.with_id(*id, |cx| {
+            let child = element.child_mut();
+            let changes =
+                self.child
+                    .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+            child.mark(changes)
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let (child_id, child_state) = state;
+        match id_path.split_first() {
+            Some((first, rest)) if first == child_id => {
+                self.child.message(rest, child_state, message, app_state)
+            }
+            Some(_) => MessageResult::Stale(message),
+            None => match message.downcast::<PasteData>() {
+                Ok(data) => MessageResult::Action((self.callback)(app_state, *data)),
+                Err(message) => MessageResult::Stale(message),
+            },
+        }
+    }
+}
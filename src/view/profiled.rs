@@ -0,0 +1,163 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Instant;
+
+use crate::profile::Phase;
+use crate::view::{Id, ViewMarker};
+use crate::widget::ChangeFlags;
+use crate::{Message, MessageResult, Profiler};
+
+use super::{Cx, View};
+
+/// Times a child view, see [`profiled`].
+pub struct Profiled<V> {
+    label: &'static str,
+    child: V,
+}
+
+pub struct ProfiledState<S> {
+    child: S,
+    // messages have no context, so the registry is kept here
+    profiler: Profiler,
+}
+
+/// Records how long `child` takes to build, rebuild and handle messages under `label`.
+///
+/// The timings go to the [`Profiler`] of the [`Cx`], and are only taken while it is enabled.
+/// Views sharing a label add up. Rebuilds slower than the
+/// [slow threshold](Profiler::set_slow_threshold) are logged with the change flags they caused.
+pub fn profiled<V>(label: &'static str, child: V) -> Profiled<V> {
+    Profiled { label, child }
+}
+
+impl<V> ViewMarker for Profiled<V> {}
+
+impl<T, A, V: View<T, A>> View<T, A> for Profiled<V> {
+    type State = ProfiledState<V::State>;
+
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let profiler = cx.profiler().clone();
+        let (id, child, element) = if profiler.is_enabled() {
+            let start = Instant::now();
+            let result = self.child.build(cx);
+            profiler.record(self.label, Phase::Build, start.elapsed());
+            result
+        } else {
+            self.child.build(cx)
+        };
+        (id, ProfiledState { child, profiler }, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        state.profiler = cx.profiler().clone();
+        if !state.profiler.is_enabled() {
+            return self
+                .child
+                .rebuild(cx, &prev.child, id, &mut state.child, element);
+        }
+        let start = Instant::now();
+        let changes = self
+            .child
+            .rebuild(cx, &prev.child, id, &mut state.child, element);
+        let elapsed = start.elapsed();
+        state.profiler.record(self.label, Phase::Rebuild, elapsed);
+        if state
+            .profiler
+            .slow_threshold()
+            .map_or(false, |threshold| elapsed > threshold)
+        {
+            tracing::warn!(
+                "slow rebuild of {}: {:?}, changes {:?}",
+                self.label,
+                elapsed,
+                changes
+            );
+        }
+        changes
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if !state.profiler.is_enabled() {
+            return self
+                .child
+                .message(id_path, &mut state.child, message, app_state);
+        }
+        let start = Instant::now();
+        let result = self
+            .child
+            .message(id_path, &mut state.child, message, app_state);
+        state
+            .profiler
+            .record(self.label, Phase::Message, start.elapsed());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::label;
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    #[test]
+    fn counts_match_rebuilds() {
+        let mut cx = view_cx();
+        cx.profiler().set_enabled(true);
+        let view = |n: u32| profiled("counter", label(format!("{n}")));
+
+        let mut prev = view(0);
+        let (mut id, mut state, mut element) = View::<()>::build(&prev, &mut cx);
+        for n in 1..=7 {
+            let next = view(n);
+            View::<()>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+            prev = next;
+        }
+
+        let stats = cx.profiler().stats("counter").unwrap();
+        assert_eq!(stats.builds.count, 1);
+        assert_eq!(stats.rebuilds.count, 7);
+        assert!(stats.rebuilds.max <= stats.rebuilds.total);
+
+        cx.profiler().reset();
+        View::<()>::rebuild(&view(8), &mut cx, &prev, &mut id, &mut state, &mut element);
+        assert_eq!(cx.profiler().stats("counter").unwrap().rebuilds.count, 1);
+    }
+
+    #[test]
+    fn nothing_recorded_while_disabled() {
+        let mut cx = view_cx();
+        let prev = profiled("counter", label("0"));
+        let (mut id, mut state, mut element) = View::<()>::build(&prev, &mut cx);
+        let next = profiled("counter", label("1"));
+        View::<()>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+        assert!(cx.profiler().snapshot().is_empty());
+    }
+}
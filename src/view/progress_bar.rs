@@ -0,0 +1,65 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::ViewMarker;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A progress bar, see [`progress`].
+pub struct ProgressBar {
+    progress: Option<f64>,
+}
+
+/// Creates a progress bar.
+///
+/// `Some` fills the bar up to the given fraction in `0.0..=1.0`, `None` shows an animated
+/// indicator for work of unknown length.
+pub fn progress(progress: Option<f64>) -> ProgressBar {
+    ProgressBar { progress }
+}
+
+impl ViewMarker for ProgressBar {}
+
+impl<T, A> View<T, A> for ProgressBar {
+    type State = ();
+
+    type Element = crate::widget::ProgressBar;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, element) = cx.with_new_id(|_| crate::widget::ProgressBar::new(self.progress));
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        element.set_progress(self.progress)
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
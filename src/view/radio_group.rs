@@ -0,0 +1,267 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::{Cx, ElementSplice, Id, View, ViewSequence};
+use crate::widget::{ChangeFlags, Pod, Radio, RadioMessage};
+use crate::{Message, MessageResult};
+
+/// A sequence of radio buttons of which exactly one is selected.
+///
+/// The group does no layout of its own, its radio buttons are placed directly into the parent
+/// container. Arrow keys move the selection within the group and keyboard focus follows it.
+pub struct RadioGroup<T, A, V, Value> {
+    options: Vec<(V, Value)>,
+    selected: Value,
+    // consider not boxing
+    callback: Box<dyn Fn(&mut T, Value) -> A + Send>,
+}
+
+/// The state of a [`RadioGroup`] sequence.
+pub struct RadioGroupState<S> {
+    group: Id,
+    /// The id of each radio, the id of its label and the state of its label.
+    items: Vec<(Id, Id, S)>,
+    moved_by_keyboard: bool,
+}
+
+/// Creates a [`RadioGroup`] with one radio button per option, labeled by the option's view.
+pub fn radio_group<T, A, V, Value>(
+    options: impl IntoIterator<Item = (V, Value)>,
+    selected: Value,
+    on_select: impl Fn(&mut T, Value) -> A + Send + 'static,
+) -> RadioGroup<T, A, V, Value>
+where
+    V: View<T, A>,
+    Value: Clone + PartialEq + Send,
+{
+    RadioGroup {
+        options: options.into_iter().collect(),
+        selected,
+        callback: Box::new(on_select),
+    }
+}
+
+/// The index `delta` options away from `index`, wrapping around at both ends.
+fn step_index(index: usize, delta: isize, len: usize) -> usize {
+    (index as isize + delta).rem_euclid(len as isize) as usize
+}
+
+impl<T, A, V, Value> RadioGroup<T, A, V, Value>
+where
+    V: View<T, A>,
+    V::Element: 'static,
+    Value: Clone + PartialEq + Send,
+{
+    fn build_radio(&self, cx: &mut Cx, group: Id, index: usize) -> ((Id, Id, V::State), Pod) {
+        let (view, value) = &self.options[index];
+        let (id, (child_id, child_state, radio)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child_element) = view.build(cx);
+            let selected = *value == self.selected;
+            let radio = Radio::new(cx.id_path(), group, selected, Pod::new(child_element));
+            (child_id, child_state, radio)
+        });
+        ((id, child_id, child_state), Pod::new(radio))
+    }
+}
+
+impl<T, A, V, Value> ViewSequence<T, A> for RadioGroup<T, A, V, Value>
+where
+    V: View<T, A>,
+    V::Element: 'static,
+    Value: Clone + PartialEq + Send,
+{
+    type State = RadioGroupState<V::State>;
+
+    fn build(&self, cx: &mut Cx, elements: &mut Vec<Pod>) -> Self::State {
+        let group = Id::next();
+        let items = (0..self.options.len())
+            .map(|index| {
+                let (item, pod) = self.build_radio(cx, group, index);
+                elements.push(pod);
+                item
+            })
+            .collect();
+        RadioGroupState {
+            group,
+            items,
+            moved_by_keyboard: false,
+        }
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        state: &mut Self::State,
+        element: &mut dyn ElementSplice<Pod>,
+    ) -> ChangeFlags {
+        let take_focus = std::mem::take(&mut state.moved_by_keyboard);
+        let mut changes = ChangeFlags::empty();
+
+        for (index, (radio_id, child_id, child_state)) in state.items.iter_mut().enumerate() {
+            if index >= self.options.len() {
+                break;
+            }
+            let (view, value) = &self.options[index];
+            let pod = element.mutate();
+            let radio = pod.expect_mut::<Radio>();
+            let mut radio_changes = cx.with_id(*radio_id, |cx| {
+                let label = radio.label_mut();
+                let flags = view.rebuild(
+                    cx,
+                    &prev.options[index].0,
+                    child_id,
+                    child_state,
+                    label.expect_mut(),
+                );
+                label.mark(flags)
+            });
+            let selected = *value == self.selected;
+            radio_changes |= radio.set_selected(selected, selected && take_focus);
+            changes |= pod.mark(radio_changes);
+        }
+
+        if self.options.len() < state.items.len() {
+            element.delete(state.items.len() - self.options.len());
+            state.items.truncate(self.options.len());
+            changes |= ChangeFlags::tree_structure();
+        }
+
+        while state.items.len() < self.options.len() {
+            let (item, pod) = self.build_radio(cx, state.group, state.items.len());
+            element.push(pod);
+            state.items.push(item);
+            changes |= ChangeFlags::tree_structure();
+        }
+
+        changes
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let Some((first, rest_path)) = id_path.split_first() else {
+            return MessageResult::Stale(message);
+        };
+        let Some(index) = state.items.iter().position(|(id, _, _)| id == first) else {
+            return MessageResult::Stale(message);
+        };
+        if let Some((child, rest_path)) = rest_path.split_first() {
+            let (_, child_id, child_state) = &mut state.items[index];
+            if child != child_id {
+                return MessageResult::Stale(message);
+            }
+            return self.options[index]
+                .0
+                .message(rest_path, child_state, message, app_state);
+        }
+
+        let selected = match message.downcast::<RadioMessage>() {
+            Ok(radio_message) => match radio_message {
+                RadioMessage::Select => index,
+                RadioMessage::Step(delta) => {
+                    state.moved_by_keyboard = true;
+                    step_index(index, delta, self.options.len())
+                }
+            },
+            Err(message) => return MessageResult::Stale(message),
+        };
+        let value = self.options[selected].1.clone();
+        MessageResult::Action((self.callback)(app_state, value))
+    }
+
+    fn count(&self, state: &Self::State) -> usize {
+        state.items.len()
+    }
+
+    fn owns_id(&self, state: &Self::State, id: Id) -> bool {
+        state.items.iter().any(|(radio_id, _, _)| *radio_id == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Axis;
+    use crate::widget::test_util::{view_cx, Harness};
+    use crate::widget::{BoxConstraints, LinearLayout};
+    use test_log::test;
+    use vello::kurbo::Size;
+
+    fn group(selected: u32) -> RadioGroup<u32, (), &'static str, u32> {
+        radio_group(
+            [("one", 1), ("two", 2), ("three", 3)],
+            selected,
+            |state: &mut u32, value| *state = value,
+        )
+    }
+
+    fn checked_count(harness: &mut Harness, pod: &mut Pod) -> usize {
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(400.0, 400.0));
+        harness.layout(pod, &bc);
+        harness
+            .accessibility(pod)
+            .nodes
+            .iter()
+            .filter(|(_, node)| node.role() == accesskit::Role::RadioButton)
+            .filter(|(_, node)| node.checked_state() == Some(accesskit::CheckedState::True))
+            .count()
+    }
+
+    #[test]
+    fn step_wraps_around() {
+        assert_eq!(step_index(2, 1, 3), 0);
+        assert_eq!(step_index(0, -1, 3), 2);
+        assert_eq!(step_index(1, 1, 3), 2);
+    }
+
+    #[test]
+    fn arrow_keys_select_and_exactly_one_is_checked() {
+        let mut cx = view_cx();
+        let mut harness = Harness::new();
+        let mut app_state = 3;
+
+        let view = group(app_state);
+        let mut elements = vec![];
+        let mut state = view.build(&mut cx, &mut elements);
+        let mut root = Pod::new(LinearLayout::new(elements, 0.0, Axis::Vertical));
+        assert_eq!(checked_count(&mut harness, &mut root), 1);
+
+        let last_radio = state.items[2].0;
+        let result = view.message(
+            &[last_radio],
+            &mut state,
+            Message::new(vec![], RadioMessage::Step(1)),
+            &mut app_state,
+        );
+        assert!(matches!(result, MessageResult::Action(())));
+        assert_eq!(app_state, 1);
+
+        let next = group(app_state);
+        let layout = root.downcast_mut::<LinearLayout>().unwrap();
+        let mut splice = layout.children.splice();
+        let changes = next.rebuild(&mut cx, &view, &mut state, &mut splice);
+        assert!(changes.contains(ChangeFlags::UPDATE));
+        let _ = root.mark(changes);
+
+        assert_eq!(checked_count(&mut harness, &mut root), 1);
+        let layout = root.downcast_mut::<LinearLayout>().unwrap();
+        let first = layout.children[0].downcast_mut::<Radio>().unwrap();
+        assert!(first.is_selected());
+    }
+}
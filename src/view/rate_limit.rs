@@ -0,0 +1,403 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Delays the actions of a child view until it has been quiet for a while, see [`debounced`].
+pub struct Debounced<V> {
+    delay: Duration,
+    child: V,
+}
+
+/// Lets through at most one action of a child view per time window, see [`throttled`].
+pub struct Throttled<V> {
+    window: Duration,
+    trailing: bool,
+    child: V,
+}
+
+pub struct RateLimitState<S, A> {
+    child_id: Id,
+    child_state: S,
+    /// The action waiting for the deadline.
+    pending: Option<A>,
+    /// Whether the deadline is running, for throttling.
+    is_closed: bool,
+    /// Whether the deadline has to be (re)started in the next rebuild.
+    needs_deadline: bool,
+}
+
+/// Delivers an action of `child` only after it produced no other action for `delay`.
+///
+/// Each action restarts the delay and replaces the one waiting, so only the latest of a burst
+/// is delivered. It is delivered in the handling of the timer message, so callbacks above see
+/// the app state of that time. A waiting action is dropped when the view is removed.
+pub fn debounced<V>(delay: Duration, child: V) -> Debounced<V> {
+    Debounced { delay, child }
+}
+
+/// Delivers the first action of `child` right away, then drops the following ones for `window`.
+///
+/// With [`trailing`](Throttled::trailing), the latest action dropped is instead delivered when
+/// the window closes, which opens the next window.
+pub fn throttled<V>(window: Duration, child: V) -> Throttled<V> {
+    Throttled {
+        window,
+        trailing: false,
+        child,
+    }
+}
+
+impl<V> Throttled<V> {
+    /// Deliver the latest action suppressed in a window when it closes.
+    pub fn trailing(mut self) -> Self {
+        self.trailing = true;
+        self
+    }
+}
+
+fn build_limited<T, A, V: View<T, A>>(
+    cx: &mut Cx,
+    child: &V,
+) -> (Id, RateLimitState<V::State, A>, widget::Deadline)
+where
+    V::Element: 'static,
+{
+    let (id, (child_id, child_state, element)) = cx.with_new_id(|cx| {
+        let (child_id, child_state, child) = child.build(cx);
+        let element = widget::Deadline::new(cx.id_path(), Pod::new(child));
+        (child_id, child_state, element)
+    });
+    let state = RateLimitState {
+        child_id,
+        child_state,
+        pending: None,
+        is_closed: false,
+        needs_deadline: false,
+    };
+    (id, state, element)
+}
+
+fn rebuild_limited<T, A, V: View<T, A>>(
+    cx: &mut Cx,
+    child: &V,
+    prev: &V,
+    delay: Duration,
+    id: &mut Id,
+    state: &mut RateLimitState<V::State, A>,
+    element: &mut widget::Deadline,
+) -> ChangeFlags
+where
+    V::Element: 'static,
+{
+    let mut changes = cx.with_id(*id, |cx| {
+        let pod = element.child_mut();
+        let changes = child.rebuild(
+            cx,
+            prev,
+            &mut state.child_id,
+            &mut state.child_state,
+            pod.expect_mut(),
+        );
+        pod.mark(changes)
+    });
+    if std::mem::take(&mut state.needs_deadline) {
+        changes |= element.set_deadline(delay);
+    }
+    changes
+}
+
+/// Passes a message to the child, returning its action if there is one.
+fn child_action<T, A, V: View<T, A>>(
+    child: &V,
+    id_path: &[Id],
+    state: &mut RateLimitState<V::State, A>,
+    message: Message,
+    app_state: &mut T,
+) -> Result<A, MessageResult<A>> {
+    match id_path.split_first() {
+        Some((first, rest)) if *first == state.child_id => {
+            match child.message(rest, &mut state.child_state, message, app_state) {
+                MessageResult::Action(action) => Ok(action),
+                // Only the latest action of a batch is kept, as for consecutive messages.
+                MessageResult::Actions(mut actions) if !actions.is_empty() => {
+                    Ok(actions.pop().unwrap())
+                }
+                result => Err(result),
+            }
+        }
+        _ => Err(MessageResult::Stale(message)),
+    }
+}
+
+impl<V> ViewMarker for Debounced<V> {}
+
+impl<T, A: Send, V: View<T, A>> View<T, A> for Debounced<V>
+where
+    V::Element: 'static,
+{
+    type State = RateLimitState<V::State, A>;
+
+    type Element = widget::Deadline;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        build_limited(cx, &self.child)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        rebuild_limited(cx, &self.child, &prev.child, self.delay, id, state, element)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if id_path.is_empty() && message.is::<()>() {
+            return match state.pending.take() {
+                Some(action) => MessageResult::Action(action),
+                None => MessageResult::Nop,
+            };
+        }
+        match child_action(&self.child, id_path, state, message, app_state) {
+            Ok(action) => {
+                state.pending = Some(action);
+                state.needs_deadline = true;
+                MessageResult::RequestRebuild
+            }
+            Err(result) => result,
+        }
+    }
+}
+
+impl<V> ViewMarker for Throttled<V> {}
+
+impl<T, A: Send, V: View<T, A>> View<T, A> for Throttled<V>
+where
+    V::Element: 'static,
+{
+    type State = RateLimitState<V::State, A>;
+
+    type Element = widget::Deadline;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        build_limited(cx, &self.child)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        rebuild_limited(
+            cx,
+            &self.child,
+            &prev.child,
+            self.window,
+            id,
+            state,
+            element,
+        )
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if id_path.is_empty() && message.is::<()>() {
+            return match state.pending.take() {
+                Some(action) if self.trailing => {
+                    state.needs_deadline = true;
+                    MessageResult::Action(action)
+                }
+                _ => {
+                    state.is_closed = false;
+                    MessageResult::Nop
+                }
+            };
+        }
+        match child_action(&self.child, id_path, state, message, app_state) {
+            Ok(action) if state.is_closed => {
+                if self.trailing {
+                    state.pending = Some(action);
+                }
+                MessageResult::Nop
+            }
+            Ok(action) => {
+                state.is_closed = true;
+                state.needs_deadline = true;
+                MessageResult::Action(action)
+            }
+            Err(result) => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{view_cx, Harness};
+    use crate::widget::{BoxConstraints, LinearLayout};
+    use crate::Axis;
+    use test_log::test;
+    use vello::kurbo::Size;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    /// A view turning every `u32` message into an action.
+    struct Emit;
+
+    impl ViewMarker for Emit {}
+
+    impl View<(), u32> for Emit {
+        type State = ();
+
+        type Element = widget::Empty;
+
+        fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+            let (id, element) = cx.with_new_id(|_| widget::Empty);
+            (id, (), element)
+        }
+
+        fn rebuild(
+            &self,
+            _cx: &mut Cx,
+            _prev: &Self,
+            _id: &mut Id,
+            _state: &mut Self::State,
+            _element: &mut Self::Element,
+        ) -> ChangeFlags {
+            ChangeFlags::empty()
+        }
+
+        fn message(
+            &self,
+            _id_path: &[Id],
+            _state: &mut Self::State,
+            message: Message,
+            _app_state: &mut (),
+        ) -> MessageResult<u32> {
+            MessageResult::Action(message.downcast().unwrap())
+        }
+    }
+
+    /// Emits `(ms, value)` pairs from the child and returns the actions delivered with their time.
+    fn trace<V>(view: V, emits: &[(u64, u32)], end: u64) -> Vec<(u64, u32)>
+    where
+        V: View<(), u32, State = RateLimitState<(), u32>, Element = widget::Deadline>,
+    {
+        let mut cx = view_cx();
+        let mut harness = Harness::new();
+        let (mut id, mut state, element) = view.build(&mut cx);
+        let mut root = Pod::new(element);
+        harness.update(&mut root);
+        let mut delivered = vec![];
+        for now in 0..=end {
+            let mut messages: Vec<_> = harness
+                .take_messages()
+                .into_iter()
+                .map(|message| (message.id_path[1..].to_vec(), message))
+                .collect();
+            for (_, value) in emits.iter().filter(|(at, _)| *at == now) {
+                messages.push((vec![state.child_id], Message::new(vec![], *value)));
+            }
+            for (id_path, message) in messages {
+                if let MessageResult::Action(value) =
+                    view.message(&id_path, &mut state, message, &mut ())
+                {
+                    delivered.push((now, value));
+                }
+            }
+            let element = root.downcast_mut().unwrap();
+            let changes = view.rebuild(&mut cx, &view, &mut id, &mut state, element);
+            let _ = root.mark(changes);
+            harness.update(&mut root);
+            harness.advance_time(&mut root, MS);
+        }
+        delivered
+    }
+
+    #[test]
+    fn debounce_delivers_the_latest_after_quiet() {
+        let emits = [(0, 1), (5, 2), (12, 3), (30, 4)];
+        let delivered = trace(debounced(MS * 10, Emit), &emits, 50);
+        assert_eq!(delivered, [(22, 3), (40, 4)]);
+    }
+
+    #[test]
+    fn throttle_drops_actions_within_the_window() {
+        let emits = [(0, 1), (3, 2), (8, 3), (12, 4), (25, 5)];
+        let delivered = trace(throttled(MS * 10, Emit), &emits, 50);
+        assert_eq!(delivered, [(0, 1), (12, 4), (25, 5)]);
+    }
+
+    #[test]
+    fn throttle_delivers_trailing_actions() {
+        let emits = [(0, 1), (3, 2), (8, 3), (12, 4), (25, 5)];
+        let delivered = trace(throttled(MS * 10, Emit).trailing(), &emits, 50);
+        assert_eq!(delivered, [(0, 1), (10, 3), (20, 4), (30, 5)]);
+    }
+
+    #[test]
+    fn removal_cancels_a_pending_action() {
+        let mut cx = view_cx();
+        let mut harness = Harness::new();
+        let view = debounced(MS * 10, Emit);
+        let (mut id, mut state, mut element) = View::<(), u32>::build(&view, &mut cx);
+        let child_id = state.child_id;
+        let _ = view.message(&[child_id], &mut state, Message::new(vec![], 1u32), &mut ());
+        let _ = view.rebuild(&mut cx, &view, &mut id, &mut state, &mut element);
+        let mut root = Pod::new(LinearLayout::new(
+            vec![Pod::new(element)],
+            0.0,
+            Axis::Vertical,
+        ));
+        let bc = BoxConstraints::tight(Size::new(100.0, 100.0));
+        harness.layout(&mut root, &bc);
+        harness.update(&mut root);
+        assert!(harness.window_state.timers.next_deadline().is_some());
+
+        drop(state);
+        root.downcast_mut::<LinearLayout>()
+            .unwrap()
+            .children
+            .clear();
+        let _ = root.mark(ChangeFlags::TREE);
+        harness.layout(&mut root, &bc);
+        assert!(harness.window_state.timers.next_deadline().is_none());
+        harness.advance_time(&mut root, MS * 20);
+        assert!(harness.take_messages().is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vello::peniko::Color;
+
+use crate::view::ViewMarker;
+use crate::widget::{SpanStyle, TextAlignment, TextSpan};
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A styled run of text in a [`RichText`], see [`span`] and [`link_span`].
+pub struct Span<A> {
+    text: String,
+    style: SpanStyle,
+    action: Option<A>,
+}
+
+/// Creates a span of plain text, styled with [`Span::style`].
+pub fn span<A>(text: impl Into<String>) -> Span<A> {
+    Span {
+        text: text.into(),
+        style: SpanStyle::default(),
+        action: None,
+    }
+}
+
+/// Creates a link span, clicking it makes `action` the action of the [`RichText`].
+pub fn link_span<A>(text: impl Into<String>, action: A) -> Span<A> {
+    Span {
+        text: text.into(),
+        style: SpanStyle::default(),
+        action: Some(action),
+    }
+}
+
+impl<A> Span<A> {
+    pub fn style(mut self, style: SpanStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn to_widget_span(&self) -> TextSpan {
+        TextSpan {
+            text: self.text.clone(),
+            style: self.style,
+            is_link: self.action.is_some(),
+        }
+    }
+}
+
+/// Wrapping text made of styled spans, see [`rich_text`].
+pub struct RichText<A> {
+    spans: Vec<Span<A>>,
+    text_size: f32,
+    text_color: Color,
+    alignment: TextAlignment,
+}
+
+/// Creates text which wraps like a [`label`](super::label) and is styled per span.
+///
+/// Clicking a link span yields a clone of the action it was created with.
+pub fn rich_text<A>(spans: impl IntoIterator<Item = Span<A>>) -> RichText<A> {
+    RichText {
+        spans: spans.into_iter().collect(),
+        text_size: 16.0,
+        text_color: Color::rgb8(0xf0, 0xf0, 0xea),
+        alignment: TextAlignment::default(),
+    }
+}
+
+impl<A> RichText<A> {
+    /// The size of spans which don't set their own.
+    pub fn text_size(mut self, text_size: f32) -> Self {
+        self.text_size = text_size;
+        self
+    }
+
+    /// The color of spans which don't set their own.
+    pub fn text_color(mut self, text_color: Color) -> Self {
+        self.text_color = text_color;
+        self
+    }
+
+    pub fn alignment(mut self, alignment: TextAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    fn widget_spans(&self) -> Vec<TextSpan> {
+        self.spans.iter().map(Span::to_widget_span).collect()
+    }
+}
+
+impl<A> ViewMarker for RichText<A> {}
+
+impl<T, A: Clone + Send> View<T, A> for RichText<A> {
+    type State = ();
+
+    type Element = crate::widget::RichText;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, mut element) =
+            cx.with_new_id(|cx| crate::widget::RichText::new(cx.id_path(), self.widget_spans()));
+        // The element has not been laid out yet, so the change flags are not needed.
+        let _ = element.set_text_size(self.text_size)
+            | element.set_text_color(self.text_color)
+            | element.set_alignment(self.alignment);
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        element.set_spans(self.widget_spans())
+            | element.set_text_size(self.text_size)
+            | element.set_text_color(self.text_color)
+            | element.set_alignment(self.alignment)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        if !id_path.is_empty() {
+            return MessageResult::Stale(message);
+        }
+        match message.downcast::<usize>() {
+            // The spans may have changed since the click, then there is nothing to report.
+            Ok(index) => match self.spans.get(index).and_then(|span| span.action.clone()) {
+                Some(action) => MessageResult::Action(action),
+                None => MessageResult::Nop,
+            },
+            Err(message) => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    fn view(url: &'static str) -> RichText<&'static str> {
+        rich_text([
+            span("bold").style(SpanStyle::bold()),
+            span(" normal "),
+            link_span("docs", url),
+        ])
+    }
+
+    #[test]
+    fn rebuild_only_invalidates_changed_spans() {
+        let mut cx = view_cx();
+        let first = view("https://example.com");
+        let (mut id, mut state, mut element) = View::<(), &str>::build(&first, &mut cx);
+        assert_eq!(element.text(), "bold normal docs");
+
+        // Only the action changed, which the widget doesn't know about.
+        let second = view("https://example.org");
+        let changes =
+            View::<(), &str>::rebuild(&second, &mut cx, &first, &mut id, &mut state, &mut element);
+        assert_eq!(changes, ChangeFlags::empty());
+
+        let result = View::<(), &str>::message(
+            &second,
+            &[],
+            &mut state,
+            Message::new(vec![], 2usize),
+            &mut (),
+        );
+        assert!(matches!(
+            result,
+            MessageResult::Action("https://example.org")
+        ));
+
+        let third = rich_text([span("bold").style(SpanStyle::italic()), span(" normal ")]);
+        let changes =
+            View::<(), &str>::rebuild(&third, &mut cx, &second, &mut id, &mut state, &mut element);
+        assert!(changes.contains(ChangeFlags::LAYOUT));
+        // A click on a link which is gone by now is dropped.
+        let result = View::<(), &str>::message(
+            &third,
+            &[],
+            &mut state,
+            Message::new(vec![], 2usize),
+            &mut (),
+        );
+        assert!(matches!(result, MessageResult::Nop));
+    }
+}
@@ -0,0 +1,350 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::marker::PhantomData;
+
+use crate::view::ViewMarker;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A component with local state and messages, see [`scoped`].
+pub struct Scoped<S, Msg, V, FInit, FBuild, FUpdate> {
+    f_init: FInit,
+    f_build: FBuild,
+    f_update: FUpdate,
+    phantom: PhantomData<fn() -> (S, Msg, V)>,
+}
+
+pub struct ScopedState<T, S, Msg, V: View<T, Msg>> {
+    local: S,
+    view: V,
+    view_state: V::State,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T, S, Msg, V: View<T, Msg>> ScopedState<T, S, Msg, V> {
+    /// The local state owned by the component.
+    pub fn local(&self) -> &S {
+        &self.local
+    }
+}
+
+/// A self-contained component in the style of the Elm architecture.
+///
+/// `init` creates the local state `S` when the view is built, it lives in the view state from
+/// then on and is not reset by rebuilds of the parent. `build` creates the child view from the
+/// local state on build and on every rebuild, the new child view is diffed against the previous
+/// one. The actions of the child are messages for the component: they are passed to `update`
+/// together with the local state, and a rebuild is requested to show the result. The child
+/// still gets the app state in its callbacks; the component itself never produces actions.
+///
+/// ```ignore
+/// #[derive(Clone, Copy)]
+/// enum Channel {
+///     Red,
+///     Green,
+///     Blue,
+/// }
+///
+/// fn color_picker<T>() -> impl View<T> {
+///     scoped(
+///         || [0u8; 3],
+///         |rgb: &[u8; 3]| {
+///             let channel = |channel: Channel| {
+///                 slider(rgb[channel as usize] as f64, 0.0..255.0, move |_: &mut T, value| {
+///                     (channel, value as u8)
+///                 })
+///             };
+///             v_stack((
+///                 label(format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])),
+///                 channel(Channel::Red),
+///                 channel(Channel::Green),
+///                 channel(Channel::Blue),
+///             ))
+///         },
+///         |rgb: &mut [u8; 3], (channel, value): (Channel, u8)| {
+///             rgb[channel as usize] = value;
+///         },
+///     )
+/// }
+/// ```
+pub fn scoped<S, Msg, V, FInit, FBuild, FUpdate>(
+    init: FInit,
+    build: FBuild,
+    update: FUpdate,
+) -> Scoped<S, Msg, V, FInit, FBuild, FUpdate>
+where
+    FInit: Fn() -> S + Send,
+    FBuild: Fn(&S) -> V + Send,
+    FUpdate: Fn(&mut S, Msg) + Send,
+{
+    Scoped {
+        f_init: init,
+        f_build: build,
+        f_update: update,
+        phantom: PhantomData,
+    }
+}
+
+impl<S, Msg, V, FInit, FBuild, FUpdate> ViewMarker for Scoped<S, Msg, V, FInit, FBuild, FUpdate> {}
+
+// This is not a `Wrapper`, though it also generates its view from state of its own. The view a
+// `Wrapper` generates has the action type of the wrapper, and `Wrapper::message` sees messages
+// before that view rather than the actions it returns, while the child of a component returns
+// the messages `update` consumes. Diffing the generated views works the same way as in `Wrapped`.
+
+impl<T, A, S, Msg, V, FInit, FBuild, FUpdate> View<T, A>
+    for Scoped<S, Msg, V, FInit, FBuild, FUpdate>
+where
+    S: Send,
+    V: View<T, Msg>,
+    FInit: Fn() -> S + Send,
+    FBuild: Fn(&S) -> V + Send,
+    FUpdate: Fn(&mut S, Msg) + Send,
+{
+    type State = ScopedState<T, S, Msg, V>;
+
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let local = (self.f_init)();
+        let view = (self.f_build)(&local);
+        let (id, view_state, element) = view.build(cx);
+        let state = ScopedState {
+            local,
+            view,
+            view_state,
+            phantom: PhantomData,
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        _prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let view = (self.f_build)(&state.local);
+        let changes = view.rebuild(cx, &state.view, id, &mut state.view_state, element);
+        state.view = view;
+        changes
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match state
+            .view
+            .message(id_path, &mut state.view_state, message, app_state)
+        {
+            MessageResult::Action(msg) => {
+                (self.f_update)(&mut state.local, msg);
+                MessageResult::RequestRebuild
+            }
+            MessageResult::Actions(msgs) => {
+                for msg in msgs {
+                    (self.f_update)(&mut state.local, msg);
+                }
+                MessageResult::RequestRebuild
+            }
+            MessageResult::RequestRebuild => MessageResult::RequestRebuild,
+            MessageResult::Nop => MessageResult::Nop,
+            MessageResult::Stale(message) => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::super::button::Button;
+    use super::*;
+    use crate::view::{button, memoize, ViewSequence};
+    use crate::widget::test_util::view_cx;
+    use crate::widget::PodSlots;
+    use test_log::test;
+
+    enum Msg {
+        Increment,
+    }
+
+    type Counter = Scoped<
+        i32,
+        Msg,
+        Button<(), Msg, String>,
+        fn() -> i32,
+        fn(&i32) -> Button<(), Msg, String>,
+        fn(&mut i32, Msg),
+    >;
+
+    thread_local! {
+        static INITS: Cell<usize> = Cell::new(0);
+    }
+
+    fn counter() -> Counter {
+        scoped(
+            || {
+                INITS.with(|inits| inits.set(inits.get() + 1));
+                0
+            },
+            |count| button(format!("{count}"), |_| Msg::Increment),
+            |count, msg| match msg {
+                Msg::Increment => *count += 1,
+            },
+        )
+    }
+
+    #[test]
+    fn messages_run_update_and_request_rebuild() {
+        let mut cx = view_cx();
+
+        let view = counter();
+        let (_, mut state, _) = View::<()>::build(&view, &mut cx);
+        let result = View::<()>::message(&view, &[], &mut state, Message::new(vec![], ()), &mut ());
+        assert!(matches!(result, MessageResult::RequestRebuild));
+        assert_eq!(*state.local(), 1);
+
+        let stale = [Id::next()];
+        let result =
+            View::<()>::message(&view, &stale, &mut state, Message::new(vec![], ()), &mut ());
+        assert!(matches!(result, MessageResult::Stale(_)));
+        assert_eq!(*state.local(), 1);
+    }
+
+    #[test]
+    fn rebuild_diffs_the_regenerated_view() {
+        let mut cx = view_cx();
+
+        let prev = counter();
+        let (mut id, mut state, mut element) = View::<()>::build(&prev, &mut cx);
+        let next = counter();
+        let changes = View::<()>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+        assert!(changes.is_empty());
+
+        View::<()>::message(&next, &[], &mut state, Message::new(vec![], ()), &mut ());
+        let changes = View::<()>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+        assert!(!changes.is_empty());
+    }
+
+    #[test]
+    fn local_state_survives_parent_rebuilds() {
+        let mut cx = view_cx();
+
+        let mut prev = counter();
+        let (mut id, mut state, mut element) = View::<()>::build(&prev, &mut cx);
+        View::<()>::message(&prev, &[], &mut state, Message::new(vec![], ()), &mut ());
+        for _ in 0..3 {
+            let next = counter();
+            View::<()>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+            prev = next;
+        }
+        assert_eq!(*state.local(), 1);
+        assert_eq!(INITS.with(Cell::get), 1);
+    }
+
+    #[test]
+    fn siblings_have_their_own_state() {
+        let mut cx = view_cx();
+
+        let siblings = (counter(), counter());
+        let mut elements = vec![];
+        let mut state = ViewSequence::<()>::build(&siblings, &mut cx, &mut elements);
+        let second = state.1 .1;
+        let result = ViewSequence::<()>::message(
+            &siblings,
+            &[second],
+            &mut state,
+            Message::new(vec![], ()),
+            &mut (),
+        );
+        // The rebuild request reaches the root although no action does.
+        assert!(matches!(result, MessageResult::RequestRebuild));
+        assert!(result.handled());
+
+        assert_eq!(*state.0 .0.local(), 0);
+        assert_eq!(*state.1 .0.local(), 1);
+    }
+
+    /// A counter behind a memoized parent, which counts how often its view is generated.
+    fn memoized_counter(builds: &Arc<AtomicUsize>) -> impl View<()> + ViewMarker {
+        let builds = builds.clone();
+        memoize((), move |_| {
+            let builds = builds.clone();
+            scoped(
+                || 0,
+                move |count: &i32| {
+                    builds.fetch_add(1, Ordering::Relaxed);
+                    button(format!("{count}"), |_: &mut ()| Msg::Increment)
+                },
+                |count: &mut i32, msg: Msg| match msg {
+                    Msg::Increment => *count += 1,
+                },
+            )
+        })
+    }
+
+    #[test]
+    fn request_rebuild_regenerates_only_its_subtree() {
+        let mut cx = view_cx();
+        let (first_builds, second_builds) =
+            (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+        let siblings = || {
+            (
+                memoized_counter(&first_builds),
+                memoized_counter(&second_builds),
+            )
+        };
+
+        let prev = siblings();
+        let mut elements = vec![];
+        let mut state = ViewSequence::<()>::build(&prev, &mut cx, &mut elements);
+        let mut slots = PodSlots::from(elements);
+        let second = state.1 .1;
+        let result = ViewSequence::<()>::message(
+            &prev,
+            &[second],
+            &mut state,
+            Message::new(vec![], ()),
+            &mut (),
+        );
+        assert!(matches!(result, MessageResult::RequestRebuild));
+
+        // The app rebuilds from the root, but only the counter which asked for it is regenerated.
+        let next = siblings();
+        let changes =
+            ViewSequence::<()>::rebuild(&next, &mut cx, &prev, &mut state, &mut slots.splice());
+        assert!(changes.contains(ChangeFlags::LAYOUT));
+        assert_eq!(first_builds.load(Ordering::Relaxed), 1);
+        assert_eq!(second_builds.load(Ordering::Relaxed), 2);
+
+        // Without another request, neither of them is.
+        let last = siblings();
+        let changes =
+            ViewSequence::<()>::rebuild(&last, &mut cx, &next, &mut state, &mut slots.splice());
+        assert!(changes.is_empty());
+        assert_eq!(first_builds.load(Ordering::Relaxed), 1);
+        assert_eq!(second_builds.load(Ordering::Relaxed), 2);
+    }
+}
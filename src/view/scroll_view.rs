@@ -60,7 +60,7 @@ where
         element: &mut Self::Element,
     ) -> bool {
         cx.with_id(*id, |cx| {
-            let child_element = element.child_mut().downcast_mut().unwrap();
+            let child_element = element.child_mut().expect_mut();
             let changed =
                 self.child
                     .rebuild(cx, &prev.child, &mut state.0, &mut state.1, child_element);
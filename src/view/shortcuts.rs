@@ -0,0 +1,159 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Pod, ShortcutScope};
+use crate::{HotKey, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Keyboard shortcuts on a child view, see [`on_key`] and [`shortcut_scope`].
+pub struct Shortcuts<T, A, V> {
+    child: V,
+    scope: ShortcutScope,
+    // consider not boxing
+    shortcuts: Vec<(HotKey, Box<dyn Fn(&mut T) -> A + Send>)>,
+}
+
+/// Produces the action returned by `pressed` when `hotkey` is pressed while keyboard focus is
+/// inside `child`, see [`ViewExt::on_key`](super::ViewExt::on_key).
+///
+/// Shortcuts with modifiers win over the focused widget, plain keys are only used when the
+/// focused widget doesn't handle them, so a focused text field still gets them.
+pub fn on_key<T, A, V>(
+    child: V,
+    hotkey: HotKey,
+    pressed: impl Fn(&mut T) -> A + Send + 'static,
+) -> Shortcuts<T, A, V> {
+    Shortcuts {
+        child,
+        scope: ShortcutScope::Focus,
+        shortcuts: vec![(hotkey, Box::new(pressed))],
+    }
+}
+
+/// Shortcuts which work anywhere in the window while `child` is shown, added with
+/// [`shortcut`](Shortcuts::shortcut).
+pub fn shortcut_scope<T, A, V>(child: V) -> Shortcuts<T, A, V> {
+    Shortcuts {
+        child,
+        scope: ShortcutScope::Window,
+        shortcuts: vec![],
+    }
+}
+
+impl<T, A, V> Shortcuts<T, A, V> {
+    /// Adds a shortcut producing the action returned by `pressed`.
+    pub fn shortcut(
+        mut self,
+        hotkey: HotKey,
+        pressed: impl Fn(&mut T) -> A + Send + 'static,
+    ) -> Self {
+        self.shortcuts.push((hotkey, Box::new(pressed)));
+        self
+    }
+
+    fn hotkeys(&self) -> Vec<HotKey> {
+        self.shortcuts
+            .iter()
+            .map(|(hotkey, _)| hotkey.clone())
+            .collect()
+    }
+}
+
+impl<T, A, V> ViewMarker for Shortcuts<T, A, V> {}
+
+impl<T, A, V: View<T, A>> View<T, A> for Shortcuts<T, A, V> {
+    type State = (Id, V::State);
+
+    type Element = widget::Shortcuts;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let element =
+                widget::Shortcuts::new(cx.id_path(), Pod::new(child), self.hotkeys(), self.scope);
+            ((child_id, child_state), element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let (child_id, child_state) = state;
+        let hotkeys = self.hotkeys();
+        if hotkeys != prev.hotkeys() {
+            element.set_hotkeys(hotkeys);
+        }
+        if self.scope != prev.scope {
+            element.set_scope(self.scope);
+        }
+        cx.with_id(*id, |cx| {
+            let child = element.child_mut();
+            let changes =
+                self.child
+                    .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+            child.mark(changes)
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let (child_id, child_state) = state;
+        match id_path.split_first() {
+            Some((first, rest)) if first == child_id => {
+                self.child.message(rest, child_state, message, app_state)
+            }
+            Some(_) => MessageResult::Stale(message),
+            None => match message.downcast::<usize>() {
+                Ok(index) => match self.shortcuts.get(index) {
+                    Some((_, pressed)) => MessageResult::Action(pressed(app_state)),
+                    None => MessageResult::Nop,
+                },
+                Err(message) => MessageResult::Stale(message),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::view_cx;
+    use crate::RawMods;
+    use test_log::test;
+
+    #[test]
+    fn messages_call_the_shortcut_pressed() {
+        let mut cx = view_cx();
+        let view = shortcut_scope("editor")
+            .shortcut(HotKey::new(RawMods::Ctrl, "s"), |_: &mut ()| "save")
+            .shortcut(HotKey::new(RawMods::Ctrl, "o"), |_: &mut ()| "open");
+        let (_, mut state, _) = View::<(), &str>::build(&view, &mut cx);
+
+        let result = view.message(&[], &mut state, Message::new(vec![], 1usize), &mut ());
+        assert!(matches!(result, MessageResult::Action("open")));
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+use crate::geometry::Axis;
+use crate::view::ViewMarker;
+use crate::widget::SliderMessage;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A slider displaying a value owned by the app state.
+///
+/// `on_change` is invoked with every new value while the thumb is dragged and after each keyboard
+/// step, `on_commit` once the user finished changing the value.
+pub struct Slider<T, A> {
+    value: f64,
+    range: Range<f64>,
+    step: Option<f64>,
+    axis: Axis,
+    // consider not boxing
+    on_change: Box<dyn Fn(&mut T, f64) -> A + Send>,
+    on_commit: Option<Box<dyn Fn(&mut T, f64) -> A + Send>>,
+}
+
+pub fn slider<T, A>(
+    value: f64,
+    range: Range<f64>,
+    on_change: impl Fn(&mut T, f64) -> A + Send + 'static,
+) -> Slider<T, A> {
+    Slider::new(value, range, on_change)
+}
+
+impl<T, A> Slider<T, A> {
+    pub fn new(
+        value: f64,
+        range: Range<f64>,
+        on_change: impl Fn(&mut T, f64) -> A + Send + 'static,
+    ) -> Self {
+        Slider {
+            value,
+            range,
+            step: None,
+            axis: Axis::Horizontal,
+            on_change: Box::new(on_change),
+            on_commit: None,
+        }
+    }
+
+    /// Rounds the value to multiples of `step`, counted from the start of the range.
+    pub fn with_step(mut self, step: f64) -> Self {
+        self.step = Some(step);
+        self
+    }
+
+    pub fn with_axis(mut self, axis: Axis) -> Self {
+        self.axis = axis;
+        self
+    }
+
+    pub fn on_commit(mut self, on_commit: impl Fn(&mut T, f64) -> A + Send + 'static) -> Self {
+        self.on_commit = Some(Box::new(on_commit));
+        self
+    }
+}
+
+impl<T, A> ViewMarker for Slider<T, A> {}
+
+impl<T, A> View<T, A> for Slider<T, A> {
+    type State = ();
+
+    type Element = crate::widget::Slider;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, element) = cx.with_new_id(|cx| {
+            let mut element =
+                crate::widget::Slider::new(cx.id_path(), self.value, self.range.clone(), self.axis);
+            let _ = element.set_step(self.step);
+            element
+        });
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changes = ChangeFlags::empty();
+        if self.range != prev.range {
+            changes |= element.set_range(self.range.clone());
+        }
+        if self.step != prev.step {
+            changes |= element.set_step(self.step);
+        }
+        if self.axis != prev.axis {
+            changes |= element.set_axis(self.axis);
+        }
+        changes | element.set_value(self.value)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if !id_path.is_empty() {
+            return MessageResult::Stale(message);
+        }
+        match message.downcast::<SliderMessage>() {
+            Ok(slider_message) => match slider_message {
+                SliderMessage::Changed(value) => {
+                    MessageResult::Action((self.on_change)(app_state, value))
+                }
+                SliderMessage::Committed(value) => match &self.on_commit {
+                    Some(on_commit) => MessageResult::Action(on_commit(app_state, value)),
+                    None => MessageResult::Nop,
+                },
+            },
+            Err(message) => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{view_cx, Harness};
+    use crate::widget::{BoxConstraints, Event, Pod};
+    use test_log::test;
+
+    fn request(pod: &Pod, action: accesskit::Action, data: Option<accesskit::ActionData>) -> Event {
+        Event::TargetedAccessibilityAction(accesskit::ActionRequest {
+            action,
+            target: pod.id().into(),
+            data,
+        })
+    }
+
+    #[test]
+    fn accessibility_set_value_reaches_on_change() {
+        let mut cx = view_cx();
+        let mut harness = Harness::new();
+        let mut value = 0.5;
+
+        let view = slider(value, 0.0..1.0, |value: &mut f64, new| *value = new);
+        let (_, mut state, element) = view.build(&mut cx);
+        let mut pod = Pod::new(element);
+        harness.layout(&mut pod, &BoxConstraints::UNBOUNDED);
+
+        let set_value = request(
+            &pod,
+            accesskit::Action::SetValue,
+            Some(accesskit::ActionData::NumericValue(0.25)),
+        );
+        harness.event(&mut pod, set_value);
+        for message in harness.take_messages() {
+            let id_path = message.id_path.clone();
+            let _ = view.message(&id_path[1..], &mut state, message, &mut value);
+        }
+        assert_eq!(value, 0.25);
+
+        let focus = request(&pod, accesskit::Action::Focus, None);
+        harness.event(&mut pod, focus);
+        assert_eq!(harness.window_state.focus, Some(pod.id()));
+    }
+}
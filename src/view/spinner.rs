@@ -0,0 +1,119 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vello::peniko::Color;
+
+use crate::view::ViewMarker;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A busy indicator, see [`spinner`].
+pub struct Spinner {
+    size: f64,
+    color: Color,
+}
+
+/// Creates a rotating arc showing that work is in progress.
+///
+/// It animates by itself while it is visible, the app state isn't involved.
+pub fn spinner() -> Spinner {
+    Spinner {
+        size: crate::widget::Spinner::DEFAULT_SIZE,
+        color: crate::widget::Spinner::DEFAULT_COLOR,
+    }
+}
+
+impl Spinner {
+    /// Sets the diameter of the arc.
+    pub fn size(mut self, size: f64) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl ViewMarker for Spinner {}
+
+impl<T, A> View<T, A> for Spinner {
+    type State = ();
+
+    type Element = crate::widget::Spinner;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, element) = cx.with_new_id(|_| crate::widget::Spinner::new(self.size, self.color));
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        element.set_size(self.size) | element.set_color(self.color)
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    #[test]
+    fn color_changes_only_repaint() {
+        let mut cx = view_cx();
+        let view = spinner();
+        let (mut id, mut state, mut element) = View::<(), ()>::build(&view, &mut cx);
+
+        let recolored = spinner().color(Color::RED);
+        let changes = View::<(), ()>::rebuild(
+            &recolored,
+            &mut cx,
+            &view,
+            &mut id,
+            &mut state,
+            &mut element,
+        );
+        assert_eq!(changes, ChangeFlags::PAINT);
+
+        let resized = spinner().color(Color::RED).size(48.0);
+        let changes = View::<(), ()>::rebuild(
+            &resized,
+            &mut cx,
+            &recolored,
+            &mut id,
+            &mut state,
+            &mut element,
+        );
+        assert!(changes.contains(ChangeFlags::LAYOUT));
+    }
+}
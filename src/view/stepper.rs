@@ -0,0 +1,128 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+use crate::view::ViewMarker;
+use crate::widget::{DecimalFormat, NumberFormat};
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A number field with increment and decrement buttons, see [`stepper`].
+pub struct Stepper<T, A, F = DecimalFormat> {
+    value: f64,
+    range: Range<f64>,
+    step: f64,
+    format: F,
+    // consider not boxing
+    on_change: Box<dyn Fn(&mut T, f64) -> A + Send>,
+}
+
+/// A field displaying a number owned by the app state, changed by `step` with buttons, the arrow
+/// keys and the mouse wheel, or by typing.
+///
+/// `on_change` is invoked with each new value, which is always inside `range`. The number is
+/// shown with as many decimal places as `step` has, see [`Stepper::with_format`] for other
+/// formats.
+pub fn stepper<T, A>(
+    value: f64,
+    range: Range<f64>,
+    step: f64,
+    on_change: impl Fn(&mut T, f64) -> A + Send + 'static,
+) -> Stepper<T, A> {
+    Stepper {
+        value,
+        range,
+        step,
+        format: DecimalFormat::for_step(step),
+        on_change: Box::new(on_change),
+    }
+}
+
+impl<T, A, F> Stepper<T, A, F> {
+    /// Sets how the number is turned into text and how typed text is read.
+    pub fn with_format<G: NumberFormat + Clone + PartialEq + 'static>(
+        self,
+        format: G,
+    ) -> Stepper<T, A, G> {
+        Stepper {
+            value: self.value,
+            range: self.range,
+            step: self.step,
+            format,
+            on_change: self.on_change,
+        }
+    }
+}
+
+impl<T, A, F> ViewMarker for Stepper<T, A, F> {}
+
+impl<T, A, F: NumberFormat + Clone + PartialEq + 'static> View<T, A> for Stepper<T, A, F> {
+    type State = ();
+
+    type Element = crate::widget::Stepper;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, element) = cx.with_new_id(|cx| {
+            crate::widget::Stepper::new(
+                cx.id_path(),
+                self.value,
+                self.range.clone(),
+                self.step,
+                Box::new(self.format.clone()),
+            )
+        });
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changes = ChangeFlags::empty();
+        if self.range != prev.range {
+            changes |= element.set_range(self.range.clone());
+        }
+        if self.step != prev.step {
+            changes |= element.set_step(self.step);
+        }
+        if self.format != prev.format {
+            changes |= element.set_format(Box::new(self.format.clone()));
+        }
+        changes | element.set_value(self.value)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if !id_path.is_empty() {
+            return MessageResult::Stale(message);
+        }
+        match message.downcast::<f64>() {
+            Ok(value) => MessageResult::Action((self.on_change)(app_state, value)),
+            // The text being typed stays in the widget until it is committed.
+            Err(message) if message.is::<String>() => MessageResult::Nop,
+            Err(message) => MessageResult::Stale(message),
+        }
+    }
+}
@@ -0,0 +1,293 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+
+use futures_core::Stream;
+use futures_task::{Context, Poll, Waker};
+use xilem_core::AsyncWake;
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A view handling the items of a stream, see [`subscribe`].
+pub struct Subscribe<K, SF, H> {
+    key: K,
+    stream_factory: SF,
+    on_item: H,
+    latest_only: bool,
+}
+
+pub struct SubscribeState<M> {
+    /// Dropped when the stream ended or the key changed, which cancels it.
+    stream: Option<Pin<Box<dyn Stream<Item = M> + Send>>>,
+    waker: Waker,
+}
+
+/// Invokes `on_item` with the app state for each item of the stream created by
+/// `stream_factory`, the return value is the action of this view.
+///
+/// The stream is created from `key` when the view is built and again whenever a rebuild changes
+/// the key, dropping the previous stream. It is polled on the app's task, which is woken
+/// through the id path of this view, one item per wake. Removing the view drops the stream,
+/// wakes still on their way find no view and are stale. The view has no visual output.
+pub fn subscribe<T, A, K, SF, S, H>(key: K, stream_factory: SF, on_item: H) -> Subscribe<K, SF, H>
+where
+    K: PartialEq + Send,
+    SF: Fn(&K) -> S + Send,
+    S: Stream + Send + 'static,
+    H: Fn(&mut T, S::Item) -> A + Send,
+{
+    Subscribe {
+        key,
+        stream_factory,
+        on_item,
+        latest_only: false,
+    }
+}
+
+impl<K, SF, H> Subscribe<K, SF, H> {
+    /// Skips items which are superseded by a later one before they were handled, for streams
+    /// where only the latest value matters.
+    pub fn latest_only(mut self) -> Self {
+        self.latest_only = true;
+        self
+    }
+}
+
+impl<M> SubscribeState<M> {
+    /// Starts `stream`, it is first polled in the message of the wake this sends.
+    fn start(&mut self, stream: impl Stream<Item = M> + Send + 'static) {
+        self.stream = Some(Box::pin(stream));
+        self.waker.wake_by_ref();
+    }
+
+    /// The next item which is ready, ends the subscription when the stream ended.
+    fn poll_next(&mut self) -> Option<M> {
+        let stream = self.stream.as_mut()?;
+        match stream
+            .as_mut()
+            .poll_next(&mut Context::from_waker(&self.waker))
+        {
+            Poll::Ready(Some(item)) => Some(item),
+            Poll::Ready(None) => {
+                self.stream = None;
+                None
+            }
+            Poll::Pending => None,
+        }
+    }
+}
+
+impl<K, SF, H> ViewMarker for Subscribe<K, SF, H> {}
+
+impl<T, A, K, SF, S, H> View<T, A> for Subscribe<K, SF, H>
+where
+    K: PartialEq + Send,
+    SF: Fn(&K) -> S + Send,
+    S: Stream + Send + 'static,
+    S::Item: Send,
+    H: Fn(&mut T, S::Item) -> A + Send,
+{
+    type State = SubscribeState<S::Item>;
+
+    type Element = widget::Empty;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, state) = cx.with_new_id(|cx| {
+            let mut state = SubscribeState {
+                stream: None,
+                waker: cx.waker(),
+            };
+            state.start((self.stream_factory)(&self.key));
+            state
+        });
+        (id, state, widget::Empty)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        _element: &mut Self::Element,
+    ) -> ChangeFlags {
+        if prev.key != self.key {
+            // Replacing the stream drops the previous one.
+            state.start((self.stream_factory)(&self.key));
+        }
+        ChangeFlags::empty()
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if !id_path.is_empty() || !message.is::<AsyncWake>() {
+            return MessageResult::Stale(message);
+        }
+        let item = if self.latest_only {
+            std::iter::from_fn(|| state.poll_next()).last()
+        } else {
+            let item = state.poll_next();
+            if item.is_some() {
+                // More items may be ready, they are handled one per wake.
+                state.waker.wake_by_ref();
+            }
+            item
+        };
+        match item {
+            Some(item) => MessageResult::Action((self.on_item)(app_state, item)),
+            None => MessageResult::Nop,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::Receiver;
+
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+    use super::*;
+    use crate::widget::test_util::view_cx_with_messages;
+    use crate::Message;
+    use test_log::test;
+
+    /// A stream of the values sent through a channel.
+    struct Channel(UnboundedReceiver<u32>);
+
+    impl Stream for Channel {
+        type Item = u32;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<u32>> {
+            self.0.poll_recv(cx)
+        }
+    }
+
+    /// Hands out a new channel per key, the test keeps the senders.
+    fn view(
+        key: u32,
+        senders: &std::sync::mpsc::Sender<(u32, UnboundedSender<u32>)>,
+        latest_only: bool,
+    ) -> impl View<Vec<(u32, u32)>, Element = widget::Empty> + ViewMarker {
+        let senders = senders.clone();
+        let view = subscribe(
+            key,
+            move |key| {
+                let (sender, receiver) = unbounded_channel();
+                senders.send((*key, sender)).unwrap();
+                Channel(receiver)
+            },
+            move |items: &mut Vec<(u32, u32)>, item| items.push((key, item)),
+        );
+        if latest_only {
+            view.latest_only()
+        } else {
+            view
+        }
+    }
+
+    /// Delivers the wakes which arrived so far, as the app task does.
+    fn deliver<V: View<Vec<(u32, u32)>>>(
+        wakes: &Receiver<Message>,
+        view: &V,
+        state: &mut V::State,
+        items: &mut Vec<(u32, u32)>,
+    ) {
+        while let Ok(wake) = wakes.try_recv() {
+            view.message(&[], state, wake, items);
+        }
+    }
+
+    #[test]
+    fn items_are_handled_in_order() {
+        let (mut cx, wakes) = view_cx_with_messages();
+        let (senders, streams) = std::sync::mpsc::channel();
+        let mut items = vec![];
+
+        let view = view(1, &senders, false);
+        let (_, mut state, _) = view.build(&mut cx);
+        let (_, sender) = streams.try_recv().unwrap();
+        deliver(&wakes, &view, &mut state, &mut items);
+        for item in 0..3 {
+            sender.send(item).unwrap();
+        }
+        deliver(&wakes, &view, &mut state, &mut items);
+        assert_eq!(items, vec![(1, 0), (1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn key_change_restarts_the_subscription() {
+        let (mut cx, wakes) = view_cx_with_messages();
+        let (senders, streams) = std::sync::mpsc::channel();
+        let mut items = vec![];
+
+        let first = view(1, &senders, false);
+        let (mut id, mut state, mut element) = first.build(&mut cx);
+        let (_, old) = streams.try_recv().unwrap();
+        deliver(&wakes, &first, &mut state, &mut items);
+
+        let same = view(1, &senders, false);
+        same.rebuild(&mut cx, &first, &mut id, &mut state, &mut element);
+        assert!(streams.try_recv().is_err());
+
+        let second = view(2, &senders, false);
+        second.rebuild(&mut cx, &same, &mut id, &mut state, &mut element);
+        let (key, new) = streams.try_recv().unwrap();
+        assert_eq!(key, 2);
+        // The previous stream was dropped with its receiver.
+        assert!(old.send(10).is_err());
+        new.send(20).unwrap();
+        deliver(&wakes, &second, &mut state, &mut items);
+        assert_eq!(items, vec![(2, 20)]);
+    }
+
+    #[test]
+    fn removal_cancels_the_stream() {
+        let (mut cx, _wakes) = view_cx_with_messages();
+        let (senders, streams) = std::sync::mpsc::channel();
+
+        let (_, state, _) = view(1, &senders, false).build(&mut cx);
+        let (_, sender) = streams.try_recv().unwrap();
+        assert!(!sender.is_closed());
+        drop(state);
+        assert!(sender.is_closed());
+    }
+
+    #[test]
+    fn latest_only_coalesces_bursts() {
+        let (mut cx, wakes) = view_cx_with_messages();
+        let (senders, streams) = std::sync::mpsc::channel();
+        let mut items = vec![];
+
+        let view = view(1, &senders, true);
+        let (_, mut state, _) = view.build(&mut cx);
+        let (_, sender) = streams.try_recv().unwrap();
+        for item in 0..5 {
+            sender.send(item).unwrap();
+        }
+        deliver(&wakes, &view, &mut state, &mut items);
+        sender.send(5).unwrap();
+        deliver(&wakes, &view, &mut state, &mut items);
+        assert_eq!(items, vec![(1, 4), (1, 5)]);
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::ViewMarker;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// An on/off switch displaying a value owned by the app state, see [`switch`].
+pub struct Switch<T, A> {
+    is_on: bool,
+    // consider not boxing
+    callback: Box<dyn Fn(&mut T, bool) -> A + Send>,
+}
+
+/// Creates a switch.
+///
+/// The callback receives the new value once a click, drag or key press is committed. Rebuilding
+/// with a different value moves the thumb there, unless the user is dragging it.
+pub fn switch<T, A>(
+    is_on: bool,
+    on_change: impl Fn(&mut T, bool) -> A + Send + 'static,
+) -> Switch<T, A> {
+    Switch {
+        is_on,
+        callback: Box::new(on_change),
+    }
+}
+
+impl<T, A> ViewMarker for Switch<T, A> {}
+
+impl<T, A> View<T, A> for Switch<T, A> {
+    type State = ();
+
+    type Element = crate::widget::Switch;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, element) =
+            cx.with_new_id(|cx| crate::widget::Switch::new(cx.id_path(), self.is_on));
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        element.set_on(self.is_on)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if !id_path.is_empty() {
+            return MessageResult::Stale(message);
+        }
+        match message.downcast::<bool>() {
+            Ok(is_on) => MessageResult::Action((self.callback)(app_state, is_on)),
+            Err(message) => MessageResult::Stale(message),
+        }
+    }
+}
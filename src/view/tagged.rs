@@ -0,0 +1,130 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Enters the widget of a child view into the registry of the window, see [`tagged`].
+pub struct Tagged<V> {
+    child: V,
+    tag: Option<String>,
+}
+
+/// Makes the widget of `child` findable with [`App::find_widget`](crate::App::find_widget) by
+/// `tag`, and by the id of this view, until it is removed from the tree.
+///
+/// The handle found sends messages like [`WidgetMessage::Focus`](crate::widget::WidgetMessage)
+/// to the widget. A tag names one view at a time, the view built last keeps it. This is the
+/// same as [`ViewExt::tagged`](super::ViewExt::tagged).
+pub fn tagged<V>(tag: impl Into<String>, child: V) -> Tagged<V> {
+    Tagged {
+        child,
+        tag: Some(tag.into()),
+    }
+}
+
+impl<V> ViewMarker for Tagged<V> {}
+
+impl<T, A, V: View<T, A>> View<T, A> for Tagged<V>
+where
+    V::Element: 'static,
+{
+    type State = (Id, V::State);
+
+    type Element = widget::Tagged;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let element = widget::Tagged::new(
+                cx.registry(),
+                cx.id_path(),
+                self.tag.clone(),
+                Pod::new(child),
+            );
+            ((child_id, child_state), element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let (child_id, child_state) = state;
+        cx.with_id(*id, |cx| {
+            if self.tag != prev.tag {
+                element.set_tag(cx.id_path(), self.tag.clone());
+            }
+            let child = element.child_mut();
+            let changes =
+                self.child
+                    .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+            child.mark(changes)
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let (child_id, child_state) = state;
+        match id_path.split_first() {
+            Some((first, rest)) if first == child_id => {
+                self.child.message(rest, child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::{label, ViewExt};
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    #[test]
+    fn the_registry_follows_the_tag() {
+        let mut cx = view_cx();
+        let view = label("Sidebar").tagged("sidebar");
+        let (mut id, mut state, mut element) = View::<(), ()>::build(&view, &mut cx);
+        let handle = cx.registry().find_widget("sidebar").unwrap();
+        assert_eq!(handle.id_path(), &vec![id]);
+        assert_eq!(
+            cx.registry().find_widget(id).unwrap().widget_id(),
+            handle.widget_id()
+        );
+
+        let renamed = label("Sidebar").tagged("navigation");
+        let _ =
+            View::<(), ()>::rebuild(&renamed, &mut cx, &view, &mut id, &mut state, &mut element);
+        assert!(cx.registry().find_widget("sidebar").is_none());
+        assert!(cx.registry().find_widget("navigation").is_some());
+
+        drop(element);
+        assert!(cx.registry().is_empty());
+    }
+}
@@ -12,19 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::any::Any;
-
-use crate::{event::MessageResult, id::Id, widget::ChangeFlags};
+use crate::view::{Id, ViewMarker};
+use crate::widget::{ChangeFlags, TextWidget};
+use crate::{Message, MessageResult};
 
 use super::{Cx, View};
 
+impl ViewMarker for String {}
+
 impl<T, A> View<T, A> for String {
     type State = ();
 
-    type Element = crate::widget::text::TextWidget;
+    type Element = TextWidget;
 
     fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
-        let (id, element) = cx.with_new_id(|_| crate::widget::text::TextWidget::new(self.clone()));
+        let (id, element) = cx.with_new_id(|_| TextWidget::new(self.clone()));
         (id, (), element)
     }
 
@@ -32,7 +34,7 @@ impl<T, A> View<T, A> for String {
         &self,
         _cx: &mut Cx,
         prev: &Self,
-        _id: &mut crate::id::Id,
+        _id: &mut Id,
         _state: &mut Self::State,
         element: &mut Self::Element,
     ) -> ChangeFlags {
@@ -43,13 +45,51 @@ impl<T, A> View<T, A> for String {
         }
     }
 
-    fn event(
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
+
+impl ViewMarker for &'static str {}
+
+impl<T, A> View<T, A> for &'static str {
+    type State = ();
+
+    type Element = TextWidget;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, element) = cx.with_new_id(|_| TextWidget::new(self.to_string()));
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        if prev != self {
+            element.set_text(self.to_string())
+        } else {
+            ChangeFlags::empty()
+        }
+    }
+
+    fn message(
         &self,
-        _id_path: &[crate::id::Id],
+        _id_path: &[Id],
         _state: &mut Self::State,
-        _event: Box<dyn Any>,
+        message: Message,
         _app_state: &mut T,
     ) -> MessageResult<A> {
-        MessageResult::Stale
+        MessageResult::Stale(message)
     }
 }
@@ -0,0 +1,103 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::ViewMarker;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A multi-line text input, see [`text_area`].
+pub struct TextArea<T, A> {
+    value: String,
+    min_lines: usize,
+    max_lines: usize,
+    // consider not boxing
+    callback: Box<dyn Fn(&mut T, String) -> A + Send>,
+}
+
+/// Creates a text area showing `value`, wrapped at its width.
+///
+/// Like with [`textbox`](super::textbox), each edit invokes the callback with the edited text,
+/// which is displayed once a rebuild passes it back in as `value`. The area is between
+/// [`DEFAULT_MIN_LINES`] and [`DEFAULT_MAX_LINES`] lines high, see [`TextArea::with_lines`].
+///
+/// [`DEFAULT_MIN_LINES`]: crate::widget::TextArea::DEFAULT_MIN_LINES
+/// [`DEFAULT_MAX_LINES`]: crate::widget::TextArea::DEFAULT_MAX_LINES
+pub fn text_area<T, A>(
+    value: impl Into<String>,
+    on_change: impl Fn(&mut T, String) -> A + Send + 'static,
+) -> TextArea<T, A> {
+    TextArea {
+        value: value.into(),
+        min_lines: crate::widget::TextArea::DEFAULT_MIN_LINES,
+        max_lines: crate::widget::TextArea::DEFAULT_MAX_LINES,
+        callback: Box::new(on_change),
+    }
+}
+
+impl<T, A> TextArea<T, A> {
+    /// Sets how many lines high the area is at least and at most, longer texts scroll.
+    pub fn with_lines(mut self, min_lines: usize, max_lines: usize) -> Self {
+        self.min_lines = min_lines;
+        self.max_lines = max_lines;
+        self
+    }
+}
+
+impl<T, A> ViewMarker for TextArea<T, A> {}
+
+impl<T, A> View<T, A> for TextArea<T, A> {
+    type State = ();
+
+    type Element = crate::widget::TextArea;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, element) = cx.with_new_id(|cx| {
+            crate::widget::TextArea::new(
+                cx.id_path(),
+                self.value.clone(),
+                self.min_lines,
+                self.max_lines,
+            )
+        });
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        element.set_text(&self.value) | element.set_line_limits(self.min_lines, self.max_lines)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if !id_path.is_empty() {
+            return MessageResult::Stale(message);
+        }
+        match message.downcast::<String>() {
+            Ok(text) => MessageResult::Action((self.callback)(app_state, text)),
+            Err(message) => MessageResult::Stale(message),
+        }
+    }
+}
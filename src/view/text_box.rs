@@ -0,0 +1,109 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::ViewMarker;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A single line text input, see [`textbox`].
+pub struct TextBox<T, A> {
+    value: String,
+    // consider not boxing
+    callback: Box<dyn Fn(&mut T, String) -> A + Send>,
+}
+
+/// Creates a text box showing `value`.
+///
+/// The app state is the source of truth: each edit invokes the callback with the edited text,
+/// which is displayed once a rebuild passes it back in as `value`. Rebuilding with a different
+/// text replaces the content, keeping the caret where it still fits.
+pub fn textbox<T, A>(
+    value: impl Into<String>,
+    on_change: impl Fn(&mut T, String) -> A + Send + 'static,
+) -> TextBox<T, A> {
+    TextBox {
+        value: value.into(),
+        callback: Box::new(on_change),
+    }
+}
+
+impl<T, A> ViewMarker for TextBox<T, A> {}
+
+impl<T, A> View<T, A> for TextBox<T, A> {
+    type State = ();
+
+    type Element = crate::widget::TextBox;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, element) =
+            cx.with_new_id(|cx| crate::widget::TextBox::new(cx.id_path(), self.value.clone()));
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        element.set_text(&self.value)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if !id_path.is_empty() {
+            return MessageResult::Stale(message);
+        }
+        match message.downcast::<String>() {
+            Ok(text) => MessageResult::Action((self.callback)(app_state, text)),
+            Err(message) => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    #[test]
+    fn edits_round_trip_through_app_state() {
+        let mut cx = view_cx();
+        let mut text = String::from("héllo");
+
+        let view = textbox(text.clone(), |text: &mut String, new| *text = new);
+        let (mut id, mut state, mut element) = view.build(&mut cx);
+        assert_eq!(element.selection(), 6..6);
+
+        let edited = Message::new(vec![], String::from("héllo!"));
+        let result = view.message(&[], &mut state, edited, &mut text);
+        assert!(matches!(result, MessageResult::Action(())));
+        assert_eq!(text, "héllo!");
+
+        // The app uppercasing the text replaces what the widget shows.
+        let next = textbox(text.to_uppercase(), |text: &mut String, new| *text = new);
+        let changes = next.rebuild(&mut cx, &view, &mut id, &mut state, &mut element);
+        assert!(changes.contains(ChangeFlags::LAYOUT));
+        assert_eq!(element.text(), "HÉLLO!");
+    }
+}
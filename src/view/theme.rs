@@ -0,0 +1,214 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::theme::THEME;
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Pod};
+use crate::{Env, Message, MessageResult, Theme, ThemeOverride};
+
+use super::{Cx, View};
+
+/// Sets or changes the [`Theme`] of a child view, see [`themed`] and [`with_theme`].
+pub struct Themed<V> {
+    styling: Styling,
+    child: V,
+}
+
+enum Styling {
+    Replace(Theme),
+    Override(ThemeOverride),
+}
+
+pub struct ThemedState<S> {
+    child_id: Id,
+    child_state: S,
+    /// The theme set for the child, and the environment it was derived from.
+    theme: Theme,
+    outer: Env,
+}
+
+/// Changes the parts of the theme set in `theme_override` for `child` and everything below it.
+///
+/// The other parts come from the theme of the views above. When only colors change, the
+/// widgets below are painted again without being laid out.
+pub fn themed<V>(theme_override: ThemeOverride, child: V) -> Themed<V> {
+    Themed {
+        styling: Styling::Override(theme_override),
+        child,
+    }
+}
+
+/// Sets the whole theme for `child` and everything below it, for example [`Theme::light`].
+pub fn with_theme<V>(theme: Theme, child: V) -> Themed<V> {
+    Themed {
+        styling: Styling::Replace(theme),
+        child,
+    }
+}
+
+impl<V> Themed<V> {
+    fn resolve(&self, env: &Env) -> Theme {
+        match &self.styling {
+            Styling::Replace(theme) => theme.clone(),
+            Styling::Override(theme_override) => theme_override.apply(Theme::get(env)),
+        }
+    }
+}
+
+impl<V> ViewMarker for Themed<V> {}
+
+impl<T, A, V: View<T, A>> View<T, A> for Themed<V>
+where
+    V::Element: 'static,
+{
+    type State = ThemedState<V::State>;
+
+    type Element = widget::EnvScope;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let outer = cx.env().clone();
+            let theme = self.resolve(&outer);
+            cx.with_env(THEME, theme.clone(), |cx| {
+                let (child_id, child_state, child) = self.child.build(cx);
+                let element = widget::EnvScope::new(Pod::new(child), cx.env().clone());
+                let state = ThemedState {
+                    child_id,
+                    child_state,
+                    theme,
+                    outer,
+                };
+                (state, element)
+            })
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let theme = self.resolve(cx.env());
+            let layout = !theme.same_metrics(&state.theme)
+                || !cx.env().same_except(&state.outer, THEME.name());
+            state.outer = cx.env().clone();
+            state.theme = theme.clone();
+            cx.with_env(THEME, theme, |cx| {
+                let child = element.child_mut();
+                let changes = self.child.rebuild(
+                    cx,
+                    &prev.child,
+                    &mut state.child_id,
+                    &mut state.child_state,
+                    child.expect_mut(),
+                );
+                let changes = child.mark(changes);
+                let env = cx.env().clone();
+                changes
+                    | if layout {
+                        element.set_env(env)
+                    } else {
+                        element.set_env_paint_only(env)
+                    }
+            })
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path.split_first() {
+            Some((first, rest)) if *first == state.child_id => {
+                self.child
+                    .message(rest, &mut state.child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use vello::peniko::Color;
+
+    use super::*;
+    use crate::view::{env_reader, label, v_stack};
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    const RED: Color = Color::rgb8(0xff, 0, 0);
+    const GREEN: Color = Color::rgb8(0, 0xff, 0);
+
+    fn accent(accent: Color) -> ThemeOverride {
+        ThemeOverride {
+            accent: Some(accent),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn nested_override_wins_for_its_subtree_only() {
+        let mut cx = view_cx();
+        let seen = Arc::new(Mutex::new(vec![]));
+        let probe = || {
+            let seen = seen.clone();
+            env_reader((), move |env, _| {
+                let theme = env.get(THEME).cloned().unwrap_or_default();
+                seen.lock().unwrap().push(theme.accent);
+                label("")
+            })
+        };
+        let view = themed(
+            accent(RED),
+            v_stack((probe(), themed(accent(GREEN), probe()), probe())),
+        );
+        let _ = View::<()>::build(&view, &mut cx);
+        assert_eq!(*seen.lock().unwrap(), [RED, GREEN, RED]);
+        assert!(cx.env().get(THEME).is_none());
+    }
+
+    #[test]
+    fn only_metrics_need_layout() {
+        let mut cx = view_cx();
+        let prev = themed(accent(RED), label("text"));
+        let (mut id, mut state, mut element) = View::<()>::build(&prev, &mut cx);
+
+        let next = themed(accent(GREEN), label("text"));
+        let changes = View::<()>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+        assert!(changes.contains(ChangeFlags::DESCENDANT_REQUESTED_PAINT));
+        assert!(!changes.contains(ChangeFlags::LAYOUT));
+
+        let prev = next;
+        let next = themed(
+            ThemeOverride {
+                spacing: Some(12.0),
+                ..accent(GREEN)
+            },
+            label("text"),
+        );
+        let changes = View::<()>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+        assert!(changes.contains(ChangeFlags::LAYOUT));
+    }
+}
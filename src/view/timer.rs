@@ -0,0 +1,179 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use crate::view::ViewMarker;
+use crate::widget::{ChangeFlags, Ticker};
+use crate::{view::Id, Message, MessageResult};
+
+use super::{Cx, View};
+
+/// An invisible view invoking a callback after a delay, see [`interval`] and [`timeout`].
+pub struct Timer<T, A> {
+    period: Duration,
+    repeat: bool,
+    // consider not boxing
+    callback: Box<dyn Fn(&mut T) -> A + Send>,
+}
+
+/// Invokes `callback` every `period` while the view is in the tree.
+///
+/// The ticks keep to the schedule started when the view was built, a changed `period` restarts
+/// it from the rebuild. The return value of the callback is the action of this view.
+pub fn interval<T, A>(
+    period: Duration,
+    callback: impl Fn(&mut T) -> A + Send + 'static,
+) -> Timer<T, A> {
+    Timer {
+        period,
+        repeat: true,
+        callback: Box::new(callback),
+    }
+}
+
+/// Invokes `callback` once, `delay` after the view was built.
+///
+/// A changed `delay` restarts the wait if the callback wasn't invoked yet. The return value of
+/// the callback is the action of this view.
+pub fn timeout<T, A>(
+    delay: Duration,
+    callback: impl Fn(&mut T) -> A + Send + 'static,
+) -> Timer<T, A> {
+    Timer {
+        period: delay,
+        repeat: false,
+        callback: Box::new(callback),
+    }
+}
+
+impl<T, A> ViewMarker for Timer<T, A> {}
+
+impl<T, A> View<T, A> for Timer<T, A> {
+    type State = ();
+
+    type Element = Ticker;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, element) =
+            cx.with_new_id(|cx| Ticker::new(cx.id_path(), self.period, self.repeat));
+        (id, (), element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        _state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        element.set_period(self.period)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if id_path.is_empty() && message.is::<()>() {
+            MessageResult::Action((self.callback)(app_state))
+        } else {
+            MessageResult::Stale(message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::v_stack;
+    use crate::widget::test_util::{view_cx, Harness};
+    use crate::widget::{BoxConstraints, Pod};
+    use test_log::test;
+    use vello::kurbo::Size;
+
+    const PERIOD: Duration = Duration::from_millis(10);
+
+    /// Delivers the ticks elapsed in `delta` to `view` and returns its actions.
+    fn run<V: View<u32, u32>>(
+        harness: &mut Harness,
+        root: &mut Pod,
+        view: &V,
+        state: &mut V::State,
+        count: &mut u32,
+        delta: Duration,
+    ) -> Vec<u32> {
+        harness.advance_time(root, delta);
+        let mut actions = vec![];
+        for message in harness.take_messages() {
+            // The path starts with the id of the stack.
+            let id_path = message.id_path.clone();
+            let result = view.message(&id_path[1..], state, message, count);
+            if let MessageResult::Action(action) = result {
+                actions.push(action);
+            }
+        }
+        actions
+    }
+
+    #[test]
+    fn interval_invokes_the_callback_on_every_tick() {
+        let mut cx = view_cx();
+        let mut harness = Harness::new();
+
+        let view = v_stack((
+            interval(PERIOD, |count: &mut u32| {
+                *count += 1;
+                *count
+            }),
+            timeout(PERIOD * 3 + PERIOD / 2, |_: &mut u32| 100),
+        ));
+        let (_, mut state, element) = view.build(&mut cx);
+        let mut root = Pod::new(element);
+        harness.layout(&mut root, &BoxConstraints::tight(Size::new(100.0, 100.0)));
+        harness.update(&mut root);
+
+        let mut count = 0;
+        let mut actions = vec![];
+        for _ in 0..5 {
+            actions.extend(run(
+                &mut harness,
+                &mut root,
+                &view,
+                &mut state,
+                &mut count,
+                PERIOD,
+            ));
+        }
+        assert_eq!(actions, vec![1, 2, 3, 100, 4, 5]);
+        assert_eq!(count, 5);
+    }
+
+    #[test]
+    fn ticks_for_other_paths_are_stale() {
+        let mut cx = view_cx();
+        let view = timeout(PERIOD, |_: &mut ()| ());
+        let (_, mut state, element) = View::<()>::build(&view, &mut cx);
+        assert!(element.is_armed());
+
+        // A tick of a removed descendant is addressed below this view.
+        let result = view.message(&[Id::next()], &mut state, Message::new(vec![], ()), &mut ());
+        assert!(matches!(result, MessageResult::Stale(_)));
+        let result = view.message(&[], &mut state, Message::new(vec![], ()), &mut ());
+        assert!(matches!(result, MessageResult::Action(())));
+    }
+}
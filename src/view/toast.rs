@@ -0,0 +1,214 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Corner, Pod, ToastMessage};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A notification shown by [`toasts`].
+pub struct Toast<V> {
+    /// Identifies the toast across rebuilds, it has to be unique among the toasts.
+    pub id: u64,
+    pub view: V,
+    /// How long the toast is shown before it expires, not counting the time it is hovered.
+    pub duration: Duration,
+}
+
+impl<V> Toast<V> {
+    pub fn new(id: u64, view: V) -> Self {
+        Toast {
+            id,
+            view,
+            duration: widget::Toasts::DEFAULT_DURATION,
+        }
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+}
+
+/// Shows notifications in a corner of the window, see [`toasts`].
+pub struct Toasts<V, TV, F> {
+    child: V,
+    toasts: Vec<Toast<TV>>,
+    on_dismiss: F,
+    corner: Corner,
+    max_visible: usize,
+}
+
+/// The state of a [`Toasts`] view.
+pub struct ToastsState<T, A, V: View<T, A>, TV: View<T, A>> {
+    child: (Id, V::State),
+    /// The toast id, view id and view state of each toast, in the order they were pushed.
+    toasts: Vec<(u64, Id, TV::State)>,
+}
+
+/// Shows `toasts` stacked in a corner of the window above `child`, the bottom right one by
+/// default.
+///
+/// A toast calls `on_dismiss` with its id when its time runs out or the user closes it. It is
+/// shown until it is no longer in `toasts`, then it slides out. Only the first few toasts are
+/// shown at once, see [`Toasts::max_visible`], the others wait until one is removed. This is
+/// the same as [`ViewExt::toasts`](super::ViewExt::toasts).
+pub fn toasts<V, TV, F>(child: V, toasts: Vec<Toast<TV>>, on_dismiss: F) -> Toasts<V, TV, F> {
+    Toasts {
+        child,
+        toasts,
+        on_dismiss,
+        corner: Corner::default(),
+        max_visible: widget::Toasts::DEFAULT_MAX_VISIBLE,
+    }
+}
+
+impl<V, TV, F> Toasts<V, TV, F> {
+    pub fn corner(mut self, corner: Corner) -> Self {
+        self.corner = corner;
+        self
+    }
+
+    /// Sets how many toasts are shown at once.
+    pub fn max_visible(mut self, max_visible: usize) -> Self {
+        self.max_visible = max_visible;
+        self
+    }
+}
+
+impl<V, TV, F> ViewMarker for Toasts<V, TV, F> {}
+
+impl<T, A, V: View<T, A>, TV: View<T, A>, F: Fn(&mut T, u64) -> A + Send> View<T, A>
+    for Toasts<V, TV, F>
+{
+    type State = ToastsState<T, A, V, TV>;
+
+    type Element = widget::Toasts;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let mut element =
+                widget::Toasts::new(cx.id_path(), Pod::new(child), self.corner, self.max_visible);
+            let mut toasts = Vec::with_capacity(self.toasts.len());
+            for toast in &self.toasts {
+                let (view_id, view_state, view) = toast.view.build(cx);
+                let _ = element.push(toast.id, Pod::new(view), toast.duration);
+                toasts.push((toast.id, view_id, view_state));
+            }
+            let state = ToastsState {
+                child: (child_id, child_state),
+                toasts,
+            };
+            (state, element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let (child_id, child_state) = &mut state.child;
+            let child = element.child_mut();
+            let changes =
+                self.child
+                    .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+            let mut changes = child.mark(changes);
+            if self.corner != prev.corner {
+                changes |= element.set_corner(self.corner);
+            }
+            if self.max_visible != prev.max_visible {
+                changes |= element.set_max_visible(self.max_visible);
+            }
+
+            // Toasts which are gone slide out, the others are matched by id.
+            state.toasts.retain(|(toast_id, _, _)| {
+                let is_kept = self.toasts.iter().any(|toast| toast.id == *toast_id);
+                if !is_kept {
+                    changes |= element.remove(*toast_id);
+                }
+                is_kept
+            });
+            for toast in &self.toasts {
+                let existing = state
+                    .toasts
+                    .iter_mut()
+                    .find(|(toast_id, _, _)| *toast_id == toast.id);
+                let prev_view = prev.toasts.iter().find(|prev| prev.id == toast.id);
+                match (existing, prev_view) {
+                    (Some((_, view_id, view_state)), Some(prev_view)) => {
+                        changes |= element.update_toast(toast.id, |view| {
+                            toast.view.rebuild(
+                                cx,
+                                &prev_view.view,
+                                view_id,
+                                view_state,
+                                view.expect_mut(),
+                            )
+                        });
+                    }
+                    _ => {
+                        let (view_id, view_state, view) = toast.view.build(cx);
+                        changes |= element.push(toast.id, Pod::new(view), toast.duration);
+                        state.toasts.push((toast.id, view_id, view_state));
+                    }
+                }
+            }
+            changes
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path.split_first() {
+            None => match message.downcast::<ToastMessage>() {
+                Ok(ToastMessage::Expired(toast_id) | ToastMessage::Closed(toast_id)) => {
+                    MessageResult::Action((self.on_dismiss)(app_state, toast_id))
+                }
+                Err(message) => MessageResult::Stale(message),
+            },
+            Some((first, rest)) if *first == state.child.0 => {
+                self.child
+                    .message(rest, &mut state.child.1, message, app_state)
+            }
+            Some((first, rest)) => {
+                let toast = state
+                    .toasts
+                    .iter_mut()
+                    .find(|(_, view_id, _)| view_id == first);
+                let Some((toast_id, _, view_state)) = toast else {
+                    return MessageResult::Stale(message);
+                };
+                match self.toasts.iter().find(|toast| toast.id == *toast_id) {
+                    Some(toast) => toast.view.message(rest, view_state, message, app_state),
+                    None => MessageResult::Stale(message),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Shows a tooltip while the pointer rests on a child view, see [`tooltip`].
+pub struct Tooltip<V, C> {
+    child: V,
+    content: C,
+    delay: Duration,
+}
+
+/// Shows `content` in a tooltip while the pointer rests on `child`.
+///
+/// The content is usually a string, but can be any view. This is the same as
+/// [`ViewExt::tooltip`](super::ViewExt::tooltip).
+pub fn tooltip<V, C>(child: V, content: C) -> Tooltip<V, C> {
+    Tooltip {
+        child,
+        content,
+        delay: widget::Tooltip::DEFAULT_DELAY,
+    }
+}
+
+impl<V, C> Tooltip<V, C> {
+    /// Sets how long the pointer has to rest on the child before the tooltip is shown.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+impl<V, C> ViewMarker for Tooltip<V, C> {}
+
+impl<T, A, V: View<T, A>, C: View<T, A>> View<T, A> for Tooltip<V, C> {
+    type State = (Id, V::State, Id, C::State);
+
+    type Element = widget::Tooltip;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let (content_id, content_state, content) = self.content.build(cx);
+            let mut element = widget::Tooltip::new(Pod::new(child), Pod::new(content));
+            element.set_delay(self.delay);
+            ((child_id, child_state, content_id, content_state), element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let (child_id, child_state, content_id, content_state) = state;
+        cx.with_id(*id, |cx| {
+            let child = element.child_mut();
+            let changes =
+                self.child
+                    .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+            let mut changes = child.mark(changes);
+            changes |= element.update_content(|content| {
+                self.content.rebuild(
+                    cx,
+                    &prev.content,
+                    content_id,
+                    content_state,
+                    content.expect_mut(),
+                )
+            });
+            if self.delay != prev.delay {
+                element.set_delay(self.delay);
+            }
+            changes
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let (child_id, child_state, content_id, content_state) = state;
+        match id_path.split_first() {
+            Some((first, rest)) if first == child_id => {
+                self.child.message(rest, child_state, message, app_state)
+            }
+            Some((first, rest)) if first == content_id => {
+                self.content
+                    .message(rest, content_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
@@ -12,62 +12,95 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{any::Any, marker::PhantomData, sync::Arc};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
 
-use crate::{event::EventResult, id::Id};
+use crate::view::ViewMarker;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
 
 use super::{Cx, View};
 
-/// An implementation of the "use_state" pattern familiar in reactive UI.
-///
-/// This may not be the final form. In this version, the parent app data
-/// is `Rc<T>`, and the child is `(Rc<T>, S)` where S is the local state.
-///
-/// The first callback creates the initial state (it is called on build but
-/// not rebuild). The second callback takes that state as an argument. It
-/// is not passed the app state, but since that state is `Rc`, it would be
-/// natural to clone it and capture it in a `move` closure.
-pub struct UseState<T, A, S, V, FInit, F> {
+/// A view with local state, see [`use_state`].
+pub struct UseState<S, V, FInit, F> {
     f_init: FInit,
     f: F,
-    phantom: PhantomData<fn() -> (T, A, S, V)>,
+    phantom: PhantomData<fn() -> (S, V)>,
 }
 
-pub struct UseStateState<T, A, S, V: View<(Arc<T>, S), A>> {
-    state: Option<S>,
+pub struct UseStateState<S, A, V: View<Local<S>, A>> {
+    local: Local<S>,
     view: V,
     view_state: V::State,
 }
 
-impl<T, A, S, V, FInit: Fn() -> S, F: Fn(&mut S) -> V> UseState<T, A, S, V, FInit, F> {
-    #[allow(unused)]
-    pub fn new(f_init: FInit, f: F) -> Self {
-        let phantom = Default::default();
-        UseState { f_init, f, phantom }
+/// The local state of a [`use_state`] view.
+///
+/// It dereferences to the state, mutably borrowing it marks the view for a rebuild.
+pub struct Local<S> {
+    state: S,
+    changed: bool,
+}
+
+impl<S> Deref for Local<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S> DerefMut for Local<S> {
+    fn deref_mut(&mut self) -> &mut S {
+        self.changed = true;
+        &mut self.state
+    }
+}
+
+/// An implementation of the "use_state" pattern familiar in reactive UI.
+///
+/// `init` creates the local state when the view is built, it lives in the view state from then
+/// on, survives rebuilds and is dropped with the view. `f` creates the child view from the
+/// local state on build and on every rebuild. The callbacks of the child get the local state as
+/// their app state, changing it requests a rebuild. To reach the app state, the child can
+/// return actions, for example translated with [`map_action`](super::map_action).
+pub fn use_state<S, V, FInit, F>(init: FInit, f: F) -> UseState<S, V, FInit, F>
+where
+    FInit: Fn() -> S + Send,
+    F: Fn(&Local<S>) -> V + Send,
+{
+    UseState {
+        f_init: init,
+        f,
+        phantom: PhantomData,
     }
 }
 
-impl<T, A, S, V: View<(Arc<T>, S), A>, FInit: Fn() -> S, F: Fn(&mut S) -> V> View<Arc<T>, A>
-    for UseState<T, A, S, V, FInit, F>
+impl<S, V, FInit, F> ViewMarker for UseState<S, V, FInit, F> {}
+
+impl<T, A, S, V, FInit, F> View<T, A> for UseState<S, V, FInit, F>
 where
     S: Send,
-    FInit: Send,
-    F: Send,
+    V: View<Local<S>, A>,
+    FInit: Fn() -> S + Send,
+    F: Fn(&Local<S>) -> V + Send,
 {
-    type State = UseStateState<T, A, S, V>;
+    type State = UseStateState<S, A, V>;
 
     type Element = V::Element;
 
     fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
-        let mut state = (self.f_init)();
-        let view = (self.f)(&mut state);
+        let local = Local {
+            state: (self.f_init)(),
+            changed: false,
+        };
+        let view = (self.f)(&local);
         let (id, view_state, element) = view.build(cx);
-        let my_state = UseStateState {
-            state: Some(state),
+        let state = UseStateState {
+            local,
             view,
             view_state,
         };
-        (id, my_state, element)
+        (id, state, element)
     }
 
     fn rebuild(
@@ -77,29 +110,94 @@ where
         id: &mut Id,
         state: &mut Self::State,
         element: &mut Self::Element,
-    ) -> bool {
-        let view = (self.f)(state.state.as_mut().unwrap());
-        let changed = view.rebuild(cx, &state.view, id, &mut state.view_state, element);
+    ) -> ChangeFlags {
+        let view = (self.f)(&state.local);
+        let changes = view.rebuild(cx, &state.view, id, &mut state.view_state, element);
         state.view = view;
-        changed
+        changes
     }
 
-    fn event(
+    fn message(
         &self,
         id_path: &[Id],
         state: &mut Self::State,
-        event: Box<dyn Any>,
-        app_state: &mut Arc<T>,
-    ) -> EventResult<A> {
-        let mut local_state = (app_state.clone(), state.state.take().unwrap());
-        let a = state
+        message: Message,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        let result = state
             .view
-            .event(id_path, &mut state.view_state, event, &mut local_state);
-        let (local_app_state, my_state) = local_state;
-        if !Arc::ptr_eq(app_state, &local_app_state) {
-            *app_state = local_app_state
+            .message(id_path, &mut state.view_state, message, &mut state.local);
+        match result {
+            MessageResult::Nop if std::mem::take(&mut state.local.changed) => {
+                MessageResult::RequestRebuild
+            }
+            result => {
+                state.local.changed = false;
+                result
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::view::{button, ViewSequence};
+    use crate::widget::test_util::view_cx;
+    use crate::widget::Button;
+    use test_log::test;
+
+    fn counter(inits: &Arc<AtomicUsize>) -> impl View<(), Element = Button> + ViewMarker {
+        let inits = inits.clone();
+        use_state(
+            move || {
+                inits.fetch_add(1, Ordering::Relaxed);
+                0
+            },
+            |count: &Local<i32>| {
+                button(format!("{}", **count), |count: &mut Local<i32>| {
+                    **count += 1;
+                })
+            },
+        )
+    }
+
+    #[test]
+    fn local_state_survives_parent_rebuilds() {
+        let mut cx = view_cx();
+        let inits = Arc::new(AtomicUsize::new(0));
+
+        let mut prev = counter(&inits);
+        let (mut id, mut state, mut element) = prev.build(&mut cx);
+        // The click is reported as the action of the button, the app rebuilds after it anyway.
+        let result = prev.message(&[], &mut state, Message::new(vec![], ()), &mut ());
+        assert!(matches!(result, MessageResult::Action(())));
+        for _ in 0..3 {
+            let next = counter(&inits);
+            next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+            prev = next;
         }
-        state.state = Some(my_state);
-        a
+        assert_eq!(*state.local, 1);
+        assert_eq!(inits.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn siblings_have_their_own_state() {
+        let mut cx = view_cx();
+        let inits = Arc::new(AtomicUsize::new(0));
+
+        let siblings = (counter(&inits), counter(&inits));
+        let mut elements = vec![];
+        let mut state = siblings.build(&mut cx, &mut elements);
+        let second = state.1 .1;
+        siblings.message(&[second], &mut state, Message::new(vec![], ()), &mut ());
+        siblings.message(&[second], &mut state, Message::new(vec![], ()), &mut ());
+
+        assert_eq!(*state.0 .0.local, 0);
+        assert_eq!(*state.1 .0.local, 2);
+        assert_eq!(inits.load(Ordering::Relaxed), 2);
     }
 }
@@ -13,48 +13,84 @@
 // limitations under the License.
 
 use std::{
+    any::Any,
     collections::HashSet,
+    panic::AssertUnwindSafe,
     sync::{mpsc::SyncSender, Arc},
 };
 
 use futures_task::{ArcWake, Waker};
 
-use xilem_core::{Id, IdPath};
+use xilem_core::{AsyncWake, Id, IdPath};
 
-use crate::widget::{AnyWidget, ChangeFlags, Pod, Widget};
+use crate::widget::{AnyWidget, ChangeFlags, Pod, Registry, Widget};
+use crate::{Env, Key, Message, Profiler};
 
-xilem_core::generate_view_trait! {View, Widget, Cx, ChangeFlags; : Send}
-xilem_core::generate_viewsequence_trait! {ViewSequence, View, ViewMarker, Widget, Cx, ChangeFlags, Pod; : Send}
-xilem_core::generate_anyview_trait! {AnyView, View, ViewMarker, Cx, ChangeFlags, AnyWidget, BoxedView; + Send}
-xilem_core::generate_memoize_view! {Memoize, MemoizeState, View, ViewMarker, Cx, ChangeFlags, s, memoize; + Send}
-xilem_core::generate_adapt_view! {View, Cx, ChangeFlags; + Send}
-xilem_core::generate_adapt_state_view! {View, Cx, ChangeFlags; + Send}
+xilem_core::generate_view_trait! {View, Widget, Cx, ChangeFlags, Message; : Send}
+xilem_core::generate_viewsequence_trait! {ViewSequence, View, ViewMarker, Widget, Cx, ChangeFlags, Pod, Message; : Send}
+xilem_core::generate_anyview_trait! {AnyView, View, ViewMarker, Cx, ChangeFlags, AnyWidget, BoxedView, Message; + Send}
+xilem_core::generate_memoize_view! {Memoize, MemoizeState, View, ViewMarker, Cx, ChangeFlags, s, memoize, Message; + Send}
+xilem_core::generate_adapt_view! {View, Cx, ChangeFlags, Message; + Send}
+xilem_core::generate_adapt_state_view! {View, Cx, ChangeFlags, Message; + Send}
 
 #[derive(Clone)]
 pub struct Cx {
     id_path: IdPath,
-    req_chan: SyncSender<IdPath>,
+    req_chan: SyncSender<Message>,
     pub(crate) pending_async: HashSet<Id>,
+    env: Env,
+    profiler: Profiler,
+    registry: Registry,
 }
 
 struct MyWaker {
     id_path: IdPath,
-    req_chan: SyncSender<IdPath>,
+    req_chan: SyncSender<Message>,
 }
 
 impl ArcWake for MyWaker {
     fn wake_by_ref(arc_self: &Arc<Self>) {
-        //println!("path = {:?}", arc_self.id_path);
-        let _ = arc_self.req_chan.send(arc_self.id_path.clone());
+        let message = Message::new(arc_self.id_path.clone(), AsyncWake).with_source("waker");
+        let _ = arc_self.req_chan.send(message);
+    }
+}
+
+/// A handle to send messages to a view from background threads.
+///
+/// Created by [`Cx::message_sender`], it addresses the view being built at that time. The app
+/// wakes up to deliver the messages through [`View::message`], where they arrive just like
+/// messages of widgets.
+#[derive(Clone)]
+pub struct MessageSender {
+    id_path: IdPath,
+    req_chan: SyncSender<Message>,
+}
+
+impl MessageSender {
+    /// The id path of the view the messages are sent to.
+    pub fn id_path(&self) -> &IdPath {
+        &self.id_path
+    }
+
+    /// Send a message to the view.
+    ///
+    /// When the app is gone, the message is handed back. A view which was removed in the
+    /// meantime doesn't get the message, it is dropped as stale by the app.
+    pub fn send(&self, message: impl Any + Send) -> Result<(), Message> {
+        let message = Message::new(self.id_path.clone(), message).with_source("message sender");
+        self.req_chan.send(message).map_err(|err| err.0)
     }
 }
 
 impl Cx {
-    pub(crate) fn new(req_chan: &SyncSender<IdPath>) -> Self {
+    pub(crate) fn new(req_chan: &SyncSender<Message>) -> Self {
         Cx {
             id_path: Vec::new(),
             req_chan: req_chan.clone(),
             pending_async: HashSet::new(),
+            env: Env::new(),
+            profiler: Profiler::new(),
+            registry: Registry::default(),
         }
     }
 
@@ -95,6 +131,57 @@ impl Cx {
         (id, result)
     }
 
+    /// Run some logic, catching a panic in it.
+    ///
+    /// After a panic, the id path and the environment are restored to what they were before.
+    pub(crate) fn catch_unwind<T, F: FnOnce(&mut Cx) -> T>(
+        &mut self,
+        f: F,
+    ) -> Result<T, Box<dyn Any + Send>> {
+        let depth = self.id_path.len();
+        let env = self.env.clone();
+        std::panic::catch_unwind(AssertUnwindSafe(|| f(self))).map_err(|payload| {
+            self.id_path.truncate(depth);
+            self.env = env;
+            payload
+        })
+    }
+
+    /// The values set for the view being built, see [`with_env`](Cx::with_env).
+    pub fn env(&self) -> &Env {
+        &self.env
+    }
+
+    /// Run some logic with `key` set to `value` in the environment.
+    ///
+    /// Views built or rebuilt by `f` see the value, the previous environment is restored
+    /// afterwards.
+    pub fn with_env<V, T, F>(&mut self, key: Key<V>, value: V, f: F) -> T
+    where
+        V: PartialEq + Send + Sync + 'static,
+        F: FnOnce(&mut Cx) -> T,
+    {
+        let prev = self.env.clone();
+        self.env.set(key, value);
+        let result = f(self);
+        self.env = prev;
+        result
+    }
+
+    /// The registry [`profiled`](super::profiled) views record their timings into.
+    pub fn profiler(&self) -> &Profiler {
+        &self.profiler
+    }
+
+    pub(crate) fn set_profiler(&mut self, profiler: Profiler) {
+        self.profiler = profiler;
+    }
+
+    /// The registry [`tagged`](super::tagged) views enter their widgets into.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
     pub fn waker(&self) -> Waker {
         futures_task::waker(Arc::new(MyWaker {
             id_path: self.id_path.clone(),
@@ -102,6 +189,14 @@ impl Cx {
         }))
     }
 
+    /// A handle for sending messages to the current view from other threads.
+    pub fn message_sender(&self) -> MessageSender {
+        MessageSender {
+            id_path: self.id_path.clone(),
+            req_chan: self.req_chan.clone(),
+        }
+    }
+
     /// Add an id for a pending async future.
     ///
     /// Rendering may be delayed when there are pending async futures, to avoid
@@ -111,3 +206,316 @@ impl Cx {
         self.pending_async.insert(id);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::widget::test_util::{view_cx, view_cx_with_messages};
+    use crate::MessageResult;
+    use test_log::test;
+
+    /// A text view which counts how often it is rebuilt.
+    struct Counted {
+        text: String,
+        rebuilds: Arc<AtomicUsize>,
+    }
+
+    impl ViewMarker for Counted {}
+
+    impl View<()> for Counted {
+        type State = ();
+
+        type Element = crate::widget::TextWidget;
+
+        fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+            let (id, element) =
+                cx.with_new_id(|_| crate::widget::TextWidget::new(self.text.clone()));
+            (id, (), element)
+        }
+
+        fn rebuild(
+            &self,
+            _cx: &mut Cx,
+            prev: &Self,
+            _id: &mut Id,
+            _state: &mut Self::State,
+            element: &mut Self::Element,
+        ) -> ChangeFlags {
+            self.rebuilds.fetch_add(1, Ordering::Relaxed);
+            if prev.text == self.text {
+                return ChangeFlags::empty();
+            }
+            element.set_text(self.text.clone())
+        }
+
+        fn message(
+            &self,
+            _id_path: &[Id],
+            _state: &mut Self::State,
+            message: Message,
+            _app_state: &mut (),
+        ) -> MessageResult<()> {
+            MessageResult::Stale(message)
+        }
+    }
+
+    /// A view with a given id, which logs the messages reaching it.
+    struct Probe {
+        id: Id,
+        name: &'static str,
+        answers: bool,
+    }
+
+    impl ViewMarker for Probe {}
+
+    impl View<Vec<&'static str>, String> for Probe {
+        type State = ();
+
+        type Element = crate::widget::TextWidget;
+
+        fn build(&self, _cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+            let element = crate::widget::TextWidget::new(self.name.to_string());
+            (self.id, (), element)
+        }
+
+        fn rebuild(
+            &self,
+            _cx: &mut Cx,
+            _prev: &Self,
+            _id: &mut Id,
+            _state: &mut Self::State,
+            _element: &mut Self::Element,
+        ) -> ChangeFlags {
+            ChangeFlags::empty()
+        }
+
+        fn message(
+            &self,
+            _id_path: &[Id],
+            _state: &mut Self::State,
+            message: Message,
+            log: &mut Vec<&'static str>,
+        ) -> MessageResult<String> {
+            log.push(self.name);
+            if !self.answers {
+                return MessageResult::Stale(message);
+            }
+            match message.downcast::<String>() {
+                Ok(payload) => MessageResult::Action(format!("{}: {payload}", self.name)),
+                Err(message) => MessageResult::Stale(message),
+            }
+        }
+    }
+
+    fn probe(id: Id, name: &'static str, answers: bool) -> Probe {
+        Probe { id, name, answers }
+    }
+
+    fn send<VT: ViewSequence<Vec<&'static str>, String>>(
+        seq: &VT,
+        state: &mut VT::State,
+        id: Id,
+        log: &mut Vec<&'static str>,
+    ) -> MessageResult<String> {
+        seq.message(&[id], state, Message::new(vec![], String::from("hi")), log)
+    }
+
+    #[test]
+    fn sequences_deliver_to_the_owner_once() {
+        let mut cx = view_cx();
+        let (first, nested, last) = (Id::next(), Id::next(), Id::next());
+        // The last child reuses the id of a child in the first half.
+        let seq = (
+            (
+                probe(first, "first", true),
+                vec![probe(nested, "nested", true)],
+            ),
+            probe(last, "last", true),
+            probe(nested, "impostor", true),
+        );
+        let mut state = ViewSequence::<Vec<&str>, String>::build(&seq, &mut cx, &mut vec![]);
+        let mut log = vec![];
+
+        let mut actions = vec![];
+        for id in [first, nested, last] {
+            match send(&seq, &mut state, id, &mut log) {
+                MessageResult::Action(action) => actions.push(action),
+                _ => panic!("expected an action"),
+            }
+        }
+        assert_eq!(actions, ["first: hi", "nested: hi", "last: hi"]);
+        assert_eq!(log, ["first", "nested", "last"]);
+    }
+
+    #[test]
+    fn stale_messages_keep_their_payload() {
+        let mut cx = view_cx();
+        let (stale, gone) = (Id::next(), Id::next());
+        let seq = (
+            vec![probe(Id::next(), "other", true)],
+            probe(stale, "stale", false),
+            probe(stale, "impostor", true),
+        );
+        let mut state = ViewSequence::<Vec<&str>, String>::build(&seq, &mut cx, &mut vec![]);
+        let mut log = vec![];
+
+        // The owner reports the message as stale, later children aren't asked.
+        for id in [stale, gone] {
+            let MessageResult::Stale(message) = send(&seq, &mut state, id, &mut log) else {
+                panic!("expected a stale message");
+            };
+            assert_eq!(message.downcast::<String>().unwrap(), "hi");
+        }
+        assert_eq!(log, ["stale"]);
+    }
+
+    #[test]
+    fn failed_downcasts_hand_back_the_message() {
+        let mut cx = view_cx();
+        let (root, answers) = (Id::next(), Id::next());
+        let seq = (
+            probe(Id::next(), "other", true),
+            vec![Some(probe(answers, "answers", true))],
+        );
+        let mut state = ViewSequence::<Vec<&str>, String>::build(&seq, &mut cx, &mut vec![]);
+        let mut log = vec![];
+
+        // The probe only takes strings, the number comes back out of the nested sequences.
+        let message = Message::new(vec![root, answers], 5u32).with_source("test");
+        let result = seq.message(&[answers], &mut state, message, &mut log);
+        let MessageResult::Stale(message) = result else {
+            panic!("expected a stale message");
+        };
+        assert_eq!(log, ["answers"]);
+        assert_eq!(message.id_path, [root, answers]);
+        assert_eq!(message.type_name(), "u32");
+        assert_eq!(message.source(), Some("test"));
+        assert_eq!(message.downcast::<u32>().unwrap(), 5);
+    }
+
+    #[test]
+    fn memoize_skips_unchanged_data() {
+        let mut cx = view_cx();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let rebuilds = Arc::new(AtomicUsize::new(0));
+        let view = |count: u32| {
+            let (calls, rebuilds) = (calls.clone(), rebuilds.clone());
+            memoize(count, move |count| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                Counted {
+                    text: format!("{count} items"),
+                    rebuilds: rebuilds.clone(),
+                }
+            })
+        };
+
+        let mut prev = view(1);
+        let (mut id, mut state, mut element) = View::<()>::build(&prev, &mut cx);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        for _ in 0..100 {
+            let next = view(1);
+            let changes =
+                View::<()>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+            assert_eq!(changes, ChangeFlags::empty());
+            prev = next;
+        }
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert_eq!(rebuilds.load(Ordering::Relaxed), 0);
+
+        // A new key generates a new child, which is diffed against the stored one.
+        let next = view(2);
+        let changes = View::<()>::rebuild(&next, &mut cx, &prev, &mut id, &mut state, &mut element);
+        assert!(changes.contains(ChangeFlags::LAYOUT));
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+        assert_eq!(rebuilds.load(Ordering::Relaxed), 1);
+        assert_eq!(element.text(), "2 items");
+    }
+
+    #[test]
+    fn boxed_view_replaces_widget_when_type_changes() {
+        use crate::view::{label, ViewExt};
+
+        let mut cx = view_cx();
+        let rebuilds = Arc::new(AtomicUsize::new(0));
+        let counted = |text: &str| {
+            Counted {
+                text: text.into(),
+                rebuilds: rebuilds.clone(),
+            }
+            .boxed()
+        };
+
+        let mut prev = counted("a");
+        let (mut id, mut state, mut element) = View::<()>::build(&prev, &mut cx);
+        let frames = [
+            counted("b"),
+            label("c").boxed(),
+            label("d").boxed(),
+            counted("e"),
+        ];
+        let mut replaced = vec![];
+        for next in frames {
+            let old_id = id;
+            let changes = next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+            replaced.push((changes.contains(ChangeFlags::TREE), old_id != id));
+            prev = next;
+        }
+        assert_eq!(
+            replaced,
+            [(false, false), (true, true), (false, false), (true, true)]
+        );
+        // Only the rebuild between two `Counted` views reached the view.
+        assert_eq!(rebuilds.load(Ordering::Relaxed), 1);
+        let text = (*element)
+            .as_any()
+            .downcast_ref::<crate::widget::TextWidget>();
+        assert_eq!(text.unwrap().text(), "e");
+
+        // A message reaching a replaced view through the state of another type is stale.
+        let message = Message::new(vec![], ());
+        let result = label("f")
+            .boxed()
+            .message(&[], &mut state, message, &mut ());
+        assert!(matches!(result, MessageResult::Stale(_)));
+    }
+
+    #[test]
+    fn message_downcast_keeps_envelope() {
+        let id = Id::next();
+        let message = crate::Message::new(vec![id], 5u32).with_source("test");
+        assert!(message.is::<u32>());
+        assert!(!message.is::<String>());
+
+        let message = message.downcast::<String>().unwrap_err();
+        assert_eq!(message.id_path, [id]);
+        assert_eq!(message.type_name(), "u32");
+        assert_eq!(message.source(), Some("test"));
+        assert_eq!(message.downcast::<u32>().unwrap(), 5);
+    }
+
+    #[test]
+    fn message_sender_delivers_from_threads() {
+        let (mut cx, messages) = view_cx_with_messages();
+        let (id, sender) = cx.with_new_id(|cx| cx.message_sender());
+        assert_eq!(sender.id_path(), &vec![id]);
+
+        let thread_sender = sender.clone();
+        std::thread::spawn(move || thread_sender.send(5u32).unwrap())
+            .join()
+            .unwrap();
+        let message = messages.recv().unwrap();
+        assert_eq!(message.id_path, [id]);
+        assert_eq!(message.downcast::<u32>().unwrap(), 5);
+
+        // Once the app is gone, the message is handed back.
+        drop(messages);
+        drop(cx);
+        let message = std::thread::spawn(move || sender.send(6u32))
+            .join()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(message.downcast::<u32>().unwrap(), 6);
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+
+use vello::kurbo::{Point, Size};
+
+use crate::widget::{EventPayload, PasteData};
+use crate::{HotKey, Key};
+
+use super::{
+    disabled, drag_source, drop_target, file_drop, map_action, modal, on_click, on_close_request,
+    on_event, on_hover, on_key, on_mount, on_paste, on_unmount, on_window_resize, tagged, toasts,
+    tooltip, with_env, BoxedView, CloseDecision, ContextMenu, Disabled, DragSource, DropTarget,
+    FocusScope, MapAction, Menu, Modal, OnCloseRequest, OnEvent, OnMount, OnPaste, OnUnmount,
+    OnWindowResize, Shortcuts, Tagged, Toast, Toasts, Tooltip, View, WithEnv,
+};
+
+/// Wrappers available on every view.
+pub trait ViewExt<T, A>: View<T, A> + Sized {
+    /// Shows `content` in a tooltip while the pointer rests on this view.
+    ///
+    /// The content is usually a string, but can be any view.
+    fn tooltip<C: View<T, A>>(self, content: C) -> Tooltip<Self, C> {
+        tooltip(self, content)
+    }
+
+    /// Shows a context menu when the user asks for one on this view, with the secondary mouse
+    /// button or the keyboard.
+    ///
+    /// `menu` builds the menu from the app state each time it opens. Choosing an item produces
+    /// its action.
+    fn context_menu<F: Fn(&T) -> Menu<A> + Send>(self, menu: F) -> ContextMenu<Self, F> {
+        ContextMenu::new(self, menu)
+    }
+
+    /// Shows `dialog` on top of the whole window while `open` is true, blocking interaction with
+    /// everything below it, see [`modal`].
+    fn modal<D: View<T, A>, F: Fn(&mut T) -> A + Send>(
+        self,
+        open: bool,
+        dialog: D,
+        on_dismiss: F,
+    ) -> Modal<Self, D, F> {
+        modal(self, open, dialog, on_dismiss)
+    }
+
+    /// Shows `toasts` stacked in a corner of the window above this view, see [`toasts`].
+    fn toasts<TV: View<T, A>, F: Fn(&mut T, u64) -> A + Send>(
+        self,
+        toasts: Vec<Toast<TV>>,
+        on_dismiss: F,
+    ) -> Toasts<Self, TV, F> {
+        self::toasts(self, toasts, on_dismiss)
+    }
+
+    /// Erases the type of this view, so that views of different types can take the same place,
+    /// for example in the branches of an `if`.
+    ///
+    /// Rebuilding diffs against the previous view when it has the same type, otherwise the
+    /// widgets are built from scratch.
+    fn boxed(self) -> BoxedView<T, A>
+    where
+        Self: 'static,
+        Self::State: 'static,
+        Self::Element: 'static,
+    {
+        Box::new(self)
+    }
+
+    /// Translates the actions of this view with `f`, see [`map_action`].
+    fn map_action<B, F: Fn(&mut T, A) -> B + Send>(self, f: F) -> MapAction<T, A, B, Self, F> {
+        map_action(self, f)
+    }
+
+    /// Produces the action returned by `clicked` when this view is clicked with the primary mouse
+    /// button, that is pressed and released over it.
+    fn on_click(self, clicked: impl Fn(&mut T) -> A + Send + 'static) -> OnEvent<T, A, Self> {
+        on_click(self, clicked)
+    }
+
+    /// Produces the action returned by `hovered` when the pointer enters this view, with `true`,
+    /// and when it leaves, with `false`.
+    fn on_hover(self, hovered: impl Fn(&mut T, bool) -> A + Send + 'static) -> OnEvent<T, A, Self> {
+        on_hover(self, hovered)
+    }
+
+    /// Produces the actions `callback` returns for the pointer events on this view, see
+    /// [`on_event`].
+    fn on_event(
+        self,
+        callback: impl Fn(&mut T, &EventPayload) -> Option<A> + Send + 'static,
+    ) -> OnEvent<T, A, Self> {
+        on_event(self, callback)
+    }
+
+    /// Produces the action returned by `pasted` when the user pastes while keyboard focus is
+    /// inside this view, see [`on_paste`].
+    fn on_paste(
+        self,
+        pasted: impl Fn(&mut T, PasteData) -> A + Send + 'static,
+    ) -> OnPaste<T, A, Self> {
+        on_paste(self, pasted)
+    }
+
+    /// Produces the action returned by `resized` with the new size of the window whenever it is
+    /// resized, see [`on_window_resize`].
+    fn on_window_resize(
+        self,
+        resized: impl Fn(&mut T, Size) -> A + Send + 'static,
+    ) -> OnWindowResize<T, A, Self> {
+        on_window_resize(self, resized)
+    }
+
+    /// Keeps the window open when the user asks to close it, unless `decide` returns
+    /// [`CloseDecision::Close`], see [`on_close_request`].
+    fn on_close_request(
+        self,
+        decide: impl Fn(&mut T) -> CloseDecision + Send + 'static,
+    ) -> OnCloseRequest<T, Self> {
+        on_close_request(self, decide)
+    }
+
+    /// Lets the user drag this view and drop a clone of `payload` on a view accepting `P`, see
+    /// [`drag_source`].
+    fn draggable<P: Clone + Send + 'static>(self, payload: P) -> DragSource<T, A, Self, P> {
+        drag_source(self, payload)
+    }
+
+    /// Produces the action returned by `dropped` when a `P` dragged from a
+    /// [`draggable`](ViewExt::draggable) view is dropped on this view, see [`drop_target`].
+    fn on_drop_of<P: Send + 'static>(
+        self,
+        dropped: impl Fn(&mut T, P, Point) -> A + Send + 'static,
+    ) -> DropTarget<T, A, Self, P> {
+        drop_target(self, dropped)
+    }
+
+    /// Produces the action returned by `dropped` when files from another application are
+    /// dropped on this view, see [`file_drop`].
+    fn on_file_drop(
+        self,
+        dropped: impl Fn(&mut T, Vec<PathBuf>, Point) -> A + Send + 'static,
+    ) -> DropTarget<T, A, Self, Vec<PathBuf>> {
+        file_drop(self, dropped)
+    }
+
+    /// Gives keyboard focus to the first focusable widget of this view when it is first shown.
+    fn auto_focus(self) -> FocusScope<Self> {
+        FocusScope::new_auto_focus(self)
+    }
+
+    /// Produces the action returned by `pressed` when `hotkey` is pressed while keyboard focus is
+    /// inside this view, see [`on_key`].
+    fn on_key(
+        self,
+        hotkey: HotKey,
+        pressed: impl Fn(&mut T) -> A + Send + 'static,
+    ) -> Shortcuts<T, A, Self> {
+        on_key(self, hotkey, pressed)
+    }
+
+    /// Produces the action returned by `callback` once, right after this view was added to the
+    /// tree, see [`on_mount`].
+    fn on_mount(self, callback: impl Fn(&mut T) -> A + Send + 'static) -> OnMount<T, A, Self> {
+        on_mount(self, callback)
+    }
+
+    /// Invokes `callback` when this view is removed from the tree, see [`on_unmount`].
+    fn on_unmount<F: Fn() + Clone + Send>(self, callback: F) -> OnUnmount<Self, F> {
+        on_unmount(self, callback)
+    }
+
+    /// Disables this view and everything below it while `disabled` is set, see [`disabled`].
+    fn disabled(self, disabled: bool) -> Disabled<Self> {
+        self::disabled(disabled, self)
+    }
+
+    /// Makes the widget of this view findable by `tag` with
+    /// [`App::find_widget`](crate::App::find_widget), see [`tagged`].
+    fn tagged(self, tag: impl Into<String>) -> Tagged<Self> {
+        self::tagged(tag, self)
+    }
+
+    /// Sets `key` to `value` for this view and everything below it, see [`with_env`].
+    fn with_env<E>(self, key: Key<E>, value: E) -> WithEnv<Self, E> {
+        with_env(key, value, self)
+    }
+}
+
+impl<T, A, V: View<T, A>> ViewExt<T, A> for V {}
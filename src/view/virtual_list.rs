@@ -0,0 +1,193 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, ListChildRequest, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// A scrolling list of which only the items near the view are built, see [`virtual_list`].
+pub struct VirtualList<F, V> {
+    n_items: usize,
+    item_height: f64,
+    build: F,
+    phantom: PhantomData<fn() -> V>,
+}
+
+pub struct VirtualListState<V, S> {
+    add: Vec<usize>,
+    remove: Vec<usize>,
+    items: BTreeMap<usize, (Id, V, S)>,
+}
+
+/// A list of `n_items` rows of `item_height`, the view of the row at an index is returned by
+/// `build`.
+///
+/// The widget asks for the rows it shows, only their views are built and diffed on rebuilds.
+pub fn virtual_list<F, V>(n_items: usize, item_height: f64, build: F) -> VirtualList<F, V>
+where
+    F: Fn(usize) -> V + Send,
+{
+    VirtualList {
+        n_items,
+        item_height,
+        build,
+        phantom: PhantomData,
+    }
+}
+
+impl<F, V> ViewMarker for VirtualList<F, V> {}
+
+impl<T, A, F, V> View<T, A> for VirtualList<F, V>
+where
+    F: Fn(usize) -> V + Send,
+    V: View<T, A>,
+    V::Element: 'static,
+{
+    type State = VirtualListState<V, V::State>;
+
+    type Element = widget::List;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, element) =
+            cx.with_new_id(|cx| widget::List::new(cx.id_path(), self.n_items, self.item_height));
+        let state = VirtualListState {
+            add: vec![],
+            remove: vec![],
+            items: BTreeMap::new(),
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let mut changes = ChangeFlags::empty();
+            if self.n_items != prev.n_items {
+                changes |= element.set_n_items(self.n_items);
+                state.items.retain(|index, _| *index < self.n_items);
+            }
+            if self.item_height != prev.item_height {
+                changes |= element.set_item_height(self.item_height);
+            }
+            for index in std::mem::take(&mut state.remove) {
+                if state.items.remove(&index).is_some() {
+                    changes |= element.remove_child(index);
+                }
+            }
+            for (index, (child_id, view, child_state)) in &mut state.items {
+                let next = (self.build)(*index);
+                let child = element.child_mut(*index).unwrap();
+                let child_changes =
+                    next.rebuild(cx, view, child_id, child_state, child.expect_mut());
+                *view = next;
+                changes |= child.mark(child_changes);
+            }
+            for index in std::mem::take(&mut state.add) {
+                if index >= self.n_items || state.items.contains_key(&index) {
+                    continue;
+                }
+                let view = (self.build)(index);
+                let (child_id, child_state, child) = view.build(cx);
+                changes |= element.set_child(index, Pod::new(child));
+                state.items.insert(index, (child_id, view, child_state));
+            }
+            changes
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path.split_first() {
+            Some((first, rest_path)) => {
+                match state
+                    .items
+                    .values_mut()
+                    .find(|(child_id, _, _)| child_id == first)
+                {
+                    Some((_, view, child_state)) => {
+                        view.message(rest_path, child_state, message, app_state)
+                    }
+                    None => MessageResult::Stale(message),
+                }
+            }
+            None => match message.downcast::<ListChildRequest>() {
+                Ok(request) => {
+                    state.add.extend(&request.add);
+                    state.remove.extend(&request.remove);
+                    MessageResult::RequestRebuild
+                }
+                Err(message) => MessageResult::Stale(message),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::label;
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    #[test]
+    fn only_requested_rows_are_built() {
+        let mut cx = view_cx();
+        let rows = |n_items| virtual_list(n_items, 24.0, |index| label(format!("Row {index}")));
+
+        let prev = rows(100_000);
+        let (mut id, mut state, mut element) = View::<()>::build(&prev, &mut cx);
+        assert!(element.child_mut(0).is_none());
+
+        let request = ListChildRequest {
+            add: vec![4999, 5000, 5001],
+            remove: vec![],
+        };
+        let result = prev.message(&[], &mut state, Message::new(vec![], request), &mut ());
+        assert!(matches!(result, MessageResult::RequestRebuild));
+        let next = rows(100_000);
+        let changes = next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+        assert!(changes.contains(ChangeFlags::tree_structure()));
+        let row = element.child_mut(5000).unwrap();
+        let label = row.downcast_mut::<widget::Label>().unwrap();
+        assert_eq!(label.text(), "Row 5000");
+
+        let request = ListChildRequest {
+            add: vec![],
+            remove: vec![4999],
+        };
+        let _ = next.message(&[], &mut state, Message::new(vec![], request), &mut ());
+        let last = rows(5001);
+        let _ = last.rebuild(&mut cx, &next, &mut id, &mut state, &mut element);
+        assert!(element.child_mut(4999).is_none());
+        assert!(element.child_mut(5000).is_some());
+        assert!(element.child_mut(5001).is_none());
+        assert_eq!(element.n_items(), 5001);
+    }
+}
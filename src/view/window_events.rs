@@ -0,0 +1,236 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vello::kurbo::Size;
+
+use crate::view::{Id, ViewMarker};
+use crate::widget::{self, ChangeFlags, CloseRequest, Pod};
+use crate::{Message, MessageResult};
+
+use super::{Cx, View};
+
+/// Turns resizes of the window into actions, see [`on_window_resize`].
+pub struct OnWindowResize<T, A, V> {
+    child: V,
+    // consider not boxing
+    callback: Box<dyn Fn(&mut T, Size) -> A + Send>,
+}
+
+/// Calls `resized` with the new size of the window whenever it is resized, and once when the
+/// window gets its first size, for example to switch to fewer columns in a narrow window.
+///
+/// The layout of `child` is not changed.
+pub fn on_window_resize<T, A, V>(
+    child: V,
+    resized: impl Fn(&mut T, Size) -> A + Send + 'static,
+) -> OnWindowResize<T, A, V> {
+    OnWindowResize {
+        child,
+        callback: Box::new(resized),
+    }
+}
+
+/// What to do when the user asks to close the window, see [`on_close_request`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseDecision {
+    Close,
+    /// Keep the window open, for example to ask about unsaved changes first.
+    Stay,
+}
+
+/// Decides about closing the window, see [`on_close_request`].
+pub struct OnCloseRequest<T, V> {
+    child: V,
+    // consider not boxing
+    callback: Box<dyn Fn(&mut T) -> CloseDecision + Send>,
+}
+
+/// Calls `decide` when the user asks to close the window while `child` is shown, which only
+/// closes when it returns [`CloseDecision::Close`].
+///
+/// The window stays open until `decide` ran, as the app state is only available while messages
+/// are handled. The layout of `child` is not changed.
+pub fn on_close_request<T, V>(
+    child: V,
+    decide: impl Fn(&mut T) -> CloseDecision + Send + 'static,
+) -> OnCloseRequest<T, V> {
+    OnCloseRequest {
+        child,
+        callback: Box::new(decide),
+    }
+}
+
+impl<T, A, V> ViewMarker for OnWindowResize<T, A, V> {}
+
+impl<T, A, V: View<T, A>> View<T, A> for OnWindowResize<T, A, V> {
+    type State = (Id, V::State);
+
+    type Element = widget::OnWindowResize;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let element = widget::OnWindowResize::new(cx.id_path(), Pod::new(child));
+            ((child_id, child_state), element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let (child_id, child_state) = state;
+        cx.with_id(*id, |cx| {
+            let child = element.child_mut();
+            let changes =
+                self.child
+                    .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+            child.mark(changes)
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let (child_id, child_state) = state;
+        match id_path.split_first() {
+            Some((first, rest)) if first == child_id => {
+                self.child.message(rest, child_state, message, app_state)
+            }
+            Some(_) => MessageResult::Stale(message),
+            None => match message.downcast::<Size>() {
+                Ok(size) => MessageResult::Action((self.callback)(app_state, size)),
+                Err(message) => MessageResult::Stale(message),
+            },
+        }
+    }
+}
+
+impl<T, V> ViewMarker for OnCloseRequest<T, V> {}
+
+impl<T, A, V: View<T, A>> View<T, A> for OnCloseRequest<T, V> {
+    /// The id and state of the child, and whether the window closes with the next rebuild.
+    type State = (Id, V::State, bool);
+
+    type Element = widget::OnCloseRequest;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, child) = self.child.build(cx);
+            let element = widget::OnCloseRequest::new(cx.id_path(), Pod::new(child));
+            ((child_id, child_state, false), element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let (child_id, child_state, close) = state;
+        let mut changes = ChangeFlags::empty();
+        if std::mem::take(close) {
+            changes |= element.close_window();
+        }
+        changes
+            | cx.with_id(*id, |cx| {
+                let child = element.child_mut();
+                let changes =
+                    self.child
+                        .rebuild(cx, &prev.child, child_id, child_state, child.expect_mut());
+                child.mark(changes)
+            })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let (child_id, child_state, close) = state;
+        match id_path.split_first() {
+            Some((first, rest)) if first == child_id => {
+                self.child.message(rest, child_state, message, app_state)
+            }
+            Some(_) => MessageResult::Stale(message),
+            None => match message.downcast::<CloseRequest>() {
+                Ok(_) => {
+                    *close = (self.callback)(app_state) == CloseDecision::Close;
+                    MessageResult::RequestRebuild
+                }
+                Err(message) => MessageResult::Stale(message),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::label;
+    use crate::widget::test_util::view_cx;
+    use test_log::test;
+
+    #[test]
+    fn only_a_close_decision_closes() {
+        let mut cx = view_cx();
+        let view = on_close_request(label("Draft"), |unsaved: &mut bool| {
+            if *unsaved {
+                CloseDecision::Stay
+            } else {
+                CloseDecision::Close
+            }
+        });
+        let (mut id, mut state, mut element) = View::<bool, ()>::build(&view, &mut cx);
+
+        let mut unsaved = true;
+        let _ = View::<bool, ()>::message(
+            &view,
+            &[],
+            &mut state,
+            Message::new(vec![], CloseRequest),
+            &mut unsaved,
+        );
+        let changes =
+            View::<bool, ()>::rebuild(&view, &mut cx, &view, &mut id, &mut state, &mut element);
+        assert!(!changes.contains(ChangeFlags::UPDATE));
+
+        unsaved = false;
+        let _ = View::<bool, ()>::message(
+            &view,
+            &[],
+            &mut state,
+            Message::new(vec![], CloseRequest),
+            &mut unsaved,
+        );
+        let changes =
+            View::<bool, ()>::rebuild(&view, &mut cx, &view, &mut id, &mut state, &mut element);
+        assert!(changes.contains(ChangeFlags::UPDATE));
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::view::ViewMarker;
+use crate::widget::Pod;
+use crate::{view::Id, widget::ChangeFlags, Message, MessageResult};
+
+use super::{Cx, ElementSplice, View, ViewSequence};
+
+/// A component generating its view from state of its own, see [`Wrapped`].
+pub trait Wrapper<T, A = ()>: Send {
+    /// The state of the component, it lives as long as the view.
+    type State: Send;
+
+    /// The view generated from the state.
+    type V: View<T, A>;
+
+    /// Creates the state when the view is built.
+    fn init(&self) -> Self::State;
+
+    /// Generates the view from the state, on build and on every rebuild.
+    fn gen(&self, state: &Self::State) -> Self::V;
+
+    /// Sees messages for the generated view before it does.
+    ///
+    /// Returning `Err` hands the message on to the generated view, which is the default. When
+    /// the message changes the state, request a rebuild to generate the view again.
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        _app_state: &mut T,
+    ) -> Result<MessageResult<A>, Message> {
+        Err(message)
+    }
+}
+
+/// The view of a [`Wrapper`].
+///
+/// The generated view is kept in the view state, so that each rebuild diffs the next generated
+/// view against the one generated before.
+pub struct Wrapped<W>(pub W);
+
+pub struct WrappedState<S, V, VS> {
+    state: S,
+    view: V,
+    view_state: VS,
+}
+
+impl<W> ViewMarker for Wrapped<W> {}
+
+impl<T, A, W: Wrapper<T, A>> View<T, A> for Wrapped<W> {
+    type State = WrappedState<W::State, W::V, <W::V as View<T, A>>::State>;
+
+    type Element = <W::V as View<T, A>>::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let state = self.0.init();
+        let view = self.0.gen(&state);
+        let (id, view_state, element) = view.build(cx);
+        let state = WrappedState {
+            state,
+            view,
+            view_state,
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        _prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let view = self.0.gen(&state.state);
+        let changes = view.rebuild(cx, &state.view, id, &mut state.view_state, element);
+        state.view = view;
+        changes
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match self
+            .0
+            .message(id_path, &mut state.state, message, app_state)
+        {
+            Ok(result) => result,
+            Err(message) => state
+                .view
+                .message(id_path, &mut state.view_state, message, app_state),
+        }
+    }
+}
+
+/// A component generating a sequence of views from state of its own, see [`WrappedSequence`].
+///
+/// This is the [`Wrapper`] of a dynamic number of children, for example of a
+/// [`LinearLayout`](super::LinearLayout).
+pub trait SequenceWrapper<T, A = ()>: Send {
+    /// The state of the component, it lives as long as the sequence.
+    type State: Send;
+
+    /// The sequence generated from the state.
+    type VT: ViewSequence<T, A>;
+
+    /// Creates the state when the sequence is built.
+    fn init(&self) -> Self::State;
+
+    /// Generates the sequence from the state, on build and on every rebuild.
+    fn gen(&self, state: &Self::State) -> Self::VT;
+
+    /// Sees messages for the generated sequence before it does, see [`Wrapper::message`].
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Message,
+        _app_state: &mut T,
+    ) -> Result<MessageResult<A>, Message> {
+        Err(message)
+    }
+}
+
+/// The view sequence of a [`SequenceWrapper`].
+///
+/// Like [`Wrapped`], it diffs each generated sequence against the one generated before.
+pub struct WrappedSequence<W>(pub W);
+
+impl<T, A, W: SequenceWrapper<T, A>> ViewSequence<T, A> for WrappedSequence<W> {
+    type State = WrappedState<W::State, W::VT, <W::VT as ViewSequence<T, A>>::State>;
+
+    fn build(&self, cx: &mut Cx, elements: &mut Vec<Pod>) -> Self::State {
+        let state = self.0.init();
+        let view = self.0.gen(&state);
+        let view_state = view.build(cx, elements);
+        WrappedState {
+            state,
+            view,
+            view_state,
+        }
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        _prev: &Self,
+        state: &mut Self::State,
+        element: &mut dyn ElementSplice<Pod>,
+    ) -> ChangeFlags {
+        let view = self.0.gen(&state.state);
+        let changes = view.rebuild(cx, &state.view, &mut state.view_state, element);
+        state.view = view;
+        changes
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Message,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match self
+            .0
+            .message(id_path, &mut state.state, message, app_state)
+        {
+            Ok(result) => result,
+            Err(message) => state
+                .view
+                .message(id_path, &mut state.view_state, message, app_state),
+        }
+    }
+
+    fn count(&self, state: &Self::State) -> usize {
+        state.view.count(&state.view_state)
+    }
+
+    fn owns_id(&self, state: &Self::State, id: Id) -> bool {
+        state.view.owns_id(&state.view_state, id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::{label, Label};
+    use crate::widget::test_util::view_cx;
+    use crate::widget::{PodSlots, SlotKey};
+    use test_log::test;
+
+    struct Increment;
+
+    /// Counts `Increment` messages, other messages go to the label.
+    struct Counter {
+        step: u32,
+    }
+
+    impl Wrapper<Vec<&'static str>> for Counter {
+        type State = u32;
+
+        type V = Label;
+
+        fn init(&self) -> u32 {
+            0
+        }
+
+        fn gen(&self, count: &u32) -> Label {
+            label(format!("{count}"))
+        }
+
+        fn message(
+            &self,
+            _id_path: &[Id],
+            count: &mut u32,
+            message: Message,
+            log: &mut Vec<&'static str>,
+        ) -> Result<MessageResult<()>, Message> {
+            let _ = message.downcast::<Increment>()?;
+            log.push("increment");
+            *count += self.step;
+            Ok(MessageResult::RequestRebuild)
+        }
+    }
+
+    #[test]
+    fn counter_builds_rebuilds_and_counts() {
+        let mut cx = view_cx();
+        let mut log = vec![];
+
+        let mut prev = Wrapped(Counter { step: 1 });
+        let (mut id, mut state, mut element) = prev.build(&mut cx);
+        assert_eq!(element.text(), "0");
+
+        for step in [2, 1] {
+            let result = prev.message(&[], &mut state, Message::new(vec![], Increment), &mut log);
+            assert!(matches!(result, MessageResult::RequestRebuild));
+            let next = Wrapped(Counter { step });
+            let changes = next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+            assert!(changes.contains(ChangeFlags::LAYOUT));
+            prev = next;
+        }
+        assert_eq!(state.state, 3);
+        assert_eq!(element.text(), "3");
+
+        // Without a change of the state, the generated view is equal to the stored one.
+        let next = Wrapped(Counter { step: 2 });
+        let changes = next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+        assert_eq!(changes, ChangeFlags::empty());
+
+        // Messages the counter doesn't take reach the label, which has no use for them.
+        let result = next.message(&[], &mut state, Message::new(vec![], 5u32), &mut log);
+        let MessageResult::Stale(message) = result else {
+            panic!("expected a stale message");
+        };
+        assert_eq!(message.downcast::<u32>().unwrap(), 5);
+        assert_eq!(log, ["increment", "increment"]);
+    }
+
+    struct Resize(usize);
+
+    /// Generates a label for each of its items, `Resize` messages change their number.
+    struct Items;
+
+    impl SequenceWrapper<()> for Items {
+        type State = usize;
+
+        type VT = Vec<Label>;
+
+        fn init(&self) -> usize {
+            3
+        }
+
+        fn gen(&self, len: &usize) -> Vec<Label> {
+            (0..*len).map(|index| label(format!("{index}"))).collect()
+        }
+
+        fn message(
+            &self,
+            _id_path: &[Id],
+            len: &mut usize,
+            message: Message,
+            _app_state: &mut (),
+        ) -> Result<MessageResult<()>, Message> {
+            let Resize(new_len) = message.downcast::<Resize>()?;
+            *len = new_len;
+            Ok(MessageResult::RequestRebuild)
+        }
+    }
+
+    #[test]
+    fn sequence_grows_and_shrinks_with_its_state() {
+        let mut cx = view_cx();
+        let seq = WrappedSequence(Items);
+        let mut elements = vec![];
+        let mut state = seq.build(&mut cx, &mut elements);
+        let mut slots = PodSlots::from(elements);
+        let keys: Vec<SlotKey> = (0..3)
+            .map(|position| slots.key(position).unwrap())
+            .collect();
+        let ids: Vec<Id> = slots.iter().map(Pod::id).collect();
+        assert_eq!(seq.count(&state), 3);
+
+        let mut resize = |len: usize, slots: &mut PodSlots, state: &mut WrappedState<_, _, _>| {
+            // The wrapper sees the message before the sequence looks for the child owning it.
+            let result = seq.message(&[ids[0]], state, Message::new(vec![], Resize(len)), &mut ());
+            assert!(matches!(result, MessageResult::RequestRebuild));
+            seq.rebuild(&mut cx, &seq, state, &mut slots.splice());
+        };
+
+        resize(5, &mut slots, &mut state);
+        assert_eq!(seq.count(&state), 5);
+        assert_eq!(slots.len(), 5);
+        let texts: Vec<String> = slots
+            .iter_mut()
+            .map(|pod| {
+                pod.downcast_mut::<crate::widget::Label>()
+                    .unwrap()
+                    .text()
+                    .into()
+            })
+            .collect();
+        assert_eq!(texts, ["0", "1", "2", "3", "4"]);
+
+        resize(2, &mut slots, &mut state);
+        assert_eq!(seq.count(&state), 2);
+        assert_eq!(slots.len(), 2);
+        assert!(seq.owns_id(&state, ids[1]));
+        assert!(!seq.owns_id(&state, ids[2]));
+
+        // The children kept through the changes are still found by their keys.
+        for position in 0..2 {
+            assert_eq!(slots.get(keys[position]).map(Pod::id), Some(ids[position]));
+        }
+        assert!(slots.get(keys[2]).is_none());
+    }
+}
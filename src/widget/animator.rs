@@ -0,0 +1,180 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use vello::{
+    kurbo::{Point, Size},
+    SceneBuilder,
+};
+
+use crate::{IdPath, Message};
+
+use super::{
+    AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx,
+    PaintCx, Pod, UpdateCx, Widget,
+};
+
+/// Drives an animation of the view owning it with animation frames.
+///
+/// While running, every animation frame sends the time since the previous one as a
+/// [`Duration`] message, the view advances the animation and rebuilds the child from it.
+/// Like [`Spinner`](super::Spinner), it does not advance while animations are paused.
+pub struct Animator {
+    id_path: IdPath,
+    child: Pod,
+    is_running: bool,
+    is_paused: bool,
+}
+
+impl Animator {
+    pub fn new(id_path: &IdPath, child: Pod) -> Self {
+        Animator {
+            id_path: id_path.clone(),
+            child,
+            is_running: false,
+            is_paused: false,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.is_running
+    }
+
+    pub fn set_running(&mut self, is_running: bool) -> ChangeFlags {
+        if self.is_running == is_running {
+            return ChangeFlags::empty();
+        }
+        self.is_running = is_running;
+        // Frames are requested in the update pass.
+        if is_running {
+            ChangeFlags::UPDATE
+        } else {
+            ChangeFlags::empty()
+        }
+    }
+
+    fn is_animating(&self) -> bool {
+        self.is_running && !self.is_paused
+    }
+}
+
+impl Widget for Animator {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.child.event(cx, event);
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        match event {
+            LifeCycle::AnimFrame(interval) => {
+                if self.is_animating() {
+                    let interval = Duration::from_nanos(*interval);
+                    cx.add_message(Message::new(self.id_path.clone(), interval));
+                    cx.request_anim_frame();
+                }
+            }
+            LifeCycle::AnimationsPaused(is_paused) => {
+                self.is_paused = *is_paused;
+                if self.is_animating() {
+                    cx.request_anim_frame();
+                }
+            }
+            _ => (),
+        }
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        if self.is_animating() {
+            cx.request_anim_frame();
+        }
+        self.child.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{Harness, Leaf};
+    use test_log::test;
+    use xilem_core::Id;
+
+    const FRAME: u64 = 16_000_000;
+
+    fn ticks(harness: &mut Harness) -> Vec<Duration> {
+        harness
+            .take_messages()
+            .into_iter()
+            .map(|message| *message.body.downcast::<Duration>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn running_animator_reports_frame_intervals() {
+        let mut harness = Harness::new();
+        let animator = Animator::new(&vec![Id::next()], Pod::new(Leaf::new(10.0, 10.0)));
+        let mut root = Pod::new(animator);
+        harness.layout(&mut root, &BoxConstraints::tight(Size::new(10.0, 10.0)));
+        harness.update(&mut root);
+        assert!(!root.anim_frame_requested());
+
+        let changes = root.downcast_mut::<Animator>().unwrap().set_running(true);
+        let _ = root.mark(changes);
+        harness.update(&mut root);
+        assert!(root.anim_frame_requested());
+        harness.lifecycle(&mut root, LifeCycle::AnimFrame(FRAME));
+        harness.lifecycle(&mut root, LifeCycle::AnimFrame(FRAME));
+        assert_eq!(ticks(&mut harness), vec![Duration::from_nanos(FRAME); 2]);
+
+        harness.lifecycle(&mut root, LifeCycle::AnimationsPaused(true));
+        harness.lifecycle(&mut root, LifeCycle::AnimFrame(FRAME));
+        assert!(ticks(&mut harness).is_empty());
+        assert!(!root.anim_frame_requested());
+        harness.lifecycle(&mut root, LifeCycle::AnimationsPaused(false));
+        assert!(root.anim_frame_requested());
+
+        let _ = root.downcast_mut::<Animator>().unwrap().set_running(false);
+        harness.lifecycle(&mut root, LifeCycle::AnimFrame(FRAME));
+        assert!(ticks(&mut harness).is_empty());
+        assert!(!root.anim_frame_requested());
+    }
+}
@@ -15,7 +15,7 @@
 //! The fundamental druid types.
 
 use crate::geometry::Axis;
-use vello::kurbo::Size;
+use vello::kurbo::{Insets, Size};
 
 /// Constraints for layout.
 ///
@@ -59,12 +59,39 @@ impl BoxConstraints {
     ///
     /// [rounded away from zero]: struct.Size.html#method.expand
     pub fn new(min: Size, max: Size) -> BoxConstraints {
+        debug_assert!(
+            min.width <= max.width && min.height <= max.height,
+            "min {min:?} exceeds max {max:?}"
+        );
+        debug_assert!(
+            min.width >= 0.0 && min.height >= 0.0,
+            "negative min {min:?}"
+        );
         BoxConstraints {
             min: min.expand(),
             max: max.expand(),
         }
     }
 
+    /// Create constraints from sizes which are aligned with device pixels already, for example
+    /// with [`distribute_to_pixels`].
+    ///
+    /// Unlike [`new`](Self::new), the sizes are not rounded to whole logical pixels, which are
+    /// off the device pixel grid at fractional scale factors.
+    ///
+    /// [`distribute_to_pixels`]: crate::distribute_to_pixels
+    pub fn new_snapped(min: Size, max: Size) -> BoxConstraints {
+        debug_assert!(
+            min.width <= max.width && min.height <= max.height,
+            "min {min:?} exceeds max {max:?}"
+        );
+        debug_assert!(
+            min.width >= 0.0 && min.height >= 0.0,
+            "negative min {min:?}"
+        );
+        BoxConstraints { min, max }
+    }
+
     /// Create a "tight" box constraints object.
     ///
     /// A "tight" constraint can only be satisfied by a single size.
@@ -165,6 +192,16 @@ impl BoxConstraints {
         BoxConstraints::new(min, max)
     }
 
+    /// Shrink min and max constraints by the total insets on each axis.
+    ///
+    /// Like [`shrink`](Self::shrink), the constraints are clamped at zero.
+    pub fn shrink_insets(&self, insets: Insets) -> BoxConstraints {
+        self.shrink(Size::new(
+            Axis::Horizontal.major_total(insets),
+            Axis::Vertical.major_total(insets),
+        ))
+    }
+
     /// Test whether these constraints contain the given `Size`.
     pub fn contains(&self, size: impl Into<Size>) -> bool {
         let size = size.into();
@@ -183,7 +220,23 @@ impl BoxConstraints {
     ///
     /// Use this function when maintaining an aspect ratio is more important than minimizing the
     /// distance between input and output size width and height.
+    ///
+    /// An aspect ratio of zero (or NaN) asks for the smallest height and the width nearest the
+    /// supplied one. An infinite aspect ratio asks for the smallest width and the largest height,
+    /// or the smallest one if the height is unbounded.
     pub fn constrain_aspect_ratio(&self, aspect_ratio: f64, width: f64) -> Size {
+        if aspect_ratio == 0.0 || aspect_ratio.is_nan() {
+            return self.constrain(Size::new(width.abs(), 0.0));
+        }
+        if aspect_ratio.is_infinite() {
+            let height = if self.is_height_bounded() {
+                self.max.height
+            } else {
+                self.min.height
+            };
+            return self.constrain(Size::new(0.0, height));
+        }
+
         // Minimizing/maximizing based on aspect ratio seems complicated, but in reality everything
         // is linear, so the amount of work to do is low.
         let ideal_size = Size {
@@ -202,10 +255,8 @@ impl BoxConstraints {
 
         // Then we check if any `Size`s with our desired aspect ratio are inside the constraints.
         // TODO this currently outputs garbage when things are < 0.
-        let min_w_min_h = self.min.height / self.min.width;
         let max_w_min_h = self.min.height / self.max.width;
         let min_w_max_h = self.max.height / self.min.width;
-        let max_w_max_h = self.max.height / self.max.width;
 
         // When the aspect ratio line crosses the constraints, the closest point must be one of the
         // two points where the aspect ratio enters/exits.
@@ -227,57 +278,57 @@ impl BoxConstraints {
                 width: self.max.width,
                 height: self.min.height,
             }
-        } else if aspect_ratio > min_w_min_h {
-            // hits the constraints on the min width line
-            if width < self.min.width {
-                // we take the point on the min width
-                Size {
-                    width: self.min.width,
-                    height: self.min.width * aspect_ratio,
-                }
-            } else if aspect_ratio < max_w_max_h {
-                // exits through max.width
-                Size {
-                    width: self.max.width,
-                    height: self.max.width * aspect_ratio,
-                }
-            } else {
-                // exits through max.height
-                Size {
-                    width: self.max.height * aspect_ratio.recip(),
-                    height: self.max.height,
-                }
-            }
         } else {
-            // final case is where we hit constraints on the min height line
-            if width < self.min.width {
-                // take the point on the min height
-                Size {
-                    width: self.min.height * aspect_ratio.recip(),
-                    height: self.min.height,
-                }
-            } else if aspect_ratio > max_w_max_h {
-                // exit thru max height
-                Size {
-                    width: self.max.height * aspect_ratio.recip(),
-                    height: self.max.height,
-                }
-            } else {
-                // exit thru max width
-                Size {
-                    width: self.max.width,
-                    height: self.max.width * aspect_ratio,
-                }
+            // The line enters the constraints on the min width or min height line and exits them
+            // on the max width or max height line. Computing the widths of both points directly
+            // keeps unbounded axes out of the arithmetic, where they would produce NaNs.
+            let entry_width = self.min.width.max(self.min.height / aspect_ratio);
+            let exit_width = self.max.width.min(self.max.height / aspect_ratio);
+            let width = width.max(entry_width).min(exit_width);
+            Size {
+                width,
+                height: width * aspect_ratio,
             }
         }
     }
 
     /// Sets the max on a given axis to infinity.
     pub fn unbound_max(&self, axis: Axis) -> Self {
-        match axis {
-            Axis::Horizontal => self.unbound_max_width(),
-            Axis::Vertical => self.unbound_max_height(),
-        }
+        axis.select(self.unbound_max_width(), self.unbound_max_height())
+    }
+
+    /// Find the largest `Size` within these `BoxConstraints` with the given aspect ratio, defined
+    /// as `height / width` like in [`constrain_aspect_ratio`](Self::constrain_aspect_ratio).
+    ///
+    /// If both axes are unbounded there is no largest size, the smallest one is returned instead.
+    /// If the min constraints rule out the aspect ratio, the size with the closest aspect ratio
+    /// is returned.
+    pub fn max_area_size(&self, aspect_ratio: f64) -> Size {
+        let aspect_ratio = aspect_ratio.abs();
+        // The width at which the first max constraint is hit.
+        let width = if aspect_ratio == 0.0 || aspect_ratio.is_nan() {
+            self.max.width
+        } else {
+            self.max.width.min(self.max.height / aspect_ratio)
+        };
+        let width = if width.is_finite() { width } else { 0.0 };
+        self.constrain_aspect_ratio(aspect_ratio, width)
+    }
+
+    /// Sets the min on a given axis to zero.
+    pub fn loosen_axis(&self, axis: Axis) -> Self {
+        axis.constraints(self, 0.0, axis.major(self.max))
+    }
+
+    /// Sets min and max on a given axis to `extent`, constrained to the current bounds.
+    pub fn tighten_axis(&self, axis: Axis, extent: f64) -> Self {
+        let extent = extent.max(axis.major(self.min)).min(axis.major(self.max));
+        axis.constraints(self, extent, extent)
+    }
+
+    /// Sets the min on a given axis to zero and the max to infinity.
+    pub fn unbounded_axis(&self, axis: Axis) -> Self {
+        axis.constraints(self, 0.0, f64::INFINITY)
     }
 
     /// Sets max width to infinity.
@@ -426,4 +477,211 @@ mod tests {
 
         assert_eq!(BoxConstraints::UNBOUNDED.min(), Size::ZERO);
     }
+
+    #[test]
+    fn shrink_insets() {
+        let insets = Insets::new(2.0, 1.0, 3.0, 4.0);
+        assert_eq!(
+            bc(10.0, 10.0, 100.0, 50.0).shrink_insets(insets),
+            bc(5.0, 5.0, 95.0, 45.0)
+        );
+        // Fractional insets are rounded away from zero like in `shrink`.
+        assert_eq!(
+            bc(0.0, 0.0, 100.0, 50.0).shrink_insets(Insets::uniform(0.25)),
+            bc(0.0, 0.0, 99.0, 49.0)
+        );
+        // Constraints smaller than the insets are clamped at zero.
+        assert_eq!(
+            bc(2.0, 20.0, 4.0, 30.0).shrink_insets(Insets::uniform_xy(10.0, 0.0)),
+            bc(0.0, 20.0, 0.0, 30.0)
+        );
+        assert_eq!(
+            BoxConstraints::UNBOUNDED.shrink_insets(insets).max(),
+            BoxConstraints::UNBOUNDED.max()
+        );
+    }
+
+    fn is_valid(bc: BoxConstraints) -> bool {
+        bc.min().width <= bc.max().width && bc.min().height <= bc.max().height
+    }
+
+    #[test]
+    fn axis_constraints() {
+        let bc = bc(10.0, 20.0, 100.0, 200.0);
+        assert_eq!(
+            Axis::Horizontal.constraints(&bc, 0.0, 50.0),
+            self::bc(0.0, 20.0, 50.0, 200.0)
+        );
+        assert_eq!(
+            Axis::Vertical.constraints(&bc, 5.0, f64::INFINITY),
+            self::bc(10.0, 5.0, 100.0, f64::INFINITY)
+        );
+        // The min is lowered to the max.
+        assert_eq!(
+            Axis::Vertical.constraints(&bc, 80.0, 40.0),
+            self::bc(10.0, 40.0, 100.0, 40.0)
+        );
+        assert_eq!(
+            Axis::Horizontal.constraints(&bc, f64::INFINITY, f64::INFINITY),
+            self::bc(f64::INFINITY, 20.0, f64::INFINITY, 200.0)
+        );
+    }
+
+    #[test]
+    fn axis_helpers() {
+        let bc = bc(10.0, 20.0, 100.0, f64::INFINITY);
+        assert_eq!(
+            bc.loosen_axis(Axis::Horizontal),
+            self::bc(0.0, 20.0, 100.0, f64::INFINITY)
+        );
+        assert_eq!(
+            bc.unbounded_axis(Axis::Horizontal),
+            self::bc(0.0, 20.0, f64::INFINITY, f64::INFINITY)
+        );
+        assert_eq!(
+            bc.tighten_axis(Axis::Horizontal, 50.0),
+            self::bc(50.0, 20.0, 50.0, f64::INFINITY)
+        );
+        // The extent is kept within the current bounds.
+        assert_eq!(
+            bc.tighten_axis(Axis::Horizontal, 500.0),
+            self::bc(100.0, 20.0, 100.0, f64::INFINITY)
+        );
+        assert_eq!(
+            bc.tighten_axis(Axis::Vertical, f64::INFINITY),
+            self::bc(10.0, f64::INFINITY, 100.0, f64::INFINITY)
+        );
+    }
+
+    #[test]
+    fn axis_helpers_keep_min_below_max() {
+        let extents = [0.0, 15.0, 150.0, f64::INFINITY];
+        let bcs = [
+            bc(0.0, 0.0, 0.0, 0.0),
+            bc(10.0, 20.0, 100.0, 200.0),
+            bc(50.0, 50.0, 50.0, 50.0),
+            bc(0.0, 0.0, f64::INFINITY, f64::INFINITY),
+        ];
+        for bc in bcs {
+            for axis in [Axis::Horizontal, Axis::Vertical] {
+                assert!(is_valid(bc.loosen_axis(axis)));
+                assert!(is_valid(bc.unbounded_axis(axis)));
+                for extent in extents {
+                    assert!(is_valid(bc.tighten_axis(axis, extent)), "{bc:?} {extent}");
+                    for other in extents {
+                        let constrained = axis.constraints(&bc, extent, other);
+                        assert!(is_valid(constrained), "{bc:?} {extent} {other}");
+                        assert_eq!(axis.minor(constrained), axis.minor(bc));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Constraints covering bounded, half bounded, unbounded and tight cases.
+    fn all_kinds() -> [BoxConstraints; 8] {
+        [
+            bc(0.0, 0.0, 100.0, 50.0),
+            bc(20.0, 10.0, 100.0, 50.0),
+            bc(0.0, 0.0, 100.0, f64::INFINITY),
+            bc(0.0, 0.0, f64::INFINITY, 50.0),
+            bc(20.0, 10.0, f64::INFINITY, 50.0),
+            BoxConstraints::UNBOUNDED,
+            bc(20.0, 10.0, f64::INFINITY, f64::INFINITY),
+            BoxConstraints::tight(Size::new(40.0, 40.0)),
+        ]
+    }
+
+    const RATIOS: [f64; 8] = [0.0, 0.25, 0.5, 1.0, 2.0, 4.0, f64::INFINITY, f64::NAN];
+
+    fn fits(bc: &BoxConstraints, size: Size) -> bool {
+        const EPSILON: f64 = 1e-9;
+        size.width.is_finite()
+            && size.height.is_finite()
+            && bc.min().width - EPSILON <= size.width
+            && size.width <= bc.max().width + EPSILON
+            && bc.min().height - EPSILON <= size.height
+            && size.height <= bc.max().height + EPSILON
+    }
+
+    #[test]
+    fn constrain_aspect_ratio_stays_within_constraints() {
+        for bc in all_kinds() {
+            for ratio in RATIOS {
+                for width in [0.0, 30.0, 100.0, 1000.0] {
+                    let size = bc.constrain_aspect_ratio(ratio, width);
+                    assert!(fits(&bc, size), "{bc:?} {ratio} {width}: {size:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn degenerate_aspect_ratios() {
+        let bc = bc(20.0, 10.0, 100.0, 50.0);
+        assert_eq!(bc.constrain_aspect_ratio(0.0, 30.0), Size::new(30.0, 10.0));
+        assert_eq!(
+            bc.constrain_aspect_ratio(f64::NAN, 300.0),
+            Size::new(100.0, 10.0)
+        );
+        assert_eq!(
+            bc.constrain_aspect_ratio(f64::INFINITY, 30.0),
+            Size::new(20.0, 50.0)
+        );
+        assert_eq!(
+            BoxConstraints::UNBOUNDED.constrain_aspect_ratio(f64::INFINITY, 30.0),
+            Size::ZERO
+        );
+        assert_eq!(bc.max_area_size(0.0), Size::new(100.0, 10.0));
+        assert_eq!(bc.max_area_size(f64::INFINITY), Size::new(20.0, 50.0));
+    }
+
+    #[test]
+    fn max_area_size_examples() {
+        assert_eq!(
+            bc(0.0, 0.0, 100.0, 50.0).max_area_size(0.5),
+            Size::new(100.0, 50.0)
+        );
+        assert_eq!(
+            bc(0.0, 0.0, 100.0, 50.0).max_area_size(1.0),
+            Size::new(50.0, 50.0)
+        );
+        assert_eq!(
+            bc(0.0, 0.0, 100.0, f64::INFINITY).max_area_size(2.0),
+            Size::new(100.0, 200.0)
+        );
+        assert_eq!(
+            bc(0.0, 0.0, f64::INFINITY, 50.0).max_area_size(0.25),
+            Size::new(200.0, 50.0)
+        );
+        // The min width rules out the ratio, the closest one is used.
+        assert_eq!(
+            bc(20.0, 10.0, 100.0, 50.0).max_area_size(4.0),
+            Size::new(20.0, 50.0)
+        );
+        // Without any max there is no largest size.
+        assert_eq!(
+            bc(20.0, 10.0, f64::INFINITY, f64::INFINITY).max_area_size(2.0),
+            Size::new(20.0, 40.0)
+        );
+    }
+
+    #[test]
+    fn max_area_size_is_largest() {
+        for bc in all_kinds() {
+            for ratio in RATIOS {
+                let size = bc.max_area_size(ratio);
+                assert!(fits(&bc, size), "{bc:?} {ratio}: {size:?}");
+                let keeps_ratio = ratio.is_finite()
+                    && ratio > 0.0
+                    && (size.height - size.width * ratio).abs() < 1e-9;
+                if keeps_ratio && (bc.is_width_bounded() || bc.is_height_bounded()) {
+                    // Growing the size any further breaks a max constraint.
+                    let at_max = (size.width - bc.max().width).abs() < 1e-9
+                        || (size.height - bc.max().height).abs() < 1e-9;
+                    assert!(at_max, "{bc:?} {ratio}: {size:?}");
+                }
+            }
+        }
+    }
 }
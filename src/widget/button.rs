@@ -12,68 +12,112 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::ops::Deref;
-
-use parley::Layout;
+use glazier::KbKey;
 use vello::{
-    kurbo::{Affine, Insets, Size},
-    peniko::{Brush, Color},
+    kurbo::{Insets, Point, Shape, Size},
     SceneBuilder,
 };
 
-use crate::{text::ParleyBrush, IdPath, Message};
+use crate::{snap_stroke_width, Axis, IdPath, Message, Theme};
 
 use super::{
     contexts::LifeCycleCx,
+    paint_focus_ring,
     piet_scene_helpers::{self, UnitPoint},
-    AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, PaintCx, UpdateCx,
-    Widget,
+    AccessCx, BoxConstraints, ChangeFlags, DebugState, Event, EventCx, Intrinsic, LayoutCx,
+    LifeCycle, PaintCx, Pod, UpdateCx, Widget, FOCUS_RING_EXTENT,
 };
 
+/// A clickable control wrapping a child, usually a label.
+///
+/// A click sends an empty message to the view at `id_path`. Clicks are triggered by releasing the
+/// mouse over the button after pressing it, by Space or Enter while the button is focused and by
+/// the accessibility default action.
 pub struct Button {
     id_path: IdPath,
-    label: String,
-    layout: Option<Layout<ParleyBrush>>,
+    child: Pod,
+    disabled: bool,
+    /// The corner radius of the last paint, to leave out the corners in hit testing.
+    corner_radius: f64,
 }
 
 impl Button {
-    pub fn new(id_path: &IdPath, label: String) -> Button {
+    pub fn new(id_path: &IdPath, child: Pod) -> Button {
         Button {
             id_path: id_path.clone(),
-            label,
-            layout: None,
+            child,
+            disabled: false,
+            corner_radius: 0.0,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool) -> ChangeFlags {
+        if self.disabled == disabled {
+            return ChangeFlags::empty();
         }
+        self.disabled = disabled;
+        ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY
     }
 
-    pub fn set_label(&mut self, label: String) -> ChangeFlags {
-        self.label = label;
-        self.layout = None;
-        ChangeFlags::LAYOUT | ChangeFlags::PAINT
+    fn click(&self, cx: &mut EventCx) {
+        cx.add_message(Message::new(self.id_path.clone(), ()));
     }
 }
 
 // See druid's button for info.
 const LABEL_INSETS: Insets = Insets::uniform_xy(8., 2.);
+const MIN_HEIGHT: f64 = 24.0;
 
 impl Widget for Button {
     fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        // The content gets the event first, input it handles doesn't press the button. Like a
+        // disabled scope, a disabled button gives its content no input.
+        let outer = cx.cx_state.disabled;
+        cx.cx_state.disabled |= self.disabled;
+        self.child.event(cx, event);
+        cx.cx_state.disabled = outer;
+        if self.disabled || cx.is_handled() {
+            if cx.is_active() && (self.disabled || matches!(event, Event::MouseUp(_))) {
+                cx.set_active(false);
+                cx.request_paint();
+            }
+            return;
+        }
         match event {
             Event::MouseDown(_) => {
                 cx.set_active(true);
+                cx.request_focus();
                 cx.request_paint();
+                cx.set_handled(true);
             }
             Event::MouseUp(_) => {
                 if cx.is_hot() && cx.is_active() {
-                    cx.add_message(Message::new(self.id_path.clone(), ()));
+                    self.click(cx);
+                    cx.set_handled(true);
                 }
                 cx.set_active(false);
                 cx.request_paint();
             }
+            Event::KeyDown(key_event) if cx.is_focused() => match &key_event.key {
+                KbKey::Enter => {
+                    self.click(cx);
+                    cx.set_handled(true);
+                }
+                KbKey::Character(c) if c == " " => {
+                    self.click(cx);
+                    cx.set_handled(true);
+                }
+                _ => (),
+            },
             Event::TargetedAccessibilityAction(request) => {
                 if request.action == accesskit::Action::Default
                     && cx.is_accesskit_target(request.target)
                 {
-                    cx.add_message(Message::new(self.id_path.clone(), ()));
+                    self.click(cx);
                 }
             }
             _ => (),
@@ -81,61 +125,75 @@ impl Widget for Button {
     }
 
     fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
-        if let LifeCycle::HotChanged(_) = event {
-            cx.request_paint()
+        match event {
+            LifeCycle::HotChanged(_) => cx.request_paint(),
+            LifeCycle::BuildFocusChain if !self.disabled => cx.register_for_focus(),
+            _ => (),
         }
+        self.child.lifecycle(cx, event);
     }
 
     fn update(&mut self, cx: &mut UpdateCx) {
-        cx.request_layout();
+        self.child.update(cx);
     }
 
     fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
         let padding = Size::new(LABEL_INSETS.x_value(), LABEL_INSETS.y_value());
-        let min_height = 24.0;
-        let mut lcx = parley::LayoutContext::new();
-        let mut layout_builder = lcx.ranged_builder(cx.font_cx(), &self.label, 1.0);
-
-        layout_builder.push_default(&parley::style::StyleProperty::Brush(ParleyBrush(
-            Brush::Solid(Color::rgb8(0xf0, 0xf0, 0xea)),
-        )));
-        let mut layout = layout_builder.build();
-        // Question for Chad: is this needed?
-        layout.break_all_lines(None, parley::layout::Alignment::Start);
-        let size = Size::new(
-            layout.width() as f64 + padding.width,
-            (layout.height() as f64 + padding.height).max(min_height),
-        );
-        self.layout = Some(layout);
-        //(Size::new(10.0, min_height), size)
-        bc.constrain(size)
+        let child_bc = bc.shrink_insets(LABEL_INSETS).loosen();
+        let child_size = self.child.layout(cx, &child_bc);
+        let size = bc.constrain(Size::new(
+            child_size.width + padding.width,
+            (child_size.height + padding.height).max(MIN_HEIGHT),
+        ));
+        let offset = (size.to_vec2() - child_size.to_vec2()) * 0.5;
+        self.child.set_origin(cx, offset.to_point());
+        let baseline = self.child.layout_rect().y1 - self.child.baseline_offset();
+        cx.set_baseline_offset(size.height - baseline);
+        cx.set_paint_insets(FOCUS_RING_EXTENT);
+        size
     }
 
     fn accessibility(&mut self, cx: &mut AccessCx) {
-        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::Button);
-        builder.set_name(self.label.deref());
-        builder.set_default_action_verb(accesskit::DefaultActionVerb::Click);
-        cx.push_node(builder);
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::Button);
+            builder.set_children(vec![self.child.id().into()]);
+            // Screen readers name the button after its content.
+            builder.set_labelled_by(vec![self.child.id().into()]);
+            builder.set_default_action_verb(accesskit::DefaultActionVerb::Click);
+            builder.add_action(accesskit::Action::Default);
+            builder.add_action(accesskit::Action::Focus);
+            if self.disabled {
+                builder.set_disabled();
+            }
+            cx.push_node(builder);
+        }
     }
 
     fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
-        let is_hot = cx.is_hot();
-        let is_active = cx.is_active();
-        let button_border_width = 2.0;
+        let disabled = self.disabled || cx.is_disabled();
+        let is_hot = cx.is_hot() && !disabled;
+        let is_active = cx.is_active() && !disabled;
+        let theme = Theme::get(cx.env());
+        self.corner_radius = theme.corner_radius;
+        let button_border_width = snap_stroke_width(2.0, cx.scale());
         let rounded_rect = cx
             .size()
             .to_rect()
             .inset(-0.5 * button_border_width)
-            .to_rounded_rect(4.0);
+            .to_rounded_rect(theme.corner_radius);
         let border_color = if is_hot {
-            Color::rgb8(0xa1, 0xa1, 0xa1)
+            theme.highlight
         } else {
-            Color::rgb8(0x3a, 0x3a, 0x3a)
+            theme.background
         };
-        let bg_stops = if is_active {
-            [Color::rgb8(0x3a, 0x3a, 0x3a), Color::rgb8(0xa1, 0xa1, 0xa1)]
+        let bg_stops = if disabled {
+            [theme.background_active, theme.background]
+        } else if is_active {
+            [theme.background, theme.highlight]
         } else {
-            [Color::rgb8(0xa1, 0xa1, 0xa1), Color::rgb8(0x3a, 0x3a, 0x3a)]
+            [theme.highlight, theme.background]
         };
         piet_scene_helpers::stroke(builder, &rounded_rect, border_color, button_border_width);
         piet_scene_helpers::fill_lin_gradient(
@@ -145,11 +203,213 @@ impl Widget for Button {
             UnitPoint::TOP,
             UnitPoint::BOTTOM,
         );
-        if let Some(layout) = &self.layout {
-            let size = Size::new(layout.width() as f64, layout.height() as f64);
-            let offset = (cx.size().to_vec2() - size.to_vec2()) * 0.5;
-            let transform = Affine::translate(offset);
-            crate::text::render_text(builder, transform, layout);
+        self.child.paint(cx, builder);
+        if !disabled {
+            paint_focus_ring(cx, builder);
+        }
+    }
+
+    fn hit_test(&self, size: Size, pos: Point) -> bool {
+        let shape = size.to_rect().to_rounded_rect(self.corner_radius);
+        shape.contains(pos)
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+
+    fn debug_state(&self) -> DebugState {
+        // Whether it is hot or pressed is part of the state of the pod.
+        DebugState::new(self.short_type_name()).with_value("disabled", self.disabled)
+    }
+
+    fn compute_intrinsic(
+        &mut self,
+        cx: &mut LayoutCx,
+        axis: Axis,
+        kind: Intrinsic,
+        cross: f64,
+    ) -> f64 {
+        let cross = (cross - axis.cross().major_total(LABEL_INSETS)).max(0.0);
+        let extent = self.child.intrinsic(cx, axis, kind, cross) + axis.major_total(LABEL_INSETS);
+        match axis {
+            Axis::Horizontal => extent,
+            Axis::Vertical => extent.max(MIN_HEIGHT),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{key_down, mouse, Harness, Leaf};
+    use crate::widget::{LinearLayout, PodFlags};
+    use std::sync::{Arc, Mutex};
+    use test_log::test;
+    use xilem_core::Id;
+
+    /// Interactive content, which logs the input it gets and handles presses.
+    struct Toggle(Arc<Mutex<Vec<&'static str>>>);
+
+    impl Widget for Toggle {
+        fn event(&mut self, cx: &mut EventCx, event: &Event) {
+            let name = match event {
+                Event::MouseDown(_) => "down",
+                Event::MouseUp(_) => "up",
+                Event::KeyDown(_) => "key",
+                _ => return,
+            };
+            self.0.lock().unwrap().push(name);
+            if let Event::MouseDown(_) = event {
+                cx.set_handled(true);
+            }
+        }
+
+        fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+            if let LifeCycle::BuildFocusChain = event {
+                cx.register_for_focus();
+            }
         }
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, _cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            Size::new(40.0, 10.0)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+    }
+
+    fn button(harness: &mut Harness) -> (IdPath, Pod) {
+        let id_path = vec![Id::next()];
+        let mut pod = Pod::new(Button::new(&id_path, Pod::new(Leaf::new(40.0, 10.0))));
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(200.0, 200.0));
+        let size = harness.layout(&mut pod, &bc);
+        assert_eq!(size, Size::new(56.0, 24.0));
+        (id_path, pod)
+    }
+
+    #[test]
+    fn click_inside_fires() {
+        let mut harness = Harness::new();
+        let (id_path, mut pod) = button(&mut harness);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((12.0, 10.0))));
+
+        let messages = harness.take_messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id_path, id_path);
+    }
+
+    #[test]
+    fn press_drag_out_release_does_not_fire() {
+        let mut harness = Harness::new();
+        let (_, mut pod) = button(&mut harness);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut pod, Event::MouseMove(mouse((300.0, 10.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((300.0, 10.0))));
+
+        assert!(harness.take_messages().is_empty());
+        assert!(!pod.is_active());
+    }
+
+    #[test]
+    fn keyboard_activation_fires_when_focused() {
+        let mut harness = Harness::new();
+        let (id_path, mut pod) = button(&mut harness);
+
+        harness.event(&mut pod, key_down(KbKey::Enter));
+        assert!(harness.take_messages().is_empty());
+
+        harness.window_state.focus = Some(pod.id());
+        harness.event(&mut pod, key_down(KbKey::Enter));
+        harness.event(&mut pod, key_down(KbKey::Character(" ".into())));
+        harness.event(&mut pod, key_down(KbKey::Tab));
+
+        let messages = harness.take_messages();
+        assert_eq!(messages.len(), 2);
+        assert!(messages.iter().all(|message| message.id_path == id_path));
+    }
+
+    #[test]
+    fn disabled_button_ignores_input() {
+        let mut harness = Harness::new();
+        let (_, mut pod) = button(&mut harness);
+        let _ = pod.downcast_mut::<Button>().unwrap().set_disabled(true);
+        harness.window_state.focus = Some(pod.id());
+
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((10.0, 10.0))));
+        harness.event(&mut pod, key_down(KbKey::Enter));
+
+        assert!(harness.take_messages().is_empty());
+    }
+
+    #[test]
+    fn disabling_repaints_and_updates_accessibility() {
+        let mut harness = Harness::new();
+        let (_, mut pod) = button(&mut harness);
+        let mut button = pod.as_mut::<Button>();
+        button.apply(|button| button.set_disabled(false));
+        assert_eq!(button.changes(), ChangeFlags::empty());
+
+        button.apply(|button| button.set_disabled(true));
+        assert!(button.disabled);
+        assert_eq!(
+            button.changes(),
+            ChangeFlags::DESCENDANT_REQUESTED_PAINT
+                | ChangeFlags::DESCENDANT_REQUESTED_ACCESSIBILITY
+        );
+        assert!(pod.state.flags.contains(PodFlags::REQUEST_PAINT));
+    }
+
+    #[test]
+    fn content_gets_input_first() {
+        let mut harness = Harness::new();
+        let log = Arc::new(Mutex::new(vec![]));
+        let toggle = Pod::new(Toggle(log.clone()));
+        let toggle_id = toggle.id();
+        let mut pod = Pod::new(Button::new(&vec![Id::next()], toggle));
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(200.0, 200.0));
+        harness.layout(&mut pod, &bc);
+
+        // The content handles the press, so the button isn't pressed and doesn't fire.
+        harness.event(&mut pod, Event::MouseDown(mouse((28.0, 12.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((28.0, 12.0))));
+        assert!(!pod.is_active());
+        assert!(harness.take_messages().is_empty());
+
+        harness.window_state.focus = Some(toggle_id);
+        harness.event(&mut pod, key_down(KbKey::Enter));
+        assert_eq!(*log.lock().unwrap(), ["down", "up", "key"]);
+        assert!(harness.take_messages().is_empty());
+
+        // A disabled button passes no input on.
+        let _ = pod.downcast_mut::<Button>().unwrap().set_disabled(true);
+        harness.event(&mut pod, Event::MouseDown(mouse((28.0, 12.0))));
+        assert_eq!(log.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn rounded_corners_miss() {
+        let mut harness = Harness::new();
+        let button = Pod::new(Button::new(&vec![], Pod::new(Leaf::new(40.0, 20.0))));
+        let button_id = button.id();
+        let mut root = Pod::new(LinearLayout::new(vec![button], 0.0, Axis::Vertical));
+        harness.frame(&mut root, &BoxConstraints::UNBOUNDED);
+
+        let center = root.widget_at(Point::new(20.0, 12.0)).unwrap();
+        assert!(center.contains(button_id));
+        let corner = root.widget_at(Point::new(0.5, 0.5)).unwrap();
+        assert_eq!(corner.target().map(|(id, _)| id), Some(root.id()));
+
+        // The pointer in the corner doesn't make the button hot either.
+        harness.event(&mut root, Event::MouseMove(mouse((0.5, 0.5))));
+        let button = &root.downcast_mut::<LinearLayout>().unwrap().children[0];
+        assert!(!button.is_hot());
     }
 }
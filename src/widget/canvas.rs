@@ -0,0 +1,188 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use vello::{kurbo::Size, SceneBuilder};
+
+use crate::{IdPath, Message};
+
+use super::{
+    contexts::LifeCycleCx, AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx,
+    LifeCycle, PaintCx, UpdateCx, Widget,
+};
+
+/// Draws the content of a [`Canvas`] into its laid out size.
+pub type PaintFn = Arc<dyn Fn(&mut SceneBuilder, Size) + Send + Sync>;
+
+/// A widget painted by a closure.
+///
+/// When pointer forwarding is enabled, mouse events are sent to the view at `id_path` as
+/// [`Event`]s in local coordinates.
+pub struct Canvas {
+    id_path: IdPath,
+    paint: PaintFn,
+    preferred_size: Option<Size>,
+    forwards_pointer: bool,
+}
+
+impl Canvas {
+    pub fn new(id_path: &IdPath, paint: PaintFn) -> Canvas {
+        Canvas {
+            id_path: id_path.clone(),
+            paint,
+            preferred_size: None,
+            forwards_pointer: false,
+        }
+    }
+
+    pub fn set_paint(&mut self, paint: PaintFn) -> ChangeFlags {
+        self.paint = paint;
+        ChangeFlags::PAINT
+    }
+
+    /// The size the canvas asks for, `None` fills the available space.
+    pub fn set_preferred_size(&mut self, preferred_size: Option<Size>) -> ChangeFlags {
+        if self.preferred_size == preferred_size {
+            return ChangeFlags::empty();
+        }
+        self.preferred_size = preferred_size;
+        ChangeFlags::LAYOUT | ChangeFlags::PAINT
+    }
+
+    pub fn set_forwards_pointer(&mut self, forwards_pointer: bool) {
+        self.forwards_pointer = forwards_pointer;
+    }
+
+    fn forward(&self, cx: &mut EventCx, event: &Event) {
+        cx.add_message(Message::new(self.id_path.clone(), event.clone()));
+    }
+}
+
+impl Widget for Canvas {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        if !self.forwards_pointer {
+            return;
+        }
+        match event {
+            Event::MouseDown(_) => {
+                // Keep receiving moves outside of the canvas until the button is released.
+                cx.set_active(true);
+                self.forward(cx, event);
+            }
+            Event::MouseUp(_) => {
+                if cx.is_active() {
+                    cx.set_active(false);
+                    self.forward(cx, event);
+                }
+            }
+            Event::MouseMove(_) | Event::MouseWheel(_) => {
+                if cx.is_hot() || cx.is_active() {
+                    self.forward(cx, event);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        cx.request_paint();
+    }
+
+    fn layout(&mut self, _cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        match self.preferred_size {
+            Some(size) => bc.constrain(size),
+            None => {
+                let width = if bc.is_width_bounded() {
+                    bc.max().width
+                } else {
+                    bc.min().width
+                };
+                let height = if bc.is_height_bounded() {
+                    bc.max().height
+                } else {
+                    bc.min().height
+                };
+                Size::new(width, height)
+            }
+        }
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        cx.push_node(accesskit::NodeBuilder::new(accesskit::Role::Canvas));
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        (self.paint)(builder, cx.size());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{mouse, Harness};
+    use crate::widget::Pod;
+    use std::sync::Mutex;
+    use test_log::test;
+    use xilem_core::Id;
+
+    #[test]
+    fn paint_receives_laid_out_size() {
+        let mut harness = Harness::new();
+        let painted = Arc::new(Mutex::new(vec![]));
+        let sizes = painted.clone();
+        let paint: PaintFn = Arc::new(move |_, size| sizes.lock().unwrap().push(size));
+        let mut pod = Pod::new(Canvas::new(&vec![Id::next()], paint));
+
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(120.0, 80.0));
+        assert_eq!(harness.layout(&mut pod, &bc), Size::new(120.0, 80.0));
+        harness.paint(&mut pod);
+
+        let canvas = pod.downcast_mut::<Canvas>().unwrap();
+        let changes = canvas.set_preferred_size(Some(Size::new(50.0, 500.0)));
+        let _ = pod.mark(changes);
+        assert_eq!(harness.layout(&mut pod, &bc), Size::new(50.0, 80.0));
+        harness.paint(&mut pod);
+
+        assert_eq!(
+            *painted.lock().unwrap(),
+            [Size::new(120.0, 80.0), Size::new(50.0, 80.0)]
+        );
+    }
+
+    #[test]
+    fn pointer_events_are_forwarded_when_enabled() {
+        let mut harness = Harness::new();
+        let paint: PaintFn = Arc::new(|_, _| {});
+        let mut pod = Pod::new(Canvas::new(&vec![Id::next()], paint));
+        let bc = BoxConstraints::tight(Size::new(100.0, 100.0));
+        harness.layout(&mut pod, &bc);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        assert!(harness.take_messages().is_empty());
+
+        pod.downcast_mut::<Canvas>()
+            .unwrap()
+            .set_forwards_pointer(true);
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 20.0))));
+        harness.event(&mut pod, Event::MouseMove(mouse((150.0, 20.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((150.0, 20.0))));
+        let messages = harness.take_messages();
+        assert_eq!(messages.len(), 3);
+        let event = messages[1].body.downcast_ref::<Event>().unwrap();
+        assert!(matches!(event, Event::MouseMove(mouse) if mouse.pos.x == 150.0));
+    }
+}
@@ -0,0 +1,331 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use glazier::KbKey;
+use vello::{
+    kurbo::{Affine, BezPath, Point, Size},
+    peniko::Stroke,
+    SceneBuilder,
+};
+
+use crate::{round_to_pixel, snap_stroke_width, IdPath, Message, Theme};
+
+use super::{
+    contexts::LifeCycleCx, paint_focus_ring_around, piet_scene_helpers, AccessCx, BoxConstraints,
+    ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, PaintCx, Pod, UpdateCx, Widget,
+    FOCUS_RING_EXTENT,
+};
+
+/// The state displayed by a [`Checkbox`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CheckState {
+    #[default]
+    Unchecked,
+    Checked,
+    /// Neither checked nor unchecked, for example a "select all" header of a partially selected
+    /// list.
+    Indeterminate,
+}
+
+impl CheckState {
+    /// The value a click toggles this state to.
+    ///
+    /// An indeterminate checkbox becomes checked.
+    pub fn toggled(self) -> bool {
+        self != CheckState::Checked
+    }
+}
+
+impl From<bool> for CheckState {
+    fn from(checked: bool) -> Self {
+        if checked {
+            CheckState::Checked
+        } else {
+            CheckState::Unchecked
+        }
+    }
+}
+
+/// A box with a check mark, followed by an optional label.
+///
+/// The checkbox does not toggle itself. A click, Space while focused or the accessibility default
+/// action send an empty message to the view at `id_path`, which is expected to update the state
+/// via [`set_state`](Checkbox::set_state).
+pub struct Checkbox {
+    id_path: IdPath,
+    state: CheckState,
+    label: Option<Pod>,
+}
+
+const BOX_SIZE: f64 = 16.0;
+
+impl Checkbox {
+    pub fn new(id_path: &IdPath, state: CheckState, label: Option<Pod>) -> Checkbox {
+        Checkbox {
+            id_path: id_path.clone(),
+            state,
+            label,
+        }
+    }
+
+    pub fn state(&self) -> CheckState {
+        self.state
+    }
+
+    pub fn set_state(&mut self, state: CheckState) -> ChangeFlags {
+        if self.state == state {
+            return ChangeFlags::empty();
+        }
+        self.state = state;
+        ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY
+    }
+
+    pub fn set_label(&mut self, label: Option<Pod>) -> ChangeFlags {
+        self.label = label;
+        ChangeFlags::tree_structure()
+            | ChangeFlags::LAYOUT
+            | ChangeFlags::PAINT
+            | ChangeFlags::ACCESSIBILITY
+    }
+
+    pub fn label_mut(&mut self) -> Option<&mut Pod> {
+        self.label.as_mut()
+    }
+
+    fn toggle(&self, cx: &mut EventCx) {
+        cx.add_message(Message::new(self.id_path.clone(), ()));
+    }
+}
+
+impl Widget for Checkbox {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        match event {
+            Event::MouseDown(_) => {
+                cx.set_active(true);
+                cx.request_focus();
+                cx.request_paint();
+            }
+            Event::MouseUp(_) => {
+                if cx.is_hot() && cx.is_active() {
+                    self.toggle(cx);
+                }
+                cx.set_active(false);
+                cx.request_paint();
+            }
+            Event::KeyDown(key_event) if cx.is_focused() => {
+                if matches!(&key_event.key, KbKey::Character(c) if c == " ") {
+                    self.toggle(cx);
+                    cx.set_handled(true);
+                }
+            }
+            Event::TargetedAccessibilityAction(request) => {
+                if request.action == accesskit::Action::Default
+                    && cx.is_accesskit_target(request.target)
+                {
+                    self.toggle(cx);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        match event {
+            LifeCycle::HotChanged(_) => cx.request_paint(),
+            LifeCycle::BuildFocusChain => cx.register_for_focus(),
+            _ => (),
+        }
+        if let Some(label) = &mut self.label {
+            label.lifecycle(cx, event);
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        if let Some(label) = &mut self.label {
+            label.update(cx);
+        }
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let spacing = Theme::get(cx.env()).spacing;
+        let mut size = Size::new(BOX_SIZE, BOX_SIZE);
+        if let Some(label) = &mut self.label {
+            let label_bc = bc.shrink((BOX_SIZE + spacing, 0.0)).loosen();
+            let label_size = label.layout(cx, &label_bc);
+            size.width += spacing + label_size.width;
+            size.height = size.height.max(label_size.height);
+            let origin = Point::new(BOX_SIZE + spacing, (size.height - label_size.height) * 0.5);
+            label.set_origin(cx, origin);
+        }
+        cx.set_paint_insets(FOCUS_RING_EXTENT);
+        bc.constrain(size)
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        if let Some(label) = &mut self.label {
+            label.accessibility(cx);
+        }
+        if !cx.is_requested() {
+            return;
+        }
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::CheckBox);
+        if let Some(label) = &self.label {
+            builder.set_children(vec![label.id().into()]);
+            builder.set_labelled_by(vec![label.id().into()]);
+        }
+        builder.set_checked_state(match self.state {
+            CheckState::Unchecked => accesskit::CheckedState::False,
+            CheckState::Checked => accesskit::CheckedState::True,
+            CheckState::Indeterminate => accesskit::CheckedState::Mixed,
+        });
+        builder.set_default_action_verb(if self.state == CheckState::Checked {
+            accesskit::DefaultActionVerb::Uncheck
+        } else {
+            accesskit::DefaultActionVerb::Check
+        });
+        builder.add_action(accesskit::Action::Default);
+        builder.add_action(accesskit::Action::Focus);
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let theme = Theme::get(cx.env());
+        let scale = cx.scale();
+        let border_width = snap_stroke_width(1.5, scale);
+        let box_top = round_to_pixel(((cx.size().height - BOX_SIZE) * 0.5).max(0.0), scale);
+        let box_radius = (theme.corner_radius - 1.0).max(0.0);
+        let box_bounds = Size::new(BOX_SIZE, BOX_SIZE)
+            .to_rect()
+            .with_origin((0.0, box_top));
+        let box_rect = box_bounds
+            .inset(-0.5 * border_width)
+            .to_rounded_rect(box_radius);
+        let border_color = if cx.is_disabled() {
+            theme.background_active
+        } else if cx.is_hot() {
+            theme.highlight
+        } else {
+            theme.border
+        };
+        let fill_color = if cx.is_active() {
+            theme.background_active
+        } else {
+            theme.background
+        };
+        piet_scene_helpers::fill_color(builder, &box_rect, fill_color);
+        piet_scene_helpers::stroke(builder, &box_rect, border_color, border_width);
+
+        let mark_color = if cx.is_disabled() {
+            theme.text_color.with_alpha_factor(0.5)
+        } else {
+            theme.text_color
+        };
+        let mut mark = BezPath::new();
+        match self.state {
+            CheckState::Unchecked => (),
+            CheckState::Checked => {
+                mark.move_to((4.0, 8.5));
+                mark.line_to((7.0, 11.5));
+                mark.line_to((12.0, 4.5));
+            }
+            CheckState::Indeterminate => {
+                mark.move_to((4.0, 8.0));
+                mark.line_to((12.0, 8.0));
+            }
+        }
+        if self.state != CheckState::Unchecked {
+            builder.stroke(
+                &Stroke::new(2.0),
+                Affine::translate((0.0, box_top)),
+                mark_color,
+                None,
+                &mark,
+            );
+        }
+
+        if let Some(label) = &mut self.label {
+            label.paint(cx, builder);
+        }
+        if !cx.is_disabled() {
+            paint_focus_ring_around(cx, builder, box_bounds, box_radius);
+        }
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        if let Some(label) = &self.label {
+            visit(label);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{key_down, mouse, Harness, Leaf};
+    use test_log::test;
+    use xilem_core::Id;
+
+    fn checkbox(harness: &mut Harness, state: CheckState) -> (IdPath, Pod) {
+        let id_path = vec![Id::next()];
+        let label = Pod::new(Leaf::new(40.0, 10.0));
+        let mut pod = Pod::new(Checkbox::new(&id_path, state, Some(label)));
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(200.0, 200.0));
+        let size = harness.layout(&mut pod, &bc);
+        assert_eq!(size, Size::new(62.0, 16.0));
+        (id_path, pod)
+    }
+
+    #[test]
+    fn indeterminate_to_checked() {
+        let mut harness = Harness::new();
+        let (_, mut pod) = checkbox(&mut harness, CheckState::Indeterminate);
+        let checkbox = pod.downcast_mut::<Checkbox>().unwrap();
+
+        assert!(checkbox.state().toggled());
+        assert_eq!(
+            checkbox.set_state(CheckState::Checked),
+            ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY
+        );
+        assert_eq!(checkbox.state(), CheckState::Checked);
+        assert!(!checkbox.state().toggled());
+        assert_eq!(
+            checkbox.set_state(CheckState::Checked),
+            ChangeFlags::empty()
+        );
+    }
+
+    #[test]
+    fn click_space_and_accessibility_toggle() {
+        let mut harness = Harness::new();
+        let (id_path, mut pod) = checkbox(&mut harness, CheckState::Unchecked);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((4.0, 4.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((4.0, 4.0))));
+        assert_eq!(harness.window_state.focus, Some(pod.id()));
+        harness.event(&mut pod, key_down(KbKey::Character(" ".into())));
+        harness.event(&mut pod, key_down(KbKey::Enter));
+        harness.event(
+            &mut pod,
+            Event::TargetedAccessibilityAction(accesskit::ActionRequest {
+                action: accesskit::Action::Default,
+                target: pod.id().into(),
+                data: None,
+            }),
+        );
+
+        let messages = harness.take_messages();
+        assert_eq!(messages.len(), 3);
+        assert!(messages.iter().all(|message| message.id_path == id_path));
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Access to the clipboard for widgets.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The format id under which the platform keeps PNG images on the clipboard.
+#[cfg(feature = "image")]
+const PNG_FORMAT: &str = if cfg!(target_os = "macos") {
+    "public.png"
+} else {
+    "image/png"
+};
+
+/// The clipboard of the system, see [`EventCx::clipboard`](super::EventCx::clipboard).
+///
+/// Without a running application, for example in tests, an in-memory clipboard of the window
+/// stands in for it.
+#[derive(Clone)]
+pub struct Clipboard(Backend);
+
+#[derive(Clone)]
+enum Backend {
+    Shell(glazier::Clipboard),
+    Memory(Rc<RefCell<Contents>>),
+}
+
+/// What the in-memory clipboard holds, setting one kind of contents clears the others like on
+/// the system clipboard.
+#[derive(Default)]
+struct Contents {
+    text: Option<String>,
+    #[cfg(feature = "image")]
+    png: Option<Vec<u8>>,
+}
+
+impl Clipboard {
+    pub(crate) fn shell(clipboard: glazier::Clipboard) -> Self {
+        Clipboard(Backend::Shell(clipboard))
+    }
+
+    /// An empty clipboard which only lives in memory.
+    pub(crate) fn in_memory() -> Self {
+        Clipboard(Backend::Memory(Default::default()))
+    }
+
+    /// The text on the clipboard, if it holds any.
+    pub fn get_text(&self) -> Option<String> {
+        match &self.0 {
+            Backend::Shell(clipboard) => clipboard.get_string(),
+            Backend::Memory(contents) => contents.borrow().text.clone(),
+        }
+    }
+
+    /// Replaces the contents of the clipboard with `text`.
+    pub fn set_text(&mut self, text: &str) {
+        match &mut self.0 {
+            Backend::Shell(clipboard) => clipboard.put_string(text),
+            Backend::Memory(contents) => {
+                let mut contents = contents.borrow_mut();
+                *contents = Contents::default();
+                contents.text = Some(text.to_string());
+            }
+        }
+    }
+
+    /// The PNG image on the clipboard, if it holds one.
+    ///
+    /// [`decode_image`](crate::decode_image) turns it into pixels.
+    #[cfg(feature = "image")]
+    pub fn get_png(&self) -> Option<Vec<u8>> {
+        match &self.0 {
+            Backend::Shell(clipboard) => clipboard
+                .preferred_format(&[PNG_FORMAT])
+                .and_then(|format| clipboard.get_format(format)),
+            Backend::Memory(contents) => contents.borrow().png.clone(),
+        }
+    }
+
+    /// Replaces the contents of the clipboard with an image, encoded as PNG.
+    #[cfg(feature = "image")]
+    pub fn set_png(&mut self, png: &[u8]) {
+        match &mut self.0 {
+            Backend::Shell(clipboard) => {
+                clipboard.put_formats(&[glazier::ClipboardFormat::new(PNG_FORMAT, png)])
+            }
+            Backend::Memory(contents) => {
+                let mut contents = contents.borrow_mut();
+                *contents = Contents::default();
+                contents.png = Some(png.to_vec());
+            }
+        }
+    }
+}
@@ -0,0 +1,898 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use glazier::{KbKey, KeyEvent, MouseButton};
+use vello::{
+    kurbo::{BezPath, Insets, Point, Rect, Size, Vec2},
+    peniko::Color,
+    SceneBuilder,
+};
+
+use crate::id::Id;
+use crate::{IdPath, Message};
+
+use super::{
+    piet_scene_helpers, AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, Label, LayoutCx,
+    LifeCycle, LifeCycleCx, OverlayHandle, PaintCx, Placement, Pod, TimerToken, UpdateCx, Widget,
+};
+
+/// An entry of a menu shown by a [`ContextMenu`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MenuEntry {
+    pub label: String,
+    pub enabled: bool,
+    /// The keyboard shortcut shown next to the label, see [`HotKey::display_label`].
+    ///
+    /// [`HotKey::display_label`]: crate::HotKey::display_label
+    pub shortcut: Option<String>,
+    /// The entries of the submenu this entry opens, empty for an entry which is chosen.
+    pub submenu: Vec<MenuEntry>,
+}
+
+/// The messages a [`ContextMenu`] sends to the view at its id path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContextMenuMessage {
+    /// The user asked for the menu, the view answers with [`ContextMenu::open`].
+    Open,
+    /// The entry at this path was chosen, starting with its index in the top level menu.
+    Choose(Vec<usize>),
+}
+
+/// Shows a menu when the user asks for one on a child widget.
+///
+/// A press of the secondary mouse button, the context menu key or Shift+F10 send
+/// [`ContextMenuMessage::Open`], and the view answers by passing the entries to [`open`]. The
+/// menu opens at the pointer, or below the child when opened with the keyboard, and flips to
+/// stay in the window.
+///
+/// Hovering an entry with a submenu opens the submenu after a short delay. While the menu is
+/// open it takes keyboard focus: the arrow keys move the highlight and open or close submenus,
+/// Enter or Space choose the highlighted entry and Escape closes the innermost menu. Choosing an
+/// entry sends [`ContextMenuMessage::Choose`]. A press outside of the menus or the window losing
+/// focus close all menus, after which focus returns to where it was.
+///
+/// [`open`]: ContextMenu::open
+pub struct ContextMenu {
+    id_path: IdPath,
+    child: Pod,
+    /// The entries to show in the next update.
+    pending: Option<Vec<MenuEntry>>,
+    /// The top level menu while it is open.
+    menu: Option<OverlayHandle>,
+    /// Where the menu was asked for, in window coordinates.
+    anchor: Point,
+    /// The focus before the menu took it, restored when it closes.
+    previous_focus: Option<Id>,
+}
+
+const ROW_HEIGHT: f64 = 24.0;
+const ROW_INSETS: Insets = Insets::uniform_xy(12.0, 0.0);
+/// The space at the end of a row for the arrow marking a submenu.
+const ARROW_WIDTH: f64 = 16.0;
+const PADDING: f64 = 4.0;
+const MIN_WIDTH: f64 = 120.0;
+/// The least space between the label of an item and its shortcut.
+const SHORTCUT_GAP: f64 = 24.0;
+/// How long the pointer rests on an entry before its submenu opens, or another one closes.
+const SUBMENU_DELAY: Duration = Duration::from_millis(200);
+
+impl ContextMenu {
+    pub fn new(id_path: &IdPath, child: Pod) -> ContextMenu {
+        ContextMenu {
+            id_path: id_path.clone(),
+            child,
+            pending: None,
+            menu: None,
+            anchor: Point::ZERO,
+            previous_focus: None,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    /// Opens a menu with `entries`, in answer to [`ContextMenuMessage::Open`].
+    ///
+    /// The menu is shown in the next update, an empty menu isn't shown.
+    pub fn open(&mut self, entries: Vec<MenuEntry>) -> ChangeFlags {
+        self.pending = Some(entries);
+        ChangeFlags::UPDATE
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.menu.is_some()
+    }
+
+    fn request(&mut self, cx: &mut EventCx, anchor: Point) {
+        self.anchor = anchor;
+        cx.add_message(Message::new(self.id_path.clone(), ContextMenuMessage::Open));
+    }
+
+    fn close(&mut self, cx: &mut EventCx) {
+        let Some(menu) = self.menu.take() else {
+            return;
+        };
+        for overlay in open_menus(&menu) {
+            cx.hide_overlay(&overlay);
+        }
+        if cx.is_focused() {
+            cx.cx_state.window_state().focus = self.previous_focus.take();
+        }
+        cx.request_paint();
+        cx.request_accessibility();
+    }
+
+    fn choose(&mut self, cx: &mut EventCx, path: Vec<usize>) {
+        cx.add_message(Message::new(
+            self.id_path.clone(),
+            ContextMenuMessage::Choose(path),
+        ));
+        self.close(cx);
+    }
+
+    /// Handles a key press while the menu is open, returns whether the key was used.
+    fn key_down(&mut self, cx: &mut EventCx, event: &KeyEvent) -> bool {
+        let Some(menu) = self.menu.clone() else {
+            return false;
+        };
+        let menus = open_menus(&menu);
+        let current = menus.last().unwrap();
+        match &event.key {
+            KbKey::ArrowDown => with_panel(current, |panel| panel.step(cx, true)),
+            KbKey::ArrowUp => with_panel(current, |panel| panel.step(cx, false)),
+            KbKey::Home => with_panel(current, |panel| {
+                let first = panel.next_enabled(None, true);
+                panel.highlight(cx, first);
+            }),
+            KbKey::End => with_panel(current, |panel| {
+                let last = panel.next_enabled(None, false);
+                panel.highlight(cx, last);
+            }),
+            KbKey::ArrowRight | KbKey::Enter => self.activate(cx, current, &event.key),
+            KbKey::Character(c) if c == " " => self.activate(cx, current, &event.key),
+            KbKey::ArrowLeft | KbKey::Escape if menus.len() > 1 => {
+                with_panel(&menus[menus.len() - 2], |panel| panel.close_submenu(cx));
+            }
+            KbKey::Escape => self.close(cx),
+            KbKey::Tab => {
+                // Closes the menu, but lets focus move on.
+                self.close(cx);
+                return false;
+            }
+            _ => (),
+        }
+        // The menu is modal for the keyboard.
+        true
+    }
+
+    /// Opens the submenu of the highlighted entry of `current`, or chooses the entry.
+    fn activate(&mut self, cx: &mut EventCx, current: &OverlayHandle, key: &KbKey) {
+        let origin = current.rect().origin();
+        let (submenu, chosen) = with_panel(current, |panel| match panel.highlighted {
+            Some(index) => (
+                panel.open_submenu(cx, index, origin),
+                panel.chosen_path(index),
+            ),
+            None => (None, None),
+        });
+        if let Some(submenu) = submenu {
+            with_panel(&submenu, |panel| {
+                let first = panel.next_enabled(None, true);
+                panel.highlight(cx, first);
+            });
+        } else if let Some(path) = chosen.filter(|_| *key != KbKey::ArrowRight) {
+            self.choose(cx, path);
+        }
+    }
+}
+
+/// The top level menu and the submenus open from it, outermost first.
+fn open_menus(menu: &OverlayHandle) -> Vec<OverlayHandle> {
+    let mut menus = vec![menu.clone()];
+    while let Some(submenu) = menus.last().unwrap().with_pod(|pod| {
+        let panel = pod.expect_mut::<MenuPanel>();
+        let index = panel.open_submenu?;
+        panel.items[index].submenu.clone()
+    }) {
+        menus.push(submenu);
+    }
+    menus
+}
+
+/// Runs `f` with the menu panel in `menu`, which is then repainted.
+fn with_panel<R>(menu: &OverlayHandle, f: impl FnOnce(&mut MenuPanel) -> R) -> R {
+    menu.with_pod(|pod| {
+        let result = f(pod.expect_mut());
+        let _ = pod.mark(ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY);
+        result
+    })
+}
+
+impl Widget for ContextMenu {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Right => {
+                self.request(cx, mouse.window_pos);
+                cx.set_handled(true);
+            }
+            Event::KeyDown(key_event) if self.menu.is_some() && cx.is_focused() => {
+                if self.key_down(cx, key_event) {
+                    cx.set_handled(true);
+                }
+            }
+            Event::KeyDown(key_event) => {
+                self.child.event(cx, event);
+                let is_menu_key = match &key_event.key {
+                    KbKey::ContextMenu => true,
+                    KbKey::F10 => key_event.mods.shift(),
+                    _ => false,
+                };
+                if is_menu_key && !cx.is_handled() {
+                    let anchor = cx.window_origin() + Vec2::new(0.0, cx.size().height);
+                    self.request(cx, anchor);
+                    cx.set_handled(true);
+                }
+            }
+            Event::OverlayDismissed { overlay, .. } => {
+                let is_ours = self
+                    .menu
+                    .as_ref()
+                    .is_some_and(|menu| open_menus(menu).iter().any(|menu| menu.id() == *overlay));
+                if is_ours {
+                    self.close(cx);
+                } else {
+                    self.child.event(cx, event);
+                }
+            }
+            _ => self.child.event(cx, event),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.child.update(cx);
+        let Some(entries) = self.pending.take() else {
+            return;
+        };
+        if let Some(menu) = self.menu.take() {
+            for overlay in open_menus(&menu) {
+                cx.hide_overlay(&overlay);
+            }
+        } else {
+            self.previous_focus = cx.cx_state.focus();
+        }
+        if entries.is_empty() {
+            cx.cx_state.window_state().focus = self.previous_focus.take();
+            return;
+        }
+        let menu = MenuPanel::build(&self.id_path, vec![], entries);
+        menu.set_placement(Placement::At(self.anchor));
+        menu.set_light_dismiss(true);
+        cx.show_overlay(&menu);
+        cx.request_focus();
+        cx.request_accessibility();
+        self.menu = Some(menu);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+        builder.set_children(vec![self.child.id().into()]);
+        if let Some(menu) = &self.menu {
+            builder.set_controls(vec![menu.id().into()]);
+            let current = open_menus(menu).pop().unwrap();
+            let highlighted = current.with_pod(|pod| {
+                let panel = pod.expect_mut::<MenuPanel>();
+                panel.highlighted.map(|index| panel.rows[index].id())
+            });
+            if let Some(highlighted) = highlighted {
+                builder.set_active_descendant(highlighted.into());
+            }
+        }
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+/// A menu shown in an overlay, the top level menu of a [`ContextMenu`] or a submenu.
+///
+/// The panel handles the pointer itself, keys are handled by the [`ContextMenu`] which keeps
+/// focus while its menus are open.
+struct MenuPanel {
+    id_path: IdPath,
+    /// The indices leading to this menu from the top level menu.
+    path: Vec<usize>,
+    items: Vec<PanelItem>,
+    /// The [`MenuRow`] of each item.
+    rows: Vec<Pod>,
+    highlighted: Option<usize>,
+    open_submenu: Option<usize>,
+    hover_timer: Option<TimerToken>,
+    width: f64,
+}
+
+struct PanelItem {
+    enabled: bool,
+    submenu: Option<OverlayHandle>,
+}
+
+impl MenuPanel {
+    /// Builds the panel for `entries` and the panels of their submenus.
+    fn build(id_path: &IdPath, path: Vec<usize>, entries: Vec<MenuEntry>) -> OverlayHandle {
+        let mut items = Vec::with_capacity(entries.len());
+        let mut rows = Vec::with_capacity(entries.len());
+        for (index, entry) in entries.into_iter().enumerate() {
+            let mut label = Label::new(entry.label.clone());
+            if !entry.enabled {
+                let _ = label.set_text_color(Color::rgb8(0x80, 0x80, 0x80));
+            }
+            let shortcut = entry.shortcut.map(|shortcut| {
+                let mut shortcut = Label::new(shortcut);
+                let _ = shortcut.set_text_color(Color::rgb8(0x80, 0x80, 0x80));
+                Pod::new(shortcut)
+            });
+            rows.push(Pod::new(MenuRow {
+                label: Pod::new(label),
+                shortcut,
+                name: entry.label,
+                enabled: entry.enabled,
+                has_submenu: !entry.submenu.is_empty(),
+                is_expanded: false,
+            }));
+            let submenu = (!entry.submenu.is_empty()).then(|| {
+                let mut path = path.clone();
+                path.push(index);
+                MenuPanel::build(id_path, path, entry.submenu)
+            });
+            items.push(PanelItem {
+                enabled: entry.enabled,
+                submenu,
+            });
+        }
+        let panel = MenuPanel {
+            id_path: id_path.clone(),
+            path,
+            items,
+            rows,
+            highlighted: None,
+            open_submenu: None,
+            hover_timer: None,
+            width: 0.0,
+        };
+        OverlayHandle::new(panel, Placement::At(Point::ZERO))
+    }
+
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        if pos.x < 0.0 || pos.x >= self.width || pos.y < PADDING {
+            return None;
+        }
+        let index = ((pos.y - PADDING) / ROW_HEIGHT) as usize;
+        (index < self.items.len()).then_some(index)
+    }
+
+    fn row_rect(&self, index: usize) -> Rect {
+        let y = PADDING + index as f64 * ROW_HEIGHT;
+        Rect::new(0.0, y, self.width, y + ROW_HEIGHT)
+    }
+
+    /// The next enabled item after `from` in the given direction, wrapping around.
+    fn next_enabled(&self, from: Option<usize>, forward: bool) -> Option<usize> {
+        let count = self.items.len();
+        (1..=count)
+            .map(|offset| match (from, forward) {
+                (Some(from), true) => (from + offset) % count,
+                (Some(from), false) => (from + count - offset) % count,
+                (None, true) => offset - 1,
+                (None, false) => count - offset,
+            })
+            .find(|&index| self.items[index].enabled)
+    }
+
+    fn step(&mut self, cx: &mut EventCx, forward: bool) {
+        let next = self.next_enabled(self.highlighted, forward);
+        self.highlight(cx, next);
+    }
+
+    fn highlight(&mut self, cx: &mut EventCx, index: Option<usize>) {
+        if self.highlighted != index {
+            self.highlighted = index;
+            cx.request_paint();
+            cx.request_accessibility();
+        }
+    }
+
+    /// The path to send when the item at `index` is chosen, if it can be chosen.
+    fn chosen_path(&self, index: usize) -> Option<Vec<usize>> {
+        let item = &self.items[index];
+        (item.enabled && item.submenu.is_none()).then(|| {
+            let mut path = self.path.clone();
+            path.push(index);
+            path
+        })
+    }
+
+    /// Opens the submenu of the item at `index` beside it, given the window origin of this
+    /// panel, and returns the submenu.
+    fn open_submenu(
+        &mut self,
+        cx: &mut EventCx,
+        index: usize,
+        origin: Point,
+    ) -> Option<OverlayHandle> {
+        if !self.items[index].enabled {
+            return None;
+        }
+        let submenu = self.items[index].submenu.clone()?;
+        if self.open_submenu != Some(index) {
+            self.close_submenu(cx);
+            submenu.set_placement(Placement::Beside(self.row_rect(index) + origin.to_vec2()));
+            cx.show_overlay(&submenu);
+            self.open_submenu = Some(index);
+            self.set_expanded(index, true);
+            cx.request_paint();
+            cx.request_accessibility();
+        }
+        Some(submenu)
+    }
+
+    /// Closes the open submenu and the submenus open from it.
+    fn close_submenu(&mut self, cx: &mut EventCx) {
+        let Some(index) = self.open_submenu.take() else {
+            return;
+        };
+        let submenu = self.items[index].submenu.clone().unwrap();
+        with_panel(&submenu, |panel| {
+            panel.close_submenu(cx);
+            panel.highlighted = None;
+        });
+        cx.hide_overlay(&submenu);
+        self.set_expanded(index, false);
+        cx.request_paint();
+        cx.request_accessibility();
+    }
+
+    fn set_expanded(&mut self, index: usize, is_expanded: bool) {
+        let row = &mut self.rows[index];
+        row.expect_mut::<MenuRow>().is_expanded = is_expanded;
+        let _ = row.mark(ChangeFlags::ACCESSIBILITY);
+    }
+
+    fn restart_hover_timer(&mut self, cx: &mut EventCx) {
+        if let Some(timer) = self.hover_timer.take() {
+            cx.cancel_timer(timer);
+        }
+        if self.highlighted != self.open_submenu {
+            self.hover_timer = Some(cx.request_timer(SUBMENU_DELAY));
+        }
+    }
+}
+
+impl Widget for MenuPanel {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        match event {
+            Event::MouseMove(mouse) => {
+                let row = self
+                    .row_at(mouse.pos)
+                    .filter(|&index| self.items[index].enabled);
+                if row.is_some() && row != self.highlighted {
+                    self.highlight(cx, row);
+                    self.restart_hover_timer(cx);
+                }
+            }
+            Event::MouseLeft() => {
+                // Keeps the submenu open while the pointer moves over to it.
+                if let Some(timer) = self.hover_timer.take() {
+                    cx.cancel_timer(timer);
+                }
+                if self.open_submenu.is_none() {
+                    self.highlight(cx, None);
+                } else {
+                    self.highlight(cx, self.open_submenu);
+                }
+            }
+            Event::Timer(token) if self.hover_timer == Some(*token) => {
+                self.hover_timer = None;
+                match self.highlighted {
+                    Some(index) if self.items[index].submenu.is_some() => {
+                        let origin = cx.window_origin();
+                        self.open_submenu(cx, index, origin);
+                    }
+                    _ => self.close_submenu(cx),
+                }
+            }
+            Event::MouseDown(mouse) => {
+                cx.set_active(true);
+                if let Some(index) = self.row_at(mouse.pos) {
+                    let origin = cx.window_origin();
+                    self.open_submenu(cx, index, origin);
+                }
+            }
+            Event::MouseUp(mouse) => {
+                if cx.is_active() {
+                    cx.set_active(false);
+                    let chosen = self
+                        .row_at(mouse.pos)
+                        .and_then(|index| self.chosen_path(index));
+                    if let Some(path) = chosen {
+                        cx.add_message(Message::new(
+                            self.id_path.clone(),
+                            ContextMenuMessage::Choose(path),
+                        ));
+                        cx.dismiss_overlay();
+                    }
+                }
+            }
+            Event::OverlayDismissed { overlay, .. } => {
+                let submenu = self
+                    .open_submenu
+                    .and_then(|index| self.items[index].submenu.as_ref());
+                if submenu.is_some_and(|submenu| submenu.id() == *overlay) {
+                    // An entry of the submenu was chosen, which closes all menus.
+                    cx.dismiss_overlay();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        for row in &mut self.rows {
+            row.lifecycle(cx, event);
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        for row in &mut self.rows {
+            row.update(cx);
+        }
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let row_bc = bc.loosen();
+        let mut width = MIN_WIDTH;
+        for row in &mut self.rows {
+            width = width.max(row.layout(cx, &row_bc).width);
+        }
+        let size = bc.constrain(Size::new(
+            width,
+            self.rows.len() as f64 * ROW_HEIGHT + 2.0 * PADDING,
+        ));
+        self.width = size.width;
+        // All rows span the panel, so that their shortcuts line up.
+        let row_bc = BoxConstraints::tight(Size::new(size.width, ROW_HEIGHT));
+        for (index, row) in self.rows.iter_mut().enumerate() {
+            row.layout(cx, &row_bc);
+            let y = PADDING + index as f64 * ROW_HEIGHT;
+            row.set_origin(cx, Point::new(0.0, y));
+        }
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        for row in &mut self.rows {
+            row.accessibility(cx);
+        }
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::Menu);
+            builder.set_children(
+                self.rows
+                    .iter()
+                    .map(|row| row.id().into())
+                    .collect::<Vec<accesskit::NodeId>>(),
+            );
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let rect = cx.size().to_rect();
+        piet_scene_helpers::fill_color(
+            builder,
+            &rect.to_rounded_rect(4.0),
+            Color::rgb8(0x2a, 0x2a, 0x2a),
+        );
+        if let Some(index) = self.highlighted.or(self.open_submenu) {
+            let highlight = self
+                .row_rect(index)
+                .inset(Insets::uniform_xy(-PADDING, 0.0));
+            piet_scene_helpers::fill_color(
+                builder,
+                &highlight.to_rounded_rect(3.0),
+                Color::rgb8(0x5a, 0x9b, 0xe6),
+            );
+        }
+        for row in &mut self.rows {
+            row.paint(cx, builder);
+        }
+        // A chevron pointing right on the items with a submenu.
+        for (index, item) in self.items.iter().enumerate() {
+            if item.submenu.is_some() {
+                let center = self.row_rect(index).center() + (rect.width() * 0.5 - 14.0, 0.0);
+                let mut chevron = BezPath::new();
+                chevron.move_to(center + (-2.0, -4.0));
+                chevron.line_to(center + (2.0, 0.0));
+                chevron.line_to(center + (-2.0, 4.0));
+                piet_scene_helpers::stroke(builder, &chevron, Color::WHITE, 1.5);
+            }
+        }
+        piet_scene_helpers::stroke(
+            builder,
+            &rect.inset(-0.5).to_rounded_rect(4.0),
+            Color::rgb8(0x3a, 0x3a, 0x3a),
+            1.0,
+        );
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        self.rows.iter().for_each(visit);
+    }
+}
+
+/// An item of a [`MenuPanel`], showing its label and its shortcut at the end.
+struct MenuRow {
+    label: Pod,
+    shortcut: Option<Pod>,
+    name: String,
+    enabled: bool,
+    has_submenu: bool,
+    is_expanded: bool,
+}
+
+impl Widget for MenuRow {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.label.event(cx, event);
+        if let Some(shortcut) = &mut self.shortcut {
+            shortcut.event(cx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.label.lifecycle(cx, event);
+        if let Some(shortcut) = &mut self.shortcut {
+            shortcut.lifecycle(cx, event);
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.label.update(cx);
+        if let Some(shortcut) = &mut self.shortcut {
+            shortcut.update(cx);
+        }
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let label_size = self.label.layout(cx, &BoxConstraints::UNBOUNDED);
+        let shortcut_size = match &mut self.shortcut {
+            Some(shortcut) => shortcut.layout(cx, &BoxConstraints::UNBOUNDED),
+            None => Size::ZERO,
+        };
+        let gap = if self.shortcut.is_some() {
+            SHORTCUT_GAP
+        } else {
+            0.0
+        };
+        let size = bc.constrain(Size::new(
+            label_size.width + gap + shortcut_size.width + ROW_INSETS.x_value() + ARROW_WIDTH,
+            ROW_HEIGHT,
+        ));
+        let y = (size.height - label_size.height) * 0.5;
+        self.label.set_origin(cx, Point::new(ROW_INSETS.x0, y));
+        if let Some(shortcut) = &mut self.shortcut {
+            let x = size.width - ROW_INSETS.x1 - ARROW_WIDTH - shortcut_size.width;
+            let y = (size.height - shortcut_size.height) * 0.5;
+            shortcut.set_origin(cx, Point::new(x, y));
+        }
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.label.accessibility(cx);
+        if let Some(shortcut) = &mut self.shortcut {
+            shortcut.accessibility(cx);
+        }
+
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::MenuItem);
+        let children = std::iter::once(&self.label).chain(&self.shortcut);
+        builder.set_children(children.map(|child| child.id().into()).collect::<Vec<_>>());
+        builder.set_name(self.name.clone());
+        if !self.enabled {
+            builder.set_disabled();
+        }
+        if self.has_submenu {
+            builder.set_expanded(self.is_expanded);
+        }
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.label.paint(cx, builder);
+        if let Some(shortcut) = &mut self.shortcut {
+            shortcut.paint(cx, builder);
+        }
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.label);
+        if let Some(shortcut) = &self.shortcut {
+            visit(shortcut);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{key_down, mouse, Harness, Leaf};
+    use crate::widget::OverlayHost;
+    use test_log::test;
+
+    const WINDOW: Size = Size::new(300.0, 200.0);
+
+    fn entry(label: &str, enabled: bool, submenu: Vec<MenuEntry>) -> MenuEntry {
+        MenuEntry {
+            label: label.into(),
+            enabled,
+            shortcut: None,
+            submenu,
+        }
+    }
+
+    fn entries() -> Vec<MenuEntry> {
+        vec![
+            entry("Cut", true, vec![]),
+            entry("Copy", false, vec![]),
+            entry(
+                "Share",
+                true,
+                vec![entry("Mail", true, vec![]), entry("Link", true, vec![])],
+            ),
+        ]
+    }
+
+    fn setup(harness: &mut Harness) -> Pod {
+        let menu = ContextMenu::new(&vec![], Pod::new(Leaf::new(300.0, 200.0)));
+        let mut root = Pod::new(OverlayHost::new(Pod::new(menu)));
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        root
+    }
+
+    fn context_menu(root: &mut Pod) -> &mut ContextMenu {
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        host.content_mut().downcast_mut().unwrap()
+    }
+
+    fn overlay_count(root: &mut Pod) -> usize {
+        root.downcast_mut::<OverlayHost>().unwrap().overlay_count()
+    }
+
+    fn messages(harness: &mut Harness) -> Vec<ContextMenuMessage> {
+        harness
+            .take_messages()
+            .into_iter()
+            .map(|message| *message.body.downcast::<ContextMenuMessage>().unwrap())
+            .collect()
+    }
+
+    /// Right-clicks at `pos` and answers the request like the view does.
+    fn open_at(harness: &mut Harness, root: &mut Pod, pos: (f64, f64)) {
+        let mut press = mouse(pos);
+        press.button = MouseButton::Right;
+        harness.event(root, Event::MouseDown(press));
+        assert_eq!(messages(harness), vec![ContextMenuMessage::Open]);
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        let content = host.content_mut();
+        let changes = content
+            .downcast_mut::<ContextMenu>()
+            .unwrap()
+            .open(entries());
+        let changes = content.mark(changes);
+        let _ = root.mark(changes);
+        harness.update(root);
+        harness.layout(root, &BoxConstraints::tight(WINDOW));
+    }
+
+    #[test]
+    fn hover_opens_submenu_and_click_chooses() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness);
+        open_at(&mut harness, &mut root, (20.0, 20.0));
+        assert_eq!(overlay_count(&mut root), 1);
+
+        // "Share" spans 72..96 in the menu at 20, 20.
+        harness.event(&mut root, Event::MouseMove(mouse((40.0, 84.0))));
+        harness.advance_time(&mut root, Duration::from_millis(100));
+        assert_eq!(overlay_count(&mut root), 1);
+        harness.advance_time(&mut root, Duration::from_millis(100));
+        assert_eq!(overlay_count(&mut root), 2);
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+
+        let menu = context_menu(&mut root).menu.clone().unwrap();
+        let submenu = open_menus(&menu).pop().unwrap();
+        assert_eq!(submenu.rect().origin(), Point::new(menu.rect().x1, 72.0));
+        // "Link" is the second row of the submenu.
+        let link = submenu.rect().origin() + (10.0, PADDING + 1.5 * ROW_HEIGHT);
+        harness.event(&mut root, Event::MouseMove(mouse(link)));
+        harness.event(&mut root, Event::MouseDown(mouse(link)));
+        harness.event(&mut root, Event::MouseUp(mouse(link)));
+
+        assert_eq!(
+            messages(&mut harness),
+            vec![ContextMenuMessage::Choose(vec![2, 1])]
+        );
+        assert_eq!(overlay_count(&mut root), 0);
+        assert!(!context_menu(&mut root).is_open());
+    }
+
+    #[test]
+    fn outside_press_and_window_blur_dismiss() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness);
+        let child = context_menu(&mut root).child_mut().id();
+        harness.window_state.focus = Some(child);
+
+        open_at(&mut harness, &mut root, (20.0, 20.0));
+        harness.event(&mut root, key_down(KbKey::End));
+        harness.event(&mut root, key_down(KbKey::ArrowRight));
+        assert_eq!(overlay_count(&mut root), 2);
+        harness.event(&mut root, Event::MouseDown(mouse((250.0, 180.0))));
+        assert!(messages(&mut harness).is_empty());
+        assert_eq!(overlay_count(&mut root), 0);
+        assert_eq!(harness.window_state.focus, Some(child));
+
+        open_at(&mut harness, &mut root, (20.0, 20.0));
+        harness.event(&mut root, Event::WindowLostFocus);
+        assert_eq!(overlay_count(&mut root), 0);
+        assert!(!context_menu(&mut root).is_open());
+        assert_eq!(harness.window_state.focus, Some(child));
+    }
+
+    #[test]
+    fn menus_flip_at_window_edges() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness);
+        open_at(&mut harness, &mut root, (290.0, 190.0));
+        let menu = context_menu(&mut root).menu.clone().unwrap();
+        assert_eq!(menu.rect().x1, 290.0);
+        assert_eq!(menu.rect().y1, 190.0);
+
+        harness.event(&mut root, key_down(KbKey::ArrowUp));
+        harness.event(&mut root, key_down(KbKey::ArrowRight));
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        let submenu = open_menus(&menu).pop().unwrap();
+        assert_eq!(submenu.rect().x1, menu.rect().x0);
+        // Aligned with the bottom of "Share", the last row.
+        assert_eq!(submenu.rect().y1, 190.0 - PADDING);
+    }
+}
@@ -17,13 +17,23 @@
 //! Note: the organization of this code roughly follows the existing Druid
 //! widget system, particularly its contexts.rs.
 
+use std::any::Any;
+use std::rc::Weak;
+use std::time::{Duration, Instant};
+
 use accesskit::TreeUpdate;
-use glazier::WindowHandle;
+use glazier::{Code, Cursor, KeyEvent, MouseButton, WindowHandle};
 use parley::FontContext;
-use vello::kurbo::{Point, Rect, Size};
+use vello::kurbo::{Insets, Point, Rect, Size, Vec2};
 
-use super::{PodFlags, WidgetState};
-use crate::Message;
+use super::clipboard::Clipboard;
+use super::damage::Damage;
+use super::overlay::{OverlayHandle, OverlayRequest};
+use super::shortcuts::Registration;
+use super::timer::{TimerQueue, TimerToken};
+use super::{PodFlags, PointerEvent, PointerId, WidgetState};
+use crate::id::Id;
+use crate::{Env, Message};
 
 // These contexts loosely follow Druid.
 
@@ -33,6 +43,189 @@ pub struct CxState<'a> {
     window: &'a WindowHandle,
     font_cx: &'a mut FontContext,
     messages: &'a mut Vec<Message>,
+    window_state: &'a mut WindowState,
+    /// The values set by the closest [`EnvScope`](super::EnvScope) around the current widget.
+    pub(crate) env: Env,
+    /// Whether a [`DisabledScope`](super::DisabledScope) around the current widget is disabled.
+    pub(crate) disabled: bool,
+}
+
+/// State of a window which outlives the single passes over the widget tree.
+pub struct WindowState {
+    /// The id of the widget receiving keyboard events.
+    pub(crate) focus: Option<Id>,
+    /// The focus the widgets were last told about, see [`Pod::deliver_focus_change`].
+    ///
+    /// [`Pod::deliver_focus_change`]: super::Pod::deliver_focus_change
+    pub(crate) delivered_focus: Option<Id>,
+    /// Whether the focus was last moved by the keyboard or assistive technology rather than a
+    /// pointer press, which is when focus rings are drawn, see [`PaintCx::is_focus_visible`].
+    pub(crate) focus_visible: bool,
+    /// The value of `focus_visible` the focused widget was last told about.
+    pub(crate) delivered_focus_visible: bool,
+    pub(crate) timers: TimerQueue,
+    /// Overlays shown or hidden since the overlay host last looked.
+    pub(crate) overlay_requests: Vec<OverlayRequest>,
+    /// Until when tooltips show up without delay, after a tooltip was dismissed.
+    pub(crate) tooltip_warm_until: Option<Instant>,
+    /// The widgets which were hot or had the pointer capture during the last mouse event, from
+    /// the root down. The cursor is resolved along this chain, see [`WindowState::cursor`].
+    pub(crate) hot_chain: Vec<Id>,
+    /// The hot widgets of the mouse event being dispatched, from the root down.
+    pub(crate) hit_path: Vec<Id>,
+    /// The hot widgets as of the last mouse event, which were told the mouse entered them.
+    pub(crate) pointer_path: Vec<Id>,
+    /// The cursors set with [`EventCx::set_cursor`] since the last mouse event began.
+    pub(crate) cursors: Vec<(Id, Cursor)>,
+    /// The cursors set with `override_cursor`, which stay until they are cleared.
+    pub(crate) cursor_overrides: Vec<(Id, Cursor)>,
+    /// The widgets registered during the current [`LifeCycle::BuildFocusChain`].
+    ///
+    /// [`LifeCycle::BuildFocusChain`]: super::LifeCycle::BuildFocusChain
+    focus_chain: Vec<Id>,
+    /// The shortcuts of the [`Shortcuts`](super::Shortcuts) widgets in the window, in the order
+    /// they were registered. Registrations of removed widgets are dropped when looking for one.
+    pub(crate) shortcuts: Vec<Weak<Registration>>,
+    /// The focus chain of the whole window as of the last tree change.
+    pub(crate) last_focus_chain: Vec<Id>,
+    /// The number of device pixels per logical pixel.
+    pub(crate) scale: f64,
+    /// The widget which gets all pointer moves and releases, see [`EventCx::capture_pointer`].
+    pub(crate) pointer_capture: Option<Id>,
+    /// The widget the capture was taken away from, which wasn't told about it yet, see
+    /// [`Pod::deliver_pointer_capture_loss`].
+    ///
+    /// [`Pod::deliver_pointer_capture_loss`]: super::Pod::deliver_pointer_capture_loss
+    pub(crate) lost_pointer_capture: Option<Id>,
+    /// The keys held down, with the widget which had focus when they were pressed.
+    pub(crate) pressed_keys: Vec<(Code, Id)>,
+    /// The height of a line for wheels which scroll by lines.
+    pub(crate) wheel_line_height: f64,
+    /// The touches and pens which are down or hovering, see [`PointerEvent`].
+    pub(crate) pointers: Vec<PointerState>,
+    /// The drag within the window, see [`EventCx::start_drag`].
+    pub(crate) drag: Option<Drag>,
+    /// Stands in for the system clipboard when no application is running.
+    pub(crate) clipboard: Clipboard,
+    /// Whether a widget asked to close the window, see [`EventCx::close_window`].
+    pub(crate) close_window: bool,
+    /// Counts the presses of double and triple clicks, see [`MouseEvent::click_count`].
+    ///
+    /// [`MouseEvent::click_count`]: super::MouseEvent::click_count
+    pub(crate) clicks: ClickCounter,
+    /// The parts of the window painted anew since the app last took them.
+    pub(crate) damage: Damage,
+}
+
+/// Counts presses of the same mouse button which follow each other closely enough to make up a
+/// double or triple click.
+pub(crate) struct ClickCounter {
+    /// How soon a press has to follow the previous one to count on.
+    pub(crate) interval: Duration,
+    /// How far from the first press of the series the following presses may be.
+    pub(crate) distance: f64,
+    last: Option<LastPress>,
+}
+
+struct LastPress {
+    button: MouseButton,
+    time: Instant,
+    /// Where the series of presses began, in window coordinates.
+    origin: Point,
+    /// The innermost widget hit by the press.
+    target: Option<Id>,
+    count: u8,
+}
+
+impl ClickCounter {
+    pub(crate) const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+    pub(crate) const DEFAULT_DISTANCE: f64 = 4.0;
+
+    pub(crate) fn new() -> Self {
+        ClickCounter {
+            interval: Self::DEFAULT_INTERVAL,
+            distance: Self::DEFAULT_DISTANCE,
+            last: None,
+        }
+    }
+
+    /// Returns the number of the press of `button` at `pos` in its series, starting a new
+    /// series when another button was pressed last, the press is too late or too far away, or
+    /// another widget is under the mouse.
+    pub(crate) fn press(
+        &mut self,
+        button: MouseButton,
+        pos: Point,
+        target: Option<Id>,
+        now: Instant,
+    ) -> u8 {
+        let counts_on = self.last.as_ref().is_some_and(|last| {
+            last.button == button
+                && last.target == target
+                && now.saturating_duration_since(last.time) <= self.interval
+                && (pos - last.origin).hypot() <= self.distance
+        });
+        match &mut self.last {
+            Some(last) if counts_on => {
+                last.time = now;
+                last.count = last.count.saturating_add(1);
+                last.count
+            }
+            _ => {
+                self.last = Some(LastPress {
+                    button,
+                    time: now,
+                    origin: pos,
+                    target,
+                    count: 1,
+                });
+                1
+            }
+        }
+    }
+
+    /// Records the innermost widget the last press hit, which the next press has to be on to
+    /// count on.
+    pub(crate) fn pressed_on(&mut self, target: Option<Id>) {
+        if let Some(last) = &mut self.last {
+            last.target = target;
+        }
+    }
+}
+
+/// A drag started with [`EventCx::start_drag`].
+pub(crate) struct Drag {
+    pub(crate) source: Id,
+    /// The dragged value, until the drop target accepting it takes it.
+    pub(crate) payload: Option<Box<dyn Any + Send>>,
+    /// Whether the widgets were told about the drag with an [`Event::DragEnter`].
+    ///
+    /// [`Event::DragEnter`]: super::Event::DragEnter
+    pub(crate) entered: bool,
+}
+
+/// What the root keeps track of for one of the [`WindowState::pointers`].
+pub(crate) struct PointerState {
+    /// The last event of the pointer, in window coordinates.
+    pub(crate) last: PointerEvent,
+    /// The widget which gets all events of the pointer, see [`EventCx::capture_pointer_id`].
+    pub(crate) capture: Option<Id>,
+    /// The widgets under the pointer as of its last event, from the root down.
+    pub(crate) hot: Vec<Id>,
+    /// Whether the events of the pointer are delivered as mouse events as well, which is the
+    /// case for the primary pointer until a widget handles its press.
+    pub(crate) emulates_mouse: bool,
+}
+
+impl PointerState {
+    pub(crate) fn new(event: &PointerEvent) -> Self {
+        PointerState {
+            last: event.clone(),
+            capture: None,
+            hot: vec![],
+            emulates_mouse: event.is_primary,
+        }
+    }
 }
 
 /// A mutable context provided to [`event`] methods of widgets.
@@ -98,7 +291,7 @@ pub struct AccessCx<'a, 'b> {
 /// [`paint`]: crate::widget::Widget::paint
 pub struct PaintCx<'a, 'b> {
     pub(crate) cx_state: &'a mut CxState<'b>,
-    pub(crate) widget_state: &'a WidgetState,
+    pub(crate) widget_state: &'a mut WidgetState,
 }
 
 /// A macro for implementing methods on multiple contexts.
@@ -115,22 +308,131 @@ macro_rules! impl_context_method {
     };
 }
 
+impl WindowState {
+    pub(crate) fn new() -> Self {
+        WindowState {
+            focus: None,
+            delivered_focus: None,
+            focus_visible: false,
+            delivered_focus_visible: false,
+            timers: TimerQueue::new(Instant::now()),
+            overlay_requests: vec![],
+            tooltip_warm_until: None,
+            hot_chain: vec![],
+            hit_path: vec![],
+            pointer_path: vec![],
+            cursors: vec![],
+            cursor_overrides: vec![],
+            focus_chain: vec![],
+            shortcuts: vec![],
+            last_focus_chain: vec![],
+            scale: 1.0,
+            pointer_capture: None,
+            lost_pointer_capture: None,
+            pressed_keys: vec![],
+            wheel_line_height: 20.0,
+            pointers: vec![],
+            drag: None,
+            clipboard: Clipboard::in_memory(),
+            close_window: false,
+            clicks: ClickCounter::new(),
+            damage: Damage::default(),
+        }
+    }
+
+    pub(crate) fn pointer(&self, pointer: PointerId) -> Option<&PointerState> {
+        self.pointers
+            .iter()
+            .find(|state| state.last.pointer_id == pointer)
+    }
+
+    pub(crate) fn pointer_mut(&mut self, pointer: PointerId) -> Option<&mut PointerState> {
+        self.pointers
+            .iter_mut()
+            .find(|state| state.last.pointer_id == pointer)
+    }
+
+    /// The cursor to show, which is the one of the deepest widget in the hot chain which set
+    /// one. The widget with the pointer capture comes first, the arrow is the default.
+    pub(crate) fn cursor(&self) -> Cursor {
+        let cursor_of = |id: &Id| {
+            self.cursors
+                .iter()
+                .chain(&self.cursor_overrides)
+                .find(|(widget, _)| widget == id)
+                .map(|(_, cursor)| cursor.clone())
+        };
+        self.pointer_capture
+            .iter()
+            .chain(self.hot_chain.iter().rev())
+            .find_map(cursor_of)
+            .unwrap_or(Cursor::Arrow)
+    }
+
+    /// Forgets the cursors of the last mouse event, the event about to be dispatched builds
+    /// the hot chain anew.
+    pub(crate) fn reset_cursor(&mut self) {
+        self.hot_chain.clear();
+        self.hit_path.clear();
+        self.cursors.clear();
+    }
+}
+
 impl<'a> CxState<'a> {
     pub fn new(
         window: &'a WindowHandle,
         font_cx: &'a mut FontContext,
         messages: &'a mut Vec<Message>,
+        window_state: &'a mut WindowState,
     ) -> Self {
         CxState {
             window,
             font_cx,
             messages,
+            window_state,
+            env: Env::new(),
+            disabled: false,
         }
     }
 
+    /// The id of the widget receiving keyboard events.
+    pub(crate) fn focus(&self) -> Option<Id> {
+        self.window_state.focus
+    }
+
+    pub(crate) fn window_state(&mut self) -> &mut WindowState {
+        self.window_state
+    }
+
+    pub(crate) fn take_focus_chain(&mut self) -> Vec<Id> {
+        std::mem::take(&mut self.window_state.focus_chain)
+    }
+
     pub(crate) fn has_messages(&self) -> bool {
         !self.messages.is_empty()
     }
+
+    /// Sends a message to the view of the innermost active shortcut matching `key`. A held key
+    /// repeating its press matches without sending the message again.
+    ///
+    /// Returns whether there was one.
+    pub(crate) fn dispatch_shortcut(&mut self, key: &KeyEvent) -> bool {
+        let shortcuts = &mut self.window_state.shortcuts;
+        shortcuts.retain(|registration| registration.strong_count() > 0);
+        let message = shortcuts
+            .iter()
+            .rev()
+            .filter_map(Weak::upgrade)
+            .find_map(|registration| registration.message_for(key));
+        match message {
+            Some(_) if key.repeat => true,
+            Some(message) => {
+                self.messages.push(message);
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 impl<'a, 'b> EventCx<'a, 'b> {
@@ -149,8 +451,129 @@ impl<'a, 'b> EventCx<'a, 'b> {
         self.widget_state.flags.set(PodFlags::IS_ACTIVE, is_active);
     }
 
+    /// Route all pointer moves and releases to this widget until the pointer is released.
+    ///
+    /// While the pointer is captured, no other widget gets these events, and this widget gets
+    /// them in its local coordinates even when the pointer left its bounds or the window. This
+    /// also makes the widget [`active`](Pod::is_active). The capture ends with the next mouse
+    /// up, with [`release_pointer`](EventCx::release_pointer) or when the widget is removed. A
+    /// widget the capture is taken away from gets [`LifeCycle::PointerCaptureLost`].
+    ///
+    /// [`LifeCycle::PointerCaptureLost`]: super::LifeCycle::PointerCaptureLost
+    pub fn capture_pointer(&mut self) {
+        let id = self.widget_state.id;
+        let window_state = &mut self.cx_state.window_state;
+        if let Some(previous) = window_state.pointer_capture.replace(id) {
+            if previous != id {
+                window_state.lost_pointer_capture = Some(previous);
+            }
+        }
+        self.set_active(true);
+    }
+
+    /// End the pointer capture of this widget, if it has it.
+    pub fn release_pointer(&mut self) {
+        if self.has_pointer_capture() {
+            self.cx_state.window_state.pointer_capture = None;
+            self.set_active(false);
+        }
+    }
+
+    /// Returns whether this widget captured the pointer, see
+    /// [`capture_pointer`](EventCx::capture_pointer).
+    pub fn has_pointer_capture(&self) -> bool {
+        self.cx_state.window_state.pointer_capture == Some(self.widget_state.id)
+    }
+
+    /// Route all further events of a touch or pen to this widget, until it's lifted or
+    /// cancelled.
+    ///
+    /// Like [`capture_pointer`](EventCx::capture_pointer) for the mouse, but each pointer has
+    /// its own capture, so several widgets can follow a finger each.
+    pub fn capture_pointer_id(&mut self, pointer: PointerId) {
+        let id = self.widget_state.id;
+        if let Some(state) = self.cx_state.window_state.pointer_mut(pointer) {
+            state.capture = Some(id);
+        }
+    }
+
+    /// End the capture of a touch or pen by this widget, if it has it.
+    pub fn release_pointer_id(&mut self, pointer: PointerId) {
+        let id = self.widget_state.id;
+        if let Some(state) = self.cx_state.window_state.pointer_mut(pointer) {
+            if state.capture == Some(id) {
+                state.capture = None;
+            }
+        }
+    }
+
+    /// Returns whether this widget captured a touch or pen, see
+    /// [`capture_pointer_id`](EventCx::capture_pointer_id).
+    pub fn has_capture_of(&self, pointer: PointerId) -> bool {
+        let id = self.widget_state.id;
+        self.cx_state
+            .window_state
+            .pointer(pointer)
+            .is_some_and(|state| state.capture == Some(id))
+    }
+
+    /// Start dragging `payload` from this widget, following the mouse until it's released.
+    ///
+    /// The widgets the drag moves over get [`Event::DragEnter`], [`Event::DragOver`] and
+    /// [`Event::DragLeave`], the one it is released over [`Event::Drop`], where a target
+    /// accepting it takes the payload with [`take_drag_payload`]. Escape cancels the drag. In
+    /// any case this widget gets an [`Event::DragEnded`] afterwards.
+    ///
+    /// [`Event::DragEnter`]: super::Event::DragEnter
+    /// [`Event::DragOver`]: super::Event::DragOver
+    /// [`Event::DragLeave`]: super::Event::DragLeave
+    /// [`Event::Drop`]: super::Event::Drop
+    /// [`Event::DragEnded`]: super::Event::DragEnded
+    /// [`take_drag_payload`]: EventCx::take_drag_payload
+    pub fn start_drag(&mut self, payload: Box<dyn Any + Send>) {
+        self.cx_state.window_state.drag = Some(Drag {
+            source: self.widget_state.id,
+            payload: Some(payload),
+            entered: false,
+        });
+    }
+
+    /// The payload of the drag within the window, see [`start_drag`](EventCx::start_drag).
+    pub fn drag_payload(&self) -> Option<&(dyn Any + Send)> {
+        let drag = self.cx_state.window_state.drag.as_ref()?;
+        drag.payload.as_deref()
+    }
+
+    /// Takes the payload of the drag within the window while handling its [`Event::Drop`],
+    /// which accepts the drop.
+    ///
+    /// [`Event::Drop`]: super::Event::Drop
+    pub fn take_drag_payload(&mut self) -> Option<Box<dyn Any + Send>> {
+        self.cx_state.window_state.drag.as_mut()?.payload.take()
+    }
+
+    /// Returns whether the current drag event is over this widget.
+    ///
+    /// Widgets a drag just left still get the event, with this being `false`.
+    pub fn is_drag_over(&self) -> bool {
+        self.widget_state.flags.contains(PodFlags::IS_DRAG_OVER)
+    }
+
+    /// Returns whether a touch or pen is over this widget, the counterpart of
+    /// [`is_hot`](EventCx::is_hot) for pointers other than the mouse.
+    pub fn is_hot_for(&self, pointer: PointerId) -> bool {
+        let id = self.widget_state.id;
+        self.cx_state
+            .window_state
+            .pointer(pointer)
+            .is_some_and(|state| state.hot.contains(&id))
+    }
+
     /// Set the event as "handled", which stops its propagation to other
     /// widgets.
+    ///
+    /// In the capture phase, this keeps the event from the descendants of the widget. In the
+    /// bubble phase, ancestors see the event as handled and siblings don't get it anymore.
     pub fn set_handled(&mut self, is_handled: bool) {
         self.is_handled = is_handled;
     }
@@ -160,10 +583,44 @@ impl<'a, 'b> EventCx<'a, 'b> {
         self.is_handled
     }
 
+    /// Sets the mouse cursor while the pointer is over this widget.
+    ///
+    /// The cursor is reset before every mouse event, so widgets set it again for each event in
+    /// which the pointer is over them. When several widgets under the pointer set a cursor, the
+    /// deepest one wins, and the widget with the pointer capture wins over all of them. See
+    /// [`override_cursor`](EventCx::override_cursor) for a cursor which stays.
+    pub fn set_cursor(&mut self, cursor: &Cursor) {
+        let id = self.widget_state.id;
+        let cursors = &mut self.cx_state.window_state.cursors;
+        cursors.retain(|(widget, _)| *widget != id);
+        cursors.push((id, cursor.clone()));
+    }
+
     /// Check whether this widget's id matches the given id.
     pub fn is_accesskit_target(&self, id: accesskit::NodeId) -> bool {
         accesskit::NodeId::from(self.widget_state.id) == id
     }
+
+    /// Dismisses the overlay containing this widget, for example after a menu item was chosen.
+    ///
+    /// The widget which showed the overlay receives an [`Event::OverlayDismissed`].
+    ///
+    /// [`Event::OverlayDismissed`]: super::Event::OverlayDismissed
+    pub fn dismiss_overlay(&mut self) {
+        self.cx_state
+            .window_state
+            .overlay_requests
+            .push(OverlayRequest::Dismiss(self.widget_state.id));
+    }
+
+    /// The system clipboard, or an in-memory one of the window when no application is running,
+    /// for example in tests.
+    pub fn clipboard(&self) -> Clipboard {
+        match glazier::Application::try_global() {
+            Some(app) => Clipboard::shell(app.clipboard()),
+            None => self.cx_state.window_state.clipboard.clone(),
+        }
+    }
 }
 
 impl<'a, 'b> LifeCycleCx<'a, 'b> {
@@ -173,6 +630,30 @@ impl<'a, 'b> LifeCycleCx<'a, 'b> {
             widget_state: root_state,
         }
     }
+
+    /// Registers this widget as taking keyboard focus, in answer to
+    /// [`LifeCycle::BuildFocusChain`].
+    ///
+    /// Widgets register in the order they are visited, which is the tab order.
+    ///
+    /// [`LifeCycle::BuildFocusChain`]: super::LifeCycle::BuildFocusChain
+    pub fn register_for_focus(&mut self) {
+        let id = self.widget_state.id;
+        self.cx_state.window_state.focus_chain.push(id);
+    }
+
+    /// Marks this widget as scrolling its content with the wheel, in answer to
+    /// [`LifeCycle::Added`].
+    ///
+    /// Scroll containers take the wheel in their capture phase, unless [`Pod::scrolls_at`] finds
+    /// another one under the pointer within them. This way the wheel scrolls the innermost one,
+    /// and the widgets in it never see the wheel.
+    ///
+    /// [`LifeCycle::Added`]: super::LifeCycle::Added
+    /// [`Pod::scrolls_at`]: super::Pod::scrolls_at
+    pub fn set_scrolls_with_wheel(&mut self) {
+        self.widget_state.flags.insert(PodFlags::SCROLLS);
+    }
 }
 
 impl<'a, 'b> UpdateCx<'a, 'b> {
@@ -191,11 +672,58 @@ impl<'a, 'b> LayoutCx<'a, 'b> {
             widget_state: root_state,
         }
     }
+
+    /// Sets the distance from the bottom of the widget to the baseline of its text, for parents
+    /// which align text across their children, see [`Pod::baseline_offset`].
+    ///
+    /// It is zero unless set during the current layout.
+    ///
+    /// [`Pod::baseline_offset`]: super::Pod::baseline_offset
+    pub fn set_baseline_offset(&mut self, offset: f64) {
+        self.widget_state.baseline_offset = offset;
+    }
+
+    /// Sets how far the widget paints beyond its size, for example for a shadow, see
+    /// [`Pod::paint_rect`].
+    ///
+    /// They are zero unless set during the current layout.
+    ///
+    /// [`Pod::paint_rect`]: super::Pod::paint_rect
+    pub fn set_paint_insets(&mut self, insets: impl Into<Insets>) {
+        self.widget_state.paint_insets = insets.into();
+    }
+
+    /// Requests a call to [`paint`] for content which moved by `delta` within the widget, like
+    /// the rows of a list when it scrolls.
+    ///
+    /// Unlike [`request_paint`](Self::request_paint), only the strip of the widget the move
+    /// exposed is damaged, and the rest is presented by moving what was presented before, see
+    /// [`Damage::scrolls`]. Children placed after this call don't damage the widget either, while
+    /// any other paint request still damages all of it.
+    ///
+    /// [`paint`]: super::Widget::paint
+    pub fn request_scroll_paint(&mut self, delta: Vec2) {
+        if delta == Vec2::ZERO {
+            return;
+        }
+        *self.widget_state.scroll_delta.get_or_insert(Vec2::ZERO) += delta;
+        self.widget_state.flags |= PodFlags::REQUEST_PAINT;
+    }
+
+    /// Returns whether the widget is aligned with device pixels, see
+    /// [`Pod::set_snap_to_pixels`].
+    ///
+    /// Containers use it to round the extents they hand out with [`distribute_to_pixels`].
+    ///
+    /// [`Pod::set_snap_to_pixels`]: super::Pod::set_snap_to_pixels
+    /// [`distribute_to_pixels`]: crate::distribute_to_pixels
+    pub fn snaps_to_pixels(&self) -> bool {
+        !self.widget_state.flags.contains(PodFlags::NO_PIXEL_SNAP)
+    }
 }
 
 // This function is unfortunate but works around kurbo versioning
 fn to_accesskit_rect(r: Rect) -> accesskit::Rect {
-    println!("{:?}", r);
     accesskit::Rect::new(r.x0, r.y0, r.x1, r.y1)
 }
 
@@ -205,6 +733,9 @@ impl<'a, 'b> AccessCx<'a, 'b> {
     /// The id of the node pushed is obtained from the context. The
     /// bounds are set based on the layout bounds.
     pub fn push_node(&mut self, mut builder: accesskit::NodeBuilder) {
+        if self.cx_state.disabled {
+            builder.set_disabled();
+        }
         builder.set_bounds(to_accesskit_rect(Rect::from_origin_size(
             self.widget_state.window_origin(),
             self.widget_state.size,
@@ -222,6 +753,24 @@ impl<'a, 'b> AccessCx<'a, 'b> {
         self.update.nodes.push((id, node));
     }
 
+    /// Add a node for a part of this widget which has no [`Pod`] of its own.
+    ///
+    /// The node gets the given id and the layout bounds of this widget, the caller has to add it
+    /// to the children of the widget's own node.
+    ///
+    /// [`Pod`]: super::Pod
+    pub fn push_child_node(&mut self, id: Id, mut builder: accesskit::NodeBuilder) {
+        if self.cx_state.disabled {
+            builder.set_disabled();
+        }
+        builder.set_bounds(to_accesskit_rect(Rect::from_origin_size(
+            self.widget_state.window_origin(),
+            self.widget_state.size,
+        )));
+        let node = builder.build(self.node_classes);
+        self.update.nodes.push((id.into(), node));
+    }
+
     /// Report whether accessibility was requested on this widget.
     ///
     /// This method is primarily intended for containers. The `accessibility`
@@ -242,6 +791,14 @@ impl<'a, 'b> PaintCx<'a, 'b> {
             widget_state,
         }
     }
+
+    /// Returns whether this widget has keyboard focus and should show it with a focus ring.
+    ///
+    /// Focus taken by a pointer press shows no ring until the next key press, like the
+    /// `:focus-visible` of the web. See [`paint_focus_ring`](super::paint_focus_ring).
+    pub fn is_focus_visible(&self) -> bool {
+        self.is_focused() && self.cx_state.window_state.focus_visible
+    }
 }
 
 // Methods on all contexts.
@@ -255,6 +812,11 @@ impl_context_method!(
     AccessCx<'_, '_>,
     PaintCx<'_, '_>,
     {
+        /// The id of this widget.
+        pub fn widget_id(&self) -> Id {
+            self.widget_state.id
+        }
+
         /// Returns whether this widget is hot.
         ///
         /// See [`is_hot`] for more details.
@@ -272,9 +834,50 @@ impl_context_method!(
         pub fn is_active(&self) -> bool {
             self.widget_state.flags.contains(PodFlags::IS_ACTIVE)
         }
+
+        /// Returns whether this widget has keyboard focus.
+        pub fn is_focused(&self) -> bool {
+            self.cx_state.focus() == Some(self.widget_state.id)
+        }
+
+        /// Returns whether this widget or one of its descendants has keyboard focus.
+        pub fn has_focus_within(&self) -> bool {
+            self.widget_state.flags.contains(PodFlags::HAS_FOCUS)
+        }
+
+        /// The values set by the views above this widget.
+        ///
+        /// Widgets receive [`LifeCycle::EnvChanged`] when they change.
+        ///
+        /// [`LifeCycle::EnvChanged`]: super::LifeCycle::EnvChanged
+        pub fn env(&self) -> &Env {
+            &self.cx_state.env
+        }
+
+        /// Returns whether this widget is disabled by a [`DisabledScope`] above it.
+        ///
+        /// Disabled widgets get no input, and should paint themselves muted.
+        ///
+        /// [`DisabledScope`]: super::DisabledScope
+        pub fn is_disabled(&self) -> bool {
+            self.cx_state.disabled
+        }
     }
 );
 
+// Methods on EventCx and LifeCycleCx
+impl_context_method!(EventCx<'_, '_>, LifeCycleCx<'_, '_>, {
+    /// Request a call to [`update`] for this widget.
+    ///
+    /// Like all requests, it marks the ancestors as well, and the next frame runs the pass once
+    /// for all widgets which requested it.
+    ///
+    /// [`update`]: super::Widget::update
+    pub fn request_update(&mut self) {
+        self.widget_state.flags |= PodFlags::REQUEST_UPDATE;
+    }
+});
+
 // Methods on EventCx, UpdateCx, and LifeCycleCx
 impl_context_method!(EventCx<'_, '_>, UpdateCx<'_, '_>, LifeCycleCx<'_, '_>, {
     /// Request layout for this widget.
@@ -293,6 +896,124 @@ impl_context_method!(EventCx<'_, '_>, UpdateCx<'_, '_>, LifeCycleCx<'_, '_>, {
     pub fn add_message(&mut self, message: Message) {
         self.cx_state.messages.push(message);
     }
+
+    /// Request a call to [`accessibility`] for this widget.
+    ///
+    /// [`accessibility`]: super::Widget::accessibility
+    pub fn request_accessibility(&mut self) {
+        self.widget_state.flags |= PodFlags::REQUEST_ACCESSIBILITY;
+    }
+
+    /// Request a [`LifeCycle::AnimFrame`] for this widget.
+    ///
+    /// The request only covers the next frame, animating widgets have to request again while
+    /// handling each frame.
+    ///
+    /// [`LifeCycle::AnimFrame`]: super::LifeCycle::AnimFrame
+    pub fn request_anim_frame(&mut self) {
+        self.widget_state.flags |= PodFlags::REQUEST_ANIM_FRAME;
+    }
+
+    /// Request keyboard focus for this widget.
+    ///
+    /// Keyboard events are delivered to the focused widget and its ancestors.
+    pub fn request_focus(&mut self) {
+        self.cx_state.window_state.focus = Some(self.widget_state.id);
+    }
+
+    /// Give up keyboard focus, if this widget has it.
+    pub fn resign_focus(&mut self) {
+        if self.is_focused() {
+            self.cx_state.window_state.focus = None;
+        }
+    }
+
+    /// Requests an [`Event::Timer`] for this widget once `delay` has passed.
+    ///
+    /// The timer fires once, the returned token identifies it in the event.
+    ///
+    /// [`Event::Timer`]: super::Event::Timer
+    pub fn request_timer(&mut self, delay: Duration) -> TimerToken {
+        self.cx_state
+            .window_state
+            .timers
+            .request(self.widget_state.id, delay)
+    }
+
+    /// Cancels a timer which has not fired yet.
+    pub fn cancel_timer(&mut self, token: TimerToken) {
+        self.cx_state.window_state.timers.cancel(token);
+    }
+
+    /// The time of the current pass, timers are measured with this clock.
+    pub fn now(&self) -> Instant {
+        self.cx_state.window_state.timers.now()
+    }
+
+    /// Sets the mouse cursor shown whenever the pointer is over this widget, until it is
+    /// cleared with [`clear_cursor_override`](EventCx::clear_cursor_override).
+    ///
+    /// A cursor set with [`EventCx::set_cursor`] during the current mouse event wins over it.
+    pub fn override_cursor(&mut self, cursor: &Cursor) {
+        let id = self.widget_state.id;
+        let overrides = &mut self.cx_state.window_state.cursor_overrides;
+        overrides.retain(|(widget, _)| *widget != id);
+        overrides.push((id, cursor.clone()));
+    }
+
+    /// Removes the cursor set with [`override_cursor`](EventCx::override_cursor).
+    pub fn clear_cursor_override(&mut self) {
+        let id = self.widget_state.id;
+        self.cx_state
+            .window_state
+            .cursor_overrides
+            .retain(|(widget, _)| *widget != id);
+    }
+
+    /// Shows `overlay` above the content of the window.
+    ///
+    /// Showing an overlay which is already shown moves it to the top.
+    pub fn show_overlay(&mut self, overlay: &OverlayHandle) {
+        self.cx_state
+            .window_state
+            .overlay_requests
+            .push(OverlayRequest::Show {
+                overlay: overlay.clone(),
+                owner: self.widget_state.id,
+            });
+    }
+
+    /// Removes `overlay` from the window, if it is shown.
+    pub fn hide_overlay(&mut self, overlay: &OverlayHandle) {
+        self.cx_state
+            .window_state
+            .overlay_requests
+            .push(OverlayRequest::Hide(overlay.clone()));
+    }
+
+    /// Closes the window once the current frame is done, without sending
+    /// [`Event::CloseRequested`](super::Event::CloseRequested) again.
+    pub fn close_window(&mut self) {
+        self.cx_state.window_state.close_window = true;
+    }
+
+    pub(crate) fn take_overlay_requests(&mut self) -> Vec<OverlayRequest> {
+        std::mem::take(&mut self.cx_state.window_state.overlay_requests)
+    }
+
+    /// Notify Xilem that this widget added or removed children.
+    ///
+    /// Containers which own their children call this instead of returning
+    /// [`ChangeFlags::tree_structure`] from a view.
+    ///
+    /// [`ChangeFlags::tree_structure`]: super::ChangeFlags::tree_structure
+    pub fn children_changed(&mut self) {
+        self.widget_state.flags |= PodFlags::TREE_CHANGED
+            | PodFlags::REQUEST_LAYOUT
+            | PodFlags::REQUEST_PAINT
+            | PodFlags::DAMAGED
+            | PodFlags::REQUEST_ACCESSIBILITY;
+    }
 });
 
 // Methods on EventCx, UpdateCx, LifeCycleCx and LayoutCx
@@ -304,9 +1025,11 @@ impl_context_method!(
     {
         /// Requests a call to [`paint`] for this widget.
         ///
+        /// The rectangle of the widget is presented again, see [`Damage`].
+        ///
         /// [`paint`]: super::Widget::paint
         pub fn request_paint(&mut self) {
-            self.widget_state.flags |= PodFlags::REQUEST_PAINT;
+            self.widget_state.flags |= PodFlags::REQUEST_PAINT | PodFlags::DAMAGED;
         }
 
         /// Notify Xilem that this widgets view context changed.
@@ -329,6 +1052,14 @@ impl_context_method!(LayoutCx<'_, '_>, PaintCx<'_, '_>, {
     pub fn font_cx(&mut self) -> &mut FontContext {
         self.cx_state.font_cx
     }
+
+    /// The scale factor of the window, the number of device pixels per logical pixel.
+    ///
+    /// Use it with [`round_to_pixel`](crate::round_to_pixel) and friends to align geometry with
+    /// device pixels.
+    pub fn scale(&self) -> f64 {
+        self.cx_state.window_state.scale
+    }
 });
 
 // Methods on all contexts besides LayoutCx.
@@ -17,18 +17,32 @@
 //! //! Note: the organization of this code roughly follows the existing Druid
 //! widget system, particularly its core.rs.
 
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
 use bitflags::bitflags;
-use vello::kurbo::{Affine, Point, Rect, Size};
+use glazier::KbKey;
+use vello::kurbo::{Affine, Insets, Point, Rect, Size, Vec2};
 use vello::{SceneBuilder, SceneFragment};
 
-use super::widget::{AnyWidget, Widget};
-use crate::{id::Id, Bloom};
+use super::contexts::PointerState;
+use super::overlay::OverlayHost;
+use super::raw_event::typed_text;
+use super::widget::{AnyWidget, Intrinsic, Widget};
+use crate::{ceil_to_pixel, id::Id, is_on_pixel, round_to_pixel, Axis, Bloom, SizeExt};
 
 use super::{
-    contexts::LifeCycleCx, AccessCx, BoxConstraints, CxState, Event, EventCx, LayoutCx, LifeCycle,
-    PaintCx, UpdateCx,
+    contexts::LifeCycleCx, AccessCx, BoxConstraints, CxState, DebugState, Empty, Event, EventCx,
+    LayoutCx, LifeCycle, LinearMeta, PaintCx, PointerEvent, PointerKind, UpdateCx, WidgetMessage,
 };
 
+thread_local! {
+    /// The pods dropped on this thread which are told about their removal with the next frame,
+    /// see [`Pod::deliver_removed`].
+    static REMOVED: RefCell<Vec<Pod>> = RefCell::new(vec![]);
+}
+
 bitflags! {
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
     pub(crate) struct PodFlags: u32 {
@@ -50,18 +64,43 @@ bitflags! {
 
         const NEEDS_SET_ORIGIN = 0x1000;
 
+        const REQUEST_ANIM_FRAME = 0x2000;
+        /// The widget or one of its descendants has keyboard focus.
+        const HAS_FOCUS = 0x4000;
+        /// The origin and size of the widget are not aligned with device pixels.
+        const NO_PIXEL_SNAP = 0x8000;
+        /// The last drag event was over the widget.
+        const IS_DRAG_OVER = 0x10000;
+        /// The widget is laid out again even when its constraints didn't change.
+        const NO_LAYOUT_CACHE = 0x20000;
+        /// The widget itself asked to be painted, not only one of its descendants, so its
+        /// rectangle is damaged when it is.
+        const DAMAGED = 0x40000;
+        /// The widget got [`LifeCycle::Added`] and gets [`LifeCycle::Removed`] when it is dropped.
+        const IS_ADDED = 0x80000;
+        /// The last paint of the widget drew a focus ring, see
+        /// [`paint_focus_ring`](super::paint_focus_ring).
+        const PAINTS_FOCUS_RING = 0x100000;
+        /// The widget scrolls its content with the wheel, see
+        /// [`LifeCycleCx::set_scrolls_with_wheel`].
+        const SCROLLS = 0x200000;
+
         const UPWARD_FLAGS = Self::REQUEST_UPDATE.bits()
             | Self::REQUEST_LAYOUT.bits()
             | Self::REQUEST_PAINT.bits()
             | Self::HAS_ACTIVE.bits()
             | Self::DESCENDANT_REQUESTED_ACCESSIBILITY.bits()
             | Self::TREE_CHANGED.bits()
-            | Self::VIEW_CONTEXT_CHANGED.bits();
+            | Self::VIEW_CONTEXT_CHANGED.bits()
+            | Self::REQUEST_ANIM_FRAME.bits()
+            | Self::HAS_FOCUS.bits()
+            | Self::NO_LAYOUT_CACHE.bits();
         const INIT_FLAGS = Self::REQUEST_UPDATE.bits()
             | Self::REQUEST_LAYOUT.bits()
             | Self::REQUEST_ACCESSIBILITY.bits()
             | Self::DESCENDANT_REQUESTED_ACCESSIBILITY.bits()
             | Self::REQUEST_PAINT.bits()
+            | Self::DAMAGED.bits()
             | Self::TREE_CHANGED.bits();
     }
 }
@@ -76,9 +115,24 @@ bitflags! {
         const PAINT = 8;
         const TREE = 0x10;
         const DESCENDANT_REQUESTED_ACCESSIBILITY = 0x20;
+        /// A descendant has to be painted, which repaints without damaging the whole widget.
+        const DESCENDANT_REQUESTED_PAINT = 0x40;
     }
 }
 
+/// The flags [`Pod::tree_debug_state`] shows, with their names.
+const DEBUG_FLAGS: [(PodFlags, &str); 9] = [
+    (PodFlags::IS_HOT, "hot"),
+    (PodFlags::IS_ACTIVE, "active"),
+    (PodFlags::HAS_FOCUS, "focus"),
+    (PodFlags::PAINTS_FOCUS_RING, "focus_ring"),
+    (PodFlags::IS_DRAG_OVER, "drag_over"),
+    (PodFlags::REQUEST_UPDATE, "request_update"),
+    (PodFlags::REQUEST_LAYOUT, "request_layout"),
+    (PodFlags::REQUEST_PAINT, "request_paint"),
+    (PodFlags::REQUEST_ANIM_FRAME, "request_anim_frame"),
+];
+
 /// A container for one widget in the hierarchy.
 ///
 /// Generally, container widgets don't contain other widgets directly,
@@ -91,6 +145,8 @@ pub struct Pod {
     pub(crate) state: WidgetState,
     pub(crate) widget: Box<dyn AnyWidget>,
     pub(crate) fragment: SceneFragment,
+    /// The type of `widget`, for diagnostics.
+    type_name: &'static str,
 }
 
 #[derive(Debug)]
@@ -109,6 +165,26 @@ pub(crate) struct WidgetState {
     //           parents: HashMap<Id, Id>,
     //           children: HashMap<Id, Vec<Id>>,
     pub(crate) sub_tree: Bloom<Id>,
+    /// The constraints of the last layout, `size` is valid for them until layout is requested.
+    pub(crate) layout_bc: Option<BoxConstraints>,
+    /// The extents measured by [`Pod::intrinsic`] since layout was last requested, keyed by its
+    /// arguments.
+    pub(crate) intrinsics: Vec<(Axis, Intrinsic, f64, f64)>,
+    /// The paint order among the siblings, see [`Pod::set_z_index`].
+    pub(crate) z_index: i32,
+    /// How the widget is laid out by a [`LinearLayout`](super::LinearLayout), see
+    /// [`Pod::set_linear_meta`].
+    pub(crate) linear_meta: LinearMeta,
+    /// The distance from the bottom of the widget to the baseline of its text, see
+    /// [`LayoutCx::set_baseline_offset`].
+    pub(crate) baseline_offset: f64,
+    /// How far the widget itself paints beyond its size, see [`LayoutCx::set_paint_insets`].
+    pub(crate) paint_insets: Insets,
+    /// The area painted by the widget and its descendants, in its own coordinate space.
+    pub(crate) local_paint_rect: Rect,
+    /// How far the content of the widget moved since it was last painted, see
+    /// [`LayoutCx::request_scroll_paint`].
+    pub(crate) scroll_delta: Option<Vec2>,
 }
 
 impl PodFlags {
@@ -127,7 +203,12 @@ impl ChangeFlags {
         // Note: this assumes PodFlags are a superset of ChangeFlags. This might
         // not always be the case, for example on "structure changed."
         let pod_flags = PodFlags::from_bits_truncate(self.bits() as _);
-        ChangeFlags::from_bits_truncate(pod_flags.upwards().bits() as _)
+        let mut result = ChangeFlags::from_bits_truncate(pod_flags.upwards().bits() as _);
+        if self.intersects(ChangeFlags::PAINT | ChangeFlags::DESCENDANT_REQUESTED_PAINT) {
+            result.remove(ChangeFlags::PAINT);
+            result |= ChangeFlags::DESCENDANT_REQUESTED_PAINT;
+        }
+        result
     }
 
     // Change flags representing change of tree structure.
@@ -146,6 +227,14 @@ impl WidgetState {
             parent_window_origin: Default::default(),
             size: Default::default(),
             sub_tree: Default::default(),
+            layout_bc: None,
+            intrinsics: vec![],
+            z_index: 0,
+            linear_meta: LinearMeta::default(),
+            baseline_offset: 0.0,
+            paint_insets: Insets::ZERO,
+            local_paint_rect: Rect::ZERO,
+            scroll_delta: None,
         }
     }
 
@@ -154,13 +243,101 @@ impl WidgetState {
         self.sub_tree = self.sub_tree.union(child_state.sub_tree);
     }
 
-    fn request(&mut self, flags: PodFlags) {
+    fn request(&mut self, mut flags: PodFlags) {
+        if flags.contains(PodFlags::REQUEST_PAINT) {
+            flags |= PodFlags::DAMAGED;
+        }
         self.flags |= flags
     }
 
     pub(crate) fn window_origin(&self) -> Point {
         self.parent_window_origin + self.origin.to_vec2()
     }
+
+    fn layout_rect(&self) -> Rect {
+        Rect::from_origin_size(self.origin, self.size)
+    }
+}
+
+/// The widgets under a point, see [`Pod::widget_at`].
+///
+/// Each widget comes with the point in its own coordinates, from the outermost widget down to
+/// the innermost.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HitChain(pub Vec<(Id, Point)>);
+
+impl HitChain {
+    /// The innermost widget under the point.
+    pub fn target(&self) -> Option<(Id, Point)> {
+        self.0.last().copied()
+    }
+
+    /// Whether the point is on the widget `id`.
+    pub fn contains(&self, id: Id) -> bool {
+        self.0.iter().any(|(hit, _)| *hit == id)
+    }
+}
+
+/// The widget of a [`Pod`], which marks the changes made to it on the pod, see [`Pod::as_mut`].
+///
+/// The widget is read through `Deref`, and changed with [`apply`](WidgetMut::apply), usually
+/// with one of its setters returning the [`ChangeFlags`] of the change.
+pub struct WidgetMut<'a, W> {
+    pod: &'a mut Pod,
+    changes: ChangeFlags,
+    widget: PhantomData<W>,
+}
+
+impl<'a, W: 'static> WidgetMut<'a, W> {
+    /// Changes the widget with `change` and marks the flags it returns on the pod.
+    pub fn apply(&mut self, change: impl FnOnce(&mut W) -> ChangeFlags) -> &mut Self {
+        let flags = change(self.pod.expect_mut());
+        self.changes |= self.pod.mark(flags);
+        self
+    }
+
+    /// Sets where the widget is painted among its siblings, see [`Pod::set_z_index`].
+    pub fn set_z_index(&mut self, z_index: i32) -> &mut Self {
+        self.changes |= self.pod.set_z_index(z_index);
+        self
+    }
+
+    /// Lays the widget out again, for changes which aren't made with a setter.
+    pub fn request_layout(&mut self) -> &mut Self {
+        self.changes |= self.pod.mark(ChangeFlags::LAYOUT);
+        self
+    }
+
+    /// Paints the widget again, for changes which aren't made with a setter.
+    pub fn request_paint(&mut self) -> &mut Self {
+        self.changes |= self.pod.mark(ChangeFlags::PAINT);
+        self
+    }
+
+    /// The changes the owner of the pod has to mark on its own pod, like the result of
+    /// [`Pod::mark`].
+    #[must_use]
+    pub fn changes(&self) -> ChangeFlags {
+        self.changes
+    }
+}
+
+impl<W: 'static> Deref for WidgetMut<'_, W> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        // `Pod::as_mut` checked the type.
+        self.pod.downcast_ref().unwrap()
+    }
+}
+
+/// The message of [`Pod::expect_mut`] for a pod holding an `actual` widget.
+fn type_mismatch<W>(actual: &str) -> String {
+    format!(
+        "expected the widget in the pod to be a `{}`, but it is a `{actual}`; \
+         did the element type of its view change?",
+        std::any::type_name::<W>()
+    )
 }
 
 impl Pod {
@@ -180,33 +357,114 @@ impl Pod {
         Pod {
             state: WidgetState::new(),
             fragment: SceneFragment::default(),
+            type_name: (*widget).type_name(),
             widget,
         }
     }
 
-    /// Returns the wrapped widget.
-    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+    /// The type name of the wrapped widget.
+    pub fn widget_type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the wrapped widget, if it is a `W`.
+    pub fn downcast_ref<W: 'static>(&self) -> Option<&W> {
+        (*self.widget).as_any().downcast_ref()
+    }
+
+    /// Returns the wrapped widget, if it is a `W`.
+    ///
+    /// Views which can't be sure about the type, like the type-erased `AnyView`, use this to
+    /// build a new widget instead.
+    pub fn downcast_mut<W: 'static>(&mut self) -> Option<&mut W> {
         (*self.widget).as_any_mut().downcast_mut()
     }
 
+    /// Returns the wrapped widget, which has to be a `W`.
+    ///
+    /// # Panics
+    ///
+    /// When the widget is of another type, naming both types. This happens when a view gets
+    /// an element of another type than it built, usually because its `Element` type changed.
+    #[track_caller]
+    pub fn expect_mut<W: 'static>(&mut self) -> &mut W {
+        let type_name = self.type_name;
+        match (*self.widget).as_any_mut().downcast_mut() {
+            Some(widget) => widget,
+            None => panic!("{}", type_mismatch::<W>(type_name)),
+        }
+    }
+
+    /// Returns the wrapped widget for changes which are marked on this pod, see [`WidgetMut`].
+    ///
+    /// # Panics
+    ///
+    /// When the widget is not a `W`, like [`expect_mut`](Pod::expect_mut).
+    #[track_caller]
+    pub fn as_mut<W: 'static>(&mut self) -> WidgetMut<W> {
+        let _ = self.expect_mut::<W>();
+        WidgetMut {
+            pod: self,
+            changes: ChangeFlags::empty(),
+            widget: PhantomData,
+        }
+    }
+
     /// Sets the requested flags on this pod and returns the ChangeFlags the owner of this Pod should set.
     pub fn mark(&mut self, flags: ChangeFlags) -> ChangeFlags {
+        if flags.contains(ChangeFlags::DESCENDANT_REQUESTED_PAINT) {
+            self.state.flags |= PodFlags::REQUEST_PAINT;
+        }
         self.state
             .request(PodFlags::from_bits_truncate(flags.bits() as _));
         flags.upwards()
     }
 
+    /// The changes requested in this subtree which the next frame has to process.
+    ///
+    /// The root uses it to decide which passes to run, a frame with only [`ChangeFlags::PAINT`]
+    /// skips update and layout.
+    pub(crate) fn pending_changes(&self) -> ChangeFlags {
+        ChangeFlags::from_bits_truncate(self.state.flags.bits() as _)
+    }
+
     /// Propagate a platform event. As in Druid, a great deal of the event
     /// dispatching logic is in this function.
     ///
-    /// This method calls [event](crate::widget::Widget::event) on the wrapped Widget if this event
-    /// is relevant to this widget.
+    /// This method calls [capture_event](crate::widget::Widget::capture_event) and then
+    /// [event](crate::widget::Widget::event) on the wrapped Widget if this event is relevant to
+    /// this widget, unless the capture phase handled it.
     pub fn event(&mut self, cx: &mut EventCx, event: &Event) {
         if cx.is_handled {
             return;
         }
+        // Disabled widgets get no input, see `DisabledScope`.
+        if cx.cx_state.disabled
+            && matches!(
+                event,
+                Event::MouseDown(_)
+                    | Event::MouseUp(_)
+                    | Event::MouseMove(_)
+                    | Event::MouseWheel(_)
+                    | Event::PointerDown(_)
+                    | Event::PointerMove(_)
+                    | Event::PointerUp(_)
+                    | Event::PointerCancel(_)
+                    | Event::DragEnter(_)
+                    | Event::DragOver(_)
+                    | Event::Drop(_)
+                    | Event::KeyDown(_)
+                    | Event::KeyUp(_)
+                    | Event::Text(_)
+                    | Event::TargetedAccessibilityAction(_)
+            )
+        {
+            return;
+        }
         let mut modified_event = None;
         let had_active = self.state.flags.contains(PodFlags::HAS_ACTIVE);
+        // A captured pointer only moves and releases on the way to the capturing widget.
+        let is_captured = cx.cx_state.window_state().pointer_capture.is_some();
         let recurse = match event {
             Event::MouseDown(mouse_event) => {
                 Pod::set_hot_state(
@@ -231,7 +489,7 @@ impl Pod {
                     cx.cx_state,
                     Some(mouse_event.pos),
                 );
-                if had_active || self.state.flags.contains(PodFlags::IS_HOT) {
+                if had_active || (!is_captured && self.state.flags.contains(PodFlags::IS_HOT)) {
                     let mut mouse_event = mouse_event.clone();
                     mouse_event.pos -= self.state.origin.to_vec2();
                     modified_event = Some(Event::MouseUp(mouse_event));
@@ -241,17 +499,24 @@ impl Pod {
                 }
             }
             Event::MouseMove(mouse_event) => {
+                // While another widget captured the pointer, nothing else is hot.
+                let is_bypassed = is_captured && !had_active;
                 let hot_changed = Pod::set_hot_state(
                     &mut self.widget,
                     &mut self.state,
                     cx.cx_state,
-                    Some(mouse_event.pos),
+                    (!is_bypassed).then_some(mouse_event.pos),
                 );
-                if had_active || self.state.flags.contains(PodFlags::IS_HOT) || hot_changed {
+                let is_hot = self.state.flags.contains(PodFlags::IS_HOT);
+                if had_active || (!is_captured && (is_hot || hot_changed)) {
                     let mut mouse_event = mouse_event.clone();
                     mouse_event.pos -= self.state.origin.to_vec2();
                     modified_event = Some(Event::MouseMove(mouse_event));
                     true
+                } else if hot_changed {
+                    // The descendants stop being hot as well.
+                    modified_event = Some(Event::MouseLeft());
+                    true
                 } else {
                     false
                 }
@@ -277,23 +542,151 @@ impl Pod {
                     Pod::set_hot_state(&mut self.widget, &mut self.state, cx.cx_state, None);
                 had_active || hot_changed
             }
-            Event::TargetedAccessibilityAction(action) => {
-                println!("TODO: {:?}", action);
-                self.state
-                    .sub_tree
-                    .may_contain(&Id::try_from_accesskit(action.target).unwrap())
+            Event::PointerDown(pointer)
+            | Event::PointerMove(pointer)
+            | Event::PointerUp(pointer)
+            | Event::PointerCancel(pointer) => {
+                let is_over = self.hits(pointer.pos);
+                let state = cx.cx_state.window_state().pointer_mut(pointer.pointer_id);
+                // A captured pointer only goes to the capturing widget and its ancestors.
+                let recurse = match state.as_ref().and_then(|state| state.capture) {
+                    Some(capture) => self.state.sub_tree.may_contain(&capture),
+                    None => is_over,
+                };
+                if recurse {
+                    if let Some(state) = state.filter(|_| is_over) {
+                        state.hot.push(self.state.id);
+                    }
+                    let mut pointer = pointer.clone();
+                    pointer.pos -= self.state.origin.to_vec2();
+                    modified_event = Some(event.with_pointer(pointer));
+                }
+                recurse
             }
+            Event::KeyDown(_) | Event::KeyUp(_) | Event::Text(_) => match cx.cx_state.focus() {
+                Some(focus) => self.state.sub_tree.may_contain(&focus),
+                None => false,
+            },
+            Event::TargetedAccessibilityAction(request) => {
+                match Id::try_from_accesskit(request.target) {
+                    // Widgets handle the other actions themselves, like a click for `Default`.
+                    Some(target) if target == self.state.id => {
+                        let window_state = cx.cx_state.window_state();
+                        match request.action {
+                            // Assistive technology moves focus like the keyboard does.
+                            accesskit::Action::Focus => {
+                                window_state.focus = Some(self.state.id);
+                                window_state.focus_visible = true;
+                            }
+                            accesskit::Action::Blur
+                                if window_state.focus == Some(self.state.id) =>
+                            {
+                                window_state.focus = None;
+                            }
+                            _ => (),
+                        }
+                        true
+                    }
+                    Some(target) => self.state.sub_tree.may_contain(&target),
+                    None => false,
+                }
+            }
+            Event::DragEnter(drag) | Event::DragOver(drag) | Event::Drop(drag) => {
+                // Widgets the drag just left are told as well.
+                let was_over = self.state.flags.contains(PodFlags::IS_DRAG_OVER);
+                let is_over = self.hits(drag.pos);
+                self.state.flags.set(PodFlags::IS_DRAG_OVER, is_over);
+                if is_over || was_over {
+                    let mut drag = drag.clone();
+                    drag.pos -= self.state.origin.to_vec2();
+                    modified_event = Some(match event {
+                        Event::DragEnter(_) => Event::DragEnter(drag),
+                        Event::DragOver(_) => Event::DragOver(drag),
+                        _ => Event::Drop(drag),
+                    });
+                }
+                is_over || was_over
+            }
+            Event::DragLeave() => self.state.flags.contains(PodFlags::IS_DRAG_OVER),
+            Event::DragEnded { source, .. } => self.state.sub_tree.may_contain(source),
+            Event::Timer(token) => self.state.sub_tree.may_contain(&token.widget),
+            Event::OverlayDismissed { owner, .. } => self.state.sub_tree.may_contain(owner),
+            Event::WindowLostFocus | Event::CloseRequested => true,
+            Event::RoutePointerEnter(id) | Event::RoutePointerLeave(id) => {
+                if *id == self.state.id {
+                    modified_event = Some(match event {
+                        Event::RoutePointerEnter(_) => Event::PointerEnter,
+                        _ => Event::PointerLeave,
+                    });
+                    true
+                } else {
+                    self.state.sub_tree.may_contain(id)
+                }
+            }
+            Event::RouteWidgetMessage { target, message } => {
+                if *target == self.state.id {
+                    if matches!(message, WidgetMessage::Focus) && !cx.cx_state.disabled {
+                        cx.cx_state.window_state().focus = Some(self.state.id);
+                    }
+                    modified_event = Some(Event::WidgetMessage(message.clone()));
+                    true
+                } else {
+                    self.state.sub_tree.may_contain(target)
+                }
+            }
+            // Only sent by the pod of the widget concerned, see `RoutePointerEnter`.
+            Event::PointerEnter | Event::PointerLeave | Event::WidgetMessage(_) => false,
         };
+        // The cursor is resolved along the widgets under the mouse, see `WindowState::cursor`.
+        if recurse
+            && matches!(
+                event,
+                Event::MouseDown(_)
+                    | Event::MouseUp(_)
+                    | Event::MouseMove(_)
+                    | Event::MouseWheel(_)
+            )
+            && self
+                .state
+                .flags
+                .intersects(PodFlags::IS_HOT | PodFlags::IS_ACTIVE)
+        {
+            let window_state = cx.cx_state.window_state();
+            window_state.hot_chain.push(self.state.id);
+            // Several mouse events may be delivered for one event of the window.
+            let hit_path = &mut window_state.hit_path;
+            if self.state.flags.contains(PodFlags::IS_HOT) && !hit_path.contains(&self.state.id) {
+                hit_path.push(self.state.id);
+            }
+        }
         if recurse {
             let mut inner_cx = EventCx {
                 cx_state: cx.cx_state,
                 widget_state: &mut self.state,
                 is_handled: false,
             };
-            self.widget
-                .event(&mut inner_cx, modified_event.as_ref().unwrap_or(event));
+            let event = modified_event.as_ref().unwrap_or(event);
+            // The capture phase runs on the way down, the bubble phase on the way back up.
+            self.widget.capture_event(&mut inner_cx, event);
+            if !inner_cx.is_handled {
+                self.widget.event(&mut inner_cx, event);
+            }
             cx.is_handled |= inner_cx.is_handled;
 
+            // The drag is over.
+            if matches!(event, Event::Drop(_) | Event::DragLeave()) {
+                self.state.flags.remove(PodFlags::IS_DRAG_OVER);
+            }
+
+            // The capture ends with the release.
+            if matches!(event, Event::MouseUp(_)) {
+                let window_state = cx.cx_state.window_state();
+                if window_state.pointer_capture == Some(self.state.id) {
+                    window_state.pointer_capture = None;
+                    self.state.flags.remove(PodFlags::IS_ACTIVE);
+                }
+            }
+
             // This clears the has_active state. Pod needs to clear this state since merge up can
             // only set flags.
             self.state.flags.set(
@@ -304,6 +697,306 @@ impl Pod {
         }
     }
 
+    /// Delivers an event from the window to the root of the widget tree.
+    ///
+    /// Key presses go to the focused widget and its ancestors, followed by the
+    /// [text](Event::Text) they typed unless a widget handled them. Key presses with modifiers go
+    /// to a matching [shortcut](super::Shortcuts) first, other key presses only when no widget
+    /// handled them or their text, so focused text fields keep plain keys. An unhandled Tab without a shortcut
+    /// moves focus to the next widget in the focus chain, Shift+Tab to the previous one, and an
+    /// Escape closes the topmost light dismiss overlay. A key release only reaches the widget
+    /// which got the press, as long as it keeps focus. Wheel deltas are converted to pixels, and
+    /// mouse events resolve the [cursor](EventCx::set_cursor) anew. Presses get their
+    /// [`click_count`](super::MouseEvent::click_count).
+    ///
+    /// Events of touches and pens are also delivered as mouse events while they emulate the
+    /// mouse, see [`Event::PointerDown`]. Losing focus cancels them.
+    ///
+    /// While a widget [drags](EventCx::start_drag) within the window, mouse moves and the
+    /// release are followed by drag events, and Escape or losing focus cancels the drag.
+    ///
+    /// Mouse events are followed by [`Event::PointerLeave`] for the widgets the mouse left and
+    /// [`Event::PointerEnter`] for those it entered.
+    pub(crate) fn window_event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.dispatch_window_event(cx, event);
+        if let Event::MouseDown(_)
+        | Event::MouseUp(_)
+        | Event::MouseMove(_)
+        | Event::MouseWheel(_)
+        | Event::MouseLeft() = event
+        {
+            self.deliver_pointer_crossings(cx);
+        }
+    }
+
+    /// Compares the widgets under the mouse with those of the previous mouse event, and tells
+    /// the widgets which were left, innermost first, and then those which were entered,
+    /// outermost first.
+    fn deliver_pointer_crossings(&mut self, cx: &mut EventCx) {
+        let window_state = cx.cx_state.window_state();
+        let hit_path = std::mem::take(&mut window_state.hit_path);
+        let previous = std::mem::replace(&mut window_state.pointer_path, hit_path);
+        let current = &cx.cx_state.window_state().pointer_path;
+        let left: Vec<_> = previous
+            .iter()
+            .rev()
+            .filter(|id| !current.contains(id))
+            .map(|id| Event::RoutePointerLeave(*id))
+            .collect();
+        let entered: Vec<_> = current
+            .iter()
+            .filter(|id| !previous.contains(id))
+            .map(|id| Event::RoutePointerEnter(*id))
+            .collect();
+        let is_handled = std::mem::replace(&mut cx.is_handled, false);
+        for event in left.into_iter().chain(entered) {
+            self.event(cx, &event);
+            cx.is_handled = false;
+        }
+        cx.is_handled = is_handled;
+    }
+
+    fn dispatch_window_event(&mut self, cx: &mut EventCx, event: &Event) {
+        if let Event::MouseDown(_)
+        | Event::MouseUp(_)
+        | Event::MouseMove(_)
+        | Event::MouseWheel(_)
+        | Event::MouseLeft() = event
+        {
+            cx.cx_state.window_state().reset_cursor();
+        }
+        match event {
+            // Also when the capturing widget didn't get the release, for example because it was
+            // disabled meanwhile.
+            Event::MouseUp(mouse) => {
+                if cx.cx_state.window_state().drag.is_some() {
+                    self.dispatch_drag(cx, Event::Drop(mouse.into()));
+                    self.end_drag(cx);
+                }
+                self.event(cx, event);
+                cx.cx_state.window_state().pointer_capture = None;
+                return;
+            }
+            Event::MouseDown(mouse) => {
+                // The widget under the mouse as of the last mouse event is the one about to be
+                // pressed, the widget actually hit is only known after the press.
+                let window_state = cx.cx_state.window_state();
+                window_state.focus_visible = false;
+                let target = window_state.pointer_path.last().copied();
+                let now = window_state.timers.now();
+                let mut mouse = mouse.clone();
+                mouse.click_count =
+                    window_state
+                        .clicks
+                        .press(mouse.button, mouse.window_pos, target, now);
+                self.event(cx, &Event::MouseDown(mouse));
+                let window_state = cx.cx_state.window_state();
+                let hit = window_state.hit_path.last().copied();
+                window_state.clicks.pressed_on(hit);
+                return;
+            }
+            Event::MouseMove(mouse) => {
+                self.event(cx, event);
+                // Also for the move which started the drag.
+                if let Some(drag) = &mut cx.cx_state.window_state().drag {
+                    let drag_event = if std::mem::replace(&mut drag.entered, true) {
+                        Event::DragOver(mouse.into())
+                    } else {
+                        Event::DragEnter(mouse.into())
+                    };
+                    self.dispatch_drag(cx, drag_event);
+                }
+                return;
+            }
+            Event::MouseLeft() if cx.cx_state.window_state().drag.is_some() => {
+                self.event(cx, event);
+                self.dispatch_drag(cx, Event::DragLeave());
+                return;
+            }
+            Event::KeyDown(key)
+                if key.key == KbKey::Escape && cx.cx_state.window_state().drag.is_some() =>
+            {
+                self.dispatch_drag(cx, Event::DragLeave());
+                self.end_drag(cx);
+                cx.is_handled = true;
+                return;
+            }
+            Event::WindowLostFocus => {
+                let window_state = cx.cx_state.window_state();
+                if let Some(id) = window_state.pointer_capture.take() {
+                    window_state.lost_pointer_capture = Some(id);
+                }
+                // The releases of the held keys go to the other window.
+                window_state.pressed_keys.clear();
+                if window_state.drag.is_some() {
+                    self.dispatch_drag(cx, Event::DragLeave());
+                    self.end_drag(cx);
+                }
+                let window_state = cx.cx_state.window_state();
+                // So do touches and pens.
+                let pointers: Vec<_> = window_state
+                    .pointers
+                    .iter()
+                    .map(|state| state.last.clone())
+                    .collect();
+                for pointer in pointers {
+                    self.pointer_event(cx, &Event::PointerCancel(pointer.clone()), &pointer);
+                    cx.is_handled = false;
+                }
+            }
+            Event::PointerDown(pointer)
+            | Event::PointerMove(pointer)
+            | Event::PointerUp(pointer)
+            | Event::PointerCancel(pointer) => {
+                if let Event::PointerDown(_) = event {
+                    cx.cx_state.window_state().focus_visible = false;
+                }
+                self.pointer_event(cx, event, pointer);
+                return;
+            }
+            Event::MouseWheel(mouse) => {
+                let mut mouse = mouse.clone();
+                mouse.normalize_wheel(cx.cx_state.window_state().wheel_line_height);
+                self.event(cx, &Event::MouseWheel(mouse));
+                return;
+            }
+            Event::KeyUp(key) => {
+                let window_state = cx.cx_state.window_state();
+                let pressed = window_state
+                    .pressed_keys
+                    .iter()
+                    .position(|(code, _)| *code == key.code);
+                let pressed_by = pressed.map(|index| window_state.pressed_keys.remove(index).1);
+                // A widget which got focus in between never saw the press.
+                if pressed_by.is_some() && pressed_by == window_state.focus {
+                    self.event(cx, event);
+                }
+                return;
+            }
+            _ => (),
+        }
+        let Event::KeyDown(key) = event else {
+            self.event(cx, event);
+            return;
+        };
+        let window_state = cx.cx_state.window_state();
+        // Holding a modifier for a shortcut or a click doesn't navigate.
+        if !matches!(
+            key.key,
+            KbKey::Shift | KbKey::Control | KbKey::Alt | KbKey::Meta
+        ) {
+            window_state.focus_visible = true;
+        }
+        window_state
+            .pressed_keys
+            .retain(|(code, _)| *code != key.code);
+        if let Some(focus) = window_state.focus {
+            window_state.pressed_keys.push((key.code, focus));
+        }
+        let has_mods = key.mods.ctrl() || key.mods.alt() || key.mods.meta();
+        if has_mods && cx.cx_state.dispatch_shortcut(key) {
+            return;
+        }
+        self.event(cx, event);
+        if !cx.is_handled {
+            if let Some(text) = typed_text(key) {
+                self.event(cx, &Event::Text(text));
+            }
+        }
+        if cx.is_handled || has_mods || cx.cx_state.dispatch_shortcut(key) {
+            return;
+        }
+        match key.key {
+            KbKey::Tab => self.move_focus(cx, !key.mods.shift()),
+            KbKey::Escape => self.dismiss_top_overlay(cx),
+            _ => (),
+        }
+    }
+
+    /// Delivers an event of a touch or pen from the window, followed by the mouse event standing
+    /// in for it while the pointer [emulates the mouse](PointerState::emulates_mouse).
+    fn pointer_event(&mut self, cx: &mut EventCx, event: &Event, pointer: &PointerEvent) {
+        let window_state = cx.cx_state.window_state();
+        if window_state.pointer(pointer.pointer_id).is_none() {
+            window_state.pointers.push(PointerState::new(pointer));
+        }
+        if let Some(state) = window_state.pointer_mut(pointer.pointer_id) {
+            state.last = pointer.clone();
+            state.hot.clear();
+        }
+        self.event(cx, event);
+
+        let window_state = cx.cx_state.window_state();
+        let Some(state) = window_state.pointer_mut(pointer.pointer_id) else {
+            return;
+        };
+        if let Event::PointerDown(_) = event {
+            state.emulates_mouse = pointer.is_primary && !cx.is_handled;
+        }
+        let emulates_mouse = state.emulates_mouse && !cx.is_handled;
+        if let Event::PointerUp(_) | Event::PointerCancel(_) = event {
+            state.capture = None;
+            state.emulates_mouse = pointer.is_primary;
+            // Touches end when lifted, while pens may keep hovering.
+            if pointer.kind == PointerKind::Touch || matches!(event, Event::PointerCancel(_)) {
+                window_state
+                    .pointers
+                    .retain(|state| state.last.pointer_id != pointer.pointer_id);
+            }
+        }
+        if !emulates_mouse {
+            return;
+        }
+        let mouse = pointer.to_mouse_event();
+        let mouse_event = match event {
+            Event::PointerDown(_) => Event::MouseDown(mouse),
+            Event::PointerMove(_) => Event::MouseMove(mouse),
+            Event::PointerUp(_) => Event::MouseUp(mouse),
+            _ => {
+                // The emulated press ends without a release.
+                if let Some(id) = window_state.pointer_capture.take() {
+                    window_state.lost_pointer_capture = Some(id);
+                }
+                Event::MouseLeft()
+            }
+        };
+        self.window_event(cx, &mouse_event);
+    }
+
+    /// Delivers a drag event made up by the root, whether or not the event causing it was
+    /// handled.
+    fn dispatch_drag(&mut self, cx: &mut EventCx, event: Event) {
+        let is_handled = std::mem::replace(&mut cx.is_handled, false);
+        self.event(cx, &event);
+        cx.is_handled = is_handled;
+    }
+
+    /// Ends the drag within the window and tells its source whether a target took the payload.
+    fn end_drag(&mut self, cx: &mut EventCx) {
+        if let Some(drag) = cx.cx_state.window_state().drag.take() {
+            let event = Event::DragEnded {
+                source: drag.source,
+                accepted: drag.payload.is_none(),
+            };
+            self.dispatch_drag(cx, event);
+        }
+    }
+
+    /// Closes the topmost light dismiss overlay, when this is the pod of the window's
+    /// [`OverlayHost`](super::OverlayHost).
+    fn dismiss_top_overlay(&mut self, cx: &mut EventCx) {
+        let Some(host) = (*self.widget).as_any_mut().downcast_mut::<OverlayHost>() else {
+            return;
+        };
+        let mut inner_cx = EventCx {
+            cx_state: cx.cx_state,
+            widget_state: &mut self.state,
+            is_handled: false,
+        };
+        host.dismiss_top(&mut inner_cx);
+        cx.widget_state.merge_up(&mut self.state);
+    }
+
     /// Propagate a lifecycle event.
     ///
     /// This method calls [lifecycle](crate::widget::Widget::lifecycle) on the wrapped Widget if
@@ -313,6 +1006,10 @@ impl Pod {
         let recurse = match event {
             LifeCycle::HotChanged(_) => false,
             LifeCycle::ViewContextChanged(view) => {
+                // The accessibility node has its bounds in window coordinates.
+                if self.state.parent_window_origin != view.window_origin {
+                    self.state.request(PodFlags::REQUEST_ACCESSIBILITY);
+                }
                 self.state.parent_window_origin = view.window_origin;
 
                 Pod::set_hot_state(
@@ -327,14 +1024,116 @@ impl Pod {
                 self.state.flags.remove(PodFlags::VIEW_CONTEXT_CHANGED);
                 true
             }
+            LifeCycle::AnimFrame(_) => {
+                // Widgets have to request every frame again, this way requests of removed widgets
+                // end with the frame after their removal.
+                let requested = self.state.flags.contains(PodFlags::REQUEST_ANIM_FRAME);
+                self.state.flags.remove(PodFlags::REQUEST_ANIM_FRAME);
+                requested
+            }
             LifeCycle::TreeUpdate => {
+                if !self.state.flags.contains(PodFlags::IS_ADDED) {
+                    // Before the children, which the widget forwards `Added` to.
+                    self.lifecycle(cx, &LifeCycle::Added);
+                }
                 if self.state.flags.contains(PodFlags::TREE_CHANGED) {
                     self.state.sub_tree.clear();
                     self.state.sub_tree.add(&self.state.id);
+                    // Children were added or removed, the cached layout doesn't include them.
                     self.state.flags.remove(PodFlags::TREE_CHANGED);
+                    self.state.flags.insert(PodFlags::REQUEST_LAYOUT);
+                    true
+                } else {
+                    // The parent rebuilds its filter, so it has to include unchanged subtrees.
+                    cx.widget_state.sub_tree = cx.widget_state.sub_tree.union(self.state.sub_tree);
+                    false
+                }
+            }
+            LifeCycle::Added => {
+                let is_new = !self.state.flags.contains(PodFlags::IS_ADDED);
+                self.state.flags.insert(PodFlags::IS_ADDED);
+                is_new
+            }
+            LifeCycle::Removed => {
+                let id = self.state.id;
+                let window_state = cx.cx_state.window_state();
+                window_state.timers.retain(|widget| widget != id);
+                window_state
+                    .cursor_overrides
+                    .retain(|(widget, _)| *widget != id);
+                let was_added = self.state.flags.contains(PodFlags::IS_ADDED);
+                self.state.flags.remove(PodFlags::IS_ADDED);
+                was_added
+            }
+            LifeCycle::BuildFocusChain => !cx.cx_state.disabled,
+            LifeCycle::AnimationsPaused(_) => true,
+            LifeCycle::DisabledChanged(disabled) => {
+                if *disabled {
+                    Pod::set_hot_state(&mut self.widget, &mut self.state, cx.cx_state, None);
+                    self.state
+                        .flags
+                        .remove(PodFlags::IS_ACTIVE | PodFlags::HAS_ACTIVE);
+                    if cx.cx_state.focus() == Some(self.state.id) {
+                        cx.cx_state.window_state().focus = None;
+                    }
+                }
+                self.state
+                    .request(PodFlags::REQUEST_PAINT | PodFlags::REQUEST_ACCESSIBILITY);
+                true
+            }
+            LifeCycle::WindowResized(_) | LifeCycle::WindowFocus(_) => true,
+            LifeCycle::ScaleChanged(_) => {
+                self.state
+                    .request(PodFlags::REQUEST_LAYOUT | PodFlags::REQUEST_PAINT);
+                true
+            }
+            LifeCycle::EnvChanged { layout } => {
+                if *layout {
+                    self.state.request(PodFlags::REQUEST_LAYOUT);
+                }
+                self.state.request(PodFlags::REQUEST_PAINT);
+                true
+            }
+            // Only sent by the pod of the widget concerned, see `RouteFocusChanged`.
+            LifeCycle::FocusChanged(_) => false,
+            // Only sent by the pod of the widget concerned, see `RoutePointerCaptureLost`.
+            LifeCycle::PointerCaptureLost => false,
+            LifeCycle::RoutePointerCaptureLost(id) => {
+                if *id == self.state.id {
+                    self.state.flags.remove(PodFlags::IS_ACTIVE);
+                    modified_event = Some(LifeCycle::PointerCaptureLost);
                     true
                 } else {
+                    self.state.sub_tree.may_contain(id)
+                }
+            }
+            LifeCycle::RouteFocusChanged { old, new } => {
+                let id = self.state.id;
+                // Descendants which have focus set it again when merging up.
+                self.state.flags.set(PodFlags::HAS_FOCUS, *new == Some(id));
+                let mut child_cx = LifeCycleCx {
+                    cx_state: cx.cx_state,
+                    widget_state: &mut self.state,
+                };
+                if *old == Some(id) || *new == Some(id) {
+                    // For the focus ring, which the widget may not paint itself.
+                    child_cx.widget_state.request(PodFlags::REQUEST_PAINT);
+                    let event = LifeCycle::FocusChanged(*new == Some(id));
+                    self.widget.lifecycle(&mut child_cx, &event);
+                    cx.widget_state.merge_up(&mut self.state);
+                }
+                [old, new]
+                    .into_iter()
+                    .flatten()
+                    .any(|focus| *focus != id && self.state.sub_tree.may_contain(focus))
+            }
+            LifeCycle::RouteFocusVisibleChanged(id) => {
+                if *id == self.state.id {
+                    self.state.request(PodFlags::REQUEST_PAINT);
+                    cx.widget_state.merge_up(&mut self.state);
                     false
+                } else {
+                    self.state.sub_tree.may_contain(id)
                 }
             }
         };
@@ -365,19 +1164,201 @@ impl Pod {
         }
     }
 
+    /// The widgets in this subtree which take keyboard focus, in tab order.
+    pub(crate) fn focus_chain(&mut self, cx: &mut LifeCycleCx) -> Vec<Id> {
+        self.lifecycle(cx, &LifeCycle::BuildFocusChain);
+        cx.cx_state.take_focus_chain()
+    }
+
+    /// Moves focus to the next or previous focusable widget in this subtree, wrapping around.
+    ///
+    /// When focus is outside of the subtree, it moves to the first or last widget.
+    pub(crate) fn move_focus(&mut self, cx: &mut EventCx, forward: bool) {
+        let chain = self.focus_chain(&mut LifeCycleCx {
+            cx_state: cx.cx_state,
+            widget_state: cx.widget_state,
+        });
+        if chain.is_empty() {
+            return;
+        }
+        let count = chain.len();
+        let current = cx
+            .cx_state
+            .focus()
+            .and_then(|focus| chain.iter().position(|id| *id == focus));
+        let next = match (current, forward) {
+            (Some(current), true) => (current + 1) % count,
+            (Some(current), false) => (current + count - 1) % count,
+            (None, true) => 0,
+            (None, false) => count - 1,
+        };
+        cx.cx_state.window_state().focus = Some(chain[next]);
+    }
+
+    /// Moves focus to a neighbor when the focused widget left the tree.
+    ///
+    /// Called on the root after the tree changed, the neighbor is the closest widget still in
+    /// the tree after the focused one in the focus chain of the last call, or before it.
+    pub(crate) fn repair_focus(&mut self, cx: &mut LifeCycleCx) {
+        let chain = self.focus_chain(cx);
+        let window_state = cx.cx_state.window_state();
+        let previous = std::mem::replace(&mut window_state.last_focus_chain, chain);
+        let Some(focus) = window_state.focus else {
+            return;
+        };
+        let chain = &window_state.last_focus_chain;
+        if chain.contains(&focus) {
+            return;
+        }
+        let Some(index) = previous.iter().position(|id| *id == focus) else {
+            return;
+        };
+        let neighbor = previous[index + 1..]
+            .iter()
+            .chain(previous[..index].iter().rev())
+            .find(|id| chain.contains(id));
+        window_state.focus = neighbor.copied();
+    }
+
+    /// Cancels the pending timers of widgets which left the tree.
+    ///
+    /// Called on the root after the tree changed, like [`repair_focus`](Pod::repair_focus).
+    pub(crate) fn drop_stale_timers(&self, cx: &mut LifeCycleCx) {
+        let sub_tree = self.state.sub_tree;
+        cx.cx_state
+            .window_state()
+            .timers
+            .retain(|widget| sub_tree.may_contain(&widget));
+    }
+
+    /// Drops the cursor overrides of widgets which left the tree.
+    ///
+    /// Called on the root after the tree changed, like [`repair_focus`](Pod::repair_focus).
+    pub(crate) fn drop_stale_cursor_overrides(&self, cx: &mut LifeCycleCx) {
+        let sub_tree = self.state.sub_tree;
+        cx.cx_state
+            .window_state()
+            .cursor_overrides
+            .retain(|(widget, _)| sub_tree.may_contain(widget));
+    }
+
+    /// Tells the pods dropped since the last call, and their descendants, that they were removed
+    /// from the tree, and drops them.
+    ///
+    /// Called by the root before [`LifeCycle::TreeUpdate`], also when the tree didn't change.
+    pub(crate) fn deliver_removed(cx: &mut LifeCycleCx) {
+        // Widgets may drop more pods in response.
+        loop {
+            let removed = REMOVED.with(|removed| std::mem::take(&mut *removed.borrow_mut()));
+            if removed.is_empty() {
+                return;
+            }
+            for mut pod in removed {
+                // Removed pods have no parent to report to.
+                let mut parent_state = WidgetState::new();
+                let mut removed_cx = LifeCycleCx {
+                    cx_state: cx.cx_state,
+                    widget_state: &mut parent_state,
+                };
+                pod.lifecycle(&mut removed_cx, &LifeCycle::Removed);
+            }
+        }
+    }
+
+    /// Ends the pointer capture of a widget which left the tree.
+    ///
+    /// Called on the root after the tree changed, like [`repair_focus`](Pod::repair_focus).
+    pub(crate) fn drop_stale_pointer_capture(&self, cx: &mut LifeCycleCx) {
+        let window_state = cx.cx_state.window_state();
+        if let Some(id) = window_state.pointer_capture {
+            if !self.state.sub_tree.may_contain(&id) {
+                window_state.pointer_capture = None;
+            }
+        }
+        for state in &mut window_state.pointers {
+            if let Some(id) = state.capture {
+                if !self.state.sub_tree.may_contain(&id) {
+                    state.capture = None;
+                }
+            }
+        }
+    }
+
+    /// Sends [`LifeCycle::PointerCaptureLost`] to the widget whose pointer capture was taken
+    /// away since the last call.
+    pub(crate) fn deliver_pointer_capture_loss(&mut self, cx: &mut LifeCycleCx) {
+        if let Some(id) = cx.cx_state.window_state().lost_pointer_capture.take() {
+            self.lifecycle(cx, &LifeCycle::RoutePointerCaptureLost(id));
+        }
+    }
+
+    /// Sends [`LifeCycle::FocusChanged`] to the widgets which lost and gained focus since the
+    /// last call, or repaints the focused widget when only its focus ring came or went.
+    pub(crate) fn deliver_focus_change(&mut self, cx: &mut LifeCycleCx) {
+        let window_state = cx.cx_state.window_state();
+        let old = window_state.delivered_focus;
+        let new = window_state.focus;
+        let visible_changed = window_state.delivered_focus_visible != window_state.focus_visible;
+        window_state.delivered_focus_visible = window_state.focus_visible;
+        if old != new {
+            window_state.delivered_focus = new;
+            self.lifecycle(cx, &LifeCycle::RouteFocusChanged { old, new });
+        } else if let Some(focus) = new.filter(|_| visible_changed) {
+            self.lifecycle(cx, &LifeCycle::RouteFocusVisibleChanged(focus));
+        }
+    }
+
     /// Propagate a layout request.
     ///
     /// This method calls [layout](crate::widget::Widget::layout) on the wrapped Widget. The container
     /// widget is responsible for calling only the children which need a call to layout. These include
     /// any Pod which has [layout_requested](Pod::layout_requested) set.
+    ///
+    /// When neither this widget nor a descendant requested layout and `bc` equals the constraints
+    /// of the last layout, the size of the last layout is returned without calling the widget.
+    /// The container still has to call [`set_origin`](Pod::set_origin) on the child.
     pub fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let is_cached = self.state.layout_bc.as_ref() == Some(bc)
+            && !self
+                .state
+                .flags
+                .intersects(PodFlags::REQUEST_LAYOUT | PodFlags::NO_LAYOUT_CACHE);
+        if is_cached {
+            return self.state.size;
+        }
+        if self.state.flags.contains(PodFlags::REQUEST_LAYOUT) {
+            self.state.intrinsics.clear();
+        }
+        self.state.baseline_offset = 0.0;
+        self.state.paint_insets = Insets::ZERO;
         let mut child_cx = LayoutCx {
             cx_state: cx.cx_state,
             widget_state: &mut self.state,
         };
-        let new_size = self.widget.layout(&mut child_cx, bc);
+        let mut new_size = self.widget.layout(&mut child_cx, bc);
         //println!("layout size = {:?}", new_size);
+        if self.snaps_to_pixels() {
+            // Rounding up keeps partially covered pixels, unless that breaks the constraints. A
+            // widget which overflows them already keeps its size.
+            let max = Size::new(
+                bc.max().width.max(new_size.width),
+                bc.max().height.max(new_size.height),
+            );
+            let snapped = new_size.expand_to_pixel(cx.scale());
+            let snapped = Size::new(snapped.width.min(max.width), snapped.height.min(max.height));
+            // The baseline keeps its distance from the top.
+            self.state.baseline_offset += snapped.height - new_size.height;
+            new_size = snapped;
+        }
+        if new_size != self.state.size {
+            // The parent covers the area the widget no longer does.
+            cx.request_paint();
+        }
         self.state.size = new_size;
+        let mut paint_rect = new_size.to_rect() + self.state.paint_insets;
+        self.widget
+            .visit_children(&mut |child| paint_rect = paint_rect.union(child.paint_rect()));
+        self.state.local_paint_rect = paint_rect;
         // Note: here we're always doing requests for downstream processing, but if we
         // make layout more incremental, we'll probably want to do this only if there
         // is an actual layout change.
@@ -385,10 +1366,71 @@ impl Pod {
             .flags
             .insert(PodFlags::NEEDS_SET_ORIGIN | PodFlags::REQUEST_ACCESSIBILITY);
         self.state.flags.remove(PodFlags::REQUEST_LAYOUT);
+        self.state.layout_bc = Some(*bc);
         cx.widget_state.merge_up(&mut self.state);
         self.state.size
     }
 
+    /// Describes the widget and its descendants for debugging, see [`DebugState`].
+    ///
+    /// Each widget comes with its origin, its size and the pending requests and interaction
+    /// states of its pod, like `hot`.
+    pub fn tree_debug_state(&self) -> DebugState {
+        let mut debug_state = self.widget.debug_state();
+        let (origin, size) = (self.state.origin, self.state.size);
+        let flags: Vec<&str> = DEBUG_FLAGS
+            .iter()
+            .filter(|(flag, _)| self.state.flags.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect();
+        if !flags.is_empty() {
+            debug_state = debug_state.with_value("flags", flags.join("|"));
+        }
+        debug_state = debug_state
+            .with_value("origin", format!("{},{}", origin.x, origin.y))
+            .with_value("size", format!("{}x{}", size.width, size.height));
+        self.widget
+            .visit_children(&mut |child| debug_state.children.push(child.tree_debug_state()));
+        debug_state
+    }
+
+    /// The intrinsic extent of the widget on `axis`, see [`Widget::compute_intrinsic`].
+    ///
+    /// Like sizes, extents are rounded up to device pixels. They are cached until layout is
+    /// requested. Measuring may lay the widget out with other constraints, so after a fresh
+    /// measurement the next [`layout`](Pod::layout) runs even for the same constraints.
+    pub fn intrinsic(&mut self, cx: &mut LayoutCx, axis: Axis, kind: Intrinsic, cross: f64) -> f64 {
+        let caches = !self
+            .state
+            .flags
+            .intersects(PodFlags::REQUEST_LAYOUT | PodFlags::NO_LAYOUT_CACHE);
+        let key = (axis, kind, cross);
+        if let Some((.., extent)) = self
+            .state
+            .intrinsics
+            .iter()
+            .find(|(axis, kind, cross, _)| caches && (*axis, *kind, *cross) == key)
+        {
+            return *extent;
+        }
+        let mut child_cx = LayoutCx {
+            cx_state: cx.cx_state,
+            widget_state: &mut self.state,
+        };
+        let mut extent = self
+            .widget
+            .compute_intrinsic(&mut child_cx, axis, kind, cross);
+        if self.snaps_to_pixels() {
+            extent = ceil_to_pixel(extent, cx.scale());
+        }
+        if caches {
+            self.state.intrinsics.push((axis, kind, cross, extent));
+        }
+        self.state.layout_bc = None;
+        cx.widget_state.merge_up(&mut self.state);
+        extent
+    }
+
     ///
     pub fn accessibility(&mut self, cx: &mut AccessCx) {
         if self.state.flags.intersects(
@@ -418,6 +1460,16 @@ impl Pod {
     pub(crate) fn paint_impl(&mut self, cx: &mut PaintCx) {
         let needs_paint = self.state.flags.contains(PodFlags::REQUEST_PAINT);
         self.state.flags.remove(PodFlags::REQUEST_PAINT);
+        // Descendants which only asked for their own paint damage their own rectangles.
+        if needs_paint && self.state.flags.contains(PodFlags::DAMAGED) {
+            self.state.flags.remove(PodFlags::DAMAGED);
+            self.state.scroll_delta = None;
+            let rect = self.state.local_paint_rect + self.state.window_origin().to_vec2();
+            cx.cx_state.window_state().damage.add(rect);
+        } else if let Some(delta) = self.state.scroll_delta.take() {
+            let rect = Rect::from_origin_size(self.state.window_origin(), self.state.size);
+            cx.cx_state.window_state().damage.add_scroll(rect, delta);
+        }
 
         let mut inner_cx = PaintCx {
             cx_state: cx.cx_state,
@@ -425,6 +1477,10 @@ impl Pod {
         };
 
         if needs_paint {
+            inner_cx
+                .widget_state
+                .flags
+                .remove(PodFlags::PAINTS_FOCUS_RING);
             let mut builder = SceneBuilder::for_fragment(&mut self.fragment);
             self.widget.paint(&mut inner_cx, &mut builder);
         }
@@ -465,15 +1521,55 @@ impl Pod {
     /// The widget container can also call `set_origin` from other context, but calling `set_origin`
     /// after the widget received [`LifeCycle::ViewContextChanged`] and before the next event results
     /// in an inconsistent state of the widget tree.
+    ///
+    /// Unless disabled with [`set_snap_to_pixels`](Self::set_snap_to_pixels), the origin is
+    /// rounded to the closest device pixel. Containers which round positions themselves use
+    /// [`set_snapped_origin`](Self::set_snapped_origin) instead.
     pub fn set_origin(&mut self, cx: &mut LayoutCx, origin: Point) {
-        if origin != self.state.origin {
-            self.state.origin = origin;
-            // request paint is called on the parent instead of this widget, since this widget's
-            // fragment does not change.
-            cx.view_context_changed();
-            cx.request_paint();
+        let origin = if self.snaps_to_pixels() {
+            let scale = cx.scale();
+            Point::new(
+                round_to_pixel(origin.x, scale),
+                round_to_pixel(origin.y, scale),
+            )
+        } else {
+            origin
+        };
+        self.place(cx, origin);
+    }
+
+    /// Sets the origin like [`set_origin`](Pod::set_origin), for a container which rounded it
+    /// to device pixels already, for example with [`distribute_to_pixels`].
+    ///
+    /// In debug builds, it panics when the widget snaps to pixels and `origin` is not on a device
+    /// pixel.
+    ///
+    /// [`distribute_to_pixels`]: crate::distribute_to_pixels
+    pub fn set_snapped_origin(&mut self, cx: &mut LayoutCx, origin: Point) {
+        let scale = cx.scale();
+        debug_assert!(
+            !self.snaps_to_pixels() || is_on_pixel(origin.x, scale) && is_on_pixel(origin.y, scale),
+            "origin {origin:?} is not on a device pixel at scale {scale}"
+        );
+        self.place(cx, origin);
+    }
+
+    fn place(&mut self, cx: &mut LayoutCx, origin: Point) {
+        if origin != self.state.origin {
+            self.state.origin = origin;
+            // request paint is called on the parent instead of this widget, since this widget's
+            // fragment does not change. Children of scrolled content move with what the parent
+            // presented before.
+            cx.view_context_changed();
+            if cx.widget_state.scroll_delta.is_some() {
+                cx.widget_state.flags |= PodFlags::REQUEST_PAINT;
+            } else {
+                cx.request_paint();
+            }
 
-            self.state.flags.insert(PodFlags::VIEW_CONTEXT_CHANGED);
+            self.state
+                .flags
+                .insert(PodFlags::VIEW_CONTEXT_CHANGED | PodFlags::REQUEST_ACCESSIBILITY);
         }
     }
 
@@ -484,10 +1580,13 @@ impl Pod {
         cx_state: &mut CxState,
         mouse_pos: Option<Point>,
     ) -> bool {
-        let rect = Rect::from_origin_size(widget_state.origin, widget_state.size);
+        let rect = widget_state.layout_rect();
         let had_hot = widget_state.flags.contains(PodFlags::IS_HOT);
         let is_hot = match mouse_pos {
-            Some(pos) => rect.contains(pos),
+            Some(pos) => {
+                rect.contains(pos)
+                    && widget.hit_test(widget_state.size, pos - widget_state.origin.to_vec2())
+            }
             None => false,
         };
         widget_state.flags.set(PodFlags::IS_HOT, is_hot);
@@ -503,6 +1602,170 @@ impl Pod {
         false
     }
 
+    /// Whether `pos`, in the coordinate space of the parent, is on the widget, see
+    /// [`Widget::hit_test`].
+    pub(crate) fn hits(&self, pos: Point) -> bool {
+        self.layout_rect().contains(pos)
+            && self
+                .widget
+                .hit_test(self.state.size, pos - self.state.origin.to_vec2())
+    }
+
+    /// The widgets at `pos`, in the coordinate space of the parent, from this one down to the
+    /// innermost.
+    ///
+    /// Of overlapping children the one painted last is taken, and children only count where
+    /// their parent is hit, so clipped parts of them aren't found. Returns `None` when `pos` is
+    /// not on this widget.
+    pub fn widget_at(&self, pos: Point) -> Option<HitChain> {
+        let mut chain = HitChain::default();
+        self.hit_chain(pos, &mut chain.0).then_some(chain)
+    }
+
+    fn hit_chain(&self, pos: Point, chain: &mut Vec<(Id, Point)>) -> bool {
+        if !self.hits(pos) {
+            return false;
+        }
+        let pos = pos - self.state.origin.to_vec2();
+        chain.push((self.id(), pos));
+        let mut topmost = None;
+        self.widget.visit_children(&mut |child| {
+            let mut child_chain = vec![];
+            if child.hit_chain(pos, &mut child_chain) {
+                topmost = Some(child_chain);
+            }
+        });
+        chain.extend(topmost.into_iter().flatten());
+        true
+    }
+
+    /// Whether `pos`, in the coordinate space of the parent, is on a widget which scrolls with the
+    /// wheel, this one or one of its descendants.
+    ///
+    /// Scroll containers ask their children in the capture phase, to leave the wheel to the
+    /// innermost of them under the pointer, see [`LifeCycleCx::set_scrolls_with_wheel`].
+    pub fn scrolls_at(&self, pos: Point) -> bool {
+        if !self.hits(pos) {
+            return false;
+        }
+        if self.state.flags.contains(PodFlags::SCROLLS) {
+            return true;
+        }
+        let pos = pos - self.state.origin.to_vec2();
+        // As in `hit_chain`, the child painted last is the one under the point.
+        let mut topmost = false;
+        self.widget.visit_children(&mut |child| {
+            if child.hits(pos) {
+                topmost = child.scrolls_at(pos);
+            }
+        });
+        topmost
+    }
+
+    /// Sets where the widget is painted among its siblings, and returns the changes the owner
+    /// of this pod has to mark on its own pod.
+    ///
+    /// Containers paint children with a higher z-index above the others, and keep the order of
+    /// children with the same z-index, which is 0 by default. Hit testing finds the child on top.
+    pub fn set_z_index(&mut self, z_index: i32) -> ChangeFlags {
+        if self.state.z_index == z_index {
+            return ChangeFlags::empty();
+        }
+        self.state.z_index = z_index;
+        ChangeFlags::PAINT
+    }
+
+    /// Where the widget is painted among its siblings, see [`set_z_index`](Pod::set_z_index).
+    pub fn z_index(&self) -> i32 {
+        self.state.z_index
+    }
+
+    /// Sets how a [`LinearLayout`](super::LinearLayout) lays the widget out among its siblings,
+    /// and returns the changes the owner of this pod has to mark on its own pod.
+    ///
+    /// The metadata stays with the widget when siblings are inserted or removed before it.
+    pub fn set_linear_meta(&mut self, meta: LinearMeta) -> ChangeFlags {
+        if self.state.linear_meta == meta {
+            return ChangeFlags::empty();
+        }
+        self.state.linear_meta = meta;
+        ChangeFlags::LAYOUT
+    }
+
+    /// How a [`LinearLayout`](super::LinearLayout) lays the widget out, see
+    /// [`set_linear_meta`](Pod::set_linear_meta).
+    pub fn linear_meta(&self) -> LinearMeta {
+        self.state.linear_meta
+    }
+
+    /// The indices of `pods` in the order they are painted, by ascending z-index.
+    pub fn paint_order<'a>(pods: impl IntoIterator<Item = &'a Pod>) -> Vec<usize> {
+        let z_indices: Vec<i32> = pods.into_iter().map(Pod::z_index).collect();
+        let mut order: Vec<usize> = (0..z_indices.len()).collect();
+        order.sort_by_key(|index| z_indices[*index]);
+        order
+    }
+
+    /// The size of the widget, as set by the last layout pass.
+    pub fn size(&self) -> Size {
+        self.state.size
+    }
+
+    /// The origin of the widget in the coordinate space of its parent, as set by the last call
+    /// to [`set_origin`](Pod::set_origin).
+    pub fn origin(&self) -> Point {
+        self.state.origin
+    }
+
+    /// The rectangle of the widget in the coordinate space of its parent, as set by the last
+    /// layout pass.
+    pub fn layout_rect(&self) -> Rect {
+        self.state.layout_rect()
+    }
+
+    /// The distance from the bottom of the widget to the baseline of its text, as set by the
+    /// last layout pass, see [`LayoutCx::set_baseline_offset`].
+    pub fn baseline_offset(&self) -> f64 {
+        self.state.baseline_offset
+    }
+
+    /// The rectangle painted by the widget and its descendants in the coordinate space of its
+    /// parent, as set by the last layout pass.
+    ///
+    /// It contains the [`layout_rect`](Pod::layout_rect), the
+    /// [paint insets](LayoutCx::set_paint_insets) of the widget, and the paint rectangles of the
+    /// children it visits. Containers skip painting children whose paint rectangle isn't visible.
+    pub fn paint_rect(&self) -> Rect {
+        self.state.local_paint_rect + self.state.origin.to_vec2()
+    }
+
+    /// Sets whether the origin and size of the widget are aligned with device pixels.
+    ///
+    /// Snapping is on by default, it keeps borders and text sharp. Turning it off can make sense
+    /// for widgets moved by animations, where jumping from pixel to pixel looks jerky.
+    pub fn set_snap_to_pixels(&mut self, snap: bool) {
+        self.state.flags.set(PodFlags::NO_PIXEL_SNAP, !snap);
+    }
+
+    /// Returns whether the origin and size of the widget are aligned with device pixels.
+    pub fn snaps_to_pixels(&self) -> bool {
+        !self.state.flags.contains(PodFlags::NO_PIXEL_SNAP)
+    }
+
+    /// Sets whether the size of the last layout is reused while the constraints stay the same.
+    ///
+    /// Caching is on by default. Widgets which change their size without requesting layout, for
+    /// example because they measure something outside of the widget tree, have to turn it off.
+    /// As the widget is only reached through its ancestors, they stop caching as well.
+    pub fn set_caches_layout(&mut self, caches: bool) {
+        self.state.flags.set(PodFlags::NO_LAYOUT_CACHE, !caches);
+    }
+
+    /// Returns whether the size of the last layout is reused while the constraints stay the same.
+    pub fn caches_layout(&self) -> bool {
+        !self.state.flags.contains(PodFlags::NO_LAYOUT_CACHE)
+    }
+
     /// Get the id of the widget in the pod.
     pub fn id(&self) -> Id {
         self.state.id
@@ -514,12 +1777,13 @@ impl Pod {
     /// will change their appearance when hot as a visual indication that they
     /// will respond to mouse interaction.
     ///
-    /// The hot status is automatically computed from the widget's layout rect. In a
-    /// container hierarchy, all widgets with layout rects containing the mouse position
-    /// have hot status. The hot status cannot be set manually.
+    /// The hot status is automatically computed from the widget's layout rect and its
+    /// [`Widget::hit_test`]. In a container hierarchy, all widgets with layout rects containing
+    /// the mouse position have hot status. The hot status cannot be set manually.
     ///
-    /// There is no special handling of the hot status for multi-pointer devices. (This is
-    /// likely to change in the future as [pointer events are planed](https://xi.zulipchat.com/#narrow/stream/351333-glazier/topic/Pointer.20Events)).
+    /// The hot status follows the mouse, and the primary touch or pen while it emulates the
+    /// mouse. Widgets can ask whether other pointers are over them with
+    /// [`EventCx::is_hot_for`].
     ///
     /// Note: a widget can be hot while another is [`active`] (for example, when
     /// clicking a button and dragging the cursor to another widget).
@@ -538,8 +1802,8 @@ impl Pod {
     /// The active status can only be set manually. Xilem doesn't automatically
     /// set it to `false` on mouse release or anything like that.
     ///
-    /// There is no special handling of the active status for multi-pointer devices. (This is
-    /// likely to change in the future as [pointer events are planed](https://xi.zulipchat.com/#narrow/stream/351333-glazier/topic/Pointer.20Events)).
+    /// Like the hot status, the active status belongs to the mouse. Touches and pens are
+    /// captured one by one with [`EventCx::capture_pointer_id`].
     ///
     /// When a widget is active, it gets mouse events even when the mouse
     /// is dragged away.
@@ -556,8 +1820,1426 @@ impl Pod {
         self.state.flags.contains(PodFlags::HAS_ACTIVE)
     }
 
+    /// Returns `true` if this widget or a descendant has keyboard focus.
+    pub fn has_focus_within(&self) -> bool {
+        self.state.flags.contains(PodFlags::HAS_FOCUS)
+    }
+
+    /// This widget or any of its children have requested an animation frame.
+    pub fn anim_frame_requested(&self) -> bool {
+        self.state.flags.contains(PodFlags::REQUEST_ANIM_FRAME)
+    }
+
     /// This widget or any of its children have requested layout.
     pub fn layout_requested(&self) -> bool {
         self.state.flags.contains(PodFlags::REQUEST_LAYOUT)
     }
 }
+
+impl Drop for Pod {
+    fn drop(&mut self) {
+        if !self.state.flags.contains(PodFlags::IS_ADDED) {
+            return;
+        }
+        // The widget lives on until it is told about its removal.
+        let mut pod = Some(Pod {
+            state: std::mem::replace(&mut self.state, WidgetState::new()),
+            widget: std::mem::replace(&mut self.widget, Box::new(Empty)),
+            fragment: std::mem::take(&mut self.fragment),
+            type_name: self.type_name,
+        });
+        let _ = REMOVED.try_with(|removed| removed.borrow_mut().extend(pod.take()));
+        if let Some(pod) = &mut pod {
+            // The thread is exiting, nobody is left to tell.
+            pod.state.flags.remove(PodFlags::IS_ADDED);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Axis;
+    use crate::widget::test_util::{key_down, mouse, touch, Harness};
+    use crate::widget::{Label, LinearLayout, PodSlots, PointerId, ScrollUnit};
+    use glazier::{Cursor, Modifiers, MouseButton};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use test_log::test;
+    use vello::kurbo::Vec2;
+    use vello::peniko::Color;
+
+    const SCALES: [f64; 3] = [1.0, 1.25, 2.0];
+
+    /// A widget with a fixed, possibly fractional size.
+    struct Fixed(Size);
+
+    impl Widget for Fixed {
+        fn event(&mut self, _cx: &mut EventCx, _event: &Event) {}
+
+        fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, _cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            self.0
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+    }
+
+    fn is_on_pixel(value: f64, scale: f64) -> bool {
+        ((value * scale).round() - value * scale).abs() < 1e-9
+    }
+
+    fn column(children: usize, spacing: f64, snap: bool) -> Pod {
+        let children = (0..children)
+            .map(|_| {
+                let mut child = Pod::new(Fixed(Size::new(30.0, 10.3)));
+                child.set_snap_to_pixels(snap);
+                child
+            })
+            .collect();
+        Pod::new(LinearLayout::new(children, spacing, Axis::Vertical))
+    }
+
+    fn layout_at(scale: f64, column: &mut Pod) {
+        let mut harness = Harness::new();
+        harness.window_state.scale = scale;
+        harness.layout(column, &BoxConstraints::UNBOUNDED);
+    }
+
+    fn children(column: &mut Pod) -> &PodSlots {
+        &column.downcast_mut::<LinearLayout>().unwrap().children
+    }
+
+    #[test]
+    fn sizes_are_rounded_up_to_pixels() {
+        for scale in SCALES {
+            let mut column = column(1, 0.0, true);
+            layout_at(scale, &mut column);
+            let size = children(&mut column)[0].state.size;
+            assert!(is_on_pixel(size.height, scale), "{scale}: {size:?}");
+            assert!(size.height >= 10.3 && (size.height - 10.3) * scale < 1.0);
+            assert_eq!(size.width, 30.0);
+        }
+    }
+
+    #[test]
+    fn origins_are_snapped_without_drift() {
+        for scale in SCALES {
+            let mut column = column(10, 0.3, true);
+            layout_at(scale, &mut column);
+            let children = children(&mut column);
+            let height = children[0].state.size.height;
+            for (index, child) in children.iter().enumerate() {
+                let origin = child.state.origin.y;
+                assert!(is_on_pixel(origin, scale), "{scale}: {origin}");
+                // The unsnapped position of the child, computed without accumulating errors.
+                let exact = index as f64 * (height + 0.3);
+                assert!(
+                    (origin - exact).abs() * scale <= 1.0,
+                    "{scale}: {origin} {exact}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn snapping_can_be_turned_off() {
+        let mut column = column(2, 0.3, false);
+        layout_at(1.25, &mut column);
+        let children = children(&mut column);
+        assert_eq!(children[0].state.size.height, 10.3);
+        assert!((children[1].state.origin.y - 10.6).abs() < 1e-9);
+        assert!(!children[1].snaps_to_pixels());
+    }
+
+    /// A leaf with text on a baseline, which paints a shadow beyond its size.
+    struct Shadowed {
+        size: Size,
+        baseline: f64,
+        shadow: Insets,
+    }
+
+    impl Widget for Shadowed {
+        fn event(&mut self, _cx: &mut EventCx, _event: &Event) {}
+
+        fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            cx.set_baseline_offset(self.baseline);
+            cx.set_paint_insets(self.shadow);
+            self.size
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+    }
+
+    /// A column of a fixed child and a row, which starts with a shadowed child.
+    fn shadowed_tree(shadow: Insets) -> Pod {
+        let shadowed = Shadowed {
+            size: Size::new(40.0, 10.0),
+            baseline: 2.0,
+            shadow,
+        };
+        let row = vec![Pod::new(shadowed), Pod::new(Fixed(Size::new(30.0, 20.0)))];
+        let column = vec![
+            Pod::new(Fixed(Size::new(100.0, 20.0))),
+            Pod::new(LinearLayout::new(row, 10.0, Axis::Horizontal)),
+        ];
+        let mut root = Pod::new(LinearLayout::new(column, 4.0, Axis::Vertical));
+        Harness::new().layout(&mut root, &BoxConstraints::UNBOUNDED);
+        root
+    }
+
+    #[test]
+    fn layout_getters_follow_the_last_layout() {
+        let mut root = shadowed_tree(Insets::ZERO);
+        assert_eq!(root.size(), Size::new(100.0, 44.0));
+        // The column aligns with its last child, the row with its first.
+        assert_eq!(root.baseline_offset(), 12.0);
+
+        let row = &mut root.downcast_mut::<LinearLayout>().unwrap().children[1];
+        assert_eq!(row.origin(), Point::new(0.0, 24.0));
+        assert_eq!(row.layout_rect(), Rect::new(0.0, 24.0, 80.0, 44.0));
+        assert_eq!(row.baseline_offset(), 12.0);
+        let children = &row.downcast_mut::<LinearLayout>().unwrap().children;
+        assert_eq!(children[0].size(), Size::new(40.0, 10.0));
+        assert_eq!(children[0].baseline_offset(), 2.0);
+        assert_eq!(children[1].origin(), Point::new(50.0, 0.0));
+        assert_eq!(children[1].baseline_offset(), 0.0);
+    }
+
+    #[test]
+    fn paint_rects_union_up_the_tree() {
+        let root = shadowed_tree(Insets::ZERO);
+        assert_eq!(root.paint_rect(), root.layout_rect());
+
+        let mut root = shadowed_tree(Insets::new(2.0, 0.0, 4.0, 30.0));
+        let row = &mut root.downcast_mut::<LinearLayout>().unwrap().children[1];
+        assert_eq!(row.paint_rect(), Rect::new(-2.0, 24.0, 80.0, 64.0));
+        let shadowed = &row.downcast_mut::<LinearLayout>().unwrap().children[0];
+        assert_eq!(shadowed.paint_rect(), Rect::new(-2.0, 0.0, 44.0, 40.0));
+        assert_eq!(root.paint_rect(), Rect::new(-2.0, 0.0, 100.0, 64.0));
+    }
+
+    type Log = std::sync::Arc<std::sync::Mutex<Vec<&'static str>>>;
+
+    /// Records the phases it sees an event in, optionally around a child.
+    struct Recorder {
+        name: &'static str,
+        child: Option<Pod>,
+        captures_wheel: bool,
+        handles: bool,
+        log: Log,
+    }
+
+    impl Recorder {
+        fn new(name: &'static str, log: &Log) -> Self {
+            Recorder {
+                name,
+                child: None,
+                captures_wheel: false,
+                handles: false,
+                log: log.clone(),
+            }
+        }
+
+        fn around(mut self, child: Recorder) -> Self {
+            self.child = Some(Pod::new(child));
+            self
+        }
+    }
+
+    impl Widget for Recorder {
+        fn capture_event(&mut self, cx: &mut EventCx, event: &Event) {
+            if self.captures_wheel && matches!(event, Event::MouseWheel(_)) {
+                self.log.lock().unwrap().push(self.name);
+                cx.set_handled(true);
+            }
+        }
+
+        fn event(&mut self, cx: &mut EventCx, event: &Event) {
+            if let Some(child) = &mut self.child {
+                child.event(cx, event);
+            }
+            if !cx.is_handled() && !matches!(event, Event::MouseMove(_)) {
+                self.log.lock().unwrap().push(self.name);
+                cx.set_handled(self.handles);
+            }
+        }
+
+        fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+            if let Some(child) = &mut self.child {
+                child.lifecycle(cx, event);
+            }
+        }
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+            if let Some(child) = &mut self.child {
+                child.layout(cx, bc);
+                child.set_origin(cx, Point::ORIGIN);
+            }
+            Size::new(100.0, 100.0)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+    }
+
+    fn dispatch(root: Recorder, event: Event, log: &Log) -> Vec<&'static str> {
+        let mut harness = Harness::new();
+        let mut pod = Pod::new(root);
+        harness.layout(&mut pod, &BoxConstraints::UNBOUNDED);
+        harness.event(&mut pod, Event::MouseMove(mouse((10.0, 10.0))));
+        // Ignore the mouse entering the widgets.
+        log.lock().unwrap().clear();
+        harness.event(&mut pod, event);
+        std::mem::take(&mut log.lock().unwrap())
+    }
+
+    #[test]
+    fn captured_wheel_never_reaches_the_child() {
+        let log = Log::default();
+        let mut scroll = Recorder::new("scroll", &log).around(Recorder::new("slider", &log));
+        scroll.captures_wheel = true;
+        let event = Event::MouseWheel(mouse((10.0, 10.0)));
+        assert_eq!(dispatch(scroll, event, &log), ["scroll"]);
+    }
+
+    #[test]
+    fn unhandled_events_bubble_up_to_the_root() {
+        let log = Log::default();
+        let root = Recorder::new("root", &log)
+            .around(Recorder::new("parent", &log).around(Recorder::new("target", &log)));
+        let event = Event::MouseDown(mouse((10.0, 10.0)));
+        assert_eq!(dispatch(root, event, &log), ["target", "parent", "root"]);
+
+        let mut target = Recorder::new("target", &log);
+        target.handles = true;
+        let root = Recorder::new("root", &log).around(Recorder::new("parent", &log).around(target));
+        let event = Event::MouseDown(mouse((10.0, 10.0)));
+        assert_eq!(dispatch(root, event, &log), ["target"]);
+    }
+
+    #[test]
+    fn key_release_needs_the_press() {
+        let log = Log::default();
+        let mut harness = Harness::new();
+        let mut root = Pod::new(Recorder::new("root", &log).around(Recorder::new("leaf", &log)));
+        harness.layout(&mut root, &BoxConstraints::UNBOUNDED);
+        let leaf = root
+            .downcast_mut::<Recorder>()
+            .unwrap()
+            .child
+            .as_ref()
+            .unwrap()
+            .id();
+        let key_up = || {
+            Event::KeyUp(glazier::KeyEvent {
+                key: KbKey::Enter,
+                state: glazier::KeyState::Up,
+                ..Default::default()
+            })
+        };
+
+        harness.window_state.focus = Some(leaf);
+        harness.event(&mut root, key_down(KbKey::Enter));
+        harness.event(&mut root, key_up());
+        assert_eq!(
+            std::mem::take(&mut *log.lock().unwrap()),
+            ["leaf", "root", "leaf", "root"]
+        );
+
+        // Focus moved on between press and release.
+        harness.event(&mut root, key_down(KbKey::Enter));
+        harness.window_state.focus = Some(root.id());
+        harness.event(&mut root, key_up());
+        assert_eq!(std::mem::take(&mut *log.lock().unwrap()), ["leaf", "root"]);
+    }
+
+    /// Records its hot changes, with the names for becoming hot and for losing it.
+    struct HotProbe([&'static str; 2], Log);
+
+    impl Widget for HotProbe {
+        fn event(&mut self, _cx: &mut EventCx, _event: &Event) {}
+
+        fn lifecycle(&mut self, _cx: &mut LifeCycleCx, event: &LifeCycle) {
+            if let LifeCycle::HotChanged(is_hot) = event {
+                let name = if *is_hot { self.0[0] } else { self.0[1] };
+                self.1.lock().unwrap().push(name);
+            }
+        }
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, _cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            Size::new(50.0, 20.0)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+    }
+
+    #[test]
+    fn hot_moves_between_adjacent_widgets() {
+        let log = Log::default();
+        let mut harness = Harness::new();
+        let children = vec![
+            Pod::new(HotProbe(["a in", "a out"], log.clone())),
+            Pod::new(HotProbe(["b in", "b out"], log.clone())),
+        ];
+        let mut row = Pod::new(LinearLayout::new(children, 0.0, Axis::Horizontal));
+        harness.layout(&mut row, &BoxConstraints::UNBOUNDED);
+
+        for x in [10.0, 30.0, 49.0, 51.0, 70.0] {
+            harness.event(&mut row, Event::MouseMove(mouse((x, 10.0))));
+        }
+        assert_eq!(*log.lock().unwrap(), ["a in", "a out", "b in"]);
+
+        // The pointer left the window.
+        harness.event(&mut row, Event::MouseLeft());
+        assert_eq!(*log.lock().unwrap(), ["a in", "a out", "b in", "b out"]);
+    }
+
+    #[test]
+    fn capture_clears_hot_elsewhere() {
+        let log = Log::default();
+        let mut harness = Harness::new();
+        let children = vec![
+            Pod::new(HotProbe(["a in", "a out"], log.clone())),
+            Pod::new(HotProbe(["b in", "b out"], log.clone())),
+        ];
+        let mut row = Pod::new(LinearLayout::new(children, 0.0, Axis::Horizontal));
+        harness.layout(&mut row, &BoxConstraints::UNBOUNDED);
+        harness.event(&mut row, Event::MouseMove(mouse((70.0, 10.0))));
+
+        // A widget outside of the row captured the pointer.
+        harness.window_state.pointer_capture = Some(Id::next());
+        harness.event(&mut row, Event::MouseMove(mouse((71.0, 10.0))));
+        assert_eq!(*log.lock().unwrap(), ["b in", "b out"]);
+    }
+
+    /// Records the mouse entering and leaving it, with the names for both, around an optional
+    /// child.
+    struct CrossingProbe {
+        names: [&'static str; 2],
+        child: Option<Pod>,
+        log: Log,
+    }
+
+    impl Widget for CrossingProbe {
+        fn event(&mut self, cx: &mut EventCx, event: &Event) {
+            if let Some(child) = &mut self.child {
+                child.event(cx, event);
+            }
+            match event {
+                Event::PointerEnter => self.log.lock().unwrap().push(self.names[0]),
+                Event::PointerLeave => self.log.lock().unwrap().push(self.names[1]),
+                _ => {}
+            }
+        }
+
+        fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+            if let Some(child) = &mut self.child {
+                child.lifecycle(cx, event);
+            }
+        }
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+            let Some(child) = &mut self.child else {
+                return Size::new(50.0, 20.0);
+            };
+            let size = child.layout(cx, bc);
+            child.set_origin(cx, Point::ORIGIN);
+            size
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+    }
+
+    /// Two probes in a row, inside of a probe for the row.
+    fn crossing_row(log: &Log) -> Pod {
+        let probe = |names, child| CrossingProbe {
+            names,
+            child,
+            log: log.clone(),
+        };
+        let children = vec![
+            Pod::new(probe(["a in", "a out"], None)),
+            Pod::new(probe(["b in", "b out"], None)),
+        ];
+        let row = Pod::new(LinearLayout::new(children, 0.0, Axis::Horizontal));
+        Pod::new(probe(["row in", "row out"], Some(row)))
+    }
+
+    #[test]
+    fn moving_between_siblings_skips_the_parent() {
+        let log = Log::default();
+        let mut harness = Harness::new();
+        let mut root = crossing_row(&log);
+        harness.layout(&mut root, &BoxConstraints::UNBOUNDED);
+
+        harness.event(&mut root, Event::MouseMove(mouse((10.0, 10.0))));
+        assert_eq!(
+            std::mem::take(&mut *log.lock().unwrap()),
+            ["row in", "a in"]
+        );
+        harness.event(&mut root, Event::MouseMove(mouse((20.0, 10.0))));
+        assert!(log.lock().unwrap().is_empty());
+        harness.event(&mut root, Event::MouseMove(mouse((70.0, 10.0))));
+        assert_eq!(std::mem::take(&mut *log.lock().unwrap()), ["a out", "b in"]);
+
+        // The pointer left the window.
+        harness.event(&mut root, Event::MouseLeft());
+        assert_eq!(
+            std::mem::take(&mut *log.lock().unwrap()),
+            ["b out", "row out"]
+        );
+    }
+
+    #[test]
+    fn capture_elsewhere_enters_nothing() {
+        let log = Log::default();
+        let mut harness = Harness::new();
+        let mut root = crossing_row(&log);
+        harness.layout(&mut root, &BoxConstraints::UNBOUNDED);
+
+        // A widget outside of the row captured the pointer.
+        harness.window_state.pointer_capture = Some(Id::next());
+        harness.event(&mut root, Event::MouseMove(mouse((70.0, 10.0))));
+        assert!(log.lock().unwrap().is_empty());
+
+        // Once it is released, the widgets under the mouse are entered.
+        harness.window_state.pointer_capture = None;
+        harness.event(&mut root, Event::MouseMove(mouse((71.0, 10.0))));
+        assert_eq!(
+            std::mem::take(&mut *log.lock().unwrap()),
+            ["row in", "b in"]
+        );
+    }
+
+    /// Asks for a cursor around an optional child in its top left corner, either for every
+    /// mouse event or as an override once it is hot.
+    struct CursorProbe {
+        cursor: Cursor,
+        sticky: bool,
+        captures: bool,
+        size: f64,
+        child: Option<Pod>,
+    }
+
+    impl CursorProbe {
+        fn new(cursor: Cursor, sticky: bool, size: f64, child: Option<CursorProbe>) -> Self {
+            CursorProbe {
+                cursor,
+                sticky,
+                captures: false,
+                size,
+                child: child.map(Pod::new),
+            }
+        }
+    }
+
+    impl Widget for CursorProbe {
+        fn event(&mut self, cx: &mut EventCx, event: &Event) {
+            if let Some(child) = &mut self.child {
+                child.event(cx, event);
+            }
+            if let Event::MouseMove(_) | Event::MouseDown(_) = event {
+                if !self.sticky {
+                    cx.set_cursor(&self.cursor);
+                }
+            }
+            if let Event::MouseDown(_) = event {
+                if self.captures {
+                    cx.capture_pointer();
+                }
+            }
+        }
+
+        fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+            if let Some(child) = &mut self.child {
+                child.lifecycle(cx, event);
+            }
+            if let LifeCycle::HotChanged(true) = event {
+                if self.sticky {
+                    cx.override_cursor(&self.cursor);
+                }
+            }
+        }
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+            if let Some(child) = &mut self.child {
+                child.layout(cx, bc);
+                child.set_origin(cx, Point::ORIGIN);
+            }
+            Size::new(self.size, self.size)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+    }
+
+    /// A root with a crosshair override, around a widget setting a resize cursor on every
+    /// event, around a leaf with an I-beam override.
+    fn cursor_probes(middle_captures: bool) -> Pod {
+        let leaf = CursorProbe::new(Cursor::IBeam, true, 20.0, None);
+        let mut middle = CursorProbe::new(Cursor::ResizeLeftRight, false, 60.0, Some(leaf));
+        middle.captures = middle_captures;
+        Pod::new(CursorProbe::new(
+            Cursor::Crosshair,
+            true,
+            100.0,
+            Some(middle),
+        ))
+    }
+
+    #[test]
+    fn deepest_hot_widget_chooses_the_cursor() {
+        let mut harness = Harness::new();
+        let mut root = cursor_probes(false);
+        harness.layout(&mut root, &BoxConstraints::UNBOUNDED);
+
+        harness.event(&mut root, Event::MouseMove(mouse((10.0, 10.0))));
+        assert!(matches!(harness.window_state.cursor(), Cursor::IBeam));
+        harness.event(&mut root, Event::MouseMove(mouse((40.0, 40.0))));
+        assert!(matches!(
+            harness.window_state.cursor(),
+            Cursor::ResizeLeftRight
+        ));
+        harness.event(&mut root, Event::MouseMove(mouse((80.0, 80.0))));
+        assert!(matches!(harness.window_state.cursor(), Cursor::Crosshair));
+        // Back over the leaf, its override still applies.
+        harness.event(&mut root, Event::MouseMove(mouse((10.0, 10.0))));
+        assert!(matches!(harness.window_state.cursor(), Cursor::IBeam));
+        harness.event(&mut root, Event::MouseLeft());
+        assert!(matches!(harness.window_state.cursor(), Cursor::Arrow));
+    }
+
+    #[test]
+    fn capturing_widget_keeps_its_cursor() {
+        let mut harness = Harness::new();
+        let mut root = cursor_probes(true);
+        harness.layout(&mut root, &BoxConstraints::UNBOUNDED);
+
+        harness.event(&mut root, Event::MouseMove(mouse((40.0, 40.0))));
+        harness.event(&mut root, Event::MouseDown(mouse((40.0, 40.0))));
+        // Neither over the leaf nor outside of the capturing widget the cursor changes.
+        for pos in [(10.0, 10.0), (90.0, 90.0)] {
+            harness.event(&mut root, Event::MouseMove(mouse(pos)));
+            assert!(matches!(
+                harness.window_state.cursor(),
+                Cursor::ResizeLeftRight
+            ));
+        }
+        harness.event(&mut root, Event::MouseUp(mouse((90.0, 90.0))));
+        assert!(matches!(harness.window_state.cursor(), Cursor::Crosshair));
+    }
+
+    /// Records the wheel deltas it receives.
+    struct WheelProbe(Arc<Mutex<Vec<Vec2>>>);
+
+    impl Widget for WheelProbe {
+        fn event(&mut self, _cx: &mut EventCx, event: &Event) {
+            if let Event::MouseWheel(mouse) = event {
+                assert_eq!(mouse.wheel_unit, ScrollUnit::Pixels);
+                self.0.lock().unwrap().push(mouse.wheel_delta);
+            }
+        }
+
+        fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, _cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            Size::new(50.0, 20.0)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+    }
+
+    #[test]
+    fn wheel_deltas_arrive_in_pixels() {
+        let deltas = Arc::new(Mutex::new(vec![]));
+        let mut harness = Harness::new();
+        harness.window_state.wheel_line_height = 16.0;
+        let mut pod = Pod::new(WheelProbe(deltas.clone()));
+        harness.layout(&mut pod, &BoxConstraints::UNBOUNDED);
+        let mut wheel = |delta: (f64, f64), unit, mods| {
+            let mut mouse = mouse((10.0, 10.0));
+            mouse.wheel_delta = delta.into();
+            mouse.wheel_unit = unit;
+            mouse.mods = mods;
+            harness.event(&mut pod, Event::MouseWheel(mouse));
+        };
+
+        wheel((0.0, 3.0), ScrollUnit::Lines, Modifiers::empty());
+        wheel((1.5, -2.5), ScrollUnit::Pixels, Modifiers::empty());
+        wheel((0.0, -1.0), ScrollUnit::Lines, Modifiers::SHIFT);
+        // Trackpads already scroll sideways on their own.
+        wheel((4.0, 1.0), ScrollUnit::Pixels, Modifiers::SHIFT);
+        assert_eq!(
+            *deltas.lock().unwrap(),
+            [
+                Vec2::new(0.0, 48.0),
+                Vec2::new(1.5, -2.5),
+                Vec2::new(-16.0, 0.0),
+                Vec2::new(4.0, 1.0),
+            ]
+        );
+    }
+
+    /// Captures the touches pressed on it and records the touch events it receives.
+    struct TouchProbe(&'static str, Arc<Mutex<Vec<String>>>);
+
+    impl Widget for TouchProbe {
+        fn event(&mut self, cx: &mut EventCx, event: &Event) {
+            let Some(pointer) = event.pointer() else {
+                return;
+            };
+            let kind = match event {
+                Event::PointerDown(_) => "down",
+                Event::PointerMove(_) => "move",
+                Event::PointerUp(_) => "up",
+                _ => "cancel",
+            };
+            if let Event::PointerDown(_) = event {
+                cx.capture_pointer_id(pointer.pointer_id);
+            }
+            let PointerId(id) = pointer.pointer_id;
+            let place = if cx.is_hot_for(pointer.pointer_id) {
+                "over"
+            } else {
+                "away"
+            };
+            let entry = format!("{} {kind} {id} {place}", self.0);
+            self.1.lock().unwrap().push(entry);
+            cx.set_handled(true);
+        }
+
+        fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, _cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            Size::new(50.0, 20.0)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+    }
+
+    #[test]
+    fn touches_are_captured_independently() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let mut harness = Harness::new();
+        let children = vec![
+            Pod::new(TouchProbe("a", log.clone())),
+            Pod::new(TouchProbe("b", log.clone())),
+        ];
+        let mut row = Pod::new(LinearLayout::new(children, 0.0, Axis::Horizontal));
+        harness.layout(&mut row, &BoxConstraints::UNBOUNDED);
+
+        harness.event(&mut row, Event::PointerDown(touch(0, (10.0, 10.0))));
+        harness.event(&mut row, Event::PointerDown(touch(1, (70.0, 10.0))));
+        // The fingers cross, each keeps going to the widget it pressed.
+        harness.event(&mut row, Event::PointerMove(touch(0, (80.0, 10.0))));
+        harness.event(&mut row, Event::PointerMove(touch(1, (20.0, 10.0))));
+        harness.event(&mut row, Event::PointerUp(touch(0, (80.0, 10.0))));
+        harness.event(&mut row, Event::PointerMove(touch(1, (60.0, 10.0))));
+        assert_eq!(harness.window_state.pointers.len(), 1);
+        harness.event(&mut row, Event::WindowLostFocus);
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "a down 0 over",
+                "b down 1 over",
+                "a move 0 away",
+                "b move 1 away",
+                "a up 0 away",
+                "b move 1 over",
+                "b cancel 1 over",
+            ]
+        );
+        assert!(harness.window_state.pointers.is_empty());
+        // The probes handled the touches, so nothing stood in for the mouse.
+        assert!(!row.is_hot());
+    }
+
+    /// Logs the click count of the presses it gets.
+    struct ClickProbe {
+        name: &'static str,
+        log: Arc<Mutex<Vec<(&'static str, u8)>>>,
+    }
+
+    impl Widget for ClickProbe {
+        fn event(&mut self, _cx: &mut EventCx, event: &Event) {
+            if let Event::MouseDown(mouse) = event {
+                let entry = (self.name, mouse.click_count);
+                self.log.lock().unwrap().push(entry);
+            }
+        }
+
+        fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, _cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            Size::new(50.0, 20.0)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+    }
+
+    fn click_row(harness: &mut Harness) -> (Pod, Arc<Mutex<Vec<(&'static str, u8)>>>) {
+        let log = Arc::new(Mutex::new(vec![]));
+        let probe = |name| {
+            Pod::new(ClickProbe {
+                name,
+                log: log.clone(),
+            })
+        };
+        let children = vec![probe("a"), probe("b")];
+        let mut root = Pod::new(LinearLayout::new(children, 0.0, Axis::Horizontal));
+        harness.layout(&mut root, &BoxConstraints::UNBOUNDED);
+        (root, log)
+    }
+
+    fn click(harness: &mut Harness, root: &mut Pod, pos: (f64, f64)) {
+        harness.event(root, Event::MouseMove(mouse(pos)));
+        harness.event(root, Event::MouseDown(mouse(pos)));
+        harness.event(root, Event::MouseUp(mouse(pos)));
+    }
+
+    #[test]
+    fn quick_presses_count_up() {
+        let mut harness = Harness::new();
+        let (mut root, log) = click_row(&mut harness);
+
+        click(&mut harness, &mut root, (10.0, 10.0));
+        harness.advance_time(&mut root, Duration::from_millis(200));
+        click(&mut harness, &mut root, (11.0, 10.0));
+        harness.advance_time(&mut root, Duration::from_millis(200));
+        click(&mut harness, &mut root, (12.0, 11.0));
+        assert_eq!(
+            std::mem::take(&mut *log.lock().unwrap()),
+            [("a", 1), ("a", 2), ("a", 3)]
+        );
+
+        // Too slow.
+        harness.advance_time(&mut root, Duration::from_millis(600));
+        click(&mut harness, &mut root, (12.0, 11.0));
+        harness.advance_time(&mut root, Duration::from_millis(600));
+        click(&mut harness, &mut root, (12.0, 11.0));
+        // Too far from the first press.
+        click(&mut harness, &mut root, (20.0, 10.0));
+        assert_eq!(
+            std::mem::take(&mut *log.lock().unwrap()),
+            [("a", 1), ("a", 1), ("a", 1)]
+        );
+    }
+
+    #[test]
+    fn presses_on_another_widget_start_over() {
+        let mut harness = Harness::new();
+        let (mut root, log) = click_row(&mut harness);
+
+        // Close by, but across the border between the probes.
+        click(&mut harness, &mut root, (48.0, 10.0));
+        click(&mut harness, &mut root, (51.0, 10.0));
+        click(&mut harness, &mut root, (51.0, 10.0));
+        // Another button.
+        let mut right = mouse((51.0, 10.0));
+        right.button = MouseButton::Right;
+        harness.event(&mut root, Event::MouseDown(right.clone()));
+        harness.event(&mut root, Event::MouseUp(right));
+        assert_eq!(
+            std::mem::take(&mut *log.lock().unwrap()),
+            [("a", 1), ("b", 1), ("b", 2), ("b", 1)]
+        );
+    }
+
+    #[test]
+    fn typed_access_to_the_widget() {
+        let mut pod = Pod::new(Fixed(Size::new(10.0, 20.0)));
+        assert!(pod.widget_type_name().ends_with("Fixed"));
+        assert_eq!(
+            pod.downcast_ref::<Fixed>().unwrap().0,
+            Size::new(10.0, 20.0)
+        );
+        assert!(pod.downcast_ref::<LinearLayout>().is_none());
+        pod.expect_mut::<Fixed>().0 = Size::new(30.0, 40.0);
+        assert_eq!(
+            pod.downcast_mut::<Fixed>().unwrap().0,
+            Size::new(30.0, 40.0)
+        );
+    }
+
+    #[test]
+    fn type_mismatches_name_both_types() {
+        let mut pod = Pod::new(Fixed(Size::ZERO));
+        let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            pod.expect_mut::<LinearLayout>();
+        }))
+        .unwrap_err();
+        let message = panic.downcast::<String>().unwrap();
+        assert_eq!(
+            *message,
+            format!(
+                "expected the widget in the pod to be a `{}`, but it is a `{}`; \
+                 did the element type of its view change?",
+                std::any::type_name::<LinearLayout>(),
+                std::any::type_name::<Fixed>(),
+            )
+        );
+    }
+
+    /// Stacks its children vertically and logs every layout, leaves grow when clicked.
+    struct LayoutProbe {
+        name: &'static str,
+        log: Log,
+        children: Vec<Pod>,
+        height: f64,
+    }
+
+    impl LayoutProbe {
+        fn new(name: &'static str, log: &Log, children: Vec<LayoutProbe>) -> Self {
+            LayoutProbe {
+                name,
+                log: log.clone(),
+                children: children.into_iter().map(Pod::new).collect(),
+                height: 10.0,
+            }
+        }
+    }
+
+    impl Widget for LayoutProbe {
+        fn event(&mut self, cx: &mut EventCx, event: &Event) {
+            for child in &mut self.children {
+                child.event(cx, event);
+            }
+            if self.children.is_empty() && matches!(event, Event::MouseDown(_)) {
+                self.height += 10.0;
+                cx.request_layout();
+            }
+        }
+
+        fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+            for child in &mut self.children {
+                child.lifecycle(cx, event);
+            }
+        }
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            self.log.lock().unwrap().push(self.name);
+            if self.children.is_empty() {
+                return Size::new(50.0, self.height);
+            }
+            // The children get the same constraints whatever the parent gets.
+            let mut y = 0.0;
+            for child in &mut self.children {
+                let size = child.layout(cx, &BoxConstraints::UNBOUNDED);
+                child.set_origin(cx, Point::new(0.0, y));
+                y += size.height;
+            }
+            Size::new(50.0, y)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+    }
+
+    /// A root with two children, the first one wrapping a leaf.
+    fn probe_tree(log: &Log) -> Pod {
+        let leaf = LayoutProbe::new("leaf", log, vec![]);
+        let first = LayoutProbe::new("first", log, vec![leaf]);
+        let second = LayoutProbe::new("second", log, vec![]);
+        Pod::new(LayoutProbe::new("root", log, vec![first, second]))
+    }
+
+    fn probe_children(root: &mut Pod) -> &mut [Pod] {
+        &mut root.downcast_mut::<LayoutProbe>().unwrap().children
+    }
+
+    #[test]
+    fn unchanged_constraints_reuse_the_layout() {
+        let log = Log::default();
+        let mut harness = Harness::new();
+        let mut root = probe_tree(&log);
+        let bc = BoxConstraints::UNBOUNDED;
+        harness.layout(&mut root, &bc);
+        let laid_out = std::mem::take(&mut *log.lock().unwrap());
+        assert_eq!(laid_out, ["root", "first", "leaf", "second"]);
+
+        assert_eq!(harness.layout(&mut root, &bc), Size::new(50.0, 20.0));
+        assert!(log.lock().unwrap().is_empty());
+
+        // The children get the same constraints as before, but are still placed.
+        let size = harness.layout(&mut root, &BoxConstraints::tight(Size::new(50.0, 20.0)));
+        assert_eq!(size, Size::new(50.0, 20.0));
+        assert_eq!(*log.lock().unwrap(), ["root"]);
+        assert_eq!(
+            probe_children(&mut root)[1].state.origin,
+            Point::new(0.0, 10.0)
+        );
+    }
+
+    #[test]
+    fn grandchild_layout_requests_reach_only_its_ancestors() {
+        let log = Log::default();
+        let mut harness = Harness::new();
+        let mut root = probe_tree(&log);
+        let bc = BoxConstraints::UNBOUNDED;
+        harness.layout(&mut root, &bc);
+        log.lock().unwrap().clear();
+
+        harness.event(&mut root, Event::MouseMove(mouse((5.0, 5.0))));
+        harness.event(&mut root, Event::MouseDown(mouse((5.0, 5.0))));
+        assert!(root.layout_requested());
+        assert!(!probe_children(&mut root)[1].layout_requested());
+        assert_eq!(harness.layout(&mut root, &bc), Size::new(50.0, 30.0));
+        assert_eq!(*log.lock().unwrap(), ["root", "first", "leaf"]);
+        // The sibling kept its size, but moved down below the grown leaf.
+        let second = &probe_children(&mut root)[1];
+        assert_eq!(second.layout_rect(), Rect::new(0.0, 20.0, 50.0, 30.0));
+    }
+
+    #[test]
+    fn pods_can_opt_out_of_the_cache() {
+        let log = Log::default();
+        let mut harness = Harness::new();
+        let mut root = probe_tree(&log);
+        probe_children(&mut root)[1].set_caches_layout(false);
+        let bc = BoxConstraints::UNBOUNDED;
+        harness.layout(&mut root, &bc);
+        log.lock().unwrap().clear();
+
+        harness.layout(&mut root, &bc);
+        assert_eq!(*log.lock().unwrap(), ["root", "second"]);
+        assert!(!root.caches_layout());
+        assert!(probe_children(&mut root)[0].caches_layout());
+    }
+
+    /// Logs where it is pressed, and places its child at a fixed offset.
+    struct Offset {
+        log: Arc<Mutex<Vec<Point>>>,
+        child: Option<Pod>,
+    }
+
+    impl Widget for Offset {
+        fn event(&mut self, cx: &mut EventCx, event: &Event) {
+            match &mut self.child {
+                Some(child) => child.event(cx, event),
+                None => {
+                    if let Event::MouseDown(mouse) = event {
+                        self.log.lock().unwrap().push(mouse.pos);
+                    }
+                }
+            }
+        }
+
+        fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+            if let Some(child) = &mut self.child {
+                child.lifecycle(cx, event);
+            }
+        }
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            if let Some(child) = &mut self.child {
+                child.layout(cx, &BoxConstraints::UNBOUNDED);
+                child.set_origin(cx, Point::new(100.0, 50.0));
+            }
+            Size::new(200.0, 100.0)
+        }
+
+        fn accessibility(&mut self, cx: &mut AccessCx) {
+            if let Some(child) = &mut self.child {
+                child.accessibility(cx);
+            }
+            if cx.is_requested() {
+                cx.push_node(accesskit::NodeBuilder::new(
+                    accesskit::Role::GenericContainer,
+                ));
+            }
+        }
+
+        fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+            if let Some(child) = &mut self.child {
+                child.paint(cx, builder);
+            }
+        }
+    }
+
+    #[test]
+    fn children_live_at_their_origin() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let mut harness = Harness::new();
+        let leaf = Pod::new(Offset {
+            log: log.clone(),
+            child: None,
+        });
+        let leaf_id: accesskit::NodeId = leaf.id().into();
+        let mut root = Pod::new(Offset {
+            log: log.clone(),
+            child: Some(leaf),
+        });
+        harness.frame(&mut root, &BoxConstraints::UNBOUNDED);
+
+        // Presses outside of the child don't reach it.
+        harness.event(&mut root, Event::MouseDown(mouse((90.0, 40.0))));
+        harness.event(&mut root, Event::MouseDown(mouse((110.0, 60.0))));
+        assert_eq!(*log.lock().unwrap(), [Point::new(10.0, 10.0)]);
+
+        let expected = Rect::new(100.0, 50.0, 300.0, 150.0);
+        let update = harness.accessibility(&mut root);
+        let (_, node) = update.nodes.iter().find(|(id, _)| *id == leaf_id).unwrap();
+        let bounds = node.bounds().unwrap();
+        let bounds = Rect::new(bounds.x0, bounds.y0, bounds.x1, bounds.y1);
+        assert_eq!(bounds, expected);
+
+        // The child's fragment is appended translated to its origin, its repaint damages the
+        // same rectangle.
+        let _ = std::mem::take(&mut harness.window_state.damage);
+        let offset = root.downcast_mut::<Offset>().unwrap();
+        let changes = offset.child.as_mut().unwrap().mark(ChangeFlags::PAINT);
+        let _ = root.mark(changes);
+        harness.frame(&mut root, &BoxConstraints::UNBOUNDED);
+        assert_eq!(harness.window_state.damage.rects(), [expected]);
+    }
+
+    /// Counts how often it is painted.
+    struct PaintCounter(Arc<AtomicUsize>);
+
+    impl Widget for PaintCounter {
+        fn event(&mut self, _cx: &mut EventCx, _event: &Event) {}
+
+        fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, _cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            Size::new(4.0, 4.0)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn clean_subtrees_append_their_cached_fragment() {
+        // The paint time of the same tree is measured by `benches/paint.rs`, here the painted
+        // widgets are counted.
+        let paints = Arc::new(AtomicUsize::new(0));
+        let rows = (0..50)
+            .map(|_| {
+                let leaves = (0..100)
+                    .map(|_| Pod::new(PaintCounter(paints.clone())))
+                    .collect();
+                Pod::new(LinearLayout::new(leaves, 0.0, Axis::Horizontal))
+            })
+            .collect();
+        let mut root = Pod::new(LinearLayout::new(rows, 0.0, Axis::Vertical));
+        let mut harness = Harness::new();
+        let bc = BoxConstraints::UNBOUNDED;
+        harness.frame(&mut root, &bc);
+        assert_eq!(paints.swap(0, Ordering::Relaxed), 5000);
+
+        let row = &mut root.downcast_mut::<LinearLayout>().unwrap().children[20];
+        let leaf = &mut row.downcast_mut::<LinearLayout>().unwrap().children[70];
+        let changes = leaf.mark(ChangeFlags::PAINT);
+        let changes = row.mark(changes);
+        let _ = root.mark(changes);
+        assert_eq!(harness.frame(&mut root, &bc), ChangeFlags::PAINT);
+        assert_eq!(paints.load(Ordering::Relaxed), 1);
+    }
+
+    /// Places its children at fixed origins, the later ones above the earlier ones.
+    struct Stack(Vec<(Point, Pod)>);
+
+    impl Widget for Stack {
+        fn event(&mut self, cx: &mut EventCx, event: &Event) {
+            for (_, child) in &mut self.0 {
+                child.event(cx, event);
+            }
+        }
+
+        fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+            for (_, child) in &mut self.0 {
+                child.lifecycle(cx, event);
+            }
+        }
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            for (origin, child) in &mut self.0 {
+                child.layout(cx, &BoxConstraints::UNBOUNDED);
+                child.set_origin(cx, *origin);
+            }
+            Size::new(100.0, 100.0)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+            for (_, child) in &mut self.0 {
+                child.paint(cx, builder);
+            }
+        }
+
+        fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+            for (_, child) in &self.0 {
+                visit(child);
+            }
+        }
+    }
+
+    fn stack(children: Vec<(Point, Pod)>) -> (Pod, Vec<Id>) {
+        let ids = children.iter().map(|(_, child)| child.id()).collect();
+        let mut root = Pod::new(Stack(children));
+        Harness::new().layout(&mut root, &BoxConstraints::UNBOUNDED);
+        (root, ids)
+    }
+
+    #[test]
+    fn topmost_child_is_hit() {
+        let (root, ids) = stack(vec![
+            (
+                Point::new(10.0, 10.0),
+                Pod::new(Fixed(Size::new(50.0, 50.0))),
+            ),
+            (
+                Point::new(30.0, 30.0),
+                Pod::new(Fixed(Size::new(50.0, 50.0))),
+            ),
+        ]);
+        let chain = root.widget_at(Point::new(40.0, 40.0)).unwrap();
+        assert_eq!(
+            chain.0,
+            [
+                (root.id(), Point::new(40.0, 40.0)),
+                (ids[1], Point::new(10.0, 10.0))
+            ]
+        );
+
+        let below = root.widget_at(Point::new(20.0, 20.0)).unwrap();
+        assert_eq!(below.target(), Some((ids[0], Point::new(10.0, 10.0))));
+        let beside = root.widget_at(Point::new(90.0, 5.0)).unwrap();
+        assert_eq!(beside.target(), Some((root.id(), Point::new(90.0, 5.0))));
+        assert_eq!(root.widget_at(Point::new(120.0, 5.0)), None);
+    }
+
+    #[test]
+    fn children_are_clipped_to_their_parent() {
+        let (root, ids) = stack(vec![(
+            Point::new(80.0, 0.0),
+            Pod::new(Fixed(Size::new(50.0, 50.0))),
+        )]);
+        let inside = root.widget_at(Point::new(90.0, 10.0)).unwrap();
+        assert!(inside.contains(ids[0]));
+        // On the child, but outside of the stack.
+        assert_eq!(root.widget_at(Point::new(110.0, 10.0)), None);
+    }
+
+    type LifeLog = Arc<Mutex<Vec<(Id, bool)>>>;
+
+    /// Logs when it is added and removed, with its children.
+    struct LifeProbe(LifeLog, Vec<Pod>);
+
+    impl LifeProbe {
+        fn pod(log: &LifeLog, children: Vec<Pod>) -> Pod {
+            Pod::new(LifeProbe(log.clone(), children))
+        }
+    }
+
+    impl Widget for LifeProbe {
+        fn event(&mut self, _cx: &mut EventCx, _event: &Event) {}
+
+        fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+            match event {
+                LifeCycle::Added => self.0.lock().unwrap().push((cx.widget_id(), true)),
+                LifeCycle::Removed => self.0.lock().unwrap().push((cx.widget_id(), false)),
+                _ => {}
+            }
+            for child in &mut self.1 {
+                child.lifecycle(cx, event);
+            }
+        }
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+            for child in &mut self.1 {
+                child.layout(cx, bc);
+                child.set_origin(cx, Point::ORIGIN);
+            }
+            Size::new(10.0, 10.0)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+    }
+
+    #[test]
+    fn added_and_removed_come_in_pairs() {
+        let log = LifeLog::default();
+        let mut harness = Harness::new();
+        let bc = BoxConstraints::UNBOUNDED;
+        let nested = LifeProbe::pod(&log, vec![LifeProbe::pod(&log, vec![])]);
+        let children = vec![LifeProbe::pod(&log, vec![]), nested];
+        let mut root = Pod::new(LinearLayout::new(children, 0.0, Axis::Vertical));
+        harness.layout(&mut root, &bc);
+        assert_eq!(log.lock().unwrap().len(), 3);
+        assert!(log.lock().unwrap().iter().all(|(_, added)| *added));
+
+        // Removing, replacing and appending children, like sequence splices do.
+        let children = &mut root.downcast_mut::<LinearLayout>().unwrap().children;
+        children.remove(1);
+        children[0] = LifeProbe::pod(&log, vec![]);
+        children.push(LifeProbe::pod(&log, vec![LifeProbe::pod(&log, vec![])]));
+        let _ = root.mark(ChangeFlags::TREE);
+        harness.layout(&mut root, &bc);
+        let removed = log
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, added)| !added)
+            .count();
+        assert_eq!(removed, 3);
+        assert_eq!(log.lock().unwrap().len(), 9);
+
+        // Moving a child keeps it in the tree.
+        let children = &mut root.downcast_mut::<LinearLayout>().unwrap().children;
+        children.swap(0, 1);
+        let _ = root.mark(ChangeFlags::TREE);
+        harness.layout(&mut root, &bc);
+        assert_eq!(log.lock().unwrap().len(), 9);
+
+        drop(root);
+        harness.layout(&mut Pod::new(LifeProbe(LifeLog::default(), vec![])), &bc);
+        let log = log.lock().unwrap();
+        let mut ids: Vec<_> = log.iter().map(|(id, _)| *id).collect();
+        ids.dedup();
+        for id in ids {
+            let events: Vec<_> = log
+                .iter()
+                .filter(|(logged, _)| *logged == id)
+                .map(|(_, added)| *added)
+                .collect();
+            assert_eq!(events, [true, false]);
+        }
+    }
+
+    #[test]
+    fn widget_mut_marks_its_changes_on_the_pod() {
+        let mut harness = Harness::new();
+        let mut pod = Pod::new(Label::new("Before".into()));
+        harness.layout(&mut pod, &BoxConstraints::UNBOUNDED);
+        assert!(pod.pending_changes().is_empty());
+
+        let mut label = pod.as_mut::<Label>();
+        label.apply(|label| label.set_text_color(Color::RED));
+        assert_eq!(label.changes(), ChangeFlags::DESCENDANT_REQUESTED_PAINT);
+        label.apply(|label| label.set_text("After".into()));
+        assert!(label.changes().contains(ChangeFlags::LAYOUT));
+        assert_eq!(label.text(), "After");
+        assert!(pod.state.flags.contains(PodFlags::REQUEST_PAINT));
+        assert!(pod.state.flags.contains(PodFlags::REQUEST_LAYOUT));
+    }
+
+    type PassLog = Arc<Mutex<Vec<&'static str>>>;
+
+    /// Requests paint when pressed and update when released, logging the passes it sees.
+    struct Requester(PassLog);
+
+    impl Widget for Requester {
+        fn event(&mut self, cx: &mut EventCx, event: &Event) {
+            match event {
+                Event::MouseDown(_) => cx.request_paint(),
+                Event::MouseUp(_) => cx.request_update(),
+                _ => (),
+            }
+        }
+
+        fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+        fn update(&mut self, _cx: &mut UpdateCx) {
+            self.0.lock().unwrap().push("update");
+        }
+
+        fn layout(&mut self, _cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            self.0.lock().unwrap().push("layout");
+            Size::new(10.0, 10.0)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {
+            self.0.lock().unwrap().push("paint");
+        }
+    }
+
+    #[test]
+    fn event_requests_run_only_their_passes() {
+        let log = PassLog::default();
+        let children = vec![
+            Pod::new(Requester(log.clone())),
+            Pod::new(Requester(log.clone())),
+        ];
+        let mut root = Pod::new(LinearLayout::new(children, 0.0, Axis::Horizontal));
+        let mut harness = Harness::new();
+        let bc = BoxConstraints::UNBOUNDED;
+        harness.frame(&mut root, &bc);
+        log.lock().unwrap().clear();
+
+        harness.event(&mut root, Event::MouseDown(mouse((5.0, 5.0))));
+        assert_eq!(harness.frame(&mut root, &bc), ChangeFlags::PAINT);
+        assert_eq!(*log.lock().unwrap(), ["paint"]);
+        log.lock().unwrap().clear();
+
+        // Requests of several widgets wait for the same frame.
+        harness.event(&mut root, Event::MouseUp(mouse((5.0, 5.0))));
+        harness.event(&mut root, Event::MouseDown(mouse((15.0, 5.0))));
+        harness.event(&mut root, Event::MouseUp(mouse((15.0, 5.0))));
+        assert_eq!(
+            harness.frame(&mut root, &bc),
+            ChangeFlags::UPDATE | ChangeFlags::PAINT
+        );
+        assert_eq!(*log.lock().unwrap(), ["update", "update", "paint"]);
+        assert!(root.pending_changes().is_empty());
+    }
+}
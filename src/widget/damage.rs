@@ -0,0 +1,237 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The parts of the window which have to be presented again.
+
+use vello::kurbo::{Rect, Vec2};
+
+/// How many separate rectangles the damage keeps before it covers the whole window.
+const MAX_RECTS: usize = 8;
+
+/// The parts of the window painted anew since the damage was last taken, in window coordinates.
+///
+/// Overlapping rectangles are merged, and too many separate ones turn into damage of the whole
+/// window, so that presenting the damage never costs more than presenting everything.
+///
+/// Content which scrolled is not painted anew, except for the strip the scroll exposed. A
+/// presenter moves what it presented before by the [scrolls](Damage::scrolls), and then
+/// presents the damaged rectangles.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Damage {
+    rects: Vec<Rect>,
+    scrolls: Vec<(Rect, Vec2)>,
+    is_full: bool,
+}
+
+impl Damage {
+    /// Whether nothing was painted anew or moved.
+    pub fn is_empty(&self) -> bool {
+        !self.is_full && self.rects.is_empty() && self.scrolls.is_empty()
+    }
+
+    /// Whether the whole window has to be presented again.
+    pub fn is_full(&self) -> bool {
+        self.is_full
+    }
+
+    /// The damaged rectangles, which don't overlap each other. Empty for full damage.
+    pub fn rects(&self) -> &[Rect] {
+        &self.rects
+    }
+
+    /// The rectangles whose presented content moves by an offset, in the order they scrolled.
+    /// Empty for full damage.
+    pub fn scrolls(&self) -> &[(Rect, Vec2)] {
+        &self.scrolls
+    }
+
+    /// The smallest rectangle containing all damaged ones, `None` for no or full damage.
+    pub fn bounds(&self) -> Option<Rect> {
+        self.rects
+            .iter()
+            .copied()
+            .reduce(|bounds, rect| bounds.union(rect))
+    }
+
+    /// Whether something within `rect` has to be presented again.
+    pub fn intersects(&self, rect: Rect) -> bool {
+        self.is_full || self.rects.iter().any(|damaged| overlaps(*damaged, rect))
+    }
+
+    pub(crate) fn add(&mut self, mut rect: Rect) {
+        if self.is_full || rect.area() <= 0.0 {
+            return;
+        }
+        // A merged rectangle may overlap others it didn't before.
+        while let Some(index) = self
+            .rects
+            .iter()
+            .position(|damaged| overlaps(*damaged, rect))
+        {
+            rect = rect.union(self.rects.swap_remove(index));
+        }
+        if self.rects.len() == MAX_RECTS {
+            self.add_full();
+        } else {
+            self.rects.push(rect);
+        }
+    }
+
+    /// Moves the content within `rect` by `delta`, and damages the part of `rect` the move
+    /// exposes.
+    pub(crate) fn add_scroll(&mut self, rect: Rect, delta: Vec2) {
+        if self.is_full || rect.area() <= 0.0 || delta == Vec2::ZERO {
+            return;
+        }
+        // Damage which wasn't presented yet moves along with the content.
+        let moved: Vec<Rect> = self
+            .rects
+            .iter()
+            .map(|damaged| (damaged.intersect(rect) + delta).intersect(rect))
+            .collect();
+        self.scrolls.push((rect, delta));
+        let kept = (rect + delta).intersect(rect);
+        let exposed = [
+            Rect::new(rect.x0, rect.y0, rect.x1, kept.y0),
+            Rect::new(rect.x0, kept.y1, rect.x1, rect.y1),
+            Rect::new(rect.x0, kept.y0, kept.x0, kept.y1),
+            Rect::new(kept.x1, kept.y0, rect.x1, kept.y1),
+        ];
+        for damaged in moved.into_iter().chain(exposed) {
+            self.add(damaged.intersect(rect));
+        }
+    }
+
+    pub(crate) fn add_full(&mut self) {
+        self.rects.clear();
+        self.scrolls.clear();
+        self.is_full = true;
+    }
+}
+
+fn overlaps(a: Rect, b: Rect) -> bool {
+    a.intersect(b).area() > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{mouse, Harness, Leaf};
+    use crate::widget::{BoxConstraints, Button, ChangeFlags, Event, LinearLayout, Pod};
+    use crate::Axis;
+    use test_log::test;
+    use vello::kurbo::Size;
+
+    #[test]
+    fn overlapping_rects_merge() {
+        let mut damage = Damage::default();
+        damage.add(Rect::new(0.0, 0.0, 10.0, 10.0));
+        damage.add(Rect::new(20.0, 0.0, 30.0, 10.0));
+        assert_eq!(damage.rects().len(), 2);
+
+        // Bridges both, so all three become one.
+        damage.add(Rect::new(5.0, 5.0, 25.0, 8.0));
+        assert_eq!(damage.rects(), [Rect::new(0.0, 0.0, 30.0, 10.0)]);
+        assert!(!damage.intersects(Rect::new(0.0, 10.0, 30.0, 20.0)));
+    }
+
+    #[test]
+    fn scrolls_damage_the_exposed_strip_and_move_earlier_damage() {
+        let view = Rect::new(0.0, 0.0, 100.0, 200.0);
+        let mut damage = Damage::default();
+        damage.add(Rect::new(0.0, 50.0, 100.0, 60.0));
+        damage.add_scroll(view, Vec2::new(0.0, -30.0));
+
+        assert_eq!(damage.scrolls(), [(view, Vec2::new(0.0, -30.0))]);
+        assert_eq!(
+            damage.rects(),
+            [
+                Rect::new(0.0, 50.0, 100.0, 60.0),
+                Rect::new(0.0, 20.0, 100.0, 30.0),
+                Rect::new(0.0, 170.0, 100.0, 200.0),
+            ]
+        );
+
+        // Scrolling further than the view is tall exposes all of it.
+        let mut damage = Damage::default();
+        damage.add_scroll(view, Vec2::new(0.0, 300.0));
+        assert_eq!(damage.rects(), [view]);
+    }
+
+    #[test]
+    fn too_many_rects_damage_everything() {
+        let mut damage = Damage::default();
+        for index in 0..MAX_RECTS {
+            let x = index as f64 * 20.0;
+            damage.add(Rect::new(x, 0.0, x + 10.0, 10.0));
+        }
+        assert!(!damage.is_full());
+        assert_eq!(
+            damage.bounds(),
+            Some(Rect::new(0.0, 0.0, MAX_RECTS as f64 * 20.0 - 10.0, 10.0))
+        );
+
+        damage.add(Rect::new(0.0, 100.0, 10.0, 110.0));
+        assert!(damage.is_full());
+        assert!(damage.rects().is_empty());
+        assert!(damage.intersects(Rect::new(500.0, 500.0, 510.0, 510.0)));
+    }
+
+    #[test]
+    fn hot_button_damages_only_itself() {
+        let mut harness = Harness::new();
+        let button = Button::new(&vec![], Pod::new(Leaf::new(40.0, 20.0)));
+        let row = vec![Pod::new(Leaf::new(30.0, 20.0)), Pod::new(button)];
+        let column = vec![
+            Pod::new(Leaf::new(100.0, 20.0)),
+            Pod::new(LinearLayout::new(row, 10.0, Axis::Horizontal)),
+        ];
+        let mut root = Pod::new(LinearLayout::new(column, 10.0, Axis::Vertical));
+        let bc = BoxConstraints::tight(Size::new(400.0, 300.0));
+        harness.frame(&mut root, &bc);
+        let _ = std::mem::take(&mut harness.window_state.damage);
+
+        let button = &root.downcast_mut::<LinearLayout>().unwrap().children[1]
+            .downcast_mut::<LinearLayout>()
+            .unwrap()
+            .children[1];
+        let rect = Rect::from_origin_size(button.state.window_origin(), button.state.size);
+        assert!(rect.origin().x > 0.0 && rect.origin().y > 0.0);
+        harness.event(&mut root, Event::MouseMove(mouse(rect.center())));
+        harness.frame(&mut root, &bc);
+        assert_eq!(harness.window_state.damage.rects(), [rect]);
+    }
+
+    #[test]
+    fn marked_changes_damage_only_the_changed_widget() {
+        let mut harness = Harness::new();
+        let children = vec![
+            Pod::new(Leaf::new(100.0, 20.0)),
+            Pod::new(Leaf::new(100.0, 20.0)),
+        ];
+        let mut root = Pod::new(LinearLayout::new(children, 10.0, Axis::Vertical));
+        let bc = BoxConstraints::tight(Size::new(400.0, 300.0));
+        harness.frame(&mut root, &bc);
+        let _ = std::mem::take(&mut harness.window_state.damage);
+
+        // Like a view, which marks the pod of its element and hands the rest to its parent.
+        let child = &mut root.downcast_mut::<LinearLayout>().unwrap().children[1];
+        let changes = child.mark(ChangeFlags::PAINT);
+        let rect = Rect::from_origin_size(child.state.window_origin(), child.state.size);
+        assert_eq!(changes, ChangeFlags::DESCENDANT_REQUESTED_PAINT);
+        let _ = root.mark(changes);
+        assert_eq!(harness.frame(&mut root, &bc), ChangeFlags::PAINT);
+        assert_eq!(harness.window_state.damage.rects(), [rect]);
+    }
+}
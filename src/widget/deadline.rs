@@ -0,0 +1,117 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use vello::{
+    kurbo::{Point, Size},
+    SceneBuilder,
+};
+
+use crate::{IdPath, Message};
+
+use super::{
+    AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx,
+    PaintCx, Pod, TimerToken, UpdateCx, Widget,
+};
+
+/// Passes everything to its child, and tells its view when a deadline set by it has passed.
+///
+/// Setting a new deadline replaces the running one, which is then never sent. The timer starts
+/// in the update following [`set_deadline`](Deadline::set_deadline), and is cancelled when the
+/// widget leaves the tree.
+pub struct Deadline {
+    id_path: IdPath,
+    child: Pod,
+    timer: Option<TimerToken>,
+    /// The delay for the timer to start in the next update.
+    pending: Option<Duration>,
+}
+
+impl Deadline {
+    pub fn new(id_path: &IdPath, child: Pod) -> Self {
+        Deadline {
+            id_path: id_path.clone(),
+            child,
+            timer: None,
+            pending: None,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    /// Sends the message `delay` after the next update.
+    pub fn set_deadline(&mut self, delay: Duration) -> ChangeFlags {
+        self.pending = Some(delay);
+        ChangeFlags::UPDATE
+    }
+
+    /// Whether a deadline is pending.
+    pub fn is_armed(&self) -> bool {
+        self.timer.is_some() || self.pending.is_some()
+    }
+}
+
+impl Widget for Deadline {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        if let Event::Timer(token) = event {
+            if self.timer == Some(*token) {
+                self.timer = None;
+                cx.add_message(Message::new(self.id_path.clone(), ()));
+                return;
+            }
+        }
+        self.child.event(cx, event);
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        if let Some(delay) = self.pending.take() {
+            if let Some(timer) = self.timer.take() {
+                cx.cancel_timer(timer);
+            }
+            self.timer = Some(cx.request_timer(delay));
+        }
+        self.child.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
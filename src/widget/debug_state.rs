@@ -0,0 +1,124 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Descriptions of widget trees for debugging.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[cfg(feature = "recording")]
+use serde::Serialize;
+
+/// A description of a widget and its descendants, see [`Widget::debug_state`] and
+/// [`Pod::tree_debug_state`].
+///
+/// It formats as an indented tree with a line per widget, and serializes to JSON with the
+/// `recording` feature.
+///
+/// [`Widget::debug_state`]: super::Widget::debug_state
+/// [`Pod::tree_debug_state`]: super::Pod::tree_debug_state
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "recording", derive(Serialize))]
+pub struct DebugState {
+    /// The short type name of the widget.
+    pub display_name: String,
+    /// Values describing the widget, like its text or axis, by name.
+    pub values: BTreeMap<String, String>,
+    /// The descriptions of the children, which the pod fills in.
+    pub children: Vec<DebugState>,
+}
+
+impl DebugState {
+    pub fn new(display_name: impl Into<String>) -> Self {
+        DebugState {
+            display_name: display_name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds a value describing the widget.
+    pub fn with_value(mut self, name: &str, value: impl ToString) -> Self {
+        self.values.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// The description as pretty printed JSON.
+    #[cfg(feature = "recording")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("debug states are serializable")
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}{}", "", self.display_name, indent = depth * 2)?;
+        for (name, value) in &self.values {
+            write!(f, " {name}={value}")?;
+        }
+        writeln!(f)?;
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DebugState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{mouse, Harness, Leaf};
+    use crate::widget::{BoxConstraints, Button, Event, Label, LinearLayout, Pod};
+    use crate::Axis;
+    use test_log::test;
+
+    #[test]
+    fn tree_dump_of_a_small_column() {
+        let mut harness = Harness::new();
+        let button = Button::new(&vec![], Pod::new(Leaf::new(40.0, 10.0)));
+        let children = vec![Pod::new(Leaf::new(100.0, 20.0)), Pod::new(button)];
+        let mut root = Pod::new(LinearLayout::new(children, 4.0, Axis::Vertical));
+        let bc = BoxConstraints::UNBOUNDED;
+        harness.frame(&mut root, &bc);
+        harness.event(&mut root, Event::MouseMove(mouse((10.0, 30.0))));
+        harness.frame(&mut root, &bc);
+
+        let state = root.tree_debug_state();
+        assert_eq!(
+            state.to_string(),
+            "\
+LinearLayout axis=Vertical children=2 flags=hot origin=0,0 size=100x48 spacing=4
+  Leaf origin=0,0 size=100x20
+  Button disabled=false flags=hot origin=0,24 size=56x24
+    Leaf origin=8,7 size=40x10
+"
+        );
+
+        #[cfg(feature = "recording")]
+        {
+            let json: serde_json::Value = serde_json::from_str(&state.to_json()).unwrap();
+            assert_eq!(json["children"][1]["display_name"], "Button");
+            assert_eq!(json["children"][1]["values"]["flags"], "hot");
+        }
+    }
+
+    #[test]
+    fn labels_show_their_text() {
+        let state = Pod::new(Label::new("Hello".into())).tree_debug_state();
+        assert_eq!(state.values["text"], "\"Hello\"");
+    }
+}
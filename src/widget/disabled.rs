@@ -0,0 +1,248 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vello::{
+    kurbo::{Point, Size},
+    SceneBuilder,
+};
+
+use super::{
+    AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx,
+    PaintCx, Pod, UpdateCx, Widget,
+};
+
+/// Disables the widgets below it.
+///
+/// Disabled widgets get no pointer or keyboard input, are left out of the focus chain and are
+/// reported as disabled to accessibility, widgets check `is_disabled()` on their contexts to
+/// paint themselves muted. A scope which is not disabled doesn't enable widgets below a scope
+/// which is. When the subtree becomes disabled or enabled, it receives
+/// [`LifeCycle::DisabledChanged`].
+pub struct DisabledScope {
+    child: Pod,
+    disabled: bool,
+    /// Whether the subtree is disabled, as it was last told.
+    is_disabled: Option<bool>,
+}
+
+impl DisabledScope {
+    pub fn new(child: Pod, disabled: bool) -> Self {
+        DisabledScope {
+            child,
+            disabled,
+            is_disabled: None,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool) -> ChangeFlags {
+        if self.disabled == disabled {
+            return ChangeFlags::empty();
+        }
+        self.disabled = disabled;
+        ChangeFlags::UPDATE
+    }
+
+    /// Tells the subtree when it became disabled or enabled, as this scope or one above changed.
+    fn notify(&mut self, cx: &mut LifeCycleCx) {
+        let is_disabled = cx.cx_state.disabled;
+        let previous = self.is_disabled.replace(is_disabled);
+        if previous.is_some() && previous != Some(is_disabled) {
+            self.child
+                .lifecycle(cx, &LifeCycle::DisabledChanged(is_disabled));
+        }
+    }
+}
+
+impl Widget for DisabledScope {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        let outer = cx.cx_state.disabled;
+        cx.cx_state.disabled |= self.disabled;
+        self.child.event(cx, event);
+        cx.cx_state.disabled = outer;
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        let outer = cx.cx_state.disabled;
+        cx.cx_state.disabled |= self.disabled;
+        if let LifeCycle::DisabledChanged(_) = event {
+            self.notify(cx);
+        } else {
+            self.child.lifecycle(cx, event);
+        }
+        cx.cx_state.disabled = outer;
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        let outer = cx.cx_state.disabled;
+        cx.cx_state.disabled |= self.disabled;
+        let mut lifecycle_cx = LifeCycleCx::new(cx.cx_state, cx.widget_state);
+        self.notify(&mut lifecycle_cx);
+        self.child.update(cx);
+        cx.cx_state.disabled = outer;
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let outer = cx.cx_state.disabled;
+        cx.cx_state.disabled |= self.disabled;
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        cx.cx_state.disabled = outer;
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        let outer = cx.cx_state.disabled;
+        cx.cx_state.disabled |= self.disabled;
+        self.child.accessibility(cx);
+        cx.cx_state.disabled = outer;
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let outer = cx.cx_state.disabled;
+        cx.cx_state.disabled |= self.disabled;
+        self.child.paint(cx, builder);
+        cx.cx_state.disabled = outer;
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{key_down, mouse, Harness, Leaf};
+    use crate::widget::{Button, LinearLayout};
+    use crate::{Axis, IdPath};
+    use glazier::KbKey;
+    use test_log::test;
+    use xilem_core::Id;
+
+    const WINDOW: Size = Size::new(400.0, 300.0);
+
+    fn button() -> (IdPath, Pod) {
+        let id_path = vec![Id::next()];
+        let button = Button::new(&id_path, Pod::new(Leaf::new(60.0, 20.0)));
+        (id_path, Pod::new(button))
+    }
+
+    /// Three buttons 24px high below each other, the middle one in a scope.
+    fn column(scope: Pod) -> Pod {
+        let children = vec![button().1, scope, button().1];
+        Pod::new(LinearLayout::new(children, 0.0, Axis::Vertical))
+    }
+
+    fn child(root: &mut Pod, index: usize) -> &mut Pod {
+        &mut root.downcast_mut::<LinearLayout>().unwrap().children[index]
+    }
+
+    fn click(harness: &mut Harness, root: &mut Pod, y: f64) {
+        harness.event(root, Event::MouseDown(mouse((10.0, y))));
+        harness.event(root, Event::MouseUp(mouse((10.0, y))));
+    }
+
+    fn set_disabled(harness: &mut Harness, root: &mut Pod, disabled: bool) {
+        let scope = child(root, 1);
+        let changes = scope
+            .downcast_mut::<DisabledScope>()
+            .unwrap()
+            .set_disabled(disabled);
+        let changes = scope.mark(changes);
+        let _ = root.mark(changes);
+        harness.update(root);
+    }
+
+    #[test]
+    fn disabled_button_ignores_clicks_until_enabled() {
+        let mut harness = Harness::new();
+        let (id_path, button) = button();
+        let mut root = column(Pod::new(DisabledScope::new(button, true)));
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        harness.update(&mut root);
+        click(&mut harness, &mut root, 30.0);
+        assert!(harness.take_messages().is_empty());
+
+        // The button is enabled again without being replaced.
+        set_disabled(&mut harness, &mut root, false);
+        click(&mut harness, &mut root, 30.0);
+        let messages = harness.take_messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id_path, id_path);
+    }
+
+    #[test]
+    fn tab_skips_disabled_widgets() {
+        let mut harness = Harness::new();
+        let mut root = column(Pod::new(DisabledScope::new(button().1, true)));
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        harness.update(&mut root);
+        let ids = [0, 1, 2].map(|index| child(&mut root, index).id());
+
+        harness.event(&mut root, key_down(KbKey::Tab));
+        assert_eq!(harness.window_state.focus, Some(ids[0]));
+        harness.event(&mut root, key_down(KbKey::Tab));
+        assert_eq!(harness.window_state.focus, Some(ids[2]));
+    }
+
+    #[test]
+    fn disabling_removes_focus() {
+        let mut harness = Harness::new();
+        let (_, button) = button();
+        let mut root = column(Pod::new(DisabledScope::new(button, false)));
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        harness.update(&mut root);
+        click(&mut harness, &mut root, 30.0);
+        assert!(harness.window_state.focus.is_some());
+
+        set_disabled(&mut harness, &mut root, true);
+        assert_eq!(harness.window_state.focus, None);
+        let update = harness.accessibility(&mut root);
+        let scope = child(&mut root, 1);
+        let button_id: accesskit::NodeId = scope
+            .downcast_mut::<DisabledScope>()
+            .unwrap()
+            .child_mut()
+            .id()
+            .into();
+        let (_, node) = update
+            .nodes
+            .iter()
+            .find(|(id, _)| *id == button_id)
+            .unwrap();
+        assert!(node.is_disabled());
+    }
+
+    #[test]
+    fn nested_scope_does_not_enable() {
+        let mut harness = Harness::new();
+        let inner = Pod::new(DisabledScope::new(button().1, false));
+        let mut root = column(Pod::new(DisabledScope::new(inner, true)));
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        harness.update(&mut root);
+        click(&mut harness, &mut root, 30.0);
+        assert!(harness.take_messages().is_empty());
+        assert!(harness.window_state.focus.is_none());
+    }
+}
@@ -0,0 +1,468 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dragging values between widgets, and dropping files from other applications.
+
+use std::any::{Any, TypeId};
+use std::path::PathBuf;
+
+use glazier::MouseButton;
+use vello::{
+    kurbo::{Point, Size},
+    peniko::Color,
+    SceneBuilder,
+};
+
+use crate::{IdPath, Message};
+
+use super::gesture::DRAG_SLOP;
+use super::{
+    piet_scene_helpers, AccessCx, BoxConstraints, ChangeFlags, DragEvent, Event, EventCx, LayoutCx,
+    LifeCycle, LifeCycleCx, OverlayHandle, PaintCx, Placement, Pod, UpdateCx, Widget,
+};
+
+/// The message a [`DragSource`] sends when its drag ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DragEnded {
+    /// Whether a drop target took the payload, rather than the drag being dropped elsewhere or
+    /// cancelled.
+    pub accepted: bool,
+}
+
+/// The message a [`DropTarget`] sends to its view.
+pub enum DropMessage {
+    /// A drag the target accepts moved onto it, with `true`, or away from it, with `false`.
+    Hover(bool),
+    /// The payload was dropped on the target, at the given position in its coordinates.
+    Dropped(Box<dyn Any + Send>, Point),
+}
+
+enum DragState {
+    Idle,
+    /// Pressed at the point, but not moved far enough for a drag yet.
+    Pressed(Point),
+    /// Dragging, with the point of the child which was grabbed.
+    Dragging(Point),
+    /// The drag ended before the button was released.
+    Ended,
+}
+
+/// Lets the user drag a child to a [`DropTarget`].
+///
+/// Once the pointer moved a few pixels while pressed, a drag with a new payload starts. A
+/// translucent image of the size of the child follows the pointer in an overlay, which needs
+/// an [`OverlayHost`](super::OverlayHost) above it. When the drag ends, the view at `id_path`
+/// receives [`DragEnded`].
+pub struct DragSource {
+    id_path: IdPath,
+    child: Pod,
+    payload: Box<dyn Fn() -> Box<dyn Any + Send>>,
+    image: OverlayHandle,
+    state: DragState,
+}
+
+impl DragSource {
+    pub fn new(
+        id_path: &IdPath,
+        child: Pod,
+        payload: impl Fn() -> Box<dyn Any + Send> + 'static,
+    ) -> Self {
+        let image = OverlayHandle::new(DragImage { size: Size::ZERO }, Placement::At(Point::ZERO));
+        image.set_interactive(false);
+        DragSource {
+            id_path: id_path.clone(),
+            child,
+            payload: Box::new(payload),
+            image,
+            state: DragState::Idle,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    /// Sets how the payload of the next drag is made.
+    pub fn set_payload(&mut self, payload: impl Fn() -> Box<dyn Any + Send> + 'static) {
+        self.payload = Box::new(payload);
+    }
+
+    /// Returns whether the child is being dragged.
+    pub fn is_dragging(&self) -> bool {
+        matches!(self.state, DragState::Dragging(_))
+    }
+
+    /// Moves the image so that the grabbed point is under the pointer at `window_pos`.
+    fn move_image(&self, cx: &mut EventCx, window_pos: Point, grab: Point) {
+        let origin = window_pos - grab.to_vec2();
+        self.image.set_placement(Placement::At(origin));
+        cx.request_layout();
+    }
+}
+
+impl Widget for DragSource {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.child.event(cx, event);
+        match event {
+            // A press a child handled, like that of a button, is no drag.
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left && !cx.is_handled() => {
+                self.state = DragState::Pressed(mouse.pos);
+                cx.capture_pointer();
+                cx.set_handled(true);
+            }
+            Event::MouseMove(mouse) => match self.state {
+                DragState::Pressed(grab) if (mouse.pos - grab).hypot() > DRAG_SLOP => {
+                    cx.start_drag((self.payload)());
+                    self.state = DragState::Dragging(grab);
+                    let size = cx.size();
+                    self.image.with_pod(|image| {
+                        image.expect_mut::<DragImage>().size = size;
+                        image.mark(ChangeFlags::LAYOUT | ChangeFlags::PAINT);
+                    });
+                    self.move_image(cx, mouse.window_pos, grab);
+                    cx.show_overlay(&self.image);
+                    cx.set_handled(true);
+                }
+                DragState::Dragging(grab) => {
+                    self.move_image(cx, mouse.window_pos, grab);
+                    cx.set_handled(true);
+                }
+                _ => (),
+            },
+            Event::MouseUp(mouse) if mouse.button == MouseButton::Left => {
+                if !matches!(self.state, DragState::Idle) {
+                    self.state = DragState::Idle;
+                    cx.release_pointer();
+                    cx.set_handled(true);
+                }
+            }
+            Event::DragEnded { source, accepted } if *source == cx.widget_id() => {
+                if self.is_dragging() {
+                    self.state = DragState::Ended;
+                }
+                cx.hide_overlay(&self.image);
+                let ended = DragEnded {
+                    accepted: *accepted,
+                };
+                cx.add_message(Message::new(self.id_path.clone(), ended));
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        if let LifeCycle::PointerCaptureLost = event {
+            // A drag which is still going on ends with the release or the lost window focus.
+            self.state = DragState::Idle;
+            return;
+        }
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.child.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+/// The translucent box following the pointer during a drag.
+struct DragImage {
+    size: Size,
+}
+
+impl Widget for DragImage {
+    fn event(&mut self, _cx: &mut EventCx, _event: &Event) {}
+
+    fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+    fn update(&mut self, _cx: &mut UpdateCx) {}
+
+    fn layout(&mut self, _cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        bc.constrain(self.size)
+    }
+
+    fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let image = cx.size().to_rounded_rect(4.0);
+        piet_scene_helpers::fill_color(builder, &image, Color::rgba8(0x80, 0x80, 0x80, 0x60));
+        piet_scene_helpers::stroke(builder, &image, Color::rgba8(0xc0, 0xc0, 0xc0, 0xa0), 1.0);
+    }
+}
+
+/// Accepts drops of one type of payload on a child, or of files from other applications when
+/// the type is `Vec<PathBuf>`.
+///
+/// The view at `id_path` receives a [`DropMessage`] when an accepted drag moves onto or away
+/// from the child, and when it is dropped. Drags of other types pass by, and dropping them here
+/// doesn't take their payload, so their source learns that they were rejected.
+pub struct DropTarget {
+    id_path: IdPath,
+    child: Pod,
+    accepts: TypeId,
+    is_hovered: bool,
+}
+
+impl DropTarget {
+    pub fn new(id_path: &IdPath, child: Pod, accepts: TypeId) -> Self {
+        DropTarget {
+            id_path: id_path.clone(),
+            child,
+            accepts,
+            is_hovered: false,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    /// Returns whether a drag the target accepts is over it.
+    pub fn is_hovered(&self) -> bool {
+        self.is_hovered
+    }
+
+    fn accepts(&self, cx: &EventCx, drag: &DragEvent) -> bool {
+        if !drag.paths.is_empty() {
+            return self.accepts == TypeId::of::<Vec<PathBuf>>();
+        }
+        cx.drag_payload()
+            .is_some_and(|payload| (*payload).type_id() == self.accepts)
+    }
+
+    fn set_hovered(&mut self, cx: &mut EventCx, is_hovered: bool) {
+        if self.is_hovered != is_hovered {
+            self.is_hovered = is_hovered;
+            let message = DropMessage::Hover(is_hovered);
+            cx.add_message(Message::new(self.id_path.clone(), message));
+        }
+    }
+}
+
+impl Widget for DropTarget {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.child.event(cx, event);
+        match event {
+            Event::DragEnter(drag) | Event::DragOver(drag) => {
+                let is_hovered = cx.is_drag_over() && self.accepts(cx, drag);
+                self.set_hovered(cx, is_hovered);
+            }
+            Event::DragLeave() => self.set_hovered(cx, false),
+            Event::Drop(drag) => {
+                // A target inside of this one may have taken the drop already.
+                let takes = !cx.is_handled() && cx.is_drag_over() && self.accepts(cx, drag);
+                self.set_hovered(cx, false);
+                if !takes {
+                    return;
+                }
+                let payload = if drag.paths.is_empty() {
+                    cx.take_drag_payload().unwrap()
+                } else {
+                    Box::new(drag.paths.clone())
+                };
+                let message = DropMessage::Dropped(payload, drag.pos);
+                cx.add_message(Message::new(self.id_path.clone(), message));
+                cx.set_handled(true);
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.child.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Axis;
+    use crate::widget::test_util::{key_down, mouse, Harness, Leaf};
+    use crate::widget::{LinearLayout, OverlayHost};
+    use glazier::KbKey;
+    use test_log::test;
+    use xilem_core::Id;
+
+    const WINDOW: Size = Size::new(200.0, 100.0);
+
+    /// A source dragging `7_u32` at (0, 0)..(50, 20) and a target accepting `accepts` at
+    /// (50, 0)..(100, 20).
+    fn setup(harness: &mut Harness, accepts: TypeId) -> Pod {
+        let leaf = || Pod::new(Leaf::new(50.0, 20.0));
+        let source = DragSource::new(&vec![Id::next()], leaf(), || Box::new(7_u32));
+        let target = DropTarget::new(&vec![Id::next()], leaf(), accepts);
+        let row = LinearLayout::new(
+            vec![Pod::new(source), Pod::new(target)],
+            0.0,
+            Axis::Horizontal,
+        );
+        let mut root = Pod::new(OverlayHost::new(Pod::new(row)));
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        root
+    }
+
+    fn messages(harness: &mut Harness) -> Vec<String> {
+        harness
+            .take_messages()
+            .into_iter()
+            .map(|message| match message.body.downcast::<DropMessage>() {
+                Ok(drop) => match *drop {
+                    DropMessage::Hover(is_hovered) => format!("hover {is_hovered}"),
+                    DropMessage::Dropped(payload, pos) => match payload.downcast::<u32>() {
+                        Ok(value) => format!("dropped {value} at {pos:?}"),
+                        Err(payload) => {
+                            let paths = payload.downcast::<Vec<PathBuf>>().unwrap();
+                            format!("dropped {paths:?} at {pos:?}")
+                        }
+                    },
+                },
+                Err(body) => format!("ended {}", body.downcast::<DragEnded>().unwrap().accepted),
+            })
+            .collect()
+    }
+
+    fn overlay_count(root: &mut Pod) -> usize {
+        root.downcast_mut::<OverlayHost>().unwrap().overlay_count()
+    }
+
+    #[test]
+    fn dragging_a_payload_onto_a_target() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, TypeId::of::<u32>());
+
+        harness.event(&mut root, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut root, Event::MouseMove(mouse((12.0, 10.0))));
+        assert_eq!(overlay_count(&mut root), 0);
+        harness.event(&mut root, Event::MouseMove(mouse((20.0, 10.0))));
+        // The image follows the pointer.
+        assert_eq!(overlay_count(&mut root), 1);
+        harness.event(&mut root, Event::MouseMove(mouse((70.0, 10.0))));
+        harness.event(&mut root, Event::MouseUp(mouse((70.0, 10.0))));
+        assert_eq!(
+            messages(&mut harness),
+            [
+                "hover true",
+                "hover false",
+                "dropped 7 at (20.0, 10.0)",
+                "ended true"
+            ]
+        );
+        assert_eq!(overlay_count(&mut root), 0);
+        assert!(harness.window_state.drag.is_none());
+    }
+
+    #[test]
+    fn rejected_and_cancelled_drags_notify_the_source() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, TypeId::of::<String>());
+
+        harness.event(&mut root, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut root, Event::MouseMove(mouse((70.0, 10.0))));
+        harness.event(&mut root, Event::MouseUp(mouse((70.0, 10.0))));
+        assert_eq!(messages(&mut harness), ["ended false"]);
+
+        harness.event(&mut root, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut root, Event::MouseMove(mouse((30.0, 10.0))));
+        harness.event(&mut root, key_down(KbKey::Escape));
+        assert_eq!(messages(&mut harness), ["ended false"]);
+        assert_eq!(overlay_count(&mut root), 0);
+        // The press stays cancelled until it's released.
+        harness.event(&mut root, Event::MouseMove(mouse((40.0, 10.0))));
+        harness.event(&mut root, Event::MouseUp(mouse((40.0, 10.0))));
+        assert!(messages(&mut harness).is_empty());
+        assert!(harness.window_state.drag.is_none());
+    }
+
+    #[test]
+    fn files_dropped_from_another_application() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, TypeId::of::<Vec<PathBuf>>());
+        let drag = |pos: (f64, f64)| DragEvent {
+            pos: pos.into(),
+            window_pos: pos.into(),
+            paths: vec![PathBuf::from("notes.txt")],
+        };
+
+        harness.event(&mut root, Event::DragEnter(drag((70.0, 10.0))));
+        harness.event(&mut root, Event::DragOver(drag((10.0, 10.0))));
+        harness.event(&mut root, Event::DragOver(drag((60.0, 10.0))));
+        harness.event(&mut root, Event::Drop(drag((60.0, 10.0))));
+        assert_eq!(
+            messages(&mut harness),
+            [
+                "hover true",
+                "hover false",
+                "hover true",
+                "hover false",
+                "dropped [\"notes.txt\"] at (10.0, 10.0)",
+            ]
+        );
+
+        // Leaving the window ends the hover.
+        harness.event(&mut root, Event::DragEnter(drag((70.0, 10.0))));
+        harness.event(&mut root, Event::DragLeave());
+        assert_eq!(messages(&mut harness), ["hover true", "hover false"]);
+    }
+}
@@ -0,0 +1,875 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, Instant};
+
+use glazier::{KbKey, KeyEvent};
+use vello::{
+    kurbo::{Affine, BezPath, Insets, Point, Rect, Size},
+    peniko::{Color, Mix},
+    SceneBuilder,
+};
+
+use crate::{IdPath, Message};
+
+use super::{
+    piet_scene_helpers::{self, UnitPoint},
+    AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx,
+    OverlayHandle, PaintCx, Placement, Pod, UpdateCx, Widget,
+};
+
+/// A control for choosing one of several options, showing the chosen one while closed.
+///
+/// A click, Enter, Space or an arrow key opens a list of the options below the control, or above
+/// it if there is no room. In the open list the arrow keys, Home, End and the page keys move the
+/// highlight, Enter or Space choose the highlighted option and Escape closes the list. Typing the
+/// start of an option label highlights it, or chooses it while the list is closed.
+///
+/// The chosen index is sent as a `usize` message to the view at `id_path`. Keyboard focus stays
+/// on the control while the list is open.
+pub struct Dropdown {
+    id_path: IdPath,
+    display: Pod,
+    selected: usize,
+    /// The labels of the options, used for type-ahead and accessibility.
+    labels: Vec<String>,
+    list: OverlayHandle,
+    is_open: bool,
+    type_ahead: String,
+    last_type_ahead: Option<Instant>,
+}
+
+const INSETS: Insets = Insets::uniform_xy(8.0, 2.0);
+const MIN_HEIGHT: f64 = 24.0;
+const ARROW_WIDTH: f64 = 20.0;
+const ROW_HEIGHT: f64 = 24.0;
+/// The number of rows the open list shows at once, longer lists scroll.
+const MAX_VISIBLE_ROWS: usize = 8;
+/// Typing after this pause starts a new type-ahead search.
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+impl Dropdown {
+    /// Creates a dropdown showing `display`, the widget of the selected option.
+    ///
+    /// Each option is given as the widget shown in the list and its label.
+    pub fn new(
+        id_path: &IdPath,
+        display: Pod,
+        options: Vec<(Pod, String)>,
+        selected: usize,
+    ) -> Dropdown {
+        let (rows, labels): (Vec<_>, Vec<_>) = options
+            .into_iter()
+            .map(|(content, label)| {
+                let row = OptionRow {
+                    content,
+                    label: label.clone(),
+                    is_selected: false,
+                };
+                (Pod::new(row), label)
+            })
+            .unzip();
+        let list = OptionList {
+            id_path: id_path.clone(),
+            rows,
+            highlighted: selected,
+            min_width: 0.0,
+            scroll: 0.0,
+            visible: 0..0,
+        };
+        let list = OverlayHandle::new(list, Placement::Below(Rect::ZERO));
+        list.set_light_dismiss(true);
+        let mut dropdown = Dropdown {
+            id_path: id_path.clone(),
+            display,
+            selected: usize::MAX,
+            labels,
+            list,
+            is_open: false,
+            type_ahead: String::new(),
+            last_type_ahead: None,
+        };
+        let _ = dropdown.set_selected(selected);
+        dropdown
+    }
+
+    /// The widget showing the selected option.
+    pub fn display_mut(&mut self) -> &mut Pod {
+        &mut self.display
+    }
+
+    pub fn set_selected(&mut self, selected: usize) -> ChangeFlags {
+        if self.selected == selected {
+            return ChangeFlags::empty();
+        }
+        let previous = std::mem::replace(&mut self.selected, selected);
+        self.change_list(|list| {
+            for (index, row) in list.rows.iter_mut().enumerate() {
+                if index == previous || index == selected {
+                    let row_widget = row.expect_mut::<OptionRow>();
+                    row_widget.is_selected = index == selected;
+                    let _ = row.mark(ChangeFlags::ACCESSIBILITY | ChangeFlags::PAINT);
+                }
+            }
+            ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY
+        }) | ChangeFlags::PAINT
+            | ChangeFlags::ACCESSIBILITY
+    }
+
+    pub fn option_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Runs `f` with the pod of the option widget at `index` and marks the changes it returns.
+    pub fn update_option(
+        &mut self,
+        index: usize,
+        f: impl FnOnce(&mut Pod) -> ChangeFlags,
+    ) -> ChangeFlags {
+        self.change_list(|list| {
+            let row = &mut list.rows[index];
+            let content = &mut row.expect_mut::<OptionRow>().content;
+            let changes = f(content);
+            let changes = content.mark(changes);
+            row.mark(changes)
+        })
+    }
+
+    pub fn set_label(&mut self, index: usize, label: String) -> ChangeFlags {
+        if self.labels[index] == label {
+            return ChangeFlags::empty();
+        }
+        self.labels[index] = label.clone();
+        self.change_list(|list| {
+            let row = &mut list.rows[index];
+            row.expect_mut::<OptionRow>().label = label;
+            row.mark(ChangeFlags::ACCESSIBILITY)
+        }) | ChangeFlags::ACCESSIBILITY
+    }
+
+    /// Appends an option, given as its widget and label.
+    pub fn push_option(&mut self, content: Pod, label: String) -> ChangeFlags {
+        let row = OptionRow {
+            content,
+            label: label.clone(),
+            is_selected: self.labels.len() == self.selected,
+        };
+        self.labels.push(label);
+        self.change_list(|list| {
+            list.rows.push(Pod::new(row));
+            ChangeFlags::tree_structure() | ChangeFlags::LAYOUT | ChangeFlags::ACCESSIBILITY
+        })
+    }
+
+    /// Removes the options from `len` on.
+    pub fn truncate_options(&mut self, len: usize) -> ChangeFlags {
+        if len >= self.labels.len() {
+            return ChangeFlags::empty();
+        }
+        self.labels.truncate(len);
+        self.change_list(|list| {
+            list.rows.truncate(len);
+            list.highlighted = list.highlighted.min(len.saturating_sub(1));
+            ChangeFlags::tree_structure() | ChangeFlags::LAYOUT | ChangeFlags::ACCESSIBILITY
+        })
+    }
+
+    /// Runs `f` with the option list and marks the changes it returns on the list.
+    fn change_list(&self, f: impl FnOnce(&mut OptionList) -> ChangeFlags) -> ChangeFlags {
+        self.list.with_pod(|pod| {
+            let changes = f(pod.expect_mut());
+            pod.mark(changes)
+        })
+    }
+
+    fn highlighted(&self) -> usize {
+        self.list
+            .with_pod(|pod| pod.expect_mut::<OptionList>().highlighted)
+    }
+
+    fn highlight(&mut self, cx: &mut EventCx, index: usize) {
+        let _ = self.change_list(|list| {
+            list.highlighted = index;
+            list.scroll_to(index);
+            ChangeFlags::LAYOUT | ChangeFlags::PAINT
+        });
+        cx.request_layout();
+        cx.request_accessibility();
+    }
+
+    fn open(&mut self, cx: &mut EventCx) {
+        if self.labels.is_empty() {
+            return;
+        }
+        let anchor = Rect::from_origin_size(cx.window_origin(), cx.size());
+        self.list.set_placement(Placement::Below(anchor));
+        let _ = self.change_list(|list| {
+            list.min_width = anchor.width();
+            ChangeFlags::LAYOUT
+        });
+        self.highlight(cx, self.selected);
+        cx.show_overlay(&self.list);
+        cx.request_paint();
+        self.is_open = true;
+    }
+
+    fn close(&mut self, cx: &mut EventCx) {
+        cx.hide_overlay(&self.list);
+        cx.request_paint();
+        cx.request_accessibility();
+        self.is_open = false;
+    }
+
+    fn choose(&mut self, cx: &mut EventCx, index: usize) {
+        cx.add_message(Message::new(self.id_path.clone(), index));
+    }
+
+    /// Extends the type-ahead search with `text` and returns the matching option.
+    fn type_ahead(&mut self, now: Instant, text: &str, current: usize) -> Option<usize> {
+        let is_stale = self
+            .last_type_ahead
+            .map_or(true, |last| now.duration_since(last) > TYPE_AHEAD_TIMEOUT);
+        if is_stale {
+            self.type_ahead.clear();
+        }
+        self.last_type_ahead = Some(now);
+        self.type_ahead.push_str(&text.to_lowercase());
+        find_type_ahead(&self.labels, &self.type_ahead, current)
+    }
+
+    /// Handles a key press while focused, returns whether the key was used.
+    fn key_down(&mut self, cx: &mut EventCx, event: &KeyEvent) -> bool {
+        if !self.is_open {
+            match &event.key {
+                KbKey::Enter | KbKey::ArrowDown | KbKey::ArrowUp => self.open(cx),
+                KbKey::Character(c) if c == " " => self.open(cx),
+                KbKey::Character(c) if !event.mods.ctrl() && !event.mods.meta() => {
+                    match self.type_ahead(cx.now(), c, self.selected) {
+                        Some(index) if index != self.selected => self.choose(cx, index),
+                        _ => (),
+                    }
+                }
+                _ => return false,
+            }
+            return true;
+        }
+
+        let last = self.labels.len().saturating_sub(1);
+        let highlighted = self.highlighted().min(last);
+        match &event.key {
+            KbKey::ArrowDown => self.highlight(cx, (highlighted + 1).min(last)),
+            KbKey::ArrowUp => self.highlight(cx, highlighted.saturating_sub(1)),
+            KbKey::PageDown => self.highlight(cx, (highlighted + MAX_VISIBLE_ROWS).min(last)),
+            KbKey::PageUp => self.highlight(cx, highlighted.saturating_sub(MAX_VISIBLE_ROWS)),
+            KbKey::Home => self.highlight(cx, 0),
+            KbKey::End => self.highlight(cx, last),
+            KbKey::Enter => {
+                self.choose(cx, highlighted);
+                self.close(cx);
+            }
+            KbKey::Character(c) if c == " " && self.type_ahead.is_empty() => {
+                self.choose(cx, highlighted);
+                self.close(cx);
+            }
+            KbKey::Escape => self.close(cx),
+            KbKey::Tab => {
+                // Closes the list, but lets focus move on.
+                self.close(cx);
+                return false;
+            }
+            KbKey::Character(c) if !event.mods.ctrl() && !event.mods.meta() => {
+                if let Some(index) = self.type_ahead(cx.now(), c, highlighted) {
+                    self.highlight(cx, index);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Finds the first label from `current` on which starts with `prefix`, ignoring case.
+///
+/// A single character, or one repeated, cycles through the labels starting with it, beginning
+/// after `current`.
+fn find_type_ahead(labels: &[String], prefix: &str, current: usize) -> Option<usize> {
+    let mut chars = prefix.chars();
+    let first = chars.next()?;
+    let (prefix, start) = if chars.all(|c| c == first) {
+        (&prefix[..first.len_utf8()], current + 1)
+    } else {
+        (prefix, current)
+    };
+    let count = labels.len();
+    (0..count)
+        .map(|offset| (start + offset) % count)
+        .find(|&index| labels[index].to_lowercase().starts_with(prefix))
+}
+
+impl Widget for Dropdown {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        match event {
+            Event::MouseDown(_) => {
+                cx.request_focus();
+                if self.is_open {
+                    self.close(cx);
+                } else {
+                    self.open(cx);
+                }
+            }
+            Event::KeyDown(key_event) if cx.is_focused() => {
+                if self.key_down(cx, key_event) {
+                    cx.set_handled(true);
+                }
+            }
+            Event::OverlayDismissed { overlay, .. } if *overlay == self.list.id() => {
+                self.is_open = false;
+                cx.request_paint();
+                cx.request_accessibility();
+            }
+            Event::TargetedAccessibilityAction(request)
+                if cx.is_accesskit_target(request.target) =>
+            {
+                match request.action {
+                    accesskit::Action::Default | accesskit::Action::Expand if !self.is_open => {
+                        self.open(cx)
+                    }
+                    accesskit::Action::Default | accesskit::Action::Collapse if self.is_open => {
+                        self.close(cx)
+                    }
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        match event {
+            LifeCycle::HotChanged(_) => cx.request_paint(),
+            LifeCycle::BuildFocusChain => cx.register_for_focus(),
+            _ => (),
+        }
+        self.display.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.display.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let padding = Size::new(INSETS.x_value() + ARROW_WIDTH, INSETS.y_value());
+        let display_size = self.display.layout(cx, &bc.shrink(padding).loosen());
+        let size = bc.constrain(Size::new(
+            display_size.width + padding.width,
+            (display_size.height + padding.height).max(MIN_HEIGHT),
+        ));
+        let y = (size.height - display_size.height) * 0.5;
+        self.display.set_origin(cx, Point::new(INSETS.x0, y));
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.display.accessibility(cx);
+
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::ComboBox);
+        builder.set_children(vec![self.display.id().into()]);
+        if let Some(label) = self.labels.get(self.selected) {
+            builder.set_value(label.clone());
+        }
+        builder.set_expanded(self.is_open);
+        builder.set_default_action_verb(accesskit::DefaultActionVerb::Click);
+        builder.add_action(accesskit::Action::Default);
+        builder.add_action(accesskit::Action::Focus);
+        builder.add_action(accesskit::Action::Expand);
+        builder.add_action(accesskit::Action::Collapse);
+        if self.is_open {
+            let (list, highlighted) = self.list.with_pod(|pod| {
+                let list = pod.expect_mut::<OptionList>();
+                let highlighted = list.rows.get(list.highlighted).map(Pod::id);
+                (pod.id(), highlighted)
+            });
+            builder.set_controls(vec![list.into()]);
+            if let Some(highlighted) = highlighted {
+                builder.set_active_descendant(highlighted.into());
+            }
+        }
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let border_width = 2.0;
+        let rounded_rect = cx
+            .size()
+            .to_rect()
+            .inset(-0.5 * border_width)
+            .to_rounded_rect(4.0);
+        let border_color = if cx.is_focused() {
+            Color::rgb8(0x5a, 0x9b, 0xe6)
+        } else if cx.is_hot() {
+            Color::rgb8(0xa1, 0xa1, 0xa1)
+        } else {
+            Color::rgb8(0x3a, 0x3a, 0x3a)
+        };
+        piet_scene_helpers::stroke(builder, &rounded_rect, border_color, border_width);
+        piet_scene_helpers::fill_lin_gradient(
+            builder,
+            &rounded_rect,
+            [Color::rgb8(0xa1, 0xa1, 0xa1), Color::rgb8(0x3a, 0x3a, 0x3a)],
+            UnitPoint::TOP,
+            UnitPoint::BOTTOM,
+        );
+
+        // A chevron pointing down, or up while the list is open.
+        let center = Point::new(
+            cx.size().width - INSETS.x1 - ARROW_WIDTH * 0.5,
+            cx.size().height * 0.5,
+        );
+        let direction = if self.is_open { -1.0 } else { 1.0 };
+        let mut chevron = BezPath::new();
+        chevron.move_to(center + (-4.0, -2.0 * direction));
+        chevron.line_to(center + (0.0, 2.0 * direction));
+        chevron.line_to(center + (4.0, -2.0 * direction));
+        piet_scene_helpers::stroke(builder, &chevron, Color::WHITE, 1.5);
+
+        self.display.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.display);
+    }
+}
+
+/// The list of options shown in an overlay while a [`Dropdown`] is open.
+///
+/// Only the rows in view are laid out and painted, so long lists stay cheap.
+struct OptionList {
+    id_path: IdPath,
+    rows: Vec<Pod>,
+    highlighted: usize,
+    /// The width of the control, the list is at least as wide.
+    min_width: f64,
+    scroll: f64,
+    /// The rows laid out in the last layout.
+    visible: std::ops::Range<usize>,
+}
+
+impl OptionList {
+    fn height(&self) -> f64 {
+        self.rows.len().min(MAX_VISIBLE_ROWS) as f64 * ROW_HEIGHT
+    }
+
+    fn max_scroll(&self) -> f64 {
+        (self.rows.len() as f64 * ROW_HEIGHT - self.height()).max(0.0)
+    }
+
+    /// Scrolls as little as possible to show the row at `index`.
+    fn scroll_to(&mut self, index: usize) {
+        let top = index as f64 * ROW_HEIGHT;
+        let bottom = top + ROW_HEIGHT;
+        if top < self.scroll {
+            self.scroll = top;
+        } else if bottom > self.scroll + self.height() {
+            self.scroll = bottom - self.height();
+        }
+        self.scroll = self.scroll.clamp(0.0, self.max_scroll());
+    }
+
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        if pos.y < 0.0 || pos.y >= self.height() {
+            return None;
+        }
+        let index = ((pos.y + self.scroll) / ROW_HEIGHT) as usize;
+        (index < self.rows.len()).then_some(index)
+    }
+}
+
+impl Widget for OptionList {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        match event {
+            Event::MouseMove(mouse) => {
+                if let Some(index) = self.row_at(mouse.pos) {
+                    if index != self.highlighted {
+                        self.highlighted = index;
+                        cx.request_paint();
+                    }
+                }
+            }
+            Event::MouseDown(_) => {
+                cx.set_active(true);
+            }
+            Event::MouseUp(mouse) => {
+                if cx.is_active() {
+                    cx.set_active(false);
+                    if let Some(index) = self.row_at(mouse.pos) {
+                        cx.add_message(Message::new(self.id_path.clone(), index));
+                        cx.dismiss_overlay();
+                    }
+                }
+            }
+            _ => {
+                for row in &mut self.rows {
+                    row.event(cx, event);
+                }
+            }
+        }
+    }
+
+    fn capture_event(&mut self, cx: &mut EventCx, event: &Event) {
+        // The wheel scrolls the options, it never reaches the rows or the widgets below.
+        if let Event::MouseWheel(mouse) = event {
+            let scroll = (self.scroll + mouse.wheel_delta.y).clamp(0.0, self.max_scroll());
+            if scroll != self.scroll {
+                self.scroll = scroll;
+                cx.request_layout();
+                cx.request_paint();
+            }
+            cx.set_handled(true);
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        if let LifeCycle::Added = event {
+            cx.set_scrolls_with_wheel();
+        }
+        for row in &mut self.rows {
+            row.lifecycle(cx, event);
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        for row in &mut self.rows {
+            row.update(cx);
+        }
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        self.scroll = self.scroll.clamp(0.0, self.max_scroll());
+        let size = bc.constrain(Size::new(self.min_width, self.height()));
+        let first = (self.scroll / ROW_HEIGHT) as usize;
+        let end = (((self.scroll + size.height) / ROW_HEIGHT).ceil() as usize).min(self.rows.len());
+        self.visible = first.min(end)..end;
+        let row_bc = BoxConstraints::tight(Size::new(size.width, ROW_HEIGHT));
+        for index in self.visible.clone() {
+            let row = &mut self.rows[index];
+            row.layout(cx, &row_bc);
+            row.set_origin(cx, Point::new(0.0, index as f64 * ROW_HEIGHT - self.scroll));
+        }
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        for row in &mut self.rows[self.visible.clone()] {
+            row.accessibility(cx);
+        }
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::ListBox);
+            builder.set_children(
+                self.rows[self.visible.clone()]
+                    .iter()
+                    .map(|row| row.id().into())
+                    .collect::<Vec<accesskit::NodeId>>(),
+            );
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let rect = cx.size().to_rect();
+        piet_scene_helpers::fill_color(builder, &rect, Color::rgb8(0x2a, 0x2a, 0x2a));
+        builder.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &rect);
+        if self.visible.contains(&self.highlighted) {
+            let y = self.highlighted as f64 * ROW_HEIGHT - self.scroll;
+            let highlight = Rect::new(0.0, y, rect.width(), y + ROW_HEIGHT);
+            piet_scene_helpers::fill_color(builder, &highlight, Color::rgb8(0x5a, 0x9b, 0xe6));
+        }
+        for row in &mut self.rows[self.visible.clone()] {
+            row.paint(cx, builder);
+        }
+        builder.pop_layer();
+        piet_scene_helpers::stroke(builder, &rect, Color::rgb8(0x3a, 0x3a, 0x3a), 1.0);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        self.rows[self.visible.clone()].iter().for_each(visit);
+    }
+}
+
+/// A row of the [`OptionList`], wrapping the widget of an option.
+struct OptionRow {
+    content: Pod,
+    label: String,
+    is_selected: bool,
+}
+
+impl Widget for OptionRow {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.content.event(cx, event);
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.content.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.content.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = bc.max();
+        let content_bc = BoxConstraints::new(Size::ZERO, size).shrink((INSETS.x_value(), 0.0));
+        let content_size = self.content.layout(cx, &content_bc);
+        let y = (size.height - content_size.height) * 0.5;
+        self.content.set_origin(cx, Point::new(INSETS.x0, y));
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.content.accessibility(cx);
+
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::ListBoxOption);
+        builder.set_children(vec![self.content.id().into()]);
+        builder.set_name(self.label.clone());
+        builder.set_selected(self.is_selected);
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        if self.is_selected {
+            let marker = Rect::new(0.0, 4.0, 3.0, cx.size().height - 4.0);
+            piet_scene_helpers::fill_color(builder, &marker, Color::WHITE);
+        }
+        self.content.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Axis;
+    use crate::widget::test_util::{key_down, mouse, Harness, Leaf};
+    use crate::widget::{LinearLayout, OverlayHost};
+    use test_log::test;
+
+    const FRUITS: [&str; 5] = ["Apple", "Banana", "Blueberry", "Cherry", "Date"];
+
+    fn labels(labels: &[&str]) -> Vec<String> {
+        labels.iter().map(|label| label.to_string()).collect()
+    }
+
+    fn dropdown(selected: usize) -> Dropdown {
+        let options = FRUITS
+            .iter()
+            .map(|label| (Pod::new(Leaf::new(60.0, 16.0)), label.to_string()))
+            .collect();
+        Dropdown::new(&vec![], Pod::new(Leaf::new(60.0, 16.0)), options, selected)
+    }
+
+    /// A window of 200×200 with a dropdown at `y`.
+    fn setup(harness: &mut Harness, y: f64) -> Pod {
+        let spacer = Pod::new(Leaf::new(10.0, y));
+        let column = LinearLayout::new(vec![spacer, Pod::new(dropdown(0))], 0.0, Axis::Vertical);
+        let mut root = Pod::new(OverlayHost::new(Pod::new(column)));
+        harness.layout(&mut root, &BoxConstraints::tight(Size::new(200.0, 200.0)));
+        root
+    }
+
+    fn dropdown_pod(root: &mut Pod) -> &mut Pod {
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        let column = host.content_mut().downcast_mut::<LinearLayout>().unwrap();
+        &mut column.children[1]
+    }
+
+    fn selections(harness: &mut Harness) -> Vec<usize> {
+        harness
+            .take_messages()
+            .into_iter()
+            .map(|message| *message.body.downcast::<usize>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn type_ahead_matches_prefixes() {
+        let fruits = labels(&FRUITS);
+        assert_eq!(find_type_ahead(&fruits, "b", 0), Some(1));
+        assert_eq!(find_type_ahead(&fruits, "b", 1), Some(2));
+        // A repeated character cycles instead of looking for "bb".
+        assert_eq!(find_type_ahead(&fruits, "bb", 2), Some(1));
+        assert_eq!(find_type_ahead(&fruits, "blu", 1), Some(2));
+        assert_eq!(find_type_ahead(&fruits, "x", 0), None);
+        assert_eq!(
+            find_type_ahead(&labels(&["Ärger", "apfel"]), "ä", 1),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn keyboard_selection() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, 20.0);
+        let id = dropdown_pod(&mut root).id();
+        harness.window_state.focus = Some(id);
+
+        harness.event(&mut root, key_down(KbKey::ArrowDown));
+        assert_eq!(
+            root.downcast_mut::<OverlayHost>().unwrap().overlay_count(),
+            1
+        );
+        harness.layout(&mut root, &BoxConstraints::tight(Size::new(200.0, 200.0)));
+
+        harness.event(&mut root, key_down(KbKey::ArrowDown));
+        harness.event(&mut root, key_down(KbKey::ArrowDown));
+        harness.event(&mut root, key_down(KbKey::ArrowUp));
+        harness.event(&mut root, key_down(KbKey::End));
+        harness.event(&mut root, key_down(KbKey::ArrowUp));
+        assert!(selections(&mut harness).is_empty());
+        harness.event(&mut root, key_down(KbKey::Enter));
+
+        assert_eq!(selections(&mut harness), vec![3]);
+        assert_eq!(
+            root.downcast_mut::<OverlayHost>().unwrap().overlay_count(),
+            0
+        );
+        let dropdown = dropdown_pod(&mut root).downcast_mut::<Dropdown>().unwrap();
+        assert!(!dropdown.is_open());
+        assert_eq!(harness.window_state.focus, Some(id));
+
+        // Escape closes without choosing.
+        harness.event(&mut root, key_down(KbKey::Enter));
+        harness.event(&mut root, key_down(KbKey::ArrowDown));
+        harness.event(&mut root, key_down(KbKey::Escape));
+        assert!(selections(&mut harness).is_empty());
+        assert_eq!(
+            root.downcast_mut::<OverlayHost>().unwrap().overlay_count(),
+            0
+        );
+    }
+
+    #[test]
+    fn type_ahead_while_closed_chooses() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, 20.0);
+        harness.window_state.focus = Some(dropdown_pod(&mut root).id());
+
+        harness.event(&mut root, key_down(KbKey::Character("c".into())));
+        assert_eq!(selections(&mut harness), vec![3]);
+        assert_eq!(
+            root.downcast_mut::<OverlayHost>().unwrap().overlay_count(),
+            0
+        );
+    }
+
+    #[test]
+    fn list_flips_above_near_bottom_edge() {
+        let mut harness = Harness::new();
+        let bc = BoxConstraints::tight(Size::new(200.0, 200.0));
+
+        let mut root = setup(&mut harness, 20.0);
+        harness.event(&mut root, Event::MouseDown(mouse((20.0, 30.0))));
+        harness.layout(&mut root, &bc);
+        let dropdown = dropdown_pod(&mut root).downcast_mut::<Dropdown>().unwrap();
+        // Five rows of 24px below the control at 20..44.
+        assert_eq!(dropdown.list.rect().y0, 44.0);
+        assert_eq!(dropdown.list.rect().height(), 120.0);
+
+        let mut root = setup(&mut harness, 170.0);
+        harness.event(&mut root, Event::MouseDown(mouse((20.0, 180.0))));
+        harness.layout(&mut root, &bc);
+        let dropdown = dropdown_pod(&mut root).downcast_mut::<Dropdown>().unwrap();
+        assert!(dropdown.is_open());
+        assert_eq!(dropdown.list.rect().y1, 170.0);
+    }
+
+    #[test]
+    fn clicks_choose_and_outside_clicks_dismiss() {
+        let mut harness = Harness::new();
+        let bc = BoxConstraints::tight(Size::new(200.0, 200.0));
+        let mut root = setup(&mut harness, 20.0);
+
+        harness.event(&mut root, Event::MouseDown(mouse((20.0, 30.0))));
+        harness.layout(&mut root, &bc);
+        // The second row spans 68..92.
+        harness.event(&mut root, Event::MouseMove(mouse((20.0, 80.0))));
+        harness.event(&mut root, Event::MouseDown(mouse((20.0, 80.0))));
+        harness.event(&mut root, Event::MouseUp(mouse((20.0, 80.0))));
+        assert_eq!(selections(&mut harness), vec![1]);
+        let dropdown = dropdown_pod(&mut root).downcast_mut::<Dropdown>().unwrap();
+        assert!(!dropdown.is_open());
+
+        harness.event(&mut root, Event::MouseDown(mouse((20.0, 30.0))));
+        harness.layout(&mut root, &bc);
+        harness.event(&mut root, Event::MouseDown(mouse((150.0, 190.0))));
+        assert!(selections(&mut harness).is_empty());
+        assert_eq!(
+            root.downcast_mut::<OverlayHost>().unwrap().overlay_count(),
+            0
+        );
+        let dropdown = dropdown_pod(&mut root).downcast_mut::<Dropdown>().unwrap();
+        assert!(!dropdown.is_open());
+    }
+
+    #[test]
+    fn only_visible_rows_are_laid_out() {
+        let options = (0..1000)
+            .map(|index| (Pod::new(Leaf::new(60.0, 16.0)), index.to_string()))
+            .collect();
+        let dropdown = Dropdown::new(&vec![], Pod::new(Leaf::new(60.0, 16.0)), options, 500);
+        let list = dropdown.list.clone();
+        let mut harness = Harness::new();
+        let mut root = Pod::new(OverlayHost::new(Pod::new(dropdown)));
+        let bc = BoxConstraints::tight(Size::new(200.0, 400.0));
+        harness.layout(&mut root, &bc);
+        harness.event(&mut root, Event::MouseDown(mouse((20.0, 10.0))));
+        harness.layout(&mut root, &bc);
+
+        list.with_pod(|pod| {
+            let list = pod.downcast_mut::<OptionList>().unwrap();
+            assert_eq!(list.visible, 493..501);
+        });
+    }
+
+    #[test]
+    fn wheel_scrolls_the_open_options() {
+        let options = (0..1000)
+            .map(|index| (Pod::new(Leaf::new(60.0, 16.0)), index.to_string()))
+            .collect();
+        let dropdown = Dropdown::new(&vec![], Pod::new(Leaf::new(60.0, 16.0)), options, 500);
+        let list = dropdown.list.clone();
+        let mut harness = Harness::new();
+        let mut root = Pod::new(OverlayHost::new(Pod::new(dropdown)));
+        let bc = BoxConstraints::tight(Size::new(200.0, 400.0));
+        harness.layout(&mut root, &bc);
+        harness.event(&mut root, Event::MouseDown(mouse((20.0, 10.0))));
+        harness.layout(&mut root, &bc);
+
+        // The wheel scrolls the options by two rows.
+        let mut wheel = mouse(list.rect().center());
+        wheel.wheel_delta = (0.0, 2.0 * ROW_HEIGHT).into();
+        harness.event(&mut root, Event::MouseWheel(wheel));
+        harness.layout(&mut root, &bc);
+        list.with_pod(|pod| {
+            let list = pod.downcast_mut::<OptionList>().unwrap();
+            assert_eq!(list.visible, 495..503);
+        });
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vello::{kurbo::Size, SceneBuilder};
+
+use super::{
+    AccessCx, BoxConstraints, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx, PaintCx, UpdateCx,
+    Widget,
+};
+
+/// A widget without content, for views which only have an effect on the app state.
+pub struct Empty;
+
+impl Widget for Empty {
+    fn event(&mut self, _cx: &mut EventCx, _event: &Event) {}
+
+    fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+    fn update(&mut self, _cx: &mut UpdateCx) {}
+
+    fn layout(&mut self, _cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        bc.min()
+    }
+
+    fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+    fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+}
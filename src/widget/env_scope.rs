@@ -0,0 +1,219 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::mem;
+
+use vello::{
+    kurbo::{Point, Size},
+    SceneBuilder,
+};
+
+use crate::Env;
+
+use super::{
+    AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx,
+    PaintCx, Pod, UpdateCx, Widget,
+};
+
+/// Provides a snapshot of the [`Env`] to the widgets below it.
+///
+/// The snapshot is what the views saw when building the child, widgets read it from their
+/// contexts with `env()`. When it changes, the subtree receives [`LifeCycle::EnvChanged`] and is
+/// laid out and painted again.
+pub struct EnvScope {
+    child: Pod,
+    env: Env,
+    /// The pending change, and whether it needs a layout.
+    env_changed: Option<bool>,
+}
+
+impl EnvScope {
+    pub fn new(child: Pod, env: Env) -> Self {
+        EnvScope {
+            child,
+            env,
+            env_changed: None,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    pub fn set_env(&mut self, env: Env) -> ChangeFlags {
+        self.change_env(env, true)
+    }
+
+    /// Like [`set_env`](EnvScope::set_env), for a change of values only used in painting.
+    ///
+    /// The subtree is painted again but not laid out, widgets which use such values during
+    /// layout request it themselves.
+    pub fn set_env_paint_only(&mut self, env: Env) -> ChangeFlags {
+        self.change_env(env, false)
+    }
+
+    fn change_env(&mut self, env: Env, layout: bool) -> ChangeFlags {
+        if env == self.env {
+            return ChangeFlags::empty();
+        }
+        self.env = env;
+        let layout = layout || self.env_changed == Some(true);
+        self.env_changed = Some(layout);
+        if layout {
+            ChangeFlags::UPDATE | ChangeFlags::LAYOUT | ChangeFlags::PAINT
+        } else {
+            ChangeFlags::UPDATE | ChangeFlags::PAINT
+        }
+    }
+}
+
+impl Widget for EnvScope {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        mem::swap(&mut self.env, &mut cx.cx_state.env);
+        self.child.event(cx, event);
+        mem::swap(&mut self.env, &mut cx.cx_state.env);
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        mem::swap(&mut self.env, &mut cx.cx_state.env);
+        self.child.lifecycle(cx, event);
+        mem::swap(&mut self.env, &mut cx.cx_state.env);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        mem::swap(&mut self.env, &mut cx.cx_state.env);
+        if let Some(layout) = self.env_changed.take() {
+            let mut lifecycle_cx = LifeCycleCx::new(cx.cx_state, cx.widget_state);
+            self.child
+                .lifecycle(&mut lifecycle_cx, &LifeCycle::EnvChanged { layout });
+        }
+        self.child.update(cx);
+        mem::swap(&mut self.env, &mut cx.cx_state.env);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        mem::swap(&mut self.env, &mut cx.cx_state.env);
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        mem::swap(&mut self.env, &mut cx.cx_state.env);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        mem::swap(&mut self.env, &mut cx.cx_state.env);
+        self.child.accessibility(cx);
+        mem::swap(&mut self.env, &mut cx.cx_state.env);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        mem::swap(&mut self.env, &mut cx.cx_state.env);
+        self.child.paint(cx, builder);
+        mem::swap(&mut self.env, &mut cx.cx_state.env);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::widget::test_util::Harness;
+    use crate::Key;
+    use test_log::test;
+
+    const ACCENT: Key<u32> = Key::new("test.accent");
+
+    /// Records the accent it sees during layout and paint.
+    struct Probe {
+        seen: Arc<Mutex<Vec<Option<u32>>>>,
+    }
+
+    impl Widget for Probe {
+        fn event(&mut self, _cx: &mut EventCx, _event: &Event) {}
+
+        fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            self.seen
+                .lock()
+                .unwrap()
+                .push(cx.env().get(ACCENT).copied());
+            Size::new(10.0, 10.0)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, cx: &mut PaintCx, _builder: &mut SceneBuilder) {
+            self.seen
+                .lock()
+                .unwrap()
+                .push(cx.env().get(ACCENT).copied());
+        }
+    }
+
+    #[test]
+    fn snapshot_is_visible_below_the_scope_only() {
+        let mut harness = Harness::new();
+        let seen = Arc::new(Mutex::new(vec![]));
+        let probe = Pod::new(Probe { seen: seen.clone() });
+        let mut pod = Pod::new(EnvScope::new(probe, Env::new().with(ACCENT, 1)));
+
+        harness.layout(&mut pod, &BoxConstraints::UNBOUNDED);
+        harness.paint(&mut pod);
+
+        // The snapshot is restored after the pass over the scope.
+        let mut outside = Pod::new(Probe { seen: seen.clone() });
+        harness.layout(&mut outside, &BoxConstraints::UNBOUNDED);
+        assert_eq!(*seen.lock().unwrap(), [Some(1), Some(1), None]);
+    }
+
+    #[test]
+    fn changed_snapshot_repaints_the_subtree() {
+        let mut harness = Harness::new();
+        let seen = Arc::new(Mutex::new(vec![]));
+        let probe = Pod::new(Probe { seen: seen.clone() });
+        let mut pod = Pod::new(EnvScope::new(probe, Env::new().with(ACCENT, 1)));
+        harness.layout(&mut pod, &BoxConstraints::UNBOUNDED);
+        harness.paint(&mut pod);
+        seen.lock().unwrap().clear();
+
+        // Setting an equal snapshot changes nothing.
+        let scope: &mut EnvScope = pod.downcast_mut().unwrap();
+        assert_eq!(
+            scope.set_env(Env::new().with(ACCENT, 1)),
+            ChangeFlags::empty()
+        );
+        harness.paint(&mut pod);
+        assert!(seen.lock().unwrap().is_empty());
+
+        let scope: &mut EnvScope = pod.downcast_mut().unwrap();
+        let changes = scope.set_env(Env::new().with(ACCENT, 2));
+        let _ = pod.mark(changes);
+        harness.update(&mut pod);
+        harness.paint(&mut pod);
+        assert_eq!(*seen.lock().unwrap(), [Some(2)]);
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vello::{kurbo::Rect, SceneBuilder};
+
+use crate::Theme;
+
+use super::{piet_scene_helpers, PaintCx, PodFlags};
+
+/// The space between a widget and its focus ring.
+const FOCUS_RING_GAP: f64 = 1.0;
+const FOCUS_RING_WIDTH: f64 = 2.0;
+
+/// How far the focus ring reaches outside of the shape it surrounds.
+///
+/// Widgets which draw one pass it to
+/// [`LayoutCx::set_paint_insets`](super::LayoutCx::set_paint_insets), so that the ring is
+/// repainted with them.
+pub const FOCUS_RING_EXTENT: f64 = FOCUS_RING_GAP + FOCUS_RING_WIDTH;
+
+/// Paints the focus ring around the layout rectangle of the widget, with the corner radius of
+/// the theme, see [`paint_focus_ring_around`].
+pub fn paint_focus_ring(cx: &mut PaintCx, builder: &mut SceneBuilder) {
+    let radius = Theme::get(cx.env()).corner_radius;
+    let rect = cx.size().to_rect();
+    paint_focus_ring_around(cx, builder, rect, radius);
+}
+
+/// Paints the focus ring around `rect` with the corners rounded by `radius`, in the accent
+/// color of the theme.
+///
+/// Nothing is painted unless [`PaintCx::is_focus_visible`], so widgets call it on every paint,
+/// usually last. `rect` is in the coordinates of the widget, for a part of it like the box of a
+/// checkbox.
+pub fn paint_focus_ring_around(
+    cx: &mut PaintCx,
+    builder: &mut SceneBuilder,
+    rect: Rect,
+    radius: f64,
+) {
+    if !cx.is_focus_visible() {
+        return;
+    }
+    let outset = FOCUS_RING_GAP + FOCUS_RING_WIDTH * 0.5;
+    let ring = rect
+        .inflate(outset, outset)
+        .to_rounded_rect(radius + outset);
+    let color = Theme::get(cx.env()).accent;
+    piet_scene_helpers::stroke(builder, &ring, color, FOCUS_RING_WIDTH);
+    cx.widget_state.flags.insert(PodFlags::PAINTS_FOCUS_RING);
+}
+
+#[cfg(test)]
+mod tests {
+    use glazier::KbKey;
+    use test_log::test;
+    use vello::kurbo::Size;
+
+    use super::*;
+    use crate::widget::test_util::{key_down, mouse, Harness, Leaf};
+    use crate::widget::{BoxConstraints, Button, Event, LinearLayout, Pod};
+    use crate::Axis;
+
+    /// Two buttons of 56x24, the second one at y = 34.
+    fn buttons(harness: &mut Harness) -> (Pod, BoxConstraints) {
+        let buttons = (0..2)
+            .map(|_| Pod::new(Button::new(&vec![], Pod::new(Leaf::new(40.0, 10.0)))))
+            .collect();
+        let mut root = Pod::new(LinearLayout::new(buttons, 10.0, Axis::Vertical));
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(200.0, 200.0));
+        harness.frame(&mut root, &bc);
+        (root, bc)
+    }
+
+    fn click(harness: &mut Harness, root: &mut Pod, y: f64) {
+        harness.event(root, Event::MouseDown(mouse((10.0, y))));
+        harness.event(root, Event::MouseUp(mouse((10.0, y))));
+    }
+
+    fn rings(root: &mut Pod) -> Vec<bool> {
+        let layout = root.downcast_mut::<LinearLayout>().unwrap();
+        (0..2)
+            .map(|index| {
+                let flags = layout.children[index].state.flags;
+                flags.contains(PodFlags::PAINTS_FOCUS_RING)
+            })
+            .collect()
+    }
+
+    fn button_id(root: &mut Pod, index: usize) -> xilem_core::Id {
+        root.downcast_mut::<LinearLayout>().unwrap().children[index].id()
+    }
+
+    #[test]
+    fn pointer_focus_hides_the_ring_until_a_key_press() {
+        let mut harness = Harness::new();
+        let (mut root, bc) = buttons(&mut harness);
+        let first = button_id(&mut root, 0);
+        let second = button_id(&mut root, 1);
+
+        click(&mut harness, &mut root, 10.0);
+        harness.frame(&mut root, &bc);
+        assert_eq!(harness.window_state.focus, Some(first));
+        assert_eq!(rings(&mut root), [false, false]);
+
+        harness.event(&mut root, key_down(KbKey::Tab));
+        harness.frame(&mut root, &bc);
+        assert_eq!(harness.window_state.focus, Some(second));
+        assert_eq!(rings(&mut root), [false, true]);
+
+        click(&mut harness, &mut root, 10.0);
+        harness.frame(&mut root, &bc);
+        assert_eq!(harness.window_state.focus, Some(first));
+        assert_eq!(rings(&mut root), [false, false]);
+
+        // Modifiers alone don't count, other keys bring the ring back in place.
+        harness.event(&mut root, key_down(KbKey::Shift));
+        harness.frame(&mut root, &bc);
+        assert_eq!(rings(&mut root), [false, false]);
+        harness.event(&mut root, key_down(KbKey::ArrowDown));
+        harness.frame(&mut root, &bc);
+        assert_eq!(harness.window_state.focus, Some(first));
+        assert_eq!(rings(&mut root), [true, false]);
+    }
+
+    #[test]
+    fn accessibility_focus_follows_and_shows_the_ring() {
+        let mut harness = Harness::new();
+        let (mut root, bc) = buttons(&mut harness);
+        let second = button_id(&mut root, 1);
+        let action = |action| {
+            Event::TargetedAccessibilityAction(accesskit::ActionRequest {
+                action,
+                target: second.into(),
+                data: None,
+            })
+        };
+
+        click(&mut harness, &mut root, 10.0);
+        harness.event(&mut root, action(accesskit::Action::Focus));
+        harness.frame(&mut root, &bc);
+        assert_eq!(harness.window_state.focus, Some(second));
+        assert_eq!(harness.accessibility(&mut root).focus, Some(second.into()));
+        assert_eq!(rings(&mut root), [false, true]);
+
+        harness.event(&mut root, action(accesskit::Action::Blur));
+        harness.frame(&mut root, &bc);
+        assert_eq!(harness.window_state.focus, None);
+        assert_eq!(harness.accessibility(&mut root).focus, None);
+        assert_eq!(rings(&mut root), [false, false]);
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use glazier::KbKey;
+use vello::{
+    kurbo::{Point, Size},
+    SceneBuilder,
+};
+
+use super::{
+    AccessCx, BoxConstraints, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx, PaintCx, Pod,
+    UpdateCx, Widget,
+};
+
+/// Keeps Tab traversal inside a child while it has focus, and focuses it when it appears.
+///
+/// With `contain`, Tab and Shift+Tab cycle through the focusable widgets of the child once
+/// focus is inside, as in a dialog. Without it, traversal continues in the rest of the window.
+/// After [`focus_first`](FocusScope::focus_first), the first focusable widget of the child gets
+/// focus in the next tree update.
+pub struct FocusScope {
+    child: Pod,
+    contain: bool,
+    focus_first: bool,
+}
+
+impl FocusScope {
+    pub fn new(child: Pod, contain: bool) -> Self {
+        FocusScope {
+            child,
+            contain,
+            focus_first: false,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    pub fn set_contain(&mut self, contain: bool) {
+        self.contain = contain;
+    }
+
+    /// Moves focus to the first focusable widget of the child in the next tree update.
+    pub fn focus_first(&mut self) {
+        self.focus_first = true;
+    }
+}
+
+impl Widget for FocusScope {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.child.event(cx, event);
+        if let Event::KeyDown(key_event) = event {
+            let mods = key_event.mods;
+            if key_event.key == KbKey::Tab
+                && self.contain
+                && !cx.is_handled()
+                && !(mods.ctrl() || mods.alt() || mods.meta())
+                && cx.has_focus_within()
+            {
+                self.child.move_focus(cx, !mods.shift());
+                cx.set_handled(true);
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.child.lifecycle(cx, event);
+        if let LifeCycle::TreeUpdate = event {
+            if std::mem::take(&mut self.focus_first) {
+                if let Some(first) = self.child.focus_chain(cx).first() {
+                    cx.cx_state.window_state().focus = Some(*first);
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.child.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{key_down, Harness, Leaf};
+    use crate::widget::{Button, ChangeFlags, LinearLayout, PodSlots};
+    use crate::Axis;
+    use glazier::{KeyEvent, KeyState, Modifiers};
+    use test_log::test;
+    use xilem_core::Id;
+
+    const WINDOW: Size = Size::new(400.0, 300.0);
+
+    fn button() -> Pod {
+        Pod::new(Button::new(
+            &vec![Id::next()],
+            Pod::new(Leaf::new(60.0, 20.0)),
+        ))
+    }
+
+    fn column(children: Vec<Pod>) -> Pod {
+        Pod::new(LinearLayout::new(children, 0.0, Axis::Vertical))
+    }
+
+    fn children(pod: &mut Pod) -> &mut PodSlots {
+        &mut pod.downcast_mut::<LinearLayout>().unwrap().children
+    }
+
+    fn shift_tab() -> Event {
+        Event::KeyDown(KeyEvent {
+            key: KbKey::Tab,
+            mods: Modifiers::SHIFT,
+            state: KeyState::Down,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn tab_cycles_through_the_window() {
+        let mut harness = Harness::new();
+        let mut root = column(vec![button(), button(), button()]);
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        let ids: Vec<_> = children(&mut root).iter().map(Pod::id).collect();
+
+        let mut focus_after = |event: Event| {
+            harness.event(&mut root, event);
+            harness.window_state.focus
+        };
+        assert_eq!(focus_after(key_down(KbKey::Tab)), Some(ids[0]));
+        assert_eq!(focus_after(key_down(KbKey::Tab)), Some(ids[1]));
+        assert_eq!(focus_after(key_down(KbKey::Tab)), Some(ids[2]));
+        assert_eq!(focus_after(key_down(KbKey::Tab)), Some(ids[0]));
+        assert_eq!(focus_after(shift_tab()), Some(ids[2]));
+        assert_eq!(focus_after(shift_tab()), Some(ids[1]));
+    }
+
+    #[test]
+    fn removed_focus_moves_to_a_neighbor() {
+        let mut harness = Harness::new();
+        let mut root = column(vec![button(), button(), button()]);
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        let ids: Vec<_> = children(&mut root).iter().map(Pod::id).collect();
+        harness.window_state.focus = Some(ids[1]);
+
+        children(&mut root).remove(1);
+        let _ = root.mark(ChangeFlags::TREE);
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        assert_eq!(harness.window_state.focus, Some(ids[2]));
+
+        // Without a following widget, the previous one takes focus.
+        children(&mut root).pop();
+        let _ = root.mark(ChangeFlags::TREE);
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        assert_eq!(harness.window_state.focus, Some(ids[0]));
+    }
+
+    #[test]
+    fn contained_tab_stays_in_the_scope() {
+        let mut harness = Harness::new();
+        let scope = FocusScope::new(column(vec![button(), button()]), true);
+        let mut root = column(vec![button(), Pod::new(scope), button()]);
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        let outside = children(&mut root)[0].id();
+        let scope = children(&mut root)[1].downcast_mut::<FocusScope>().unwrap();
+        let inside: Vec<_> = children(scope.child_mut()).iter().map(Pod::id).collect();
+
+        harness.window_state.focus = Some(outside);
+        harness.event(&mut root, key_down(KbKey::Tab));
+        assert_eq!(harness.window_state.focus, Some(inside[0]));
+        harness.event(&mut root, key_down(KbKey::Tab));
+        assert_eq!(harness.window_state.focus, Some(inside[1]));
+        harness.event(&mut root, key_down(KbKey::Tab));
+        assert_eq!(harness.window_state.focus, Some(inside[0]));
+        harness.event(&mut root, shift_tab());
+        assert_eq!(harness.window_state.focus, Some(inside[1]));
+    }
+}
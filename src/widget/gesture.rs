@@ -0,0 +1,578 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use bitflags::bitflags;
+use glazier::MouseButton;
+use vello::{
+    kurbo::{Point, Size, Vec2},
+    SceneBuilder,
+};
+
+use crate::{IdPath, Message};
+
+use super::{
+    AccessCx, BoxConstraints, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx, PaintCx, Pod,
+    PointerId, PointerKind, TimerToken, UpdateCx, Widget,
+};
+
+/// How far the pointer has to move while pressed before a drag starts.
+pub(crate) const DRAG_SLOP: f64 = 4.0;
+
+bitflags! {
+    /// The gestures a [`Gesture`] recognizes.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Gestures: u8 {
+        const CLICK = 1;
+        const DOUBLE_CLICK = 2;
+        const DRAG = 4;
+        const LONG_PRESS = 8;
+        const PINCH = 16;
+    }
+}
+
+/// The stage of a drag, see [`DragUpdate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DragPhase {
+    Start,
+    Move,
+    End,
+}
+
+/// The progress of a drag, in the coordinates of the dragged widget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DragUpdate {
+    pub phase: DragPhase,
+    /// The position of the pointer.
+    pub position: Point,
+    /// How far the pointer moved since the last update.
+    pub delta: Vec2,
+    /// How far the pointer moved since it was pressed.
+    pub total: Vec2,
+}
+
+/// The progress of a two finger pinch, in the coordinates of the pinched widget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PinchUpdate {
+    pub phase: DragPhase,
+    /// The point halfway between the fingers.
+    pub center: Point,
+    /// The distance between the fingers relative to where the pinch started, above 1 when they
+    /// spread apart.
+    pub scale: f64,
+}
+
+/// A gesture recognized by a [`Gesture`], sent to its view.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GestureEvent {
+    Click(Point),
+    DoubleClick(Point),
+    LongPress(Point),
+    Drag(DragUpdate),
+    Pinch(PinchUpdate),
+}
+
+/// A press of the primary button which has not been released yet.
+struct Press {
+    origin: Point,
+    last: Point,
+    long_press: Option<TimerToken>,
+    /// The number of the press in a series of quick presses, see
+    /// [`MouseEvent::click_count`](super::MouseEvent::click_count).
+    click_count: u8,
+    is_dragging: bool,
+    /// Set once the press turned into another gesture, so releasing it is no click.
+    is_consumed: bool,
+}
+
+/// A touch on the widget, followed for pinches.
+struct Touch {
+    id: PointerId,
+    pos: Point,
+}
+
+/// Recognizes clicks, double clicks, long presses, drags and pinches on a child and sends them
+/// to the view at `id_path` as [`GestureEvent`]s.
+///
+/// A drag starts once the pointer moved a few pixels while pressed, and keeps receiving the
+/// pointer until the button is released. A press which became a drag or a long press is no
+/// click. The second press of a [double click](super::MouseEvent::click_count) makes a double
+/// click instead, the third one a click again.
+///
+/// The first finger on a touchscreen acts like the mouse. Once a second finger comes down, the
+/// press of the first one is dropped and the two fingers pinch until one of them is lifted.
+pub struct Gesture {
+    id_path: IdPath,
+    child: Pod,
+    gestures: Gestures,
+    long_press_delay: Duration,
+    press: Option<Press>,
+    /// Up to two touches which came down on the widget.
+    touches: Vec<Touch>,
+    /// The distance between the touches when the pinch started, while they pinch.
+    pinch_start: Option<f64>,
+}
+
+impl Gesture {
+    pub const DEFAULT_LONG_PRESS_DELAY: Duration = Duration::from_millis(500);
+
+    pub fn new(id_path: &IdPath, child: Pod, gestures: Gestures) -> Self {
+        Gesture {
+            id_path: id_path.clone(),
+            child,
+            gestures,
+            long_press_delay: Self::DEFAULT_LONG_PRESS_DELAY,
+            press: None,
+            touches: vec![],
+            pinch_start: None,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    pub fn set_gestures(&mut self, gestures: Gestures) {
+        self.gestures = gestures;
+    }
+
+    pub fn set_long_press_delay(&mut self, delay: Duration) {
+        self.long_press_delay = delay;
+    }
+
+    fn send(&self, cx: &mut EventCx, event: GestureEvent) {
+        cx.add_message(Message::new(self.id_path.clone(), event));
+    }
+
+    fn press(&mut self, cx: &mut EventCx, pos: Point, click_count: u8) {
+        let long_press = self
+            .gestures
+            .contains(Gestures::LONG_PRESS)
+            .then(|| cx.request_timer(self.long_press_delay));
+        self.press = Some(Press {
+            origin: pos,
+            last: pos,
+            long_press,
+            click_count,
+            is_dragging: false,
+            is_consumed: false,
+        });
+        cx.capture_pointer();
+    }
+
+    fn drag_to(&mut self, cx: &mut EventCx, pos: Point) {
+        let Some(press) = &mut self.press else {
+            return;
+        };
+        if !press.is_dragging && (pos - press.origin).hypot() <= DRAG_SLOP {
+            return;
+        }
+        // Moving beyond the slop is no long press anymore.
+        if let Some(timer) = press.long_press.take() {
+            cx.cancel_timer(timer);
+        }
+        if !self.gestures.contains(Gestures::DRAG) {
+            return;
+        }
+        let phase = if press.is_dragging {
+            DragPhase::Move
+        } else {
+            press.is_dragging = true;
+            press.is_consumed = true;
+            DragPhase::Start
+        };
+        let update = DragUpdate {
+            phase,
+            position: pos,
+            delta: pos - press.last,
+            total: pos - press.origin,
+        };
+        press.last = pos;
+        self.send(cx, GestureEvent::Drag(update));
+    }
+
+    fn release(&mut self, cx: &mut EventCx, pos: Point) {
+        let Some(press) = self.press.take() else {
+            return;
+        };
+        cx.release_pointer();
+        if let Some(timer) = press.long_press {
+            cx.cancel_timer(timer);
+        }
+        if press.is_dragging {
+            let update = DragUpdate {
+                phase: DragPhase::End,
+                position: pos,
+                delta: pos - press.last,
+                total: pos - press.origin,
+            };
+            self.send(cx, GestureEvent::Drag(update));
+            return;
+        }
+        if press.is_consumed || !cx.is_hot() {
+            return;
+        }
+        let is_double = press.click_count % 2 == 0;
+        if is_double && self.gestures.contains(Gestures::DOUBLE_CLICK) {
+            self.send(cx, GestureEvent::DoubleClick(pos));
+        } else if self.gestures.contains(Gestures::CLICK) {
+            self.send(cx, GestureEvent::Click(pos));
+        }
+    }
+
+    /// Drops the press without a click, returning its long press timer to cancel and the end of
+    /// its drag, which ends where it was.
+    fn drop_press(&mut self) -> (Option<TimerToken>, Option<GestureEvent>) {
+        let Some(press) = self.press.take() else {
+            return (None, None);
+        };
+        let drag_end = press.is_dragging.then(|| {
+            GestureEvent::Drag(DragUpdate {
+                phase: DragPhase::End,
+                position: press.last,
+                delta: Vec2::ZERO,
+                total: press.last - press.origin,
+            })
+        });
+        (press.long_press, drag_end)
+    }
+
+    fn pinch_update(&self, phase: DragPhase) -> GestureEvent {
+        let [a, b] = [self.touches[0].pos, self.touches[1].pos];
+        let start = self.pinch_start.unwrap_or(1.0);
+        GestureEvent::Pinch(PinchUpdate {
+            phase,
+            center: a.midpoint(b),
+            scale: if start > 0.0 {
+                (b - a).hypot() / start
+            } else {
+                1.0
+            },
+        })
+    }
+
+    /// Follows the touches for pinches. Returns whether the event belonged to a pinch.
+    fn touch_event(&mut self, cx: &mut EventCx, event: &Event) -> bool {
+        let Some(pointer) = event.pointer() else {
+            return false;
+        };
+        let index = self
+            .touches
+            .iter()
+            .position(|touch| touch.id == pointer.pointer_id);
+        match (event, index) {
+            (Event::PointerDown(_), None) => {
+                if pointer.kind != PointerKind::Touch
+                    || self.touches.len() == 2
+                    || cx.is_handled()
+                    || !self.gestures.contains(Gestures::PINCH)
+                {
+                    return false;
+                }
+                cx.capture_pointer_id(pointer.pointer_id);
+                self.touches.push(Touch {
+                    id: pointer.pointer_id,
+                    pos: pointer.pos,
+                });
+                if self.touches.len() < 2 {
+                    // Until the second finger, the first one acts like the mouse.
+                    return false;
+                }
+                // The first finger stops acting like the mouse.
+                if self.press.is_some() {
+                    cx.release_pointer();
+                    let (long_press, drag_end) = self.drop_press();
+                    if let Some(timer) = long_press {
+                        cx.cancel_timer(timer);
+                    }
+                    if let Some(drag_end) = drag_end {
+                        self.send(cx, drag_end);
+                    }
+                }
+                let [a, b] = [self.touches[0].pos, self.touches[1].pos];
+                self.pinch_start = Some((b - a).hypot());
+                self.send(cx, self.pinch_update(DragPhase::Start));
+                true
+            }
+            (Event::PointerMove(_), Some(index)) => {
+                self.touches[index].pos = pointer.pos;
+                if self.pinch_start.is_none() {
+                    return false;
+                }
+                self.send(cx, self.pinch_update(DragPhase::Move));
+                true
+            }
+            (Event::PointerUp(_) | Event::PointerCancel(_), Some(index)) => {
+                let was_pinching = self.pinch_start.is_some();
+                if was_pinching {
+                    self.touches[index].pos = pointer.pos;
+                    self.send(cx, self.pinch_update(DragPhase::End));
+                    self.pinch_start = None;
+                }
+                self.touches.remove(index);
+                was_pinching
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Widget for Gesture {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.child.event(cx, event);
+        if self.touch_event(cx, event) {
+            cx.set_handled(true);
+            return;
+        }
+        match event {
+            // A press a child handled, like that of a button, is no gesture.
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left && !cx.is_handled() => {
+                self.press(cx, mouse.pos, mouse.click_count);
+                cx.set_handled(true);
+            }
+            Event::MouseMove(mouse) if self.press.is_some() => {
+                self.drag_to(cx, mouse.pos);
+                cx.set_handled(true);
+            }
+            Event::MouseUp(mouse) if mouse.button == MouseButton::Left && self.press.is_some() => {
+                self.release(cx, mouse.pos);
+                cx.set_handled(true);
+            }
+            Event::Timer(token) => {
+                let Some(press) = &mut self.press else {
+                    return;
+                };
+                if press.long_press == Some(*token) {
+                    press.long_press = None;
+                    press.is_consumed = true;
+                    let origin = press.origin;
+                    self.send(cx, GestureEvent::LongPress(origin));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        if let LifeCycle::PointerCaptureLost = event {
+            // A lost press is no click, and a drag ends where it was.
+            let (long_press, drag_end) = self.drop_press();
+            if let Some(timer) = long_press {
+                cx.cancel_timer(timer);
+            }
+            if let Some(drag_end) = drag_end {
+                cx.add_message(Message::new(self.id_path.clone(), drag_end));
+            }
+            return;
+        }
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.child.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{mouse, touch, Harness, Leaf};
+    use test_log::test;
+    use xilem_core::Id;
+
+    fn gesture(harness: &mut Harness) -> Pod {
+        let child = Pod::new(Leaf::new(100.0, 100.0));
+        let mut pod = Pod::new(Gesture::new(&vec![Id::next()], child, Gestures::all()));
+        harness.layout(&mut pod, &BoxConstraints::UNBOUNDED);
+        pod
+    }
+
+    fn gestures(harness: &mut Harness) -> Vec<GestureEvent> {
+        harness
+            .take_messages()
+            .into_iter()
+            .map(|message| *message.body.downcast::<GestureEvent>().unwrap())
+            .collect()
+    }
+
+    fn drag_phases(events: &[GestureEvent]) -> Vec<(DragPhase, Vec2)> {
+        events
+            .iter()
+            .map(|event| match event {
+                GestureEvent::Drag(update) => (update.phase, update.delta),
+                other => panic!("expected a drag, got {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn drag_starts_after_the_slop() {
+        let mut harness = Harness::new();
+        let mut pod = gesture(&mut harness);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut pod, Event::MouseMove(mouse((12.0, 11.0))));
+        assert!(gestures(&mut harness).is_empty());
+
+        harness.event(&mut pod, Event::MouseMove(mouse((20.0, 10.0))));
+        // Outside of the widget, the drag keeps the pointer.
+        harness.event(&mut pod, Event::MouseMove(mouse((150.0, 10.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((150.0, 10.0))));
+        assert_eq!(
+            drag_phases(&gestures(&mut harness)),
+            [
+                (DragPhase::Start, Vec2::new(10.0, 0.0)),
+                (DragPhase::Move, Vec2::new(130.0, 0.0)),
+                (DragPhase::End, Vec2::ZERO),
+            ]
+        );
+    }
+
+    #[test]
+    fn completed_drag_is_no_click() {
+        let mut harness = Harness::new();
+        let mut pod = gesture(&mut harness);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((11.0, 10.0))));
+        assert_eq!(
+            gestures(&mut harness),
+            [GestureEvent::Click(Point::new(11.0, 10.0))]
+        );
+
+        // Dragged away and back, released where it started.
+        harness.advance_time(&mut pod, Duration::from_secs(1));
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut pod, Event::MouseMove(mouse((40.0, 10.0))));
+        harness.event(&mut pod, Event::MouseMove(mouse((10.0, 10.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((10.0, 10.0))));
+        let phases = drag_phases(&gestures(&mut harness));
+        assert_eq!(phases.last().unwrap().0, DragPhase::End);
+    }
+
+    #[test]
+    fn second_click_close_by_is_a_double_click() {
+        let mut harness = Harness::new();
+        let mut pod = gesture(&mut harness);
+        let mut click = |harness: &mut Harness, pos: (f64, f64)| {
+            harness.event(&mut pod, Event::MouseDown(mouse(pos)));
+            harness.event(&mut pod, Event::MouseUp(mouse(pos)));
+        };
+
+        click(&mut harness, (10.0, 10.0));
+        click(&mut harness, (12.0, 10.0));
+        assert_eq!(
+            gestures(&mut harness),
+            [
+                GestureEvent::Click(Point::new(10.0, 10.0)),
+                GestureEvent::DoubleClick(Point::new(12.0, 10.0)),
+            ]
+        );
+
+        // Too far apart.
+        click(&mut harness, (10.0, 10.0));
+        click(&mut harness, (60.0, 10.0));
+        assert!(gestures(&mut harness)
+            .iter()
+            .all(|event| matches!(event, GestureEvent::Click(_))));
+    }
+
+    #[test]
+    fn movement_cancels_long_press() {
+        let mut harness = Harness::new();
+        let mut pod = gesture(&mut harness);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.advance_time(&mut pod, Duration::from_millis(600));
+        harness.event(&mut pod, Event::MouseUp(mouse((10.0, 10.0))));
+        assert_eq!(
+            gestures(&mut harness),
+            [GestureEvent::LongPress(Point::new(10.0, 10.0))]
+        );
+
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.advance_time(&mut pod, Duration::from_millis(100));
+        harness.event(&mut pod, Event::MouseMove(mouse((30.0, 10.0))));
+        harness.advance_time(&mut pod, Duration::from_millis(600));
+        let events = gestures(&mut harness);
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, GestureEvent::LongPress(_))));
+    }
+
+    #[test]
+    fn two_fingers_pinch() {
+        let mut harness = Harness::new();
+        let mut pod = gesture(&mut harness);
+        let pinch = |phase, center: (f64, f64), scale| {
+            GestureEvent::Pinch(PinchUpdate {
+                phase,
+                center: center.into(),
+                scale,
+            })
+        };
+
+        harness.event(&mut pod, Event::PointerDown(touch(0, (10.0, 10.0))));
+        harness.event(&mut pod, Event::PointerDown(touch(1, (30.0, 10.0))));
+        harness.event(&mut pod, Event::PointerMove(touch(1, (50.0, 10.0))));
+        // The first finger left the widget, but the gesture keeps it.
+        harness.event(&mut pod, Event::PointerMove(touch(0, (-30.0, 10.0))));
+        harness.event(&mut pod, Event::PointerUp(touch(1, (50.0, 10.0))));
+        harness.event(&mut pod, Event::PointerUp(touch(0, (-30.0, 10.0))));
+        // The press of the first finger turned into the pinch, so there is no click.
+        assert_eq!(
+            gestures(&mut harness),
+            [
+                pinch(DragPhase::Start, (20.0, 10.0), 1.0),
+                pinch(DragPhase::Move, (30.0, 10.0), 2.0),
+                pinch(DragPhase::Move, (10.0, 10.0), 4.0),
+                pinch(DragPhase::End, (10.0, 10.0), 4.0),
+            ]
+        );
+
+        // A single finger still taps.
+        harness.advance_time(&mut pod, Duration::from_secs(1));
+        harness.event(&mut pod, Event::PointerDown(touch(0, (10.0, 10.0))));
+        harness.event(&mut pod, Event::PointerUp(touch(0, (10.0, 10.0))));
+        assert_eq!(
+            gestures(&mut harness),
+            [GestureEvent::Click(Point::new(10.0, 10.0))]
+        );
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Layout and paint passes without a window, for the benchmarks in `benches/`.
+
+use parley::FontContext;
+use vello::kurbo::{Point, Size};
+use vello::SceneFragment;
+
+use crate::Message;
+
+use super::{
+    BoxConstraints, CxState, LayoutCx, LifeCycle, LifeCycleCx, PaintCx, Pod, WidgetState,
+    WindowState,
+};
+
+/// Runs the layout and paint passes of a widget tree like the frames of an app, without a
+/// window.
+///
+/// It exists for the benchmarks and is not part of the API.
+#[doc(hidden)]
+pub struct Headless {
+    window: glazier::WindowHandle,
+    font_cx: FontContext,
+    messages: Vec<Message>,
+    window_state: WindowState,
+    root_state: WidgetState,
+}
+
+impl Headless {
+    pub fn new() -> Self {
+        Headless {
+            window: Default::default(),
+            font_cx: FontContext::new(),
+            messages: vec![],
+            window_state: WindowState::new(),
+            root_state: WidgetState::new(),
+        }
+    }
+
+    /// Lays `pod` out at the origin.
+    pub fn layout(&mut self, pod: &mut Pod, bc: &BoxConstraints) -> Size {
+        let mut cx_state = CxState::new(
+            &self.window,
+            &mut self.font_cx,
+            &mut self.messages,
+            &mut self.window_state,
+        );
+        let mut lifecycle_cx = LifeCycleCx::new(&mut cx_state, &mut self.root_state);
+        pod.lifecycle(&mut lifecycle_cx, &LifeCycle::TreeUpdate);
+        let mut layout_cx = LayoutCx::new(&mut cx_state, &mut self.root_state);
+        let size = pod.layout(&mut layout_cx, bc);
+        pod.set_origin(&mut layout_cx, Point::ORIGIN);
+        size
+    }
+
+    /// Paints the widgets of `pod` which requested it, and returns the fragment of the tree.
+    pub fn paint<'a>(&mut self, pod: &'a mut Pod) -> &'a SceneFragment {
+        let mut cx_state = CxState::new(
+            &self.window,
+            &mut self.font_cx,
+            &mut self.messages,
+            &mut self.window_state,
+        );
+        let mut paint_cx = PaintCx::new(&mut cx_state, &mut self.root_state);
+        pod.paint_custom(&mut paint_cx)
+    }
+}
+
+impl Default for Headless {
+    fn default() -> Self {
+        Headless::new()
+    }
+}
@@ -0,0 +1,243 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use vello::{
+    kurbo::{Affine, Size},
+    peniko::Mix,
+    SceneBuilder,
+};
+
+use super::{
+    contexts::LifeCycleCx, piet_scene_helpers::UnitPoint, AccessCx, BoxConstraints, ChangeFlags,
+    Event, EventCx, LayoutCx, LifeCycle, PaintCx, UpdateCx, Widget,
+};
+
+/// How an [`Image`] is scaled into the box it is laid out in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Fit {
+    /// Stretches the image to the box, ignoring its aspect ratio.
+    Fill,
+    /// Scales the image to the largest size fitting into the box.
+    #[default]
+    Contain,
+    /// Scales the image to the smallest size covering the box, clipping the rest.
+    Cover,
+    /// Like `Contain`, but never scales the image up.
+    ScaleDown,
+}
+
+/// Displays a raster image.
+///
+/// Images are usually shared with other widgets through the [`ImageCache`](crate::ImageCache).
+pub struct Image {
+    image: Arc<vello::peniko::Image>,
+    fit: Fit,
+    alignment: UnitPoint,
+}
+
+impl Image {
+    pub fn new(image: Arc<vello::peniko::Image>) -> Image {
+        Image {
+            image,
+            fit: Fit::default(),
+            alignment: UnitPoint::CENTER,
+        }
+    }
+
+    pub fn set_image(&mut self, image: Arc<vello::peniko::Image>) -> ChangeFlags {
+        if Arc::ptr_eq(&self.image, &image) {
+            return ChangeFlags::empty();
+        }
+        let resized = self.intrinsic_size() != intrinsic_size(&image);
+        self.image = image;
+        if resized {
+            ChangeFlags::LAYOUT | ChangeFlags::PAINT
+        } else {
+            ChangeFlags::PAINT
+        }
+    }
+
+    pub fn set_fit(&mut self, fit: Fit) -> ChangeFlags {
+        if self.fit == fit {
+            return ChangeFlags::empty();
+        }
+        self.fit = fit;
+        ChangeFlags::LAYOUT | ChangeFlags::PAINT
+    }
+
+    /// Where the image is placed in its box when it doesn't fill it.
+    pub fn set_alignment(&mut self, alignment: UnitPoint) -> ChangeFlags {
+        if self.alignment == alignment {
+            return ChangeFlags::empty();
+        }
+        self.alignment = alignment;
+        ChangeFlags::PAINT
+    }
+
+    pub fn intrinsic_size(&self) -> Size {
+        intrinsic_size(&self.image)
+    }
+}
+
+fn intrinsic_size(image: &vello::peniko::Image) -> Size {
+    Size::new(image.width as f64, image.height as f64)
+}
+
+/// The size of an image box, given the image size and the constraints.
+fn layout_size(fit: Fit, intrinsic: Size, bc: &BoxConstraints) -> Size {
+    if intrinsic.is_empty() {
+        return bc.min();
+    }
+    let max = bc.max();
+    // Infinite in unbounded directions, which never limit the scale.
+    let scale = (max.width / intrinsic.width).min(max.height / intrinsic.height);
+    if !scale.is_finite() {
+        return bc.constrain(intrinsic);
+    }
+    match fit {
+        Fit::Contain => bc.max_area_size(intrinsic.height / intrinsic.width),
+        Fit::ScaleDown => bc.constrain(intrinsic * scale.min(1.0)),
+        // These take all the space they get.
+        Fit::Fill | Fit::Cover => {
+            let width = if bc.is_width_bounded() {
+                max.width
+            } else {
+                intrinsic.width * scale
+            };
+            let height = if bc.is_height_bounded() {
+                max.height
+            } else {
+                intrinsic.height * scale
+            };
+            bc.constrain(Size::new(width, height))
+        }
+    }
+}
+
+/// Maps the image into a box of `size`.
+fn image_transform(fit: Fit, alignment: UnitPoint, intrinsic: Size, size: Size) -> Affine {
+    let scale_x = size.width / intrinsic.width;
+    let scale_y = size.height / intrinsic.height;
+    let (scale_x, scale_y) = match fit {
+        Fit::Fill => (scale_x, scale_y),
+        Fit::Contain => (scale_x.min(scale_y), scale_x.min(scale_y)),
+        Fit::Cover => (scale_x.max(scale_y), scale_x.max(scale_y)),
+        Fit::ScaleDown => {
+            let scale = scale_x.min(scale_y).min(1.0);
+            (scale, scale)
+        }
+    };
+    let scaled = Size::new(intrinsic.width * scale_x, intrinsic.height * scale_y);
+    let free = (size - scaled).to_rect();
+    let origin = alignment.resolve(free);
+    Affine::translate(origin.to_vec2()) * Affine::scale_non_uniform(scale_x, scale_y)
+}
+
+impl Widget for Image {
+    fn event(&mut self, _cx: &mut EventCx, _event: &Event) {}
+
+    fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        cx.request_paint();
+    }
+
+    fn layout(&mut self, _cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        layout_size(self.fit, self.intrinsic_size(), bc)
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        cx.push_node(accesskit::NodeBuilder::new(accesskit::Role::Image));
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let intrinsic = self.intrinsic_size();
+        let size = cx.size();
+        if intrinsic.is_empty() || size.is_empty() {
+            return;
+        }
+        let transform = image_transform(self.fit, self.alignment, intrinsic, size);
+        // Covering images overflow their box.
+        builder.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &size.to_rect());
+        builder.draw_image(&self.image, transform);
+        builder.pop_layer();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+    use vello::kurbo::Point;
+
+    const INTRINSIC: Size = Size::new(200.0, 100.0);
+
+    fn loose(width: f64, height: f64) -> BoxConstraints {
+        BoxConstraints::new(Size::ZERO, Size::new(width, height))
+    }
+
+    #[test]
+    fn layout_size_per_fit() {
+        let larger = loose(400.0, 400.0);
+        let smaller = loose(100.0, 100.0);
+        assert_eq!(
+            layout_size(Fit::Contain, INTRINSIC, &larger),
+            Size::new(400.0, 200.0)
+        );
+        assert_eq!(
+            layout_size(Fit::Contain, INTRINSIC, &smaller),
+            Size::new(100.0, 50.0)
+        );
+        assert_eq!(layout_size(Fit::ScaleDown, INTRINSIC, &larger), INTRINSIC);
+        assert_eq!(
+            layout_size(Fit::ScaleDown, INTRINSIC, &smaller),
+            Size::new(100.0, 50.0)
+        );
+        assert_eq!(
+            layout_size(Fit::Fill, INTRINSIC, &larger),
+            Size::new(400.0, 400.0)
+        );
+        assert_eq!(
+            layout_size(Fit::Cover, INTRINSIC, &smaller),
+            Size::new(100.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn layout_size_with_unbounded_axis() {
+        let unbounded_height = loose(100.0, f64::INFINITY);
+        for fit in [Fit::Fill, Fit::Contain, Fit::Cover, Fit::ScaleDown] {
+            assert_eq!(
+                layout_size(fit, INTRINSIC, &unbounded_height),
+                Size::new(100.0, 50.0),
+                "{fit:?}"
+            );
+        }
+        let unbounded = loose(f64::INFINITY, f64::INFINITY);
+        assert_eq!(layout_size(Fit::Cover, INTRINSIC, &unbounded), INTRINSIC);
+    }
+
+    #[test]
+    fn transform_aligns_inside_the_box() {
+        let size = Size::new(100.0, 100.0);
+        let contain = image_transform(Fit::Contain, UnitPoint::BOTTOM, INTRINSIC, size);
+        assert_eq!(contain * Point::ZERO, Point::new(0.0, 50.0));
+        assert_eq!(contain * Point::new(200.0, 100.0), Point::new(100.0, 100.0));
+
+        let cover = image_transform(Fit::Cover, UnitPoint::CENTER, INTRINSIC, size);
+        assert_eq!(cover * Point::ZERO, Point::new(-50.0, 0.0));
+    }
+}
@@ -0,0 +1,359 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parley::{FontContext, Layout};
+use vello::{
+    kurbo::{Affine, Rect, Size},
+    peniko::{Brush, Color},
+    SceneBuilder,
+};
+
+use crate::text::ParleyBrush;
+use crate::Axis;
+
+use super::{
+    contexts::LifeCycleCx, AccessCx, BoxConstraints, ChangeFlags, DebugState, Event, EventCx,
+    Intrinsic, LayoutCx, LifeCycle, PaintCx, UpdateCx, Widget,
+};
+
+/// How the lines of a [`Label`] are aligned to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAlignment {
+    #[default]
+    Start,
+    Center,
+    End,
+    /// Stretches all lines but the last one to the full width.
+    Justify,
+}
+
+impl From<TextAlignment> for parley::layout::Alignment {
+    fn from(alignment: TextAlignment) -> Self {
+        match alignment {
+            TextAlignment::Start => parley::layout::Alignment::Start,
+            TextAlignment::Center => parley::layout::Alignment::Middle,
+            TextAlignment::End => parley::layout::Alignment::End,
+            TextAlignment::Justify => parley::layout::Alignment::Justified,
+        }
+    }
+}
+
+/// Static text which wraps to the available width.
+///
+/// With [`set_max_lines`](Label::set_max_lines), text which doesn't fit into that many lines is
+/// cut off with an ellipsis.
+pub struct Label {
+    text: String,
+    text_size: f32,
+    text_color: Color,
+    alignment: TextAlignment,
+    max_lines: Option<usize>,
+    /// The laid out text and the wrap width it was built for.
+    layout: Option<(Layout<ParleyBrush>, Option<f64>)>,
+    is_truncated: bool,
+}
+
+const DEFAULT_TEXT_SIZE: f32 = 16.0;
+const ELLIPSIS: char = '…';
+
+impl Label {
+    pub fn new(text: String) -> Label {
+        Label {
+            text,
+            text_size: DEFAULT_TEXT_SIZE,
+            text_color: Color::rgb8(0xf0, 0xf0, 0xea),
+            alignment: TextAlignment::default(),
+            max_lines: None,
+            layout: None,
+            is_truncated: false,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: String) -> ChangeFlags {
+        if self.text == text {
+            return ChangeFlags::empty();
+        }
+        self.text = text;
+        self.invalidate() | ChangeFlags::ACCESSIBILITY
+    }
+
+    pub fn set_text_size(&mut self, text_size: f32) -> ChangeFlags {
+        if self.text_size == text_size {
+            return ChangeFlags::empty();
+        }
+        self.text_size = text_size;
+        self.invalidate()
+    }
+
+    pub fn set_text_color(&mut self, text_color: Color) -> ChangeFlags {
+        if self.text_color == text_color {
+            return ChangeFlags::empty();
+        }
+        self.text_color = text_color;
+        // The color is applied when painting, the shaped text stays valid.
+        ChangeFlags::PAINT
+    }
+
+    pub fn set_alignment(&mut self, alignment: TextAlignment) -> ChangeFlags {
+        if self.alignment == alignment {
+            return ChangeFlags::empty();
+        }
+        self.alignment = alignment;
+        self.invalidate()
+    }
+
+    /// Limits the text to `max_lines` lines, `None` shows all of them.
+    ///
+    /// A limit of zero is treated as one line.
+    pub fn set_max_lines(&mut self, max_lines: Option<usize>) -> ChangeFlags {
+        let max_lines = max_lines.map(|lines| lines.max(1));
+        if self.max_lines == max_lines {
+            return ChangeFlags::empty();
+        }
+        self.max_lines = max_lines;
+        self.invalidate()
+    }
+
+    /// The number of lines shown after the last layout.
+    pub fn line_count(&self) -> usize {
+        self.layout.as_ref().map_or(0, |(layout, _)| layout.len())
+    }
+
+    /// Whether the last layout had to cut off the text to fit into the maximum lines.
+    pub fn is_truncated(&self) -> bool {
+        self.is_truncated
+    }
+
+    /// Rectangles of the given thickness which underline each line after the last layout.
+    pub(crate) fn underlines(&self, thickness: f64) -> Vec<Rect> {
+        let Some((layout, _)) = &self.layout else {
+            return vec![];
+        };
+        layout
+            .lines()
+            .map(|line| {
+                let metrics = line.metrics();
+                let x0 = metrics.offset as f64;
+                let x1 = (metrics.offset + metrics.advance - metrics.trailing_whitespace) as f64;
+                let y = (metrics.baseline + metrics.descent * 0.5) as f64;
+                Rect::new(x0, y, x1, y + thickness)
+            })
+            .collect()
+    }
+
+    fn invalidate(&mut self) -> ChangeFlags {
+        self.layout = None;
+        ChangeFlags::LAYOUT | ChangeFlags::PAINT
+    }
+
+    fn build_layout(
+        &self,
+        font_cx: &mut FontContext,
+        text: &str,
+        wrap_width: Option<f64>,
+    ) -> Layout<ParleyBrush> {
+        let mut lcx = parley::LayoutContext::new();
+        let mut layout_builder = lcx.ranged_builder(font_cx, text, 1.0);
+        layout_builder.push_default(&parley::style::StyleProperty::Brush(ParleyBrush(
+            Brush::Solid(self.text_color),
+        )));
+        layout_builder.push_default(&parley::style::StyleProperty::FontSize(self.text_size));
+        let mut layout = layout_builder.build();
+        layout.break_all_lines(wrap_width.map(|w| w as f32), self.alignment.into());
+        layout
+    }
+
+    /// Shapes the text for `wrap_width` unless the last layout was for the same width, and
+    /// returns the size of the shaped text.
+    fn shape(&mut self, font_cx: &mut FontContext, wrap_width: Option<f64>) -> Size {
+        let is_cached = matches!(&self.layout, Some((_, width)) if *width == wrap_width);
+        if !is_cached {
+            let layout = self.layout_text(font_cx, wrap_width);
+            self.layout = Some((layout, wrap_width));
+        }
+        let (layout, _) = self.layout.as_ref().unwrap();
+        Size::new(layout.width() as f64, layout.height() as f64)
+    }
+
+    /// Lays out the text, replacing its end with an ellipsis if it needs more than `max_lines`.
+    fn layout_text(
+        &mut self,
+        font_cx: &mut FontContext,
+        wrap_width: Option<f64>,
+    ) -> Layout<ParleyBrush> {
+        let layout = self.build_layout(font_cx, &self.text, wrap_width);
+        self.is_truncated = false;
+        let Some(max_lines) = self.max_lines.filter(|max_lines| layout.len() > *max_lines) else {
+            return layout;
+        };
+        self.is_truncated = true;
+
+        // Find the longest prefix of the visible lines which still fits with the ellipsis.
+        let visible_end = layout
+            .get(max_lines - 1)
+            .map_or(0, |line| line.text_range().end);
+        let boundaries: Vec<usize> = self.text[..visible_end]
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([visible_end])
+            .collect();
+        let mut best = self.build_layout(font_cx, &ELLIPSIS.to_string(), wrap_width);
+        let (mut fits, mut overflows) = (0, boundaries.len());
+        while overflows - fits > 1 {
+            let mid = (fits + overflows) / 2;
+            let candidate = format!("{}{ELLIPSIS}", self.text[..boundaries[mid]].trim_end());
+            let layout = self.build_layout(font_cx, &candidate, wrap_width);
+            if layout.len() <= max_lines {
+                fits = mid;
+                best = layout;
+            } else {
+                overflows = mid;
+            }
+        }
+        best
+    }
+}
+
+impl Widget for Label {
+    fn event(&mut self, _cx: &mut EventCx, _event: &Event) {}
+
+    fn lifecycle(&mut self, _cx: &mut LifeCycleCx, event: &LifeCycle) {
+        // The pod requests layout, where the text is shaped anew.
+        if let LifeCycle::ScaleChanged(_) = event {
+            self.layout = None;
+        }
+    }
+
+    fn update(&mut self, _cx: &mut UpdateCx) {}
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let wrap_width = bc.is_width_bounded().then(|| bc.max().width);
+        let size = bc.constrain(self.shape(cx.font_cx(), wrap_width));
+        let last_line = self
+            .layout
+            .as_ref()
+            .and_then(|(layout, _)| layout.lines().last());
+        if let Some(line) = last_line {
+            cx.set_baseline_offset(size.height - line.metrics().baseline as f64);
+        }
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::StaticText);
+        builder.set_name(self.text.as_str());
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, _cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        if let Some((layout, _)) = &self.layout {
+            let brush = Brush::Solid(self.text_color);
+            crate::text::render_text_with_brush(builder, Affine::IDENTITY, layout, &brush);
+        }
+    }
+
+    fn debug_state(&self) -> DebugState {
+        DebugState::new(self.short_type_name()).with_value("text", format!("{:?}", self.text))
+    }
+
+    fn compute_intrinsic(
+        &mut self,
+        cx: &mut LayoutCx,
+        axis: Axis,
+        kind: Intrinsic,
+        cross: f64,
+    ) -> f64 {
+        let wrap_width = match axis {
+            // Breaking at every opportunity leaves the longest word as the widest line.
+            Axis::Horizontal => (kind == Intrinsic::Min).then_some(0.0),
+            Axis::Vertical => cross.is_finite().then_some(cross),
+        };
+        axis.major(self.shape(cx.font_cx(), wrap_width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::Harness;
+    use crate::widget::Pod;
+    use test_log::test;
+
+    const TEXT: &str = "The quick brown fox jumps over the lazy dog";
+
+    fn layout(harness: &mut Harness, pod: &mut Pod, max_width: f64) -> Size {
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(max_width, 1000.0));
+        harness.layout(pod, &bc)
+    }
+
+    fn label(pod: &mut Pod) -> &mut Label {
+        pod.downcast_mut::<Label>().unwrap()
+    }
+
+    #[test]
+    fn wraps_to_max_width() {
+        let mut harness = Harness::new();
+        let mut pod = Pod::new(Label::new(TEXT.into()));
+
+        let wide = layout(&mut harness, &mut pod, 10_000.0);
+        assert_eq!(label(&mut pod).line_count(), 1);
+        assert!(wide.width > 0.0);
+
+        let narrow = layout(&mut harness, &mut pod, wide.width / 2.0);
+        assert!(narrow.width <= wide.width / 2.0);
+        assert!(label(&mut pod).line_count() >= 2);
+        assert!(narrow.height > wide.height);
+    }
+
+    #[test]
+    fn only_changes_invalidate_the_layout() {
+        let mut label = Label::new(TEXT.into());
+        assert_eq!(label.set_text(TEXT.into()), ChangeFlags::empty());
+        assert_eq!(label.set_text_size(DEFAULT_TEXT_SIZE), ChangeFlags::empty());
+        assert_eq!(label.set_max_lines(None), ChangeFlags::empty());
+        assert!(label.set_text_size(20.0).contains(ChangeFlags::LAYOUT));
+        assert!(label
+            .set_alignment(TextAlignment::Center)
+            .contains(ChangeFlags::LAYOUT));
+        assert_eq!(label.set_text_color(Color::RED), ChangeFlags::PAINT);
+    }
+
+    #[test]
+    fn ellipsis_only_when_lines_exceed_max() {
+        let mut harness = Harness::new();
+        let mut pod = Pod::new(Label::new(TEXT.into()));
+        let wide = layout(&mut harness, &mut pod, 10_000.0);
+        let max_width = wide.width / 3.0;
+        layout(&mut harness, &mut pod, max_width);
+        let lines = label(&mut pod).line_count();
+        assert!(lines >= 3);
+
+        let changes = label(&mut pod).set_max_lines(Some(lines));
+        let _ = pod.mark(changes);
+        layout(&mut harness, &mut pod, max_width);
+        assert!(!label(&mut pod).is_truncated());
+        assert_eq!(label(&mut pod).line_count(), lines);
+
+        let changes = label(&mut pod).set_max_lines(Some(lines - 1));
+        let _ = pod.mark(changes);
+        let size = layout(&mut harness, &mut pod, max_width);
+        assert!(label(&mut pod).is_truncated());
+        assert_eq!(label(&mut pod).line_count(), lines - 1);
+        assert!(size.width <= max_width);
+    }
+}
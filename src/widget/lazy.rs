@@ -0,0 +1,186 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vello::{
+    kurbo::{Point, Size},
+    SceneBuilder,
+};
+
+use crate::{IdPath, Message};
+
+use super::{
+    AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx,
+    PaintCx, Pod, UpdateCx, Widget,
+};
+
+/// A placeholder which asks its view for the real child once it is laid out.
+///
+/// Messages can't be sent during layout, so the request goes out with the
+/// [`LifeCycle::ViewContextChanged`] following the first layout. Containers which don't lay
+/// out hidden content, like collapsed sections or inactive tabs, keep it a placeholder. Once
+/// the child is set, the widget is transparent.
+pub struct Lazy {
+    id_path: IdPath,
+    child: Option<Pod>,
+    is_laid_out: bool,
+    is_requested: bool,
+}
+
+impl Lazy {
+    pub fn new(id_path: &IdPath) -> Self {
+        Lazy {
+            id_path: id_path.clone(),
+            child: None,
+            is_laid_out: false,
+            is_requested: false,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> Option<&mut Pod> {
+        self.child.as_mut()
+    }
+
+    /// Replaces the placeholder with `child`.
+    pub fn set_child(&mut self, mut child: Pod) -> ChangeFlags {
+        let changes = child.mark(
+            ChangeFlags::UPDATE
+                | ChangeFlags::LAYOUT
+                | ChangeFlags::ACCESSIBILITY
+                | ChangeFlags::PAINT,
+        );
+        self.child = Some(child);
+        changes | ChangeFlags::tree_structure()
+    }
+}
+
+impl Widget for Lazy {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        if let Some(child) = &mut self.child {
+            child.event(cx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        match &mut self.child {
+            Some(child) => child.lifecycle(cx, event),
+            None => {
+                if let LifeCycle::ViewContextChanged(_) = event {
+                    if self.is_laid_out && !self.is_requested {
+                        self.is_requested = true;
+                        cx.add_message(Message::new(self.id_path.clone(), ()));
+                    }
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        if let Some(child) = &mut self.child {
+            child.update(cx);
+        }
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        match &mut self.child {
+            Some(child) => {
+                let size = child.layout(cx, bc);
+                child.set_origin(cx, Point::ORIGIN);
+                size
+            }
+            None => {
+                if !self.is_laid_out {
+                    self.is_laid_out = true;
+                    cx.view_context_changed();
+                }
+                bc.min()
+            }
+        }
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        if let Some(child) = &mut self.child {
+            child.accessibility(cx);
+        }
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            if let Some(child) = &self.child {
+                builder.set_children(vec![child.id().into()]);
+            }
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        if let Some(child) = &mut self.child {
+            child.paint(cx, builder);
+        }
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        if let Some(child) = &self.child {
+            visit(child);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{Harness, Leaf};
+    use crate::widget::ViewContext;
+    use test_log::test;
+    use vello::kurbo::Rect;
+    use xilem_core::Id;
+
+    const WINDOW: Size = Size::new(100.0, 100.0);
+
+    fn view_context(harness: &mut Harness, root: &mut Pod) {
+        let view = ViewContext {
+            window_origin: Point::ORIGIN,
+            clip: Rect::from_origin_size(Point::ORIGIN, WINDOW),
+            mouse_position: None,
+        };
+        harness.lifecycle(root, LifeCycle::ViewContextChanged(view));
+    }
+
+    #[test]
+    fn child_is_requested_once_after_the_first_layout() {
+        let mut harness = Harness::new();
+        let id_path = vec![Id::next()];
+        let mut root = Pod::new(Lazy::new(&id_path));
+
+        // Without a layout, as in a collapsed section, nothing is requested.
+        view_context(&mut harness, &mut root);
+        harness.update(&mut root);
+        assert!(harness.take_messages().is_empty());
+
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        view_context(&mut harness, &mut root);
+        let messages = harness.take_messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id_path, id_path);
+
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        view_context(&mut harness, &mut root);
+        assert!(harness.take_messages().is_empty());
+
+        let lazy = root.downcast_mut::<Lazy>().unwrap();
+        let changes = lazy.set_child(Pod::new(Leaf::new(30.0, 20.0)));
+        assert!(changes.contains(ChangeFlags::tree_structure()));
+        let _ = root.mark(changes);
+        let size = harness.layout(&mut root, &BoxConstraints::new(Size::ZERO, WINDOW));
+        assert_eq!(size, Size::new(30.0, 20.0));
+    }
+}
@@ -12,13 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::geometry::Axis;
+use crate::geometry::{distribute_to_pixels, round_to_pixel, Axis, Direction, RectExt, Span};
 use crate::widget::{AccessCx, BoxConstraints, Event};
 use accesskit::NodeId;
 use vello::kurbo::Size;
 use vello::SceneBuilder;
 
-use super::{contexts::LifeCycleCx, EventCx, LayoutCx, LifeCycle, PaintCx, Pod, UpdateCx, Widget};
+use super::{
+    contexts::LifeCycleCx, ChangeFlags, DebugState, EventCx, Intrinsic, LayoutCx, LifeCycle,
+    PaintCx, Pod, PodSlots, UpdateCx, Widget,
+};
 
 /// LinearLayout is a simple widget which does layout for a ViewSequence.
 ///
@@ -27,25 +30,144 @@ use super::{contexts::LifeCycleCx, EventCx, LayoutCx, LifeCycle, PaintCx, Pod, U
 /// This Widget is only temporary and is probably going to be replaced by something like Druid's Flex
 /// widget.
 pub struct LinearLayout {
-    pub children: Vec<Pod>,
+    pub children: PodSlots,
     pub spacing: f64,
     pub axis: Axis,
+    /// Places the children starting at the high end of the axis, right to left or bottom to top.
+    pub reversed: bool,
+    pub overflow: Overflow,
+}
+
+/// Per child layout parameters of a [`LinearLayout`].
+///
+/// Each child carries its own, see [`Pod::set_linear_meta`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearMeta {
+    /// How much this child gives up when the children don't fit on the major axis.
+    ///
+    /// The deficit is distributed proportionally to `shrink × natural_size`. A value of `0.0`
+    /// makes the child rigid.
+    pub shrink: f64,
+}
+
+/// What a [`LinearLayout`] does when its children don't fit on the major axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// Children keep their natural size and overflow the layout.
+    #[default]
+    Visible,
+    /// Children are shrunk according to their [`LinearMeta::shrink`] factor.
+    Shrink,
+}
+
+impl Default for LinearMeta {
+    fn default() -> Self {
+        LinearMeta { shrink: 1.0 }
+    }
 }
 
 impl LinearLayout {
     pub fn new(children: Vec<Pod>, spacing: f64, axis: Axis) -> Self {
         LinearLayout {
-            children,
+            children: children.into(),
             spacing,
             axis,
+            reversed: false,
+            overflow: Overflow::default(),
+        }
+    }
+
+    pub fn set_spacing(&mut self, spacing: f64) -> ChangeFlags {
+        if self.spacing == spacing {
+            return ChangeFlags::empty();
+        }
+        self.spacing = spacing;
+        ChangeFlags::LAYOUT
+    }
+
+    pub fn set_axis(&mut self, axis: Axis) -> ChangeFlags {
+        if self.axis == axis {
+            return ChangeFlags::empty();
         }
+        self.axis = axis;
+        ChangeFlags::LAYOUT
     }
+
+    pub fn set_reversed(&mut self, reversed: bool) -> ChangeFlags {
+        if self.reversed == reversed {
+            return ChangeFlags::empty();
+        }
+        self.reversed = reversed;
+        ChangeFlags::LAYOUT
+    }
+
+    pub fn set_overflow(&mut self, overflow: Overflow) -> ChangeFlags {
+        if self.overflow == overflow {
+            return ChangeFlags::empty();
+        }
+        self.overflow = overflow;
+        ChangeFlags::LAYOUT
+    }
+
+    /// Returns the direction in which the children are placed.
+    pub fn direction(&self) -> Direction {
+        Direction::from((self.axis, self.reversed))
+    }
+
+    /// Returns the layout metadata of the child at `index`.
+    pub fn child_meta(&self, index: usize) -> LinearMeta {
+        self.children[index].linear_meta()
+    }
+}
+
+/// Removes `deficit` from `sizes` proportionally to `shrink × size`.
+///
+/// No entry is shrunk below its minimum. Since clamping at the minimum leaves part of the deficit
+/// unresolved, the distribution is repeated until the deficit is gone or every shrinkable entry
+/// reached its minimum. Returns the remaining deficit.
+fn distribute_shrink(sizes: &mut [f64], mins: &[f64], shrink: &[f64], mut deficit: f64) -> f64 {
+    // Guards against endless loops caused by rounding errors.
+    const EPSILON: f64 = 1e-9;
+
+    while deficit > EPSILON {
+        let total_weight: f64 = sizes
+            .iter()
+            .zip(mins)
+            .zip(shrink)
+            .filter(|((size, min), _)| *size > *min)
+            .map(|((size, _), shrink)| shrink * size)
+            .sum();
+        if total_weight <= 0.0 {
+            break;
+        }
+
+        let mut removed = 0.0;
+        for ((size, min), shrink) in sizes.iter_mut().zip(mins).zip(shrink) {
+            if *size <= *min {
+                continue;
+            }
+            let share = deficit * shrink * *size / total_weight;
+            let new_size = (*size - share).max(*min);
+            removed += *size - new_size;
+            *size = new_size;
+        }
+        if removed <= EPSILON {
+            break;
+        }
+        deficit -= removed;
+    }
+
+    deficit.max(0.0)
 }
 
 impl Widget for LinearLayout {
     fn event(&mut self, cx: &mut EventCx, event: &Event) {
-        for child in &mut self.children {
-            child.event(cx, event);
+        // The children on top get events first.
+        for index in Pod::paint_order(&self.children).into_iter().rev() {
+            self.children[index].event(cx, event);
+            if cx.is_handled() {
+                break;
+            }
         }
     }
 
@@ -62,25 +184,99 @@ impl Widget for LinearLayout {
     }
 
     fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
-        let child_bc = self.axis.with_major(*bc, 0.0..f64::INFINITY);
+        let child_bc = bc.unbounded_axis(self.axis);
         let child_count = self.children.len();
+        let total_spacing = self.spacing * child_count.saturating_sub(1) as f64;
+
+        let mut sizes: Vec<Size> = self
+            .children
+            .iter_mut()
+            .map(|child| child.layout(cx, &child_bc))
+            .collect();
+
+        let available = self.axis.major(bc.max());
+        let natural = sizes.iter().map(|size| self.axis.major(*size)).sum::<f64>() + total_spacing;
+        if self.overflow == Overflow::Shrink && available.is_finite() && natural > available {
+            let shrink: Vec<f64> = (0..child_count)
+                .map(|index| self.child_meta(index).shrink.max(0.0))
+                .collect();
+            let mut majors: Vec<f64> = sizes.iter().map(|size| self.axis.major(*size)).collect();
+            // Shrinkable children give up at most down to their minimum intrinsic extent.
+            let minor = self.axis.minor(bc.max());
+            let mins: Vec<f64> = self
+                .children
+                .iter_mut()
+                .zip(&majors)
+                .zip(&shrink)
+                .map(|((child, major), shrink)| {
+                    if *shrink > 0.0 {
+                        child.intrinsic(cx, self.axis, Intrinsic::Min, minor)
+                    } else {
+                        *major
+                    }
+                })
+                .collect();
+            distribute_shrink(&mut majors, &mins, &shrink, natural - available);
+            // Rounding the shrunk extents together keeps the last child at the end.
+            let snaps = cx.snaps_to_pixels();
+            if snaps {
+                distribute_to_pixels(&mut majors, cx.scale());
+            }
+
+            for (index, child) in self.children.iter_mut().enumerate() {
+                if shrink[index] > 0.0 {
+                    let shrunk_bc = if snaps {
+                        BoxConstraints::new_snapped(
+                            self.axis.with_major(bc.min(), 0.0),
+                            self.axis.with_major(bc.max(), majors[index]),
+                        )
+                    } else {
+                        self.axis.constraints(bc, 0.0, majors[index])
+                    };
+                    sizes[index] = child.layout(cx, &shrunk_bc);
+                }
+            }
+        }
 
         let mut major_used: f64 = 0.0;
         let mut max_minor: f64 = 0.0;
+        let mut starts = Vec::with_capacity(child_count);
 
-        for (index, child) in self.children.iter_mut().enumerate() {
-            let size = child.layout(cx, &child_bc);
-            child.set_origin(cx, self.axis.pack(major_used, 0.0));
-            major_used += self.axis.major(size);
+        for (index, size) in sizes.iter().enumerate() {
+            starts.push(major_used);
+            major_used += self.axis.major(*size);
             if index < child_count - 1 {
                 major_used += self.spacing;
+            }
+            max_minor = max_minor.max(self.axis.minor(*size));
+        }
+
+        let size = bc.constrain(self.axis.pack(major_used, max_minor));
 
-                println!("insert spacing {}", self.spacing);
+        // Rounding the positions rather than the gaps doesn't add up the rounding errors.
+        let (snaps, scale) = (cx.snaps_to_pixels(), cx.scale());
+        let direction = self.direction();
+        let extent = self.axis.major(size);
+        for (child, start) in self.children.iter_mut().zip(starts) {
+            let major = direction.resolve(start, self.axis.major(child.size()), extent);
+            if snaps && child.snaps_to_pixels() {
+                let major = round_to_pixel(major, scale);
+                child.set_snapped_origin(cx, self.axis.pack(major, 0.0));
+            } else {
+                child.set_origin(cx, self.axis.pack(major, 0.0));
             }
-            max_minor = max_minor.max(self.axis.minor(size));
         }
 
-        self.axis.pack(major_used, max_minor)
+        // Rows align with the text of their first child, columns with that of their last.
+        let baseline_child = match self.axis {
+            Axis::Horizontal => self.children.iter().next(),
+            Axis::Vertical => self.children.iter().next_back(),
+        };
+        if let Some(child) = baseline_child {
+            let baseline = child.layout_rect().y1 - child.baseline_offset();
+            cx.set_baseline_offset(size.height - baseline);
+        }
+        size
     }
 
     fn accessibility(&mut self, cx: &mut AccessCx) {
@@ -89,7 +285,7 @@ impl Widget for LinearLayout {
         }
 
         if cx.is_requested() {
-            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::Group);
             builder.set_children(
                 self.children
                     .iter()
@@ -101,9 +297,481 @@ impl Widget for LinearLayout {
     }
 
     fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
-        for child in &mut self.children {
-            println!("paint child!");
-            child.paint(cx, builder);
+        // Children which paint only beyond the layout are not painted.
+        let viewport = Span::new(0.0, self.axis.major(cx.size()));
+        for index in Pod::paint_order(&self.children) {
+            let child = &mut self.children[index];
+            if child.paint_rect().intersects_major(self.axis, viewport) {
+                child.paint(cx, builder);
+            }
+        }
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        for index in Pod::paint_order(&self.children) {
+            visit(&self.children[index]);
+        }
+    }
+
+    fn debug_state(&self) -> DebugState {
+        DebugState::new(self.short_type_name())
+            .with_value("axis", format!("{:?}", self.axis))
+            .with_value("children", self.children.len())
+            .with_value("spacing", self.spacing)
+    }
+
+    fn compute_intrinsic(
+        &mut self,
+        cx: &mut LayoutCx,
+        axis: Axis,
+        kind: Intrinsic,
+        cross: f64,
+    ) -> f64 {
+        let total_spacing = self.spacing * self.children.len().saturating_sub(1) as f64;
+        if axis == self.axis {
+            // Only shrinking children can go below their natural size.
+            let shrinks = kind == Intrinsic::Min && self.overflow == Overflow::Shrink;
+            let mut total = total_spacing;
+            for index in 0..self.children.len() {
+                let kind = if shrinks && self.child_meta(index).shrink > 0.0 {
+                    Intrinsic::Min
+                } else {
+                    Intrinsic::Max
+                };
+                total += self.children[index].intrinsic(cx, axis, kind, cross);
+            }
+            return total;
+        }
+
+        // On the minor axis, the children are measured with the major extent layout gives them.
+        let mut majors: Vec<f64> = self
+            .children
+            .iter_mut()
+            .map(|child| child.intrinsic(cx, self.axis, Intrinsic::Max, f64::INFINITY))
+            .collect();
+        let natural = majors.iter().sum::<f64>() + total_spacing;
+        let mut shrunk = vec![false; self.children.len()];
+        if self.overflow == Overflow::Shrink && cross.is_finite() && natural > cross {
+            let shrink: Vec<f64> = (0..self.children.len())
+                .map(|index| self.child_meta(index).shrink.max(0.0))
+                .collect();
+            let mins: Vec<f64> = self
+                .children
+                .iter_mut()
+                .zip(&majors)
+                .zip(&shrink)
+                .map(|((child, major), shrink)| {
+                    if *shrink > 0.0 {
+                        child.intrinsic(cx, self.axis, Intrinsic::Min, f64::INFINITY)
+                    } else {
+                        *major
+                    }
+                })
+                .collect();
+            distribute_shrink(&mut majors, &mins, &shrink, natural - cross);
+            if cx.snaps_to_pixels() {
+                distribute_to_pixels(&mut majors, cx.scale());
+            }
+            for (shrunk, shrink) in shrunk.iter_mut().zip(&shrink) {
+                *shrunk = *shrink > 0.0;
+            }
+        }
+
+        self.children
+            .iter_mut()
+            .zip(majors)
+            .zip(shrunk)
+            .map(|((child, major), shrunk)| {
+                let cross = if shrunk { major } else { f64::INFINITY };
+                child.intrinsic(cx, axis, kind, cross)
+            })
+            .fold(0.0, f64::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::is_on_pixel;
+    use crate::widget::test_util::{mouse, Harness, Leaf};
+    use crate::widget::{Button, CheckState, Checkbox, Label, Slider, TextBox};
+    use std::sync::{Arc, Mutex};
+    use test_log::test;
+    use vello::kurbo::Point;
+
+    fn icon() -> Pod {
+        Pod::new(Leaf {
+            size: Size::new(20.0, 20.0),
+            min_width: 20.0,
+        })
+    }
+
+    fn label() -> Pod {
+        Pod::new(Leaf {
+            size: Size::new(60.0, 16.0),
+            min_width: 10.0,
+        })
+    }
+
+    fn rigid(mut pod: Pod) -> Pod {
+        let _ = pod.set_linear_meta(LinearMeta { shrink: 0.0 });
+        pod
+    }
+
+    fn row(overflow: Overflow) -> Pod {
+        let mut row =
+            LinearLayout::new(vec![rigid(icon()), label(), label()], 0.0, Axis::Horizontal);
+        row.overflow = overflow;
+        Pod::new(row)
+    }
+
+    fn layout(row: &mut Pod, max_width: f64) -> Size {
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(max_width, 100.0));
+        Harness::new().layout(row, &bc)
+    }
+
+    fn frames(row: &mut Pod) -> Vec<(f64, f64)> {
+        row.downcast_mut::<LinearLayout>()
+            .unwrap()
+            .children
+            .iter()
+            .map(|child| (child.state.origin.x, child.state.size.width))
+            .collect()
+    }
+
+    #[test]
+    fn rigid_icon_with_shrinkable_labels() {
+        let mut row = row(Overflow::Shrink);
+        let size = layout(&mut row, 100.0);
+
+        assert_eq!(size, Size::new(100.0, 20.0));
+        assert_eq!(frames(&mut row), [(0.0, 20.0), (20.0, 40.0), (60.0, 40.0)]);
+        let row = row.downcast_mut::<LinearLayout>().unwrap();
+        assert_eq!(row.children[2].state.origin, Point::new(60.0, 0.0));
+    }
+
+    #[test]
+    fn shrink_stops_at_minimum() {
+        let mut row = row(Overflow::Shrink);
+        let size = layout(&mut row, 30.0);
+
+        // The children overflow the row, which stays within its constraints.
+        assert_eq!(size, Size::new(30.0, 20.0));
+        assert_eq!(frames(&mut row), [(0.0, 20.0), (20.0, 10.0), (30.0, 10.0)]);
+    }
+
+    #[test]
+    fn shrunk_thirds_fill_the_row() {
+        let halves = (0..3).map(|_| {
+            Pod::new(Leaf {
+                size: Size::new(50.0, 10.0),
+                min_width: 0.0,
+            })
+        });
+        let mut row = LinearLayout::new(halves.collect(), 0.0, Axis::Horizontal);
+        row.overflow = Overflow::Shrink;
+        let mut row = Pod::new(row);
+        let mut harness = Harness::new();
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(100.0, 100.0));
+
+        assert_eq!(harness.layout(&mut row, &bc), Size::new(100.0, 10.0));
+        assert_eq!(frames(&mut row), [(0.0, 34.0), (34.0, 33.0), (67.0, 33.0)]);
+
+        // At 1.5, each third is exactly 50 device pixels.
+        harness.set_scale(&mut row, 1.5);
+        assert_eq!(harness.layout(&mut row, &bc), Size::new(100.0, 10.0));
+        let device_frames: Vec<_> = frames(&mut row)
+            .into_iter()
+            .map(|(origin, width)| {
+                assert!(is_on_pixel(origin, 1.5) && is_on_pixel(width, 1.5));
+                ((origin * 1.5).round(), (width * 1.5).round())
+            })
+            .collect();
+        assert_eq!(device_frames, [(0.0, 50.0), (50.0, 50.0), (100.0, 50.0)]);
+    }
+
+    #[test]
+    fn shrink_is_weighted_by_natural_size() {
+        let mut sizes = [60.0, 20.0];
+        let remaining = distribute_shrink(&mut sizes, &[0.0, 0.0], &[1.0, 1.0], 40.0);
+
+        assert_eq!(remaining, 0.0);
+        assert_eq!(sizes, [30.0, 10.0]);
+    }
+
+    #[test]
+    fn visible_overflow_keeps_natural_sizes() {
+        let mut row = row(Overflow::Visible);
+        let size = layout(&mut row, 100.0);
+
+        assert_eq!(size, Size::new(100.0, 20.0));
+        assert_eq!(frames(&mut row), [(0.0, 20.0), (20.0, 60.0), (80.0, 60.0)]);
+    }
+
+    #[test]
+    fn reversed_row_fills_its_minimum_size_from_the_right() {
+        let mut row = row(Overflow::Visible);
+        row.downcast_mut::<LinearLayout>().unwrap().reversed = true;
+        let bc = BoxConstraints::new(Size::new(200.0, 30.0), Size::new(200.0, 100.0));
+        let size = Harness::new().layout(&mut row, &bc);
+
+        assert_eq!(size, Size::new(200.0, 30.0));
+        assert_eq!(
+            frames(&mut row),
+            [(180.0, 20.0), (120.0, 60.0), (60.0, 60.0)]
+        );
+    }
+
+    #[test]
+    fn shrink_factors_stay_with_their_children() {
+        let mut pod = row(Overflow::Shrink);
+        let row = pod.downcast_mut::<LinearLayout>().unwrap();
+        row.children.insert(0, label());
+        let _ = pod.mark(ChangeFlags::LAYOUT);
+        let size = layout(&mut pod, 160.0);
+
+        // Only the labels shrink, the icon moved to the second place.
+        assert_eq!(size, Size::new(160.0, 20.0));
+        assert_eq!(
+            frames(&mut pod),
+            [(0.0, 47.0), (47.0, 20.0), (67.0, 47.0), (114.0, 46.0)]
+        );
+    }
+
+    #[test]
+    fn reversed_row_starts_at_the_right() {
+        let mut row = row(Overflow::Visible);
+        row.downcast_mut::<LinearLayout>().unwrap().reversed = true;
+        let size = layout(&mut row, 200.0);
+
+        assert_eq!(size, Size::new(140.0, 20.0));
+        assert_eq!(frames(&mut row), [(120.0, 20.0), (60.0, 60.0), (0.0, 60.0)]);
+    }
+
+    #[test]
+    fn setters_request_layout_only_on_change() {
+        let mut pod = row(Overflow::Visible);
+        let row = pod.downcast_mut::<LinearLayout>().unwrap();
+        assert_eq!(row.set_spacing(row.spacing), ChangeFlags::empty());
+        assert_eq!(row.set_spacing(4.0), ChangeFlags::LAYOUT);
+        assert_eq!(row.set_axis(Axis::Horizontal), ChangeFlags::empty());
+        assert_eq!(row.set_axis(Axis::Vertical), ChangeFlags::LAYOUT);
+        assert_eq!(row.set_reversed(true), ChangeFlags::LAYOUT);
+        assert_eq!(row.set_overflow(Overflow::Visible), ChangeFlags::empty());
+        assert_eq!(row.set_overflow(Overflow::Shrink), ChangeFlags::LAYOUT);
+
+        let icon = &mut row.children[0];
+        let meta = icon.linear_meta();
+        assert_eq!(icon.set_linear_meta(meta), ChangeFlags::empty());
+        assert_eq!(
+            icon.set_linear_meta(LinearMeta::default()),
+            ChangeFlags::LAYOUT
+        );
+    }
+
+    /// A column of a shrinking row with labels, a rigid row with a button and a leaf.
+    fn nested() -> Pod {
+        let shrinking = vec![
+            Pod::new(Label::new("Shrinks down to its longest word".into())),
+            rigid(icon()),
+            Pod::new(Label::new("Another label".into())),
+        ];
+        let mut shrinking = LinearLayout::new(shrinking, 4.0, Axis::Horizontal);
+        shrinking.overflow = Overflow::Shrink;
+        let button = Button::new(&vec![], Pod::new(Label::new("Button".into())));
+        let rigid = vec![icon(), label(), Pod::new(button)];
+        let rigid = LinearLayout::new(rigid, 8.0, Axis::Horizontal);
+        let column = vec![Pod::new(shrinking), Pod::new(rigid), label()];
+        Pod::new(LinearLayout::new(column, 2.0, Axis::Vertical))
+    }
+
+    #[test]
+    fn intrinsics_match_layouts() {
+        let mut harness = Harness::new();
+        let mut pod = nested();
+        let max_width =
+            harness.intrinsic(&mut pod, Axis::Horizontal, Intrinsic::Max, f64::INFINITY);
+        let size = harness.layout(&mut pod, &BoxConstraints::UNBOUNDED);
+        assert_eq!(max_width, size.width);
+
+        for width in [max_width, 200.0, 120.0] {
+            let height = harness.intrinsic(&mut pod, Axis::Vertical, Intrinsic::Max, width);
+            let bc = BoxConstraints::new(Size::ZERO, Size::new(width, f64::INFINITY));
+            assert_eq!(
+                height,
+                harness.layout(&mut pod, &bc).height,
+                "width {width}"
+            );
+        }
+
+        // At its minimum width, the shrinking row has no room to spare.
+        let row = &mut pod.downcast_mut::<LinearLayout>().unwrap().children[0];
+        let min_width = harness.intrinsic(row, Axis::Horizontal, Intrinsic::Min, f64::INFINITY);
+        assert!(min_width < max_width);
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(min_width, f64::INFINITY));
+        assert_eq!(harness.layout(row, &bc).width, min_width);
+    }
+
+    #[test]
+    fn intrinsics_are_cached_until_layout_is_requested() {
+        let mut harness = Harness::new();
+        let mut pod = nested();
+        harness.layout(&mut pod, &BoxConstraints::UNBOUNDED);
+        let width = harness.intrinsic(&mut pod, Axis::Horizontal, Intrinsic::Max, f64::INFINITY);
+        assert_eq!(pod.state.intrinsics.len(), 1);
+
+        let column = pod.downcast_mut::<LinearLayout>().unwrap();
+        let leaf = column.children[2].downcast_mut::<Leaf>().unwrap();
+        leaf.size.width = width + 50.0;
+        let changes = column.children[2].mark(ChangeFlags::LAYOUT);
+        let _ = pod.mark(changes);
+        let wider = harness.intrinsic(&mut pod, Axis::Horizontal, Intrinsic::Max, f64::INFINITY);
+        assert_eq!(wider, width + 50.0);
+    }
+
+    type LayerLog = Arc<Mutex<Vec<(&'static str, &'static str)>>>;
+
+    /// A square which logs when it is painted and claims mouse presses.
+    struct Layer(&'static str, LayerLog);
+
+    impl Widget for Layer {
+        fn event(&mut self, cx: &mut EventCx, event: &Event) {
+            if let Event::MouseDown(_) = event {
+                self.1.lock().unwrap().push((self.0, "press"));
+                cx.set_handled(true);
+            }
         }
+
+        fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, _cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            Size::new(20.0, 20.0)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {
+            self.1.lock().unwrap().push((self.0, "paint"));
+        }
+    }
+
+    #[test]
+    fn z_index_orders_painting_and_hits() {
+        let log = LayerLog::default();
+        let children = vec![
+            Pod::new(Layer("first", log.clone())),
+            Pod::new(Layer("second", log.clone())),
+        ];
+        let ids: Vec<_> = children.iter().map(Pod::id).collect();
+        // The second child covers the right half of the first.
+        let mut root = Pod::new(LinearLayout::new(children, -10.0, Axis::Horizontal));
+        let mut harness = Harness::new();
+        let bc = BoxConstraints::UNBOUNDED;
+        let overlap = Point::new(15.0, 10.0);
+
+        harness.frame(&mut root, &bc);
+        harness.event(&mut root, Event::MouseDown(mouse(overlap)));
+        assert_eq!(
+            *log.lock().unwrap(),
+            [("first", "paint"), ("second", "paint"), ("second", "press")]
+        );
+        let target = root.widget_at(overlap).unwrap().target();
+        assert_eq!(target.map(|(id, _)| id), Some(ids[1]));
+        log.lock().unwrap().clear();
+
+        let row = root.downcast_mut::<LinearLayout>().unwrap();
+        let changes = row.children[0].set_z_index(1);
+        assert_eq!(changes, ChangeFlags::PAINT);
+        // The children paint again as well, to show their order.
+        for child in &mut row.children {
+            let _ = child.mark(ChangeFlags::PAINT);
+        }
+        let _ = root.mark(changes);
+        harness.frame(&mut root, &bc);
+        harness.event(&mut root, Event::MouseDown(mouse(overlap)));
+        assert_eq!(
+            *log.lock().unwrap(),
+            [("second", "paint"), ("first", "paint"), ("first", "press")]
+        );
+        let target = root.widget_at(overlap).unwrap().target();
+        assert_eq!(target.map(|(id, _)| id), Some(ids[0]));
+    }
+
+    #[test]
+    fn form_accessibility_tree() {
+        let mut harness = Harness::new();
+        let subscribe = Pod::new(Label::new("Subscribe".into()));
+        let subscribe_id: accesskit::NodeId = subscribe.id().into();
+        let send = Pod::new(Label::new("Send".into()));
+        let send_id: accesskit::NodeId = send.id().into();
+        let children = vec![
+            Pod::new(Label::new("Name".into())),
+            Pod::new(TextBox::new(&vec![], "Ada".into())),
+            Pod::new(Checkbox::new(&vec![], CheckState::Checked, Some(subscribe))),
+            Pod::new(Slider::new(&vec![], 0.5, 0.0..1.0, Axis::Horizontal)),
+            Pod::new(Button::new(&vec![], send)),
+        ];
+        let ids: Vec<accesskit::NodeId> = children.iter().map(|child| child.id().into()).collect();
+        let mut root = Pod::new(LinearLayout::new(children, 4.0, Axis::Vertical));
+        harness.frame(&mut root, &BoxConstraints::UNBOUNDED);
+
+        let update = harness.accessibility(&mut root);
+        let node = |id: accesskit::NodeId| {
+            let (_, node) = update
+                .nodes
+                .iter()
+                .find(|(node_id, _)| *node_id == id)
+                .unwrap();
+            node
+        };
+        let form = node(root.id().into());
+        assert_eq!(form.role(), accesskit::Role::Group);
+        assert_eq!(form.children(), ids);
+
+        let roles: Vec<_> = ids.iter().map(|id| node(*id).role()).collect();
+        assert_eq!(
+            roles,
+            [
+                accesskit::Role::StaticText,
+                accesskit::Role::TextField,
+                accesskit::Role::CheckBox,
+                accesskit::Role::Slider,
+                accesskit::Role::Button,
+            ]
+        );
+        assert_eq!(node(ids[0]).name(), Some("Name"));
+        assert_eq!(node(ids[1]).value(), Some("Ada"));
+        let checkbox = node(ids[2]);
+        assert_eq!(
+            checkbox.checked_state(),
+            Some(accesskit::CheckedState::True)
+        );
+        assert_eq!(checkbox.children(), [subscribe_id]);
+        assert_eq!(checkbox.labelled_by(), [subscribe_id]);
+        assert_eq!(node(subscribe_id).name(), Some("Subscribe"));
+        assert_eq!(node(ids[3]).numeric_value(), Some(0.5));
+        let button = node(ids[4]);
+        assert_eq!(button.labelled_by(), [send_id]);
+        assert_eq!(node(send_id).name(), Some("Send"));
+
+        // The bounds are in window coordinates.
+        let layout = root.downcast_mut::<LinearLayout>().unwrap();
+        let rect = layout.children[4].layout_rect();
+        let bounds = button.bounds().unwrap();
+        assert_eq!(
+            (bounds.x0, bounds.y0, bounds.x1, bounds.y1),
+            (rect.x0, rect.y0, rect.x1, rect.y1)
+        );
+
+        // Without changes, nothing is sent again, and a change sends only the widget's node.
+        assert!(harness.accessibility(&mut root).nodes.is_empty());
+        let layout = root.downcast_mut::<LinearLayout>().unwrap();
+        let changes = layout.children[4].mark(ChangeFlags::ACCESSIBILITY);
+        let _ = root.mark(changes);
+        let update = harness.accessibility(&mut root);
+        let sent: Vec<_> = update.nodes.iter().map(|(id, _)| *id).collect();
+        assert_eq!(sent, [ids[4]]);
     }
 }
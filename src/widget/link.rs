@@ -0,0 +1,200 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use glazier::{Cursor, KbKey};
+use vello::{kurbo::Size, peniko::Color, SceneBuilder};
+
+use crate::{IdPath, Message};
+
+use super::{
+    contexts::LifeCycleCx, paint_focus_ring, piet_scene_helpers, AccessCx, BoxConstraints,
+    ChangeFlags, Event, EventCx, Label, LayoutCx, LifeCycle, PaintCx, UpdateCx, Widget,
+    FOCUS_RING_EXTENT,
+};
+
+/// Text which is clicked like a [`Button`](super::Button) but looks like a link.
+///
+/// A click sends an empty message to the view at `id_path`, by releasing the mouse after
+/// pressing it, by Space or Enter while focused and by the accessibility default action. The
+/// whole layout box of the text counts for hovering and clicking, not only the glyphs, so short
+/// links are easier to hit.
+pub struct Link {
+    id_path: IdPath,
+    label: Label,
+}
+
+const LINK_COLOR: Color = Color::rgb8(0x5a, 0x9b, 0xe6);
+const UNDERLINE_THICKNESS: f64 = 1.0;
+
+impl Link {
+    pub fn new(id_path: &IdPath, text: String) -> Link {
+        let mut label = Label::new(text);
+        let _ = label.set_text_color(LINK_COLOR);
+        Link {
+            id_path: id_path.clone(),
+            label,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        self.label.text()
+    }
+
+    pub fn set_text(&mut self, text: String) -> ChangeFlags {
+        self.label.set_text(text)
+    }
+
+    fn click(&self, cx: &mut EventCx) {
+        cx.add_message(Message::new(self.id_path.clone(), ()));
+    }
+}
+
+impl Widget for Link {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        match event {
+            Event::MouseDown(_) => {
+                cx.set_active(true);
+                cx.request_focus();
+                cx.request_paint();
+            }
+            Event::MouseUp(_) => {
+                if cx.is_hot() && cx.is_active() {
+                    self.click(cx);
+                }
+                cx.set_active(false);
+                cx.request_paint();
+            }
+            Event::KeyDown(key_event) if cx.is_focused() => match &key_event.key {
+                KbKey::Enter => {
+                    self.click(cx);
+                    cx.set_handled(true);
+                }
+                KbKey::Character(c) if c == " " => {
+                    self.click(cx);
+                    cx.set_handled(true);
+                }
+                _ => (),
+            },
+            Event::TargetedAccessibilityAction(request) => {
+                if request.action == accesskit::Action::Default
+                    && cx.is_accesskit_target(request.target)
+                {
+                    self.click(cx);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        match event {
+            LifeCycle::HotChanged(is_hot) => {
+                if *is_hot {
+                    cx.override_cursor(&Cursor::Pointer);
+                }
+                cx.request_paint();
+            }
+            LifeCycle::BuildFocusChain => cx.register_for_focus(),
+            _ => (),
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        cx.request_layout();
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.label.layout(cx, bc);
+        cx.set_paint_insets(FOCUS_RING_EXTENT);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::Link);
+        builder.set_name(self.label.text());
+        builder.set_default_action_verb(accesskit::DefaultActionVerb::Click);
+        builder.add_action(accesskit::Action::Default);
+        builder.add_action(accesskit::Action::Focus);
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.label.paint(cx, builder);
+        if cx.is_hot() {
+            for underline in self.label.underlines(UNDERLINE_THICKNESS) {
+                piet_scene_helpers::fill_color(builder, &underline, LINK_COLOR);
+            }
+        }
+        paint_focus_ring(cx, builder);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{key_down, mouse, Harness};
+    use crate::widget::Pod;
+    use test_log::test;
+    use xilem_core::Id;
+
+    /// Lays out a link stretched to a box much wider than its text.
+    fn link(harness: &mut Harness) -> (IdPath, Pod, Size) {
+        let id_path = vec![Id::next()];
+        let mut pod = Pod::new(Link::new(&id_path, "docs".into()));
+        let text_size = harness.layout(&mut pod, &BoxConstraints::UNBOUNDED);
+        let bc = BoxConstraints::tight(Size::new(300.0, text_size.height));
+        harness.layout(&mut pod, &bc);
+        (id_path, pod, text_size)
+    }
+
+    #[test]
+    fn keyboard_activation_fires_when_focused() {
+        let mut harness = Harness::new();
+        let (id_path, mut pod, _) = link(&mut harness);
+
+        harness.event(&mut pod, key_down(KbKey::Enter));
+        assert!(harness.take_messages().is_empty());
+
+        harness.window_state.focus = Some(pod.id());
+        harness.event(&mut pod, key_down(KbKey::Enter));
+        harness.event(&mut pod, key_down(KbKey::Character(" ".into())));
+        harness.event(&mut pod, key_down(KbKey::Character("a".into())));
+
+        let messages = harness.take_messages();
+        assert_eq!(messages.len(), 2);
+        assert!(messages.iter().all(|message| message.id_path == id_path));
+    }
+
+    #[test]
+    fn whole_layout_box_is_hit() {
+        let mut harness = Harness::new();
+        let (_, mut pod, text_size) = link(&mut harness);
+        // Right of the glyphs, but inside the box the link was laid out with.
+        let pos = (text_size.width + 100.0, text_size.height * 0.5);
+
+        harness.event(&mut pod, Event::MouseMove(mouse(pos)));
+        assert!(pod.is_hot());
+        assert!(matches!(harness.window_state.cursor(), Cursor::Pointer));
+        harness.event(&mut pod, Event::MouseDown(mouse(pos)));
+        harness.event(&mut pod, Event::MouseUp(mouse(pos)));
+        assert_eq!(harness.take_messages().len(), 1);
+        assert_eq!(harness.window_state.focus, Some(pod.id()));
+
+        // Outside of the box nothing happens.
+        let outside = (310.0, text_size.height * 0.5);
+        harness.event(&mut pod, Event::MouseMove(mouse(outside)));
+        assert!(!pod.is_hot());
+        assert!(matches!(harness.window_state.cursor(), Cursor::Arrow));
+    }
+}
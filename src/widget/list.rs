@@ -13,31 +13,54 @@
 // limitations under the License.
 
 //! A virtualized list of items.
-//!
-//! Note that this for experimentation and may be hacky in a number of
-//! ways not ideal for production use.
 
 use std::collections::BTreeMap;
+use std::ops::Range;
 
-use druid_shell::kurbo::{Point, Rect, Size};
+use vello::{
+    kurbo::{Affine, Point, Rect, Size, Vec2},
+    peniko::Mix,
+    SceneBuilder,
+};
 
-use crate::{event::Event, id::IdPath, Widget};
+use crate::{id::Id, round_to_pixel, IdPath, Message};
 
 use super::{
-    EventCx, LayoutCx, LifeCycle, LifeCycleCx, PaintCx, Pod, PreparePaintCx, RawEvent, UpdateCx,
+    AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx,
+    PaintCx, Pod, UpdateCx, Widget,
 };
 
+/// A scrolling list of `n_items` rows of `item_height`, of which only the rows near the view
+/// exist as widgets.
+///
+/// The list asks the view at `id_path` for the rows it needs with a [`ListChildRequest`], and
+/// the view answers with [`set_child`](List::set_child) and
+/// [`remove_child`](List::remove_child). The row containing the focus stays realized when it
+/// scrolls out of view.
+///
+/// Assistive technologies see the total number of items and the realized rows with their
+/// positions in it. They scroll with [`accesskit::Action::SetScrollOffset`] on the list, and
+/// bring a row into view with [`accesskit::Action::ScrollIntoView`] or
+/// [`accesskit::Action::ScrollToPoint`] on it, which also focuses the row.
 pub struct List {
     id_path: IdPath,
     n_items: usize,
     item_height: f64,
     items: BTreeMap<usize, Pod>,
-    item_range: (usize, usize),
+    scroll: f64,
+    /// The offset of the rows in the last layout, rounded like their origins.
+    laid_out_offset: f64,
+    /// The rows asked for last, the view may not have set them yet.
+    requested: Range<usize>,
+    /// The row outside of `requested` which was kept for the focus.
+    kept: Option<usize>,
+    /// The row containing the focus.
+    focused: Option<usize>,
 }
 
 /// A request to change children.
 ///
-/// This is an event sent by the list widget to the client when the viewport changes.
+/// This is a message sent by the list widget to the client when the viewport changes.
 /// Correct handling is for each index in `add` to result in a `set_child` call, and
 /// corresponding for `remove` and `remove_child`.
 #[derive(Debug)]
@@ -46,102 +69,592 @@ pub struct ListChildRequest {
     pub remove: Vec<usize>,
 }
 
+/// The number of rows realized beyond each edge of the view.
+const OVERSCAN: usize = 10;
+
 impl List {
-    pub fn new(id_path: IdPath, n_items: usize, item_height: f64) -> Self {
+    pub fn new(id_path: &IdPath, n_items: usize, item_height: f64) -> Self {
         List {
-            id_path,
+            id_path: id_path.clone(),
             n_items,
             item_height,
             items: BTreeMap::new(),
-            item_range: (0, 0),
+            scroll: 0.0,
+            laid_out_offset: 0.0,
+            requested: 0..0,
+            kept: None,
+            focused: None,
+        }
+    }
+
+    pub fn n_items(&self) -> usize {
+        self.n_items
+    }
+
+    /// Changes the number of items, dropping the rows past the new end.
+    pub fn set_n_items(&mut self, n_items: usize) -> ChangeFlags {
+        if n_items == self.n_items {
+            return ChangeFlags::empty();
+        }
+        self.n_items = n_items;
+        let mut changes = ChangeFlags::LAYOUT | ChangeFlags::ACCESSIBILITY;
+        let realized = self.items.len();
+        self.items.retain(|index, _| *index < n_items);
+        if self.items.len() != realized {
+            changes |= ChangeFlags::tree_structure();
+        }
+        self.requested = self.requested.start.min(n_items)..self.requested.end.min(n_items);
+        self.kept = self.kept.filter(|index| *index < n_items);
+        self.focused = self.focused.filter(|index| *index < n_items);
+        // The rows announce the number of items as well.
+        for item in self.items.values_mut() {
+            item.expect_mut::<ListItem>().n_items = n_items;
+            changes |= item.mark(ChangeFlags::ACCESSIBILITY);
+        }
+        changes
+    }
+
+    pub fn set_item_height(&mut self, item_height: f64) -> ChangeFlags {
+        if item_height == self.item_height {
+            return ChangeFlags::empty();
+        }
+        self.item_height = item_height;
+        ChangeFlags::LAYOUT | ChangeFlags::ACCESSIBILITY | ChangeFlags::PAINT
+    }
+
+    /// How far the list is scrolled down.
+    pub fn scroll_offset(&self) -> f64 {
+        self.scroll
+    }
+
+    /// Sets the widget of the row at index `i`, replacing the one there.
+    pub fn set_child(&mut self, i: usize, child: Pod) -> ChangeFlags {
+        let item = ListItem {
+            content: child,
+            index: i,
+            n_items: self.n_items,
+        };
+        self.items.insert(i, Pod::new(item));
+        ChangeFlags::tree_structure() | ChangeFlags::LAYOUT | ChangeFlags::ACCESSIBILITY
+    }
+
+    pub fn remove_child(&mut self, i: usize) -> ChangeFlags {
+        match self.items.remove(&i) {
+            Some(_) => {
+                ChangeFlags::tree_structure() | ChangeFlags::LAYOUT | ChangeFlags::ACCESSIBILITY
+            }
+            None => ChangeFlags::empty(),
+        }
+    }
+
+    /// The widget of the row at index `i`, if it is realized.
+    pub fn child_mut(&mut self, i: usize) -> Option<&mut Pod> {
+        let item = self.items.get_mut(&i)?;
+        Some(&mut item.expect_mut::<ListItem>().content)
+    }
+
+    fn max_scroll(&self, height: f64) -> f64 {
+        (self.n_items as f64 * self.item_height - height).max(0.0)
+    }
+
+    fn set_scroll(&mut self, cx: &mut EventCx, scroll: f64) {
+        let scroll = scroll.clamp(0.0, self.max_scroll(cx.size().height));
+        if scroll != self.scroll {
+            self.scroll = scroll;
+            // Layout moves the rows and requests the paint.
+            cx.request_layout();
+            cx.request_accessibility();
         }
     }
 
-    pub fn set_child(&mut self, i: usize, child: Pod) {
-        self.items.insert(i, child);
+    /// The rows to realize for the part of the list in view, `visible` in the coordinates of
+    /// the widget.
+    fn range_for(&self, visible: Rect) -> Range<usize> {
+        if visible.is_empty() || self.item_height <= 0.0 {
+            return 0..0;
+        }
+        let first = ((visible.y0 + self.scroll) / self.item_height).floor() as usize;
+        let end = ((visible.y1 + self.scroll) / self.item_height).ceil() as usize;
+        let end = end.saturating_add(OVERSCAN).min(self.n_items);
+        first.saturating_sub(OVERSCAN).min(end)..end
     }
 
-    pub fn remove_child(&mut self, i: usize) {
-        self.items.remove(&i);
+    /// Asks the view for the rows of `range` and the focused row, and to drop the others.
+    fn request_items(&mut self, cx: &mut LifeCycleCx, range: Range<usize>) {
+        let kept = self.focused.filter(|index| !range.contains(index));
+        if range == self.requested && kept == self.kept {
+            return;
+        }
+        let was_requested =
+            |index: &usize| self.requested.contains(index) || self.kept == Some(*index);
+        let is_wanted = |index: &usize| range.contains(index) || kept == Some(*index);
+        let add = range
+            .clone()
+            .chain(kept)
+            .filter(|index| !was_requested(index))
+            .collect();
+        let remove = self
+            .requested
+            .clone()
+            .chain(self.kept)
+            .filter(|index| !is_wanted(index))
+            .collect();
+        cx.add_message(Message::new(
+            self.id_path.clone(),
+            ListChildRequest { add, remove },
+        ));
+        self.requested = range;
+        self.kept = kept;
     }
 
-    /// Note: this will panic if the child is not set. The client is
-    /// responsible for tracking which children are set.
-    pub fn child_mut(&mut self, i: usize) -> &mut Pod {
-        self.items.get_mut(&i).unwrap()
+    /// Handles an action on the row at `index`, returns whether it was one the list handles.
+    fn item_action(
+        &mut self,
+        cx: &mut EventCx,
+        index: usize,
+        request: &accesskit::ActionRequest,
+    ) -> bool {
+        let top = index as f64 * self.item_height;
+        let height = cx.size().height;
+        let scroll = match (request.action, &request.data) {
+            (accesskit::Action::ScrollIntoView, _) => {
+                if top < self.scroll {
+                    top
+                } else if top + self.item_height > self.scroll + height {
+                    top + self.item_height - height
+                } else {
+                    self.scroll
+                }
+            }
+            (
+                accesskit::Action::ScrollToPoint,
+                Some(accesskit::ActionData::ScrollToPoint(point)),
+            ) => top - (point.y - cx.window_origin().y),
+            _ => return false,
+        };
+        self.set_scroll(cx, scroll);
+        // The row is realized, and the focus keeps it so while the rows around it are requested.
+        if let Some(item) = self.items.get(&index) {
+            cx.cx_state.window_state().focus = Some(item.id());
+        }
+        true
     }
 }
 
 impl Widget for List {
-    fn event(&mut self, cx: &mut EventCx, event: &RawEvent) {
-        for (_, child) in &mut self.items {
-            child.event(cx, event);
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        match event {
+            Event::TargetedAccessibilityAction(request)
+                if cx.is_accesskit_target(request.target) =>
+            {
+                if let (
+                    accesskit::Action::SetScrollOffset,
+                    Some(accesskit::ActionData::SetScrollOffset(offset)),
+                ) = (request.action, &request.data)
+                {
+                    self.set_scroll(cx, offset.y);
+                }
+            }
+            Event::TargetedAccessibilityAction(request) => {
+                let target = Id::try_from_accesskit(request.target);
+                let index = self
+                    .items
+                    .iter()
+                    .find(|(_, item)| Some(item.id()) == target)
+                    .map(|(index, _)| *index);
+                match index {
+                    Some(index) if self.item_action(cx, index, request) => cx.set_handled(true),
+                    _ => {
+                        for item in self.items.values_mut() {
+                            item.event(cx, event);
+                        }
+                    }
+                }
+            }
+            _ => {
+                for item in self.items.values_mut() {
+                    item.event(cx, event);
+                }
+            }
+        }
+    }
+
+    fn capture_event(&mut self, cx: &mut EventCx, event: &Event) {
+        // The wheel scrolls the list, even over rows which take it themselves, like sliders. Lists
+        // in the rows scroll on their own.
+        if let Event::MouseWheel(mouse) = event {
+            if !self.items.values().any(|item| item.scrolls_at(mouse.pos)) {
+                self.set_scroll(cx, self.scroll + mouse.wheel_delta.y);
+                cx.set_handled(true);
+            }
         }
     }
 
     fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
-        for (_, child) in &mut self.items {
-            child.lifecycle(cx, event);
+        for item in self.items.values_mut() {
+            item.lifecycle(cx, event);
+        }
+        match event {
+            LifeCycle::Added => cx.set_scrolls_with_wheel(),
+            LifeCycle::ViewContextChanged(view) => {
+                let visible = view.clip.intersect(cx.size().to_rect());
+                let range = self.range_for(visible);
+                self.request_items(cx, range);
+            }
+            LifeCycle::RouteFocusChanged { .. } => {
+                let focused = self
+                    .items
+                    .iter()
+                    .find(|(_, item)| item.has_focus_within())
+                    .map(|(index, _)| *index);
+                if focused != self.focused {
+                    self.focused = focused;
+                    // A row kept for the focus may go now.
+                    cx.view_context_changed();
+                }
+            }
+            _ => (),
         }
     }
 
     fn update(&mut self, cx: &mut UpdateCx) {
-        for (_, child) in &mut self.items {
-            child.update(cx);
+        for item in self.items.values_mut() {
+            item.update(cx);
         }
     }
 
-    fn measure(&mut self, cx: &mut LayoutCx) -> (Size, Size) {
-        for (_, child) in &mut self.items {
-            child.measure(cx);
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let width = if bc.max().width.is_finite() {
+            bc.max().width
+        } else {
+            bc.min().width
+        };
+        let size = bc.constrain(Size::new(width, self.n_items as f64 * self.item_height));
+        self.scroll = self.scroll.clamp(0.0, self.max_scroll(size.height));
+        let offset = if cx.snaps_to_pixels() {
+            round_to_pixel(-self.scroll, cx.scale())
+        } else {
+            -self.scroll
+        };
+        // The rows in view move as a whole, only the rows they uncover are presented anew.
+        cx.request_scroll_paint(Vec2::new(0.0, offset - self.laid_out_offset));
+        self.laid_out_offset = offset;
+        let item_bc = BoxConstraints::tight(Size::new(size.width, self.item_height));
+        for (index, item) in &mut self.items {
+            item.layout(cx, &item_bc);
+            let y = *index as f64 * self.item_height - self.scroll;
+            item.set_origin(cx, Point::new(0.0, y));
         }
-        let height = self.n_items as f64 * self.item_height;
-        (Size::new(0.0, height), Size::new(1e9, height))
+        // The rows in view are requested with the view context, messages can't be sent here.
+        cx.view_context_changed();
+        size
     }
 
-    fn layout(&mut self, cx: &mut LayoutCx, proposed_size: Size) -> Size {
-        // TODO: recurse to children
-        let child_proposed = Size::new(proposed_size.width, self.item_height);
-        for (i, child) in &mut self.items {
-            let _ = child.layout(cx, child_proposed);
-            child.state.origin = Point::new(0.0, *i as f64 * self.item_height);
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        for item in self.items.values_mut() {
+            item.accessibility(cx);
+        }
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::List);
+            builder.set_children(
+                self.items
+                    .values()
+                    .map(|item| item.id().into())
+                    .collect::<Vec<accesskit::NodeId>>(),
+            );
+            builder.set_size_of_set(self.n_items);
+            builder.set_scroll_y(self.scroll);
+            builder.set_scroll_y_min(0.0);
+            builder.set_scroll_y_max(self.max_scroll(cx.size().height));
+            builder.add_action(accesskit::Action::SetScrollOffset);
+            cx.push_node(builder);
         }
-        let height = self.n_items as f64 * self.item_height;
-        Size::new(proposed_size.width, height)
     }
 
-    fn prepare_paint(&mut self, cx: &mut PreparePaintCx, visible: Rect) {
-        let start = ((visible.y0 / self.item_height).floor() as usize).saturating_sub(10).min(self.n_items);
-        let end = ((visible.y1 / self.item_height).ceil() as usize + 10).min(self.n_items);
-        if (start, end) != self.item_range {
-            // item range has changed, send a request
-            let mut add = Vec::new();
-            let mut remove = Vec::new();
-            if self.item_range.1 <= start || self.item_range.0 >= end {
-                add.extend(start..end);
-                remove.extend(self.item_range.0..self.item_range.1);
-            } else {
-                if self.item_range.0 < start {
-                    remove.extend(self.item_range.0..start);
-                } else if start < self.item_range.0 {
-                    add.extend((start..self.item_range.0).rev());
-                }
-                if self.item_range.1 < end {
-                    add.extend(self.item_range.1..end);
-                } else if end < self.item_range.1 {
-                    remove.extend(end..self.item_range.1);
-                }
-            }
-            let req = ListChildRequest { add, remove };
-            //println!("req: {:?}", req);
-            cx.add_event(Event::new(self.id_path.clone(), req));
-            self.item_range = (start, end);
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let rect = cx.size().to_rect();
+        builder.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &rect);
+        for item in self.items.values_mut() {
+            item.paint(cx, builder);
         }
+        builder.pop_layer();
     }
 
-    fn paint(&mut self, cx: &mut PaintCx) {
-        for (_, child) in &mut self.items {
-            child.paint(cx);
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        self.items.values().for_each(visit);
+    }
+}
+
+/// A row of a [`List`], wrapping the widget of an item with its position for accessibility.
+struct ListItem {
+    content: Pod,
+    index: usize,
+    n_items: usize,
+}
+
+impl Widget for ListItem {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.content.event(cx, event);
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.content.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.content.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = bc.max();
+        self.content
+            .layout(cx, &BoxConstraints::new(Size::ZERO, size));
+        self.content.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.content.accessibility(cx);
+
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::ListItem);
+        builder.set_children(vec![self.content.id().into()]);
+        builder.set_position_in_set(self.index + 1);
+        builder.set_size_of_set(self.n_items);
+        builder.add_action(accesskit::Action::Focus);
+        builder.add_action(accesskit::Action::ScrollIntoView);
+        builder.add_action(accesskit::Action::ScrollToPoint);
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.content.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Axis;
+    use crate::widget::test_util::{mouse, Harness, Leaf};
+    use crate::widget::{Slider, SliderMessage};
+    use accesskit::{Action, ActionData, ActionRequest, NodeId, TreeUpdate};
+    use test_log::test;
+
+    const WINDOW: Size = Size::new(200.0, 240.0);
+    const ROW: f64 = 24.0;
+
+    /// Answers the requests of the list like its view, with a leaf per row.
+    fn serve(harness: &mut Harness, root: &mut Pod) -> Vec<ListChildRequest> {
+        let requests: Vec<_> = harness
+            .take_messages()
+            .into_iter()
+            .map(|message| *message.body.downcast::<ListChildRequest>().unwrap())
+            .collect();
+        let list = root.downcast_mut::<List>().unwrap();
+        let mut changes = ChangeFlags::empty();
+        for request in &requests {
+            for index in &request.remove {
+                changes |= list.remove_child(*index);
+            }
+            for index in &request.add {
+                changes |= list.set_child(*index, Pod::new(Leaf::new(100.0, 20.0)));
+            }
         }
+        let _ = root.mark(changes);
+        harness.frame(root, &BoxConstraints::tight(WINDOW));
+        requests
+    }
+
+    fn action(target: NodeId, action: Action, data: Option<ActionData>) -> Event {
+        Event::TargetedAccessibilityAction(ActionRequest {
+            action,
+            target,
+            data,
+        })
+    }
+
+    fn item_at(update: &TreeUpdate, position: usize) -> (NodeId, &accesskit::Node) {
+        let (id, node) = update
+            .nodes
+            .iter()
+            .find(|(_, node)| node.position_in_set() == Some(position))
+            .unwrap();
+        (*id, node)
+    }
+
+    #[test]
+    fn actions_realize_and_focus_far_items() {
+        let mut harness = Harness::new();
+        let bc = BoxConstraints::tight(WINDOW);
+        let mut root = Pod::new(List::new(&vec![], 100_000, ROW));
+        let list_id: NodeId = root.id().into();
+        harness.frame(&mut root, &bc);
+
+        // Ten rows are in view, ten more are realized below them.
+        let requests = serve(&mut harness, &mut root);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].add, (0..20).collect::<Vec<_>>());
+        let messages = harness.take_messages();
+        assert!(!messages.iter().any(|message| message.is::<SliderMessage>()));
+
+        let update = harness.accessibility(&mut root);
+        let (_, list_node) = update.nodes.iter().find(|(id, _)| *id == list_id).unwrap();
+        assert_eq!(list_node.role(), accesskit::Role::List);
+        assert_eq!(list_node.size_of_set(), Some(100_000));
+        assert_eq!(list_node.children().len(), 20);
+
+        // An assistive technology scrolls to the item at index 5000.
+        let offset = accesskit::Point::new(0.0, 5000.0 * ROW);
+        let scroll = action(
+            list_id,
+            Action::SetScrollOffset,
+            Some(ActionData::SetScrollOffset(offset)),
+        );
+        harness.event(&mut root, scroll);
+        harness.frame(&mut root, &bc);
+        let requests = serve(&mut harness, &mut root);
+        assert_eq!(requests[0].add, (4990..5020).collect::<Vec<_>>());
+        assert_eq!(requests[0].remove, (0..20).collect::<Vec<_>>());
+        let list = root.downcast_mut::<List>().unwrap();
+        assert_eq!(list.scroll_offset(), 5000.0 * ROW);
+        assert!(list.child_mut(5000).is_some());
+        assert!(list.child_mut(0).is_none());
+
+        let update = harness.accessibility(&mut root);
+        let (item_id, item) = item_at(&update, 5001);
+        assert_eq!(item.role(), accesskit::Role::ListItem);
+        assert_eq!(item.size_of_set(), Some(100_000));
+        assert_eq!(item.bounds().unwrap().y0, 0.0);
+        let (_, list_node) = update.nodes.iter().find(|(id, _)| *id == list_id).unwrap();
+        assert_eq!(list_node.children()[10], item_id);
+
+        // Bringing a row below the view into it scrolls just enough and focuses it.
+        let (item_id, _) = item_at(&update, 5016);
+        harness.event(&mut root, action(item_id, Action::ScrollIntoView, None));
+        assert_eq!(harness.window_state.focus, Id::try_from_accesskit(item_id));
+        harness.frame(&mut root, &bc);
+        let requests = serve(&mut harness, &mut root);
+        assert_eq!(requests[0].add, (5020..5026).collect::<Vec<_>>());
+        assert_eq!(requests[0].remove, (4990..4996).collect::<Vec<_>>());
+        let list = root.downcast_mut::<List>().unwrap();
+        assert_eq!(list.scroll_offset(), 5016.0 * ROW - WINDOW.height);
+
+        // The focused row stays when the list scrolls far away.
+        let top = action(
+            list_id,
+            Action::SetScrollOffset,
+            Some(ActionData::SetScrollOffset(accesskit::Point::ZERO)),
+        );
+        harness.event(&mut root, top);
+        harness.frame(&mut root, &bc);
+        let requests = serve(&mut harness, &mut root);
+        assert_eq!(requests[0].add, (0..20).collect::<Vec<_>>());
+        assert_eq!(requests[0].remove.len(), 29);
+        assert!(!requests[0].remove.contains(&5015));
+        let list = root.downcast_mut::<List>().unwrap();
+        assert!(list.child_mut(5015).is_some());
+        let update = harness.accessibility(&mut root);
+        let (_, list_node) = update.nodes.iter().find(|(id, _)| *id == list_id).unwrap();
+        assert_eq!(list_node.children().len(), 21);
+        assert_eq!(list_node.children()[20], item_id);
+    }
+
+    #[test]
+    fn scrolling_damages_only_the_exposed_strip() {
+        let mut harness = Harness::new();
+        let bc = BoxConstraints::tight(WINDOW);
+        let mut root = Pod::new(List::new(&vec![], 100, ROW));
+        harness.frame(&mut root, &bc);
+        serve(&mut harness, &mut root);
+        let _ = std::mem::take(&mut harness.window_state.damage);
+
+        let mut wheel = mouse((60.0, 10.0));
+        wheel.wheel_delta = (0.0, 30.0).into();
+        harness.event(&mut root, Event::MouseWheel(wheel));
+        harness.frame(&mut root, &bc);
+
+        // The rows move up as they were presented, below them the list is presented anew.
+        let damage = &harness.window_state.damage;
+        assert_eq!(
+            damage.scrolls(),
+            [(WINDOW.to_rect(), Vec2::new(0.0, -30.0))]
+        );
+        assert_eq!(damage.rects(), [Rect::new(0.0, 210.0, 200.0, 240.0)]);
+
+        // Other changes of the list still present all of it.
+        let _ = std::mem::take(&mut harness.window_state.damage);
+        let changes = root.downcast_mut::<List>().unwrap().set_item_height(30.0);
+        let _ = root.mark(changes);
+        harness.frame(&mut root, &bc);
+        let damage = &harness.window_state.damage;
+        assert!(damage.scrolls().is_empty());
+        assert_eq!(damage.rects()[0].union(WINDOW.to_rect()), damage.rects()[0]);
+    }
+
+    #[test]
+    fn wheel_scrolls_the_list_before_rows_see_it() {
+        let mut harness = Harness::new();
+        let bc = BoxConstraints::tight(WINDOW);
+        let mut root = Pod::new(List::new(&vec![], 100, ROW));
+        harness.frame(&mut root, &bc);
+        serve(&mut harness, &mut root);
+
+        // The first row is a focused slider, which would step with the wheel on its own.
+        let list = root.downcast_mut::<List>().unwrap();
+        let slider = Slider::new(&vec![], 50.0, 0.0..100.0, Axis::Horizontal);
+        let changes = list.set_child(0, Pod::new(slider));
+        let _ = root.mark(changes);
+        harness.frame(&mut root, &bc);
+        let list = root.downcast_mut::<List>().unwrap();
+        harness.window_state.focus = Some(list.child_mut(0).unwrap().id());
+
+        let mut wheel = mouse((60.0, 10.0));
+        wheel.wheel_delta = (0.0, 30.0).into();
+        harness.event(&mut root, Event::MouseWheel(wheel));
+        let list = root.downcast_mut::<List>().unwrap();
+        assert_eq!(list.scroll_offset(), 30.0);
+        let slider = list.child_mut(0).unwrap().downcast_mut::<Slider>().unwrap();
+        assert_eq!(slider.value(), 50.0);
+        let messages = harness.take_messages();
+        assert!(!messages.iter().any(|message| message.is::<SliderMessage>()));
+    }
+
+    #[test]
+    fn nested_list_keeps_the_wheel_from_its_parent() {
+        let mut harness = Harness::new();
+        let bc = BoxConstraints::tight(WINDOW);
+        let mut root = Pod::new(List::new(&vec![], 100, ROW));
+        harness.frame(&mut root, &bc);
+        serve(&mut harness, &mut root);
+
+        let list = root.downcast_mut::<List>().unwrap();
+        let changes = list.set_child(1, Pod::new(List::new(&vec![], 10, ROW)));
+        let _ = root.mark(changes);
+        harness.frame(&mut root, &bc);
+        // The inner list scrolls without rows as well.
+        harness.take_messages();
+
+        let wheel = |y| {
+            let mut wheel = mouse((60.0, y));
+            wheel.wheel_delta = (0.0, 30.0).into();
+            Event::MouseWheel(wheel)
+        };
+        harness.event(&mut root, wheel(ROW + 10.0));
+        let list = root.downcast_mut::<List>().unwrap();
+        assert_eq!(list.scroll_offset(), 0.0);
+        let inner = list.child_mut(1).unwrap().downcast_mut::<List>().unwrap();
+        assert_eq!(inner.scroll_offset(), 30.0);
+
+        // Next to the inner list, the outer one scrolls.
+        harness.event(&mut root, wheel(10.0));
+        let list = root.downcast_mut::<List>().unwrap();
+        assert_eq!(list.scroll_offset(), 30.0);
+        let inner = list.child_mut(1).unwrap().downcast_mut::<List>().unwrap();
+        assert_eq!(inner.scroll_offset(), 30.0);
     }
 }
@@ -12,25 +12,124 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod animator;
 mod box_constraints;
 mod button;
+mod canvas;
+mod checkbox;
+mod clipboard;
+mod context_menu;
 mod contexts;
 mod core;
+mod damage;
+mod deadline;
+mod debug_state;
+mod disabled;
+mod drag_drop;
+mod dropdown;
+mod empty;
+mod env_scope;
+mod focus_ring;
+mod focus_scope;
+mod gesture;
+mod headless;
+mod image;
+mod label;
+mod lazy;
 //mod layout_observer;
-//mod list;
 mod linear_layout;
+mod link;
+mod list;
+mod modal;
+mod mount;
+mod on_event;
+mod one_of;
+mod overlay;
+mod paste;
 mod piet_scene_helpers;
+mod pod_slots;
+mod progress_bar;
+mod radio;
 mod raw_event;
+mod registry;
+mod rich_text;
+mod shortcuts;
+mod slider;
+mod spinner;
+mod stepper;
+mod switch;
+#[cfg(test)]
+pub(crate) mod test_util;
 //mod scroll_view;
-//mod text;
+mod text;
+mod text_area;
+mod text_box;
+mod text_editing;
+mod ticker;
+mod timer;
+mod toast;
+mod tooltip;
 #[allow(clippy::module_inception)]
 mod widget;
+mod window_events;
 
-pub use self::core::{ChangeFlags, Pod};
+pub use self::core::{ChangeFlags, HitChain, Pod, WidgetMut};
 pub(crate) use self::core::{PodFlags, WidgetState};
+pub use self::image::{Fit, Image};
+pub use animator::Animator;
 pub use box_constraints::BoxConstraints;
 pub use button::Button;
+pub use canvas::{Canvas, PaintFn};
+pub use checkbox::{CheckState, Checkbox};
+pub use clipboard::Clipboard;
+pub use context_menu::{ContextMenu, ContextMenuMessage, MenuEntry};
+pub(crate) use contexts::WindowState;
 pub use contexts::{AccessCx, CxState, EventCx, LayoutCx, LifeCycleCx, PaintCx, UpdateCx};
-pub use linear_layout::LinearLayout;
-pub use raw_event::{Event, LifeCycle, MouseEvent, ViewContext};
-pub use widget::{AnyWidget, Widget};
+pub use damage::Damage;
+pub use deadline::Deadline;
+pub use debug_state::DebugState;
+pub use disabled::DisabledScope;
+pub use drag_drop::{DragEnded, DragSource, DropMessage, DropTarget};
+pub use dropdown::Dropdown;
+pub use empty::Empty;
+pub use env_scope::EnvScope;
+pub use focus_ring::{paint_focus_ring, paint_focus_ring_around, FOCUS_RING_EXTENT};
+pub use focus_scope::FocusScope;
+pub use gesture::{DragPhase, DragUpdate, Gesture, GestureEvent, Gestures, PinchUpdate};
+#[doc(hidden)]
+pub use headless::Headless;
+pub use label::{Label, TextAlignment};
+pub use lazy::Lazy;
+pub use linear_layout::{LinearLayout, LinearMeta, Overflow};
+pub use link::Link;
+pub use list::{List, ListChildRequest};
+pub use modal::{DismissRequest, Modal};
+pub use mount::Mount;
+pub use on_event::{EventPayload, Listen, OnEvent};
+pub use one_of::OneOf;
+pub use overlay::{Corner, OverlayHandle, OverlayHost, Placement};
+pub use paste::{PasteData, PasteTarget};
+pub use piet_scene_helpers::UnitPoint;
+pub use pod_slots::{PodSlots, SlotKey, SlotSplice};
+pub use progress_bar::ProgressBar;
+pub use radio::{Radio, RadioMessage};
+pub use raw_event::{
+    DragEvent, Event, LifeCycle, MouseEvent, PointerEvent, PointerId, PointerKind, ScrollUnit,
+    ViewContext,
+};
+pub use registry::{Registry, Tagged, WidgetHandle, WidgetMessage, WidgetQuery};
+pub use rich_text::{RichText, SpanStyle, TextSpan};
+pub use shortcuts::{ShortcutScope, Shortcuts};
+pub use slider::{Slider, SliderMessage};
+pub use spinner::Spinner;
+pub use stepper::{DecimalFormat, NumberFormat, Stepper};
+pub use switch::Switch;
+pub use text::TextWidget;
+pub use text_area::TextArea;
+pub use text_box::TextBox;
+pub use ticker::Ticker;
+pub use timer::{MockTimerSource, SystemTimerSource, TimerSource, TimerToken};
+pub use toast::{ToastMessage, Toasts};
+pub use tooltip::Tooltip;
+pub use widget::{AnyWidget, Intrinsic, Widget};
+pub use window_events::{CloseRequest, OnCloseRequest, OnWindowResize};
@@ -0,0 +1,496 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use glazier::KbKey;
+use vello::{
+    kurbo::{Insets, Point, Rect, Size},
+    peniko::Color,
+    SceneBuilder,
+};
+
+use crate::id::Id;
+use crate::{IdPath, Message};
+
+use super::{
+    piet_scene_helpers, AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle,
+    LifeCycleCx, OverlayHandle, PaintCx, Placement, Pod, UpdateCx, Widget,
+};
+
+/// The message a [`Modal`] sends to the view at its id path when the user asks to dismiss the
+/// dialog, with Escape or a press on the scrim.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DismissRequest;
+
+/// Shows a dialog above the whole window, blocking interaction with everything below it.
+///
+/// While a dialog is set, it is shown centered on a scrim which dims the window. Pointer events
+/// only reach the dialog and keyboard focus moves into it: Tab and Shift+Tab cycle through the
+/// focusable widgets of the dialog. Escape, when the dialog doesn't use it, and presses on the
+/// scrim send a [`DismissRequest`], but the dialog stays open until it is unset. Focus returns to
+/// where it was when the dialog closes.
+///
+/// A dialog may contain a modal itself, whose dialog then stacks on top.
+pub struct Modal {
+    child: Pod,
+    layer: OverlayHandle,
+    is_shown: bool,
+    /// The focus before the dialog took it, restored when it closes.
+    previous_focus: Option<Id>,
+}
+
+const DIALOG_INSETS: Insets = Insets::uniform(16.0);
+/// The space kept free around the dialog frame.
+const MARGIN: f64 = 24.0;
+
+impl Modal {
+    pub fn new(id_path: &IdPath, child: Pod, dialog: Option<Pod>) -> Modal {
+        let layer = ModalLayer {
+            id_path: id_path.clone(),
+            focus_first: dialog.is_some(),
+            dialog,
+            frame: Rect::ZERO,
+        };
+        Modal {
+            child,
+            layer: OverlayHandle::new(layer, Placement::At(Point::ZERO)),
+            is_shown: false,
+            previous_focus: None,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    /// Opens the modal with `dialog`, or closes it with `None`.
+    pub fn set_dialog(&mut self, dialog: Option<Pod>) -> ChangeFlags {
+        self.change_layer(|layer| {
+            layer.focus_first = dialog.is_some();
+            layer.dialog = dialog;
+            ChangeFlags::tree_structure()
+                | ChangeFlags::LAYOUT
+                | ChangeFlags::PAINT
+                | ChangeFlags::ACCESSIBILITY
+        }) | ChangeFlags::UPDATE
+    }
+
+    /// Runs `f` with the pod of the dialog, if there is one, and marks the changes it returns.
+    pub fn update_dialog(&mut self, f: impl FnOnce(&mut Pod) -> ChangeFlags) -> ChangeFlags {
+        self.change_layer(|layer| match &mut layer.dialog {
+            Some(dialog) => {
+                let changes = f(dialog);
+                dialog.mark(changes)
+            }
+            None => ChangeFlags::empty(),
+        })
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.layer
+            .with_pod(|pod| pod.expect_mut::<ModalLayer>().dialog.is_some())
+    }
+
+    /// Runs `f` with the layer and marks the changes it returns on the layer.
+    fn change_layer(&self, f: impl FnOnce(&mut ModalLayer) -> ChangeFlags) -> ChangeFlags {
+        self.layer.with_pod(|pod| {
+            let changes = f(pod.expect_mut());
+            pod.mark(changes)
+        })
+    }
+}
+
+impl Widget for Modal {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        match event {
+            // Nothing below the dialog is interactive while it is open.
+            Event::MouseDown(_)
+            | Event::MouseUp(_)
+            | Event::MouseMove(_)
+            | Event::MouseWheel(_)
+            | Event::KeyDown(_)
+            | Event::KeyUp(_)
+            | Event::Text(_)
+                if self.is_shown => {}
+            _ => self.child.event(cx, event),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.child.update(cx);
+        let is_open = self.is_open();
+        if is_open == self.is_shown {
+            return;
+        }
+        self.is_shown = is_open;
+        if is_open {
+            // Keys go to the layer until it moves focus into the dialog.
+            self.previous_focus = cx.cx_state.focus();
+            cx.cx_state.window_state().focus = Some(self.layer.id());
+            cx.show_overlay(&self.layer);
+        } else {
+            cx.hide_overlay(&self.layer);
+            cx.cx_state.window_state().focus = self.previous_focus.take();
+        }
+        cx.request_paint();
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+        builder.set_children(vec![self.child.id().into()]);
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+/// The overlay of a [`Modal`], covering the whole window with the scrim and the dialog.
+struct ModalLayer {
+    id_path: IdPath,
+    dialog: Option<Pod>,
+    /// Whether focus moves to the first focusable widget of the dialog in the next tree update.
+    focus_first: bool,
+    /// The frame around the dialog.
+    frame: Rect,
+}
+
+impl ModalLayer {
+    fn dismiss(&self, cx: &mut EventCx) {
+        cx.add_message(Message::new(self.id_path.clone(), DismissRequest));
+        cx.set_handled(true);
+    }
+}
+
+impl Widget for ModalLayer {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        let Some(dialog) = &mut self.dialog else {
+            return;
+        };
+        match event {
+            Event::MouseDown(mouse) if !self.frame.contains(mouse.pos) => self.dismiss(cx),
+            Event::KeyDown(key_event) => {
+                dialog.event(cx, event);
+                if cx.is_handled() {
+                    return;
+                }
+                match key_event.key {
+                    KbKey::Tab => {
+                        dialog.move_focus(cx, !key_event.mods.shift());
+                        cx.request_paint();
+                        cx.set_handled(true);
+                    }
+                    KbKey::Escape => self.dismiss(cx),
+                    _ => (),
+                }
+            }
+            _ => dialog.event(cx, event),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        let Some(dialog) = &mut self.dialog else {
+            return;
+        };
+        dialog.lifecycle(cx, event);
+        if let LifeCycle::TreeUpdate = event {
+            if std::mem::take(&mut self.focus_first) {
+                if let Some(first) = dialog.focus_chain(cx).first() {
+                    cx.cx_state.window_state().focus = Some(*first);
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        if let Some(dialog) = &mut self.dialog {
+            dialog.update(cx);
+        }
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = bc.max();
+        let Some(dialog) = &mut self.dialog else {
+            return size;
+        };
+        let padding = Size::new(
+            DIALOG_INSETS.x_value() + 2.0 * MARGIN,
+            DIALOG_INSETS.y_value() + 2.0 * MARGIN,
+        );
+        let dialog_bc = BoxConstraints::new(Size::ZERO, size).shrink(padding);
+        let dialog_size = dialog.layout(cx, &dialog_bc);
+        let origin = Point::new(
+            (size.width - dialog_size.width) * 0.5,
+            (size.height - dialog_size.height) * 0.5,
+        );
+        dialog.set_origin(cx, origin);
+        self.frame = Rect::from_origin_size(origin, dialog_size) + DIALOG_INSETS;
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::Dialog);
+        if let Some(dialog) = &mut self.dialog {
+            dialog.accessibility(cx);
+            builder.set_children(vec![dialog.id().into()]);
+        }
+        builder.set_modal();
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let Some(dialog) = &mut self.dialog else {
+            return;
+        };
+        piet_scene_helpers::fill_color(
+            builder,
+            &cx.size().to_rect(),
+            Color::rgba8(0x00, 0x00, 0x00, 0x99),
+        );
+        let frame = self.frame.to_rounded_rect(6.0);
+        piet_scene_helpers::fill_color(builder, &frame, Color::rgb8(0x2a, 0x2a, 0x2a));
+        piet_scene_helpers::stroke(builder, &frame, Color::rgb8(0x3a, 0x3a, 0x3a), 1.0);
+        dialog.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        if let Some(dialog) = &self.dialog {
+            visit(dialog);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Axis;
+    use crate::widget::test_util::{key_down, mouse, Harness, Leaf};
+    use crate::widget::{Button, LinearLayout, OverlayHost};
+    use glazier::{KeyEvent, KeyState, Modifiers};
+    use test_log::test;
+
+    const WINDOW: Size = Size::new(400.0, 300.0);
+
+    fn button(id: Id) -> Pod {
+        Pod::new(Button::new(&vec![id], Pod::new(Leaf::new(60.0, 20.0))))
+    }
+
+    /// A column of buttons with the given ids.
+    fn buttons(ids: &[Id]) -> Pod {
+        let buttons = ids.iter().map(|id| button(*id)).collect();
+        Pod::new(LinearLayout::new(buttons, 0.0, Axis::Vertical))
+    }
+
+    fn setup(harness: &mut Harness, underlying: Id) -> Pod {
+        let modal = Modal::new(&vec![Id::next()], button(underlying), None);
+        let mut root = Pod::new(OverlayHost::new(Pod::new(modal)));
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        root
+    }
+
+    /// Runs `f` with the modal and passes its changes through an update and layout.
+    fn change(harness: &mut Harness, root: &mut Pod, f: impl FnOnce(&mut Modal) -> ChangeFlags) {
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        let content = host.content_mut();
+        let changes = f(content.downcast_mut().unwrap());
+        let changes = content.mark(changes);
+        let _ = root.mark(changes);
+        harness.update(root);
+        harness.layout(root, &BoxConstraints::tight(WINDOW));
+    }
+
+    fn modal(root: &mut Pod) -> &mut Modal {
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        host.content_mut().downcast_mut().unwrap()
+    }
+
+    fn frame(modal: &Modal) -> Rect {
+        modal
+            .layer
+            .with_pod(|pod| pod.downcast_mut::<ModalLayer>().unwrap().frame)
+    }
+
+    fn click(harness: &mut Harness, root: &mut Pod, pos: Point) {
+        harness.event(root, Event::MouseMove(mouse(pos)));
+        harness.event(root, Event::MouseDown(mouse(pos)));
+        harness.event(root, Event::MouseUp(mouse(pos)));
+    }
+
+    /// The last element of the id path of each message, and whether it is a dismiss request.
+    fn messages(harness: &mut Harness) -> Vec<(Id, bool)> {
+        harness
+            .take_messages()
+            .into_iter()
+            .map(|message| {
+                (
+                    *message.id_path.last().unwrap(),
+                    message.body.is::<DismissRequest>(),
+                )
+            })
+            .collect()
+    }
+
+    fn shift_tab() -> Event {
+        Event::KeyDown(KeyEvent {
+            key: KbKey::Tab,
+            state: KeyState::Down,
+            mods: Modifiers::SHIFT,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn scrim_blocks_pointer_events() {
+        let mut harness = Harness::new();
+        let underlying = Id::next();
+        let mut root = setup(&mut harness, underlying);
+        click(&mut harness, &mut root, Point::new(5.0, 5.0));
+        assert_eq!(messages(&mut harness), vec![(underlying, false)]);
+
+        let ok = Id::next();
+        change(&mut harness, &mut root, |modal| {
+            modal.set_dialog(Some(buttons(&[ok])))
+        });
+        click(&mut harness, &mut root, Point::new(5.0, 5.0));
+        let modal_id = modal(&mut root)
+            .layer
+            .with_pod(|pod| pod.downcast_mut::<ModalLayer>().unwrap().id_path[0]);
+        // The press on the scrim asks for dismissal, but the dialog stays open.
+        assert_eq!(messages(&mut harness), vec![(modal_id, true)]);
+        assert!(modal(&mut root).is_open());
+
+        let inside =
+            frame(modal(&mut root)).origin() + (DIALOG_INSETS.x0 + 5.0, DIALOG_INSETS.y0 + 5.0);
+        click(&mut harness, &mut root, inside);
+        assert_eq!(messages(&mut harness), vec![(ok, false)]);
+
+        change(&mut harness, &mut root, |modal| modal.set_dialog(None));
+        assert_eq!(
+            root.downcast_mut::<OverlayHost>().unwrap().overlay_count(),
+            0
+        );
+        click(&mut harness, &mut root, Point::new(5.0, 5.0));
+        assert_eq!(messages(&mut harness), vec![(underlying, false)]);
+    }
+
+    #[test]
+    fn focus_wraps_inside_dialog() {
+        let mut harness = Harness::new();
+        let underlying = Id::next();
+        let mut root = setup(&mut harness, underlying);
+        let underlying_pod = modal(&mut root).child_mut().id();
+        harness.window_state.focus = Some(underlying_pod);
+
+        let mut dialog = buttons(&[Id::next(), Id::next(), Id::next()]);
+        let focusable: Vec<Id> = dialog
+            .downcast_mut::<LinearLayout>()
+            .unwrap()
+            .children
+            .iter()
+            .map(Pod::id)
+            .collect();
+        change(&mut harness, &mut root, |modal| {
+            modal.set_dialog(Some(dialog))
+        });
+        assert_eq!(harness.window_state.focus, Some(focusable[0]));
+
+        let mut focus_after = |harness: &mut Harness, event: Event| {
+            harness.event(&mut root, event);
+            harness.window_state.focus
+        };
+        assert_eq!(
+            focus_after(&mut harness, key_down(KbKey::Tab)),
+            Some(focusable[1])
+        );
+        assert_eq!(
+            focus_after(&mut harness, key_down(KbKey::Tab)),
+            Some(focusable[2])
+        );
+        assert_eq!(
+            focus_after(&mut harness, key_down(KbKey::Tab)),
+            Some(focusable[0])
+        );
+        assert_eq!(focus_after(&mut harness, shift_tab()), Some(focusable[2]));
+        // Enter reaches the focused dialog button, but never the one below.
+        harness.event(&mut root, key_down(KbKey::Enter));
+        assert!(messages(&mut harness)
+            .iter()
+            .all(|(id, _)| *id != underlying));
+
+        change(&mut harness, &mut root, |modal| modal.set_dialog(None));
+        assert_eq!(harness.window_state.focus, Some(underlying_pod));
+    }
+
+    #[test]
+    fn stacked_modals_nest() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, Id::next());
+        let inner_id = Id::next();
+        let inner = Modal::new(&vec![inner_id], button(Id::next()), None);
+        change(&mut harness, &mut root, |modal| {
+            modal.set_dialog(Some(Pod::new(inner)))
+        });
+        let outer_focus = harness.window_state.focus;
+        assert!(outer_focus.is_some());
+
+        let mut confirm = buttons(&[Id::next()]);
+        let confirm_id = confirm.downcast_mut::<LinearLayout>().unwrap().children[0].id();
+        change(&mut harness, &mut root, |modal| {
+            modal.update_dialog(|dialog| {
+                dialog
+                    .downcast_mut::<Modal>()
+                    .unwrap()
+                    .set_dialog(Some(confirm))
+            })
+        });
+        assert_eq!(
+            root.downcast_mut::<OverlayHost>().unwrap().overlay_count(),
+            2
+        );
+        assert_eq!(harness.window_state.focus, Some(confirm_id));
+
+        // Escape and the scrim only dismiss the top dialog.
+        harness.event(&mut root, key_down(KbKey::Escape));
+        click(&mut harness, &mut root, Point::new(5.0, 5.0));
+        assert_eq!(
+            messages(&mut harness),
+            vec![(inner_id, true), (inner_id, true)]
+        );
+
+        change(&mut harness, &mut root, |modal| {
+            modal.update_dialog(|dialog| dialog.downcast_mut::<Modal>().unwrap().set_dialog(None))
+        });
+        assert_eq!(
+            root.downcast_mut::<OverlayHost>().unwrap().overlay_count(),
+            1
+        );
+        assert_eq!(harness.window_state.focus, outer_focus);
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vello::{
+    kurbo::{Point, Size},
+    SceneBuilder,
+};
+
+use crate::{IdPath, Message};
+
+use super::{
+    AccessCx, BoxConstraints, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx, PaintCx, Pod,
+    UpdateCx, Widget,
+};
+
+/// Tells its view that it is in the tree, around an optional child.
+///
+/// The message is sent in the first update after the widget was added. Without a child, the
+/// widget takes no space.
+pub struct Mount {
+    id_path: IdPath,
+    child: Option<Pod>,
+    is_announced: bool,
+}
+
+impl Mount {
+    pub fn new(id_path: &IdPath, child: Option<Pod>) -> Self {
+        Mount {
+            id_path: id_path.clone(),
+            child,
+            is_announced: false,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> Option<&mut Pod> {
+        self.child.as_mut()
+    }
+}
+
+impl Widget for Mount {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        if let Some(child) = &mut self.child {
+            child.event(cx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        if let Some(child) = &mut self.child {
+            child.lifecycle(cx, event);
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        if !std::mem::replace(&mut self.is_announced, true) {
+            cx.add_message(Message::new(self.id_path.clone(), ()));
+        }
+        if let Some(child) = &mut self.child {
+            child.update(cx);
+        }
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        match &mut self.child {
+            Some(child) => {
+                let size = child.layout(cx, bc);
+                child.set_origin(cx, Point::ORIGIN);
+                size
+            }
+            None => bc.min(),
+        }
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        let Some(child) = &mut self.child else {
+            return;
+        };
+        child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        if let Some(child) = &mut self.child {
+            child.paint(cx, builder);
+        }
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        if let Some(child) = &self.child {
+            visit(child);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::Harness;
+    use crate::widget::ChangeFlags;
+    use test_log::test;
+    use xilem_core::Id;
+
+    #[test]
+    fn mount_is_announced_in_the_first_update_only() {
+        let mut harness = Harness::new();
+        let id_path = vec![Id::next()];
+        let mut root = Pod::new(Mount::new(&id_path, None));
+        harness.update(&mut root);
+        let messages = harness.take_messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id_path, id_path);
+
+        let _ = root.mark(ChangeFlags::UPDATE);
+        harness.update(&mut root);
+        assert!(harness.take_messages().is_empty());
+    }
+}
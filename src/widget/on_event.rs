@@ -0,0 +1,243 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bitflags::bitflags;
+use glazier::MouseButton;
+use vello::{
+    kurbo::{Point, Size},
+    SceneBuilder,
+};
+
+use crate::{IdPath, Message};
+
+use super::{
+    AccessCx, BoxConstraints, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx, MouseEvent,
+    PaintCx, Pod, UpdateCx, Widget,
+};
+
+bitflags! {
+    /// The kinds of [`EventPayload`] an [`OnEvent`] reports.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Listen: u8 {
+        const CLICK = 1;
+        const HOVER = 2;
+        const POINTER = 4;
+    }
+}
+
+/// What an [`OnEvent`] reports to its view.
+#[derive(Debug, Clone)]
+pub enum EventPayload {
+    /// A mouse button was pressed over the child, see [`Listen::POINTER`].
+    MouseDown(MouseEvent),
+    /// A mouse button was released after being pressed over the child, or over the child, see
+    /// [`Listen::POINTER`].
+    MouseUp(MouseEvent),
+    /// The mouse moved over the child, or anywhere while a button pressed over the child is
+    /// held, see [`Listen::POINTER`].
+    MouseMove(MouseEvent),
+    /// The primary button was pressed and released over the child, see [`Listen::CLICK`].
+    Click(MouseEvent),
+    /// The pointer entered the child with `true` or left it with `false`, see
+    /// [`Listen::HOVER`].
+    Hover(bool),
+}
+
+/// Reports pointer events on a child to the view at `id_path`, without changing its layout.
+///
+/// Only the kinds of events asked for are reported, each as an [`EventPayload`] message. The
+/// child receives all events before this widget looks at them, and only presses and releases
+/// the child left unhandled make a click, which handles the release.
+pub struct OnEvent {
+    id_path: IdPath,
+    child: Pod,
+    listen: Listen,
+    /// Whether the primary button was pressed over the child and not yet released.
+    is_pressed: bool,
+}
+
+impl OnEvent {
+    pub fn new(id_path: &IdPath, child: Pod, listen: Listen) -> Self {
+        OnEvent {
+            id_path: id_path.clone(),
+            child,
+            listen,
+            is_pressed: false,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    pub fn set_listen(&mut self, listen: Listen) {
+        self.listen = listen;
+    }
+
+    fn report(&self, cx: &mut EventCx, kind: Listen, payload: EventPayload) {
+        if self.listen.contains(kind) {
+            cx.add_message(Message::new(self.id_path.clone(), payload));
+        }
+    }
+}
+
+impl Widget for OnEvent {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.child.event(cx, event);
+        match event {
+            Event::MouseDown(mouse) => {
+                cx.set_active(true);
+                // Presses a child handled, like those of a button, don't make clicks here.
+                self.is_pressed = mouse.button == MouseButton::Left && !cx.is_handled();
+                self.report(cx, Listen::POINTER, EventPayload::MouseDown(mouse.clone()));
+            }
+            Event::MouseUp(mouse) => {
+                self.report(cx, Listen::POINTER, EventPayload::MouseUp(mouse.clone()));
+                if self.is_pressed && mouse.button == MouseButton::Left {
+                    if cx.is_hot() && !cx.is_handled() && self.listen.contains(Listen::CLICK) {
+                        self.report(cx, Listen::CLICK, EventPayload::Click(mouse.clone()));
+                        cx.set_handled(true);
+                    }
+                    self.is_pressed = false;
+                }
+                cx.set_active(false);
+            }
+            Event::MouseMove(mouse) if cx.is_hot() || cx.is_active() => {
+                self.report(cx, Listen::POINTER, EventPayload::MouseMove(mouse.clone()));
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(is_hot) = event {
+            if self.listen.contains(Listen::HOVER) {
+                cx.add_message(Message::new(
+                    self.id_path.clone(),
+                    EventPayload::Hover(*is_hot),
+                ));
+            }
+        }
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.child.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{mouse, Harness, Leaf};
+    use crate::widget::Button;
+    use test_log::test;
+    use xilem_core::Id;
+
+    fn on_event(harness: &mut Harness, listen: Listen) -> Pod {
+        let mut pod = Pod::new(OnEvent::new(
+            &vec![Id::next()],
+            Pod::new(Leaf::new(50.0, 50.0)),
+            listen,
+        ));
+        harness.layout(&mut pod, &BoxConstraints::UNBOUNDED);
+        pod
+    }
+
+    fn payloads(harness: &mut Harness) -> Vec<EventPayload> {
+        harness
+            .take_messages()
+            .into_iter()
+            .map(|message| *message.body.downcast::<EventPayload>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn click_is_reported_once_per_press_and_release_inside() {
+        let mut harness = Harness::new();
+        let mut pod = on_event(&mut harness, Listen::CLICK);
+
+        harness.event(&mut pod, Event::MouseMove(mouse((10.0, 10.0))));
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((20.0, 20.0))));
+        let clicks = payloads(&mut harness);
+        assert_eq!(clicks.len(), 1);
+        assert!(matches!(clicks[0], EventPayload::Click(_)));
+
+        // Released outside, or released without being pressed inside first.
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((200.0, 10.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((10.0, 10.0))));
+        assert!(payloads(&mut harness).is_empty());
+    }
+
+    #[test]
+    fn hover_ends_when_the_pointer_jumps_away() {
+        let mut harness = Harness::new();
+        let mut pod = on_event(&mut harness, Listen::HOVER);
+
+        harness.event(&mut pod, Event::MouseMove(mouse((10.0, 10.0))));
+        harness.event(&mut pod, Event::MouseMove(mouse((20.0, 10.0))));
+        harness.event(&mut pod, Event::MouseMove(mouse((400.0, 300.0))));
+        let hovers = payloads(&mut harness);
+        assert!(matches!(
+            hovers[..],
+            [EventPayload::Hover(true), EventPayload::Hover(false)]
+        ));
+    }
+
+    #[test]
+    fn clicks_handled_by_the_child_are_not_reported() {
+        let mut harness = Harness::new();
+        let button = Button::new(&vec![Id::next()], Pod::new(Leaf::new(40.0, 20.0)));
+        let mut pod = Pod::new(OnEvent::new(
+            &vec![Id::next()],
+            Pod::new(button),
+            Listen::CLICK,
+        ));
+        harness.layout(&mut pod, &BoxConstraints::UNBOUNDED);
+
+        harness.event(&mut pod, Event::MouseMove(mouse((5.0, 5.0))));
+        harness.event(&mut pod, Event::MouseDown(mouse((5.0, 5.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((5.0, 5.0))));
+        // Only the button reports its click.
+        let messages = harness.take_messages();
+        assert_eq!(messages.len(), 1);
+        assert!(!messages[0].is::<EventPayload>());
+    }
+}
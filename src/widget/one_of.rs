@@ -0,0 +1,123 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vello::{
+    kurbo::{Point, Size},
+    SceneBuilder,
+};
+
+use super::{
+    AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx,
+    PaintCx, Pod, UpdateCx, Widget,
+};
+
+/// Shows one of several branches, keeping hidden branches around on request.
+///
+/// Only the shown branch is part of the tree, a parked branch receives no events or passes
+/// until it is shown again.
+pub struct OneOf {
+    index: usize,
+    child: Pod,
+    parked: Vec<(usize, Pod)>,
+}
+
+impl OneOf {
+    pub fn new(index: usize, child: Pod) -> Self {
+        OneOf {
+            index,
+            child,
+            parked: vec![],
+        }
+    }
+
+    /// The index of the shown branch.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    /// Removes and returns the parked branch `index`.
+    pub fn take_parked(&mut self, index: usize) -> Option<Pod> {
+        let position = self
+            .parked
+            .iter()
+            .position(|(parked, _)| *parked == index)?;
+        Some(self.parked.remove(position).1)
+    }
+
+    /// Drops the parked branch `index`, if any.
+    pub fn drop_parked(&mut self, index: usize) {
+        self.parked.retain(|(parked, _)| *parked != index);
+    }
+
+    /// Shows `child` as the branch `index`, parking the branch shown so far if `park` is set
+    /// and dropping it otherwise.
+    pub fn show(&mut self, index: usize, mut child: Pod, park: bool) -> ChangeFlags {
+        // A parked branch missed the passes while it was hidden.
+        let changes = child.mark(
+            ChangeFlags::UPDATE
+                | ChangeFlags::LAYOUT
+                | ChangeFlags::ACCESSIBILITY
+                | ChangeFlags::PAINT,
+        );
+        let previous = std::mem::replace(&mut self.child, child);
+        self.drop_parked(self.index);
+        if park {
+            self.parked.push((self.index, previous));
+        }
+        self.index = index;
+        changes | ChangeFlags::tree_structure()
+    }
+}
+
+impl Widget for OneOf {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.child.event(cx, event);
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.child.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
@@ -0,0 +1,719 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A layer of widgets shown above the content of a window, used for tooltips, menus and the
+//! like.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use accesskit::NodeId;
+use vello::kurbo::{Point, Rect, Size};
+use vello::SceneBuilder;
+
+use crate::id::Id;
+
+use super::{
+    AccessCx, BoxConstraints, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx, PaintCx, Pod,
+    UpdateCx, ViewContext, Widget,
+};
+
+/// Where an overlay is positioned in the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Placement {
+    /// Below the given rect in window coordinates, or above it if there is no room below.
+    Below(Rect),
+    /// With the top left corner at the given point in window coordinates, flipped to the left
+    /// or above the point if there is no room.
+    At(Point),
+    /// Right of the given rect in window coordinates, aligned with its top edge, or left of it
+    /// if there is no room. Used for submenus.
+    Beside(Rect),
+    /// In a corner of the window, the given distance away from both edges.
+    Corner(Corner, f64),
+}
+
+/// A corner of a rectangle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl Corner {
+    pub fn is_top(self) -> bool {
+        matches!(self, Corner::TopLeft | Corner::TopRight)
+    }
+
+    pub fn is_left(self) -> bool {
+        matches!(self, Corner::TopLeft | Corner::BottomLeft)
+    }
+}
+
+impl Placement {
+    /// The origin of an overlay of `size` in a window of `window` size.
+    ///
+    /// The overlay is moved as far as needed to stay inside the window.
+    pub fn origin(&self, size: Size, window: Size) -> Point {
+        let (x, y) = match *self {
+            Placement::Below(anchor) => (
+                anchor.x0,
+                flip(anchor.y1, anchor.y0, size.height, window.height),
+            ),
+            Placement::At(point) => (
+                flip(point.x, point.x, size.width, window.width),
+                flip(point.y, point.y, size.height, window.height),
+            ),
+            Placement::Beside(anchor) => (
+                flip(anchor.x1, anchor.x0, size.width, window.width),
+                flip(anchor.y0, anchor.y1, size.height, window.height),
+            ),
+            Placement::Corner(corner, inset) => (
+                if corner.is_left() {
+                    inset
+                } else {
+                    window.width - size.width - inset
+                },
+                if corner.is_top() {
+                    inset
+                } else {
+                    window.height - size.height - inset
+                },
+            ),
+        };
+        Point::new(
+            x.min(window.width - size.width).max(0.0),
+            y.min(window.height - size.height).max(0.0),
+        )
+    }
+}
+
+/// Places an extent of `length` starting at `start`, or ending at `end` if it doesn't fit
+/// before `limit` but does fit before `end`.
+fn flip(start: f64, end: f64, length: f64, limit: f64) -> f64 {
+    if start + length > limit && end - length >= 0.0 {
+        end - length
+    } else {
+        start
+    }
+}
+
+struct Overlay {
+    pod: Pod,
+    placement: Placement,
+    interactive: bool,
+    light_dismiss: bool,
+    /// The widget which showed the overlay last.
+    owner: Option<Id>,
+    /// The rect of the overlay in window coordinates, as of the last layout.
+    rect: Rect,
+}
+
+/// A widget which can be shown above the window content with [`show_overlay`].
+///
+/// The handle is shared between the widget which owns the overlay and the [`OverlayHost`]
+/// showing it. Once all handles outside the host are dropped, the overlay is removed.
+///
+/// [`show_overlay`]: EventCx::show_overlay
+#[derive(Clone)]
+pub struct OverlayHandle(Rc<RefCell<Overlay>>);
+
+impl OverlayHandle {
+    pub fn new(widget: impl Widget + 'static, placement: Placement) -> Self {
+        OverlayHandle(Rc::new(RefCell::new(Overlay {
+            pod: Pod::new(widget),
+            placement,
+            interactive: true,
+            light_dismiss: false,
+            owner: None,
+            rect: Rect::ZERO,
+        })))
+    }
+
+    /// Runs `f` with the pod of the overlay widget.
+    ///
+    /// Changes have to be reported with [`Pod::mark`] and requested on the owning widget as
+    /// well, otherwise the host doesn't visit the overlay.
+    pub fn with_pod<R>(&self, f: impl FnOnce(&mut Pod) -> R) -> R {
+        f(&mut self.0.borrow_mut().pod)
+    }
+
+    /// Moves the overlay, the owning widget has to request layout afterwards.
+    pub fn set_placement(&self, placement: Placement) {
+        self.0.borrow_mut().placement = placement;
+    }
+
+    /// Sets whether the overlay receives pointer events, which is the default.
+    ///
+    /// Pointer events pass through non-interactive overlays to the content below.
+    pub fn set_interactive(&self, interactive: bool) {
+        self.0.borrow_mut().interactive = interactive;
+    }
+
+    /// Sets whether pressing the pointer outside of the overlay or the window losing focus
+    /// dismisses it. An Escape no widget handled dismisses the topmost of these overlays.
+    ///
+    /// The owner, the widget which showed the overlay, receives an [`Event::OverlayDismissed`].
+    /// Overlays above the pressed one are dismissed as well, so submenus close when their parent
+    /// menu is clicked.
+    pub fn set_light_dismiss(&self, light_dismiss: bool) {
+        self.0.borrow_mut().light_dismiss = light_dismiss;
+    }
+
+    /// The id of the overlay widget.
+    pub fn id(&self) -> Id {
+        self.0.borrow().pod.id()
+    }
+
+    /// The rect of the overlay in window coordinates, as of the last layout.
+    pub(crate) fn rect(&self) -> Rect {
+        self.0.borrow().rect
+    }
+
+    fn is(&self, other: &OverlayHandle) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+pub(crate) enum OverlayRequest {
+    Show {
+        overlay: OverlayHandle,
+        owner: Id,
+    },
+    Hide(OverlayHandle),
+    /// Dismisses the overlay containing the widget.
+    Dismiss(Id),
+}
+
+/// The root widget of a window, which shows overlays above its content.
+///
+/// Pointer events go to the topmost interactive overlay under the pointer, the content only
+/// receives them when no overlay is hit.
+pub struct OverlayHost {
+    content: Pod,
+    /// The shown overlays, from bottom to top.
+    overlays: Vec<OverlayHandle>,
+    /// Overlays to remove whose owner has to be notified.
+    dismissed: Vec<OverlayHandle>,
+}
+
+impl OverlayHost {
+    pub fn new(content: Pod) -> Self {
+        OverlayHost {
+            content,
+            overlays: vec![],
+            dismissed: vec![],
+        }
+    }
+
+    pub fn content_mut(&mut self) -> &mut Pod {
+        &mut self.content
+    }
+
+    /// The number of shown overlays.
+    pub fn overlay_count(&self) -> usize {
+        self.overlays.len()
+    }
+
+    /// Applies the requests and drops the overlays no widget refers to anymore.
+    ///
+    /// Returns whether the shown overlays changed.
+    fn apply(&mut self, requests: Vec<OverlayRequest>) -> bool {
+        let count = self.overlays.len();
+        let mut changed = false;
+        for request in requests {
+            match request {
+                OverlayRequest::Show { overlay, owner } => {
+                    overlay.0.borrow_mut().owner = Some(owner);
+                    if self.overlays.last().is_some_and(|top| top.is(&overlay)) {
+                        continue;
+                    }
+                    self.overlays.retain(|shown| !shown.is(&overlay));
+                    self.overlays.push(overlay);
+                    changed = true;
+                }
+                OverlayRequest::Hide(overlay) => {
+                    self.overlays.retain(|shown| !shown.is(&overlay));
+                }
+                OverlayRequest::Dismiss(widget) => {
+                    let containing =
+                        self.overlays.iter().rev().find(|overlay| {
+                            overlay.0.borrow().pod.state.sub_tree.may_contain(&widget)
+                        });
+                    self.dismissed.extend(containing.cloned());
+                }
+            }
+        }
+        self.overlays
+            .retain(|overlay| Rc::strong_count(&overlay.0) > 1);
+        changed || self.overlays.len() != count
+    }
+
+    /// Removes the dismissed overlays which are still shown and notifies their owners.
+    ///
+    /// Owners may show and hide overlays in response, so this repeats until no overlay is left to
+    /// dismiss. Returns whether the shown overlays changed.
+    fn dismiss(&mut self, cx: &mut EventCx) -> bool {
+        let mut changed = self.apply(cx.take_overlay_requests());
+        while !self.dismissed.is_empty() {
+            for overlay in std::mem::take(&mut self.dismissed) {
+                let Some(index) = self.overlays.iter().position(|shown| shown.is(&overlay)) else {
+                    continue;
+                };
+                self.overlays.remove(index);
+                changed = true;
+                let owner = overlay.0.borrow().owner;
+                if let Some(owner) = owner {
+                    let event = Event::OverlayDismissed {
+                        owner,
+                        overlay: overlay.id(),
+                    };
+                    // The notification is independent of whether the triggering event was handled.
+                    let is_handled = std::mem::replace(&mut cx.is_handled, false);
+                    self.content.event(cx, &event);
+                    for shown in self.overlays.iter().rev() {
+                        shown.0.borrow_mut().pod.event(cx, &event);
+                    }
+                    cx.is_handled = is_handled;
+                }
+            }
+            changed |= self.apply(cx.take_overlay_requests());
+        }
+        changed
+    }
+
+    /// Dismisses the topmost overlay which is dismissed by pressing outside of it, for an
+    /// Escape no widget handled.
+    pub(crate) fn dismiss_top(&mut self, cx: &mut EventCx) {
+        let top = self
+            .overlays
+            .iter()
+            .rev()
+            .find(|overlay| overlay.0.borrow().light_dismiss);
+        if let Some(top) = top {
+            self.dismissed.push(top.clone());
+            if self.dismiss(cx) {
+                cx.children_changed();
+                cx.view_context_changed();
+            }
+        }
+    }
+
+    /// The index of the overlay receiving a pointer event at `pos`.
+    fn pointer_target(&self, pos: Point) -> Option<usize> {
+        if self.content.has_active() {
+            return None;
+        }
+        self.overlays.iter().rposition(|handle| {
+            let overlay = handle.0.borrow();
+            overlay.interactive && (overlay.pod.has_active() || handle.rect().contains(pos))
+        })
+    }
+}
+
+impl Widget for OverlayHost {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        match event {
+            Event::MouseDown(mouse)
+            | Event::MouseUp(mouse)
+            | Event::MouseMove(mouse)
+            | Event::MouseWheel(mouse) => {
+                let target = self.pointer_target(mouse.pos);
+                if let Event::MouseDown(_) = event {
+                    let above_target = target.map_or(0, |index| index + 1);
+                    let light_dismissed = self.overlays[above_target..]
+                        .iter()
+                        .filter(|overlay| overlay.0.borrow().light_dismiss);
+                    self.dismissed.extend(light_dismissed.cloned());
+                }
+                for (index, overlay) in self.overlays.iter().enumerate() {
+                    if Some(index) != target {
+                        overlay.0.borrow_mut().pod.event(cx, &Event::MouseLeft());
+                    }
+                }
+                match target {
+                    Some(index) => {
+                        self.content.event(cx, &Event::MouseLeft());
+                        self.overlays[index].0.borrow_mut().pod.event(cx, event);
+                    }
+                    None => self.content.event(cx, event),
+                }
+            }
+            Event::PointerDown(pointer)
+            | Event::PointerMove(pointer)
+            | Event::PointerUp(pointer)
+            | Event::PointerCancel(pointer) => {
+                let window_state = cx.cx_state.window_state();
+                let is_captured = window_state
+                    .pointer(pointer.pointer_id)
+                    .is_some_and(|state| state.capture.is_some());
+                // An uncaptured touch only reaches the overlay under it, or else the content.
+                let target = self.overlays.iter().rposition(|handle| {
+                    handle.0.borrow().interactive && handle.rect().contains(pointer.pos)
+                });
+                for (index, overlay) in self.overlays.iter().enumerate().rev() {
+                    if is_captured || target == Some(index) {
+                        overlay.0.borrow_mut().pod.event(cx, event);
+                    }
+                }
+                if is_captured || target.is_none() {
+                    self.content.event(cx, event);
+                }
+            }
+            _ => {
+                if let Event::WindowLostFocus = event {
+                    let light_dismissed = self
+                        .overlays
+                        .iter()
+                        .filter(|overlay| overlay.0.borrow().light_dismiss);
+                    self.dismissed.extend(light_dismissed.cloned());
+                }
+                for overlay in self.overlays.iter().rev() {
+                    overlay.0.borrow_mut().pod.event(cx, event);
+                }
+                self.content.event(cx, event);
+            }
+        }
+        if self.dismiss(cx) {
+            cx.children_changed();
+            cx.view_context_changed();
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        if let LifeCycle::ViewContextChanged(view) = event {
+            // Only the overlay under the pointer, or the content if there is none, can be hot.
+            let target = view.mouse_position.and_then(|pos| self.pointer_target(pos));
+            let covered = LifeCycle::ViewContextChanged(ViewContext {
+                window_origin: view.window_origin,
+                clip: view.clip,
+                mouse_position: None,
+            });
+            let content_event = if target.is_some() { &covered } else { event };
+            self.content.lifecycle(cx, content_event);
+            for (index, overlay) in self.overlays.iter().enumerate() {
+                let overlay_event = if target == Some(index) {
+                    event
+                } else {
+                    &covered
+                };
+                overlay.0.borrow_mut().pod.lifecycle(cx, overlay_event);
+            }
+        } else {
+            self.content.lifecycle(cx, event);
+            for overlay in &self.overlays {
+                overlay.0.borrow_mut().pod.lifecycle(cx, event);
+            }
+        }
+        if self.apply(cx.take_overlay_requests()) {
+            cx.children_changed();
+            cx.view_context_changed();
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.content.update(cx);
+        // Overlays shown during the pass are updated in the same pass, as they may show overlays
+        // of their own.
+        let mut changed = false;
+        loop {
+            for overlay in &self.overlays {
+                overlay.0.borrow_mut().pod.update(cx);
+            }
+            if !self.apply(cx.take_overlay_requests()) {
+                break;
+            }
+            changed = true;
+        }
+        if changed {
+            cx.children_changed();
+            cx.view_context_changed();
+        }
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = bc.constrain(self.content.layout(cx, bc));
+        self.content.set_origin(cx, Point::ORIGIN);
+        let overlay_bc = BoxConstraints::new(Size::ZERO, size);
+        for overlay in &self.overlays {
+            let overlay = &mut *overlay.0.borrow_mut();
+            let overlay_size = overlay.pod.layout(cx, &overlay_bc);
+            let origin = overlay.placement.origin(overlay_size, size);
+            overlay.pod.set_origin(cx, origin);
+            overlay.rect = Rect::from_origin_size(origin, overlay_size);
+        }
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.content.accessibility(cx);
+        for overlay in &self.overlays {
+            overlay.0.borrow_mut().pod.accessibility(cx);
+        }
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            let mut children = vec![NodeId::from(self.content.id())];
+            children.extend(
+                self.overlays
+                    .iter()
+                    .map(|overlay| NodeId::from(overlay.0.borrow().pod.id())),
+            );
+            builder.set_children(children);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.content.paint(cx, builder);
+        for overlay in &self.overlays {
+            overlay.0.borrow_mut().pod.paint(cx, builder);
+        }
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.content);
+        for overlay in &self.overlays {
+            visit(&overlay.0.borrow().pod);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{key_down, mouse, Harness, Leaf};
+    use crate::widget::PodFlags;
+    use glazier::KbKey;
+    use test_log::test;
+
+    const WINDOW: Size = Size::new(200.0, 100.0);
+
+    #[test]
+    fn placement_flips_above_without_room() {
+        let size = Size::new(50.0, 30.0);
+        let below = Placement::Below(Rect::new(10.0, 10.0, 40.0, 20.0));
+        assert_eq!(below.origin(size, WINDOW), Point::new(10.0, 20.0));
+
+        let flipped = Placement::Below(Rect::new(10.0, 60.0, 40.0, 80.0));
+        assert_eq!(flipped.origin(size, WINDOW), Point::new(10.0, 30.0));
+    }
+
+    #[test]
+    fn point_and_side_placements_flip() {
+        let size = Size::new(50.0, 30.0);
+        let at = Placement::At(Point::new(10.0, 10.0));
+        assert_eq!(at.origin(size, WINDOW), Point::new(10.0, 10.0));
+        let at = Placement::At(Point::new(180.0, 90.0));
+        assert_eq!(at.origin(size, WINDOW), Point::new(130.0, 60.0));
+
+        let item = Rect::new(100.0, 80.0, 180.0, 95.0);
+        let beside = Placement::Beside(item);
+        assert_eq!(beside.origin(size, WINDOW), Point::new(50.0, 65.0));
+        let beside = Placement::Beside(item - (100.0, 70.0));
+        assert_eq!(beside.origin(size, WINDOW), Point::new(80.0, 10.0));
+
+        let corner = Placement::Corner(Corner::BottomRight, 8.0);
+        assert_eq!(corner.origin(size, WINDOW), Point::new(142.0, 62.0));
+        let corner = Placement::Corner(Corner::TopLeft, 8.0);
+        assert_eq!(corner.origin(size, WINDOW), Point::new(8.0, 8.0));
+    }
+
+    #[test]
+    fn placement_stays_in_window() {
+        let size = Size::new(50.0, 90.0);
+        // Neither below nor above fits, the overlay is pushed up from the bottom edge.
+        let placement = Placement::Below(Rect::new(180.0, 40.0, 190.0, 50.0));
+        assert_eq!(placement.origin(size, WINDOW), Point::new(150.0, 10.0));
+    }
+
+    /// A leaf which shows or hides an overlay on click.
+    struct Owner {
+        overlay: OverlayHandle,
+        clicks: usize,
+        dismissals: usize,
+    }
+
+    impl Owner {
+        fn new(overlay: &OverlayHandle) -> Self {
+            Owner {
+                overlay: overlay.clone(),
+                clicks: 0,
+                dismissals: 0,
+            }
+        }
+    }
+
+    impl Widget for Owner {
+        fn event(&mut self, cx: &mut EventCx, event: &Event) {
+            match event {
+                Event::MouseDown(_) => {
+                    self.clicks += 1;
+                    if self.clicks % 2 == 1 {
+                        cx.show_overlay(&self.overlay);
+                    } else {
+                        cx.hide_overlay(&self.overlay);
+                    }
+                }
+                Event::OverlayDismissed { overlay, .. } if *overlay == self.overlay.id() => {
+                    self.dismissals += 1;
+                }
+                _ => (),
+            }
+        }
+
+        fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, _cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+            bc.constrain(Size::new(20.0, 20.0))
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+    }
+
+    #[test]
+    fn overlays_receive_pointer_events_first() {
+        let mut harness = Harness::new();
+        let overlay = OverlayHandle::new(
+            Leaf::new(40.0, 40.0),
+            Placement::Below(Rect::new(0.0, 0.0, 20.0, 20.0)),
+        );
+        let owner = Owner::new(&overlay);
+        let mut root = Pod::new(OverlayHost::new(Pod::new(owner)));
+        let bc = BoxConstraints::tight(WINDOW);
+        harness.layout(&mut root, &bc);
+
+        harness.event(&mut root, Event::MouseDown(mouse((5.0, 5.0))));
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        assert_eq!(host.overlay_count(), 1);
+        harness.layout(&mut root, &bc);
+
+        // The overlay covers (0, 20)..(40, 60), a click there misses the owner.
+        harness.event(&mut root, Event::MouseMove(mouse((5.0, 30.0))));
+        assert!(overlay.with_pod(|pod| pod.is_hot()));
+        harness.event(&mut root, Event::MouseDown(mouse((5.0, 30.0))));
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        assert_eq!(host.overlay_count(), 1);
+
+        harness.event(&mut root, Event::MouseDown(mouse((5.0, 5.0))));
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        assert_eq!(host.overlay_count(), 0);
+    }
+
+    #[test]
+    fn light_dismiss_notifies_owner() {
+        let mut harness = Harness::new();
+        let overlay = OverlayHandle::new(
+            Leaf::new(40.0, 40.0),
+            Placement::Below(Rect::new(0.0, 0.0, 20.0, 20.0)),
+        );
+        overlay.set_light_dismiss(true);
+        let mut root = Pod::new(OverlayHost::new(Pod::new(Owner::new(&overlay))));
+        let bc = BoxConstraints::tight(WINDOW);
+        harness.layout(&mut root, &bc);
+        harness.event(&mut root, Event::MouseDown(mouse((5.0, 5.0))));
+        harness.layout(&mut root, &bc);
+
+        // Presses inside don't dismiss.
+        harness.event(&mut root, Event::MouseDown(mouse((5.0, 30.0))));
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        assert_eq!(host.overlay_count(), 1);
+
+        harness.event(&mut root, Event::MouseDown(mouse((150.0, 80.0))));
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        assert_eq!(host.overlay_count(), 0);
+        let owner = host.content_mut().downcast_mut::<Owner>().unwrap();
+        assert_eq!((owner.clicks, owner.dismissals), (1, 1));
+    }
+
+    #[test]
+    fn closing_an_overlay_under_the_pointer_makes_the_content_hot() {
+        let mut harness = Harness::new();
+        let overlay = OverlayHandle::new(
+            Leaf::new(40.0, 40.0),
+            Placement::Below(Rect::new(0.0, 0.0, 20.0, 20.0)),
+        );
+        overlay.set_light_dismiss(true);
+        let mut root = Pod::new(OverlayHost::new(Pod::new(Owner::new(&overlay))));
+        let bc = BoxConstraints::tight(WINDOW);
+        harness.layout(&mut root, &bc);
+        harness.event(&mut root, Event::MouseDown(mouse((5.0, 5.0))));
+        harness.layout(&mut root, &bc);
+        harness.event(&mut root, Event::MouseMove(mouse((5.0, 30.0))));
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        assert!(!host.content_mut().is_hot());
+
+        // The pointer doesn't move, the app sends the view context to the tree again.
+        harness.event(&mut root, key_down(KbKey::Escape));
+        assert!(root.state.flags.contains(PodFlags::VIEW_CONTEXT_CHANGED));
+        let view = ViewContext {
+            window_origin: Point::ORIGIN,
+            clip: WINDOW.to_rect(),
+            mouse_position: Some(Point::new(5.0, 30.0)),
+        };
+        harness.lifecycle(&mut root, LifeCycle::ViewContextChanged(view));
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        assert!(host.content_mut().is_hot());
+    }
+
+    #[test]
+    fn unhandled_escape_dismisses_the_top_overlay() {
+        let mut harness = Harness::new();
+        let overlay = OverlayHandle::new(
+            Leaf::new(40.0, 40.0),
+            Placement::Below(Rect::new(0.0, 0.0, 20.0, 20.0)),
+        );
+        overlay.set_light_dismiss(true);
+        let mut root = Pod::new(OverlayHost::new(Pod::new(Owner::new(&overlay))));
+        let bc = BoxConstraints::tight(WINDOW);
+        harness.layout(&mut root, &bc);
+        harness.event(&mut root, Event::MouseDown(mouse((5.0, 5.0))));
+        harness.layout(&mut root, &bc);
+
+        // Nothing has focus, the window handles the key.
+        harness.event(&mut root, key_down(KbKey::Escape));
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        assert_eq!(host.overlay_count(), 0);
+        let owner = host.content_mut().downcast_mut::<Owner>().unwrap();
+        assert_eq!(owner.dismissals, 1);
+    }
+
+    #[test]
+    fn dropped_overlays_are_removed() {
+        let mut harness = Harness::new();
+        let overlay = OverlayHandle::new(
+            Leaf::new(40.0, 40.0),
+            Placement::Below(Rect::new(0.0, 0.0, 20.0, 20.0)),
+        );
+        let owner = Owner::new(&overlay);
+        drop(overlay);
+        let mut root = Pod::new(OverlayHost::new(Pod::new(owner)));
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        harness.event(&mut root, Event::MouseDown(mouse((5.0, 5.0))));
+
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        assert_eq!(host.overlay_count(), 1);
+        *host.content_mut() = Pod::new(Leaf::new(20.0, 20.0));
+        harness.update(&mut root);
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        assert_eq!(host.overlay_count(), 0);
+    }
+}
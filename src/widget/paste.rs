@@ -0,0 +1,202 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use glazier::KbKey;
+use vello::{
+    kurbo::{Point, Size},
+    SceneBuilder,
+};
+
+use crate::{IdPath, Message};
+
+use super::text_editing::is_command;
+use super::{
+    AccessCx, BoxConstraints, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx, PaintCx, Pod,
+    UpdateCx, Widget,
+};
+
+/// The contents of the clipboard a [`PasteTarget`] received.
+#[derive(Debug, Clone)]
+pub enum PasteData {
+    Text(String),
+    /// An image encoded as PNG, which [`decode_image`](crate::decode_image) turns into pixels.
+    #[cfg(feature = "image")]
+    Png(Vec<u8>),
+}
+
+/// Sends the contents of the clipboard to the view at `id_path` when the user pastes while
+/// keyboard focus is inside the child.
+///
+/// Pastes the child handles itself, like those of a text box, are left to it. Images are
+/// preferred over text when the clipboard holds both.
+pub struct PasteTarget {
+    id_path: IdPath,
+    child: Pod,
+}
+
+impl PasteTarget {
+    pub fn new(id_path: &IdPath, child: Pod) -> Self {
+        PasteTarget {
+            id_path: id_path.clone(),
+            child,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+}
+
+impl Widget for PasteTarget {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.child.event(cx, event);
+        let Event::KeyDown(key_event) = event else {
+            return;
+        };
+        let is_paste =
+            matches!(&key_event.key, KbKey::Character(c) if c == "v") && is_command(key_event.mods);
+        if !is_paste || cx.is_handled() || !cx.has_focus_within() {
+            return;
+        }
+        let clipboard = cx.clipboard();
+        #[cfg(feature = "image")]
+        let data = clipboard
+            .get_png()
+            .map(PasteData::Png)
+            .or_else(|| clipboard.get_text().map(PasteData::Text));
+        #[cfg(not(feature = "image"))]
+        let data = clipboard.get_text().map(PasteData::Text);
+        if let Some(data) = data {
+            cx.add_message(Message::new(self.id_path.clone(), data));
+            cx.set_handled(true);
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.child.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{Harness, Leaf};
+    use crate::widget::{Button, LinearLayout, TextBox};
+    use crate::Axis;
+    use glazier::{KeyEvent, KeyState, Modifiers};
+    use test_log::test;
+    use xilem_core::Id;
+
+    fn command(key: &str) -> Event {
+        let mods = if cfg!(target_os = "macos") {
+            Modifiers::META
+        } else {
+            Modifiers::CONTROL
+        };
+        Event::KeyDown(KeyEvent {
+            key: KbKey::Character(key.into()),
+            mods,
+            state: KeyState::Down,
+            ..Default::default()
+        })
+    }
+
+    fn paste_target(id_path: &IdPath) -> Pod {
+        let button = Button::new(&vec![Id::next()], Pod::new(Leaf::new(60.0, 20.0)));
+        Pod::new(PasteTarget::new(id_path, Pod::new(button)))
+    }
+
+    #[test]
+    fn paste_goes_to_the_focused_target() {
+        let mut harness = Harness::new();
+        let (first_path, second_path) = (vec![Id::next()], vec![Id::next()]);
+        let children = vec![paste_target(&first_path), paste_target(&second_path)];
+        let mut root = Pod::new(LinearLayout::new(children, 0.0, Axis::Horizontal));
+        harness.layout(&mut root, &BoxConstraints::UNBOUNDED);
+        let second = root.downcast_mut::<LinearLayout>().unwrap().children[1]
+            .downcast_mut::<PasteTarget>()
+            .unwrap()
+            .child_mut()
+            .id();
+
+        harness.window_state.clipboard.set_text("pasted");
+        harness.event(&mut root, command("v"));
+        assert!(harness.take_messages().is_empty());
+
+        harness.window_state.focus = Some(second);
+        harness.event(&mut root, command("v"));
+        let messages = harness.take_messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id_path, second_path);
+        let data = messages[0].body.downcast_ref::<PasteData>().unwrap();
+        assert!(matches!(data, PasteData::Text(text) if text == "pasted"));
+    }
+
+    #[test]
+    fn text_boxes_copy_and_paste_through_the_clipboard() {
+        let mut harness = Harness::new();
+        let children = vec![
+            Pod::new(TextBox::new(&vec![Id::next()], "copied".to_string())),
+            Pod::new(TextBox::new(&vec![Id::next()], String::new())),
+        ];
+        let ids: Vec<_> = children.iter().map(Pod::id).collect();
+        let mut root = Pod::new(LinearLayout::new(children, 0.0, Axis::Horizontal));
+        harness.layout(&mut root, &BoxConstraints::UNBOUNDED);
+
+        harness.window_state.focus = Some(ids[0]);
+        harness.event(&mut root, command("a"));
+        harness.event(&mut root, command("c"));
+        assert_eq!(
+            harness.window_state.clipboard.get_text().as_deref(),
+            Some("copied")
+        );
+
+        harness.window_state.focus = Some(ids[1]);
+        harness.event(&mut root, command("v"));
+        let edits: Vec<String> = harness
+            .take_messages()
+            .into_iter()
+            .map(|message| *message.body.downcast::<String>().unwrap())
+            .collect();
+        assert_eq!(edits, ["copied"]);
+    }
+}
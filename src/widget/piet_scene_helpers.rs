@@ -1,8 +1,8 @@
 use vello::kurbo::{self, Affine, Rect, Shape};
-use vello::peniko::{BrushRef, ColorStopsSource, Fill, Gradient, Stroke};
+use vello::peniko::{BrushRef, Color, ColorStopsSource, Fill, Gradient, Stroke};
 use vello::SceneBuilder;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct UnitPoint {
     u: f64,
     v: f64,
@@ -73,3 +73,7 @@ pub fn fill_lin_gradient(
     let brush = Gradient::new_linear(start.resolve(rect), end.resolve(rect)).with_stops(stops);
     builder.fill(Fill::NonZero, Affine::IDENTITY, &brush, None, path);
 }
+
+pub fn fill_color(builder: &mut SceneBuilder, path: &impl Shape, color: Color) {
+    builder.fill(Fill::NonZero, Affine::IDENTITY, color, None, path);
+}
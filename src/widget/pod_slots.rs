@@ -0,0 +1,449 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage for the children of containers, with keys which stay valid while siblings come and go.
+
+use std::collections::VecDeque;
+use std::ops::{Index, IndexMut};
+
+use xilem_core::ElementSplice;
+
+use super::Pod;
+
+/// Identifies a child in [`PodSlots`] for as long as it stays there.
+///
+/// Unlike a position, a key isn't affected by inserting or removing other children, so it can
+/// be held by messages or animations which arrive later. The key of a removed child doesn't
+/// find a child which reuses its slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SlotKey {
+    index: u32,
+    generation: u32,
+}
+
+struct Slot {
+    generation: u32,
+    pod: Option<Pod>,
+}
+
+/// The children of a container, in order.
+///
+/// The pods stay in their slot while they are in the container, and the order only keeps
+/// their keys. Inserting and removing near either end doesn't move any pods, and takes time
+/// independent of the number of children.
+#[derive(Default)]
+pub struct PodSlots {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    order: VecDeque<SlotKey>,
+}
+
+impl PodSlots {
+    pub fn new() -> Self {
+        PodSlots::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// The key of the child at `position`.
+    pub fn key(&self, position: usize) -> Option<SlotKey> {
+        self.order.get(position).copied()
+    }
+
+    /// The child with `key`, if it is still in the container.
+    pub fn get(&self, key: SlotKey) -> Option<&Pod> {
+        self.slots
+            .get(key.index as usize)
+            .filter(|slot| slot.generation == key.generation)
+            .and_then(|slot| slot.pod.as_ref())
+    }
+
+    /// The child with `key`, if it is still in the container.
+    pub fn get_mut(&mut self, key: SlotKey) -> Option<&mut Pod> {
+        self.slots
+            .get_mut(key.index as usize)
+            .filter(|slot| slot.generation == key.generation)
+            .and_then(|slot| slot.pod.as_mut())
+    }
+
+    /// Inserts `pod` at `position`, moving the children after it back.
+    ///
+    /// # Panics
+    ///
+    /// If `position` is greater than the number of children.
+    pub fn insert(&mut self, position: usize, pod: Pod) -> SlotKey {
+        assert!(position <= self.len(), "insertion position out of bounds");
+        let key = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.pod = Some(pod);
+                SlotKey {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                let index = u32::try_from(self.slots.len()).expect("too many children");
+                self.slots.push(Slot {
+                    generation: 0,
+                    pod: Some(pod),
+                });
+                SlotKey {
+                    index,
+                    generation: 0,
+                }
+            }
+        };
+        self.order.insert(position, key);
+        key
+    }
+
+    pub fn push(&mut self, pod: Pod) -> SlotKey {
+        self.insert(self.len(), pod)
+    }
+
+    /// Removes the child at `position`, moving the children after it forward.
+    ///
+    /// # Panics
+    ///
+    /// If there is no child at `position`.
+    pub fn remove(&mut self, position: usize) -> Pod {
+        let key = self
+            .order
+            .remove(position)
+            .expect("removal position out of bounds");
+        self.free_slot(key)
+    }
+
+    pub fn pop(&mut self) -> Option<Pod> {
+        let key = self.order.pop_back()?;
+        Some(self.free_slot(key))
+    }
+
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.order.swap(a, b);
+    }
+
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.free.clear();
+        self.order.clear();
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            slots: &self.slots,
+            keys: self.order.iter(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        // The slots don't follow the order, so the children are sorted into it first.
+        let mut sorted: Vec<Option<&mut Pod>> = Vec::new();
+        sorted.resize_with(self.order.len(), || None);
+        let mut positions = vec![usize::MAX; self.slots.len()];
+        for (position, key) in self.order.iter().enumerate() {
+            positions[key.index as usize] = position;
+        }
+        for (slot, position) in self.slots.iter_mut().zip(positions) {
+            if let Some(pod) = &mut slot.pod {
+                sorted[position] = Some(pod);
+            }
+        }
+        IterMut {
+            pods: sorted.into_iter(),
+        }
+    }
+
+    /// A splice over the children, for rebuilding them from a view sequence.
+    pub fn splice(&mut self) -> SlotSplice<'_> {
+        SlotSplice {
+            slots: self,
+            position: 0,
+        }
+    }
+
+    fn slot(&self, key: SlotKey) -> &Pod {
+        self.slots[key.index as usize]
+            .pod
+            .as_ref()
+            .expect("ordered slots are occupied")
+    }
+
+    fn slot_mut(&mut self, key: SlotKey) -> &mut Pod {
+        self.slots[key.index as usize]
+            .pod
+            .as_mut()
+            .expect("ordered slots are occupied")
+    }
+
+    fn free_slot(&mut self, key: SlotKey) -> Pod {
+        let slot = &mut self.slots[key.index as usize];
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(key.index);
+        slot.pod.take().expect("ordered slots are occupied")
+    }
+}
+
+impl Index<usize> for PodSlots {
+    type Output = Pod;
+
+    fn index(&self, position: usize) -> &Pod {
+        self.slot(self.order[position])
+    }
+}
+
+impl IndexMut<usize> for PodSlots {
+    fn index_mut(&mut self, position: usize) -> &mut Pod {
+        self.slot_mut(self.order[position])
+    }
+}
+
+impl From<Vec<Pod>> for PodSlots {
+    fn from(pods: Vec<Pod>) -> Self {
+        pods.into_iter().collect()
+    }
+}
+
+impl FromIterator<Pod> for PodSlots {
+    fn from_iter<I: IntoIterator<Item = Pod>>(pods: I) -> Self {
+        let mut slots = PodSlots::new();
+        for pod in pods {
+            slots.push(pod);
+        }
+        slots
+    }
+}
+
+impl<'a> IntoIterator for &'a PodSlots {
+    type Item = &'a Pod;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut PodSlots {
+    type Item = &'a mut Pod;
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> IterMut<'a> {
+        self.iter_mut()
+    }
+}
+
+/// The children of [`PodSlots`] in order, see [`PodSlots::iter`].
+pub struct Iter<'a> {
+    slots: &'a [Slot],
+    keys: std::collections::vec_deque::Iter<'a, SlotKey>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a Pod;
+
+    fn next(&mut self) -> Option<&'a Pod> {
+        let key = self.keys.next()?;
+        self.slots[key.index as usize].pod.as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for Iter<'a> {
+    fn next_back(&mut self) -> Option<&'a Pod> {
+        let key = self.keys.next_back()?;
+        self.slots[key.index as usize].pod.as_ref()
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+/// The children of [`PodSlots`] in order, see [`PodSlots::iter_mut`].
+pub struct IterMut<'a> {
+    pods: std::vec::IntoIter<Option<&'a mut Pod>>,
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = &'a mut Pod;
+
+    fn next(&mut self) -> Option<&'a mut Pod> {
+        self.pods.next().flatten()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pods.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for IterMut<'a> {
+    fn next_back(&mut self) -> Option<&'a mut Pod> {
+        self.pods.next_back().flatten()
+    }
+}
+
+impl ExactSizeIterator for IterMut<'_> {}
+
+/// Rebuilds [`PodSlots`] from a view sequence, see [`PodSlots::splice`].
+///
+/// Inserted children get a new key, deleted ones free theirs, and the children the sequence
+/// keeps don't move.
+pub struct SlotSplice<'a> {
+    slots: &'a mut PodSlots,
+    position: usize,
+}
+
+impl ElementSplice<Pod> for SlotSplice<'_> {
+    fn skip(&mut self, n: usize) {
+        self.position += n;
+    }
+
+    fn delete(&mut self, n: usize) {
+        let keys: Vec<SlotKey> = self
+            .slots
+            .order
+            .drain(self.position..self.position + n)
+            .collect();
+        for key in keys {
+            self.slots.free_slot(key);
+        }
+    }
+
+    fn push(&mut self, element: Pod) {
+        self.slots.insert(self.position, element);
+        self.position += 1;
+    }
+
+    fn mutate(&mut self) -> &mut Pod {
+        let key = self.slots.order[self.position];
+        self.position += 1;
+        self.slots.slot_mut(key)
+    }
+
+    fn len(&self) -> usize {
+        self.position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::Leaf;
+    use test_log::test;
+    use xilem_core::Id;
+
+    fn leaves(count: usize) -> PodSlots {
+        (0..count)
+            .map(|_| Pod::new(Leaf::new(10.0, 10.0)))
+            .collect()
+    }
+
+    fn ids(slots: &PodSlots) -> Vec<Id> {
+        slots.iter().map(Pod::id).collect()
+    }
+
+    #[test]
+    fn keys_stay_valid_while_siblings_come_and_go() {
+        let mut slots = leaves(3);
+        let keys: Vec<SlotKey> = (0..3)
+            .map(|position| slots.key(position).unwrap())
+            .collect();
+        let before = ids(&slots);
+
+        let front = slots.insert(0, Pod::new(Leaf::new(10.0, 10.0)));
+        let removed = slots.remove(2);
+        assert_eq!(removed.id(), before[1]);
+        assert_eq!(slots.get(keys[0]).map(Pod::id), Some(before[0]));
+        assert_eq!(slots.get(keys[2]).map(Pod::id), Some(before[2]));
+        assert_eq!(slots.key(0), Some(front));
+        assert!(slots.get(keys[1]).is_none());
+
+        // The freed slot is reused, but the old key doesn't find its new child.
+        let reused = slots.push(Pod::new(Leaf::new(10.0, 10.0)));
+        assert_ne!(reused, keys[1]);
+        assert!(slots.get(keys[1]).is_none());
+        assert_eq!(slots.get(reused).map(Pod::id), Some(slots[3].id()));
+        assert_eq!(slots.len(), 4);
+    }
+
+    #[test]
+    fn iteration_follows_the_order() {
+        let mut slots = leaves(3);
+        let before = ids(&slots);
+        slots.swap(0, 2);
+        slots.insert(1, Pod::new(Leaf::new(10.0, 10.0)));
+        let inserted = slots[1].id();
+
+        let expected = vec![before[2], inserted, before[1], before[0]];
+        assert_eq!(ids(&slots), expected);
+        let mutated: Vec<Id> = slots.iter_mut().map(|pod| pod.id()).collect();
+        assert_eq!(mutated, expected);
+        let reversed: Vec<Id> = slots.iter().rev().map(Pod::id).collect();
+        assert_eq!(reversed, expected.into_iter().rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn splices_keep_the_children_they_skip() {
+        let mut slots = leaves(4);
+        let before = ids(&slots);
+        let kept = slots.key(3).unwrap();
+
+        let mut splice = slots.splice();
+        splice.push(Pod::new(Leaf::new(10.0, 10.0)));
+        splice.skip(1);
+        splice.delete(2);
+        let _ = splice.mutate();
+        assert_eq!(splice.len(), 3);
+
+        let after = ids(&slots);
+        assert_eq!(after.len(), 3);
+        assert_eq!(&after[1..], [before[0], before[3]]);
+        assert_eq!(slots.get(kept).map(Pod::id), Some(before[3]));
+    }
+
+    #[test]
+    fn front_insertion_into_a_long_list_moves_no_pods() {
+        let mut slots = leaves(50_000);
+        // The removal frees a slot, so the insertion doesn't grow the storage either.
+        let _ = slots.pop();
+        let addresses = |slots: &PodSlots| -> Vec<*const Pod> {
+            slots.iter().map(|pod| pod as *const Pod).collect()
+        };
+        let before = addresses(&slots);
+        let before_ids = ids(&slots);
+
+        let mut splice = slots.splice();
+        splice.push(Pod::new(Leaf::new(10.0, 10.0)));
+        splice.skip(before.len());
+        assert_eq!(splice.len(), 50_000);
+
+        // Every child is still there, at the same address.
+        let after = addresses(&slots);
+        let moved = before
+            .iter()
+            .zip(&after[1..])
+            .filter(|(a, b)| a != b)
+            .count();
+        assert_eq!(moved, 0);
+        assert_eq!(ids(&slots)[1..], before_ids[..]);
+    }
+}
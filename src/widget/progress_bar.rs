@@ -0,0 +1,222 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vello::{
+    kurbo::{Rect, Size},
+    peniko::Color,
+    SceneBuilder,
+};
+
+use super::{
+    contexts::LifeCycleCx, piet_scene_helpers, AccessCx, BoxConstraints, ChangeFlags, Event,
+    EventCx, LayoutCx, LifeCycle, PaintCx, UpdateCx, Widget,
+};
+
+/// A bar showing the progress of a long-running task.
+///
+/// With a known progress the bar is filled up to that fraction. Without one, a segment sweeps
+/// across the bar for as long as the bar is visible.
+pub struct ProgressBar {
+    progress: Option<f64>,
+    /// The position of the sweeping segment in the indeterminate mode, in `0.0..1.0`.
+    phase: f64,
+    is_visible: bool,
+    is_paused: bool,
+}
+
+const BAR_HEIGHT: f64 = 6.0;
+const DEFAULT_WIDTH: f64 = 200.0;
+/// The time the indeterminate segment takes to sweep across the bar once, in seconds.
+const SWEEP_PERIOD: f64 = 1.5;
+/// The length of the indeterminate segment relative to the bar.
+const SEGMENT_LENGTH: f64 = 0.3;
+
+impl ProgressBar {
+    pub fn new(progress: Option<f64>) -> ProgressBar {
+        ProgressBar {
+            progress,
+            phase: 0.0,
+            is_visible: true,
+            is_paused: false,
+        }
+    }
+
+    /// The filled fraction of the bar, `None` in the indeterminate mode.
+    pub fn fraction(&self) -> Option<f64> {
+        self.progress.map(|progress| {
+            if progress.is_nan() {
+                0.0
+            } else {
+                progress.clamp(0.0, 1.0)
+            }
+        })
+    }
+
+    pub fn set_progress(&mut self, progress: Option<f64>) -> ChangeFlags {
+        let mode_changed = self.progress.is_some() != progress.is_some();
+        if self.progress == progress {
+            return ChangeFlags::empty();
+        }
+        self.progress = progress;
+        let mut changes = ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY;
+        if mode_changed {
+            // Starts the animation, see `update`.
+            changes |= ChangeFlags::UPDATE;
+        }
+        changes
+    }
+
+    fn is_animating(&self) -> bool {
+        self.progress.is_none() && self.is_visible && !self.is_paused
+    }
+}
+
+impl Widget for ProgressBar {
+    fn event(&mut self, _cx: &mut EventCx, _event: &Event) {}
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        match event {
+            LifeCycle::AnimFrame(interval) => {
+                if self.is_animating() {
+                    let elapsed = *interval as f64 * 1e-9;
+                    self.phase = (self.phase + elapsed / SWEEP_PERIOD).fract();
+                    cx.request_paint();
+                    cx.request_anim_frame();
+                }
+            }
+            LifeCycle::ViewContextChanged(view) => {
+                self.is_visible = view.clip.intersect(cx.size().to_rect()).area() > 0.0;
+                if self.is_animating() {
+                    cx.request_anim_frame();
+                }
+            }
+            LifeCycle::AnimationsPaused(is_paused) => {
+                self.is_paused = *is_paused;
+                if self.is_animating() {
+                    cx.request_anim_frame();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        if self.is_animating() {
+            cx.request_anim_frame();
+        }
+    }
+
+    fn layout(&mut self, _cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            DEFAULT_WIDTH
+        };
+        bc.constrain(Size::new(width, BAR_HEIGHT))
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::ProgressIndicator);
+        match self.fraction() {
+            Some(fraction) => {
+                builder.set_numeric_value(fraction);
+                builder.set_min_numeric_value(0.0);
+                builder.set_max_numeric_value(1.0);
+            }
+            None => builder.set_busy(),
+        }
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let track = cx.size().to_rect();
+        let radius = track.height() * 0.5;
+        piet_scene_helpers::fill_color(
+            builder,
+            &track.to_rounded_rect(radius),
+            Color::rgb8(0x3a, 0x3a, 0x3a),
+        );
+        let (start, end) = match self.fraction() {
+            Some(fraction) => (0.0, fraction),
+            None => {
+                // The segment enters on the left and leaves on the right.
+                let head = self.phase * (1.0 + SEGMENT_LENGTH);
+                ((head - SEGMENT_LENGTH).max(0.0), head.min(1.0))
+            }
+        };
+        if end > start {
+            let filled = Rect::new(
+                track.x0 + start * track.width(),
+                track.y0,
+                track.x0 + end * track.width(),
+                track.y1,
+            );
+            piet_scene_helpers::fill_color(
+                builder,
+                &filled.to_rounded_rect(radius),
+                Color::rgb8(0x5a, 0x9b, 0xe6),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Axis;
+    use crate::widget::test_util::Harness;
+    use crate::widget::{LinearLayout, Pod};
+    use test_log::test;
+
+    #[test]
+    fn fraction_is_clamped() {
+        assert_eq!(ProgressBar::new(Some(-0.5)).fraction(), Some(0.0));
+        assert_eq!(ProgressBar::new(Some(0.25)).fraction(), Some(0.25));
+        assert_eq!(ProgressBar::new(Some(1.5)).fraction(), Some(1.0));
+        assert_eq!(ProgressBar::new(Some(f64::NAN)).fraction(), Some(0.0));
+        assert_eq!(ProgressBar::new(None).fraction(), None);
+    }
+
+    #[test]
+    fn mode_change_requests_update() {
+        let mut bar = ProgressBar::new(Some(0.5));
+        assert_eq!(
+            bar.set_progress(Some(0.75)),
+            ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY
+        );
+        assert!(bar.set_progress(None).contains(ChangeFlags::UPDATE));
+        assert_eq!(bar.set_progress(None), ChangeFlags::empty());
+    }
+
+    #[test]
+    fn frame_requests_cease_after_removal() {
+        let mut harness = Harness::new();
+        let bar = Pod::new(ProgressBar::new(None));
+        let mut root = Pod::new(LinearLayout::new(vec![bar], 0.0, Axis::Vertical));
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(100.0, 100.0));
+        harness.layout(&mut root, &bc);
+        harness.update(&mut root);
+        assert!(root.anim_frame_requested());
+
+        harness.lifecycle(&mut root, LifeCycle::AnimFrame(16_000_000));
+        assert!(root.anim_frame_requested());
+
+        root.downcast_mut::<LinearLayout>()
+            .unwrap()
+            .children
+            .clear();
+        harness.lifecycle(&mut root, LifeCycle::AnimFrame(16_000_000));
+        assert!(!root.anim_frame_requested());
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use glazier::KbKey;
+use vello::{
+    kurbo::{Circle, Point, Size},
+    peniko::Color,
+    SceneBuilder,
+};
+use xilem_core::Id;
+
+use crate::{IdPath, Message};
+
+use super::{
+    contexts::LifeCycleCx, piet_scene_helpers, AccessCx, BoxConstraints, ChangeFlags, Event,
+    EventCx, LayoutCx, LifeCycle, PaintCx, Pod, UpdateCx, Widget,
+};
+
+/// The message a [`Radio`] sends to its view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadioMessage {
+    /// The radio button was clicked.
+    Select,
+    /// An arrow key asked to move the selection by the given number of options.
+    Step(isize),
+}
+
+/// One option of an exclusive-choice group.
+///
+/// Radio buttons don't know their siblings. The view of the group decides which option is selected
+/// and hands focus to the newly selected radio when the selection was moved with the keyboard.
+pub struct Radio {
+    id_path: IdPath,
+    group: Id,
+    selected: bool,
+    focus_on_update: bool,
+    label: Pod,
+}
+
+const DOT_SIZE: f64 = 16.0;
+const LABEL_SPACING: f64 = 6.0;
+
+impl Radio {
+    pub fn new(id_path: &IdPath, group: Id, selected: bool, label: Pod) -> Radio {
+        Radio {
+            id_path: id_path.clone(),
+            group,
+            selected,
+            focus_on_update: false,
+            label,
+        }
+    }
+
+    /// The identity of the group this radio belongs to.
+    pub fn group(&self) -> Id {
+        self.group
+    }
+
+    pub fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    /// Sets whether this radio is selected.
+    ///
+    /// If `take_focus` is set, the radio requests keyboard focus during the next update.
+    pub fn set_selected(&mut self, selected: bool, take_focus: bool) -> ChangeFlags {
+        let mut changes = ChangeFlags::empty();
+        if self.selected != selected {
+            self.selected = selected;
+            changes |= ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY;
+        }
+        if take_focus {
+            self.focus_on_update = true;
+            changes |= ChangeFlags::UPDATE;
+        }
+        changes
+    }
+
+    pub fn label_mut(&mut self) -> &mut Pod {
+        &mut self.label
+    }
+
+    fn send(&self, cx: &mut EventCx, message: RadioMessage) {
+        cx.add_message(Message::new(self.id_path.clone(), message));
+    }
+}
+
+impl Widget for Radio {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        match event {
+            Event::MouseDown(_) => {
+                cx.set_active(true);
+                cx.request_focus();
+                cx.request_paint();
+            }
+            Event::MouseUp(_) => {
+                if cx.is_hot() && cx.is_active() {
+                    self.send(cx, RadioMessage::Select);
+                }
+                cx.set_active(false);
+                cx.request_paint();
+            }
+            Event::KeyDown(key_event) if cx.is_focused() => {
+                let message = match &key_event.key {
+                    KbKey::ArrowUp | KbKey::ArrowLeft => RadioMessage::Step(-1),
+                    KbKey::ArrowDown | KbKey::ArrowRight => RadioMessage::Step(1),
+                    KbKey::Character(c) if c == " " => RadioMessage::Select,
+                    _ => return,
+                };
+                self.send(cx, message);
+                cx.set_handled(true);
+            }
+            Event::TargetedAccessibilityAction(request) => {
+                if request.action == accesskit::Action::Default
+                    && cx.is_accesskit_target(request.target)
+                {
+                    self.send(cx, RadioMessage::Select);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        match event {
+            LifeCycle::HotChanged(_) => cx.request_paint(),
+            LifeCycle::BuildFocusChain => cx.register_for_focus(),
+            _ => (),
+        }
+        self.label.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        if self.focus_on_update {
+            self.focus_on_update = false;
+            cx.request_focus();
+            cx.request_paint();
+        }
+        self.label.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let label_bc = bc.shrink((DOT_SIZE + LABEL_SPACING, 0.0)).loosen();
+        let label_size = self.label.layout(cx, &label_bc);
+        let size = Size::new(
+            DOT_SIZE + LABEL_SPACING + label_size.width,
+            DOT_SIZE.max(label_size.height),
+        );
+        let origin = Point::new(
+            DOT_SIZE + LABEL_SPACING,
+            (size.height - label_size.height) * 0.5,
+        );
+        self.label.set_origin(cx, origin);
+        bc.constrain(size)
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.label.accessibility(cx);
+        if !cx.is_requested() {
+            return;
+        }
+
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::RadioButton);
+        builder.set_children(vec![self.label.id().into()]);
+        builder.set_labelled_by(vec![self.label.id().into()]);
+        builder.set_checked_state(if self.selected {
+            accesskit::CheckedState::True
+        } else {
+            accesskit::CheckedState::False
+        });
+        builder.set_default_action_verb(accesskit::DefaultActionVerb::Click);
+        builder.add_action(accesskit::Action::Default);
+        builder.add_action(accesskit::Action::Focus);
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let border_width = 1.5;
+        let center = Point::new(DOT_SIZE * 0.5, cx.size().height * 0.5);
+        let outline = Circle::new(center, (DOT_SIZE - border_width) * 0.5);
+        let border_color = if cx.is_focused() {
+            Color::rgb8(0x5a, 0x9b, 0xe6)
+        } else if cx.is_hot() {
+            Color::rgb8(0xa1, 0xa1, 0xa1)
+        } else {
+            Color::rgb8(0x6a, 0x6a, 0x6a)
+        };
+        piet_scene_helpers::fill_color(builder, &outline, Color::rgb8(0x3a, 0x3a, 0x3a));
+        piet_scene_helpers::stroke(builder, &outline, border_color, border_width);
+        if self.selected {
+            let dot = Circle::new(center, DOT_SIZE * 0.25);
+            piet_scene_helpers::fill_color(builder, &dot, Color::rgb8(0xf0, 0xf0, 0xea));
+        }
+        self.label.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.label);
+    }
+}
@@ -17,8 +17,14 @@
 //! Note: arguably this module should be renamed, perhaps we should use
 //! "event" for this level and maybe "message" at the View level.
 
-use glazier::{Modifiers, MouseButton, MouseButtons};
-use vello::kurbo::{Point, Rect, Vec2};
+use std::path::PathBuf;
+
+use glazier::{KbKey, KeyEvent, Modifiers, MouseButton, MouseButtons};
+use vello::kurbo::{Point, Rect, Size, Vec2};
+
+use super::registry::WidgetMessage;
+use super::timer::TimerToken;
+use crate::id::Id;
 
 #[derive(Debug, Clone)]
 pub enum Event {
@@ -27,7 +33,115 @@ pub enum Event {
     MouseMove(MouseEvent),
     MouseWheel(MouseEvent),
     MouseLeft(),
+    /// A touch, pen or other pointer was pressed down.
+    ///
+    /// The pointer events go to the widgets under the pointer, or only to the widget which
+    /// [captured](super::EventCx::capture_pointer_id) the pointer and its ancestors. Events of
+    /// the [primary](PointerEvent::is_primary) pointer which no widget handled are delivered
+    /// again as mouse events, so widgets which only know about the mouse follow the first
+    /// finger. The mouse itself only sends mouse events.
+    PointerDown(PointerEvent),
+    /// A pointer moved, pressed or hovering.
+    PointerMove(PointerEvent),
+    /// A pointer was lifted. Touches end with it.
+    PointerUp(PointerEvent),
+    /// The platform took the pointer away, for example for a system gesture, or the window
+    /// lost focus. Widgets drop what the pointer was doing without committing it.
+    PointerCancel(PointerEvent),
+    /// A key was pressed, delivered to the focused widget and its ancestors.
+    ///
+    /// Holding a key down repeats the press with [`KeyEvent::repeat`] set. Text fields insert
+    /// the [`Text`](Event::Text) following the press instead of its key.
+    KeyDown(KeyEvent),
+    /// A key was released, delivered to the focused widget and its ancestors.
+    KeyUp(KeyEvent),
+    /// Text typed on the keyboard, delivered to the focused widget and its ancestors.
+    ///
+    /// Follows the [`KeyDown`](Event::KeyDown) typing it, also when it repeats, unless a
+    /// widget handled the press.
+    Text(String),
     TargetedAccessibilityAction(accesskit::ActionRequest),
+    /// A timer requested by the receiving widget has elapsed.
+    Timer(TimerToken),
+    /// The overlay was dismissed by the user, delivered to the widget which showed it.
+    ///
+    /// See [`OverlayHandle::set_light_dismiss`](super::OverlayHandle::set_light_dismiss) and
+    /// [`EventCx::dismiss_overlay`](super::EventCx::dismiss_overlay).
+    OverlayDismissed {
+        owner: Id,
+        overlay: Id,
+    },
+    /// The window lost keyboard focus to another window.
+    ///
+    /// Overlays which are dismissed by pressing outside of them are dismissed as well.
+    WindowLostFocus,
+    /// The mouse entered this widget, delivered only to it.
+    ///
+    /// When the mouse moves between two children of a widget, the child it left gets a
+    /// [`PointerLeave`](Event::PointerLeave) first, then the other one this event, and the
+    /// parent neither. While a widget [captured](super::EventCx::capture_pointer) the pointer,
+    /// only it and its ancestors can be entered, as nothing else is hot. Touches and pens
+    /// cause these events while they emulate the mouse.
+    PointerEnter,
+    /// The mouse left this widget, or the window, delivered only to it. See
+    /// [`PointerEnter`](Event::PointerEnter).
+    PointerLeave,
+    /// Carries [`Event::PointerEnter`] to the widget with the id, where [`Pod`] turns it into
+    /// that event. Widgets forward it to their children like the other events.
+    ///
+    /// [`Pod`]: super::Pod
+    RoutePointerEnter(Id),
+    /// Carries [`Event::PointerLeave`] to the widget with the id, like
+    /// [`Event::RoutePointerEnter`].
+    RoutePointerLeave(Id),
+    /// The user asked to close the window, delivered to every widget.
+    ///
+    /// A widget which handles the event keeps the window open, for example to ask about unsaved
+    /// changes first. It can close the window later with
+    /// [`EventCx::close_window`](super::EventCx::close_window).
+    CloseRequested,
+    /// A drag entered the window, or started inside of it.
+    ///
+    /// Drag events go to the widgets under the drag, and to those it just left, see
+    /// [`EventCx::is_drag_over`](super::EventCx::is_drag_over).
+    DragEnter(DragEvent),
+    /// A drag moved over the window.
+    DragOver(DragEvent),
+    /// A drag left the window or was cancelled, delivered to the widgets it was over.
+    DragLeave(),
+    /// A drag was dropped. The target which takes it handles the event.
+    Drop(DragEvent),
+    /// A drag started with [`EventCx::start_drag`](super::EventCx::start_drag) ended,
+    /// delivered to the widget which started it.
+    ///
+    /// `accepted` tells whether a target took the payload, which is not the case when the drag
+    /// was dropped outside of a target accepting it or cancelled with Escape.
+    DragEnded {
+        source: Id,
+        accepted: bool,
+    },
+    /// A message sent to a widget through a [`WidgetHandle`](super::WidgetHandle), delivered only
+    /// to it.
+    WidgetMessage(WidgetMessage),
+    /// Carries [`Event::WidgetMessage`] to the widget with the id `target`, like
+    /// [`Event::RoutePointerEnter`]. Containers on the way see it, for example to scroll the
+    /// target into view.
+    RouteWidgetMessage {
+        target: Id,
+        message: WidgetMessage,
+    },
+}
+
+/// A drag of files from another application, or of a payload within the window.
+#[derive(Debug, Clone)]
+pub struct DragEvent {
+    /// The position of the drag in the coordinate space of the receiver.
+    pub pos: Point,
+    /// The position of the drag in the window coordinate space.
+    pub window_pos: Point,
+    /// The dragged files, empty for drags within the window, whose payload is available with
+    /// [`EventCx::drag_payload`](super::EventCx::drag_payload).
+    pub paths: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -38,17 +152,216 @@ pub struct MouseEvent {
     pub window_pos: Point,
     pub buttons: MouseButtons,
     pub mods: Modifiers,
-    pub count: u8,
+    /// The number of this press in a series of quick presses of the same button on the same
+    /// widget, 2 for the second press of a double click and 3 for a triple click. Counted by
+    /// the root, and 1 for events other than presses.
+    pub click_count: u8,
     pub focus: bool,
     pub button: MouseButton,
+    /// How far a wheel or trackpad scrolled, in [`wheel_unit`](MouseEvent::wheel_unit).
+    ///
+    /// Widgets receive the delta in pixels, with Shift turning vertical scrolling sideways.
     pub wheel_delta: Vec2,
+    pub wheel_unit: ScrollUnit,
+}
+
+/// Identifies one of several pointers which are down at the same time, like the fingers on a
+/// touchscreen. Ids can be reused once a pointer was lifted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PointerId(pub u64);
+
+/// The device behind a [`PointerEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerKind {
+    Mouse,
+    Touch,
+    Pen,
+}
+
+#[derive(Debug, Clone)]
+pub struct PointerEvent {
+    pub pointer_id: PointerId,
+    pub kind: PointerKind,
+    /// The position of the pointer in the coordinate space of the receiver.
+    pub pos: Point,
+    /// The position of the pointer in the window coordinate space.
+    pub window_pos: Point,
+    /// How hard the pointer is pressed, from 0 to 1, or 0.5 for devices which can't tell.
+    pub pressure: f64,
+    pub buttons: MouseButtons,
+    /// The button which changed, for presses and releases.
+    pub button: MouseButton,
+    pub mods: Modifiers,
+    /// Whether this is the first of the pointers which are down, or the only one hovering.
+    pub is_primary: bool,
+}
+
+impl PointerEvent {
+    /// The mouse event which stands in for this event for widgets which only handle the mouse.
+    pub(crate) fn to_mouse_event(&self) -> MouseEvent {
+        MouseEvent {
+            pos: self.pos,
+            window_pos: self.window_pos,
+            buttons: self.buttons,
+            mods: self.mods,
+            click_count: 1,
+            focus: false,
+            button: self.button,
+            wheel_delta: Vec2::ZERO,
+            wheel_unit: ScrollUnit::Pixels,
+        }
+    }
+}
+
+impl From<&MouseEvent> for DragEvent {
+    fn from(mouse: &MouseEvent) -> Self {
+        DragEvent {
+            pos: mouse.pos,
+            window_pos: mouse.window_pos,
+            paths: vec![],
+        }
+    }
+}
+
+impl Event {
+    /// The pointer event carried by this event, if it is one.
+    pub fn pointer(&self) -> Option<&PointerEvent> {
+        match self {
+            Event::PointerDown(pointer)
+            | Event::PointerMove(pointer)
+            | Event::PointerUp(pointer)
+            | Event::PointerCancel(pointer) => Some(pointer),
+            _ => None,
+        }
+    }
+
+    /// The same kind of pointer event, carrying `pointer` instead.
+    pub(crate) fn with_pointer(&self, pointer: PointerEvent) -> Event {
+        match self {
+            Event::PointerDown(_) => Event::PointerDown(pointer),
+            Event::PointerMove(_) => Event::PointerMove(pointer),
+            Event::PointerUp(_) => Event::PointerUp(pointer),
+            Event::PointerCancel(_) => Event::PointerCancel(pointer),
+            _ => self.clone(),
+        }
+    }
+}
+
+/// The text typed with `key`, which is its character unless Ctrl or Cmd turn it into a
+/// shortcut.
+pub(crate) fn typed_text(key: &KeyEvent) -> Option<String> {
+    match &key.key {
+        KbKey::Character(c) if !key.mods.ctrl() && !key.mods.meta() => Some(c.clone()),
+        _ => None,
+    }
+}
+
+/// The unit of [`MouseEvent::wheel_delta`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollUnit {
+    /// Lines of text, as reported by wheels which scroll in notches.
+    Lines,
+    /// Pixels, as reported by trackpads and by the shell.
+    Pixels,
+}
+
+impl MouseEvent {
+    /// Converts the wheel delta to pixels, where a line is `line_height` high, and turns
+    /// vertical scrolling with Shift held into horizontal scrolling.
+    pub(crate) fn normalize_wheel(&mut self, line_height: f64) {
+        if self.wheel_unit == ScrollUnit::Lines {
+            self.wheel_delta *= line_height;
+            self.wheel_unit = ScrollUnit::Pixels;
+        }
+        if self.mods.shift() && self.wheel_delta.x == 0.0 {
+            self.wheel_delta = Vec2::new(self.wheel_delta.y, 0.0);
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum LifeCycle {
     HotChanged(bool),
     ViewContextChanged(ViewContext),
+    /// Sent to widgets which requested an animation frame, carrying the nanoseconds since the
+    /// previous frame.
+    AnimFrame(u64),
     TreeUpdate,
+    /// Sent to a widget and its descendants once they are in the tree, before their first
+    /// layout.
+    ///
+    /// Widgets can register with the window here, and undo that with [`LifeCycle::Removed`].
+    Added,
+    /// Sent to a widget and its descendants with the frame after they were removed from the tree,
+    /// right before they are dropped.
+    ///
+    /// Exactly the widgets which got [`LifeCycle::Added`] get it, whichever way their pod was
+    /// dropped. Widgets which were moved to another place in the tree stay added.
+    Removed,
+    /// Collects the widgets which take keyboard focus, see
+    /// [`LifeCycleCx::register_for_focus`](super::LifeCycleCx::register_for_focus).
+    BuildFocusChain,
+    /// Sent to the widget which lost keyboard focus, with `false`, and to the one which gained
+    /// it, with `true`.
+    ///
+    /// Both are repainted, for their [focus ring](super::paint_focus_ring).
+    FocusChanged(bool),
+    /// Carries a focus change to the widgets involved, where [`Pod`] turns it into
+    /// [`LifeCycle::FocusChanged`]. Widgets forward it to their children like the other events.
+    ///
+    /// [`Pod`]: super::Pod
+    RouteFocusChanged {
+        old: Option<Id>,
+        new: Option<Id>,
+    },
+    /// Carries a change of [`PaintCx::is_focus_visible`] to the focused widget, whose [`Pod`]
+    /// repaints it. Widgets forward it to their children like the other events.
+    ///
+    /// [`PaintCx::is_focus_visible`]: super::PaintCx::is_focus_visible
+    /// [`Pod`]: super::Pod
+    RouteFocusVisibleChanged(Id),
+    /// Sent to a widget which lost its pointer capture before the pointer was released, because
+    /// another widget captured it or the window lost focus.
+    ///
+    /// See [`EventCx::capture_pointer`](super::EventCx::capture_pointer).
+    PointerCaptureLost,
+    /// Carries a lost pointer capture to the widget concerned, where [`Pod`] turns it into
+    /// [`LifeCycle::PointerCaptureLost`]. Widgets forward it to their children like the other
+    /// events.
+    ///
+    /// [`Pod`]: super::Pod
+    RoutePointerCaptureLost(Id),
+    /// Pauses animations in a subtree, or resumes them with `false`.
+    ///
+    /// The app sends it to the whole tree when the window is minimized and restored. Containers
+    /// send it to children which are not shown, and forward it to them as paused while they stay
+    /// hidden. Animated widgets stop requesting frames until they are resumed.
+    AnimationsPaused(bool),
+    /// Sent to a subtree when the values in its [`Env`](crate::Env) changed.
+    ///
+    /// [`Pod`](super::Pod) requests paint for every widget in the subtree, and layout unless
+    /// `layout` is false, which means only values used in painting changed, such as theme
+    /// colors. Widgets which cache values resolved from the environment drop them.
+    EnvChanged {
+        layout: bool,
+    },
+    /// Sent to a subtree when it became disabled or enabled, see
+    /// [`DisabledScope`](super::DisabledScope).
+    ///
+    /// [`Pod`](super::Pod) requests paint and accessibility for every widget in the subtree.
+    /// Disabled widgets lose hot, active and focus state.
+    DisabledChanged(bool),
+    /// Sent to every widget when the window got a new size, before the root is laid out at
+    /// that size.
+    WindowResized(Size),
+    /// Sent to every widget when the number of device pixels per logical pixel changed.
+    ///
+    /// [`Pod`](super::Pod) requests layout and paint for every widget in the subtree. Widgets
+    /// which cache text layouts drop them.
+    ScaleChanged(f64),
+    /// Sent to every widget when the window gained keyboard focus, with `true`, or lost it to
+    /// another window.
+    WindowFocus(bool),
 }
 
 #[derive(Debug)]
@@ -64,7 +377,7 @@ impl<'a> From<&'a glazier::MouseEvent> for MouseEvent {
             pos,
             buttons,
             mods,
-            count,
+            count: _,
             focus,
             button,
             wheel_delta,
@@ -74,10 +387,13 @@ impl<'a> From<&'a glazier::MouseEvent> for MouseEvent {
             window_pos: *pos,
             buttons: *buttons,
             mods: *mods,
-            count: *count,
+            // The root counts the clicks itself, see `ClickCounter`.
+            click_count: 1,
             focus: *focus,
             button: *button,
+            // The shell converts wheel notches to pixels itself.
             wheel_delta: *wheel_delta,
+            wheel_unit: ScrollUnit::Pixels,
         }
     }
 }
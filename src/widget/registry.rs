@@ -0,0 +1,366 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Finding the widgets of views by id or tag, to send them messages.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use vello::kurbo::{Point, Size};
+use vello::SceneBuilder;
+
+use crate::id::Id;
+use crate::IdPath;
+
+use super::{
+    AccessCx, BoxConstraints, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx, PaintCx, Pod,
+    UpdateCx, Widget,
+};
+
+/// A message for a widget, sent with [`WidgetHandle::send`] and delivered to the widget as
+/// [`Event::WidgetMessage`].
+#[derive(Clone)]
+pub enum WidgetMessage {
+    /// Gives keyboard focus to the widget, unless it is disabled. The pod of the widget takes
+    /// care of it.
+    Focus,
+    /// Asks the scroll containers around the widget to show it.
+    ScrollIntoView,
+    /// A message for widgets which know its type.
+    Custom(Arc<dyn Any + Send + Sync>),
+}
+
+impl fmt::Debug for WidgetMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WidgetMessage::Focus => f.write_str("Focus"),
+            WidgetMessage::ScrollIntoView => f.write_str("ScrollIntoView"),
+            WidgetMessage::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// What [`Registry::find_widget`] looks for: the tag or the id of a
+/// [tagged](crate::view::ViewExt::tagged) view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WidgetQuery<'a> {
+    Tag(&'a str),
+    View(Id),
+}
+
+impl<'a> From<&'a str> for WidgetQuery<'a> {
+    fn from(tag: &'a str) -> Self {
+        WidgetQuery::Tag(tag)
+    }
+}
+
+impl From<Id> for WidgetQuery<'_> {
+    fn from(id: Id) -> Self {
+        WidgetQuery::View(id)
+    }
+}
+
+/// Where the widget of a tagged view is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    /// The id path of the view, ending with its id.
+    id_path: IdPath,
+    widget: Id,
+    tag: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    by_view: HashMap<Id, Entry>,
+    /// The view having each tag.
+    tags: HashMap<String, Id>,
+    /// The messages sent since the app last delivered them, with their target widget.
+    pending: Vec<(Id, WidgetMessage)>,
+}
+
+/// The widgets of the [tagged](crate::view::ViewExt::tagged) views of a window, by view id and
+/// tag.
+///
+/// Tagged views enter their widget when they are built and rebuilt, and leave the registry with
+/// it when it is dropped. Clones share the registry, so it can be used from other threads.
+#[derive(Debug, Clone, Default)]
+pub struct Registry(Arc<Mutex<Entries>>);
+
+impl Registry {
+    /// The widget of the tagged view with the tag or id, if it is still in the tree.
+    pub fn find_widget<'a>(&self, query: impl Into<WidgetQuery<'a>>) -> Option<WidgetHandle> {
+        let entries = self.0.lock().unwrap();
+        let view = match query.into() {
+            WidgetQuery::Tag(tag) => *entries.tags.get(tag)?,
+            WidgetQuery::View(id) => id,
+        };
+        let entry = entries.by_view.get(&view)?;
+        Some(WidgetHandle {
+            id_path: entry.id_path.clone(),
+            widget: entry.widget,
+            registry: self.clone(),
+        })
+    }
+
+    /// The number of widgets in the registry.
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().by_view.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Enters the widget of the view at `id_path`, replacing the one it had before.
+    pub(crate) fn register(&self, id_path: &IdPath, widget: Id, tag: Option<String>) {
+        let view = *id_path.last().expect("tagged views have an id");
+        let mut entries = self.0.lock().unwrap();
+        if let Some(old_tag) = entries.by_view.get(&view).and_then(|old| old.tag.clone()) {
+            if entries.tags.get(&old_tag) == Some(&view) {
+                entries.tags.remove(&old_tag);
+            }
+        }
+        if let Some(tag) = &tag {
+            entries.tags.insert(tag.clone(), view);
+        }
+        let entry = Entry {
+            id_path: id_path.clone(),
+            widget,
+            tag,
+        };
+        entries.by_view.insert(view, entry);
+    }
+
+    /// Removes the widget of the view, unless the view entered another widget since.
+    pub(crate) fn unregister(&self, view: Id, widget: Id) {
+        let mut entries = self.0.lock().unwrap();
+        if entries.by_view.get(&view).map(|entry| entry.widget) != Some(widget) {
+            return;
+        }
+        let entry = entries.by_view.remove(&view).unwrap();
+        if let Some(tag) = entry.tag {
+            if entries.tags.get(&tag) == Some(&view) {
+                entries.tags.remove(&tag);
+            }
+        }
+    }
+
+    /// The messages sent since the last call, with their target widget.
+    pub(crate) fn take_pending(&self) -> Vec<(Id, WidgetMessage)> {
+        std::mem::take(&mut self.0.lock().unwrap().pending)
+    }
+}
+
+/// A widget found with [`Registry::find_widget`], to send it messages.
+#[derive(Debug, Clone)]
+pub struct WidgetHandle {
+    id_path: IdPath,
+    widget: Id,
+    registry: Registry,
+}
+
+impl WidgetHandle {
+    /// The id of the widget.
+    pub fn widget_id(&self) -> Id {
+        self.widget
+    }
+
+    /// The id path of the tagged view, ending with its id.
+    pub fn id_path(&self) -> &IdPath {
+        &self.id_path
+    }
+
+    /// Sends `message` to the widget with the next frame.
+    ///
+    /// The message is dropped when the widget was removed by then.
+    pub fn send(&self, message: WidgetMessage) {
+        let mut entries = self.registry.0.lock().unwrap();
+        entries.pending.push((self.widget, message));
+    }
+
+    /// Gives keyboard focus to the widget with the next frame.
+    pub fn focus(&self) {
+        self.send(WidgetMessage::Focus);
+    }
+
+    /// Asks the scroll containers around the widget to show it with the next frame.
+    pub fn scroll_into_view(&self) {
+        self.send(WidgetMessage::ScrollIntoView);
+    }
+}
+
+/// Keeps its child in the [`Registry`] for the view at `id_path`, until it is dropped.
+pub struct Tagged {
+    registry: Registry,
+    view: Id,
+    child: Pod,
+}
+
+impl Tagged {
+    pub fn new(registry: &Registry, id_path: &IdPath, tag: Option<String>, child: Pod) -> Self {
+        registry.register(id_path, child.id(), tag);
+        Tagged {
+            registry: registry.clone(),
+            view: *id_path.last().expect("tagged views have an id"),
+            child,
+        }
+    }
+
+    /// Enters the child again, with a new tag.
+    pub fn set_tag(&mut self, id_path: &IdPath, tag: Option<String>) {
+        self.registry.register(id_path, self.child.id(), tag);
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+}
+
+impl Drop for Tagged {
+    fn drop(&mut self) {
+        self.registry.unregister(self.view, self.child.id());
+    }
+}
+
+impl Widget for Tagged {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.child.event(cx, event);
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.child.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{Harness, Leaf};
+    use crate::widget::{ChangeFlags, LinearLayout};
+    use crate::Axis;
+    use test_log::test;
+
+    /// A leaf which logs the widget messages reaching it.
+    struct Receiver(Arc<Mutex<Vec<String>>>);
+
+    impl Widget for Receiver {
+        fn event(&mut self, _cx: &mut EventCx, event: &Event) {
+            if let Event::WidgetMessage(WidgetMessage::Custom(payload)) = event {
+                let text = payload.downcast_ref::<&str>().unwrap();
+                self.0.lock().unwrap().push(text.to_string());
+            }
+        }
+
+        fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+        fn update(&mut self, _cx: &mut UpdateCx) {}
+
+        fn layout(&mut self, _cx: &mut LayoutCx, _bc: &BoxConstraints) -> Size {
+            Size::new(10.0, 10.0)
+        }
+
+        fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+        fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+    }
+
+    #[test]
+    fn tagged_widgets_get_messages_until_removed() {
+        let mut harness = Harness::new();
+        let registry = Registry::default();
+        let log = Arc::new(Mutex::new(vec![]));
+        let id_path = vec![Id::next(), Id::next()];
+        let receiver = Pod::new(Receiver(log.clone()));
+        let receiver_id = receiver.id();
+        let tagged = Tagged::new(&registry, &id_path, Some("sidebar".into()), receiver);
+        let inner = vec![Pod::new(Leaf::new(10.0, 10.0)), Pod::new(tagged)];
+        let outer = vec![
+            Pod::new(Leaf::new(10.0, 10.0)),
+            Pod::new(LinearLayout::new(inner, 0.0, Axis::Horizontal)),
+        ];
+        let mut root = Pod::new(LinearLayout::new(outer, 0.0, Axis::Vertical));
+        harness.layout(&mut root, &BoxConstraints::UNBOUNDED);
+
+        let handle = registry.find_widget("sidebar").unwrap();
+        assert_eq!(handle.widget_id(), receiver_id);
+        assert_eq!(handle.id_path(), &id_path);
+        assert_eq!(
+            registry.find_widget(id_path[1]).map(|handle| handle.widget_id()),
+            Some(receiver_id)
+        );
+        handle.send(WidgetMessage::Custom(Arc::new("hello")));
+        handle.focus();
+        harness.deliver_widget_messages(&mut root, &registry);
+        assert_eq!(*log.lock().unwrap(), ["hello"]);
+        assert_eq!(harness.window_state.focus, Some(receiver_id));
+
+        // Removing the tagged view takes it out of the registry.
+        let outer = &mut root.downcast_mut::<LinearLayout>().unwrap().children;
+        outer[1].downcast_mut::<LinearLayout>().unwrap().children.pop();
+        let _ = root.mark(ChangeFlags::TREE);
+        harness.layout(&mut root, &BoxConstraints::UNBOUNDED);
+        assert!(registry.find_widget("sidebar").is_none());
+        assert!(registry.is_empty());
+
+        // Messages sent through a stale handle go nowhere.
+        handle.send(WidgetMessage::Custom(Arc::new("late")));
+        harness.deliver_widget_messages(&mut root, &registry);
+        assert_eq!(*log.lock().unwrap(), ["hello"]);
+    }
+
+    #[test]
+    fn retagging_replaces_the_old_tag() {
+        let registry = Registry::default();
+        let id_path = vec![Id::next()];
+        let child = Pod::new(Leaf::new(10.0, 10.0));
+        let mut tagged = Tagged::new(&registry, &id_path, Some("old".into()), child);
+        tagged.set_tag(&id_path, Some("new".into()));
+        assert!(registry.find_widget("old").is_none());
+        assert!(registry.find_widget("new").is_some());
+
+        drop(tagged);
+        assert!(registry.find_widget("new").is_none());
+        assert!(registry.find_widget(id_path[0]).is_none());
+    }
+}
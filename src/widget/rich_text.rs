@@ -0,0 +1,458 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use glazier::Cursor;
+use parley::style::{FontStyle, FontWeight, StyleProperty};
+use parley::{FontContext, Layout};
+use vello::{
+    kurbo::{Affine, Point, Rect, Size},
+    peniko::{Brush, Color},
+    SceneBuilder,
+};
+
+use crate::text::ParleyBrush;
+use crate::{IdPath, Message};
+
+use super::{
+    contexts::LifeCycleCx, piet_scene_helpers, AccessCx, BoxConstraints, ChangeFlags, Event,
+    EventCx, LayoutCx, LifeCycle, PaintCx, TextAlignment, UpdateCx, Widget,
+};
+
+/// Overrides of the default text style for a [`TextSpan`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SpanStyle {
+    pub weight: Option<FontWeight>,
+    pub italic: bool,
+    pub size: Option<f32>,
+    pub color: Option<Color>,
+}
+
+impl SpanStyle {
+    pub fn bold() -> Self {
+        SpanStyle {
+            weight: Some(FontWeight::BOLD),
+            ..Default::default()
+        }
+    }
+
+    pub fn italic() -> Self {
+        SpanStyle {
+            italic: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_weight(mut self, weight: FontWeight) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// A run of text in a [`RichText`] with its own style.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSpan {
+    pub text: String,
+    pub style: SpanStyle,
+    /// Whether the span is a link, which can be clicked.
+    pub is_link: bool,
+}
+
+/// Wrapping text made of differently styled spans, some of which can be links.
+///
+/// Clicking a link sends the index of its span to the view at `id_path`. Wrapping and alignment
+/// work like with [`Label`](super::Label).
+pub struct RichText {
+    id_path: IdPath,
+    spans: Vec<TextSpan>,
+    /// The text of all spans.
+    text: String,
+    text_size: f32,
+    text_color: Color,
+    alignment: TextAlignment,
+    /// The laid out text and the wrap width it was built for.
+    layout: Option<(Layout<ParleyBrush>, Option<f64>)>,
+    /// The parts of the links on each line, in layout order.
+    link_areas: Vec<LinkArea>,
+    /// The span index of the link under the pointer.
+    hovered: Option<usize>,
+    /// The span index of the link the pointer went down on.
+    pressed: Option<usize>,
+    /// The ids of the accessibility nodes for the links, grown as needed.
+    link_ids: Vec<crate::id::Id>,
+}
+
+/// The part of a link on one line.
+struct LinkArea {
+    span: usize,
+    rect: Rect,
+    baseline: f64,
+}
+
+const DEFAULT_TEXT_SIZE: f32 = 16.0;
+const LINK_COLOR: Color = Color::rgb8(0x5a, 0x9b, 0xe6);
+const UNDERLINE_OFFSET: f64 = 2.0;
+
+impl RichText {
+    pub fn new(id_path: &IdPath, spans: Vec<TextSpan>) -> RichText {
+        RichText {
+            id_path: id_path.clone(),
+            text: spans.iter().map(|span| span.text.as_str()).collect(),
+            spans,
+            text_size: DEFAULT_TEXT_SIZE,
+            text_color: Color::rgb8(0xf0, 0xf0, 0xea),
+            alignment: TextAlignment::default(),
+            layout: None,
+            link_areas: vec![],
+            hovered: None,
+            pressed: None,
+            link_ids: vec![],
+        }
+    }
+
+    pub fn spans(&self) -> &[TextSpan] {
+        &self.spans
+    }
+
+    /// The text of all spans.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the spans, the text is only laid out again if they differ.
+    pub fn set_spans(&mut self, spans: Vec<TextSpan>) -> ChangeFlags {
+        if self.spans == spans {
+            return ChangeFlags::empty();
+        }
+        self.text = spans.iter().map(|span| span.text.as_str()).collect();
+        self.spans = spans;
+        self.hovered = None;
+        self.pressed = None;
+        self.invalidate() | ChangeFlags::ACCESSIBILITY
+    }
+
+    pub fn set_text_size(&mut self, text_size: f32) -> ChangeFlags {
+        if self.text_size == text_size {
+            return ChangeFlags::empty();
+        }
+        self.text_size = text_size;
+        self.invalidate()
+    }
+
+    pub fn set_text_color(&mut self, text_color: Color) -> ChangeFlags {
+        if self.text_color == text_color {
+            return ChangeFlags::empty();
+        }
+        self.text_color = text_color;
+        // Unlike in a label, the colors are part of the shaped text, as spans may have their own.
+        self.invalidate()
+    }
+
+    pub fn set_alignment(&mut self, alignment: TextAlignment) -> ChangeFlags {
+        if self.alignment == alignment {
+            return ChangeFlags::empty();
+        }
+        self.alignment = alignment;
+        self.invalidate()
+    }
+
+    /// The span index of the link at `pos`, if any.
+    pub fn link_at(&self, pos: Point) -> Option<usize> {
+        self.link_areas
+            .iter()
+            .find(|area| area.rect.contains(pos))
+            .map(|area| area.span)
+    }
+
+    fn invalidate(&mut self) -> ChangeFlags {
+        self.layout = None;
+        ChangeFlags::LAYOUT | ChangeFlags::PAINT
+    }
+
+    /// The byte ranges of the spans in the text.
+    fn span_ranges(&self) -> impl Iterator<Item = (usize, &TextSpan, std::ops::Range<usize>)> {
+        let mut start = 0;
+        self.spans.iter().enumerate().map(move |(index, span)| {
+            let range = start..start + span.text.len();
+            start = range.end;
+            (index, span, range)
+        })
+    }
+
+    fn build_layout(
+        &self,
+        font_cx: &mut FontContext,
+        wrap_width: Option<f64>,
+    ) -> Layout<ParleyBrush> {
+        let mut lcx = parley::LayoutContext::new();
+        let mut layout_builder = lcx.ranged_builder(font_cx, &self.text, 1.0);
+        layout_builder.push_default(&StyleProperty::Brush(ParleyBrush(Brush::Solid(
+            self.text_color,
+        ))));
+        layout_builder.push_default(&StyleProperty::FontSize(self.text_size));
+        for (_, span, range) in self.span_ranges() {
+            let style = &span.style;
+            if let Some(weight) = style.weight {
+                layout_builder.push(&StyleProperty::FontWeight(weight), range.clone());
+            }
+            if style.italic {
+                layout_builder.push(&StyleProperty::FontStyle(FontStyle::Italic), range.clone());
+            }
+            if let Some(size) = style.size {
+                layout_builder.push(&StyleProperty::FontSize(size), range.clone());
+            }
+            let color = style.color.or(span.is_link.then_some(LINK_COLOR));
+            if let Some(color) = color {
+                let brush = ParleyBrush(Brush::Solid(color));
+                layout_builder.push(&StyleProperty::Brush(brush), range);
+            }
+        }
+        let mut layout = layout_builder.build();
+        layout.break_all_lines(wrap_width.map(|w| w as f32), self.alignment.into());
+        layout
+    }
+
+    /// Finds the parts of the links on each line of `layout`.
+    fn find_link_areas(&self, layout: &Layout<ParleyBrush>) -> Vec<LinkArea> {
+        let mut areas = vec![];
+        for line in layout.lines() {
+            let line_range = line.text_range();
+            let metrics = line.metrics();
+            // Trailing whitespace hangs past the end of the line and is not hit.
+            let line_end = (metrics.offset + metrics.advance - metrics.trailing_whitespace) as f64;
+            let x_of = |offset: usize| {
+                if offset >= line_range.end {
+                    line_end
+                } else {
+                    parley::layout::Cursor::from_position(layout, offset, true).offset as f64
+                }
+            };
+            let baseline = metrics.baseline as f64;
+            let top = baseline - (metrics.ascent + metrics.leading * 0.5) as f64;
+            let bottom = baseline + (metrics.descent + metrics.leading * 0.5) as f64;
+            for (index, span, range) in self.span_ranges() {
+                let start = range.start.max(line_range.start);
+                let end = range.end.min(line_range.end);
+                if !span.is_link || start >= end {
+                    continue;
+                }
+                let (x0, x1) = (x_of(start), x_of(end));
+                if x1 > x0 {
+                    areas.push(LinkArea {
+                        span: index,
+                        rect: Rect::new(x0, top, x1, bottom),
+                        baseline,
+                    });
+                }
+            }
+        }
+        areas
+    }
+
+    fn set_hovered(&mut self, cx: &mut EventCx, hovered: Option<usize>) {
+        if self.hovered != hovered {
+            self.hovered = hovered;
+            cx.request_paint();
+        }
+        if hovered.is_some() {
+            cx.set_cursor(&Cursor::Pointer);
+        }
+    }
+}
+
+impl Widget for RichText {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        match event {
+            Event::MouseMove(mouse_event) => {
+                let hovered = self.link_at(mouse_event.pos).filter(|_| cx.is_hot());
+                self.set_hovered(cx, hovered);
+            }
+            Event::MouseDown(mouse_event) => {
+                self.pressed = self.link_at(mouse_event.pos);
+                if self.pressed.is_some() {
+                    cx.set_active(true);
+                    cx.set_handled(true);
+                    cx.set_cursor(&Cursor::Pointer);
+                }
+            }
+            Event::MouseUp(mouse_event) => {
+                if let Some(pressed) = self.pressed.take() {
+                    cx.set_active(false);
+                    // Releasing on another part of the same link, for example on the next line,
+                    // still counts as a click.
+                    if self.link_at(mouse_event.pos) == Some(pressed) {
+                        cx.add_message(Message::new(self.id_path.clone(), pressed));
+                    }
+                }
+                let hovered = self.link_at(mouse_event.pos).filter(|_| cx.is_hot());
+                self.set_hovered(cx, hovered);
+            }
+            Event::MouseLeft() => self.set_hovered(cx, None),
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        match event {
+            LifeCycle::HotChanged(false) => {
+                if self.hovered.take().is_some() {
+                    cx.request_paint();
+                }
+            }
+            // The pod requests layout, where the text is shaped anew.
+            LifeCycle::ScaleChanged(_) => self.layout = None,
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, _cx: &mut UpdateCx) {}
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let wrap_width = bc.is_width_bounded().then(|| bc.max().width);
+        let is_cached = matches!(&self.layout, Some((_, width)) if *width == wrap_width);
+        if !is_cached {
+            let layout = self.build_layout(cx.font_cx(), wrap_width);
+            self.link_areas = self.find_link_areas(&layout);
+            self.layout = Some((layout, wrap_width));
+        }
+        let (layout, _) = self.layout.as_ref().unwrap();
+        bc.constrain(Size::new(layout.width() as f64, layout.height() as f64))
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        let links: Vec<&TextSpan> = self.spans.iter().filter(|span| span.is_link).collect();
+        while self.link_ids.len() < links.len() {
+            self.link_ids.push(crate::id::Id::next());
+        }
+        for (span, id) in links.iter().zip(&self.link_ids) {
+            let mut link = accesskit::NodeBuilder::new(accesskit::Role::Link);
+            link.set_name(span.text.as_str());
+            cx.push_child_node(*id, link);
+        }
+
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::StaticText);
+        builder.set_name(self.text.as_str());
+        let link_ids = &self.link_ids[..links.len()];
+        builder.set_children(link_ids.iter().map(|id| (*id).into()).collect::<Vec<_>>());
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, _cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let Some((layout, _)) = &self.layout else {
+            return;
+        };
+        crate::text::render_text(builder, Affine::IDENTITY, layout);
+        let Some(hovered) = self.hovered else {
+            return;
+        };
+        let color = self.spans[hovered].style.color.unwrap_or(LINK_COLOR);
+        for area in self.link_areas.iter().filter(|area| area.span == hovered) {
+            let y = area.baseline + UNDERLINE_OFFSET;
+            let underline = Rect::new(area.rect.x0, y, area.rect.x1, y + 1.0);
+            piet_scene_helpers::fill_color(builder, &underline, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{mouse, Harness};
+    use crate::widget::Pod;
+    use test_log::test;
+    use xilem_core::Id;
+
+    fn span(text: &str, is_link: bool) -> TextSpan {
+        TextSpan {
+            text: text.into(),
+            style: SpanStyle::default(),
+            is_link,
+        }
+    }
+
+    fn layout(harness: &mut Harness, pod: &mut Pod, max_width: f64) {
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(max_width, 1000.0));
+        harness.layout(pod, &bc);
+    }
+
+    fn clicked_spans(harness: &mut Harness) -> Vec<usize> {
+        harness
+            .take_messages()
+            .into_iter()
+            .map(|message| *message.body.downcast::<usize>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn wrapped_link_is_one_link() {
+        let mut harness = Harness::new();
+        let spans = vec![
+            span("Read ", false),
+            span("the whole documentation", true),
+            span(" first.", false),
+        ];
+        let mut pod = Pod::new(RichText::new(&vec![Id::next()], spans));
+        layout(&mut harness, &mut pod, 10_000.0);
+        let rich_text = pod.downcast_mut::<RichText>().unwrap();
+        assert_eq!(rich_text.link_areas.len(), 1);
+        let width = rich_text.link_areas[0].rect.x1;
+
+        // Wrapping right before the end of the link splits it over two lines.
+        layout(&mut harness, &mut pod, width - 1.0);
+        let rich_text = pod.downcast_mut::<RichText>().unwrap();
+        let points: Vec<Point> = rich_text
+            .link_areas
+            .iter()
+            .map(|area| area.rect.center())
+            .collect();
+        assert!(points.len() >= 2);
+        assert!(points[0].y < points[points.len() - 1].y);
+
+        for point in &points {
+            harness.event(&mut pod, Event::MouseMove(mouse(*point)));
+            assert!(matches!(harness.window_state.cursor(), Cursor::Pointer));
+            harness.event(&mut pod, Event::MouseDown(mouse(*point)));
+            harness.event(&mut pod, Event::MouseUp(mouse(*point)));
+        }
+        assert_eq!(clicked_spans(&mut harness), vec![1; points.len()]);
+
+        // Pressing on one line and releasing on the other is still a click.
+        harness.event(&mut pod, Event::MouseDown(mouse(points[0])));
+        harness.event(&mut pod, Event::MouseUp(mouse(points[points.len() - 1])));
+        assert_eq!(clicked_spans(&mut harness), [1]);
+    }
+
+    #[test]
+    fn plain_text_is_not_clickable() {
+        let mut harness = Harness::new();
+        let spans = vec![span("plain ", false), span("link", true)];
+        let mut pod = Pod::new(RichText::new(&vec![Id::next()], spans));
+        layout(&mut harness, &mut pod, 10_000.0);
+
+        harness.event(&mut pod, Event::MouseMove(mouse((2.0, 8.0))));
+        assert!(matches!(harness.window_state.cursor(), Cursor::Arrow));
+        harness.event(&mut pod, Event::MouseDown(mouse((2.0, 8.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((2.0, 8.0))));
+        assert!(clicked_spans(&mut harness).is_empty());
+    }
+}
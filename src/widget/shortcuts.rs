@@ -0,0 +1,262 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use glazier::KeyEvent;
+use vello::{
+    kurbo::{Point, Size},
+    SceneBuilder,
+};
+
+use crate::{HotKey, IdPath, Message};
+
+use super::{
+    AccessCx, BoxConstraints, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx, PaintCx, Pod,
+    UpdateCx, Widget,
+};
+
+/// When the shortcuts of a [`Shortcuts`] widget are active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutScope {
+    /// As long as the widget is in the window.
+    Window,
+    /// While the widget or one of its descendants has keyboard focus.
+    Focus,
+}
+
+/// The shortcuts of a [`Shortcuts`] widget, shared with the window which dispatches them.
+pub(crate) struct Registration {
+    id_path: IdPath,
+    hotkeys: RefCell<Vec<HotKey>>,
+    scope: Cell<ShortcutScope>,
+    has_focus_within: Cell<bool>,
+}
+
+impl Registration {
+    /// The message for the view if one of the shortcuts matches `key` and they are active.
+    pub(crate) fn message_for(&self, key: &KeyEvent) -> Option<Message> {
+        if self.scope.get() == ShortcutScope::Focus && !self.has_focus_within.get() {
+            return None;
+        }
+        let index = self
+            .hotkeys
+            .borrow()
+            .iter()
+            .position(|hotkey| hotkey.matches(key))?;
+        Some(Message::new(self.id_path.clone(), index))
+    }
+}
+
+/// Sends the index of a keyboard shortcut to the view at `id_path` when it is pressed.
+///
+/// Shortcuts with modifiers take precedence over the focused widget, plain keys are only
+/// dispatched when the focused widget doesn't handle them or the text they type. Holding the
+/// keys of a shortcut fires it once. When several widgets have a matching
+/// shortcut, the innermost one wins. The shortcuts go away with the widget.
+pub struct Shortcuts {
+    child: Pod,
+    registration: Rc<Registration>,
+    is_registered: bool,
+}
+
+impl Shortcuts {
+    pub fn new(id_path: &IdPath, child: Pod, hotkeys: Vec<HotKey>, scope: ShortcutScope) -> Self {
+        Shortcuts {
+            child,
+            registration: Rc::new(Registration {
+                id_path: id_path.clone(),
+                hotkeys: RefCell::new(hotkeys),
+                scope: Cell::new(scope),
+                has_focus_within: Cell::new(false),
+            }),
+            is_registered: false,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    pub fn set_hotkeys(&mut self, hotkeys: Vec<HotKey>) {
+        *self.registration.hotkeys.borrow_mut() = hotkeys;
+    }
+
+    pub fn set_scope(&mut self, scope: ShortcutScope) {
+        self.registration.scope.set(scope);
+    }
+}
+
+impl Widget for Shortcuts {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.child.event(cx, event);
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.child.lifecycle(cx, event);
+        if let LifeCycle::RouteFocusChanged { .. } = event {
+            self.registration
+                .has_focus_within
+                .set(cx.has_focus_within());
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        if !self.is_registered {
+            let registration = Rc::downgrade(&self.registration);
+            cx.cx_state.window_state().shortcuts.push(registration);
+            self.is_registered = true;
+        }
+        self.child.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{mouse, Harness};
+    use crate::widget::TextBox;
+    use crate::RawMods;
+    use glazier::{KbKey, KeyState, Modifiers};
+    use test_log::test;
+    use xilem_core::Id;
+
+    fn key(c: &str, mods: Modifiers) -> Event {
+        Event::KeyDown(KeyEvent {
+            key: KbKey::Character(c.into()),
+            mods,
+            state: KeyState::Down,
+            ..Default::default()
+        })
+    }
+
+    /// The key held down, repeating its press.
+    fn repeat(c: &str, mods: Modifiers) -> Event {
+        Event::KeyDown(KeyEvent {
+            key: KbKey::Character(c.into()),
+            mods,
+            state: KeyState::Down,
+            repeat: true,
+            ..Default::default()
+        })
+    }
+
+    /// Sets up a text box inside shortcuts for Ctrl+S and plain "s".
+    fn shortcuts(harness: &mut Harness, scope: ShortcutScope) -> (IdPath, Pod) {
+        let id_path = vec![Id::next()];
+        let text_box = TextBox::new(&vec![Id::next()], String::new());
+        let hotkeys = vec![HotKey::new(RawMods::Ctrl, "s"), HotKey::new(None, "s")];
+        let mut pod = Pod::new(Shortcuts::new(&id_path, Pod::new(text_box), hotkeys, scope));
+        harness.update(&mut pod);
+        harness.layout(&mut pod, &BoxConstraints::tight(Size::new(200.0, 30.0)));
+        (id_path, pod)
+    }
+
+    /// The indices of the shortcuts sent to `id_path`.
+    fn fired(harness: &mut Harness, id_path: &IdPath) -> Vec<usize> {
+        harness
+            .take_messages()
+            .into_iter()
+            .filter(|message| message.id_path == *id_path)
+            .map(|message| *message.body.downcast::<usize>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn modifiers_win_over_a_focused_text_box() {
+        let mut harness = Harness::new();
+        let (id_path, mut pod) = shortcuts(&mut harness, ShortcutScope::Window);
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((10.0, 10.0))));
+        assert!(harness.window_state.focus.is_some());
+
+        harness.event(&mut pod, key("s", Modifiers::CONTROL));
+        assert_eq!(fired(&mut harness, &id_path), [0]);
+        harness.event(&mut pod, key("s", Modifiers::empty()));
+        assert!(fired(&mut harness, &id_path).is_empty());
+
+        // Without focus the plain key reaches the shortcut.
+        harness.window_state.focus = None;
+        harness.event(&mut pod, key("s", Modifiers::empty()));
+        assert_eq!(fired(&mut harness, &id_path), [1]);
+
+        // Removing the widget removes its shortcuts.
+        drop(pod);
+        let mut other = Pod::new(TextBox::new(&vec![Id::next()], String::new()));
+        harness.event(&mut other, key("s", Modifiers::CONTROL));
+        assert!(fired(&mut harness, &id_path).is_empty());
+        assert!(harness.window_state.shortcuts.is_empty());
+    }
+
+    #[test]
+    fn focus_scope_needs_focus_inside() {
+        let mut harness = Harness::new();
+        let (id_path, mut pod) = shortcuts(&mut harness, ShortcutScope::Focus);
+
+        harness.event(&mut pod, key("s", Modifiers::CONTROL));
+        assert!(fired(&mut harness, &id_path).is_empty());
+
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut pod, key("s", Modifiers::CONTROL));
+        assert_eq!(fired(&mut harness, &id_path), [0]);
+    }
+
+    #[test]
+    fn held_keys_fire_once_but_type_every_repeat() {
+        let mut harness = Harness::new();
+        let (id_path, mut pod) = shortcuts(&mut harness, ShortcutScope::Window);
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((10.0, 10.0))));
+
+        harness.event(&mut pod, key("s", Modifiers::CONTROL));
+        for _ in 0..3 {
+            harness.event(&mut pod, repeat("s", Modifiers::CONTROL));
+        }
+        assert_eq!(fired(&mut harness, &id_path), [0]);
+
+        harness.event(&mut pod, key("s", Modifiers::empty()));
+        for _ in 0..3 {
+            harness.event(&mut pod, repeat("s", Modifiers::empty()));
+        }
+        let shortcuts = pod.downcast_mut::<Shortcuts>().unwrap();
+        let text_box = shortcuts.child.downcast_mut::<TextBox>().unwrap();
+        assert_eq!(text_box.text(), "ssss");
+        assert!(fired(&mut harness, &id_path).is_empty());
+    }
+}
@@ -0,0 +1,668 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+use glazier::KbKey;
+use vello::{
+    kurbo::{Circle, Point, Rect, Size},
+    SceneBuilder,
+};
+
+use crate::geometry::Axis;
+use crate::{IdPath, Message, Theme};
+
+use super::{
+    contexts::LifeCycleCx, paint_focus_ring_around, piet_scene_helpers, AccessCx, BoxConstraints,
+    ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, PaintCx, UpdateCx, Widget, FOCUS_RING_EXTENT,
+};
+
+/// The message a [`Slider`] sends to its view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SliderMessage {
+    /// The value changed, possibly in the middle of a drag.
+    Changed(f64),
+    /// The user finished changing the value.
+    Committed(f64),
+}
+
+/// A track with a draggable thumb selecting a value in a range.
+///
+/// The value grows to the right of a horizontal slider and up a vertical one, so that the up
+/// arrow and scrolling up raise the value either way.
+/// While the thumb is dragged the slider owns its value, [`set_value`](Slider::set_value) only
+/// takes effect again after the thumb was released. The same goes for holding a key, whose
+/// repeated presses are committed once it is released.
+pub struct Slider {
+    id_path: IdPath,
+    value: f64,
+    range: Range<f64>,
+    step: Option<f64>,
+    axis: Axis,
+    is_dragging: bool,
+    /// Whether a held key changed the value since it was committed.
+    is_key_held: bool,
+}
+
+const THUMB_SIZE: f64 = 20.0;
+const TRACK_WIDTH: f64 = 4.0;
+const DEFAULT_LENGTH: f64 = 200.0;
+
+impl Slider {
+    pub fn new(id_path: &IdPath, value: f64, range: Range<f64>, axis: Axis) -> Slider {
+        let mut slider = Slider {
+            id_path: id_path.clone(),
+            value: 0.0,
+            range,
+            step: None,
+            axis,
+            is_dragging: false,
+            is_key_held: false,
+        };
+        slider.value = slider.quantize(value);
+        slider
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Sets the value, unless the thumb is currently dragged.
+    pub fn set_value(&mut self, value: f64) -> ChangeFlags {
+        let value = self.quantize(value);
+        if self.is_dragging || value == self.value {
+            return ChangeFlags::empty();
+        }
+        self.value = value;
+        ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY
+    }
+
+    pub fn set_range(&mut self, range: Range<f64>) -> ChangeFlags {
+        if self.range == range {
+            return ChangeFlags::empty();
+        }
+        self.range = range;
+        self.value = self.quantize(self.value);
+        ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY
+    }
+
+    pub fn set_step(&mut self, step: Option<f64>) -> ChangeFlags {
+        let step = step.filter(|step| *step > 0.0);
+        if self.step == step {
+            return ChangeFlags::empty();
+        }
+        self.step = step;
+        self.value = self.quantize(self.value);
+        ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY
+    }
+
+    pub fn set_axis(&mut self, axis: Axis) -> ChangeFlags {
+        if self.axis == axis {
+            return ChangeFlags::empty();
+        }
+        self.axis = axis;
+        ChangeFlags::LAYOUT | ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY
+    }
+
+    /// Clamps `value` to the range and rounds it to the nearest step.
+    fn quantize(&self, value: f64) -> f64 {
+        let Range { start, end } = self.range;
+        let value = match self.step {
+            Some(step) => start + ((value - start) / step).round() * step,
+            None => value,
+        };
+        value.clamp(start.min(end), end.max(start))
+    }
+
+    /// The distance the center of the thumb can travel for a slider of the given major length.
+    fn track_length(length: f64) -> f64 {
+        (length - THUMB_SIZE).max(0.0)
+    }
+
+    /// Converts between positions on the major axis and positions along the track, which runs
+    /// from the bottom up for vertical sliders.
+    fn along_track(&self, pos: f64, length: f64) -> f64 {
+        match self.axis {
+            Axis::Horizontal => pos,
+            Axis::Vertical => length - pos,
+        }
+    }
+
+    /// Maps a position on the major axis to a value.
+    fn value_at(&self, pos: f64, length: f64) -> f64 {
+        let pos = self.along_track(pos, length);
+        let track_length = Self::track_length(length);
+        let fraction = if track_length > 0.0 {
+            ((pos - THUMB_SIZE * 0.5) / track_length).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        self.quantize(self.range.start + fraction * (self.range.end - self.range.start))
+    }
+
+    /// Maps the current value to the position of the thumb's center on the major axis.
+    fn thumb_pos(&self, length: f64) -> f64 {
+        let span = self.range.end - self.range.start;
+        let fraction = if span != 0.0 {
+            (self.value - self.range.start) / span
+        } else {
+            0.0
+        };
+        let pos = THUMB_SIZE * 0.5 + fraction * Self::track_length(length);
+        self.along_track(pos, length)
+    }
+
+    fn small_step(&self) -> f64 {
+        self.step
+            .unwrap_or((self.range.end - self.range.start) / 100.0)
+    }
+
+    fn big_step(&self) -> f64 {
+        self.small_step()
+            .max((self.range.end - self.range.start) / 10.0)
+    }
+
+    /// Moves the value and reports it. Returns whether the value changed.
+    fn change_to(&mut self, cx: &mut EventCx, value: f64) -> bool {
+        let value = self.quantize(value);
+        if value == self.value {
+            return false;
+        }
+        self.value = value;
+        cx.add_message(Message::new(
+            self.id_path.clone(),
+            SliderMessage::Changed(value),
+        ));
+        cx.request_paint();
+        true
+    }
+
+    fn commit(&self, cx: &mut EventCx) {
+        cx.add_message(Message::new(
+            self.id_path.clone(),
+            SliderMessage::Committed(self.value),
+        ));
+    }
+}
+
+impl Widget for Slider {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        let length = self.axis.major(cx.size());
+        match event {
+            Event::MouseDown(mouse_event) => {
+                // The thumb follows the pointer also outside of the slider and the window.
+                cx.capture_pointer();
+                cx.request_focus();
+                self.is_dragging = true;
+                let value = self.value_at(self.axis.major(mouse_event.pos), length);
+                self.change_to(cx, value);
+                cx.request_paint();
+            }
+            Event::MouseMove(mouse_event) => {
+                if cx.has_pointer_capture() {
+                    let value = self.value_at(self.axis.major(mouse_event.pos), length);
+                    self.change_to(cx, value);
+                }
+            }
+            Event::MouseUp(_) => {
+                if cx.has_pointer_capture() {
+                    cx.release_pointer();
+                    self.is_dragging = false;
+                    self.commit(cx);
+                    cx.request_paint();
+                }
+            }
+            Event::KeyDown(key_event) if cx.is_focused() => {
+                let value = match &key_event.key {
+                    KbKey::ArrowRight | KbKey::ArrowUp => self.value + self.small_step(),
+                    KbKey::ArrowLeft | KbKey::ArrowDown => self.value - self.small_step(),
+                    KbKey::PageUp => self.value + self.big_step(),
+                    KbKey::PageDown => self.value - self.big_step(),
+                    KbKey::Home => self.range.start,
+                    KbKey::End => self.range.end,
+                    _ => return,
+                };
+                if self.change_to(cx, value) {
+                    if key_event.repeat {
+                        self.is_key_held = true;
+                    } else {
+                        self.commit(cx);
+                    }
+                }
+                cx.set_handled(true);
+            }
+            Event::KeyUp(_) if self.is_key_held => {
+                self.is_key_held = false;
+                self.commit(cx);
+            }
+            Event::MouseWheel(mouse_event) if cx.is_focused() && !self.is_dragging => {
+                // Scrolling up or to the left raises the value, whichever way the slider lies.
+                let delta = mouse_event.wheel_delta;
+                let delta = if delta.x.abs() > delta.y.abs() {
+                    delta.x
+                } else {
+                    delta.y
+                };
+                if delta == 0.0 {
+                    return;
+                }
+                if self.change_to(cx, self.value - delta.signum() * self.small_step()) {
+                    self.commit(cx);
+                }
+                cx.set_handled(true);
+            }
+            Event::TargetedAccessibilityAction(request)
+                if cx.is_accesskit_target(request.target) =>
+            {
+                let value = match (request.action, &request.data) {
+                    (accesskit::Action::Increment, _) => self.value + self.small_step(),
+                    (accesskit::Action::Decrement, _) => self.value - self.small_step(),
+                    (
+                        accesskit::Action::SetValue,
+                        Some(accesskit::ActionData::NumericValue(value)),
+                    ) => *value,
+                    _ => return,
+                };
+                if self.change_to(cx, value) {
+                    self.commit(cx);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        match event {
+            LifeCycle::HotChanged(_) => cx.request_paint(),
+            LifeCycle::BuildFocusChain => cx.register_for_focus(),
+            // The drag ends where it was, as if the pointer was released there.
+            LifeCycle::PointerCaptureLost if self.is_dragging => {
+                self.is_dragging = false;
+                cx.add_message(Message::new(
+                    self.id_path.clone(),
+                    SliderMessage::Committed(self.value),
+                ));
+                cx.request_paint();
+            }
+            // The release of the held key goes elsewhere.
+            LifeCycle::FocusChanged(false) if self.is_key_held => {
+                self.is_key_held = false;
+                cx.add_message(Message::new(
+                    self.id_path.clone(),
+                    SliderMessage::Committed(self.value),
+                ));
+            }
+            _ => (),
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        cx.request_paint();
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let max_length = self.axis.major(bc.max());
+        let length = if max_length.is_finite() {
+            max_length
+        } else {
+            DEFAULT_LENGTH
+        };
+        cx.set_paint_insets(FOCUS_RING_EXTENT);
+        bc.constrain(self.axis.pack::<Size>(length, THUMB_SIZE))
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::Slider);
+        builder.set_numeric_value(self.value);
+        builder.set_min_numeric_value(self.range.start);
+        builder.set_max_numeric_value(self.range.end);
+        if let Some(step) = self.step {
+            builder.set_numeric_value_step(step);
+        }
+        builder.add_action(accesskit::Action::Increment);
+        builder.add_action(accesskit::Action::Decrement);
+        builder.add_action(accesskit::Action::SetValue);
+        builder.add_action(accesskit::Action::Focus);
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let size = cx.size();
+        let length = self.axis.major(size);
+        let center_minor = self.axis.minor(size) * 0.5;
+        let thumb_pos = self.thumb_pos(length);
+        let theme = Theme::get(cx.env());
+
+        let track_start = self
+            .axis
+            .pack::<Point>(THUMB_SIZE * 0.5, center_minor - TRACK_WIDTH * 0.5);
+        let track_end = self
+            .axis
+            .pack::<Point>(length - THUMB_SIZE * 0.5, center_minor + TRACK_WIDTH * 0.5);
+        let track = Rect::from_points(track_start, track_end).to_rounded_rect(TRACK_WIDTH * 0.5);
+        piet_scene_helpers::fill_color(builder, &track, theme.background);
+        // The track is filled from the start of the range to the thumb.
+        let filled_start = self.axis.pack::<Point>(
+            self.along_track(THUMB_SIZE * 0.5, length),
+            center_minor - TRACK_WIDTH * 0.5,
+        );
+        let filled_end = self
+            .axis
+            .pack::<Point>(thumb_pos, center_minor + TRACK_WIDTH * 0.5);
+        let filled = Rect::from_points(filled_start, filled_end).to_rounded_rect(TRACK_WIDTH * 0.5);
+        let filled_color = if cx.is_disabled() {
+            theme.background_active
+        } else {
+            theme.accent
+        };
+        piet_scene_helpers::fill_color(builder, &filled, filled_color);
+
+        let thumb = Circle::new(
+            self.axis.pack::<Point>(thumb_pos, center_minor),
+            THUMB_SIZE * 0.5 - 1.0,
+        );
+        let thumb_color = if cx.is_disabled() {
+            theme.text_color.with_alpha_factor(0.5)
+        } else if cx.is_active() {
+            theme.text_color.with_alpha_factor(0.75)
+        } else if cx.is_hot() {
+            theme.text_color.with_alpha_factor(0.9)
+        } else {
+            theme.text_color
+        };
+        piet_scene_helpers::fill_color(builder, &thumb, thumb_color);
+        let thumb_bounds = Rect::from_center_size(thumb.center, Size::new(THUMB_SIZE, THUMB_SIZE));
+        paint_focus_ring_around(cx, builder, thumb_bounds, THUMB_SIZE * 0.5);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{key_down, mouse, touch, Harness};
+    use crate::widget::{Pod, ScrollUnit};
+    use glazier::{KeyEvent, KeyState, Modifiers};
+    use test_log::test;
+    use xilem_core::Id;
+
+    fn slider(harness: &mut Harness, step: Option<f64>) -> Pod {
+        let mut slider = Slider::new(&vec![Id::next()], 0.0, 0.0..100.0, Axis::Horizontal);
+        let _ = slider.set_step(step);
+        let mut pod = Pod::new(slider);
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(120.0, 100.0));
+        assert_eq!(harness.layout(&mut pod, &bc), Size::new(120.0, 20.0));
+        pod
+    }
+
+    fn messages(harness: &mut Harness) -> Vec<SliderMessage> {
+        harness
+            .take_messages()
+            .into_iter()
+            .map(|message| *message.body.downcast::<SliderMessage>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn setters_report_only_real_changes() {
+        let mut slider = Slider::new(&vec![Id::next()], 50.0, 0.0..100.0, Axis::Horizontal);
+        assert!(slider.set_range(0.0..100.0).is_empty());
+        assert!(slider.set_step(None).is_empty());
+        // A step of zero means no step.
+        assert!(slider.set_step(Some(0.0)).is_empty());
+        assert!(slider.set_axis(Axis::Horizontal).is_empty());
+        assert!(!slider.set_range(0.0..10.0).contains(ChangeFlags::LAYOUT));
+        assert!(slider
+            .set_axis(Axis::Vertical)
+            .contains(ChangeFlags::LAYOUT));
+    }
+
+    #[test]
+    fn pixel_value_mapping_at_both_ends() {
+        let slider = Slider::new(&vec![Id::next()], 0.0, 0.0..100.0, Axis::Horizontal);
+
+        assert_eq!(slider.value_at(10.0, 120.0), 0.0);
+        assert_eq!(slider.value_at(-50.0, 120.0), 0.0);
+        assert_eq!(slider.value_at(110.0, 120.0), 100.0);
+        assert_eq!(slider.value_at(500.0, 120.0), 100.0);
+        assert_eq!(slider.value_at(60.0, 120.0), 50.0);
+        assert_eq!(slider.thumb_pos(120.0), 10.0);
+
+        // Vertical sliders start at the bottom.
+        let slider = Slider::new(&vec![Id::next()], 100.0, 0.0..100.0, Axis::Vertical);
+        assert_eq!(slider.thumb_pos(120.0), 10.0);
+        assert_eq!(slider.value_at(110.0, 120.0), 0.0);
+        assert_eq!(slider.value_at(10.0, 120.0), 100.0);
+    }
+
+    #[test]
+    fn step_rounding() {
+        let mut slider = Slider::new(&vec![Id::next()], 0.0, 0.0..100.0, Axis::Horizontal);
+        let _ = slider.set_step(Some(25.0));
+
+        assert_eq!(slider.quantize(12.4), 0.0);
+        assert_eq!(slider.quantize(12.6), 25.0);
+        assert_eq!(slider.quantize(99.0), 100.0);
+        assert_eq!(slider.value_at(48.0, 120.0), 50.0);
+    }
+
+    #[test]
+    fn drag_emits_changes_then_commit() {
+        let mut harness = Harness::new();
+        let mut pod = slider(&mut harness, None);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((60.0, 10.0))));
+        harness.event(&mut pod, Event::MouseMove(mouse((110.0, 10.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((110.0, 10.0))));
+
+        assert_eq!(
+            messages(&mut harness),
+            [
+                SliderMessage::Changed(50.0),
+                SliderMessage::Changed(100.0),
+                SliderMessage::Committed(100.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn drag_wins_over_rebuild_until_release() {
+        let mut harness = Harness::new();
+        let mut pod = slider(&mut harness, None);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((60.0, 10.0))));
+        let slider = pod.downcast_mut::<Slider>().unwrap();
+        assert_eq!(slider.set_value(0.0), ChangeFlags::empty());
+        assert_eq!(slider.value(), 50.0);
+
+        harness.event(&mut pod, Event::MouseUp(mouse((60.0, 10.0))));
+        let slider = pod.downcast_mut::<Slider>().unwrap();
+        assert!(slider.set_value(0.0).contains(ChangeFlags::PAINT));
+        assert_eq!(slider.value(), 0.0);
+    }
+
+    #[test]
+    fn drag_continues_outside_of_the_window() {
+        let mut harness = Harness::new();
+        let mut pod = slider(&mut harness, None);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((60.0, 10.0))));
+        harness.event(&mut pod, Event::MouseMove(mouse((-40.0, -300.0))));
+        harness.event(&mut pod, Event::MouseMove(mouse((500.0, 900.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((500.0, 900.0))));
+        assert_eq!(
+            messages(&mut harness),
+            [
+                SliderMessage::Changed(50.0),
+                SliderMessage::Changed(0.0),
+                SliderMessage::Changed(100.0),
+                SliderMessage::Committed(100.0),
+            ]
+        );
+
+        // The release ended the capture.
+        assert_eq!(harness.window_state.pointer_capture, None);
+        assert!(!pod.is_active());
+        harness.event(&mut pod, Event::MouseMove(mouse((60.0, 10.0))));
+        assert!(messages(&mut harness).is_empty());
+    }
+
+    #[test]
+    fn lost_capture_commits_the_drag() {
+        let mut harness = Harness::new();
+        let mut pod = slider(&mut harness, None);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((60.0, 10.0))));
+        harness.event(&mut pod, Event::WindowLostFocus);
+        assert_eq!(
+            messages(&mut harness),
+            [SliderMessage::Changed(50.0), SliderMessage::Committed(50.0)]
+        );
+        harness.event(&mut pod, Event::MouseMove(mouse((110.0, 10.0))));
+        assert!(messages(&mut harness).is_empty());
+        assert!(!pod.is_active());
+    }
+
+    #[test]
+    fn wheel_steps_the_focused_slider() {
+        let mut harness = Harness::new();
+        let mut pod = slider(&mut harness, Some(5.0));
+        let wheel = |delta: (f64, f64), unit, mods| {
+            let mut mouse = mouse((60.0, 10.0));
+            mouse.wheel_delta = delta.into();
+            mouse.wheel_unit = unit;
+            mouse.mods = mods;
+            Event::MouseWheel(mouse)
+        };
+
+        // Without focus the wheel is left to the ancestors.
+        harness.event(
+            &mut pod,
+            wheel((0.0, -1.0), ScrollUnit::Lines, Modifiers::empty()),
+        );
+        assert_eq!(pod.downcast_mut::<Slider>().unwrap().value(), 0.0);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((10.0, 10.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((10.0, 10.0))));
+        harness.take_messages();
+
+        harness.event(
+            &mut pod,
+            wheel((0.0, -1.0), ScrollUnit::Lines, Modifiers::empty()),
+        );
+        harness.event(
+            &mut pod,
+            wheel((0.0, -3.5), ScrollUnit::Pixels, Modifiers::empty()),
+        );
+        // Shift turns the downward scroll sideways, which still lowers the value.
+        harness.event(
+            &mut pod,
+            wheel((0.0, 2.0), ScrollUnit::Pixels, Modifiers::SHIFT),
+        );
+        assert_eq!(
+            messages(&mut harness),
+            [
+                SliderMessage::Changed(5.0),
+                SliderMessage::Committed(5.0),
+                SliderMessage::Changed(10.0),
+                SliderMessage::Committed(10.0),
+                SliderMessage::Changed(5.0),
+                SliderMessage::Committed(5.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn primary_touch_drags_like_the_mouse() {
+        let mut harness = Harness::new();
+        let mut pod = slider(&mut harness, None);
+
+        harness.event(&mut pod, Event::PointerDown(touch(0, (60.0, 10.0))));
+        // A second finger is left to widgets which know about touches.
+        harness.event(&mut pod, Event::PointerDown(touch(1, (10.0, 10.0))));
+        harness.event(&mut pod, Event::PointerMove(touch(1, (20.0, 10.0))));
+        harness.event(&mut pod, Event::PointerMove(touch(0, (110.0, 10.0))));
+        harness.event(&mut pod, Event::PointerUp(touch(0, (110.0, 10.0))));
+        harness.event(&mut pod, Event::PointerUp(touch(1, (20.0, 10.0))));
+        assert_eq!(
+            messages(&mut harness),
+            [
+                SliderMessage::Changed(50.0),
+                SliderMessage::Changed(100.0),
+                SliderMessage::Committed(100.0),
+            ]
+        );
+        assert_eq!(harness.window_state.pointer_capture, None);
+    }
+
+    #[test]
+    fn up_keys_move_the_thumb_of_a_vertical_slider_up() {
+        let mut harness = Harness::new();
+        let slider = Slider::new(&vec![Id::next()], 50.0, 0.0..100.0, Axis::Vertical);
+        let mut pod = Pod::new(slider);
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(100.0, 120.0));
+        assert_eq!(harness.layout(&mut pod, &bc), Size::new(20.0, 120.0));
+        harness.window_state.focus = Some(pod.id());
+        let thumb_pos = |pod: &mut Pod| pod.downcast_mut::<Slider>().unwrap().thumb_pos(120.0);
+        assert_eq!(thumb_pos(&mut pod), 60.0);
+
+        harness.event(&mut pod, key_down(KbKey::ArrowUp));
+        assert!(thumb_pos(&mut pod) < 60.0);
+        harness.event(&mut pod, key_down(KbKey::PageDown));
+        harness.event(&mut pod, key_down(KbKey::ArrowDown));
+        assert!(thumb_pos(&mut pod) > 60.0);
+        harness.event(&mut pod, key_down(KbKey::End));
+        assert_eq!(thumb_pos(&mut pod), 10.0);
+        assert_eq!(
+            messages(&mut harness),
+            [
+                SliderMessage::Changed(51.0),
+                SliderMessage::Committed(51.0),
+                SliderMessage::Changed(41.0),
+                SliderMessage::Committed(41.0),
+                SliderMessage::Changed(40.0),
+                SliderMessage::Committed(40.0),
+                SliderMessage::Changed(100.0),
+                SliderMessage::Committed(100.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn held_key_commits_on_release() {
+        let mut harness = Harness::new();
+        let mut pod = slider(&mut harness, Some(10.0));
+        harness.window_state.focus = Some(pod.id());
+        let held = |state, repeat| KeyEvent {
+            key: KbKey::ArrowRight,
+            state,
+            repeat,
+            ..Default::default()
+        };
+
+        harness.event(&mut pod, key_down(KbKey::ArrowRight));
+        harness.event(&mut pod, Event::KeyDown(held(KeyState::Down, true)));
+        harness.event(&mut pod, Event::KeyDown(held(KeyState::Down, true)));
+        harness.event(&mut pod, Event::KeyUp(held(KeyState::Up, false)));
+        assert_eq!(
+            messages(&mut harness),
+            [
+                SliderMessage::Changed(10.0),
+                SliderMessage::Committed(10.0),
+                SliderMessage::Changed(20.0),
+                SliderMessage::Changed(30.0),
+                SliderMessage::Committed(30.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::f64::consts::TAU;
+
+use vello::{
+    kurbo::{Arc, Size, Vec2},
+    peniko::Color,
+    SceneBuilder,
+};
+
+use super::{
+    contexts::LifeCycleCx, piet_scene_helpers, AccessCx, BoxConstraints, ChangeFlags, Event,
+    EventCx, LayoutCx, LifeCycle, PaintCx, UpdateCx, Widget,
+};
+
+/// A rotating arc showing that work is in progress.
+///
+/// The spinner animates by itself while it is visible. It stops requesting frames when it is
+/// scrolled out of view, removed, or paused with [`LifeCycle::AnimationsPaused`], which the app
+/// sends while the window is minimized.
+pub struct Spinner {
+    /// The diameter of the arc.
+    size: f64,
+    color: Color,
+    /// The seconds the spinner has been animating, the angle is derived from it.
+    elapsed: f64,
+    is_visible: bool,
+    is_paused: bool,
+}
+
+/// The time the arc takes for a full turn, in seconds.
+const REVOLUTION: f64 = 1.0;
+/// The part of the circle covered by the arc.
+const ARC_LENGTH: f64 = 0.75;
+
+impl Spinner {
+    pub const DEFAULT_SIZE: f64 = 24.0;
+    pub const DEFAULT_COLOR: Color = Color::rgb8(0x5a, 0x9b, 0xe6);
+
+    pub fn new(size: f64, color: Color) -> Spinner {
+        Spinner {
+            size,
+            color,
+            elapsed: 0.0,
+            is_visible: true,
+            is_paused: false,
+        }
+    }
+
+    pub fn set_size(&mut self, size: f64) -> ChangeFlags {
+        if self.size == size {
+            return ChangeFlags::empty();
+        }
+        self.size = size;
+        ChangeFlags::LAYOUT | ChangeFlags::PAINT
+    }
+
+    pub fn set_color(&mut self, color: Color) -> ChangeFlags {
+        if self.color == color {
+            return ChangeFlags::empty();
+        }
+        self.color = color;
+        ChangeFlags::PAINT
+    }
+
+    /// The rotation of the arc in radians, in `0.0..TAU`.
+    pub fn angle(&self) -> f64 {
+        (self.elapsed / REVOLUTION).fract() * TAU
+    }
+
+    fn is_animating(&self) -> bool {
+        self.is_visible && !self.is_paused
+    }
+}
+
+impl Widget for Spinner {
+    fn event(&mut self, _cx: &mut EventCx, _event: &Event) {}
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        match event {
+            LifeCycle::AnimFrame(interval) => {
+                if self.is_animating() {
+                    // Only the fraction of a turn matters, this keeps the precision.
+                    self.elapsed = (self.elapsed + *interval as f64 * 1e-9) % REVOLUTION;
+                    cx.request_paint();
+                    cx.request_anim_frame();
+                }
+            }
+            LifeCycle::ViewContextChanged(view) => {
+                self.is_visible = view.clip.intersect(cx.size().to_rect()).area() > 0.0;
+                if self.is_animating() {
+                    cx.request_anim_frame();
+                }
+            }
+            LifeCycle::AnimationsPaused(is_paused) => {
+                self.is_paused = *is_paused;
+                if self.is_animating() {
+                    cx.request_anim_frame();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        if self.is_animating() {
+            cx.request_anim_frame();
+        }
+    }
+
+    fn layout(&mut self, _cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        bc.constrain(Size::new(self.size, self.size))
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::ProgressIndicator);
+        builder.set_busy();
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let size = cx.size();
+        let width = (size.min_side() / 8.0).max(1.0);
+        let radius = (size.min_side() - width) * 0.5;
+        let arc = Arc {
+            center: size.to_rect().center(),
+            radii: Vec2::new(radius, radius),
+            start_angle: self.angle(),
+            sweep_angle: ARC_LENGTH * TAU,
+            x_rotation: 0.0,
+        };
+        piet_scene_helpers::stroke(builder, &arc, self.color, width);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Axis;
+    use crate::widget::test_util::Harness;
+    use crate::widget::{LinearLayout, Pod};
+    use std::time::Duration;
+    use test_log::test;
+
+    const FRAME: u64 = 16_000_000;
+
+    fn setup(harness: &mut Harness) -> Pod {
+        let spinner = Pod::new(Spinner::new(Spinner::DEFAULT_SIZE, Spinner::DEFAULT_COLOR));
+        let mut root = Pod::new(LinearLayout::new(vec![spinner], 0.0, Axis::Vertical));
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(100.0, 100.0));
+        harness.layout(&mut root, &bc);
+        harness.update(&mut root);
+        root
+    }
+
+    fn spinner(root: &mut Pod) -> &mut Spinner {
+        let layout = root.downcast_mut::<LinearLayout>().unwrap();
+        layout.children[0].downcast_mut().unwrap()
+    }
+
+    #[test]
+    fn angle_depends_on_elapsed_time_only() {
+        let mut harness = Harness::new();
+        let mut fast = setup(&mut harness);
+        let mut slow = setup(&mut harness);
+        for _ in 0..4 {
+            harness.lifecycle(&mut fast, LifeCycle::AnimFrame(25_000_000));
+        }
+        harness.lifecycle(&mut slow, LifeCycle::AnimFrame(100_000_000));
+        let angle = spinner(&mut fast).angle();
+        assert!((angle - spinner(&mut slow).angle()).abs() < 1e-9);
+        assert!((angle - 0.1 * TAU).abs() < 1e-9);
+    }
+
+    #[test]
+    fn frames_advance_with_the_clock() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness);
+        // The first frame only starts measuring.
+        assert!(harness.anim_frame(&mut root));
+        assert_eq!(spinner(&mut root).angle(), 0.0);
+
+        harness.advance_time(&mut root, Duration::from_millis(100));
+        assert!(harness.anim_frame(&mut root));
+        assert!((spinner(&mut root).angle() - 0.1 * TAU).abs() < 1e-9);
+    }
+
+    #[test]
+    fn spinners_share_the_interval_of_a_frame() {
+        let mut harness = Harness::new();
+        let spinners = (0..2)
+            .map(|_| Pod::new(Spinner::new(Spinner::DEFAULT_SIZE, Spinner::DEFAULT_COLOR)))
+            .collect();
+        let mut root = Pod::new(LinearLayout::new(spinners, 0.0, Axis::Vertical));
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(100.0, 100.0));
+        harness.layout(&mut root, &bc);
+        harness.update(&mut root);
+
+        harness.anim_frame(&mut root);
+        harness.advance_time(&mut root, Duration::from_millis(16));
+        harness.anim_frame(&mut root);
+        harness.advance_time(&mut root, Duration::from_millis(25));
+        harness.anim_frame(&mut root);
+
+        let layout = root.downcast_mut::<LinearLayout>().unwrap();
+        let angles: Vec<f64> = layout
+            .children
+            .iter_mut()
+            .map(|child| child.downcast_mut::<Spinner>().unwrap().angle())
+            .collect();
+        assert!((angles[0] - 0.041 * TAU).abs() < 1e-9);
+        assert_eq!(angles[0], angles[1]);
+    }
+
+    #[test]
+    fn frame_requests_cease_after_removal() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness);
+        assert!(root.anim_frame_requested());
+        harness.lifecycle(&mut root, LifeCycle::AnimFrame(FRAME));
+        assert!(root.anim_frame_requested());
+
+        root.downcast_mut::<LinearLayout>()
+            .unwrap()
+            .children
+            .clear();
+        harness.lifecycle(&mut root, LifeCycle::AnimFrame(FRAME));
+        assert!(!root.anim_frame_requested());
+    }
+
+    #[test]
+    fn pausing_stops_frame_requests_until_resumed() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness);
+        harness.lifecycle(&mut root, LifeCycle::AnimationsPaused(true));
+        harness.lifecycle(&mut root, LifeCycle::AnimFrame(FRAME));
+        assert!(!root.anim_frame_requested());
+        let angle = spinner(&mut root).angle();
+
+        harness.lifecycle(&mut root, LifeCycle::AnimationsPaused(false));
+        assert!(root.anim_frame_requested());
+        harness.lifecycle(&mut root, LifeCycle::AnimFrame(FRAME));
+        assert!(spinner(&mut root).angle() > angle);
+    }
+}
@@ -0,0 +1,552 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+use std::time::Duration;
+
+use glazier::KbKey;
+use vello::{
+    kurbo::{BezPath, Point, Rect, Size},
+    peniko::Color,
+    SceneBuilder,
+};
+
+use crate::{IdPath, Message};
+
+use super::{
+    piet_scene_helpers, AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle,
+    LifeCycleCx, PaintCx, Pod, TextBox, TimerToken, UpdateCx, Widget,
+};
+
+/// Turns the value of a [`Stepper`] into text and back.
+pub trait NumberFormat: Send {
+    fn format(&self, value: f64) -> String;
+
+    /// Reads a value typed by the user, `None` if the text is not a number.
+    fn parse(&self, text: &str) -> Option<f64>;
+}
+
+/// Formats numbers with a fixed number of decimal places and optionally groups thousands.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DecimalFormat {
+    decimals: usize,
+    thousands_separator: Option<char>,
+}
+
+impl DecimalFormat {
+    pub fn new(decimals: usize) -> Self {
+        DecimalFormat {
+            decimals,
+            thousands_separator: None,
+        }
+    }
+
+    /// The format with as many decimal places as `step` needs, up to six.
+    pub fn for_step(step: f64) -> Self {
+        let decimals = (0..6)
+            .find(|decimals| {
+                let scaled = step * 10f64.powi(*decimals as i32);
+                (scaled - scaled.round()).abs() < 1e-9
+            })
+            .unwrap_or(6);
+        DecimalFormat::new(decimals)
+    }
+
+    pub fn with_thousands_separator(mut self, separator: char) -> Self {
+        self.thousands_separator = Some(separator);
+        self
+    }
+}
+
+impl NumberFormat for DecimalFormat {
+    fn format(&self, value: f64) -> String {
+        let text = format!("{:.*}", self.decimals, value);
+        let Some(separator) = self.thousands_separator else {
+            return text;
+        };
+        let (sign, unsigned) = match text.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", text.as_str()),
+        };
+        let (integer, fraction) = match unsigned.find('.') {
+            Some(dot) => unsigned.split_at(dot),
+            None => (unsigned, ""),
+        };
+        let mut grouped = String::from(sign);
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                grouped.push(separator);
+            }
+            grouped.push(digit);
+        }
+        grouped + fraction
+    }
+
+    fn parse(&self, text: &str) -> Option<f64> {
+        let text: String = text
+            .trim()
+            .chars()
+            .filter(|c| Some(*c) != self.thousands_separator)
+            .collect();
+        let value = text.parse::<f64>().ok().filter(|value| value.is_finite())?;
+        // Typed values are rounded to what is shown.
+        let scale = 10f64.powi(self.decimals as i32);
+        Some((value * scale).round() / scale)
+    }
+}
+
+/// A text field for a number with buttons to increment and decrement it.
+///
+/// Typed text is parsed when the user presses Enter or the field loses focus. The value is
+/// clamped to the range then, text which isn't a number reverts to the current value and is
+/// marked as invalid while it is being typed. Holding a button repeats the step after a delay,
+/// the arrow keys and the mouse wheel step while the field is focused. Each new value is sent
+/// to the view at `id_path` as an `f64`, the view is expected to rebuild with the value it
+/// wants to display.
+pub struct Stepper {
+    id_path: IdPath,
+    value: f64,
+    range: Range<f64>,
+    step: f64,
+    format: Box<dyn NumberFormat>,
+    text_box: Pod,
+    /// Whether the text being typed is not a number.
+    is_invalid: bool,
+    /// The direction of the held button, +1 or -1, and the timer repeating its step.
+    repeat: Option<(f64, TimerToken)>,
+}
+
+/// The width of the column with the buttons.
+const BUTTON_WIDTH: f64 = 20.0;
+/// The time a button has to be held until the step repeats.
+const REPEAT_DELAY: Duration = Duration::from_millis(400);
+/// The time between repeated steps while a button is held.
+const REPEAT_INTERVAL: Duration = Duration::from_millis(50);
+
+impl Stepper {
+    pub fn new(
+        id_path: &IdPath,
+        value: f64,
+        range: Range<f64>,
+        step: f64,
+        format: Box<dyn NumberFormat>,
+    ) -> Stepper {
+        let text_box = TextBox::new(id_path, String::new());
+        let mut stepper = Stepper {
+            id_path: id_path.clone(),
+            value: 0.0,
+            range,
+            step,
+            format,
+            text_box: Pod::new(text_box),
+            is_invalid: false,
+            repeat: None,
+        };
+        stepper.value = stepper.clamp(value);
+        let _ = stepper.show_value();
+        stepper
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// The text in the field, which differs from the value while the user is typing.
+    pub fn text(&mut self) -> &str {
+        self.text_box.expect_mut::<TextBox>().text()
+    }
+
+    /// Sets the value, replacing text the user may be typing.
+    pub fn set_value(&mut self, value: f64) -> ChangeFlags {
+        let value = self.clamp(value);
+        if value == self.value {
+            return ChangeFlags::empty();
+        }
+        self.value = value;
+        self.show_value()
+    }
+
+    pub fn set_range(&mut self, range: Range<f64>) -> ChangeFlags {
+        if self.range == range {
+            return ChangeFlags::empty();
+        }
+        self.range = range;
+        self.value = self.clamp(self.value);
+        self.show_value()
+    }
+
+    pub fn set_step(&mut self, step: f64) -> ChangeFlags {
+        if self.step == step {
+            return ChangeFlags::empty();
+        }
+        self.step = step;
+        ChangeFlags::ACCESSIBILITY
+    }
+
+    pub fn set_format(&mut self, format: Box<dyn NumberFormat>) -> ChangeFlags {
+        self.format = format;
+        self.show_value()
+    }
+
+    fn clamp(&self, value: f64) -> f64 {
+        let Range { start, end } = self.range;
+        value.clamp(start.min(end), end.max(start))
+    }
+
+    /// Replaces the text in the field with the formatted value.
+    fn show_value(&mut self) -> ChangeFlags {
+        let text = self.format.format(self.value);
+        let text_box = self.text_box.expect_mut::<TextBox>();
+        let changes = text_box.set_text(&text);
+        self.is_invalid = false;
+        self.text_box.mark(changes) | ChangeFlags::PAINT
+    }
+
+    /// Parses the typed text, returns the new value if it changed.
+    fn commit(&mut self) -> Option<f64> {
+        let text = self.text().to_owned();
+        let value = self.format.parse(&text).map(|value| self.clamp(value));
+        let changed = value.filter(|value| *value != self.value);
+        if let Some(value) = changed {
+            self.value = value;
+        }
+        let _ = self.show_value();
+        changed
+    }
+
+    /// Commits the typed text and moves the value by `steps` steps, returns the new value if it
+    /// changed.
+    fn step_by(&mut self, steps: f64) -> Option<f64> {
+        let committed = self.commit();
+        let value = self.clamp(self.value + steps * self.step);
+        if value == self.value {
+            return committed;
+        }
+        self.value = value;
+        let _ = self.show_value();
+        Some(value)
+    }
+
+    /// Sends a new value to the view and repaints.
+    fn report(&self, cx: &mut EventCx, value: Option<f64>) {
+        if let Some(value) = value {
+            cx.add_message(Message::new(self.id_path.clone(), value));
+        }
+        cx.request_layout();
+        cx.request_paint();
+        cx.request_accessibility();
+    }
+
+    fn button_rects(size: Size) -> (Rect, Rect) {
+        let x0 = size.width - BUTTON_WIDTH;
+        let middle = size.height * 0.5;
+        (
+            Rect::new(x0, 0.0, size.width, middle),
+            Rect::new(x0, middle, size.width, size.height),
+        )
+    }
+
+    fn is_editing(&self, cx: &EventCx) -> bool {
+        cx.cx_state.focus() == Some(self.text_box.id())
+    }
+
+    /// Marks the typed text as invalid while it doesn't parse.
+    fn check_text(&mut self, cx: &mut EventCx) {
+        let text = self.text().to_owned();
+        let is_invalid = self.format.parse(&text).is_none();
+        if is_invalid != self.is_invalid {
+            self.is_invalid = is_invalid;
+            cx.request_paint();
+            cx.request_accessibility();
+        }
+    }
+}
+
+impl Widget for Stepper {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        let (up, down) = Stepper::button_rects(cx.size());
+        match event {
+            Event::MouseDown(mouse) if up.contains(mouse.pos) || down.contains(mouse.pos) => {
+                let direction = if up.contains(mouse.pos) { 1.0 } else { -1.0 };
+                cx.set_active(true);
+                let value = self.step_by(direction);
+                self.report(cx, value);
+                self.repeat = Some((direction, cx.request_timer(REPEAT_DELAY)));
+            }
+            Event::MouseUp(_) if cx.is_active() => {
+                cx.set_active(false);
+                if let Some((_, timer)) = self.repeat.take() {
+                    cx.cancel_timer(timer);
+                }
+                cx.request_paint();
+            }
+            Event::Timer(token) if self.repeat.is_some_and(|(_, timer)| timer == *token) => {
+                let (direction, _) = self.repeat.unwrap();
+                let value = self.step_by(direction);
+                self.report(cx, value);
+                self.repeat = Some((direction, cx.request_timer(REPEAT_INTERVAL)));
+            }
+            Event::MouseWheel(mouse) if self.is_editing(cx) && mouse.wheel_delta.y != 0.0 => {
+                let value = self.step_by(-mouse.wheel_delta.y.signum());
+                self.report(cx, value);
+                cx.set_handled(true);
+            }
+            Event::TargetedAccessibilityAction(request)
+                if cx.is_accesskit_target(request.target) =>
+            {
+                let steps = match request.action {
+                    accesskit::Action::Increment => 1.0,
+                    accesskit::Action::Decrement => -1.0,
+                    _ => return,
+                };
+                let value = self.step_by(steps);
+                self.report(cx, value);
+            }
+            _ => {
+                self.text_box.event(cx, event);
+                if !self.is_editing(cx) {
+                    return;
+                }
+                let key_event = match event {
+                    Event::KeyDown(key_event) => key_event,
+                    Event::Text(_) if cx.is_handled() => {
+                        self.check_text(cx);
+                        return;
+                    }
+                    _ => return,
+                };
+                let value = match key_event.key {
+                    _ if cx.is_handled() => {
+                        // The key edited the text or moved the caret.
+                        self.check_text(cx);
+                        return;
+                    }
+                    KbKey::Enter => self.commit(),
+                    KbKey::ArrowUp => self.step_by(1.0),
+                    KbKey::ArrowDown => self.step_by(-1.0),
+                    _ => return,
+                };
+                self.report(cx, value);
+                cx.set_handled(true);
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(_) = event {
+            cx.request_paint();
+        }
+        self.text_box.lifecycle(cx, event);
+        if let LifeCycle::RouteFocusChanged { old, .. } = event {
+            // Leaving the field commits the typed text.
+            if *old == Some(self.text_box.id()) {
+                if let Some(value) = self.commit() {
+                    cx.add_message(Message::new(self.id_path.clone(), value));
+                }
+                cx.request_layout();
+                cx.request_paint();
+                cx.request_accessibility();
+            }
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.text_box.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let text_bc = bc.shrink((BUTTON_WIDTH, 0.0));
+        let text_size = self.text_box.layout(cx, &text_bc);
+        self.text_box.set_origin(cx, Point::ORIGIN);
+        bc.constrain(Size::new(text_size.width + BUTTON_WIDTH, text_size.height))
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.text_box.accessibility(cx);
+
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::SpinButton);
+        builder.set_numeric_value(self.value);
+        builder.set_min_numeric_value(self.range.start);
+        builder.set_max_numeric_value(self.range.end);
+        builder.set_numeric_value_step(self.step);
+        if self.is_invalid {
+            builder.set_invalid(accesskit::Invalid::True);
+        }
+        builder.set_children(vec![self.text_box.id().into()]);
+        builder.add_action(accesskit::Action::Increment);
+        builder.add_action(accesskit::Action::Decrement);
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.text_box.paint(cx, builder);
+        let size = cx.size();
+        if self.is_invalid {
+            let frame = Size::new(size.width - BUTTON_WIDTH, size.height)
+                .to_rect()
+                .to_rounded_rect(4.0);
+            piet_scene_helpers::stroke(builder, &frame, Color::rgb8(0xe6, 0x5a, 0x5a), 1.0);
+        }
+
+        let (up, down) = Stepper::button_rects(size);
+        let pressed = self.repeat.map(|(direction, _)| direction);
+        for (rect, direction) in [(up, 1.0), (down, -1.0)] {
+            let background = if pressed == Some(direction) {
+                Color::rgb8(0x3a, 0x3a, 0x3a)
+            } else {
+                Color::rgb8(0x2a, 0x2a, 0x2a)
+            };
+            piet_scene_helpers::fill_color(builder, &rect.inset(-1.0), background);
+
+            let at_limit = self.clamp(self.value + direction * self.step) == self.value;
+            let color = if at_limit {
+                Color::rgb8(0x5a, 0x5a, 0x5a)
+            } else {
+                Color::rgb8(0xf0, 0xf0, 0xea)
+            };
+            let center = rect.center();
+            let tip = center.y - direction * 3.0;
+            let base = center.y + direction * 2.0;
+            let mut arrow = BezPath::new();
+            arrow.move_to((center.x - 4.0, base));
+            arrow.line_to((center.x, tip));
+            arrow.line_to((center.x + 4.0, base));
+            arrow.close_path();
+            piet_scene_helpers::fill_color(builder, &arrow, color);
+        }
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.text_box);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{key_down, mouse, Harness};
+    use test_log::test;
+
+    const UP: Point = Point::new(210.0, 3.0);
+    const DOWN: Point = Point::new(210.0, 21.0);
+
+    fn setup(harness: &mut Harness, value: f64, range: Range<f64>, step: f64) -> Pod {
+        let format = Box::new(DecimalFormat::for_step(step));
+        let mut root = Pod::new(Stepper::new(&vec![], value, range, step, format));
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(220.0, 100.0));
+        harness.layout(&mut root, &bc);
+        root
+    }
+
+    fn stepper(root: &mut Pod) -> &mut Stepper {
+        root.downcast_mut().unwrap()
+    }
+
+    /// The values sent, skipping the text edits.
+    fn values(harness: &mut Harness) -> Vec<f64> {
+        harness
+            .take_messages()
+            .into_iter()
+            .filter_map(|message| message.body.downcast::<f64>().ok())
+            .map(|value| *value)
+            .collect()
+    }
+
+    fn click(harness: &mut Harness, root: &mut Pod, pos: Point) {
+        harness.event(root, Event::MouseDown(mouse(pos)));
+        harness.event(root, Event::MouseUp(mouse(pos)));
+    }
+
+    /// Moves focus into the field and replaces its text, like typing does.
+    fn type_text(harness: &mut Harness, root: &mut Pod, text: &str) {
+        let stepper = stepper(root);
+        harness.window_state.focus = Some(stepper.text_box.id());
+        let text_box = stepper.text_box.downcast_mut::<TextBox>().unwrap();
+        let _ = text_box.set_text(text);
+    }
+
+    #[test]
+    fn held_button_repeats_after_delay() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, 0.0, 0.0..100.0, 1.0);
+
+        harness.event(&mut root, Event::MouseDown(mouse(UP)));
+        assert_eq!(values(&mut harness), vec![1.0]);
+        harness.advance_time(&mut root, Duration::from_millis(399));
+        assert_eq!(values(&mut harness), vec![]);
+        harness.advance_time(&mut root, Duration::from_millis(1));
+        assert_eq!(values(&mut harness), vec![2.0]);
+        harness.advance_time(&mut root, Duration::from_millis(49));
+        assert_eq!(values(&mut harness), vec![]);
+        for value in [3.0, 4.0, 5.0] {
+            harness.advance_time(&mut root, Duration::from_millis(50));
+            assert_eq!(values(&mut harness), vec![value]);
+        }
+        assert_eq!(stepper(&mut root).text(), "5");
+
+        harness.event(&mut root, Event::MouseUp(mouse(UP)));
+        harness.advance_time(&mut root, Duration::from_secs(1));
+        assert_eq!(values(&mut harness), vec![]);
+    }
+
+    #[test]
+    fn values_are_clamped_to_range() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, 9.0, 0.0..10.0, 2.0);
+
+        click(&mut harness, &mut root, UP);
+        assert_eq!(values(&mut harness), vec![10.0]);
+        click(&mut harness, &mut root, UP);
+        assert_eq!(values(&mut harness), vec![]);
+        click(&mut harness, &mut root, DOWN);
+        assert_eq!(values(&mut harness), vec![8.0]);
+
+        type_text(&mut harness, &mut root, "25");
+        harness.event(&mut root, key_down(KbKey::Enter));
+        assert_eq!(values(&mut harness), vec![10.0]);
+        assert_eq!(stepper(&mut root).text(), "10");
+
+        type_text(&mut harness, &mut root, "-3");
+        harness.event(&mut root, key_down(KbKey::ArrowUp));
+        assert_eq!(values(&mut harness), vec![2.0]);
+    }
+
+    #[test]
+    fn invalid_text_reverts_on_blur() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, 5.0, 0.0..10.0, 0.5);
+        assert_eq!(stepper(&mut root).text(), "5.0");
+
+        type_text(&mut harness, &mut root, "5.0");
+        harness.event(&mut root, key_down(KbKey::Character("x".into())));
+        assert!(stepper(&mut root).is_invalid);
+
+        harness.window_state.focus = None;
+        harness.update(&mut root);
+        assert_eq!(values(&mut harness), vec![]);
+        let stepper = stepper(&mut root);
+        assert_eq!(stepper.text(), "5.0");
+        assert!(!stepper.is_invalid);
+    }
+
+    #[test]
+    fn decimal_format_groups_thousands() {
+        let format = DecimalFormat::new(2).with_thousands_separator(',');
+        assert_eq!(format.format(-1234567.891), "-1,234,567.89");
+        assert_eq!(format.format(999.0), "999.00");
+        assert_eq!(format.parse(" 1,234.567 "), Some(1234.57));
+        assert_eq!(format.parse("12a"), None);
+        assert_eq!(DecimalFormat::for_step(0.25), DecimalFormat::new(2));
+    }
+}
@@ -0,0 +1,383 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use glazier::KbKey;
+use vello::{
+    kurbo::{Circle, Point, Shape, Size},
+    peniko::Color,
+    SceneBuilder,
+};
+
+use crate::{IdPath, Message};
+
+use super::{
+    contexts::LifeCycleCx, piet_scene_helpers, AccessCx, BoxConstraints, ChangeFlags, Event,
+    EventCx, LayoutCx, LifeCycle, PaintCx, UpdateCx, Widget,
+};
+
+/// A toggle with a thumb which can be clicked or dragged between its off and on sides.
+///
+/// A click toggles the switch, releasing a drag snaps the thumb to the nearer side. The new value
+/// is sent to the view at `id_path` only when the interaction is committed.
+pub struct Switch {
+    id_path: IdPath,
+    is_on: bool,
+    /// The position of the thumb between the off (`0.0`) and on (`1.0`) side.
+    thumb: f64,
+    drag: Option<Drag>,
+}
+
+struct Drag {
+    start_x: f64,
+    start_thumb: f64,
+    moved: bool,
+}
+
+const SWITCH_WIDTH: f64 = 40.0;
+const SWITCH_HEIGHT: f64 = 22.0;
+const THUMB_PADDING: f64 = 2.0;
+/// The time the thumb takes to travel from one side to the other, in seconds.
+const SNAP_DURATION: f64 = 0.12;
+/// How far the pointer has to move before a press counts as drag instead of a click.
+const DRAG_THRESHOLD: f64 = 3.0;
+
+impl Switch {
+    pub fn new(id_path: &IdPath, is_on: bool) -> Switch {
+        Switch {
+            id_path: id_path.clone(),
+            is_on,
+            thumb: if is_on { 1.0 } else { 0.0 },
+            drag: None,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.is_on
+    }
+
+    /// Sets the value, the thumb moves to the matching side once it isn't dragged anymore.
+    pub fn set_on(&mut self, is_on: bool) -> ChangeFlags {
+        if self.is_on == is_on {
+            return ChangeFlags::empty();
+        }
+        self.is_on = is_on;
+        // The update starts the snap animation.
+        ChangeFlags::UPDATE | ChangeFlags::ACCESSIBILITY
+    }
+
+    fn target(&self) -> f64 {
+        if self.is_on {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// The distance the thumb travels between both sides, in pixels.
+    fn travel(size: Size) -> f64 {
+        (size.width - size.height).max(1.0)
+    }
+
+    fn commit(&mut self, cx: &mut EventCx, is_on: bool) {
+        if is_on != self.is_on {
+            self.is_on = is_on;
+            cx.add_message(Message::new(self.id_path.clone(), is_on));
+        }
+        cx.request_anim_frame();
+        cx.request_paint();
+    }
+}
+
+impl Widget for Switch {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        match event {
+            Event::MouseDown(mouse_event) => {
+                cx.capture_pointer();
+                cx.request_focus();
+                self.drag = Some(Drag {
+                    start_x: mouse_event.pos.x,
+                    start_thumb: self.thumb,
+                    moved: false,
+                });
+                cx.request_paint();
+            }
+            Event::MouseMove(mouse_event) => {
+                if let Some(drag) = &mut self.drag {
+                    let dx = mouse_event.pos.x - drag.start_x;
+                    drag.moved |= dx.abs() >= DRAG_THRESHOLD;
+                    if drag.moved {
+                        let travel = Self::travel(cx.size());
+                        self.thumb = (drag.start_thumb + dx / travel).clamp(0.0, 1.0);
+                        cx.request_paint();
+                    }
+                }
+            }
+            Event::MouseUp(_) => {
+                if let Some(drag) = self.drag.take() {
+                    cx.release_pointer();
+                    let is_on = if drag.moved {
+                        self.thumb >= 0.5
+                    } else if cx.is_hot() {
+                        !self.is_on
+                    } else {
+                        self.is_on
+                    };
+                    self.commit(cx, is_on);
+                }
+            }
+            Event::KeyDown(key_event) if cx.is_focused() => {
+                let is_toggle = match &key_event.key {
+                    KbKey::Enter => true,
+                    KbKey::Character(c) => c == " ",
+                    _ => false,
+                };
+                if is_toggle && self.drag.is_none() {
+                    self.commit(cx, !self.is_on);
+                    cx.set_handled(true);
+                }
+            }
+            Event::TargetedAccessibilityAction(request) => {
+                if request.action == accesskit::Action::Default
+                    && cx.is_accesskit_target(request.target)
+                    && self.drag.is_none()
+                {
+                    self.commit(cx, !self.is_on);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        match event {
+            LifeCycle::HotChanged(_) => cx.request_paint(),
+            LifeCycle::BuildFocusChain => cx.register_for_focus(),
+            // An unfinished drag snaps back to the current state.
+            LifeCycle::PointerCaptureLost if self.drag.take().is_some() => {
+                cx.request_anim_frame();
+                cx.request_paint();
+            }
+            LifeCycle::AnimFrame(interval) => {
+                if self.drag.is_some() {
+                    return;
+                }
+                let step = *interval as f64 * 1e-9 / SNAP_DURATION;
+                let target = self.target();
+                self.thumb = if self.thumb < target {
+                    (self.thumb + step).min(target)
+                } else {
+                    (self.thumb - step).max(target)
+                };
+                if self.thumb != target {
+                    cx.request_anim_frame();
+                }
+                cx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        if self.drag.is_none() && self.thumb != self.target() {
+            cx.request_anim_frame();
+        }
+        cx.request_paint();
+    }
+
+    fn layout(&mut self, _cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        bc.constrain(Size::new(SWITCH_WIDTH, SWITCH_HEIGHT))
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::Switch);
+        builder.set_checked_state(if self.is_on {
+            accesskit::CheckedState::True
+        } else {
+            accesskit::CheckedState::False
+        });
+        builder.set_default_action_verb(accesskit::DefaultActionVerb::Click);
+        builder.add_action(accesskit::Action::Default);
+        builder.add_action(accesskit::Action::Focus);
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let size = cx.size();
+        let track = size.to_rect().to_rounded_rect(size.height * 0.5);
+        // Blend the track color with the thumb position, so it follows drags and animations.
+        let off = [0x3a, 0x3a, 0x3a];
+        let on = [0x5a, 0x9b, 0xe6];
+        let mix = |i: usize| (off[i] as f64 + (on[i] as f64 - off[i] as f64) * self.thumb) as u8;
+        piet_scene_helpers::fill_color(builder, &track, Color::rgb8(mix(0), mix(1), mix(2)));
+        if cx.is_focused() {
+            piet_scene_helpers::stroke(builder, &track, Color::rgb8(0xf0, 0xf0, 0xea), 1.0);
+        }
+
+        let radius = size.height * 0.5;
+        let center = Point::new(radius + self.thumb * Self::travel(size), radius);
+        let thumb_color = if cx.is_active() || cx.is_hot() {
+            Color::rgb8(0xff, 0xff, 0xff)
+        } else {
+            Color::rgb8(0xf0, 0xf0, 0xea)
+        };
+        piet_scene_helpers::fill_color(
+            builder,
+            &Circle::new(center, radius - THUMB_PADDING),
+            thumb_color,
+        );
+    }
+
+    fn hit_test(&self, size: Size, pos: Point) -> bool {
+        // The corners outside of the track belong to the widgets below.
+        let track = size.to_rect().to_rounded_rect(size.height * 0.5);
+        track.contains(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{mouse, Harness};
+    use crate::widget::Pod;
+    use std::time::Duration;
+    use test_log::test;
+    use xilem_core::Id;
+
+    fn switch(harness: &mut Harness, is_on: bool) -> Pod {
+        let mut pod = Pod::new(Switch::new(&vec![Id::next()], is_on));
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(100.0, 100.0));
+        harness.layout(&mut pod, &bc);
+        pod
+    }
+
+    fn thumb(pod: &mut Pod) -> f64 {
+        pod.downcast_mut::<Switch>().unwrap().thumb
+    }
+
+    fn committed(harness: &mut Harness) -> Vec<bool> {
+        harness
+            .take_messages()
+            .into_iter()
+            .map(|message| *message.body.downcast::<bool>().unwrap())
+            .collect()
+    }
+
+    fn set_on(pod: &mut Pod, is_on: bool) {
+        let flags = pod.downcast_mut::<Switch>().unwrap().set_on(is_on);
+        let _ = pod.mark(flags);
+    }
+
+    /// Runs animation frames until the switch stops requesting them.
+    fn settle(harness: &mut Harness, pod: &mut Pod) {
+        for _ in 0..100 {
+            if !pod.anim_frame_requested() {
+                return;
+            }
+            harness.lifecycle(pod, LifeCycle::AnimFrame(16_000_000));
+        }
+        panic!("the animation did not settle");
+    }
+
+    #[test]
+    fn drag_release_past_midpoint_turns_on() {
+        let mut harness = Harness::new();
+        let mut pod = switch(&mut harness, false);
+
+        // The travel is 40 - 22 = 18 pixels.
+        harness.event(&mut pod, Event::MouseDown(mouse((11.0, 11.0))));
+        harness.event(&mut pod, Event::MouseMove(mouse((23.6, 11.0))));
+        assert!((thumb(&mut pod) - 0.7).abs() < 1e-9);
+        harness.event(&mut pod, Event::MouseUp(mouse((23.6, 11.0))));
+
+        assert_eq!(committed(&mut harness), [true]);
+        settle(&mut harness, &mut pod);
+        assert_eq!(thumb(&mut pod), 1.0);
+    }
+
+    #[test]
+    fn drag_release_before_midpoint_snaps_back() {
+        let mut harness = Harness::new();
+        let mut pod = switch(&mut harness, false);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((11.0, 11.0))));
+        harness.event(&mut pod, Event::MouseMove(mouse((16.4, 11.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((16.4, 11.0))));
+
+        assert!(committed(&mut harness).is_empty());
+        settle(&mut harness, &mut pod);
+        assert_eq!(thumb(&mut pod), 0.0);
+    }
+
+    #[test]
+    fn click_toggles() {
+        let mut harness = Harness::new();
+        let mut pod = switch(&mut harness, true);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((11.0, 11.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((12.0, 11.0))));
+
+        assert_eq!(committed(&mut harness), [false]);
+    }
+
+    #[test]
+    fn external_change_mid_animation_reverses_without_jump() {
+        let mut harness = Harness::new();
+        let mut pod = switch(&mut harness, false);
+
+        set_on(&mut pod, true);
+        harness.update(&mut pod);
+        harness.lifecycle(&mut pod, LifeCycle::AnimFrame(60_000_000));
+        let halfway = thumb(&mut pod);
+        assert!(halfway > 0.0 && halfway < 1.0);
+
+        set_on(&mut pod, false);
+        harness.update(&mut pod);
+        assert_eq!(thumb(&mut pod), halfway);
+
+        settle(&mut harness, &mut pod);
+        assert_eq!(thumb(&mut pod), 0.0);
+    }
+
+    #[test]
+    fn finished_snap_stops_requesting_frames() {
+        let mut harness = Harness::new();
+        let mut pod = switch(&mut harness, false);
+
+        set_on(&mut pod, true);
+        harness.update(&mut pod);
+        let mut frames = 0;
+        while harness.anim_frame(&mut pod) {
+            frames += 1;
+            assert!(frames < 100, "the animation did not settle");
+            harness.advance_time(&mut pod, Duration::from_millis(16));
+        }
+        assert_eq!(thumb(&mut pod), 1.0);
+        assert!(!pod.anim_frame_requested());
+    }
+
+    #[test]
+    fn external_change_does_not_yank_dragged_thumb() {
+        let mut harness = Harness::new();
+        let mut pod = switch(&mut harness, false);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((11.0, 11.0))));
+        harness.event(&mut pod, Event::MouseMove(mouse((20.0, 11.0))));
+        let dragged = thumb(&mut pod);
+        set_on(&mut pod, true);
+        harness.update(&mut pod);
+        harness.lifecycle(&mut pod, LifeCycle::AnimFrame(16_000_000));
+
+        assert_eq!(thumb(&mut pod), dragged);
+    }
+}
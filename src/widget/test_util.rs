@@ -0,0 +1,412 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for driving widgets in unit tests without a window.
+
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
+use accesskit::TreeUpdate;
+use glazier::{KbKey, KeyEvent, KeyState, Modifiers, MouseButton, MouseButtons};
+use parley::FontContext;
+use vello::kurbo::{Point, Size, Vec2};
+use vello::{SceneBuilder, SceneFragment};
+
+use crate::recording::{tree_hash, Input, Recorder, Replayer};
+use crate::view::Cx;
+use crate::{Axis, Message};
+
+use super::timer::TimerQueue;
+use super::{
+    AccessCx, BoxConstraints, ChangeFlags, CxState, Event, EventCx, Intrinsic, LayoutCx, LifeCycle,
+    LifeCycleCx, MockTimerSource, MouseEvent, PaintCx, Pod, PodFlags, PointerEvent, PointerId,
+    PointerKind, Registry, ScrollUnit, TimerSource, UpdateCx, ViewContext, Widget, WidgetState,
+    WindowState,
+};
+
+/// Owns the state shared by all contexts and dispatches into a root [`Pod`].
+pub(crate) struct Harness {
+    window: glazier::WindowHandle,
+    font_cx: FontContext,
+    pub(crate) messages: Vec<Message>,
+    pub(crate) window_state: WindowState,
+    root_state: WidgetState,
+    /// The clock of the timers, which only moves with [`advance_time`](Harness::advance_time).
+    pub(crate) clock: MockTimerSource,
+    last_anim_frame: Option<Instant>,
+    /// Records the events delivered with [`event`](Harness::event).
+    pub(crate) recorder: Option<Recorder>,
+}
+
+impl Harness {
+    pub(crate) fn new() -> Self {
+        let clock = MockTimerSource::new();
+        let mut window_state = WindowState::new();
+        window_state.timers = TimerQueue::new(clock.now());
+        Harness {
+            window: Default::default(),
+            font_cx: FontContext::new(),
+            messages: vec![],
+            window_state,
+            root_state: WidgetState::new(),
+            clock,
+            last_anim_frame: None,
+            recorder: None,
+        }
+    }
+
+    /// Tells removed widgets, updates the bloom filters of `pod` and lays it out at the origin.
+    pub(crate) fn layout(&mut self, pod: &mut Pod, bc: &BoxConstraints) -> Size {
+        let mut cx_state = CxState::new(
+            &self.window,
+            &mut self.font_cx,
+            &mut self.messages,
+            &mut self.window_state,
+        );
+        let tree_changed = pod.state.flags.contains(PodFlags::TREE_CHANGED);
+        let mut lifecycle_cx = LifeCycleCx::new(&mut cx_state, &mut self.root_state);
+        Pod::deliver_removed(&mut lifecycle_cx);
+        pod.lifecycle(&mut lifecycle_cx, &LifeCycle::TreeUpdate);
+        if tree_changed {
+            pod.repair_focus(&mut lifecycle_cx);
+            pod.drop_stale_timers(&mut lifecycle_cx);
+            pod.drop_stale_pointer_capture(&mut lifecycle_cx);
+            pod.drop_stale_cursor_overrides(&mut lifecycle_cx);
+        }
+        let mut layout_cx = LayoutCx::new(&mut cx_state, &mut self.root_state);
+        let size = pod.layout(&mut layout_cx, bc);
+        pod.set_origin(&mut layout_cx, Point::ORIGIN);
+        size
+    }
+
+    /// Measures the intrinsic extent of `pod`, see [`Pod::intrinsic`].
+    pub(crate) fn intrinsic(
+        &mut self,
+        pod: &mut Pod,
+        axis: Axis,
+        kind: Intrinsic,
+        cross: f64,
+    ) -> f64 {
+        let mut cx_state = CxState::new(
+            &self.window,
+            &mut self.font_cx,
+            &mut self.messages,
+            &mut self.window_state,
+        );
+        let mut layout_cx = LayoutCx::new(&mut cx_state, &mut self.root_state);
+        pod.intrinsic(&mut layout_cx, axis, kind, cross)
+    }
+
+    pub(crate) fn event(&mut self, pod: &mut Pod, event: Event) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record_event(self.clock.now(), &event);
+        }
+        let mut cx_state = CxState::new(
+            &self.window,
+            &mut self.font_cx,
+            &mut self.messages,
+            &mut self.window_state,
+        );
+        let mut event_cx = EventCx::new(&mut cx_state, &mut self.root_state);
+        pod.window_event(&mut event_cx, &event);
+        let mut lifecycle_cx = LifeCycleCx::new(&mut cx_state, &mut self.root_state);
+        pod.deliver_focus_change(&mut lifecycle_cx);
+        pod.deliver_pointer_capture_loss(&mut lifecycle_cx);
+    }
+
+    pub(crate) fn lifecycle(&mut self, pod: &mut Pod, event: LifeCycle) {
+        let mut cx_state = CxState::new(
+            &self.window,
+            &mut self.font_cx,
+            &mut self.messages,
+            &mut self.window_state,
+        );
+        let mut lifecycle_cx = LifeCycleCx::new(&mut cx_state, &mut self.root_state);
+        pod.lifecycle(&mut lifecycle_cx, &event);
+    }
+
+    pub(crate) fn update(&mut self, pod: &mut Pod) {
+        let mut cx_state = CxState::new(
+            &self.window,
+            &mut self.font_cx,
+            &mut self.messages,
+            &mut self.window_state,
+        );
+        let mut update_cx = UpdateCx::new(&mut cx_state, &mut self.root_state);
+        pod.update(&mut update_cx);
+        let mut lifecycle_cx = LifeCycleCx::new(&mut cx_state, &mut self.root_state);
+        pod.deliver_focus_change(&mut lifecycle_cx);
+        pod.deliver_pointer_capture_loss(&mut lifecycle_cx);
+    }
+
+    /// Runs the passes of a frame for the changes pending in `pod`, like the app does, and
+    /// returns the passes which ran.
+    pub(crate) fn frame(&mut self, pod: &mut Pod, bc: &BoxConstraints) -> ChangeFlags {
+        let mut passes = ChangeFlags::empty();
+        if pod.pending_changes().contains(ChangeFlags::UPDATE) {
+            self.update(pod);
+            passes |= ChangeFlags::UPDATE;
+        }
+        if pod.pending_changes().contains(ChangeFlags::LAYOUT) {
+            self.layout(pod, bc);
+            passes |= ChangeFlags::LAYOUT;
+        }
+        if pod.state.flags.contains(PodFlags::VIEW_CONTEXT_CHANGED) {
+            let view = ViewContext {
+                window_origin: Point::ORIGIN,
+                clip: pod.state.size.to_rect(),
+                mouse_position: None,
+            };
+            self.lifecycle(pod, LifeCycle::ViewContextChanged(view));
+        }
+        if pod.pending_changes().contains(ChangeFlags::PAINT) {
+            let mut cx_state = CxState::new(
+                &self.window,
+                &mut self.font_cx,
+                &mut self.messages,
+                &mut self.window_state,
+            );
+            let mut paint_cx = PaintCx::new(&mut cx_state, &mut self.root_state);
+            pod.paint_impl(&mut paint_cx);
+            passes |= ChangeFlags::PAINT;
+        }
+        passes
+    }
+
+    /// Paints `pod` into a throwaway scene fragment.
+    pub(crate) fn paint(&mut self, pod: &mut Pod) {
+        let mut cx_state = CxState::new(
+            &self.window,
+            &mut self.font_cx,
+            &mut self.messages,
+            &mut self.window_state,
+        );
+        let mut paint_cx = PaintCx::new(&mut cx_state, &mut self.root_state);
+        let mut fragment = SceneFragment::new();
+        let mut builder = SceneBuilder::for_fragment(&mut fragment);
+        pod.paint_raw(&mut paint_cx, &mut builder);
+    }
+
+    /// Collects the accessibility nodes `pod` and its descendants push.
+    pub(crate) fn accessibility(&mut self, pod: &mut Pod) -> TreeUpdate {
+        let mut update = TreeUpdate::default();
+        let mut node_classes = accesskit::NodeClassSet::new();
+        let mut cx_state = CxState::new(
+            &self.window,
+            &mut self.font_cx,
+            &mut self.messages,
+            &mut self.window_state,
+        );
+        let mut access_cx = AccessCx {
+            cx_state: &mut cx_state,
+            widget_state: &mut self.root_state,
+            update: &mut update,
+            node_classes: &mut node_classes,
+        };
+        pod.accessibility(&mut access_cx);
+        update.focus = self.window_state.focus.map(Into::into);
+        update
+    }
+
+    /// Tells the widgets about a new size of the window and lays `pod` out at it, like the app
+    /// does.
+    pub(crate) fn resize(&mut self, pod: &mut Pod, size: Size) -> Size {
+        self.lifecycle(pod, LifeCycle::WindowResized(size));
+        self.layout(pod, &BoxConstraints::tight(size))
+    }
+
+    /// Changes the number of device pixels per logical pixel and tells the widgets.
+    pub(crate) fn set_scale(&mut self, pod: &mut Pod, scale: f64) {
+        self.window_state.scale = scale;
+        self.lifecycle(pod, LifeCycle::ScaleChanged(scale));
+    }
+
+    /// Asks the widgets whether the window may close, returns `false` when one handled the
+    /// request to keep it open.
+    pub(crate) fn request_close(&mut self, pod: &mut Pod) -> bool {
+        let mut cx_state = CxState::new(
+            &self.window,
+            &mut self.font_cx,
+            &mut self.messages,
+            &mut self.window_state,
+        );
+        let mut event_cx = EventCx::new(&mut cx_state, &mut self.root_state);
+        pod.window_event(&mut event_cx, &Event::CloseRequested);
+        !event_cx.is_handled()
+    }
+
+    /// Moves the clock of the timers forward and delivers the timers which elapsed.
+    pub(crate) fn advance_time(&mut self, pod: &mut Pod, delta: Duration) {
+        self.clock.advance(delta);
+        self.window_state.timers.set_now(self.clock.now());
+        while let Some(token) = self.window_state.timers.pop_due() {
+            self.event(pod, Event::Timer(token));
+        }
+    }
+
+    /// Delivers the messages sent to widgets of `registry`, like the app does before painting.
+    pub(crate) fn deliver_widget_messages(&mut self, pod: &mut Pod, registry: &Registry) {
+        for (target, message) in registry.take_pending() {
+            self.event(pod, Event::RouteWidgetMessage { target, message });
+        }
+    }
+
+    /// Delivers an animation frame like the app does before painting, with the time the clock
+    /// moved since the previous frame. Returns whether a widget had requested the frame.
+    pub(crate) fn anim_frame(&mut self, pod: &mut Pod) -> bool {
+        if !pod.anim_frame_requested() {
+            self.last_anim_frame = None;
+            return false;
+        }
+        let now = self.clock.now();
+        let interval = self
+            .last_anim_frame
+            .map(|last| now.duration_since(last).as_nanos() as u64)
+            .unwrap_or(0);
+        self.last_anim_frame = Some(now);
+        self.lifecycle(pod, LifeCycle::AnimFrame(interval));
+        true
+    }
+
+    /// Records the accessibility tree of `pod` if the recorder was asked for a checkpoint, like
+    /// the app does after painting.
+    pub(crate) fn checkpoint(&mut self, pod: &mut Pod) {
+        let Some(recorder) = self.recorder.clone() else {
+            return;
+        };
+        if recorder.take_checkpoint_request() {
+            let hash = tree_hash(&self.accessibility(pod));
+            recorder.record(self.clock.now(), Input::Checkpoint { hash });
+        }
+    }
+
+    /// Plays back a recording into `pod`, moving the clock to the time of each input.
+    ///
+    /// Resizes lay `pod` out at the new size. Panics when the accessibility tree differs from
+    /// the recording at a checkpoint.
+    pub(crate) fn replay(&mut self, pod: &mut Pod, mut replayer: Replayer) {
+        let start = self.clock.now();
+        while let Some(recorded) = replayer.pop() {
+            let at = start + Duration::from_micros(recorded.at_us);
+            let delta = at.saturating_duration_since(self.clock.now());
+            self.advance_time(pod, delta);
+            match recorded.input {
+                Input::Resize { width, height } => {
+                    self.resize(pod, Size::new(width, height));
+                }
+                Input::Checkpoint { hash } => {
+                    let actual = tree_hash(&self.accessibility(pod));
+                    assert_eq!(
+                        actual, hash,
+                        "the replay diverged from the recording at {}µs",
+                        recorded.at_us
+                    );
+                }
+                input => {
+                    if let Some(event) = input.to_event() {
+                        self.event(pod, event);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Takes all messages sent since the last call.
+    pub(crate) fn take_messages(&mut self) -> Vec<Message> {
+        std::mem::take(&mut self.messages)
+    }
+}
+
+/// A view context for building and rebuilding views in tests, which drops the messages and
+/// wakes sent through it.
+pub(crate) fn view_cx() -> Cx {
+    view_cx_with_messages().0
+}
+
+/// A view context for tests, with the receiver of the messages and wakes sent through it.
+pub(crate) fn view_cx_with_messages() -> (Cx, Receiver<Message>) {
+    let (req_chan, messages) = std::sync::mpsc::sync_channel(100);
+    (Cx::new(&req_chan), messages)
+}
+
+pub(crate) fn mouse(pos: impl Into<Point>) -> MouseEvent {
+    let pos = pos.into();
+    MouseEvent {
+        pos,
+        window_pos: pos,
+        buttons: MouseButtons::new(),
+        mods: Modifiers::empty(),
+        click_count: 1,
+        focus: false,
+        button: MouseButton::Left,
+        wheel_delta: Vec2::ZERO,
+        wheel_unit: ScrollUnit::Pixels,
+    }
+}
+
+/// A touch at `pos`, where the touch with id 0 is the primary one.
+pub(crate) fn touch(pointer: u64, pos: impl Into<Point>) -> PointerEvent {
+    let pos = pos.into();
+    PointerEvent {
+        pointer_id: PointerId(pointer),
+        kind: PointerKind::Touch,
+        pos,
+        window_pos: pos,
+        pressure: 0.5,
+        buttons: MouseButtons::new().with(MouseButton::Left),
+        button: MouseButton::Left,
+        mods: Modifiers::empty(),
+        is_primary: pointer == 0,
+    }
+}
+
+pub(crate) fn key_down(key: KbKey) -> Event {
+    Event::KeyDown(KeyEvent {
+        key,
+        state: KeyState::Down,
+        ..Default::default()
+    })
+}
+
+/// A leaf widget with a natural size which can be shrunk down to `min_width`.
+pub(crate) struct Leaf {
+    pub(crate) size: Size,
+    pub(crate) min_width: f64,
+}
+
+impl Leaf {
+    pub(crate) fn new(width: f64, height: f64) -> Self {
+        Leaf {
+            size: Size::new(width, height),
+            min_width: 0.0,
+        }
+    }
+}
+
+impl Widget for Leaf {
+    fn event(&mut self, _cx: &mut EventCx, _event: &Event) {}
+
+    fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+    fn update(&mut self, _cx: &mut UpdateCx) {}
+
+    fn layout(&mut self, _cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = bc.constrain(self.size);
+        Size::new(size.width.max(self.min_width), size.height)
+    }
+
+    fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+    fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+}
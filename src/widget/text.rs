@@ -14,44 +14,52 @@
 
 use parley::Layout;
 use vello::{
-    kurbo::{Affine, Point, Size},
-    peniko::{Brush, Color},
-    SceneBuilder, SceneFragment,
+    kurbo::{Affine, Size},
+    peniko::Brush,
+    SceneBuilder,
 };
 
 use crate::text::ParleyBrush;
+use crate::Theme;
 
 use super::{
-    align::{FirstBaseline, LastBaseline, SingleAlignment, VertAlignment},
-    contexts::LifeCycleCx,
-    AlignCx, ChangeFlags, EventCx, LayoutCx, LifeCycle, PaintCx, RawEvent, UpdateCx, Widget,
+    contexts::LifeCycleCx, AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx,
+    LifeCycle, PaintCx, UpdateCx, Widget,
 };
 
 pub struct TextWidget {
     text: String,
     layout: Option<Layout<ParleyBrush>>,
-    is_wrapped: bool,
 }
 
 impl TextWidget {
     pub fn new(text: String) -> TextWidget {
-        TextWidget {
-            text,
-            is_wrapped: false,
-            layout: None,
-        }
+        TextWidget { text, layout: None }
     }
 
     pub fn set_text(&mut self, text: String) -> ChangeFlags {
+        if self.text == text {
+            return ChangeFlags::empty();
+        }
         self.text = text;
-        ChangeFlags::LAYOUT | ChangeFlags::PAINT
+        self.layout = None;
+        ChangeFlags::LAYOUT | ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
     }
 }
 
 impl Widget for TextWidget {
-    fn event(&mut self, _cx: &mut EventCx, _event: &RawEvent) {}
+    fn event(&mut self, _cx: &mut EventCx, _event: &Event) {}
 
-    fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        // The text color is part of the layout.
+        if let LifeCycle::EnvChanged { .. } = event {
+            cx.request_layout();
+        }
+    }
 
     fn update(&mut self, cx: &mut UpdateCx) {
         // All changes potentially require layout. Note: we could be finer
@@ -59,32 +67,28 @@ impl Widget for TextWidget {
         cx.request_layout();
     }
 
-    fn measure(&mut self, cx: &mut LayoutCx) -> (Size, Size) {
-        let min_size = Size::ZERO;
-        let max_size = Size::new(50.0, 50.0);
-        self.is_wrapped = false;
-        (min_size, max_size)
-    }
-
-    fn layout(&mut self, cx: &mut LayoutCx, proposed_size: Size) -> Size {
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
         let mut lcx = parley::LayoutContext::new();
         let mut layout_builder = lcx.ranged_builder(cx.font_cx(), &self.text, 1.0);
         layout_builder.push_default(&parley::style::StyleProperty::Brush(ParleyBrush(
-            Brush::Solid(Color::rgb8(255, 255, 255)),
+            Brush::Solid(Theme::get(cx.env()).text_color),
         )));
         let mut layout = layout_builder.build();
-        // Question for Chad: is this needed?
         layout.break_all_lines(None, parley::layout::Alignment::Start);
+        let size = Size::new(layout.width() as f64, layout.height() as f64);
         self.layout = Some(layout);
-        cx.widget_state.max_size
+        bc.constrain(size)
     }
 
-    fn align(&self, cx: &mut AlignCx, alignment: SingleAlignment) {}
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::StaticText);
+        builder.set_name(self.text.as_str());
+        cx.push_node(builder);
+    }
 
-    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+    fn paint(&mut self, _cx: &mut PaintCx, builder: &mut SceneBuilder) {
         if let Some(layout) = &self.layout {
-            let transform = Affine::translate((40.0, 40.0));
-            crate::text::render_text(builder, transform, &layout);
+            crate::text::render_text(builder, Affine::IDENTITY, layout);
         }
     }
 }
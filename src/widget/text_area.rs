@@ -0,0 +1,716 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+use std::time::Duration;
+
+use glazier::{Cursor, KbKey, KeyEvent};
+use parley::Layout;
+use vello::{
+    kurbo::{Affine, Point, Rect, Size, Vec2},
+    peniko::{Brush, Color, Mix},
+    SceneBuilder,
+};
+
+use crate::text::ParleyBrush;
+use crate::{IdPath, Message};
+
+use super::text_editing::{
+    content_end, floor_boundary, inline_text_box, line_of, LineGeometry, TextEditing,
+};
+use super::{
+    contexts::LifeCycleCx, piet_scene_helpers, AccessCx, BoxConstraints, ChangeFlags, Event,
+    EventCx, LayoutCx, LifeCycle, PaintCx, TimerToken, UpdateCx, Widget,
+};
+
+/// Multiple lines of editable text, wrapped at the width of the widget.
+///
+/// The height follows the number of lines between a minimum and a maximum line count, longer
+/// texts scroll vertically. Like with [`TextBox`](super::TextBox), edits are applied right away
+/// and the new text is sent to the view at `id_path`, which is expected to rebuild with the
+/// text it wants to display.
+pub struct TextArea {
+    id_path: IdPath,
+    editing: TextEditing,
+    layout: Option<Layout<ParleyBrush>>,
+    /// The visual lines of the layout.
+    lines: Vec<VisualLine>,
+    /// The width the text was wrapped at.
+    wrap_width: f64,
+    min_lines: usize,
+    max_lines: usize,
+    /// How far the text is scrolled up.
+    scroll: f64,
+    /// Whether the next layout scrolls the caret into view.
+    reveal_caret: bool,
+    caret_visible: bool,
+    /// The timer which toggles the caret next, while the area is focused.
+    blink_timer: Option<TimerToken>,
+    /// The ids of the accessibility nodes for the lines, grown as needed.
+    line_ids: Vec<crate::id::Id>,
+}
+
+/// A line of the wrapped text, in text coordinates.
+struct VisualLine {
+    range: Range<usize>,
+    /// The end of the text on the line, before its line break.
+    end: usize,
+    top: f64,
+    height: f64,
+    width: f64,
+}
+
+/// The lines of a [`TextArea`] for vertical caret movement.
+struct Lines<'a> {
+    text: &'a str,
+    layout: Option<&'a Layout<ParleyBrush>>,
+    lines: &'a [VisualLine],
+}
+
+impl LineGeometry for Lines<'_> {
+    fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    fn line_range(&self, line: usize) -> Range<usize> {
+        self.lines[line].range.clone()
+    }
+
+    fn line_end(&self, line: usize) -> usize {
+        self.lines[line].end
+    }
+
+    fn x_of(&self, offset: usize) -> f64 {
+        let line = &self.lines[line_of(self, offset)];
+        match self.layout {
+            _ if offset <= line.range.start => 0.0,
+            _ if offset >= line.end => line.width,
+            Some(layout) => {
+                parley::layout::Cursor::from_position(layout, offset, true).offset as f64
+            }
+            None => 0.0,
+        }
+    }
+
+    fn offset_at(&self, line: usize, x: f64) -> usize {
+        let line = &self.lines[line];
+        match self.layout {
+            _ if x <= 0.0 => line.range.start,
+            _ if x >= line.width => line.end,
+            Some(layout) => {
+                let y = line.top + line.height * 0.5;
+                let cursor = parley::layout::Cursor::from_point(layout, x as f32, y as f32);
+                floor_boundary(
+                    self.text,
+                    cursor.insert_point.clamp(line.range.start, line.end),
+                )
+            }
+            None => line.range.start,
+        }
+    }
+}
+
+const DEFAULT_WIDTH: f64 = 300.0;
+const MIN_LINE_HEIGHT: f64 = 16.0;
+const INSET_X: f64 = 6.0;
+const INSET_Y: f64 = 4.0;
+/// The width of the highlight for a selected line break.
+const BREAK_WIDTH: f64 = 4.0;
+/// The time the caret stays visible or hidden.
+const BLINK_INTERVAL: Duration = Duration::from_millis(530);
+
+impl TextArea {
+    pub const DEFAULT_MIN_LINES: usize = 3;
+    pub const DEFAULT_MAX_LINES: usize = 10;
+
+    pub fn new(id_path: &IdPath, text: String, min_lines: usize, max_lines: usize) -> TextArea {
+        TextArea {
+            id_path: id_path.clone(),
+            editing: TextEditing::new(text),
+            layout: None,
+            lines: vec![],
+            wrap_width: 0.0,
+            min_lines,
+            max_lines,
+            scroll: 0.0,
+            reveal_caret: false,
+            caret_visible: true,
+            blink_timer: None,
+            line_ids: vec![],
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.editing.text
+    }
+
+    /// The selected byte range, empty when only the caret is shown.
+    pub fn selection(&self) -> Range<usize> {
+        self.editing.selection()
+    }
+
+    /// Replaces the displayed text, keeping the caret and selection where possible.
+    pub fn set_text(&mut self, text: &str) -> ChangeFlags {
+        if self.editing.text == text {
+            return ChangeFlags::empty();
+        }
+        self.editing.set_text(text.to_string());
+        self.layout = None;
+        ChangeFlags::LAYOUT | ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY
+    }
+
+    /// Sets how many lines the height of the widget covers at least and at most.
+    pub fn set_line_limits(&mut self, min_lines: usize, max_lines: usize) -> ChangeFlags {
+        if (self.min_lines, self.max_lines) == (min_lines, max_lines) {
+            return ChangeFlags::empty();
+        }
+        self.min_lines = min_lines;
+        self.max_lines = max_lines;
+        ChangeFlags::LAYOUT | ChangeFlags::PAINT
+    }
+
+    fn geometry(&self) -> Lines {
+        Lines {
+            text: &self.editing.text,
+            layout: self.layout.as_ref(),
+            lines: &self.lines,
+        }
+    }
+
+    /// Collects the visual lines of the current layout.
+    fn build_lines(&mut self) {
+        let text = &self.editing.text;
+        self.lines.clear();
+        let mut top = 0.0;
+        for line in self.layout.iter().flat_map(|layout| layout.lines()) {
+            let metrics = line.metrics();
+            let height =
+                ((metrics.ascent + metrics.descent + metrics.leading) as f64).max(MIN_LINE_HEIGHT);
+            let range = line.text_range();
+            self.lines.push(VisualLine {
+                end: content_end(text, range.clone()),
+                range,
+                top,
+                height,
+                width: metrics.advance as f64,
+            });
+            top += height;
+        }
+        // The empty line behind a final line break has no glyphs, so it may not be reported.
+        let height = self
+            .lines
+            .last()
+            .map_or(MIN_LINE_HEIGHT, |line| line.height);
+        let has_last_line = self
+            .lines
+            .last()
+            .is_some_and(|line| line.range.is_empty() && line.range.start == text.len());
+        if self.lines.is_empty() || (text.ends_with('\n') && !has_last_line) {
+            self.lines.push(VisualLine {
+                range: text.len()..text.len(),
+                end: text.len(),
+                top,
+                height,
+                width: 0.0,
+            });
+        }
+    }
+
+    fn content_height(&self) -> f64 {
+        self.lines.last().map_or(0.0, |line| line.top + line.height)
+    }
+
+    /// The byte offset closest to `pos` in widget coordinates.
+    fn offset_at_point(&self, pos: Point) -> usize {
+        let y = pos.y - INSET_Y + self.scroll;
+        let line = self
+            .lines
+            .iter()
+            .rposition(|line| line.top <= y)
+            .unwrap_or(0);
+        if self.lines.is_empty() {
+            return 0;
+        }
+        self.geometry().offset_at(line, pos.x - INSET_X)
+    }
+
+    /// Shows the caret and restarts blinking, called whenever it moves.
+    fn reset_blink(&mut self, cx: &mut EventCx) {
+        self.caret_visible = true;
+        self.reveal_caret = true;
+        if let Some(timer) = self.blink_timer.take() {
+            cx.cancel_timer(timer);
+        }
+        self.blink_timer = Some(cx.request_timer(BLINK_INTERVAL));
+        // Scrolling to the caret happens in layout.
+        cx.request_layout();
+        cx.request_paint();
+    }
+
+    /// Applies a key press, returns whether it was handled.
+    fn key_down(&mut self, cx: &mut EventCx, event: &KeyEvent) -> bool {
+        let extend = event.mods.shift();
+        let line_height = self
+            .lines
+            .first()
+            .map_or(MIN_LINE_HEIGHT, |line| line.height);
+        let page = ((cx.size().height - 2.0 * INSET_Y) / line_height)
+            .floor()
+            .max(1.0) as isize;
+        // The editing core is changed while the geometry of the current text is read.
+        let text = self.editing.text.clone();
+        let geometry = Lines {
+            text: &text,
+            layout: self.layout.as_ref(),
+            lines: &self.lines,
+        };
+        let editing = &mut self.editing;
+        let edited = match &event.key {
+            KbKey::ArrowUp => editing.move_lines(-1, extend, &geometry),
+            KbKey::ArrowDown => editing.move_lines(1, extend, &geometry),
+            KbKey::PageUp => editing.move_lines(-page, extend, &geometry),
+            KbKey::PageDown => editing.move_lines(page, extend, &geometry),
+            KbKey::Home if !event.mods.ctrl() => editing.line_home(extend, &geometry),
+            KbKey::End if !event.mods.ctrl() => editing.line_end(extend, &geometry),
+            KbKey::Enter => editing.insert("\n"),
+            _ => match editing.edit_key(cx, event) {
+                Some(edited) => edited,
+                None => return false,
+            },
+        };
+        self.after_edit(cx, edited);
+        true
+    }
+
+    /// Reports the text to the view if it was `edited`, and shows the caret where it is now.
+    fn after_edit(&mut self, cx: &mut EventCx, edited: bool) {
+        if edited {
+            self.layout = None;
+            cx.request_accessibility();
+            cx.add_message(Message::new(
+                self.id_path.clone(),
+                self.editing.text.clone(),
+            ));
+        }
+        self.reset_blink(cx);
+    }
+
+    /// Keeps the scroll offset inside the text and, if requested, the caret line in view.
+    fn clamp_scroll(&mut self, height: f64) {
+        let visible = (height - 2.0 * INSET_Y).max(0.0);
+        if std::mem::take(&mut self.reveal_caret) && !self.lines.is_empty() {
+            let line = &self.lines[line_of(&self.geometry(), self.editing.active)];
+            if line.top + line.height - self.scroll > visible {
+                self.scroll = line.top + line.height - visible;
+            }
+            if line.top < self.scroll {
+                self.scroll = line.top;
+            }
+        }
+        self.scroll = self
+            .scroll
+            .clamp(0.0, (self.content_height() - visible).max(0.0));
+    }
+
+    /// The offset of an accessibility text position.
+    fn offset_of(&self, position: &accesskit::TextPosition) -> Option<usize> {
+        let line = self
+            .line_ids
+            .iter()
+            .position(|id| accesskit::NodeId::from(*id) == position.node)?;
+        let line = self.lines.get(line)?;
+        let text = &self.editing.text[line.range.clone()];
+        let offset = text
+            .char_indices()
+            .nth(position.character_index)
+            .map_or(text.len(), |(i, _)| i);
+        Some(line.range.start + offset)
+    }
+}
+
+impl Widget for TextArea {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        let selection = (self.editing.anchor, self.editing.active);
+        match event {
+            Event::MouseDown(mouse_event) => {
+                cx.request_focus();
+                cx.set_active(true);
+                let offset = self.offset_at_point(mouse_event.pos);
+                match mouse_event.click_count {
+                    2 => self.editing.select_word_at(offset),
+                    3.. => {
+                        let text = self.editing.text.clone();
+                        let geometry = Lines {
+                            text: &text,
+                            layout: self.layout.as_ref(),
+                            lines: &self.lines,
+                        };
+                        self.editing.select_line_at(offset, &geometry);
+                    }
+                    _ => {
+                        self.editing.move_caret(offset, mouse_event.mods.shift());
+                    }
+                }
+                self.reset_blink(cx);
+            }
+            Event::MouseMove(mouse_event) => {
+                if cx.is_active() {
+                    let offset = self.offset_at_point(mouse_event.pos);
+                    self.editing.move_caret(offset, true);
+                    self.reset_blink(cx);
+                }
+            }
+            Event::MouseUp(_) => cx.set_active(false),
+            Event::Timer(token) if self.blink_timer == Some(*token) => {
+                self.caret_visible = !self.caret_visible;
+                self.blink_timer = cx.is_focused().then(|| cx.request_timer(BLINK_INTERVAL));
+                cx.request_paint();
+            }
+            Event::MouseWheel(mouse_event) => {
+                let scroll = self.scroll;
+                self.scroll += mouse_event.wheel_delta.y;
+                self.clamp_scroll(cx.size().height);
+                if self.scroll != scroll {
+                    cx.set_handled(true);
+                    cx.request_paint();
+                }
+            }
+            Event::KeyDown(key_event) if cx.is_focused() => {
+                if self.key_down(cx, key_event) {
+                    cx.set_handled(true);
+                }
+            }
+            Event::Text(text) if cx.is_focused() => {
+                let edited = self.editing.insert(text);
+                self.after_edit(cx, edited);
+                cx.set_handled(true);
+            }
+            Event::TargetedAccessibilityAction(request)
+                if cx.is_accesskit_target(request.target) =>
+            {
+                match (request.action, &request.data) {
+                    (accesskit::Action::Focus, _) => {
+                        cx.request_focus();
+                        self.reset_blink(cx);
+                    }
+                    (
+                        accesskit::Action::SetTextSelection,
+                        Some(accesskit::ActionData::SetTextSelection(selection)),
+                    ) => {
+                        let anchor = self.offset_of(&selection.anchor);
+                        let active = self.offset_of(&selection.focus);
+                        if let (Some(anchor), Some(active)) = (anchor, active) {
+                            self.editing.move_caret(anchor, false);
+                            self.editing.move_caret(active, true);
+                            self.reset_blink(cx);
+                        }
+                    }
+                    (accesskit::Action::SetValue, Some(accesskit::ActionData::Value(value))) => {
+                        let edited = self.editing.text != **value;
+                        self.editing.set_text(value.to_string());
+                        self.after_edit(cx, edited);
+                    }
+                    (
+                        accesskit::Action::ReplaceSelectedText,
+                        Some(accesskit::ActionData::Value(value)),
+                    ) => {
+                        let edited = self.editing.insert(value);
+                        self.after_edit(cx, edited);
+                    }
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+        // Assistive technology hears about the caret when it moved, not on every mouse move.
+        if (self.editing.anchor, self.editing.active) != selection {
+            cx.request_accessibility();
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        if let LifeCycle::BuildFocusChain = event {
+            cx.register_for_focus();
+        }
+        if let LifeCycle::HotChanged(true) = event {
+            cx.override_cursor(&Cursor::IBeam);
+        }
+        if let LifeCycle::FocusChanged(false) = event {
+            // Losing focus hides the caret.
+            if let Some(timer) = self.blink_timer.take() {
+                cx.cancel_timer(timer);
+            }
+            cx.request_paint();
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        cx.request_layout();
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            DEFAULT_WIDTH
+        };
+        let wrap_width = (width - 2.0 * INSET_X).max(0.0);
+        if self.layout.is_none() || wrap_width != self.wrap_width {
+            let mut lcx = parley::LayoutContext::new();
+            let mut layout_builder = lcx.ranged_builder(cx.font_cx(), &self.editing.text, 1.0);
+            layout_builder.push_default(&parley::style::StyleProperty::Brush(ParleyBrush(
+                Brush::Solid(Color::rgb8(0xf0, 0xf0, 0xea)),
+            )));
+            let mut layout = layout_builder.build();
+            layout.break_all_lines(Some(wrap_width as f32), parley::layout::Alignment::Start);
+            self.layout = Some(layout);
+            self.wrap_width = wrap_width;
+            self.build_lines();
+        }
+        let line_height = self.lines[0].height;
+        let max_lines = self.max_lines.max(self.min_lines);
+        let shown_lines = self.lines.len().clamp(self.min_lines, max_lines);
+        let height = shown_lines as f64 * line_height + 2.0 * INSET_Y;
+        let size = bc.constrain(Size::new(width, height));
+        self.clamp_scroll(size.height);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        while self.line_ids.len() < self.lines.len() {
+            self.line_ids.push(crate::id::Id::next());
+        }
+        let text = self.editing.text.as_str();
+        let geometry = self.geometry();
+        for (line, id) in self.lines.iter().zip(&self.line_ids) {
+            let x_of = |offset: usize| INSET_X + geometry.x_of(line.range.start + offset);
+            // A selected line break is highlighted, so it has a width.
+            let end_x = if line.range.end > line.end {
+                INSET_X + line.width + BREAK_WIDTH
+            } else {
+                INSET_X + line.width
+            };
+            let run = inline_text_box(&text[line.range.clone()], x_of, end_x);
+            cx.push_child_node(*id, run);
+        }
+
+        let position = |offset: usize| {
+            let line = line_of(&geometry, offset);
+            let start = self.lines[line].range.start;
+            accesskit::TextPosition {
+                node: self.line_ids[line].into(),
+                character_index: text[start..offset].chars().count(),
+            }
+        };
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::TextField);
+        builder.set_value(text);
+        builder.set_multiline();
+        let line_ids = &self.line_ids[..self.lines.len()];
+        builder.set_children(line_ids.iter().map(|id| (*id).into()).collect::<Vec<_>>());
+        builder.set_text_selection(accesskit::TextSelection {
+            anchor: position(self.editing.anchor),
+            focus: position(self.editing.active),
+        });
+        builder.add_action(accesskit::Action::Focus);
+        builder.add_action(accesskit::Action::SetTextSelection);
+        builder.add_action(accesskit::Action::SetValue);
+        builder.add_action(accesskit::Action::ReplaceSelectedText);
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let size = cx.size();
+        let frame = size.to_rect().to_rounded_rect(4.0);
+        piet_scene_helpers::fill_color(builder, &frame, Color::rgb8(0x20, 0x20, 0x20));
+        let border = if cx.is_focused() {
+            Color::rgb8(0x5a, 0x9b, 0xe6)
+        } else {
+            Color::rgb8(0x3a, 0x3a, 0x3a)
+        };
+        piet_scene_helpers::stroke(builder, &frame, border, 1.0);
+
+        let inner = Rect::new(
+            INSET_X,
+            INSET_Y,
+            size.width - INSET_X,
+            size.height - INSET_Y,
+        );
+        builder.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &inner);
+        let origin = Vec2::new(INSET_X, INSET_Y - self.scroll);
+        let geometry = self.geometry();
+
+        let selection = self.editing.selection();
+        if !selection.is_empty() {
+            for line in &self.lines {
+                if selection.end <= line.range.start || selection.start > line.end {
+                    continue;
+                }
+                let x0 = geometry.x_of(selection.start.max(line.range.start));
+                // A selected line break is highlighted behind the text.
+                let x1 = if selection.end > line.end {
+                    line.width + BREAK_WIDTH
+                } else {
+                    geometry.x_of(selection.end)
+                };
+                let highlight = Rect::new(x0, line.top, x1, line.top + line.height) + origin;
+                piet_scene_helpers::fill_color(builder, &highlight, Color::rgb8(0x2a, 0x4b, 0x76));
+            }
+        }
+        if let Some(layout) = &self.layout {
+            crate::text::render_text(builder, Affine::translate(origin), layout);
+        }
+        if cx.is_focused() && self.caret_visible && !self.lines.is_empty() {
+            let line = &self.lines[line_of(&geometry, self.editing.active)];
+            let x = (origin.x + geometry.x_of(self.editing.active)).round() + 0.5;
+            let y = origin.y + line.top;
+            let caret = Rect::new(x - 0.5, y, x + 0.5, y + line.height);
+            piet_scene_helpers::fill_color(builder, &caret, Color::rgb8(0xf0, 0xf0, 0xea));
+        }
+        builder.pop_layer();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{key_down, Harness};
+    use crate::widget::{Pod, PodFlags};
+    use test_log::test;
+    use xilem_core::Id;
+
+    fn text_area(harness: &mut Harness, text: &str, min: usize, max: usize) -> (IdPath, Pod) {
+        let id_path = vec![Id::next()];
+        let mut pod = Pod::new(TextArea::new(&id_path, text.to_string(), min, max));
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(300.0, 1000.0));
+        harness.layout(&mut pod, &bc);
+        (id_path, pod)
+    }
+
+    fn height(harness: &mut Harness, pod: &mut Pod) -> f64 {
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(300.0, 1000.0));
+        harness.layout(pod, &bc).height
+    }
+
+    #[test]
+    fn enter_inserts_line_break() {
+        let mut harness = Harness::new();
+        let (id_path, mut pod) = text_area(&mut harness, "ab", 1, 5);
+        harness.window_state.focus = Some(pod.id());
+
+        harness.event(&mut pod, key_down(KbKey::ArrowLeft));
+        harness.event(&mut pod, key_down(KbKey::Enter));
+        let messages = harness.take_messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id_path, id_path);
+        assert_eq!(*messages[0].body.downcast_ref::<String>().unwrap(), "a\nb");
+
+        // Up moves to the first line and End stops before its line break.
+        height(&mut harness, &mut pod);
+        harness.event(&mut pod, key_down(KbKey::ArrowUp));
+        harness.event(&mut pod, key_down(KbKey::End));
+        let text_area = pod.downcast_mut::<TextArea>().unwrap();
+        assert_eq!(text_area.selection(), 1..1);
+    }
+
+    #[test]
+    fn height_follows_lines_between_limits() {
+        let mut harness = Harness::new();
+        let (_, mut pod) = text_area(&mut harness, "one", 2, 4);
+        let two_lines = height(&mut harness, &mut pod);
+
+        let flags = pod.downcast_mut::<TextArea>().unwrap().set_text("1\n2\n3");
+        assert!(flags.contains(ChangeFlags::LAYOUT));
+        let _ = pod.mark(flags);
+        let three_lines = height(&mut harness, &mut pod);
+        assert!(three_lines > two_lines);
+
+        // A trailing line break starts a fourth line.
+        let flags = pod
+            .downcast_mut::<TextArea>()
+            .unwrap()
+            .set_text("1\n2\n3\n");
+        let _ = pod.mark(flags);
+        let four_lines = height(&mut harness, &mut pod);
+        assert!(four_lines > three_lines);
+
+        // Beyond the maximum the height stays and the caret line is scrolled into view.
+        let flags = pod
+            .downcast_mut::<TextArea>()
+            .unwrap()
+            .set_text("1\n2\n3\n4\n5\n6\n7\n8");
+        let _ = pod.mark(flags);
+        assert_eq!(height(&mut harness, &mut pod), four_lines);
+        harness.window_state.focus = Some(pod.id());
+        harness.event(&mut pod, key_down(KbKey::PageDown));
+        harness.event(&mut pod, key_down(KbKey::PageDown));
+        height(&mut harness, &mut pod);
+        let text_area = pod.downcast_mut::<TextArea>().unwrap();
+        assert_eq!(text_area.selection(), 15..15);
+        assert!(text_area.scroll > 0.0);
+    }
+
+    #[test]
+    fn accessibility_selects_across_lines_and_replaces_text() {
+        let mut harness = Harness::new();
+        let (_, mut pod) = text_area(&mut harness, "ab\ncd", 1, 5);
+        let area_id = accesskit::NodeId::from(pod.id());
+        let update = harness.accessibility(&mut pod);
+        let node = |update: &accesskit::TreeUpdate, id: accesskit::NodeId| {
+            let mut nodes = update.nodes.iter();
+            let (_, node) = nodes.find(|(node_id, _)| *node_id == id).unwrap();
+            node.clone()
+        };
+        let lines = node(&update, area_id).children().to_vec();
+        assert_eq!(lines.len(), 2);
+        // The line break is a character of the first line, drawn highlighted when selected.
+        let first = node(&update, lines[0]);
+        assert_eq!(first.value(), Some("ab\n"));
+        assert_eq!(first.word_lengths(), [3]);
+        assert!(first.character_widths().unwrap()[2] > 0.0);
+
+        let select = Event::TargetedAccessibilityAction(accesskit::ActionRequest {
+            action: accesskit::Action::SetTextSelection,
+            target: area_id,
+            data: Some(accesskit::ActionData::SetTextSelection(
+                accesskit::TextSelection {
+                    anchor: accesskit::TextPosition {
+                        node: lines[0],
+                        character_index: 1,
+                    },
+                    focus: accesskit::TextPosition {
+                        node: lines[1],
+                        character_index: 1,
+                    },
+                },
+            )),
+        });
+        harness.event(&mut pod, select);
+        assert_eq!(pod.downcast_mut::<TextArea>().unwrap().selection(), 1..4);
+        assert!(pod.state.flags.contains(PodFlags::REQUEST_PAINT));
+
+        let replace = Event::TargetedAccessibilityAction(accesskit::ActionRequest {
+            action: accesskit::Action::ReplaceSelectedText,
+            target: area_id,
+            data: Some(accesskit::ActionData::Value("-".into())),
+        });
+        harness.event(&mut pod, replace);
+        let messages = harness.take_messages();
+        assert_eq!(*messages[0].body.downcast_ref::<String>().unwrap(), "a-d");
+        height(&mut harness, &mut pod);
+        let update = harness.accessibility(&mut pod);
+        let area = node(&update, area_id);
+        assert_eq!(area.value(), Some("a-d"));
+        assert_eq!(area.children().len(), 1);
+    }
+}
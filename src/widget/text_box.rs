@@ -0,0 +1,652 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+use std::time::Duration;
+
+use glazier::{Cursor, KeyEvent};
+use parley::Layout;
+use vello::{
+    kurbo::{Affine, Point, Rect, Size},
+    peniko::{Brush, Color, Mix},
+    SceneBuilder,
+};
+
+use crate::text::ParleyBrush;
+use crate::{IdPath, Message};
+
+use super::text_editing::{floor_boundary, inline_text_box, TextEditing};
+use super::{
+    contexts::LifeCycleCx, paint_focus_ring_around, piet_scene_helpers, AccessCx, BoxConstraints,
+    ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, PaintCx, TimerToken, UpdateCx, Widget,
+    FOCUS_RING_EXTENT,
+};
+
+/// A single line of editable text.
+///
+/// Edits are applied right away and the new text is sent to the view at `id_path`. The view is
+/// expected to rebuild with the text it wants to display, when that is the text the widget
+/// already shows, the caret and selection stay where they are.
+pub struct TextBox {
+    id_path: IdPath,
+    editing: TextEditing,
+    layout: Option<Layout<ParleyBrush>>,
+    /// How far the text is scrolled to the left to keep the caret visible.
+    scroll: f64,
+    caret_visible: bool,
+    /// The timer which toggles the caret next, while the box is focused.
+    blink_timer: Option<TimerToken>,
+    /// The id of the accessibility node for the text run.
+    run_id: crate::id::Id,
+}
+
+const DEFAULT_WIDTH: f64 = 200.0;
+const MIN_LINE_HEIGHT: f64 = 16.0;
+const INSET_X: f64 = 6.0;
+const INSET_Y: f64 = 4.0;
+const FRAME_RADIUS: f64 = 4.0;
+/// The time the caret stays visible or hidden.
+const BLINK_INTERVAL: Duration = Duration::from_millis(530);
+
+impl TextBox {
+    pub fn new(id_path: &IdPath, text: String) -> TextBox {
+        TextBox {
+            id_path: id_path.clone(),
+            editing: TextEditing::new(text),
+            layout: None,
+            scroll: 0.0,
+            caret_visible: true,
+            blink_timer: None,
+            run_id: crate::id::Id::next(),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.editing.text
+    }
+
+    /// The selected byte range, empty when only the caret is shown.
+    pub fn selection(&self) -> Range<usize> {
+        self.editing.selection()
+    }
+
+    /// Replaces the displayed text.
+    ///
+    /// The caret and selection are kept where possible, so setting the text the widget already
+    /// shows, for example after a local edit went through the app state, changes nothing.
+    pub fn set_text(&mut self, text: &str) -> ChangeFlags {
+        if self.editing.text == text {
+            return ChangeFlags::empty();
+        }
+        self.editing.set_text(text.to_string());
+        self.layout = None;
+        ChangeFlags::LAYOUT | ChangeFlags::PAINT | ChangeFlags::ACCESSIBILITY
+    }
+
+    /// The horizontal position of the byte `offset` in the text layout.
+    fn offset_x(&self, offset: usize) -> f64 {
+        let Some(layout) = &self.layout else {
+            return 0.0;
+        };
+        if offset >= self.editing.text.len() {
+            return layout.width() as f64;
+        }
+        parley::layout::Cursor::from_position(layout, offset, true).offset as f64
+    }
+
+    /// The byte offset closest to the horizontal position `x` in widget coordinates.
+    fn offset_at(&self, x: f64) -> usize {
+        let Some(layout) = &self.layout else {
+            return 0;
+        };
+        let x = x - INSET_X + self.scroll;
+        if x <= 0.0 {
+            return 0;
+        }
+        if x >= layout.width() as f64 {
+            return self.editing.text.len();
+        }
+        let cursor = parley::layout::Cursor::from_point(layout, x as f32, 0.0);
+        floor_boundary(&self.editing.text, cursor.insert_point)
+    }
+
+    /// Shows the caret and restarts blinking, called whenever it moves.
+    fn reset_blink(&mut self, cx: &mut EventCx) {
+        self.caret_visible = true;
+        if let Some(timer) = self.blink_timer.take() {
+            cx.cancel_timer(timer);
+        }
+        self.blink_timer = Some(cx.request_timer(BLINK_INTERVAL));
+        // Scrolling to the caret happens in layout.
+        cx.request_layout();
+        cx.request_paint();
+    }
+
+    /// Applies a key press, returns whether it was handled.
+    fn key_down(&mut self, cx: &mut EventCx, event: &KeyEvent) -> bool {
+        let Some(edited) = self.editing.edit_key(cx, event) else {
+            return false;
+        };
+        self.after_edit(cx, edited);
+        true
+    }
+
+    /// Reports the text to the view if it was `edited`, and shows the caret where it is now.
+    fn after_edit(&mut self, cx: &mut EventCx, edited: bool) {
+        if edited {
+            self.layout = None;
+            cx.request_accessibility();
+            cx.add_message(Message::new(
+                self.id_path.clone(),
+                self.editing.text.clone(),
+            ));
+        }
+        self.reset_blink(cx);
+    }
+
+    /// Keeps the caret inside the visible part of the box.
+    fn scroll_to_caret(&mut self, width: f64) {
+        let visible = (width - 2.0 * INSET_X).max(0.0);
+        let text_width = self.layout.as_ref().map_or(0.0, |l| l.width() as f64);
+        let caret = self.offset_x(self.editing.active);
+        if caret - self.scroll > visible {
+            self.scroll = caret - visible;
+        }
+        if caret < self.scroll {
+            self.scroll = caret;
+        }
+        self.scroll = self.scroll.clamp(0.0, (text_width - visible).max(0.0));
+    }
+
+    fn char_index(&self, offset: usize) -> usize {
+        self.editing.text[..offset].chars().count()
+    }
+}
+
+impl Widget for TextBox {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        let selection = (self.editing.anchor, self.editing.active);
+        match event {
+            Event::MouseDown(mouse_event) => {
+                cx.request_focus();
+                cx.set_active(true);
+                let offset = self.offset_at(mouse_event.pos.x);
+                match mouse_event.click_count {
+                    2 => self.editing.select_word_at(offset),
+                    // The only line is all of the text.
+                    3.. => {
+                        self.editing.select_all();
+                    }
+                    _ => {
+                        self.editing.move_caret(offset, mouse_event.mods.shift());
+                    }
+                }
+                self.reset_blink(cx);
+            }
+            Event::MouseMove(mouse_event) => {
+                if cx.is_active() {
+                    let offset = self.offset_at(mouse_event.pos.x);
+                    self.editing.move_caret(offset, true);
+                    self.reset_blink(cx);
+                }
+            }
+            Event::MouseUp(_) => cx.set_active(false),
+            Event::Timer(token) if self.blink_timer == Some(*token) => {
+                self.caret_visible = !self.caret_visible;
+                self.blink_timer = cx.is_focused().then(|| cx.request_timer(BLINK_INTERVAL));
+                cx.request_paint();
+            }
+            Event::KeyDown(key_event) if cx.is_focused() => {
+                if self.key_down(cx, key_event) {
+                    cx.set_handled(true);
+                }
+            }
+            Event::Text(text) if cx.is_focused() => {
+                let edited = self.editing.insert(text);
+                self.after_edit(cx, edited);
+                cx.set_handled(true);
+            }
+            Event::TargetedAccessibilityAction(request)
+                if cx.is_accesskit_target(request.target) =>
+            {
+                match (request.action, &request.data) {
+                    (accesskit::Action::Focus, _) => {
+                        cx.request_focus();
+                        self.reset_blink(cx);
+                    }
+                    (
+                        accesskit::Action::SetTextSelection,
+                        Some(accesskit::ActionData::SetTextSelection(selection)),
+                    ) => {
+                        let offset = |index| {
+                            let text = &self.editing.text;
+                            text.char_indices()
+                                .nth(index)
+                                .map_or(text.len(), |(i, _)| i)
+                        };
+                        let anchor = offset(selection.anchor.character_index);
+                        let active = offset(selection.focus.character_index);
+                        self.editing.move_caret(anchor, false);
+                        self.editing.move_caret(active, true);
+                        self.reset_blink(cx);
+                    }
+                    (accesskit::Action::SetValue, Some(accesskit::ActionData::Value(value))) => {
+                        let edited = self.editing.text != **value;
+                        self.editing.set_text(value.to_string());
+                        self.after_edit(cx, edited);
+                    }
+                    (
+                        accesskit::Action::ReplaceSelectedText,
+                        Some(accesskit::ActionData::Value(value)),
+                    ) => {
+                        let edited = self.editing.insert(value);
+                        self.after_edit(cx, edited);
+                    }
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+        // Assistive technology hears about the caret when it moved, not on every mouse move.
+        if (self.editing.anchor, self.editing.active) != selection {
+            cx.request_accessibility();
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        if let LifeCycle::BuildFocusChain = event {
+            cx.register_for_focus();
+        }
+        if let LifeCycle::HotChanged(true) = event {
+            cx.override_cursor(&Cursor::IBeam);
+        }
+        if let LifeCycle::FocusChanged(false) = event {
+            // Losing focus hides the caret.
+            if let Some(timer) = self.blink_timer.take() {
+                cx.cancel_timer(timer);
+            }
+            cx.request_paint();
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        cx.request_layout();
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        if self.layout.is_none() {
+            let mut lcx = parley::LayoutContext::new();
+            let mut layout_builder = lcx.ranged_builder(cx.font_cx(), &self.editing.text, 1.0);
+            layout_builder.push_default(&parley::style::StyleProperty::Brush(ParleyBrush(
+                Brush::Solid(Color::rgb8(0xf0, 0xf0, 0xea)),
+            )));
+            let mut layout = layout_builder.build();
+            layout.break_all_lines(None, parley::layout::Alignment::Start);
+            self.layout = Some(layout);
+        }
+        let line_height = self
+            .layout
+            .as_ref()
+            .map_or(0.0, |layout| layout.height() as f64)
+            .max(MIN_LINE_HEIGHT);
+        let size = bc.constrain(Size::new(DEFAULT_WIDTH, line_height + 2.0 * INSET_Y));
+        self.scroll_to_caret(size.width);
+        cx.set_paint_insets(FOCUS_RING_EXTENT);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        let text = self.editing.text.as_str();
+
+        let text_x = INSET_X - self.scroll;
+        let run = inline_text_box(
+            text,
+            |offset| text_x + self.offset_x(offset),
+            text_x + self.offset_x(text.len()),
+        );
+        cx.push_child_node(self.run_id, run);
+
+        let position = |offset| accesskit::TextPosition {
+            node: self.run_id.into(),
+            character_index: self.char_index(offset),
+        };
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::TextField);
+        builder.set_value(text);
+        builder.push_child(self.run_id.into());
+        builder.set_text_selection(accesskit::TextSelection {
+            anchor: position(self.editing.anchor),
+            focus: position(self.editing.active),
+        });
+        builder.add_action(accesskit::Action::Focus);
+        builder.add_action(accesskit::Action::SetTextSelection);
+        builder.add_action(accesskit::Action::SetValue);
+        builder.add_action(accesskit::Action::ReplaceSelectedText);
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let size = cx.size();
+        let frame = size.to_rect().to_rounded_rect(FRAME_RADIUS);
+        piet_scene_helpers::fill_color(builder, &frame, Color::rgb8(0x20, 0x20, 0x20));
+        let border = if cx.is_disabled() {
+            Color::rgb8(0x2a, 0x2a, 0x2a)
+        } else if cx.is_focused() {
+            Color::rgb8(0x5a, 0x9b, 0xe6)
+        } else {
+            Color::rgb8(0x3a, 0x3a, 0x3a)
+        };
+        piet_scene_helpers::stroke(builder, &frame, border, 1.0);
+
+        let inner = Rect::new(
+            INSET_X,
+            INSET_Y,
+            size.width - INSET_X,
+            size.height - INSET_Y,
+        );
+        builder.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &inner);
+        let text_x = INSET_X - self.scroll;
+
+        let selection = self.editing.selection();
+        if !selection.is_empty() {
+            let x0 = text_x + self.offset_x(selection.start);
+            let x1 = text_x + self.offset_x(selection.end);
+            let highlight = Rect::new(x0, inner.y0, x1, inner.y1);
+            piet_scene_helpers::fill_color(builder, &highlight, Color::rgb8(0x2a, 0x4b, 0x76));
+        }
+        if let Some(layout) = &self.layout {
+            crate::text::render_text(builder, Affine::translate((text_x, INSET_Y)), layout);
+        }
+        if cx.is_focused() && self.caret_visible {
+            let x = (text_x + self.offset_x(self.editing.active)).round() + 0.5;
+            let caret =
+                Rect::from_points(Point::new(x - 0.5, inner.y0), Point::new(x + 0.5, inner.y1));
+            piet_scene_helpers::fill_color(builder, &caret, Color::rgb8(0xf0, 0xf0, 0xea));
+        }
+        builder.pop_layer();
+        // The border shows focus taken by a click as well, the ring only keyboard focus.
+        paint_focus_ring_around(cx, builder, size.to_rect(), FRAME_RADIUS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Axis;
+    use crate::widget::test_util::{key_down, mouse, Harness};
+    use crate::widget::{LinearLayout, Pod, PodFlags};
+    use glazier::{KbKey, KeyState, Modifiers};
+    use test_log::test;
+    use xilem_core::Id;
+
+    /// "a", "é" (2 bytes), "👍" (4 bytes) and "b".
+    const MIXED: &str = "aé👍b";
+
+    fn text_box(harness: &mut Harness, text: &str) -> (IdPath, Pod) {
+        let id_path = vec![Id::next()];
+        let mut pod = Pod::new(TextBox::new(&id_path, text.to_string()));
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(300.0, 100.0));
+        harness.layout(&mut pod, &bc);
+        (id_path, pod)
+    }
+
+    fn key(key: KbKey, mods: Modifiers) -> Event {
+        Event::KeyDown(KeyEvent {
+            key,
+            mods,
+            state: KeyState::Down,
+            ..Default::default()
+        })
+    }
+
+    fn sent_texts(harness: &mut Harness) -> Vec<String> {
+        harness
+            .take_messages()
+            .into_iter()
+            .map(|message| *message.body.downcast::<String>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn keys_only_edit_when_focused() {
+        let mut harness = Harness::new();
+        let (_, mut pod) = text_box(&mut harness, "ab");
+
+        harness.event(&mut pod, key_down(KbKey::Character("c".into())));
+        assert!(sent_texts(&mut harness).is_empty());
+
+        harness.window_state.focus = Some(pod.id());
+        harness.event(&mut pod, key_down(KbKey::Character("c".into())));
+        harness.event(&mut pod, key_down(KbKey::ArrowLeft));
+        harness.event(&mut pod, key_down(KbKey::Backspace));
+        assert_eq!(sent_texts(&mut harness), ["abc", "ac"]);
+    }
+
+    #[test]
+    fn caret_blinks_while_focused() {
+        let mut harness = Harness::new();
+        let (_, mut pod) = text_box(&mut harness, "ab");
+        let caret_visible = |pod: &mut Pod| pod.downcast_mut::<TextBox>().unwrap().caret_visible;
+
+        harness.event(&mut pod, Event::MouseDown(mouse((1.0, 5.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((1.0, 5.0))));
+        harness.advance_time(&mut pod, Duration::from_millis(500));
+        assert!(caret_visible(&mut pod));
+        harness.advance_time(&mut pod, Duration::from_millis(50));
+        assert!(!caret_visible(&mut pod));
+        // Typing shows the caret again and restarts the blinking.
+        harness.event(&mut pod, key_down(KbKey::Character("c".into())));
+        harness.advance_time(&mut pod, Duration::from_millis(500));
+        assert!(caret_visible(&mut pod));
+        harness.advance_time(&mut pod, Duration::from_millis(50));
+        assert!(!caret_visible(&mut pod));
+
+        // Without focus the caret stops blinking.
+        harness.window_state.focus = None;
+        harness.update(&mut pod);
+        harness.advance_time(&mut pod, Duration::from_secs(2));
+        assert!(harness.window_state.timers.next_deadline().is_none());
+    }
+
+    #[test]
+    fn local_edit_keeps_caret_through_rebuild() {
+        let mut harness = Harness::new();
+        let (id_path, mut pod) = text_box(&mut harness, "wörld");
+        harness.window_state.focus = Some(pod.id());
+
+        harness.event(&mut pod, key(KbKey::Home, Modifiers::empty()));
+        harness.event(
+            &mut pod,
+            key(KbKey::Character("h".into()), Modifiers::empty()),
+        );
+        harness.event(&mut pod, key(KbKey::ArrowRight, Modifiers::SHIFT));
+        harness.event(&mut pod, key(KbKey::ArrowRight, Modifiers::SHIFT));
+        let messages = harness.take_messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id_path, id_path);
+
+        let text_box = pod.downcast_mut::<TextBox>().unwrap();
+        assert_eq!(text_box.set_text("hwörld"), ChangeFlags::empty());
+        assert_eq!(text_box.selection(), 1..4);
+
+        // A different text from the app clamps the selection instead.
+        assert!(text_box.set_text("hw").contains(ChangeFlags::LAYOUT));
+        assert_eq!(text_box.selection(), 1..2);
+    }
+
+    #[test]
+    fn command_shortcuts_do_not_insert() {
+        let mut harness = Harness::new();
+        let (_, mut pod) = text_box(&mut harness, "abc");
+        harness.window_state.focus = Some(pod.id());
+        let command = if cfg!(target_os = "macos") {
+            Modifiers::META
+        } else {
+            Modifiers::CONTROL
+        };
+
+        harness.event(&mut pod, key(KbKey::Character("a".into()), command));
+        assert_eq!(pod.downcast_mut::<TextBox>().unwrap().selection(), 0..3);
+        harness.event(&mut pod, key(KbKey::Character("x".into()), command));
+        assert_eq!(sent_texts(&mut harness), [""]);
+        assert_eq!(
+            harness.window_state.clipboard.get_text().as_deref(),
+            Some("abc")
+        );
+        harness.event(&mut pod, key(KbKey::Character("v".into()), command));
+        assert_eq!(sent_texts(&mut harness), ["abc"]);
+    }
+
+    #[test]
+    fn double_click_selects_a_word_and_triple_click_the_line() {
+        let mut harness = Harness::new();
+        let (_, mut pod) = text_box(&mut harness, "one two");
+        let selection = |pod: &mut Pod| pod.downcast_mut::<TextBox>().unwrap().selection();
+
+        for _ in 0..2 {
+            harness.event(&mut pod, Event::MouseDown(mouse((1.0, 5.0))));
+            harness.event(&mut pod, Event::MouseUp(mouse((1.0, 5.0))));
+        }
+        assert_eq!(selection(&mut pod), 0..3);
+        harness.event(&mut pod, Event::MouseDown(mouse((1.0, 5.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((1.0, 5.0))));
+        assert_eq!(selection(&mut pod), 0..7);
+    }
+
+    #[test]
+    fn click_focuses_and_accessibility_reports_char_indices() {
+        let mut harness = Harness::new();
+        let (_, mut pod) = text_box(&mut harness, MIXED);
+
+        harness.event(&mut pod, Event::MouseDown(mouse((1.0, 5.0))));
+        harness.event(&mut pod, Event::MouseUp(mouse((1.0, 5.0))));
+        assert_eq!(harness.window_state.focus, Some(pod.id()));
+        assert_eq!(pod.downcast_mut::<TextBox>().unwrap().selection(), 0..0);
+
+        harness.event(&mut pod, key(KbKey::End, Modifiers::SHIFT));
+        let update = harness.accessibility(&mut pod);
+        let (_, node) = update
+            .nodes
+            .iter()
+            .find(|(id, _)| *id == pod.id().into())
+            .unwrap();
+        let selection = node.text_selection().unwrap();
+        assert_eq!(selection.anchor.character_index, 0);
+        assert_eq!(selection.focus.character_index, 4);
+    }
+
+    /// The node of the text box in the accessibility update, if it changed.
+    fn text_node(harness: &mut Harness, pod: &mut Pod) -> Option<accesskit::Node> {
+        let update = harness.accessibility(pod);
+        let id = accesskit::NodeId::from(pod.id());
+        let mut nodes = update.nodes.into_iter();
+        nodes
+            .find(|(node_id, _)| *node_id == id)
+            .map(|(_, node)| node)
+    }
+
+    #[test]
+    fn accessibility_actions_select_and_replace_text() {
+        let mut harness = Harness::new();
+        let (_, mut pod) = text_box(&mut harness, MIXED);
+        let run_id = pod.downcast_mut::<TextBox>().unwrap().run_id;
+        let position = |character_index| accesskit::TextPosition {
+            node: run_id.into(),
+            character_index,
+        };
+
+        let select = Event::TargetedAccessibilityAction(accesskit::ActionRequest {
+            action: accesskit::Action::SetTextSelection,
+            target: pod.id().into(),
+            data: Some(accesskit::ActionData::SetTextSelection(
+                accesskit::TextSelection {
+                    anchor: position(1),
+                    focus: position(3),
+                },
+            )),
+        });
+        harness.event(&mut pod, select);
+        // "é" and "👍" are selected and shown as such.
+        assert_eq!(pod.downcast_mut::<TextBox>().unwrap().selection(), 1..7);
+        assert!(pod.state.flags.contains(PodFlags::REQUEST_PAINT));
+        let selection = text_node(&mut harness, &mut pod).unwrap().text_selection();
+        assert_eq!(selection.unwrap().focus.character_index, 3);
+
+        let replace = Event::TargetedAccessibilityAction(accesskit::ActionRequest {
+            action: accesskit::Action::ReplaceSelectedText,
+            target: pod.id().into(),
+            data: Some(accesskit::ActionData::Value("ü".into())),
+        });
+        harness.event(&mut pod, replace);
+        assert_eq!(sent_texts(&mut harness), ["aüb"]);
+        let node = text_node(&mut harness, &mut pod).unwrap();
+        assert_eq!(node.value(), Some("aüb"));
+    }
+
+    #[test]
+    fn only_changes_update_accessibility() {
+        let mut harness = Harness::new();
+        let (_, mut pod) = text_box(&mut harness, "ab");
+        harness.window_state.focus = Some(pod.id());
+        let _ = harness.accessibility(&mut pod);
+
+        harness.event(&mut pod, key_down(KbKey::Character("c".into())));
+        let node = text_node(&mut harness, &mut pod).unwrap();
+        assert_eq!(node.value(), Some("abc"));
+        assert_eq!(node.text_selection().unwrap().focus.character_index, 3);
+
+        // The caret is already at the end, and hovering moves nothing.
+        harness.event(&mut pod, key_down(KbKey::ArrowRight));
+        harness.event(&mut pod, Event::MouseMove(mouse((1.0, 5.0))));
+        assert!(harness.accessibility(&mut pod).nodes.is_empty());
+
+        harness.event(&mut pod, key_down(KbKey::ArrowLeft));
+        let node = text_node(&mut harness, &mut pod).unwrap();
+        assert_eq!(node.text_selection().unwrap().focus.character_index, 2);
+    }
+
+    #[test]
+    fn typing_goes_to_the_focused_box_and_tab_moves_on() {
+        let mut harness = Harness::new();
+        let (first_path, second_path) = (vec![Id::next()], vec![Id::next()]);
+        let first = Pod::new(TextBox::new(&first_path, String::new()));
+        let second = Pod::new(TextBox::new(&second_path, String::new()));
+        let (first_id, second_id) = (first.id(), second.id());
+        let row = LinearLayout::new(vec![first, second], 0.0, Axis::Horizontal);
+        let mut pod = Pod::new(row);
+        harness.layout(&mut pod, &BoxConstraints::UNBOUNDED);
+
+        harness.window_state.focus = Some(first_id);
+        harness.event(&mut pod, key_down(KbKey::Character("a".into())));
+        // Tab moves focus instead of being typed.
+        harness.event(&mut pod, key_down(KbKey::Tab));
+        assert_eq!(harness.window_state.focus, Some(second_id));
+        harness.event(&mut pod, key_down(KbKey::Character("b".into())));
+
+        let edits: Vec<_> = harness
+            .take_messages()
+            .into_iter()
+            .map(|message| {
+                let id_path = message.id_path.clone();
+                (id_path, *message.body.downcast::<String>().unwrap())
+            })
+            .collect();
+        assert_eq!(
+            edits,
+            [
+                (first_path, "a".to_string()),
+                (second_path, "b".to_string())
+            ]
+        );
+    }
+}
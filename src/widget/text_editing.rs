@@ -0,0 +1,646 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+use glazier::{KbKey, KeyEvent, Modifiers};
+
+use super::EventCx;
+
+/// The text of an editing widget and its selection, as byte offsets on char boundaries.
+///
+/// This is the editing core shared by [`TextBox`] and [`TextArea`], the widgets add layout,
+/// scrolling and painting.
+///
+/// [`TextBox`]: super::TextBox
+/// [`TextArea`]: super::TextArea
+#[derive(Debug, Default)]
+pub(crate) struct TextEditing {
+    pub(crate) text: String,
+    /// Where the selection started, stays put while the selection is extended.
+    pub(crate) anchor: usize,
+    /// The caret, the end of the selection that moves.
+    pub(crate) active: usize,
+    /// The horizontal position the caret keeps returning to while it moves between lines, set
+    /// by the first vertical movement.
+    goal_x: Option<f64>,
+}
+
+impl TextEditing {
+    pub(crate) fn new(text: String) -> Self {
+        let end = text.len();
+        TextEditing {
+            text,
+            anchor: end,
+            active: end,
+            goal_x: None,
+        }
+    }
+
+    pub(crate) fn selection(&self) -> Range<usize> {
+        self.anchor.min(self.active)..self.anchor.max(self.active)
+    }
+
+    pub(crate) fn selected_text(&self) -> &str {
+        &self.text[self.selection()]
+    }
+
+    /// Replaces the text, keeping the selection at the same offsets as far as they still fit.
+    pub(crate) fn set_text(&mut self, text: String) {
+        self.text = text;
+        self.anchor = floor_boundary(&self.text, self.anchor);
+        self.active = floor_boundary(&self.text, self.active);
+    }
+
+    // The movement methods return whether the text was edited, which they never do, so they
+    // can be used in the same match as the editing ones.
+
+    pub(crate) fn move_caret(&mut self, offset: usize, extend: bool) -> bool {
+        self.active = floor_boundary(&self.text, offset);
+        if !extend {
+            self.anchor = self.active;
+        }
+        self.goal_x = None;
+        false
+    }
+
+    pub(crate) fn left(&mut self, extend: bool) -> bool {
+        let selection = self.selection();
+        if !extend && !selection.is_empty() {
+            return self.move_caret(selection.start, false);
+        }
+        self.move_caret(prev_boundary(&self.text, self.active), extend)
+    }
+
+    pub(crate) fn right(&mut self, extend: bool) -> bool {
+        let selection = self.selection();
+        if !extend && !selection.is_empty() {
+            return self.move_caret(selection.end, false);
+        }
+        self.move_caret(next_boundary(&self.text, self.active), extend)
+    }
+
+    pub(crate) fn word_left(&mut self, extend: bool) -> bool {
+        self.move_caret(word_start_before(&self.text, self.active), extend)
+    }
+
+    pub(crate) fn word_right(&mut self, extend: bool) -> bool {
+        self.move_caret(word_end_after(&self.text, self.active), extend)
+    }
+
+    pub(crate) fn home(&mut self, extend: bool) -> bool {
+        self.move_caret(0, extend)
+    }
+
+    pub(crate) fn end(&mut self, extend: bool) -> bool {
+        self.move_caret(self.text.len(), extend)
+    }
+
+    pub(crate) fn select_all(&mut self) -> bool {
+        self.anchor = 0;
+        self.active = self.text.len();
+        self.goal_x = None;
+        false
+    }
+
+    /// Selects the word containing `offset`, or the character there if it isn't part of a word.
+    pub(crate) fn select_word_at(&mut self, offset: usize) {
+        let mut offset = floor_boundary(&self.text, offset);
+        if offset == self.text.len() {
+            offset = prev_boundary(&self.text, offset);
+        }
+        let mut start = offset;
+        let mut end = next_boundary(&self.text, offset);
+        if self.text[offset..end].chars().any(is_word) {
+            while let Some(c) = self.text[..start]
+                .chars()
+                .next_back()
+                .filter(|c| is_word(*c))
+            {
+                start -= c.len_utf8();
+            }
+            while let Some(c) = self.text[end..].chars().next().filter(|c| is_word(*c)) {
+                end += c.len_utf8();
+            }
+        }
+        self.anchor = start;
+        self.active = end;
+        self.goal_x = None;
+    }
+
+    /// Selects the line containing `offset`, without its line break.
+    pub(crate) fn select_line_at(&mut self, offset: usize, geometry: &impl LineGeometry) {
+        if geometry.line_count() == 0 {
+            return;
+        }
+        let line = line_of(geometry, offset);
+        self.anchor = geometry.line_range(line).start;
+        self.active = geometry.line_end(line);
+        self.goal_x = None;
+    }
+
+    /// Replaces the selection with `text`, placing the caret behind it.
+    pub(crate) fn insert(&mut self, text: &str) -> bool {
+        let selection = self.selection();
+        if selection.is_empty() && text.is_empty() {
+            return false;
+        }
+        self.text.replace_range(selection.clone(), text);
+        self.move_caret(selection.start + text.len(), false);
+        true
+    }
+
+    pub(crate) fn backspace(&mut self) -> bool {
+        if self.selection().is_empty() {
+            self.anchor = prev_boundary(&self.text, self.active);
+        }
+        self.insert("")
+    }
+
+    pub(crate) fn delete(&mut self) -> bool {
+        if self.selection().is_empty() {
+            self.anchor = next_boundary(&self.text, self.active);
+        }
+        self.insert("")
+    }
+
+    /// Removes and returns the selected text, `None` if nothing is selected.
+    pub(crate) fn cut(&mut self) -> Option<String> {
+        let cut = self.selected_text().to_string();
+        if cut.is_empty() {
+            return None;
+        }
+        self.insert("");
+        Some(cut)
+    }
+
+    /// Moves the caret `delta` lines down, or up for negative values, to the position closest
+    /// to the goal column. Beyond the first and last line the caret moves to the start and end
+    /// of the text.
+    pub(crate) fn move_lines(
+        &mut self,
+        delta: isize,
+        extend: bool,
+        geometry: &impl LineGeometry,
+    ) -> bool {
+        if geometry.line_count() == 0 {
+            return false;
+        }
+        let goal_x = self.goal_x.unwrap_or_else(|| geometry.x_of(self.active));
+        let target = line_of(geometry, self.active) as isize + delta;
+        let offset = if target < 0 {
+            0
+        } else if target as usize >= geometry.line_count() {
+            self.text.len()
+        } else {
+            geometry.offset_at(target as usize, goal_x)
+        };
+        self.move_caret(offset, extend);
+        self.goal_x = Some(goal_x);
+        false
+    }
+
+    /// Moves the caret to the start of its line.
+    pub(crate) fn line_home(&mut self, extend: bool, geometry: &impl LineGeometry) -> bool {
+        if geometry.line_count() == 0 {
+            return false;
+        }
+        let line = geometry.line_range(line_of(geometry, self.active));
+        self.move_caret(line.start, extend)
+    }
+
+    /// Moves the caret to the end of its line, before the line break.
+    pub(crate) fn line_end(&mut self, extend: bool, geometry: &impl LineGeometry) -> bool {
+        if geometry.line_count() == 0 {
+            return false;
+        }
+        let end = geometry.line_end(line_of(geometry, self.active));
+        self.move_caret(end, extend)
+    }
+
+    /// Applies a key press which edits the text or moves the caret within a line, typing is
+    /// left to the [`Text`](super::Event::Text) following it.
+    ///
+    /// Returns `None` for keys which are left to the widget, otherwise whether the text was
+    /// edited.
+    pub(crate) fn edit_key(&mut self, cx: &mut EventCx, event: &KeyEvent) -> Option<bool> {
+        let extend = event.mods.shift();
+        let edited = match &event.key {
+            KbKey::ArrowLeft if event.mods.ctrl() => self.word_left(extend),
+            KbKey::ArrowRight if event.mods.ctrl() => self.word_right(extend),
+            KbKey::ArrowLeft => self.left(extend),
+            KbKey::ArrowRight => self.right(extend),
+            KbKey::Home => self.home(extend),
+            KbKey::End => self.end(extend),
+            KbKey::Backspace => self.backspace(),
+            KbKey::Delete => self.delete(),
+            // Holding a shortcut doesn't repeat it.
+            KbKey::Character(_) if is_command(event.mods) && event.repeat => false,
+            KbKey::Character(c) if is_command(event.mods) => match c.as_str() {
+                "a" => self.select_all(),
+                "c" => {
+                    cx.clipboard().set_text(self.selected_text());
+                    false
+                }
+                "x" => match self.cut() {
+                    Some(cut) => {
+                        cx.clipboard().set_text(&cut);
+                        true
+                    }
+                    None => false,
+                },
+                "v" => match cx.clipboard().get_text() {
+                    Some(pasted) => self.insert(&pasted.replace("\r\n", "\n")),
+                    None => false,
+                },
+                _ => return None,
+            },
+            // Typed characters arrive as `Event::Text`.
+            _ => return None,
+        };
+        Some(edited)
+    }
+}
+
+/// Whether the platform's modifier for shortcuts like copy and paste is held.
+pub(crate) fn is_command(mods: Modifiers) -> bool {
+    if cfg!(target_os = "macos") {
+        mods.meta()
+    } else {
+        mods.ctrl()
+    }
+}
+
+/// The visual lines of a laid out text, which vertical caret movement steps through.
+pub(crate) trait LineGeometry {
+    fn line_count(&self) -> usize;
+
+    /// The byte range of `line`, including a line break at its end.
+    fn line_range(&self, line: usize) -> Range<usize>;
+
+    /// The end of the text of `line`, before its line break.
+    fn line_end(&self, line: usize) -> usize;
+
+    /// The horizontal position of the caret at `offset`.
+    fn x_of(&self, offset: usize) -> f64;
+
+    /// The caret offset on `line` closest to the horizontal position `x`.
+    fn offset_at(&self, line: usize, x: f64) -> usize;
+}
+
+/// The line containing the caret at `offset`, the later one where a line wraps at `offset`.
+pub(crate) fn line_of(geometry: &impl LineGeometry, offset: usize) -> usize {
+    (0..geometry.line_count())
+        .rfind(|line| geometry.line_range(*line).start <= offset)
+        .unwrap_or(0)
+}
+
+/// The end of the text of `line`, before its line break.
+pub(crate) fn content_end(text: &str, line: Range<usize>) -> usize {
+    let content = text[line.clone()].trim_end_matches(['\n', '\r']);
+    line.start + content.len()
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Describes a run of `text` as an inline text box for assistive technology.
+///
+/// The character positions and widths come from `x_of`, the horizontal position of a byte offset
+/// in the run in widget coordinates, which is read from the layout the widget paints. `end_x` is
+/// where the last character ends.
+pub(crate) fn inline_text_box(
+    text: &str,
+    x_of: impl Fn(usize) -> f64,
+    end_x: f64,
+) -> accesskit::NodeBuilder {
+    let mut builder = accesskit::NodeBuilder::new(accesskit::Role::InlineTextBox);
+    builder.set_value(text);
+    builder.set_text_direction(accesskit::TextDirection::LeftToRight);
+    let lengths = text.chars().map(|c| c.len_utf8() as u8);
+    builder.set_character_lengths(lengths.collect::<Vec<_>>());
+    let positions: Vec<f32> = text.char_indices().map(|(i, _)| x_of(i) as f32).collect();
+    let ends = positions.iter().skip(1).copied().chain([end_x as f32]);
+    let widths = positions
+        .iter()
+        .zip(ends)
+        .map(|(x, end)| (end - x).max(0.0));
+    builder.set_character_widths(widths.collect::<Vec<_>>());
+    builder.set_character_positions(positions);
+    builder.set_word_lengths(word_lengths(text));
+    builder
+}
+
+/// The lengths in chars of the words of `text` for assistive technology, which are the same
+/// words as for word movement.
+///
+/// Every word takes the characters up to the next word with it, only leading characters which
+/// are not part of a word form one of their own.
+fn word_lengths(text: &str) -> Vec<u8> {
+    let mut lengths = vec![];
+    let mut length = 0u8;
+    let mut prev_is_word = false;
+    for c in text.chars() {
+        let is_word = is_word(c);
+        if length == u8::MAX || (length > 0 && is_word && !prev_is_word) {
+            lengths.push(length);
+            length = 0;
+        }
+        length += 1;
+        prev_is_word = is_word;
+    }
+    if length > 0 {
+        lengths.push(length);
+    }
+    lengths
+}
+
+/// The largest char boundary not after `offset`.
+pub(crate) fn floor_boundary(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+pub(crate) fn prev_boundary(text: &str, offset: usize) -> usize {
+    text[..offset]
+        .char_indices()
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+pub(crate) fn next_boundary(text: &str, offset: usize) -> usize {
+    text[offset..]
+        .chars()
+        .next()
+        .map_or(offset, |c| offset + c.len_utf8())
+}
+
+/// The start of the word before `offset`, skipping anything between them.
+fn word_start_before(text: &str, offset: usize) -> usize {
+    let mut chars = text[..offset].char_indices().rev().peekable();
+    while chars.next_if(|(_, c)| !is_word(*c)).is_some() {}
+    let mut start = chars.peek().map_or(0, |(i, _)| *i);
+    for (i, c) in chars {
+        if !is_word(c) {
+            break;
+        }
+        start = i;
+    }
+    start
+}
+
+/// The end of the word after `offset`, skipping anything between them.
+fn word_end_after(text: &str, offset: usize) -> usize {
+    let mut chars = text[offset..].char_indices().peekable();
+    while chars.next_if(|(_, c)| !is_word(*c)).is_some() {}
+    let rest = text.len() - offset;
+    let end = chars.find(|(_, c)| !is_word(*c)).map_or(rest, |(i, _)| i);
+    offset + end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    /// "a", "é" (2 bytes), "👍" (4 bytes) and "b".
+    const MIXED: &str = "aé👍b";
+
+    fn buffer(text: &str, caret: usize) -> TextEditing {
+        let mut buffer = TextEditing::new(text.to_string());
+        buffer.move_caret(caret, false);
+        buffer
+    }
+
+    #[test]
+    fn char_movement_steps_over_multi_byte_chars() {
+        let mut buffer = buffer(MIXED, 0);
+        let mut offsets = vec![];
+        for _ in 0..5 {
+            buffer.right(false);
+            offsets.push(buffer.active);
+        }
+        assert_eq!(offsets, [1, 3, 7, 8, 8]);
+
+        offsets.clear();
+        for _ in 0..5 {
+            buffer.left(false);
+            offsets.push(buffer.active);
+        }
+        assert_eq!(offsets, [7, 3, 1, 0, 0]);
+    }
+
+    #[test]
+    fn word_movement() {
+        // "héllo" is 6 bytes, "wörld" starts at 7 and ends at 13.
+        let text = "héllo wörld, über";
+        let mut buffer = buffer(text, 0);
+        buffer.word_right(false);
+        assert_eq!(buffer.active, 6);
+        buffer.word_right(false);
+        assert_eq!(buffer.active, 13);
+        buffer.word_right(false);
+        assert_eq!(buffer.active, text.len());
+        buffer.word_left(false);
+        assert_eq!(buffer.active, 15);
+        buffer.word_left(false);
+        assert_eq!(buffer.active, 7);
+        buffer.word_left(true);
+        assert_eq!(buffer.selection(), 0..7);
+    }
+
+    #[test]
+    fn shift_selection_extends_and_plain_arrows_collapse() {
+        let mut buffer = buffer(MIXED, 1);
+        buffer.right(true);
+        buffer.right(true);
+        assert_eq!(buffer.selection(), 1..7);
+        assert_eq!(buffer.selected_text(), "é👍");
+
+        buffer.left(false);
+        assert_eq!(buffer.selection(), 1..1);
+
+        buffer.end(true);
+        buffer.home(true);
+        assert_eq!(buffer.selection(), 0..1);
+        assert_eq!(buffer.anchor, 1);
+    }
+
+    #[test]
+    fn editing_removes_whole_chars() {
+        let mut buffer = buffer(MIXED, 7);
+        assert!(buffer.backspace());
+        assert_eq!(buffer.text, "aéb");
+        assert_eq!(buffer.active, 3);
+
+        buffer.move_caret(1, false);
+        assert!(buffer.delete());
+        assert_eq!(buffer.text, "ab");
+        assert_eq!(buffer.active, 1);
+
+        buffer.home(false);
+        assert!(!buffer.backspace());
+        buffer.end(false);
+        assert!(!buffer.delete());
+        assert_eq!(buffer.text, "ab");
+    }
+
+    #[test]
+    fn insert_replaces_selection() {
+        let mut buffer = buffer(MIXED, 1);
+        buffer.move_caret(7, true);
+        assert!(buffer.insert("ß"));
+        assert_eq!(buffer.text, "aßb");
+        assert_eq!(buffer.selection(), 3..3);
+        assert!(!buffer.insert(""));
+    }
+
+    #[test]
+    fn cut_and_select_word() {
+        let mut buffer = buffer("héllo wörld", 0);
+        assert_eq!(buffer.cut(), None);
+
+        // An offset inside "ö" still selects the whole word.
+        buffer.select_word_at(9);
+        assert_eq!(buffer.selected_text(), "wörld");
+        assert_eq!(buffer.cut().as_deref(), Some("wörld"));
+        assert_eq!(buffer.text, "héllo ");
+
+        buffer.select_word_at(6);
+        assert_eq!(buffer.selected_text(), " ");
+        buffer.select_all();
+        assert_eq!(buffer.selected_text(), "héllo ");
+    }
+
+    #[test]
+    fn inline_text_box_reports_words_and_char_geometry() {
+        // Every byte is one unit wide.
+        let builder = inline_text_box("  héllo, wörld", |offset| offset as f64, 16.0);
+        let node = builder.build(&mut accesskit::NodeClassSet::new());
+        assert_eq!(node.word_lengths(), [2, 7, 5]);
+        assert_eq!(node.character_lengths().len(), 14);
+        assert_eq!(&node.character_positions().unwrap()[2..5], [2.0, 3.0, 5.0]);
+        assert_eq!(&node.character_widths().unwrap()[2..5], [1.0, 2.0, 1.0]);
+        assert_eq!(node.character_widths().unwrap()[13], 1.0);
+
+        let long = "a".repeat(300);
+        assert_eq!(word_lengths(&long), [255, 45]);
+    }
+
+    #[test]
+    fn set_text_clamps_to_char_boundaries() {
+        let mut buffer = buffer("👍👍", 8);
+        buffer.set_text("ab👍".into());
+        assert_eq!(buffer.selection(), 6..6);
+
+        let mut buffer = buffer("abcd", 0);
+        buffer.move_caret(3, true);
+        buffer.set_text("é👍".into());
+        // Offset 3 is inside "👍", the selection shrinks to the boundary before it.
+        assert_eq!((buffer.anchor, buffer.active), (0, 2));
+    }
+    /// Lines split at line breaks, with every char one unit wide.
+    struct Monospace {
+        text: String,
+        lines: Vec<Range<usize>>,
+    }
+
+    impl Monospace {
+        fn new(text: &str) -> Self {
+            let mut start = 0;
+            let lines = text
+                .split_inclusive('\n')
+                .map(|line| {
+                    start += line.len();
+                    start - line.len()..start
+                })
+                .collect();
+            Monospace {
+                text: text.to_string(),
+                lines,
+            }
+        }
+    }
+
+    impl LineGeometry for Monospace {
+        fn line_count(&self) -> usize {
+            self.lines.len()
+        }
+
+        fn line_range(&self, line: usize) -> Range<usize> {
+            self.lines[line].clone()
+        }
+
+        fn line_end(&self, line: usize) -> usize {
+            content_end(&self.text, self.lines[line].clone())
+        }
+
+        fn x_of(&self, offset: usize) -> f64 {
+            let start = self.lines[line_of(self, offset)].start;
+            self.text[start..offset].chars().count() as f64
+        }
+
+        fn offset_at(&self, line: usize, x: f64) -> usize {
+            let start = self.lines[line].start;
+            let end = self.line_end(line);
+            self.text[start..end]
+                .char_indices()
+                .nth(x as usize)
+                .map_or(end, |(i, _)| start + i)
+        }
+    }
+
+    #[test]
+    fn vertical_movement_keeps_goal_column() {
+        // Lines of 9, 2 and 12 chars.
+        let text = "long line\nab\nlonger lines";
+        let lines = Monospace::new(text);
+        let mut buffer = buffer(text, 7);
+
+        buffer.move_lines(1, false, &lines);
+        assert_eq!(buffer.active, 12);
+        buffer.move_lines(1, false, &lines);
+        assert_eq!(buffer.active, 20);
+        buffer.move_lines(-2, true, &lines);
+        assert_eq!(buffer.selection(), 7..20);
+
+        // Horizontal movement sets a new goal.
+        buffer.move_lines(1, false, &lines);
+        assert_eq!(buffer.active, 12);
+        buffer.left(false);
+        buffer.move_lines(1, false, &lines);
+        assert_eq!(buffer.active, 14);
+
+        buffer.move_lines(1, false, &lines);
+        assert_eq!(buffer.active, text.len());
+        buffer.move_lines(-5, false, &lines);
+        assert_eq!(buffer.active, 0);
+    }
+
+    #[test]
+    fn line_home_and_end_stop_at_line_breaks() {
+        let text = "long line\nab\nlonger lines";
+        let lines = Monospace::new(text);
+        let mut buffer = buffer(text, 11);
+        buffer.line_end(false, &lines);
+        assert_eq!(buffer.active, 12);
+        buffer.line_home(true, &lines);
+        assert_eq!(buffer.selection(), 10..12);
+        assert_eq!(line_of(&lines, text.len()), 2);
+    }
+}
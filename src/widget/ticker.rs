@@ -0,0 +1,230 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, Instant};
+
+use vello::{kurbo::Size, SceneBuilder};
+
+use crate::{IdPath, Message};
+
+use super::{
+    AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx,
+    PaintCx, TimerToken, UpdateCx, Widget,
+};
+
+/// An invisible widget sending a message after a delay, once or repeatedly.
+///
+/// Repeated ticks are scheduled from the deadline of the previous one rather than from when it
+/// was delivered, so late deliveries don't add up. Ticks which were missed entirely are skipped.
+/// The timer is armed in the first update and re-armed from the current time when the period
+/// changes. When the widget leaves the tree, its timer is cancelled with it.
+pub struct Ticker {
+    id_path: IdPath,
+    period: Duration,
+    repeat: bool,
+    /// The running timer and its deadline.
+    timer: Option<(TimerToken, Instant)>,
+    needs_arm: bool,
+}
+
+impl Ticker {
+    /// The shortest period, shorter ones are raised to it.
+    pub const MIN_PERIOD: Duration = Duration::from_millis(1);
+
+    pub fn new(id_path: &IdPath, period: Duration, repeat: bool) -> Self {
+        Ticker {
+            id_path: id_path.clone(),
+            period: period.max(Self::MIN_PERIOD),
+            repeat,
+            timer: None,
+            needs_arm: true,
+        }
+    }
+
+    /// Changes the period, the next tick is one period after the following update.
+    ///
+    /// A single tick which was already sent isn't sent again.
+    pub fn set_period(&mut self, period: Duration) -> ChangeFlags {
+        let period = period.max(Self::MIN_PERIOD);
+        if self.period == period {
+            return ChangeFlags::empty();
+        }
+        self.period = period;
+        if self.timer.is_none() && !self.repeat {
+            return ChangeFlags::empty();
+        }
+        self.needs_arm = true;
+        ChangeFlags::UPDATE
+    }
+
+    /// Whether a tick is pending.
+    pub fn is_armed(&self) -> bool {
+        self.timer.is_some() || self.needs_arm
+    }
+}
+
+impl Widget for Ticker {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        let Event::Timer(token) = event else {
+            return;
+        };
+        let Some((timer, deadline)) = self.timer else {
+            return;
+        };
+        if timer != *token {
+            return;
+        }
+        self.timer = None;
+        cx.add_message(Message::new(self.id_path.clone(), ()));
+        if self.repeat {
+            let now = cx.now();
+            let mut next = deadline + self.period;
+            while next <= now {
+                next += self.period;
+            }
+            self.timer = Some((cx.request_timer(next - now), next));
+        }
+    }
+
+    fn lifecycle(&mut self, _cx: &mut LifeCycleCx, _event: &LifeCycle) {}
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        if std::mem::take(&mut self.needs_arm) {
+            if let Some((timer, _)) = self.timer.take() {
+                cx.cancel_timer(timer);
+            }
+            let deadline = cx.now() + self.period;
+            self.timer = Some((cx.request_timer(self.period), deadline));
+        }
+    }
+
+    fn layout(&mut self, _cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        bc.min()
+    }
+
+    fn accessibility(&mut self, _cx: &mut AccessCx) {}
+
+    fn paint(&mut self, _cx: &mut PaintCx, _builder: &mut SceneBuilder) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::Harness;
+    use crate::widget::{LinearLayout, Pod};
+    use crate::Axis;
+    use test_log::test;
+    use xilem_core::Id;
+
+    const PERIOD: Duration = Duration::from_millis(10);
+
+    fn setup(harness: &mut Harness, repeat: bool) -> Pod {
+        let ticker = Pod::new(Ticker::new(&vec![Id::next()], PERIOD, repeat));
+        let mut root = Pod::new(LinearLayout::new(vec![ticker], 0.0, Axis::Vertical));
+        harness.layout(&mut root, &BoxConstraints::tight(Size::new(100.0, 100.0)));
+        harness.update(&mut root);
+        root
+    }
+
+    fn ticker(root: &mut Pod) -> &mut Ticker {
+        let layout = root.downcast_mut::<LinearLayout>().unwrap();
+        layout.children[0].downcast_mut().unwrap()
+    }
+
+    fn tick_count(harness: &mut Harness, root: &mut Pod, delta: Duration) -> usize {
+        harness.advance_time(root, delta);
+        harness.take_messages().len()
+    }
+
+    #[test]
+    fn ticks_keep_to_the_schedule() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, true);
+        assert_eq!(
+            tick_count(&mut harness, &mut root, Duration::from_millis(9)),
+            0
+        );
+        // Delivered 5ms late, the next tick is still due at 20ms.
+        assert_eq!(
+            tick_count(&mut harness, &mut root, Duration::from_millis(6)),
+            1
+        );
+        assert_eq!(
+            tick_count(&mut harness, &mut root, Duration::from_millis(4)),
+            0
+        );
+        assert_eq!(
+            tick_count(&mut harness, &mut root, Duration::from_millis(1)),
+            1
+        );
+        // Missed ticks are skipped instead of delivered in a burst.
+        assert_eq!(
+            tick_count(&mut harness, &mut root, Duration::from_millis(35)),
+            1
+        );
+        assert_eq!(
+            tick_count(&mut harness, &mut root, Duration::from_millis(5)),
+            1
+        );
+    }
+
+    #[test]
+    fn single_tick_is_sent_once() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, false);
+        assert_eq!(tick_count(&mut harness, &mut root, PERIOD), 1);
+        assert!(!ticker(&mut root).is_armed());
+        assert_eq!(tick_count(&mut harness, &mut root, PERIOD * 5), 0);
+        assert!(ticker(&mut root).set_period(PERIOD * 2).is_empty());
+    }
+
+    #[test]
+    fn period_change_rearms_from_now() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, true);
+        harness.advance_time(&mut root, Duration::from_millis(6));
+        let child = &mut root.downcast_mut::<LinearLayout>().unwrap().children[0];
+        let changes = child
+            .downcast_mut::<Ticker>()
+            .unwrap()
+            .set_period(PERIOD * 2);
+        let changes = child.mark(changes);
+        let _ = root.mark(changes);
+        harness.update(&mut root);
+        assert_eq!(
+            tick_count(&mut harness, &mut root, Duration::from_millis(19)),
+            0
+        );
+        assert_eq!(
+            tick_count(&mut harness, &mut root, Duration::from_millis(1)),
+            1
+        );
+        assert_eq!(tick_count(&mut harness, &mut root, PERIOD * 2), 1);
+    }
+
+    #[test]
+    fn removal_cancels_the_timer() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, true);
+        assert!(harness.window_state.timers.next_deadline().is_some());
+        root.downcast_mut::<LinearLayout>()
+            .unwrap()
+            .children
+            .clear();
+        let _ = root.mark(ChangeFlags::TREE);
+        harness.layout(&mut root, &BoxConstraints::tight(Size::new(100.0, 100.0)));
+        assert!(harness.window_state.timers.next_deadline().is_none());
+        assert_eq!(tick_count(&mut harness, &mut root, PERIOD * 3), 0);
+    }
+}
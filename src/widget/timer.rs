@@ -0,0 +1,182 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Timers requested by widgets.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::id::Id;
+
+/// Identifies a timer requested through `request_timer`.
+///
+/// When the timer elapses, the requesting widget receives an [`Event::Timer`] with this token.
+///
+/// [`Event::Timer`]: super::Event::Timer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerToken {
+    serial: u64,
+    pub(crate) widget: Id,
+}
+
+/// The clock the timers of a window are measured with.
+///
+/// The app reads the system clock, tests use a [`MockTimerSource`] to control time.
+pub trait TimerSource {
+    fn now(&self) -> Instant;
+}
+
+/// A [`TimerSource`] reading [`Instant::now`].
+pub struct SystemTimerSource;
+
+impl TimerSource for SystemTimerSource {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A [`TimerSource`] which only moves when it is told to.
+///
+/// Clones share the same clock, so a test can keep one to advance the time of the app or
+/// harness it handed the other to.
+#[derive(Clone)]
+pub struct MockTimerSource {
+    now: Rc<Cell<Instant>>,
+}
+
+impl MockTimerSource {
+    /// A clock standing at the current time.
+    pub fn new() -> Self {
+        MockTimerSource {
+            now: Rc::new(Cell::new(Instant::now())),
+        }
+    }
+
+    /// Moves the clock forward by `delta`.
+    pub fn advance(&self, delta: Duration) {
+        self.now.set(self.now.get() + delta);
+    }
+}
+
+impl Default for MockTimerSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimerSource for MockTimerSource {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
+
+/// The pending timers of a window and the clock they are measured with.
+///
+/// The clock only moves when the owner sets it, tests use this to control time.
+pub(crate) struct TimerQueue {
+    now: Instant,
+    next_serial: u64,
+    pending: Vec<(Instant, TimerToken)>,
+}
+
+impl TimerQueue {
+    pub(crate) fn new(now: Instant) -> Self {
+        TimerQueue {
+            now,
+            next_serial: 0,
+            pending: vec![],
+        }
+    }
+
+    pub(crate) fn now(&self) -> Instant {
+        self.now
+    }
+
+    pub(crate) fn set_now(&mut self, now: Instant) {
+        self.now = self.now.max(now);
+    }
+
+    pub(crate) fn request(&mut self, widget: Id, delay: Duration) -> TimerToken {
+        self.next_serial += 1;
+        let token = TimerToken {
+            serial: self.next_serial,
+            widget,
+        };
+        self.pending.push((self.now + delay, token));
+        token
+    }
+
+    pub(crate) fn cancel(&mut self, token: TimerToken) {
+        self.pending.retain(|(_, pending)| *pending != token);
+    }
+
+    /// Cancels the timers of the widgets for which `keep` returns `false`.
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(Id) -> bool) {
+        self.pending.retain(|(_, token)| keep(token.widget));
+    }
+
+    /// Removes and returns the earliest timer which is due, timers with the same deadline
+    /// elapse in the order they were requested.
+    pub(crate) fn pop_due(&mut self) -> Option<TimerToken> {
+        let (index, _) = self
+            .pending
+            .iter()
+            .enumerate()
+            .filter(|(_, (deadline, _))| *deadline <= self.now)
+            .min_by_key(|(_, (deadline, token))| (*deadline, token.serial))?;
+        Some(self.pending.remove(index).1)
+    }
+
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        self.pending.iter().map(|(deadline, _)| *deadline).min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn timers_elapse_in_deadline_order() {
+        let start = Instant::now();
+        let mut queue = TimerQueue::new(start);
+        let widget = Id::next();
+        let late = queue.request(widget, Duration::from_millis(20));
+        let early = queue.request(widget, Duration::from_millis(10));
+        let cancelled = queue.request(widget, Duration::from_millis(5));
+        queue.cancel(cancelled);
+
+        assert_eq!(queue.pop_due(), None);
+        assert_eq!(
+            queue.next_deadline(),
+            Some(start + Duration::from_millis(10))
+        );
+
+        queue.set_now(start + Duration::from_millis(30));
+        assert_eq!(queue.pop_due(), Some(early));
+        assert_eq!(queue.pop_due(), Some(late));
+        assert_eq!(queue.pop_due(), None);
+    }
+
+    #[test]
+    fn mock_clones_share_the_clock() {
+        let source = MockTimerSource::new();
+        let start = source.now();
+        let clone = source.clone();
+        clone.advance(Duration::from_millis(10));
+        assert_eq!(source.now(), start + Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,691 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, Instant};
+
+use vello::{
+    kurbo::{BezPath, Insets, Point, Rect, Size},
+    peniko::Color,
+    SceneBuilder,
+};
+
+use crate::{IdPath, Message};
+
+use super::{
+    piet_scene_helpers, AccessCx, BoxConstraints, ChangeFlags, Corner, Event, EventCx, LayoutCx,
+    LifeCycle, LifeCycleCx, OverlayHandle, PaintCx, Placement, Pod, TimerToken, UpdateCx, Widget,
+};
+
+/// The messages [`Toasts`] send to the view at their id path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToastMessage {
+    /// The time of the toast with this id ran out.
+    Expired(u64),
+    /// The user closed the toast with this id.
+    Closed(u64),
+}
+
+/// Shows transient notifications stacked in a corner of the window, above a child widget.
+///
+/// Toasts are identified by an id chosen by the app. At most [`max_visible`] toasts are shown
+/// at once, starting with the first pushed nearest to the corner, the others wait in a queue.
+/// Each shown toast counts down its duration, paused while the pointer rests on it, and sends
+/// [`ToastMessage::Expired`] when it runs out. Its close button sends [`ToastMessage::Closed`].
+/// Either way the toast stays until it is [`remove`]d, then it slides out and the stack closes
+/// the gap.
+///
+/// [`max_visible`]: Toasts::set_max_visible
+/// [`remove`]: Toasts::remove
+pub struct Toasts {
+    id_path: IdPath,
+    child: Pod,
+    stack: OverlayHandle,
+    is_shown: bool,
+}
+
+/// The width of a toast, unless the window is narrower.
+const TOAST_WIDTH: f64 = 320.0;
+const TOAST_INSETS: Insets = Insets::uniform_xy(12.0, 10.0);
+const CLOSE_SIZE: f64 = 16.0;
+/// The space between toasts.
+const GAP: f64 = 8.0;
+/// The distance of the stack from the window edges.
+const EDGE_INSET: f64 = 16.0;
+/// The time a toast takes to slide in or out, in seconds.
+const SLIDE_DURATION: f64 = 0.2;
+
+impl Toasts {
+    /// How long a toast is shown if not given otherwise.
+    pub const DEFAULT_DURATION: Duration = Duration::from_secs(4);
+    pub const DEFAULT_MAX_VISIBLE: usize = 3;
+
+    pub fn new(id_path: &IdPath, child: Pod, corner: Corner, max_visible: usize) -> Toasts {
+        let stack = ToastStack {
+            entries: vec![],
+            corner,
+            max_visible,
+        };
+        Toasts {
+            id_path: id_path.clone(),
+            child,
+            stack: OverlayHandle::new(stack, Placement::Corner(corner, EDGE_INSET)),
+            is_shown: false,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    /// Queues a toast showing `content` for `duration`, after the toasts pushed before.
+    pub fn push(&mut self, id: u64, content: Pod, duration: Duration) -> ChangeFlags {
+        let frame = ToastFrame {
+            id_path: self.id_path.clone(),
+            id,
+            content,
+        };
+        self.change_stack(|stack| {
+            stack.entries.push(ToastEntry {
+                id,
+                frame: Pod::new(frame),
+                remaining: duration,
+                timer: None,
+                is_paused: false,
+                is_leaving: false,
+                presence: 0.0,
+            });
+            ChangeFlags::tree_structure() | ChangeFlags::UPDATE | ChangeFlags::LAYOUT
+        })
+    }
+
+    /// Runs `f` with the pod of the content of the toast with `id` and marks the changes it
+    /// returns.
+    pub fn update_toast(
+        &mut self,
+        id: u64,
+        f: impl FnOnce(&mut Pod) -> ChangeFlags,
+    ) -> ChangeFlags {
+        self.change_stack(|stack| match stack.entry_mut(id) {
+            Some(entry) => {
+                let frame = &mut entry.frame;
+                let content = &mut frame.expect_mut::<ToastFrame>().content;
+                let changes = f(content);
+                let changes = content.mark(changes);
+                frame.mark(changes)
+            }
+            None => ChangeFlags::empty(),
+        })
+    }
+
+    /// Removes the toast with `id`, which slides out.
+    pub fn remove(&mut self, id: u64) -> ChangeFlags {
+        self.change_stack(|stack| match stack.entry_mut(id) {
+            Some(entry) => {
+                entry.is_leaving = true;
+                // A pending timer is ignored from now on.
+                entry.timer = None;
+                ChangeFlags::UPDATE | ChangeFlags::LAYOUT | ChangeFlags::ACCESSIBILITY
+            }
+            None => ChangeFlags::empty(),
+        })
+    }
+
+    pub fn set_corner(&mut self, corner: Corner) -> ChangeFlags {
+        self.stack
+            .set_placement(Placement::Corner(corner, EDGE_INSET));
+        self.change_stack(|stack| {
+            stack.corner = corner;
+            ChangeFlags::LAYOUT | ChangeFlags::PAINT
+        }) | ChangeFlags::LAYOUT
+    }
+
+    /// Sets how many toasts are shown at once.
+    pub fn set_max_visible(&mut self, max_visible: usize) -> ChangeFlags {
+        self.change_stack(|stack| {
+            stack.max_visible = max_visible;
+            ChangeFlags::UPDATE | ChangeFlags::LAYOUT | ChangeFlags::ACCESSIBILITY
+        })
+    }
+
+    /// The ids of the shown toasts which are not leaving, in stack order.
+    pub fn visible(&self) -> Vec<u64> {
+        self.stack.with_pod(|pod| {
+            let stack = pod.expect_mut::<ToastStack>();
+            let visibility = stack.visibility();
+            stack
+                .entries
+                .iter()
+                .zip(visibility)
+                .filter(|(entry, is_visible)| *is_visible && !entry.is_leaving)
+                .map(|(entry, _)| entry.id)
+                .collect()
+        })
+    }
+
+    /// Runs `f` with the stack and marks the changes it returns on the stack.
+    fn change_stack(&self, f: impl FnOnce(&mut ToastStack) -> ChangeFlags) -> ChangeFlags {
+        self.stack.with_pod(|pod| {
+            let changes = f(pod.expect_mut());
+            pod.mark(changes)
+        })
+    }
+}
+
+impl Widget for Toasts {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.child.event(cx, event);
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.child.update(cx);
+        if !self.is_shown {
+            // An empty stack has no size, so it stays shown.
+            cx.show_overlay(&self.stack);
+            self.is_shown = true;
+        }
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+        builder.set_children(vec![self.child.id().into()]);
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+/// The toasts in the overlay of [`Toasts`], including the queued ones.
+struct ToastStack {
+    entries: Vec<ToastEntry>,
+    corner: Corner,
+    max_visible: usize,
+}
+
+struct ToastEntry {
+    id: u64,
+    /// The [`ToastFrame`] around the content.
+    frame: Pod,
+    /// The time left until the toast expires, counted while it is shown and not hovered.
+    remaining: Duration,
+    /// The running timer and when it was started.
+    timer: Option<(TimerToken, Instant)>,
+    is_paused: bool,
+    is_leaving: bool,
+    /// How far the toast has slid in, from 0 to 1.
+    presence: f64,
+}
+
+impl ToastEntry {
+    fn start_timer(&mut self, timer: impl FnOnce(Duration) -> TimerToken, now: Instant) {
+        if self.timer.is_none() && !self.is_paused && !self.is_leaving && !self.remaining.is_zero()
+        {
+            self.timer = Some((timer(self.remaining), now));
+        }
+    }
+}
+
+impl ToastStack {
+    fn entry_mut(&mut self, id: u64) -> Option<&mut ToastEntry> {
+        // A toast may be pushed again while its previous entry is still leaving.
+        self.entries
+            .iter_mut()
+            .find(|entry| entry.id == id && !entry.is_leaving)
+    }
+
+    /// Whether each entry is shown: leaving entries until they are gone and the first
+    /// `max_visible` of the others.
+    fn visibility(&self) -> Vec<bool> {
+        let mut staying = 0;
+        self.entries
+            .iter()
+            .map(|entry| {
+                if entry.is_leaving {
+                    entry.presence > 0.0
+                } else {
+                    staying += 1;
+                    staying <= self.max_visible
+                }
+            })
+            .collect()
+    }
+
+    fn is_animating(&self) -> bool {
+        self.entries
+            .iter()
+            .zip(self.visibility())
+            .any(|(entry, is_visible)| {
+                let target = if is_visible && !entry.is_leaving {
+                    1.0
+                } else {
+                    0.0
+                };
+                entry.presence != target
+            })
+    }
+}
+
+impl Widget for ToastStack {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        if let Event::Timer(token) = event {
+            let expired = self
+                .entries
+                .iter_mut()
+                .find(|entry| entry.timer.is_some_and(|(timer, _)| timer == *token));
+            if let Some(entry) = expired {
+                entry.timer = None;
+                entry.remaining = Duration::ZERO;
+                let message = ToastMessage::Expired(entry.id);
+                let frame = entry.frame.expect_mut::<ToastFrame>();
+                cx.add_message(Message::new(frame.id_path.clone(), message));
+            }
+        }
+
+        let visibility = self.visibility();
+        for (entry, is_visible) in self.entries.iter_mut().zip(visibility) {
+            if !is_visible {
+                continue;
+            }
+            entry.frame.event(cx, event);
+            // The countdown pauses while the pointer rests on the toast.
+            let is_hot = entry.frame.is_hot();
+            if is_hot && !entry.is_paused {
+                entry.is_paused = true;
+                if let Some((timer, started)) = entry.timer.take() {
+                    cx.cancel_timer(timer);
+                    let elapsed = cx.now().duration_since(started);
+                    entry.remaining = entry.remaining.saturating_sub(elapsed);
+                }
+            } else if !is_hot && entry.is_paused {
+                entry.is_paused = false;
+                let now = cx.now();
+                entry.start_timer(|delay| cx.request_timer(delay), now);
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        for entry in &mut self.entries {
+            entry.frame.lifecycle(cx, event);
+        }
+        if let LifeCycle::AnimFrame(interval) = event {
+            let step = *interval as f64 * 1e-9 / SLIDE_DURATION;
+            let visibility = self.visibility();
+            for (entry, is_visible) in self.entries.iter_mut().zip(visibility) {
+                if is_visible && !entry.is_leaving {
+                    entry.presence = (entry.presence + step).min(1.0);
+                } else {
+                    entry.presence = (entry.presence - step).max(0.0);
+                }
+            }
+            let count = self.entries.len();
+            self.entries
+                .retain(|entry| !entry.is_leaving || entry.presence > 0.0);
+            if self.entries.len() != count {
+                cx.children_changed();
+            }
+            if self.is_animating() {
+                cx.request_anim_frame();
+            }
+            cx.request_layout();
+            cx.request_paint();
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        let visibility = self.visibility();
+        let now = cx.now();
+        for (entry, is_visible) in self.entries.iter_mut().zip(visibility) {
+            entry.frame.update(cx);
+            if is_visible {
+                entry.start_timer(|delay| cx.request_timer(delay), now);
+            }
+        }
+        if self.is_animating() {
+            cx.request_anim_frame();
+        }
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let width = TOAST_WIDTH.min(bc.max().width);
+        let frame_bc = BoxConstraints::new(Size::new(width, 0.0), Size::new(width, f64::INFINITY));
+        let visibility = self.visibility();
+        let mut heights = Vec::with_capacity(self.entries.len());
+        let mut height = 0.0;
+        for (entry, is_visible) in self.entries.iter_mut().zip(&visibility) {
+            let frame_height = if *is_visible {
+                entry.frame.layout(cx, &frame_bc).height
+            } else {
+                0.0
+            };
+            heights.push(frame_height);
+            height += (frame_height + GAP) * entry.presence;
+        }
+        let height = (height - GAP).max(0.0);
+
+        // The first toast is nearest to the corner, the others stack away from it.
+        let mut offset = 0.0;
+        for ((entry, is_visible), frame_height) in
+            self.entries.iter_mut().zip(visibility).zip(heights)
+        {
+            if !is_visible {
+                continue;
+            }
+            let slide = (1.0 - entry.presence) * (width + EDGE_INSET);
+            let x = if self.corner.is_left() { -slide } else { slide };
+            let y = if self.corner.is_top() {
+                offset
+            } else {
+                height - offset - frame_height
+            };
+            entry.frame.set_origin(cx, Point::new(x, y));
+            offset += (frame_height + GAP) * entry.presence;
+        }
+        Size::new(width, height)
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        let visibility = self.visibility();
+        let mut children = vec![];
+        for (entry, is_visible) in self.entries.iter_mut().zip(visibility) {
+            if is_visible {
+                entry.frame.accessibility(cx);
+                children.push(entry.frame.id().into());
+            }
+        }
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(children);
+            builder.set_live(accesskit::Live::Polite);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let visibility = self.visibility();
+        for (entry, is_visible) in self.entries.iter_mut().zip(visibility) {
+            if is_visible {
+                entry.frame.paint(cx, builder);
+            }
+        }
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        let visibility = self.visibility();
+        for (entry, is_visible) in self.entries.iter().zip(visibility) {
+            if is_visible {
+                visit(&entry.frame);
+            }
+        }
+    }
+}
+
+/// The frame of a toast with its close button.
+struct ToastFrame {
+    id_path: IdPath,
+    id: u64,
+    content: Pod,
+}
+
+impl ToastFrame {
+    fn close_rect(size: Size) -> Rect {
+        let x = size.width - TOAST_INSETS.x1 - CLOSE_SIZE;
+        Rect::new(
+            x,
+            TOAST_INSETS.y0,
+            x + CLOSE_SIZE,
+            TOAST_INSETS.y0 + CLOSE_SIZE,
+        )
+    }
+}
+
+impl Widget for ToastFrame {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        let close = ToastFrame::close_rect(cx.size());
+        match event {
+            Event::MouseDown(mouse) if close.contains(mouse.pos) => {
+                cx.set_active(true);
+                cx.request_paint();
+            }
+            Event::MouseUp(mouse) if cx.is_active() => {
+                cx.set_active(false);
+                if close.contains(mouse.pos) {
+                    let message = ToastMessage::Closed(self.id);
+                    cx.add_message(Message::new(self.id_path.clone(), message));
+                }
+                cx.request_paint();
+            }
+            _ => self.content.event(cx, event),
+        }
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(_) = event {
+            cx.request_paint();
+        }
+        self.content.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.content.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let padding = Size::new(
+            TOAST_INSETS.x_value() + CLOSE_SIZE + GAP,
+            TOAST_INSETS.y_value(),
+        );
+        let content_size = self.content.layout(cx, &bc.shrink(padding).loosen());
+        let size = bc.constrain(Size::new(
+            content_size.width + padding.width,
+            (content_size.height + padding.height).max(CLOSE_SIZE + padding.height),
+        ));
+        self.content
+            .set_origin(cx, Point::new(TOAST_INSETS.x0, TOAST_INSETS.y0));
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.content.accessibility(cx);
+
+        let mut builder = accesskit::NodeBuilder::new(accesskit::Role::Status);
+        builder.set_children(vec![self.content.id().into()]);
+        cx.push_node(builder);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let frame = cx.size().to_rect().to_rounded_rect(6.0);
+        piet_scene_helpers::fill_color(builder, &frame, Color::rgb8(0x33, 0x33, 0x33));
+        let border = if cx.is_hot() {
+            Color::rgb8(0x5a, 0x9b, 0xe6)
+        } else {
+            Color::rgb8(0x4a, 0x4a, 0x4a)
+        };
+        piet_scene_helpers::stroke(builder, &frame, border, 1.0);
+        self.content.paint(cx, builder);
+
+        // A cross as close button.
+        let close = ToastFrame::close_rect(cx.size()).inset(-4.0);
+        let mut cross = BezPath::new();
+        cross.move_to((close.x0, close.y0));
+        cross.line_to((close.x1, close.y1));
+        cross.move_to((close.x1, close.y0));
+        cross.line_to((close.x0, close.y1));
+        let color = if cx.is_active() {
+            Color::WHITE
+        } else {
+            Color::rgb8(0xa1, 0xa1, 0xa1)
+        };
+        piet_scene_helpers::stroke(builder, &cross, color, 1.5);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{mouse, Harness, Leaf};
+    use crate::widget::OverlayHost;
+    use test_log::test;
+
+    const WINDOW: Size = Size::new(400.0, 300.0);
+    /// A frame around a leaf of height 20 is 40 high, the first one ends 16 above the bottom.
+    const FIRST_TOAST: Point = Point::new(100.0, 260.0);
+
+    fn setup(harness: &mut Harness, max_visible: usize) -> Pod {
+        let child = Pod::new(Leaf::new(400.0, 300.0));
+        let toasts = Toasts::new(&vec![], child, Corner::BottomRight, max_visible);
+        let mut root = Pod::new(OverlayHost::new(Pod::new(toasts)));
+        harness.update(&mut root);
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        root
+    }
+
+    /// Runs `f` with the [`Toasts`] and processes the changes like the app does.
+    fn change(
+        harness: &mut Harness,
+        root: &mut Pod,
+        f: impl FnOnce(&mut Toasts) -> ChangeFlags,
+    ) -> ChangeFlags {
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        let content = host.content_mut();
+        let changes = f(content.downcast_mut().unwrap());
+        let changes = content.mark(changes);
+        let changes = root.mark(changes);
+        harness.update(root);
+        harness.layout(root, &BoxConstraints::tight(WINDOW));
+        changes
+    }
+
+    fn push(harness: &mut Harness, root: &mut Pod, id: u64, duration: Duration) {
+        let content = Pod::new(Leaf::new(200.0, 20.0));
+        let _ = change(harness, root, |toasts| toasts.push(id, content, duration));
+    }
+
+    fn visible(root: &mut Pod) -> Vec<u64> {
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        host.content_mut()
+            .downcast_mut::<Toasts>()
+            .unwrap()
+            .visible()
+    }
+
+    /// Runs the slide animations to their end.
+    fn settle(harness: &mut Harness, root: &mut Pod) {
+        for _ in 0..2 {
+            harness.lifecycle(root, LifeCycle::AnimFrame(150_000_000));
+        }
+        harness.layout(root, &BoxConstraints::tight(WINDOW));
+    }
+
+    fn messages(harness: &mut Harness) -> Vec<ToastMessage> {
+        harness
+            .take_messages()
+            .into_iter()
+            .map(|message| *message.body.downcast::<ToastMessage>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn excess_toasts_are_queued_in_order() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, 2);
+        for id in 1..=3 {
+            push(&mut harness, &mut root, id, Duration::from_secs(1));
+        }
+        assert_eq!(visible(&mut root), vec![1, 2]);
+
+        // Only the shown toasts count down.
+        harness.advance_time(&mut root, Duration::from_secs(1));
+        assert_eq!(
+            messages(&mut harness),
+            vec![ToastMessage::Expired(1), ToastMessage::Expired(2)]
+        );
+
+        let _ = change(&mut harness, &mut root, |toasts| toasts.remove(1));
+        assert_eq!(visible(&mut root), vec![2, 3]);
+        harness.advance_time(&mut root, Duration::from_secs(1));
+        assert_eq!(messages(&mut harness), vec![ToastMessage::Expired(3)]);
+    }
+
+    #[test]
+    fn hovering_pauses_the_countdown() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, 3);
+        push(&mut harness, &mut root, 1, Duration::from_secs(1));
+        settle(&mut harness, &mut root);
+
+        harness.advance_time(&mut root, Duration::from_millis(400));
+        harness.event(&mut root, Event::MouseMove(mouse(FIRST_TOAST)));
+        harness.advance_time(&mut root, Duration::from_secs(2));
+        assert_eq!(messages(&mut harness), vec![]);
+
+        harness.event(&mut root, Event::MouseMove(mouse((100.0, 20.0))));
+        harness.advance_time(&mut root, Duration::from_millis(500));
+        assert_eq!(messages(&mut harness), vec![]);
+        harness.advance_time(&mut root, Duration::from_millis(100));
+        assert_eq!(messages(&mut harness), vec![ToastMessage::Expired(1)]);
+    }
+
+    #[test]
+    fn closed_toasts_slide_out() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness, 3);
+        push(&mut harness, &mut root, 1, Duration::from_secs(5));
+        push(&mut harness, &mut root, 2, Duration::from_secs(5));
+        settle(&mut harness, &mut root);
+
+        // The close button of the first toast is at the top right of its frame.
+        let close = Point::new(364.0, 262.0);
+        harness.event(&mut root, Event::MouseDown(mouse(close)));
+        harness.event(&mut root, Event::MouseUp(mouse(close)));
+        assert_eq!(messages(&mut harness), vec![ToastMessage::Closed(1)]);
+
+        let _ = change(&mut harness, &mut root, |toasts| toasts.remove(1));
+        assert_eq!(visible(&mut root), vec![2]);
+        let count = |root: &mut Pod| {
+            let host = root.downcast_mut::<OverlayHost>().unwrap();
+            let toasts = host.content_mut().downcast_mut::<Toasts>().unwrap();
+            toasts
+                .stack
+                .with_pod(|stack| stack.downcast_mut::<ToastStack>().unwrap().entries.len())
+        };
+        harness.lifecycle(&mut root, LifeCycle::AnimFrame(100_000_000));
+        assert_eq!(count(&mut root), 2);
+        harness.lifecycle(&mut root, LifeCycle::AnimFrame(100_000_000));
+        assert_eq!(count(&mut root), 1);
+        assert!(!root.anim_frame_requested());
+    }
+}
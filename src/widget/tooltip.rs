@@ -0,0 +1,377 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use vello::{
+    kurbo::{Insets, Point, Rect, Size},
+    peniko::Color,
+    SceneBuilder,
+};
+
+use super::{
+    piet_scene_helpers, AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle,
+    LifeCycleCx, OverlayHandle, PaintCx, Placement, Pod, TimerToken, UpdateCx, Widget,
+};
+
+/// Shows a tooltip while the pointer rests on a child.
+///
+/// The tooltip appears once the pointer stayed over the child for the delay and is dismissed
+/// when the pointer leaves, clicks or scrolls. After a tooltip was dismissed by leaving, the
+/// tooltips of all widgets in the window show up immediately for a short while, so moving along
+/// adjacent widgets doesn't wait for each delay.
+///
+/// The tooltip is an overlay, it needs an [`OverlayHost`](super::OverlayHost) above it.
+pub struct Tooltip {
+    child: Pod,
+    overlay: OverlayHandle,
+    delay: Duration,
+    timer: Option<TimerToken>,
+    is_shown: bool,
+    /// Set by a click or scroll, no tooltip is shown until the pointer left the child.
+    is_suppressed: bool,
+    /// The last position of the pointer over the child, in window coordinates.
+    pointer: Point,
+}
+
+/// How long tooltips show up immediately after one was dismissed.
+const WARM_DURATION: Duration = Duration::from_millis(600);
+/// The distance between the pointer and the tooltip, keeping the cursor clear.
+const POINTER_OFFSET: f64 = 16.0;
+
+const FRAME_INSETS: Insets = Insets::uniform_xy(6.0, 4.0);
+
+impl Tooltip {
+    /// The delay used unless [`set_delay`](Tooltip::set_delay) is called.
+    pub const DEFAULT_DELAY: Duration = Duration::from_millis(500);
+
+    pub fn new(child: Pod, content: Pod) -> Tooltip {
+        let overlay = OverlayHandle::new(TooltipFrame { content }, Placement::Below(Rect::ZERO));
+        overlay.set_interactive(false);
+        Tooltip {
+            child,
+            overlay,
+            delay: Self::DEFAULT_DELAY,
+            timer: None,
+            is_shown: false,
+            is_suppressed: false,
+            pointer: Point::ZERO,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    /// Runs `f` with the pod of the tooltip content and marks the changes it returns.
+    pub fn update_content(&mut self, f: impl FnOnce(&mut Pod) -> ChangeFlags) -> ChangeFlags {
+        self.overlay.with_pod(|frame| {
+            let content = &mut frame.expect_mut::<TooltipFrame>().content;
+            let changes = f(content);
+            let changes = content.mark(changes);
+            frame.mark(changes)
+        })
+    }
+
+    /// Sets how long the pointer has to rest on the child before the tooltip is shown.
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+    }
+
+    pub fn is_shown(&self) -> bool {
+        self.is_shown
+    }
+
+    fn show(&mut self, cx: &mut EventCx) {
+        let anchor = Rect::from_points(self.pointer, self.pointer + (0.0, POINTER_OFFSET));
+        self.overlay.set_placement(Placement::Below(anchor));
+        cx.show_overlay(&self.overlay);
+        cx.request_layout();
+        cx.request_accessibility();
+        self.is_shown = true;
+    }
+}
+
+impl Widget for Tooltip {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        match event {
+            Event::MouseMove(mouse) if cx.is_hot() => {
+                self.pointer = mouse.window_pos;
+                if !self.is_shown && !self.is_suppressed {
+                    let window_state = cx.cx_state.window_state();
+                    let is_warm = window_state
+                        .tooltip_warm_until
+                        .is_some_and(|until| window_state.timers.now() < until);
+                    if is_warm {
+                        if let Some(timer) = self.timer.take() {
+                            cx.cancel_timer(timer);
+                        }
+                        self.show(cx);
+                    } else if self.timer.is_none() {
+                        self.timer = Some(cx.request_timer(self.delay));
+                    }
+                }
+            }
+            Event::MouseDown(_) | Event::MouseWheel(_) => {
+                if let Some(timer) = self.timer.take() {
+                    cx.cancel_timer(timer);
+                }
+                if self.is_shown {
+                    cx.hide_overlay(&self.overlay);
+                    cx.request_accessibility();
+                    self.is_shown = false;
+                }
+                self.is_suppressed = true;
+            }
+            Event::Timer(token) if self.timer == Some(*token) => {
+                self.timer = None;
+                self.show(cx);
+            }
+            _ => (),
+        }
+        self.child.event(cx, event);
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(false) = event {
+            if let Some(timer) = self.timer.take() {
+                cx.cancel_timer(timer);
+            }
+            if self.is_shown {
+                cx.hide_overlay(&self.overlay);
+                cx.request_accessibility();
+                self.is_shown = false;
+                let warm_until = cx.now() + WARM_DURATION;
+                cx.cx_state.window_state().tooltip_warm_until = Some(warm_until);
+            }
+            self.is_suppressed = false;
+        }
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.child.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            if self.is_shown {
+                let frame = self.overlay.with_pod(|frame| frame.id());
+                builder.set_described_by(vec![frame.into()]);
+            }
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+/// The box around the content of a tooltip.
+struct TooltipFrame {
+    content: Pod,
+}
+
+impl Widget for TooltipFrame {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.content.event(cx, event);
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.content.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.content.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let padding = Size::new(FRAME_INSETS.x_value(), FRAME_INSETS.y_value());
+        let content_size = self
+            .content
+            .layout(cx, &bc.shrink_insets(FRAME_INSETS).loosen());
+        self.content
+            .set_origin(cx, Point::new(FRAME_INSETS.x0, FRAME_INSETS.y0));
+        bc.constrain(content_size + padding)
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.content.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::Tooltip);
+            builder.set_children(vec![self.content.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        let frame = cx.size().to_rounded_rect(4.0);
+        piet_scene_helpers::fill_color(builder, &frame, Color::rgb8(0x20, 0x20, 0x20));
+        piet_scene_helpers::stroke(builder, &frame, Color::rgb8(0x50, 0x50, 0x50), 1.0);
+        self.content.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Axis;
+    use crate::widget::test_util::{mouse, Harness, Leaf};
+    use crate::widget::{LinearLayout, OverlayHost};
+    use test_log::test;
+
+    const WINDOW: Size = Size::new(200.0, 100.0);
+
+    fn tooltip() -> Pod {
+        Pod::new(Tooltip::new(
+            Pod::new(Leaf::new(50.0, 20.0)),
+            Pod::new(Leaf::new(40.0, 10.0)),
+        ))
+    }
+
+    /// Two adjacent tooltips at (0, 0)..(50, 20) and (50, 0)..(100, 20).
+    fn setup(harness: &mut Harness) -> Pod {
+        let row = LinearLayout::new(vec![tooltip(), tooltip()], 0.0, Axis::Horizontal);
+        let mut root = Pod::new(OverlayHost::new(Pod::new(row)));
+        harness.layout(&mut root, &BoxConstraints::tight(WINDOW));
+        root
+    }
+
+    fn shown(root: &mut Pod) -> [bool; 2] {
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        let row = host.content_mut().downcast_mut::<LinearLayout>().unwrap();
+        let mut shown = row
+            .children
+            .iter_mut()
+            .map(|child| child.downcast_mut::<Tooltip>().unwrap().is_shown());
+        [shown.next().unwrap(), shown.next().unwrap()]
+    }
+
+    fn overlay_count(root: &mut Pod) -> usize {
+        root.downcast_mut::<OverlayHost>().unwrap().overlay_count()
+    }
+
+    #[test]
+    fn shows_after_delay() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness);
+
+        harness.event(&mut root, Event::MouseMove(mouse((10.0, 10.0))));
+        harness.advance_time(&mut root, Duration::from_millis(300));
+        // Moving on the widget doesn't restart the delay.
+        harness.event(&mut root, Event::MouseMove(mouse((12.0, 10.0))));
+        assert_eq!(shown(&mut root), [false, false]);
+        harness.advance_time(&mut root, Duration::from_millis(250));
+        assert_eq!(shown(&mut root), [true, false]);
+        assert_eq!(overlay_count(&mut root), 1);
+    }
+
+    #[test]
+    fn leaving_before_delay_cancels() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness);
+
+        harness.event(&mut root, Event::MouseMove(mouse((10.0, 10.0))));
+        harness.advance_time(&mut root, Duration::from_millis(300));
+        harness.event(&mut root, Event::MouseMove(mouse((10.0, 50.0))));
+        harness.advance_time(&mut root, Duration::from_secs(1));
+        assert_eq!(shown(&mut root), [false, false]);
+        assert_eq!(overlay_count(&mut root), 0);
+    }
+
+    #[test]
+    fn click_dismisses_until_leave() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness);
+
+        harness.event(&mut root, Event::MouseMove(mouse((10.0, 10.0))));
+        harness.advance_time(&mut root, Duration::from_millis(500));
+        assert_eq!(overlay_count(&mut root), 1);
+        harness.event(&mut root, Event::MouseDown(mouse((10.0, 10.0))));
+        assert_eq!(shown(&mut root), [false, false]);
+        assert_eq!(overlay_count(&mut root), 0);
+
+        harness.event(&mut root, Event::MouseMove(mouse((14.0, 10.0))));
+        harness.advance_time(&mut root, Duration::from_secs(1));
+        assert_eq!(shown(&mut root), [false, false]);
+    }
+
+    #[test]
+    fn warm_state_shows_adjacent_tooltip_immediately() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness);
+
+        harness.event(&mut root, Event::MouseMove(mouse((40.0, 10.0))));
+        harness.advance_time(&mut root, Duration::from_millis(500));
+        assert_eq!(shown(&mut root), [true, false]);
+
+        harness.event(&mut root, Event::MouseMove(mouse((60.0, 10.0))));
+        assert_eq!(shown(&mut root), [false, true]);
+        assert_eq!(overlay_count(&mut root), 1);
+    }
+
+    #[test]
+    fn warm_state_expires() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness);
+
+        harness.event(&mut root, Event::MouseMove(mouse((40.0, 10.0))));
+        harness.advance_time(&mut root, Duration::from_millis(500));
+        harness.event(&mut root, Event::MouseMove(mouse((40.0, 50.0))));
+        harness.advance_time(&mut root, Duration::from_secs(1));
+
+        harness.event(&mut root, Event::MouseMove(mouse((60.0, 10.0))));
+        assert_eq!(shown(&mut root), [false, false]);
+        harness.advance_time(&mut root, Duration::from_millis(500));
+        assert_eq!(shown(&mut root), [false, true]);
+    }
+
+    #[test]
+    fn tooltip_stays_in_window() {
+        let mut harness = Harness::new();
+        let mut root = setup(&mut harness);
+
+        let window = Size::new(110.0, 40.0);
+        harness.layout(&mut root, &BoxConstraints::tight(window));
+
+        harness.event(&mut root, Event::MouseMove(mouse((95.0, 15.0))));
+        harness.advance_time(&mut root, Duration::from_millis(500));
+        harness.layout(&mut root, &BoxConstraints::tight(window));
+        let host = root.downcast_mut::<OverlayHost>().unwrap();
+        let row = host.content_mut().downcast_mut::<LinearLayout>().unwrap();
+        let tooltip = row.children[1].downcast_mut::<Tooltip>().unwrap();
+        // The 52×18 frame fits neither below nor above the pointer, nor right of it.
+        assert_eq!(tooltip.overlay.rect(), Rect::new(58.0, 22.0, 110.0, 40.0));
+    }
+}
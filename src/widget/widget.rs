@@ -13,16 +13,27 @@
 // limitations under the License.
 
 use std::any::Any;
-use std::ops::DerefMut;
+use std::ops::{Deref, DerefMut};
 
 use crate::geometry::Axis;
-use vello::kurbo::Size;
+use vello::kurbo::{Point, Size};
 use vello::SceneBuilder;
 
 use super::box_constraints::BoxConstraints;
 use super::contexts::{AccessCx, EventCx, LayoutCx, LifeCycleCx, PaintCx, UpdateCx};
+use super::core::Pod;
+use super::debug_state::DebugState;
 use super::raw_event::{Event, LifeCycle};
 
+/// Which intrinsic extent [`Widget::compute_intrinsic`] measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intrinsic {
+    /// The smallest extent the widget can take without cutting off its content.
+    Min,
+    /// The extent the widget takes when it isn't constrained.
+    Max,
+}
+
 /// A basic widget trait.
 pub trait Widget {
     /// Handle an event.
@@ -37,6 +48,18 @@ pub trait Widget {
     /// [`Command`]: struct.Command.html
     fn event(&mut self, cx: &mut EventCx, event: &Event);
 
+    /// Handle an event before it reaches the children of this widget.
+    ///
+    /// Events travel in two phases. In the capture phase, they go from the root down to their
+    /// target, calling this method on every widget on the way. In the bubble phase, [`event`]
+    /// delivers them from the target back up to the root. A widget which calls
+    /// [`EventCx::set_handled`] here claims the event: neither its descendants nor its own
+    /// [`event`] see it, and the ancestors find it handled.
+    ///
+    /// [`event`]: Widget::event
+    #[allow(unused_variables)]
+    fn capture_event(&mut self, cx: &mut EventCx, event: &Event) {}
+
     /// Handle a life cycle notification.
     ///
     /// This method is called to notify your widget of certain special events,
@@ -86,6 +109,9 @@ pub trait Widget {
     /// For efficiency, a container should only invoke layout of a child widget
     /// once, though there is nothing enforcing this.
     ///
+    /// The pod skips this method while the constraints stay the same and no layout was
+    /// requested, so anything else the size depends on has to request layout when it changes.
+    ///
     /// The layout strategy is strongly inspired by Flutter.
     ///
     /// [`WidgetPod::layout`]: struct.WidgetPod.html#method.layout
@@ -110,6 +136,23 @@ pub trait Widget {
     /// [`RenderContext`]: trait.RenderContext.html
     fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder);
 
+    /// Whether `pos`, in the coordinates of the widget, is on the widget.
+    ///
+    /// Widgets which aren't rectangular, like a rounded button, return `false` outside of their
+    /// shape, so that the pointer there goes to the widgets below. The pod only asks for points
+    /// within `size`, and a miss excludes the children as well.
+    #[allow(unused_variables)]
+    fn hit_test(&self, size: Size, pos: Point) -> bool {
+        true
+    }
+
+    /// Calls `visit` with the children of the widget which can be visible, in paint order.
+    ///
+    /// [`Pod::widget_at`] descends through these, so containers have to report their children
+    /// here to make them found.
+    #[allow(unused_variables)]
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {}
+
     /*
     #[doc(hidden)]
     /// Get the identity of the widget; this is basically only implemented by
@@ -139,47 +182,39 @@ pub trait Widget {
             .unwrap_or(name)
     }
 
-    /*
-    #[doc(hidden)]
-    /// From the current data, get a best-effort description of the state of
-    /// this widget and its children for debugging purposes.
+    /// A description of the widget for debugging, see [`Pod::tree_debug_state`].
+    ///
+    /// Widgets add the values which matter for their layout and appearance. The pod adds the
+    /// children along with its own state.
     fn debug_state(&self) -> DebugState {
-        #![allow(unused_variables)]
-        DebugState {
-            display_name: self.short_type_name().to_string(),
-            ..Default::default()
-        }
+        DebugState::new(self.short_type_name())
     }
-    */
 
-    /// Computes max intrinsic/preferred dimension of a widget on the provided axis.
-    ///
-    /// Max intrinsic/preferred dimension is the dimension the widget could take, provided infinite
-    /// constraint on that axis.
+    /// Computes the intrinsic extent of the widget on `axis`, without laying it out for good.
     ///
-    /// If axis == Axis::Horizontal, widget is being asked to calculate max intrinsic width.
-    /// If axis == Axis::Vertical, widget is being asked to calculate max intrinsic height.
+    /// `cross` is the maximum extent on the other axis, which may be infinite. The
+    /// [`Intrinsic::Max`] width of a text is the width of its longest line, and its
+    /// [`Intrinsic::Min`] width the width of its longest word. Its height for a `cross` width is
+    /// the height of the text wrapped to that width.
     ///
-    /// Box constraints must be honored in intrinsics computation.
-    ///
-    /// AspectRatioBox is an example where constraints are honored. If height is finite, max intrinsic
-    /// width is *height * ratio*.
-    /// Only when height is infinite, child's max intrinsic width is calculated.
-    ///
-    /// Intrinsic is a *could-be* value. It's the value a widget *could* have given infinite constraints.
-    /// This does not mean the value returned by layout() would be the same.
+    /// The default lays the widget out with a maximum extent on `axis` of infinity for the
+    /// maximum, and of zero for the minimum. Containers should override it to ask their children
+    /// through [`Pod::intrinsic`], which caches the answers.
     ///
     /// This method **must** return a finite value.
-    fn compute_max_intrinsic(
+    fn compute_intrinsic(
         &mut self,
+        cx: &mut LayoutCx,
         axis: Axis,
-        ctx: &mut LayoutCx,
-        bc: &BoxConstraints,
+        kind: Intrinsic,
+        cross: f64,
     ) -> f64 {
-        match axis {
-            Axis::Horizontal => self.layout(ctx, bc).width,
-            Axis::Vertical => self.layout(ctx, bc).height,
-        }
+        let max_major = match kind {
+            Intrinsic::Min => 0.0,
+            Intrinsic::Max => f64::INFINITY,
+        };
+        let bc = BoxConstraints::new(Size::ZERO, axis.pack(max_major, cross));
+        axis.major(self.layout(cx, &bc))
     }
 }
 
@@ -210,6 +245,10 @@ impl Widget for Box<dyn AnyWidget> {
         self.deref_mut().event(cx, event);
     }
 
+    fn capture_event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.deref_mut().capture_event(cx, event);
+    }
+
     fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
         self.deref_mut().lifecycle(cx, event);
     }
@@ -229,4 +268,26 @@ impl Widget for Box<dyn AnyWidget> {
     fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
         self.deref_mut().paint(cx, builder);
     }
+
+    fn hit_test(&self, size: Size, pos: Point) -> bool {
+        self.deref().hit_test(size, pos)
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        self.deref().visit_children(visit);
+    }
+
+    fn debug_state(&self) -> DebugState {
+        self.deref().debug_state()
+    }
+
+    fn compute_intrinsic(
+        &mut self,
+        cx: &mut LayoutCx,
+        axis: Axis,
+        kind: Intrinsic,
+        cross: f64,
+    ) -> f64 {
+        self.deref_mut().compute_intrinsic(cx, axis, kind, cross)
+    }
 }
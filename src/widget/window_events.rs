@@ -0,0 +1,243 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vello::{
+    kurbo::{Point, Size},
+    SceneBuilder,
+};
+
+use crate::{IdPath, Message};
+
+use super::{
+    AccessCx, BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx,
+    PaintCx, Pod, UpdateCx, Widget,
+};
+
+/// Sends the new size of the window to the view at `id_path` whenever the window is resized.
+pub struct OnWindowResize {
+    id_path: IdPath,
+    child: Pod,
+}
+
+/// The message an [`OnCloseRequest`] sends to its view when the user asks to close the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CloseRequest;
+
+/// Keeps the window open when the user asks to close it, and asks the view at `id_path`
+/// instead, which can close it with [`close_window`](OnCloseRequest::close_window).
+pub struct OnCloseRequest {
+    id_path: IdPath,
+    child: Pod,
+    close: bool,
+}
+
+impl OnWindowResize {
+    pub fn new(id_path: &IdPath, child: Pod) -> Self {
+        OnWindowResize {
+            id_path: id_path.clone(),
+            child,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+}
+
+impl OnCloseRequest {
+    pub fn new(id_path: &IdPath, child: Pod) -> Self {
+        OnCloseRequest {
+            id_path: id_path.clone(),
+            child,
+            close: false,
+        }
+    }
+
+    pub fn child_mut(&mut self) -> &mut Pod {
+        &mut self.child
+    }
+
+    /// Closes the window with the next update, without asking again.
+    pub fn close_window(&mut self) -> ChangeFlags {
+        self.close = true;
+        ChangeFlags::UPDATE
+    }
+}
+
+impl Widget for OnWindowResize {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        self.child.event(cx, event);
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        if let LifeCycle::WindowResized(size) = event {
+            cx.add_message(Message::new(self.id_path.clone(), *size));
+        }
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        self.child.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+impl Widget for OnCloseRequest {
+    fn event(&mut self, cx: &mut EventCx, event: &Event) {
+        if let Event::CloseRequested = event {
+            cx.add_message(Message::new(self.id_path.clone(), CloseRequest));
+            cx.set_handled(true);
+        }
+        self.child.event(cx, event);
+    }
+
+    fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+        self.child.lifecycle(cx, event);
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx) {
+        if std::mem::take(&mut self.close) {
+            cx.close_window();
+        }
+        self.child.update(cx);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(cx, bc);
+        self.child.set_origin(cx, Point::ORIGIN);
+        size
+    }
+
+    fn accessibility(&mut self, cx: &mut AccessCx) {
+        self.child.accessibility(cx);
+
+        if cx.is_requested() {
+            let mut builder = accesskit::NodeBuilder::new(accesskit::Role::GenericContainer);
+            builder.set_children(vec![self.child.id().into()]);
+            cx.push_node(builder);
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx, builder: &mut SceneBuilder) {
+        self.child.paint(cx, builder);
+    }
+
+    fn visit_children(&self, visit: &mut dyn FnMut(&Pod)) {
+        visit(&self.child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::test_util::{Harness, Leaf};
+    use crate::widget::{Label, LinearLayout, PodFlags};
+    use crate::Axis;
+    use test_log::test;
+    use xilem_core::Id;
+
+    #[test]
+    fn resizes_reach_outer_widgets_first() {
+        let mut harness = Harness::new();
+        let (outer, inner) = (vec![Id::next()], vec![Id::next()]);
+        let leaf = Pod::new(Leaf::new(20.0, 20.0));
+        let inner_pod = Pod::new(OnWindowResize::new(&inner, leaf));
+        let mut root = Pod::new(OnWindowResize::new(&outer, inner_pod));
+        let size = harness.resize(&mut root, Size::new(300.0, 200.0));
+        assert_eq!(size, Size::new(300.0, 200.0));
+
+        let messages = harness.take_messages();
+        let delivered: Vec<_> = messages
+            .iter()
+            .map(|message| {
+                let size = message.body.downcast_ref::<Size>().unwrap();
+                (message.id_path.clone(), *size)
+            })
+            .collect();
+        assert_eq!(
+            delivered,
+            [
+                (outer, Size::new(300.0, 200.0)),
+                (inner, Size::new(300.0, 200.0))
+            ]
+        );
+    }
+
+    #[test]
+    fn close_requests_can_be_vetoed() {
+        let mut harness = Harness::new();
+        let mut plain = Pod::new(Leaf::new(20.0, 20.0));
+        harness.layout(&mut plain, &BoxConstraints::UNBOUNDED);
+        assert!(harness.request_close(&mut plain));
+
+        let guarded = vec![Id::next()];
+        let children = vec![
+            Pod::new(Leaf::new(20.0, 20.0)),
+            Pod::new(Leaf::new(20.0, 20.0)),
+        ];
+        let row = Pod::new(LinearLayout::new(children, 0.0, Axis::Horizontal));
+        let mut root = Pod::new(OnCloseRequest::new(&guarded, row));
+        harness.layout(&mut root, &BoxConstraints::UNBOUNDED);
+        assert!(!harness.request_close(&mut root));
+        let messages = harness.take_messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id_path, guarded);
+        assert!(messages[0].body.is::<CloseRequest>());
+        assert!(!harness.window_state.close_window);
+
+        // The view decided to close after all.
+        let changes = root
+            .downcast_mut::<OnCloseRequest>()
+            .unwrap()
+            .close_window();
+        root.mark(changes);
+        harness.update(&mut root);
+        assert!(harness.window_state.close_window);
+    }
+
+    #[test]
+    fn scale_changes_shape_text_again() {
+        let mut harness = Harness::new();
+        let mut root = Pod::new(Label::new("Scaled".into()));
+        harness.layout(&mut root, &BoxConstraints::UNBOUNDED);
+        assert!(!root.state.flags.contains(PodFlags::REQUEST_LAYOUT));
+
+        harness.set_scale(&mut root, 2.0);
+        assert_eq!(harness.window_state.scale, 2.0);
+        assert!(root.state.flags.contains(PodFlags::REQUEST_LAYOUT));
+    }
+}